@@ -1,55 +1,96 @@
 use cadmus_core::anyhow::{format_err, Context as ResultExt, Error};
 use cadmus_core::assets::open_documentation;
-use cadmus_core::battery::{Battery, KoboBattery};
+use cadmus_core::backup;
+use cadmus_core::battery::{
+    Battery, BatteryHistory, BatterySample, KoboBattery, BATTERY_HISTORY_PATH,
+};
+use cadmus_core::bedtime::{Bedtime, Phase};
+use cadmus_core::bluetooth::Remotes;
+use cadmus_core::calibre::Calibre;
+use cadmus_core::captive;
 use cadmus_core::chrono::Local;
 use cadmus_core::context::Context;
+use cadmus_core::crash;
 use cadmus_core::device::{FrontlightKind, Orientation, CURRENT_DEVICE};
-use cadmus_core::document::sys_info_as_html;
+use cadmus_core::document::{sys_info_as_html, HumanSize};
+use cadmus_core::download;
+use cadmus_core::external_keyboard::Keyboards;
 use cadmus_core::font::Fonts;
 use cadmus_core::framebuffer::{Framebuffer, KoboFramebuffer1, KoboFramebuffer2, UpdateMode};
 use cadmus_core::frontlight::{
-    Frontlight, NaturalFrontlight, PremixedFrontlight, StandardFrontlight,
+    AutoBrightness, Frontlight, NaturalFrontlight, PremixedFrontlight, StandardFrontlight,
 };
-use cadmus_core::geom::{DiagDir, Rectangle, Region};
+use cadmus_core::geom::Rectangle;
 use cadmus_core::gesture::{gesture_events, GestureEvent};
 use cadmus_core::helpers::{load_toml, save_toml};
+use cadmus_core::hooks;
 use cadmus_core::input::{
     button_scheme_event, device_events, display_rotate_event, raw_events, usb_events,
 };
 use cadmus_core::input::{
     ButtonCode, ButtonStatus, DeviceEvent, PowerSource, VAL_PRESS, VAL_RELEASE,
 };
+use cadmus_core::kiosk;
 use cadmus_core::library::Library;
 use cadmus_core::lightsensor::{KoboLightSensor, LightSensor};
+use cadmus_core::mail;
+use cadmus_core::net;
+use cadmus_core::recorder::Recorder;
+use cadmus_core::reset;
 use cadmus_core::rtc::Rtc;
-use cadmus_core::settings::{ButtonScheme, IntermKind, RotationLock, Settings, SETTINGS_PATH};
+use cadmus_core::scripting::{Scripts, SCRIPTS_DIRNAME};
+use cadmus_core::settings::{
+    BedtimeAction, ButtonScheme, CoverCloseAction, CoverOpenAction, HookTrigger, IntermKind,
+    RotationLock, Settings, INTERNAL_CARD_ROOT, SETTINGS_PATH,
+};
+use cadmus_core::storage;
+use cadmus_core::sync_queue;
+use cadmus_core::view::battery_stats::BatteryStats;
 use cadmus_core::view::calculator::Calculator;
+use cadmus_core::view::captive_portal::CaptivePortal;
+use cadmus_core::view::command_palette::show_command_palette;
 use cadmus_core::view::common::{
-    find_notification_mut, locate, locate_by_id, overlapping_rectangle, transfer_notifications,
+    find_notification_mut, gesture_map_event, locate, locate_by_id, overlapping_rectangle,
+    transfer_notifications, ButtonMapping,
 };
 use cadmus_core::view::common::{toggle_input_history_menu, toggle_keyboard_layout_menu};
+use cadmus_core::view::confirm_dialog::ConfirmDialog;
+use cadmus_core::view::dashboard::Dashboard;
 use cadmus_core::view::dialog::Dialog;
 use cadmus_core::view::dictionary::Dictionary as DictionaryApp;
+use cadmus_core::view::file_chooser::{FileChooser, SelectionMode};
+use cadmus_core::view::focus::{activate_focus, move_focus, navigates, ButtonNavigation};
 use cadmus_core::view::frontlight::FrontlightWindow;
 use cadmus_core::view::home::Home;
+use cadmus_core::view::inspector::toggle_inspector;
 use cadmus_core::view::intermission::Intermission;
 use cadmus_core::view::menu::{Menu, MenuKind};
-use cadmus_core::view::notification::Notification;
+use cadmus_core::view::notification::{
+    dismiss_toast, notify, toggle_notification_drawer, Notification,
+};
 use cadmus_core::view::ota::show_ota_view;
+use cadmus_core::view::perf_hud::{tick_perf_hud, toggle_perf_hud};
+use cadmus_core::view::pin_pad::PinPad;
+use cadmus_core::view::progress_dialog::{show_progress_dialog, ProgressEvent};
 use cadmus_core::view::reader::Reader;
 use cadmus_core::view::rotation_values::RotationValues;
+use cadmus_core::view::screenshots::{take_screenshot, Screenshots};
 use cadmus_core::view::settings_editor::SettingsEditor;
+use cadmus_core::view::setup_wizard::{self, SetupChoice, SetupStep};
 use cadmus_core::view::sketch::Sketch;
 use cadmus_core::view::touch_events::TouchEvents;
 use cadmus_core::view::{handle_event, process_render_queue, wait_for_all};
 use cadmus_core::view::{
-    AppCmd, EntryId, EntryKind, Event, NotificationEvent, RenderData, RenderQueue, UpdateData,
-    View, ViewId,
+    AppCmd, Bus, EntryId, EntryKind, Event, Hub, NotificationEvent, RenderData, RenderQueue,
+    UpdateData, View, ViewId,
 };
+use cadmus_core::web_server::{api, WebServer};
+use cadmus_core::webdav;
 use std::collections::VecDeque;
 use std::env;
 use std::fs::File;
-use std::path::Path;
+use std::mem;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::thread;
@@ -82,7 +123,14 @@ const KOBO_UPDATE_BUNDLE: &str = "/mnt/onboard/.kobo/KoboRoot.tgz";
 
 const CLOCK_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
 const BATTERY_REFRESH_INTERVAL: Duration = Duration::from_secs(299);
+const AMBIENT_LIGHT_REFRESH_INTERVAL: Duration = Duration::from_secs(3);
 const AUTO_SUSPEND_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+const AUTO_WIFI_OFF_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+const BEDTIME_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+const MAIL_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+const STORAGE_REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+// The user is deemed to be reading if they were active this recently.
+const BEDTIME_ACTIVE_DELAY: Duration = Duration::from_secs(120);
 const SUSPEND_WAIT_DELAY: Duration = Duration::from_secs(15);
 const PREPARE_SUSPEND_WAIT_DELAY: Duration = Duration::from_secs(3);
 
@@ -96,6 +144,7 @@ enum TaskId {
     CheckBattery,
     PrepareSuspend,
     Suspend,
+    CoverSuspend,
 }
 
 struct HistoryItem {
@@ -125,6 +174,10 @@ fn build_context(fb: Box<dyn Framebuffer>) -> Result<Context, Error> {
         return Err(format_err!("no libraries found"));
     }
 
+    if settings.kiosk.enabled {
+        settings.selected_library = settings.kiosk.library;
+    }
+
     if settings.selected_library >= settings.libraries.len() {
         settings.selected_library = 0;
     }
@@ -190,6 +243,53 @@ fn schedule_task(
     });
 }
 
+// Shows the suspend screen and schedules the suspension.
+fn start_suspend(
+    view: &mut dyn View,
+    tasks: &mut Vec<Task>,
+    hub: &Sender<Event>,
+    bus: &mut Bus,
+    rq: &mut RenderQueue,
+    context: &mut Context,
+) {
+    view.handle_event(&Event::Suspend, hub, bus, rq, context);
+    hooks::trigger(HookTrigger::Suspend, None, context, hub);
+    let interm = Intermission::new(context.fb.rect(), IntermKind::Suspend, context);
+    interm.animate(hub);
+    rq.add(RenderData::new(
+        interm.id(),
+        *interm.rect(),
+        UpdateMode::Full,
+    ));
+    schedule_task(
+        TaskId::PrepareSuspend,
+        Event::PrepareSuspend,
+        PREPARE_SUSPEND_WAIT_DELAY,
+        hub,
+        tasks,
+    );
+    view.children_mut().push(Box::new(interm) as Box<dyn View>);
+}
+
+// Covers the screen with the suspend screen of the locked device.
+fn lock(view: &mut dyn View, rq: &mut RenderQueue, context: &mut Context) {
+    let interm = Intermission::new(context.fb.rect(), IntermKind::Suspend, context);
+    rq.add(RenderData::new(
+        interm.id(),
+        *interm.rect(),
+        UpdateMode::Full,
+    ));
+    view.children_mut().push(Box::new(interm) as Box<dyn View>);
+}
+
+fn unlock(view: &mut dyn View, rq: &mut RenderQueue) {
+    if let Some(index) = locate::<Intermission>(view) {
+        let rect = *view.child(index).rect();
+        view.children_mut().remove(index);
+        rq.add(RenderData::expose(rect, UpdateMode::Full));
+    }
+}
+
 fn resume(
     id: TaskId,
     tasks: &mut Vec<Task>,
@@ -208,6 +308,10 @@ fn resume(
         if context.settings.wifi {
             Command::new("scripts/wifi-enable.sh").status().ok();
         }
+        if context.settings.bluetooth {
+            Command::new("scripts/bluetooth-enable.sh").status().ok();
+        }
+        hooks::trigger(HookTrigger::Resume, None, context, hub);
     }
     if id == TaskId::Suspend || id == TaskId::PrepareSuspend {
         tasks.retain(|task| task.id != TaskId::PrepareSuspend);
@@ -250,6 +354,25 @@ fn power_off(
     context.fb.update(interm.rect(), UpdateMode::Full).ok();
 }
 
+fn record_battery_sample(context: &mut Context) {
+    let Ok(level) = context.battery.capacity().map(|v| v[0]) else {
+        return;
+    };
+    let charging = context.battery.status().is_ok_and(|v| v[0].is_wired());
+    let frontlight = if context.settings.frontlight {
+        context.frontlight.levels().intensity
+    } else {
+        0.0
+    };
+    context.battery_history.push(BatterySample {
+        time: Local::now().timestamp(),
+        level,
+        charging,
+        frontlight,
+        wifi: context.online,
+    });
+}
+
 fn set_wifi(enable: bool, context: &mut Context) {
     if context.settings.wifi == enable {
         return;
@@ -263,16 +386,102 @@ fn set_wifi(enable: bool, context: &mut Context) {
     }
 }
 
+// The settings editor flips the setting before asking for the change.
+fn set_bluetooth(enable: bool, remotes: &Remotes, context: &mut Context) {
+    context.settings.bluetooth = enable;
+    remotes.set_enabled(enable);
+    if enable {
+        Command::new("scripts/bluetooth-enable.sh").status().ok();
+    } else {
+        Command::new("scripts/bluetooth-disable.sh").status().ok();
+    }
+}
+
+/// Empties the items, returning the message telling how much space was freed.
+fn reclaim_items(items: &[storage::Item]) -> String {
+    let mut freed = 0;
+    let mut failed = 0;
+    for item in items {
+        match storage::reclaim(item) {
+            Ok(size) => freed += size,
+            Err(e) => {
+                error!("Can't clean up {}: {:#}.", item.path.display(), e);
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        format!(
+            "Freed {}, but {} item(s) couldn't be cleaned up.",
+            freed.human_size(),
+            failed
+        )
+    } else {
+        format!("Freed {}.", freed.human_size())
+    }
+}
+
+/// Replaces the dialog of the setup wizard by the one of the given step, or ends the wizard.
+fn show_setup_step(
+    view: &mut dyn View,
+    step: Option<SetupStep>,
+    hub: &Hub,
+    rq: &mut RenderQueue,
+    context: &mut Context,
+) {
+    if let Some(index) = locate_by_id(view, ViewId::SetupDialog) {
+        let rect = overlapping_rectangle(view.child(index));
+        rq.add(RenderData::expose(rect, UpdateMode::Gui));
+        view.children_mut().remove(index);
+    }
+
+    if let Some(step) = step {
+        let dialog = setup_wizard::dialog(step, context);
+        rq.add(RenderData::new(
+            dialog.id(),
+            *dialog.rect(),
+            UpdateMode::Gui,
+        ));
+        view.children_mut().push(Box::new(dialog) as Box<dyn View>);
+    } else {
+        let msg = match save_toml(&context.settings, SETTINGS_PATH) {
+            Ok(()) => "The setup is complete.".to_string(),
+            Err(e) => format!("Can't save the settings: {:#}.", e),
+        };
+        notify(view, msg, hub, rq, context);
+    }
+}
+
+fn start_web_server(context: &Context, hub: &Hub) -> Option<WebServer> {
+    WebServer::start(&context.settings, hub)
+        .map_err(|e| error!("Can't start the web server: {:#}.", e))
+        .ok()
+}
+
 #[derive(PartialEq)]
 enum ExitStatus {
     Quit,
     Restart,
     Reboot,
     PowerOff,
+    Reset { backup: bool },
+    Restore(PathBuf),
 }
 
 pub fn run() -> Result<(), Error> {
+    crash::install_hook(env!("GIT_VERSION"));
+
     let mut inactive_since = Instant::now();
+    // Whether the Wi-Fi was turned off for being idle, and the events waiting for its return.
+    let mut wifi_idled = false;
+    let mut network_queue: Vec<Event> = Vec::new();
+    let mut mail_checked_at: Option<Instant> = None;
+    // Whether the free space was below the threshold at the last check.
+    let mut storage_low = false;
+    // Whether the storage is to be shared once a computer is plugged in.
+    let mut share_pending = false;
+    // Whether the file chooser was opened by the setup wizard.
+    let mut choosing_library = false;
     let mut exit_status = ExitStatus::Quit;
 
     let mut fb: Box<dyn Framebuffer> = if CURRENT_DEVICE.mark() != 8 {
@@ -287,15 +496,33 @@ pub fn run() -> Result<(), Error> {
         fb.set_rotation(startup_rotation).ok();
     }
 
+    let first_run = !Path::new(SETTINGS_PATH).exists();
     let mut context = build_context(fb).context("can't build context")?;
+    context.scripts = Scripts::load(Path::new(SCRIPTS_DIRNAME));
+
+    // Devices with a gyroscope keep the orientation they're held in, unless told otherwise.
+    let quarter_turns = context.settings.display.startup_rotation.rem_euclid(4);
+    let rotation = CURRENT_DEVICE.from_canonical(quarter_turns);
+    if quarter_turns != 0 && rotation != context.display.rotation {
+        if let Ok(dims) = context.fb.set_rotation(rotation) {
+            context.display.rotation = rotation;
+            context.display.dims = dims;
+        }
+    }
 
     context.plugged = context.battery.status().is_ok_and(|v| v[0].is_wired());
+    // Whether a computer is plugged in. The battery can't tell a computer from a charger.
+    let mut host_plugged = context.plugged;
 
     if context.settings.import.startup_trigger {
         context.batch_import();
     }
     context.load_dictionaries();
     context.load_keyboard_layouts();
+    context.load_themes();
+    context.apply_theme();
+    context.load_translations();
+    context.battery_history = BatteryHistory::load(BATTERY_HISTORY_PATH);
 
     let mut paths = Vec::new();
     for ti in &TOUCH_INPUTS {
@@ -318,6 +545,7 @@ pub fn run() -> Result<(), Error> {
     }
 
     let (raw_sender, raw_receiver) = raw_events(paths);
+    let remotes = Remotes::new(raw_sender.clone());
     let touch_screen = gesture_events(device_events(
         raw_receiver,
         context.display,
@@ -326,6 +554,13 @@ pub fn run() -> Result<(), Error> {
     let usb_port = usb_events();
 
     let (tx, rx) = mpsc::channel();
+    let keyboards = Keyboards::new(raw_sender.clone(), tx.clone());
+    let mut calibre: Option<Calibre> = None;
+    let mut web_server = if context.settings.web_server.enabled && !context.settings.kiosk.enabled {
+        start_web_server(&context, &tx)
+    } else {
+        None
+    };
     let tx2 = tx.clone();
 
     thread::spawn(move || {
@@ -353,6 +588,14 @@ pub fn run() -> Result<(), Error> {
         tx5.send(Event::BatteryTick).ok();
     });
 
+    if CURRENT_DEVICE.has_lightsensor() {
+        let tx7 = tx.clone();
+        thread::spawn(move || loop {
+            thread::sleep(AMBIENT_LIGHT_REFRESH_INTERVAL);
+            tx7.send(Event::AmbientLightTick).ok();
+        });
+    }
+
     if context.settings.auto_suspend > 0.0 {
         let tx6 = tx.clone();
         thread::spawn(move || loop {
@@ -361,6 +604,30 @@ pub fn run() -> Result<(), Error> {
         });
     }
 
+    let tx8 = tx.clone();
+    thread::spawn(move || loop {
+        thread::sleep(AUTO_WIFI_OFF_REFRESH_INTERVAL);
+        tx8.send(Event::MightDisableWifi).ok();
+    });
+
+    let tx9 = tx.clone();
+    thread::spawn(move || loop {
+        thread::sleep(BEDTIME_REFRESH_INTERVAL);
+        tx9.send(Event::BedtimeTick).ok();
+    });
+
+    let tx10 = tx.clone();
+    thread::spawn(move || loop {
+        thread::sleep(MAIL_REFRESH_INTERVAL);
+        tx10.send(Event::MightCheckMail).ok();
+    });
+
+    let tx11 = tx.clone();
+    thread::spawn(move || loop {
+        thread::sleep(STORAGE_REFRESH_INTERVAL);
+        tx11.send(Event::CheckStorage).ok();
+    });
+
     context.fb.set_inverted(context.settings.inverted);
 
     if context.settings.wifi {
@@ -369,6 +636,11 @@ pub fn run() -> Result<(), Error> {
         Command::new("scripts/wifi-disable.sh").status().ok();
     }
 
+    if context.settings.bluetooth {
+        Command::new("scripts/bluetooth-enable.sh").status().ok();
+        remotes.set_enabled(true);
+    }
+
     if context.settings.frontlight {
         let levels = context.settings.frontlight_levels;
         context.frontlight.set_warmth(levels.warmth);
@@ -384,6 +656,16 @@ pub fn run() -> Result<(), Error> {
     let mut view: Box<dyn View> =
         Box::new(Home::new(context.fb.rect(), &tx, &mut rq, &mut context)?);
 
+    if context.settings.home.dashboard.start_screen {
+        let dashboard = Dashboard::new(context.fb.rect(), &mut rq, &mut context);
+        history.push(HistoryItem {
+            view: mem::replace(&mut view, Box::new(dashboard)),
+            rotation: context.display.rotation,
+            monochrome: context.fb.monochrome(),
+            dithered: context.fb.dithered(),
+        });
+    }
+
     let mut updating = Vec::new();
     let current_dir = env::current_dir()?;
 
@@ -401,6 +683,12 @@ pub fn run() -> Result<(), Error> {
     );
 
     let mut bus = VecDeque::with_capacity(4);
+    let mut button_navigation = ButtonNavigation::default();
+    let mut button_mapping = ButtonMapping::default();
+    let mut auto_brightness = AutoBrightness::new();
+    let mut bedtime = Bedtime::new();
+    // Whether closing the sleep cover locked the screen.
+    let mut cover_locked = false;
 
     schedule_task(
         TaskId::CheckBattery,
@@ -410,8 +698,76 @@ pub fn run() -> Result<(), Error> {
         &mut tasks,
     );
     tx.send(Event::WakeUp).ok();
+    tx.send(Event::CheckStorage).ok();
+    hooks::trigger(HookTrigger::Startup, None, &context, &tx);
+
+    if let Some(path) = crash::take_pending_report() {
+        let dialog = Dialog::builder(
+            ViewId::CrashDialog,
+            "Cadmus crashed during the last session.".to_string(),
+        )
+        .add_button("Dismiss", Event::Close(ViewId::CrashDialog))
+        .add_button(
+            "View",
+            Event::Select(EntryId::ViewCrashReport(path.clone())),
+        )
+        .add_button("Export", Event::Select(EntryId::ExportCrashReport(path)))
+        .build(&mut context);
+        rq.add(RenderData::new(
+            dialog.id(),
+            *dialog.rect(),
+            UpdateMode::Gui,
+        ));
+        view.children_mut().push(Box::new(dialog) as Box<dyn View>);
+    }
+
+    if first_run {
+        show_setup_step(
+            view.as_mut(),
+            Some(SetupStep::Language),
+            &tx,
+            &mut rq,
+            &mut context,
+        );
+    }
+
+    let mut recorder = Recorder::from_settings(&context.settings.logging)
+        .map_err(|e| error!("Can't record events: {:#}.", e))
+        .ok()
+        .flatten();
 
     while let Ok(evt) = rx.recv() {
+        let handling_started = Instant::now();
+        crash::record_event(&evt);
+        if let Some(recorder) = recorder.as_mut() {
+            recorder
+                .record(&evt)
+                .map_err(|e| error!("Can't record event: {:#}.", e))
+                .ok();
+        }
+
+        if kiosk::blocks(&evt, &tx, &context) {
+            continue;
+        }
+
+        if context.settings.button_navigation
+            && navigates(view.as_ref())
+            && button_navigation.handle_event(&evt, &tx)
+        {
+            continue;
+        }
+
+        // The power button keeps resuming the device, whatever it's mapped to.
+        if !context.shared
+            && !context.covered
+            && !tasks
+                .iter()
+                .any(|task| task.id == TaskId::PrepareSuspend || task.id == TaskId::Suspend)
+            && button_mapping.handle_event(&evt, view.as_ref(), &tx, &context)
+        {
+            continue;
+        }
+
         match evt {
             Event::Device(de) => match de {
                 DeviceEvent::Button {
@@ -423,6 +779,12 @@ pub fn run() -> Result<(), Error> {
                         continue;
                     }
 
+                    if cover_locked {
+                        cover_locked = false;
+                        unlock(view.as_mut(), &mut rq);
+                        continue;
+                    }
+
                     if tasks.iter().any(|task| task.id == TaskId::PrepareSuspend) {
                         resume(
                             TaskId::PrepareSuspend,
@@ -444,7 +806,8 @@ pub fn run() -> Result<(), Error> {
                     } else {
                         view.handle_event(&Event::Suspend, &tx, &mut bus, &mut rq, &mut context);
                         let interm =
-                            Intermission::new(context.fb.rect(), IntermKind::Suspend, &context);
+                            Intermission::new(context.fb.rect(), IntermKind::Suspend, &mut context);
+                        interm.animate(&tx);
                         rq.add(RenderData::new(
                             interm.id(),
                             *interm.rect(),
@@ -476,6 +839,7 @@ pub fn run() -> Result<(), Error> {
 
                     if !context.settings.sleep_cover
                         || context.shared
+                        || cover_locked
                         || tasks.iter().any(|task| {
                             task.id == TaskId::PrepareSuspend || task.id == TaskId::Suspend
                         })
@@ -483,22 +847,32 @@ pub fn run() -> Result<(), Error> {
                         continue;
                     }
 
-                    view.handle_event(&Event::Suspend, &tx, &mut bus, &mut rq, &mut context);
-                    let interm =
-                        Intermission::new(context.fb.rect(), IntermKind::Suspend, &context);
-                    rq.add(RenderData::new(
-                        interm.id(),
-                        *interm.rect(),
-                        UpdateMode::Full,
-                    ));
-                    schedule_task(
-                        TaskId::PrepareSuspend,
-                        Event::PrepareSuspend,
-                        PREPARE_SUSPEND_WAIT_DELAY,
-                        &tx,
-                        &mut tasks,
-                    );
-                    view.children_mut().push(Box::new(interm) as Box<dyn View>);
+                    let actions = &context.settings.sleep_cover_actions;
+                    match actions.close {
+                        CoverCloseAction::Suspend if actions.suspend_delay > 0 => {
+                            let delay = Duration::from_secs(actions.suspend_delay as u64);
+                            schedule_task(
+                                TaskId::CoverSuspend,
+                                Event::CoverSuspend,
+                                delay,
+                                &tx,
+                                &mut tasks,
+                            );
+                        }
+                        CoverCloseAction::Suspend => start_suspend(
+                            view.as_mut(),
+                            &mut tasks,
+                            &tx,
+                            &mut bus,
+                            &mut rq,
+                            &mut context,
+                        ),
+                        CoverCloseAction::Lock => {
+                            cover_locked = true;
+                            lock(view.as_mut(), &mut rq, &mut context);
+                        }
+                        CoverCloseAction::Ignore => (),
+                    }
                 }
                 DeviceEvent::CoverOff => {
                     if !context.covered {
@@ -506,12 +880,19 @@ pub fn run() -> Result<(), Error> {
                     }
 
                     context.covered = false;
+                    tasks.retain(|task| task.id != TaskId::CoverSuspend);
 
-                    if context.shared || !context.settings.sleep_cover {
+                    if context.shared
+                        || !context.settings.sleep_cover
+                        || context.settings.sleep_cover_actions.open == CoverOpenAction::Ignore
+                    {
                         continue;
                     }
 
-                    if tasks.iter().any(|task| task.id == TaskId::PrepareSuspend) {
+                    if cover_locked {
+                        cover_locked = false;
+                        unlock(view.as_mut(), &mut rq);
+                    } else if tasks.iter().any(|task| task.id == TaskId::PrepareSuspend) {
                         resume(
                             TaskId::PrepareSuspend,
                             &mut tasks,
@@ -532,6 +913,9 @@ pub fn run() -> Result<(), Error> {
                     }
                 }
                 DeviceEvent::NetUp => {
+                    for evt in network_queue.drain(..) {
+                        tx.send(evt).ok();
+                    }
                     if tasks
                         .iter()
                         .any(|task| task.id == TaskId::PrepareSuspend || task.id == TaskId::Suspend)
@@ -556,6 +940,25 @@ pub fn run() -> Result<(), Error> {
                     );
                     context.online = true;
                     view.children_mut().push(Box::new(notif) as Box<dyn View>);
+                    let tx2 = tx.clone();
+                    thread::spawn(move || {
+                        if let Ok(Some(page)) = captive::detect() {
+                            tx2.send(Event::PortalPage(Some(Box::new(page)))).ok();
+                        }
+                    });
+                    sync_queue::flush(&context.settings, &tx);
+                    hooks::trigger(HookTrigger::NetworkUp, None, &context, &tx);
+                    if context.settings.webdav.enabled
+                        || (context.settings.webdav.annotations.is_some()
+                            && sync_queue::has_pending_annotations())
+                    {
+                        tx.send(Event::Select(EntryId::SyncWebdav)).ok();
+                    }
+                    if context.settings.mail.enabled
+                        && mail::spawn_check(&context.settings, &tx, true)
+                    {
+                        mail_checked_at = Some(Instant::now());
+                    }
                     if view.is::<Home>() {
                         view.handle_event(&evt, &tx, &mut bus, &mut rq, &mut context);
                     } else if let Some(entry) =
@@ -572,6 +975,8 @@ pub fn run() -> Result<(), Error> {
                     }
                 }
                 DeviceEvent::Plug(power_source) => {
+                    host_plugged = power_source == PowerSource::Host;
+
                     if context.plugged {
                         continue;
                     }
@@ -611,7 +1016,9 @@ pub fn run() -> Result<(), Error> {
                                 );
                             }
 
-                            if context.settings.auto_share {
+                            if context.settings.kiosk.enabled {
+                                debug!("The storage isn't shared in kiosk mode.");
+                            } else if context.settings.auto_share || share_pending {
                                 tx.send(Event::PrepareShare).ok();
                             } else {
                                 let dialog = Dialog::builder(
@@ -640,6 +1047,8 @@ pub fn run() -> Result<(), Error> {
                         continue;
                     }
 
+                    host_plugged = false;
+
                     if context.shared {
                         context.shared = false;
                         Command::new("scripts/usb-disable.sh").status().ok();
@@ -661,6 +1070,10 @@ pub fn run() -> Result<(), Error> {
                         if context.settings.wifi {
                             Command::new("scripts/wifi-enable.sh").status().ok();
                         }
+                        if context.settings.bluetooth {
+                            Command::new("scripts/bluetooth-enable.sh").status().ok();
+                        }
+                        remotes.set_enabled(context.settings.bluetooth);
                         if context.settings.frontlight {
                             let levels = context.settings.frontlight_levels;
                             context.frontlight.set_warmth(levels.warmth);
@@ -731,13 +1144,21 @@ pub fn run() -> Result<(), Error> {
 
                     tx.send(Event::Select(EntryId::Rotate(n))).ok();
                 }
-                DeviceEvent::UserActivity if context.settings.auto_suspend > 0.0 => {
+                DeviceEvent::UserActivity
+                    if context.settings.auto_suspend > 0.0
+                        || context.settings.auto_wifi_off > 0.0
+                        || context.settings.bedtime.enabled =>
+                {
                     inactive_since = Instant::now();
                 }
                 _ => {
                     handle_event(view.as_mut(), &evt, &tx, &mut bus, &mut rq, &mut context);
                 }
             },
+            Event::BatteryTick => {
+                record_battery_sample(&mut context);
+                handle_event(view.as_mut(), &evt, &tx, &mut bus, &mut rq, &mut context);
+            }
             Event::CheckBattery => {
                 schedule_task(
                     TaskId::CheckBattery,
@@ -780,7 +1201,10 @@ pub fn run() -> Result<(), Error> {
                 context.library.flush();
 
                 if context.settings.frontlight {
-                    context.settings.frontlight_levels = context.frontlight.levels();
+                    // The light dimmed for the bedtime is back on waking up.
+                    context.settings.frontlight_levels = bedtime
+                        .take_levels()
+                        .unwrap_or_else(|| context.frontlight.levels());
                     context.frontlight.set_intensity(0.0);
                     context.frontlight.set_warmth(0.0);
                 }
@@ -788,6 +1212,11 @@ pub fn run() -> Result<(), Error> {
                     Command::new("scripts/wifi-disable.sh").status().ok();
                     context.online = false;
                 }
+                if context.settings.bluetooth {
+                    Command::new("scripts/bluetooth-disable.sh").status().ok();
+                }
+                record_battery_sample(&mut context);
+                context.battery_history.save(BATTERY_HISTORY_PATH);
                 // https://github.com/koreader/koreader/commit/71afe36
                 schedule_task(
                     TaskId::Suspend,
@@ -851,11 +1280,29 @@ pub fn run() -> Result<(), Error> {
                     }
                 }
             }
+            Event::Select(EntryId::ShareStorage) => {
+                if context.shared {
+                    continue;
+                }
+                if host_plugged {
+                    tx.send(Event::PrepareShare).ok();
+                } else {
+                    share_pending = !share_pending;
+                    let msg = if share_pending {
+                        "The storage will be shared once a computer is plugged in."
+                    } else {
+                        "The storage won't be shared."
+                    };
+                    notify(view.as_mut(), msg.to_string(), &tx, &mut rq, &mut context);
+                }
+            }
             Event::PrepareShare => {
                 if context.shared {
                     continue;
                 }
 
+                share_pending = false;
+
                 tasks.clear();
                 view.handle_event(&Event::Back, &tx, &mut bus, &mut rq, &mut context);
                 while let Some(mut item) = history.pop() {
@@ -876,9 +1323,13 @@ pub fn run() -> Result<(), Error> {
                     .map_err(|e| error!("Can't save settings: {:#}.", e))
                     .ok();
                 context.library.flush();
+                context.battery_history.save(BATTERY_HISTORY_PATH);
 
                 if context.settings.frontlight {
-                    context.settings.frontlight_levels = context.frontlight.levels();
+                    // The light dimmed for the bedtime is back on waking up.
+                    context.settings.frontlight_levels = bedtime
+                        .take_levels()
+                        .unwrap_or_else(|| context.frontlight.levels());
                     context.frontlight.set_intensity(0.0);
                     context.frontlight.set_warmth(0.0);
                 }
@@ -886,8 +1337,11 @@ pub fn run() -> Result<(), Error> {
                     Command::new("scripts/wifi-disable.sh").status().ok();
                     context.online = false;
                 }
+                if context.settings.bluetooth {
+                    Command::new("scripts/bluetooth-disable.sh").status().ok();
+                }
 
-                let interm = Intermission::new(context.fb.rect(), IntermKind::Share, &context);
+                let interm = Intermission::new(context.fb.rect(), IntermKind::Share, &mut context);
                 rq.add(RenderData::new(
                     interm.id(),
                     *interm.rect(),
@@ -910,45 +1364,12 @@ pub fn run() -> Result<(), Error> {
                     exit_status = ExitStatus::PowerOff;
                     break;
                 }
-                GestureEvent::MultiTap(mut points) => {
-                    if points[0].x > points[1].x {
-                        points.swap(0, 1);
-                    }
-                    let rect = context.fb.rect();
-                    let r1 = Region::from_point(
-                        points[0],
-                        rect,
-                        context.settings.reader.strip_width,
-                        context.settings.reader.corner_width,
-                    );
-                    let r2 = Region::from_point(
-                        points[1],
-                        rect,
-                        context.settings.reader.strip_width,
-                        context.settings.reader.corner_width,
-                    );
-                    match (r1, r2) {
-                        (
-                            Region::Corner(DiagDir::SouthWest),
-                            Region::Corner(DiagDir::NorthEast),
-                        ) => {
-                            rq.add(RenderData::new(
-                                view.id(),
-                                context.fb.rect(),
-                                UpdateMode::Full,
-                            ));
-                        }
-                        (
-                            Region::Corner(DiagDir::NorthWest),
-                            Region::Corner(DiagDir::SouthEast),
-                        ) => {
-                            tx.send(Event::Select(EntryId::TakeScreenshot)).ok();
-                        }
-                        _ => (),
-                    }
-                }
                 _ => {
-                    handle_event(view.as_mut(), &evt, &tx, &mut bus, &mut rq, &mut context);
+                    if let Some(mapped_evt) = gesture_map_event(&ge, view.as_ref(), &context) {
+                        tx.send(mapped_evt).ok();
+                    } else {
+                        handle_event(view.as_mut(), &evt, &tx, &mut bus, &mut rq, &mut context);
+                    }
                 }
             },
             Event::ToggleFrontlight => {
@@ -989,6 +1410,8 @@ pub fn run() -> Result<(), Error> {
                     );
                 }
                 let path = info.file.path.clone();
+                let full_path = context.library.home.join(&path);
+                hooks::trigger(HookTrigger::DocumentOpened, Some(&full_path), &context, &tx);
                 if let Some(r) = Reader::new(context.fb.rect(), *info, &tx, &mut context) {
                     let mut next_view = Box::new(r) as Box<dyn View>;
                     transfer_notifications(
@@ -1023,6 +1446,9 @@ pub fn run() -> Result<(), Error> {
                     );
                 }
             }
+            Event::Select(EntryId::Notifications) => {
+                toggle_notification_drawer(view.as_mut(), None, &mut rq, &mut context);
+            }
             Event::Select(EntryId::About) => {
                 #[cfg(feature = "test")]
                 let version_text = match option_env!("PR_INFO") {
@@ -1060,6 +1486,117 @@ pub fn run() -> Result<(), Error> {
                 });
                 view = next_view;
             }
+            Event::Select(EntryId::ViewCrashReport(ref path)) => {
+                tx.send(Event::Close(ViewId::CrashDialog)).ok();
+                match crash::report_as_html(path) {
+                    Ok(html) => {
+                        tx.send(Event::OpenHtml(html, None)).ok();
+                    }
+                    Err(e) => {
+                        let msg = format!("Can't open the crash report: {:#}.", e);
+                        notify(view.as_mut(), msg, &tx, &mut rq, &mut context);
+                    }
+                }
+            }
+            Event::Select(EntryId::ExportCrashReport(ref path)) => {
+                tx.send(Event::Close(ViewId::CrashDialog)).ok();
+                let msg = match crash::export_report(path, &context.library.home) {
+                    Ok(target) => format!(
+                        "The crash report was exported to {}.",
+                        target
+                            .strip_prefix(&context.library.home)
+                            .unwrap_or(&target)
+                            .display()
+                    ),
+                    Err(e) => format!("Can't export the crash report: {:#}.", e),
+                };
+                notify(view.as_mut(), msg, &tx, &mut rq, &mut context);
+            }
+            Event::CheckStorage => {
+                let threshold = context.settings.low_storage_threshold * 1024 * 1024;
+                let Some((free, _)) = storage::space(Path::new(INTERNAL_CARD_ROOT)) else {
+                    continue;
+                };
+                let low = threshold > 0 && free < threshold;
+                if low && !storage_low {
+                    let notif = Notification::new(
+                        None,
+                        format!(
+                            "Only {} left on the device. Tap to free up space.",
+                            free.human_size()
+                        ),
+                        false,
+                        &tx,
+                        &mut rq,
+                        &mut context,
+                    )
+                    .with_action(Event::Select(EntryId::CleanUpStorage));
+                    view.children_mut().push(Box::new(notif) as Box<dyn View>);
+                }
+                storage_low = low;
+            }
+            Event::Select(EntryId::CleanUpStorage) => {
+                if let Some(index) = locate_by_id(view.as_ref(), ViewId::CleanupMenu) {
+                    let rect = *view.child(index).rect();
+                    view.children_mut().remove(index);
+                    rq.add(RenderData::expose(rect, UpdateMode::Gui));
+                }
+                let items = storage::reclaimable_items(&context.settings);
+                if items.is_empty() {
+                    notify(
+                        view.as_mut(),
+                        "There's nothing to clean up.".to_string(),
+                        &tx,
+                        &mut rq,
+                        &mut context,
+                    );
+                    continue;
+                }
+                let total: u64 = items.iter().map(|item| item.size).sum();
+                let mut entries = items
+                    .iter()
+                    .map(|item| {
+                        EntryKind::Command(item.label(), EntryId::Reclaim(item.path.clone()))
+                    })
+                    .collect::<Vec<EntryKind>>();
+                if items.len() > 1 {
+                    entries.push(EntryKind::Separator);
+                    entries.push(EntryKind::Command(
+                        format!("Clean Up All ({})", total.human_size()),
+                        EntryId::ReclaimAll,
+                    ));
+                }
+                let center = context.fb.rect().center();
+                let cleanup_menu = Menu::new(
+                    Rectangle::from_point(center),
+                    ViewId::CleanupMenu,
+                    MenuKind::Contextual,
+                    entries,
+                    &mut context,
+                );
+                rq.add(RenderData::new(
+                    cleanup_menu.id(),
+                    *cleanup_menu.rect(),
+                    UpdateMode::Gui,
+                ));
+                view.children_mut()
+                    .push(Box::new(cleanup_menu) as Box<dyn View>);
+            }
+            Event::Select(EntryId::Reclaim(ref path)) => {
+                let items = storage::reclaimable_items(&context.settings)
+                    .into_iter()
+                    .filter(|item| item.path == *path)
+                    .collect::<Vec<storage::Item>>();
+                let msg = reclaim_items(&items);
+                notify(view.as_mut(), msg, &tx, &mut rq, &mut context);
+                tx.send(Event::CheckStorage).ok();
+            }
+            Event::Select(EntryId::ReclaimAll) => {
+                let items = storage::reclaimable_items(&context.settings);
+                let msg = reclaim_items(&items);
+                notify(view.as_mut(), msg, &tx, &mut rq, &mut context);
+                tx.send(Event::CheckStorage).ok();
+            }
             Event::Select(EntryId::OpenDocumentation) => {
                 view.children_mut().retain(|child| !child.is::<Menu>());
 
@@ -1110,7 +1647,8 @@ pub fn run() -> Result<(), Error> {
                 view = next_view;
             }
             Event::Select(EntryId::Launch(app_cmd)) => {
-                view.children_mut().retain(|child| !child.is::<Menu>());
+                view.children_mut()
+                    .retain(|child| !child.is::<Menu>() && !child.is::<Dialog>());
                 let monochrome = context.fb.monochrome();
                 let mut next_view: Box<dyn View> = match app_cmd {
                     AppCmd::Sketch => {
@@ -1137,11 +1675,26 @@ pub fn run() -> Result<(), Error> {
                     AppCmd::TouchEvents => {
                         Box::new(TouchEvents::new(context.fb.rect(), &mut rq, &mut context))
                     }
+                    AppCmd::Screenshots => {
+                        Box::new(Screenshots::new(context.fb.rect(), &mut rq, &mut context))
+                    }
                     AppCmd::RotationValues => Box::new(RotationValues::new(
                         context.fb.rect(),
                         &mut rq,
                         &mut context,
                     )),
+                    AppCmd::Dashboard => {
+                        Box::new(Dashboard::new(context.fb.rect(), &mut rq, &mut context))
+                    }
+                    AppCmd::BatteryStats => {
+                        Box::new(BatteryStats::new(context.fb.rect(), &mut rq, &mut context))
+                    }
+                    AppCmd::CaptivePortal => Box::new(CaptivePortal::new(
+                        context.fb.rect(),
+                        &tx,
+                        &mut rq,
+                        &mut context,
+                    )),
                     AppCmd::SettingsEditor => Box::new(SettingsEditor::new(
                         context.fb.rect(),
                         &mut rq,
@@ -1206,6 +1759,219 @@ pub fn run() -> Result<(), Error> {
                         .push(Box::new(preset_menu) as Box<dyn View>);
                 }
             }
+            Event::Focus(view_id) => {
+                keyboards.set_typing(view_id.is_some());
+                handle_event(view.as_mut(), &evt, &tx, &mut bus, &mut rq, &mut context);
+            }
+            Event::MoveFocus(dir) => {
+                move_focus(view.as_ref(), dir, &mut rq, &mut context);
+            }
+            Event::ActivateFocus => {
+                activate_focus(view.as_ref(), &tx, &context);
+            }
+            Event::Show(ViewId::CommandPalette) => {
+                show_command_palette(view.as_mut(), &tx, &mut rq, &mut context);
+            }
+            Event::Show(ViewId::SetupDialog) => {
+                show_setup_step(
+                    view.as_mut(),
+                    Some(SetupStep::Language),
+                    &tx,
+                    &mut rq,
+                    &mut context,
+                );
+            }
+            Event::Select(EntryId::Setup(SetupChoice::ChooseLibrary)) => {
+                if let Some(index) = locate_by_id(view.as_ref(), ViewId::SetupDialog) {
+                    view.children_mut().remove(index);
+                }
+                choosing_library = true;
+                let file_chooser = FileChooser::new(
+                    context.fb.rect(),
+                    PathBuf::from(INTERNAL_CARD_ROOT),
+                    SelectionMode::Directory,
+                    &tx,
+                    &mut rq,
+                    &mut context,
+                );
+                rq.add(RenderData::new(
+                    file_chooser.id(),
+                    *file_chooser.rect(),
+                    UpdateMode::Gui,
+                ));
+                view.children_mut()
+                    .push(Box::new(file_chooser) as Box<dyn View>);
+            }
+            Event::Select(EntryId::Setup(SetupChoice::Restore(true))) => {
+                if let Some(index) = locate_by_id(view.as_ref(), ViewId::SetupDialog) {
+                    let rect = overlapping_rectangle(view.child(index));
+                    rq.add(RenderData::expose(rect, UpdateMode::Gui));
+                    view.children_mut().remove(index);
+                }
+                tx.send(Event::Select(EntryId::ShowBackups)).ok();
+            }
+            Event::Select(EntryId::Setup(ref choice)) => {
+                let step = setup_wizard::apply(choice, &tx, &mut context);
+                show_setup_step(view.as_mut(), step, &tx, &mut rq, &mut context);
+            }
+            Event::FileChooserClosed(Some(ref path)) if choosing_library => {
+                choosing_library = false;
+                let choice = SetupChoice::Library(path.clone());
+                let step = setup_wizard::apply(&choice, &tx, &mut context);
+                show_setup_step(view.as_mut(), step, &tx, &mut rq, &mut context);
+            }
+            Event::Close(ViewId::FileChooser) if choosing_library => {
+                choosing_library = false;
+                if let Some(index) = locate_by_id(view.as_ref(), ViewId::FileChooser) {
+                    view.children_mut().remove(index);
+                }
+                rq.add(RenderData::new(
+                    view.id(),
+                    context.fb.rect(),
+                    UpdateMode::Gui,
+                ));
+                show_setup_step(
+                    view.as_mut(),
+                    Some(SetupStep::Library),
+                    &tx,
+                    &mut rq,
+                    &mut context,
+                );
+            }
+            Event::Show(ViewId::ResetDialog) => {
+                let dialog = Dialog::builder(
+                    ViewId::ResetDialog,
+                    "Erase the settings and the library databases? The books are kept.".to_string(),
+                )
+                .add_button("Cancel", Event::Close(ViewId::ResetDialog))
+                .add_button(
+                    "Back Up and Reset",
+                    Event::Select(EntryId::ResetCadmus { backup: true }),
+                )
+                .add_button(
+                    "Reset",
+                    Event::Select(EntryId::ResetCadmus { backup: false }),
+                )
+                .build(&mut context);
+                rq.add(RenderData::new(
+                    dialog.id(),
+                    *dialog.rect(),
+                    UpdateMode::Gui,
+                ));
+                view.children_mut().push(Box::new(dialog) as Box<dyn View>);
+            }
+            Event::Select(EntryId::ResetCadmus { backup }) => {
+                exit_status = ExitStatus::Reset { backup };
+                break;
+            }
+            Event::Select(EntryId::ToggleKioskMode) => {
+                let title = if context.settings.kiosk.enabled {
+                    "Enter the PIN to leave kiosk mode."
+                } else {
+                    "Choose a PIN for kiosk mode."
+                };
+                let pin_pad = PinPad::new(ViewId::KioskPin, title, &mut context);
+                rq.add(RenderData::new(
+                    pin_pad.id(),
+                    *pin_pad.rect(),
+                    UpdateMode::Gui,
+                ));
+                view.children_mut().push(Box::new(pin_pad) as Box<dyn View>);
+            }
+            Event::Submit(ViewId::KioskPin, ref pin) => {
+                let msg = if context.settings.kiosk.enabled {
+                    if kiosk::unlock(pin, &mut context.settings) {
+                        if context.settings.web_server.enabled {
+                            if let Some(server) = start_web_server(&context, &tx) {
+                                web_server.replace(server);
+                            }
+                        }
+                        "Kiosk mode is off."
+                    } else {
+                        "Wrong PIN."
+                    }
+                } else if pin.len() < 4 {
+                    "The PIN must have at least 4 digits."
+                } else {
+                    kiosk::lock(pin, &mut context.settings);
+                    drop(web_server.take());
+                    "Kiosk mode is on."
+                };
+                save_toml(&context.settings, SETTINGS_PATH)
+                    .map_err(|e| error!("Can't save settings: {:#}.", e))
+                    .ok();
+                notify(view.as_mut(), msg.to_string(), &tx, &mut rq, &mut context);
+            }
+            Event::Select(EntryId::CreateBackup) => {
+                context.library.flush();
+                let settings = context.settings.clone();
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    let msg =
+                        match backup::create(&settings, Path::new("."), &backup::backups_dir()) {
+                            Ok(path) => format!("Backed up to {}.", path.display()),
+                            Err(e) => format!("Can't back up: {:#}.", e),
+                        };
+                    tx.send(Event::Notification(NotificationEvent::Show(msg)))
+                        .ok();
+                });
+            }
+            Event::Select(EntryId::ShowBackups) => {
+                let archives = backup::archives();
+                if archives.is_empty() {
+                    notify(
+                        view.as_mut(),
+                        "No backups found.".to_string(),
+                        &tx,
+                        &mut rq,
+                        &mut context,
+                    );
+                    continue;
+                }
+                let entries = archives
+                    .into_iter()
+                    .map(|path| {
+                        let name = path.file_name().unwrap_or_default().to_string_lossy();
+                        EntryKind::Command(name.into_owned(), EntryId::SelectBackup(path))
+                    })
+                    .collect::<Vec<EntryKind>>();
+                let center = context.fb.rect().center();
+                let backups_menu = Menu::new(
+                    Rectangle::from_point(center),
+                    ViewId::BackupsMenu,
+                    MenuKind::Contextual,
+                    entries,
+                    &mut context,
+                );
+                rq.add(RenderData::new(
+                    backups_menu.id(),
+                    *backups_menu.rect(),
+                    UpdateMode::Gui,
+                ));
+                view.children_mut()
+                    .push(Box::new(backups_menu) as Box<dyn View>);
+            }
+            Event::Select(EntryId::SelectBackup(ref path)) => {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                let dialog = ConfirmDialog::new(
+                    ViewId::ConfirmDialog,
+                    "Restore this backup? Cadmus restarts afterwards.",
+                    &name,
+                    "Restore",
+                    Event::Select(EntryId::RestoreBackup(path.clone())),
+                    &mut context,
+                );
+                rq.add(RenderData::new(
+                    dialog.id(),
+                    *dialog.rect(),
+                    UpdateMode::Gui,
+                ));
+                view.children_mut().push(Box::new(dialog) as Box<dyn View>);
+            }
+            Event::Select(EntryId::RestoreBackup(ref path)) => {
+                exit_status = ExitStatus::Restore(path.clone());
+                break;
+            }
             Event::Show(ViewId::Frontlight) => {
                 if !context.settings.frontlight {
                     context.set_frontlight(true);
@@ -1240,6 +2006,14 @@ pub fn run() -> Result<(), Error> {
                     rq.add(RenderData::expose(rect, UpdateMode::Gui));
                     view.children_mut().remove(index);
                 }
+                dismiss_toast(view.as_mut(), id, &tx, &mut rq, &mut context);
+            }
+            Event::Select(EntryId::FullRefresh) => {
+                rq.add(RenderData::new(
+                    view.id(),
+                    context.fb.rect(),
+                    UpdateMode::Full,
+                ));
             }
             Event::Select(EntryId::ToggleInverted) => {
                 context.fb.toggle_inverted();
@@ -1280,6 +2054,9 @@ pub fn run() -> Result<(), Error> {
             }
             Event::Select(EntryId::SetRotationLock(rotation_lock)) => {
                 context.settings.rotation_lock = rotation_lock;
+
+                // Re-dispatch event to view hierarchy so UI can update
+                handle_event(view.as_mut(), &evt, &tx, &mut bus, &mut rq, &mut context);
             }
             Event::Select(EntryId::SetButtonScheme(button_scheme)) => {
                 context.settings.button_scheme = button_scheme;
@@ -1298,19 +2075,81 @@ pub fn run() -> Result<(), Error> {
                 handle_event(view.as_mut(), &evt, &tx, &mut bus, &mut rq, &mut context);
             }
             Event::SetWifi(enable) => {
+                wifi_idled = false;
+                network_queue.clear();
                 set_wifi(enable, &mut context);
             }
+            Event::SetBluetooth(enable) => {
+                set_bluetooth(enable, &remotes, &mut context);
+            }
+            Event::Select(
+                EntryId::CheckForUpdates
+                | EntryId::SyncProgress
+                | EntryId::SyncWebdav
+                | EntryId::CheckMail
+                | EntryId::ToggleCalibre
+                | EntryId::RetryDownloads
+                | EntryId::ToggleDownloads,
+            ) if wifi_idled
+                && !context.settings.wifi
+                && (download::is_paused()
+                    || !matches!(evt, Event::Select(EntryId::ToggleDownloads))) =>
+            {
+                wifi_idled = false;
+                set_wifi(true, &mut context);
+                notify(
+                    view.as_mut(),
+                    "Turning the Wi-Fi back on.".to_string(),
+                    &tx,
+                    &mut rq,
+                    &mut context,
+                );
+                network_queue.push(evt);
+            }
             Event::Select(EntryId::CheckForUpdates) => {
                 show_ota_view(view.as_mut(), &tx, &mut rq, &mut context);
             }
             Event::Select(EntryId::ToggleWifi) => {
+                wifi_idled = false;
+                network_queue.clear();
                 set_wifi(!context.settings.wifi, &mut context);
             }
+            Event::Select(EntryId::ToggleWebServer) => {
+                context.settings.web_server.enabled = !context.settings.web_server.enabled;
+                let msg = if context.settings.web_server.enabled {
+                    web_server = start_web_server(&context, &tx);
+                    match web_server.as_ref() {
+                        Some(server) => {
+                            let ip = Command::new("scripts/ip.sh")
+                                .output()
+                                .map(|o| String::from_utf8_lossy(&o.stdout).trim_end().to_string())
+                                .unwrap_or_default();
+                            format!("Web server started at http://{}:{}/.", ip, server.port())
+                        }
+                        None => {
+                            context.settings.web_server.enabled = false;
+                            "Can't start the web server.".to_string()
+                        }
+                    }
+                } else {
+                    drop(web_server.take());
+                    "Web server stopped.".to_string()
+                };
+                notify(view.as_mut(), msg, &tx, &mut rq, &mut context);
+            }
+            Event::Select(EntryId::ToggleInspector) => {
+                toggle_inspector(&mut rq, &mut context);
+            }
+            Event::Select(EntryId::TogglePerfHud) => {
+                toggle_perf_hud(&tx, &mut rq, &mut context);
+            }
+            Event::PerfHudTick => {
+                tick_perf_hud(&mut rq, &mut context);
+            }
             Event::Select(EntryId::TakeScreenshot) => {
-                let name = Local::now().format("screenshot-%Y%m%d_%H%M%S.png");
-                let msg = match context.fb.save(&name.to_string()) {
-                    Err(e) => format!("{}", e),
-                    Ok(_) => format!("Saved {}.", name),
+                let msg = match take_screenshot(&mut context) {
+                    Err(e) => format!("{:#}", e),
+                    Ok(path) => format!("Saved {}.", path.display()),
                 };
                 let notif = Notification::new(None, msg, false, &tx, &mut rq, &mut context);
                 view.children_mut().push(Box::new(notif) as Box<dyn View>);
@@ -1332,10 +2171,12 @@ pub fn run() -> Result<(), Error> {
                     );
                 }
             }
+            Event::Progress(ProgressEvent::Show(id, title, kind)) => {
+                show_progress_dialog(view.as_mut(), id, title, kind, &mut rq, &mut context);
+            }
             Event::Notification(notif_event) => match notif_event {
                 NotificationEvent::Show(msg) => {
-                    let notif = Notification::new(None, msg, false, &tx, &mut rq, &mut context);
-                    view.children_mut().push(Box::new(notif) as Box<dyn View>);
+                    notify(view.as_mut(), msg, &tx, &mut rq, &mut context);
                 }
                 NotificationEvent::ShowPinned(id, msg) => {
                     let notif = Notification::new(Some(id), msg, true, &tx, &mut rq, &mut context);
@@ -1362,8 +2203,7 @@ pub fn run() -> Result<(), Error> {
                 }
             },
             Event::Notify(msg) => {
-                let notif = Notification::new(None, msg, false, &tx, &mut rq, &mut context);
-                view.children_mut().push(Box::new(notif) as Box<dyn View>);
+                notify(view.as_mut(), msg, &tx, &mut rq, &mut context);
             }
             Event::Select(EntryId::Restart) => {
                 exit_status = ExitStatus::Restart;
@@ -1387,9 +2227,14 @@ pub fn run() -> Result<(), Error> {
                 }
                 let seconds = 60.0 * context.settings.auto_suspend;
                 if inactive_since.elapsed() > Duration::from_secs_f32(seconds) {
+                    if cover_locked {
+                        cover_locked = false;
+                        unlock(view.as_mut(), &mut rq);
+                    }
                     view.handle_event(&Event::Suspend, &tx, &mut bus, &mut rq, &mut context);
                     let interm =
-                        Intermission::new(context.fb.rect(), IntermKind::Suspend, &context);
+                        Intermission::new(context.fb.rect(), IntermKind::Suspend, &mut context);
+                    interm.animate(&tx);
                     rq.add(RenderData::new(
                         interm.id(),
                         *interm.rect(),
@@ -1405,11 +2250,341 @@ pub fn run() -> Result<(), Error> {
                     view.children_mut().push(Box::new(interm) as Box<dyn View>);
                 }
             }
+            Event::MightDisableWifi if context.settings.auto_wifi_off > 0.0 => {
+                // Someone uploading from a computer is idle on the device.
+                if !context.settings.wifi
+                    || context.shared
+                    || !network_queue.is_empty()
+                    || web_server.is_some()
+                    || calibre.is_some()
+                    || (!download::is_paused() && download::pending_count() > 0)
+                    || net::is_busy()
+                    || tasks
+                        .iter()
+                        .any(|task| task.id == TaskId::PrepareSuspend || task.id == TaskId::Suspend)
+                {
+                    continue;
+                }
+                let seconds = 60.0 * context.settings.auto_wifi_off;
+                if inactive_since.elapsed() > Duration::from_secs_f32(seconds) {
+                    set_wifi(false, &mut context);
+                    wifi_idled = true;
+                    notify(
+                        view.as_mut(),
+                        "The Wi-Fi was turned off after a period of inactivity.".to_string(),
+                        &tx,
+                        &mut rq,
+                        &mut context,
+                    );
+                }
+            }
+            Event::MightCheckMail if context.settings.mail.enabled => {
+                if !context.online || context.shared {
+                    continue;
+                }
+                let interval = Duration::from_secs(60 * context.settings.mail.interval as u64);
+                if mail_checked_at.is_some_and(|instant| instant.elapsed() < interval) {
+                    continue;
+                }
+                if mail::spawn_check(&context.settings, &tx, true) {
+                    mail_checked_at = Some(Instant::now());
+                }
+            }
+            Event::PortalPage(Some(..))
+                if !view.is::<CaptivePortal>()
+                    && locate_by_id(view.as_ref(), ViewId::PortalDialog).is_none() =>
+            {
+                let dialog = Dialog::builder(
+                    ViewId::PortalDialog,
+                    "This network requires signing in.".to_string(),
+                )
+                .add_button("Cancel", Event::Close(ViewId::PortalDialog))
+                .add_button(
+                    "Sign In",
+                    Event::Select(EntryId::Launch(AppCmd::CaptivePortal)),
+                )
+                .build(&mut context);
+                rq.add(RenderData::new(
+                    dialog.id(),
+                    *dialog.rect(),
+                    UpdateMode::Gui,
+                ));
+                view.children_mut().push(Box::new(dialog) as Box<dyn View>);
+            }
+            Event::FileReceived(ref path) | Event::FileRemoved(ref path) => {
+                if context.shared {
+                    continue;
+                }
+                context.batch_import();
+                view.handle_event(&Event::Reseed, &tx, &mut bus, &mut rq, &mut context);
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let msg = if matches!(evt, Event::FileReceived(..)) {
+                    format!("Received {}.", name)
+                } else {
+                    format!("Removed {}.", name)
+                };
+                notify(view.as_mut(), msg, &tx, &mut rq, &mut context);
+            }
+            Event::Select(EntryId::ToggleCalibre) => {
+                if calibre.take().is_some() {
+                    context.calibre = false;
+                } else {
+                    context.library.flush();
+                    match Calibre::connect(&context.settings, &tx) {
+                        Ok(connection) => {
+                            calibre = Some(connection);
+                            context.calibre = true;
+                            notify(
+                                view.as_mut(),
+                                "Looking for Calibre.".to_string(),
+                                &tx,
+                                &mut rq,
+                                &mut context,
+                            );
+                        }
+                        Err(e) => {
+                            let msg = format!("Can't connect to Calibre: {:#}.", e);
+                            notify(view.as_mut(), msg, &tx, &mut rq, &mut context);
+                        }
+                    }
+                }
+            }
+            Event::CalibreDisconnected => {
+                calibre = None;
+                context.calibre = false;
+            }
+            Event::Select(EntryId::SyncWebdav) => {
+                context.library.flush();
+                let msg = if webdav::spawn_sync(&context.settings, &tx) {
+                    "Syncing with the WebDAV server."
+                } else {
+                    "A WebDAV sync is already running."
+                };
+                notify(view.as_mut(), msg.to_string(), &tx, &mut rq, &mut context);
+            }
+            Event::Select(EntryId::ShowWebdavLog) => {
+                tx.send(Event::OpenHtml(webdav::log_as_html(), None)).ok();
+            }
+            Event::Select(EntryId::CheckMail) => {
+                let msg = if mail::spawn_check(&context.settings, &tx, false) {
+                    mail_checked_at = Some(Instant::now());
+                    "Checking the mailbox."
+                } else {
+                    "The mailbox is already being checked."
+                };
+                notify(view.as_mut(), msg.to_string(), &tx, &mut rq, &mut context);
+            }
+            Event::Select(EntryId::RunHook(index)) => {
+                if let Some(hook) = context.settings.hooks.get(index) {
+                    if let Err(e) = hooks::run(hook, "menu", None, &context, &tx) {
+                        let msg = format!("Can't run {}: {:#}.", hook.name, e);
+                        notify(view.as_mut(), msg, &tx, &mut rq, &mut context);
+                    }
+                }
+            }
+            Event::Select(EntryId::ShowDownloads) => {
+                tx.send(Event::OpenHtml(download::queue_as_html(), None))
+                    .ok();
+            }
+            Event::Select(EntryId::ToggleDownloads) => {
+                let paused = !download::is_paused();
+                download::set_paused(paused);
+                let msg = if paused {
+                    "Downloads paused."
+                } else {
+                    "Downloads resumed."
+                };
+                notify(view.as_mut(), msg.to_string(), &tx, &mut rq, &mut context);
+            }
+            Event::Select(EntryId::RetryDownloads) => {
+                let count = download::retry_failed();
+                let msg = format!(
+                    "Retrying {} download{}.",
+                    count,
+                    if count == 1 { "" } else { "s" }
+                );
+                notify(view.as_mut(), msg, &tx, &mut rq, &mut context);
+            }
+            Event::RemoteOpen(ref path) => {
+                let parent = path.parent().map_or_else(
+                    || context.library.home.clone(),
+                    |parent| context.library.home.join(parent),
+                );
+                let (files, _) = context.library.list(&parent, None, false);
+                if let Some(info) = files.into_iter().find(|info| info.file.path == *path) {
+                    if view.is::<Reader>() {
+                        view.handle_event(&Event::Back, &tx, &mut bus, &mut rq, &mut context);
+                        tx.send(Event::Back).ok();
+                    }
+                    tx.send(Event::Open(Box::new(info))).ok();
+                } else {
+                    let msg = format!("Can't find {}.", path.display());
+                    notify(view.as_mut(), msg, &tx, &mut rq, &mut context);
+                }
+            }
+            Event::RemoteStatus(ref sender) => {
+                let reader = std::iter::once(&view)
+                    .chain(history.iter().rev().map(|item| &item.view))
+                    .find_map(|view| view.downcast_ref::<Reader>());
+                sender.send(api::Status::new(&mut context, reader)).ok();
+            }
+            Event::WebdavSynced(ref report) => {
+                if report.changed_locally() && !context.shared {
+                    context.batch_import();
+                    view.handle_event(&Event::Reseed, &tx, &mut bus, &mut rq, &mut context);
+                }
+                notify(
+                    view.as_mut(),
+                    format!("{}.", report),
+                    &tx,
+                    &mut rq,
+                    &mut context,
+                );
+            }
+            Event::BedtimeTick => {
+                if context.shared
+                    || tasks
+                        .iter()
+                        .any(|task| task.id == TaskId::PrepareSuspend || task.id == TaskId::Suspend)
+                {
+                    continue;
+                }
+                let now = Local::now().naive_local();
+                match bedtime.phase(now, &context.settings.bedtime) {
+                    Some(Phase::WindDown(progress)) if context.settings.frontlight => {
+                        if let Some(levels) = bedtime.dim(
+                            context.frontlight.levels(),
+                            progress,
+                            &context.settings.bedtime,
+                        ) {
+                            context.frontlight.set_warmth(levels.warmth);
+                            context.frontlight.set_intensity(levels.intensity);
+                        }
+                    }
+                    Some(Phase::Due) if inactive_since.elapsed() < BEDTIME_ACTIVE_DELAY => {
+                        if locate_by_id(view.as_ref(), ViewId::BedtimeDialog).is_some() {
+                            continue;
+                        }
+                        let dialog =
+                            Dialog::builder(ViewId::BedtimeDialog, "It's bedtime.".to_string())
+                                .add_button("Snooze", Event::Select(EntryId::SnoozeBedtime))
+                                .add_button("Sleep", Event::Select(EntryId::GoToBed))
+                                .build(&mut context);
+                        rq.add(RenderData::new(
+                            dialog.id(),
+                            *dialog.rect(),
+                            UpdateMode::Gui,
+                        ));
+                        view.children_mut().push(Box::new(dialog) as Box<dyn View>);
+                    }
+                    Some(Phase::Due) => {
+                        tx.send(Event::Select(EntryId::GoToBed)).ok();
+                    }
+                    _ => (),
+                }
+            }
+            Event::Select(EntryId::SnoozeBedtime) => {
+                tx.send(Event::Close(ViewId::BedtimeDialog)).ok();
+                bedtime.snooze(Local::now().naive_local(), &context.settings.bedtime);
+                notify(
+                    view.as_mut(),
+                    format!(
+                        "The bedtime was postponed by {} minutes.",
+                        context.settings.bedtime.snooze
+                    ),
+                    &tx,
+                    &mut rq,
+                    &mut context,
+                );
+            }
+            Event::Select(EntryId::GoToBed) => {
+                if let Some(index) = locate_by_id(view.as_ref(), ViewId::BedtimeDialog) {
+                    let rect = overlapping_rectangle(view.child(index));
+                    rq.add(RenderData::expose(rect, UpdateMode::Gui));
+                    view.children_mut().remove(index);
+                }
+                if context.shared
+                    || tasks
+                        .iter()
+                        .any(|task| task.id == TaskId::PrepareSuspend || task.id == TaskId::Suspend)
+                {
+                    continue;
+                }
+                bedtime.finish(Local::now().naive_local(), &context.settings.bedtime);
+                match context.settings.bedtime.action {
+                    BedtimeAction::Suspend => {
+                        if cover_locked {
+                            cover_locked = false;
+                            unlock(view.as_mut(), &mut rq);
+                        }
+                        start_suspend(
+                            view.as_mut(),
+                            &mut tasks,
+                            &tx,
+                            &mut bus,
+                            &mut rq,
+                            &mut context,
+                        );
+                    }
+                    BedtimeAction::PowerOff => {
+                        power_off(view.as_mut(), &mut history, &mut updating, &mut context);
+                        exit_status = ExitStatus::PowerOff;
+                        break;
+                    }
+                }
+            }
+            Event::AmbientLightTick => {
+                if !context.settings.frontlight
+                    || !context.settings.auto_brightness.enabled
+                    || bedtime.is_dimming()
+                    || context.shared
+                    || tasks
+                        .iter()
+                        .any(|task| task.id == TaskId::PrepareSuspend || task.id == TaskId::Suspend)
+                {
+                    continue;
+                }
+                if let Ok(level) = context.lightsensor.level() {
+                    let intensity = context.frontlight.levels().intensity;
+                    if let Some(intensity) =
+                        auto_brightness.adjust(level, intensity, &context.settings.auto_brightness)
+                    {
+                        context.frontlight.set_intensity(intensity);
+                    }
+                }
+            }
+            Event::CoverSuspend => {
+                tasks.retain(|task| task.id != TaskId::CoverSuspend);
+                if !context.covered
+                    || context.shared
+                    || tasks
+                        .iter()
+                        .any(|task| task.id == TaskId::PrepareSuspend || task.id == TaskId::Suspend)
+                {
+                    continue;
+                }
+                start_suspend(
+                    view.as_mut(),
+                    &mut tasks,
+                    &tx,
+                    &mut bus,
+                    &mut rq,
+                    &mut context,
+                );
+            }
+            Event::ClockTick => {
+                context.scripts.clock(Local::now(), &context, &tx);
+                handle_event(view.as_mut(), &evt, &tx, &mut bus, &mut rq, &mut context);
+            }
             _ => {
                 handle_event(view.as_mut(), &evt, &tx, &mut bus, &mut rq, &mut context);
             }
         }
 
+        context.perf_hud.record_event(handling_started.elapsed());
         process_render_queue(view.as_ref(), &mut rq, &mut context, &mut updating);
 
         while let Some(ce) = bus.pop_front() {
@@ -1426,17 +2601,37 @@ pub fn run() -> Result<(), Error> {
 
     if tasks.iter().all(|task| task.id != TaskId::Suspend) {
         if context.settings.frontlight {
-            context.settings.frontlight_levels = context.frontlight.levels();
+            context.settings.frontlight_levels = bedtime
+                .take_levels()
+                .unwrap_or_else(|| context.frontlight.levels());
         }
     }
 
     context.library.flush();
+    context.battery_history.save(BATTERY_HISTORY_PATH);
 
     let path = Path::new(SETTINGS_PATH);
     save_toml(&context.settings, path).context("can't save settings")?;
 
     match exit_status {
-        ExitStatus::Restart => {
+        ExitStatus::Reset { backup } => {
+            if backup {
+                let path =
+                    backup::create(&context.settings, Path::new("."), &backup::backups_dir())
+                        .context("can't back up before resetting")?;
+                info!("Backed up to {}.", path.display());
+            }
+            let entries = reset::entries(&context.settings, Path::new("."));
+            reset::wipe(&entries).context("can't reset")?;
+        }
+        ExitStatus::Restore(ref path) => {
+            backup::restore(path, Path::new(".")).context("can't restore")?;
+        }
+        _ => (),
+    }
+
+    match exit_status {
+        ExitStatus::Restart | ExitStatus::Reset { .. } | ExitStatus::Restore(..) => {
             File::create("/tmp/restart").ok();
         }
         ExitStatus::Reboot => {
@@ -0,0 +1,535 @@
+//! Decoding the frames of animated GIF and PNG images.
+//!
+//! The frames are composed over a white canvas, as the screen has no transparency: each frame
+//! is the full picture shown at its time, not the rectangle that changed. The static contexts
+//! only decode the first frame.
+
+use crate::color::Color;
+use crate::framebuffer::Pixmap;
+use anyhow::{format_err, Context, Error};
+use png::{BlendOp, ColorType, DisposeOp, Transformations};
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+use std::time::Duration;
+
+/// The delay of the frames that don't specify one.
+const DEFAULT_DELAY: Duration = Duration::from_millis(100);
+
+// The largest number of entries of the dictionary of the LZW compression of GIF.
+const MAX_LZW_CODES: usize = 4096;
+
+pub struct Frame {
+    pub pixmap: Pixmap,
+    /// How long the frame is shown before the next one.
+    pub delay: Duration,
+}
+
+pub struct Animation {
+    pub frames: Vec<Frame>,
+}
+
+/// Returns whether the files of the given kind can be animated.
+pub fn is_animated_kind(kind: &str) -> bool {
+    matches!(kind, "gif" | "png" | "apng")
+}
+
+impl Animation {
+    /// Decodes at most `max_frames` frames of the image, with `samples` samples per pixel.
+    pub fn open<P: AsRef<Path>>(
+        path: P,
+        max_frames: usize,
+        samples: usize,
+    ) -> Result<Animation, Error> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).with_context(|| format!("can't read {}", path.display()))?;
+        Animation::decode(&bytes, max_frames, samples)
+            .with_context(|| format!("can't decode {}", path.display()))
+    }
+
+    pub fn decode(bytes: &[u8], max_frames: usize, samples: usize) -> Result<Animation, Error> {
+        let mut canvases = if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            decode_gif(bytes, max_frames.max(1))?
+        } else if bytes.starts_with(b"\x89PNG") {
+            decode_png(bytes, max_frames.max(1))?
+        } else {
+            return Err(format_err!("unknown image format"));
+        };
+        if canvases.is_empty() {
+            return Err(format_err!("no frames"));
+        }
+        let frames = canvases
+            .drain(..)
+            .map(|(canvas, delay)| Frame {
+                pixmap: canvas.to_pixmap(samples),
+                delay,
+            })
+            .collect();
+        Ok(Animation { frames })
+    }
+
+    pub fn is_animated(&self) -> bool {
+        self.frames.len() > 1
+    }
+}
+
+// An RGB picture composed from the successive frames.
+#[derive(Clone)]
+struct Canvas {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32) -> Canvas {
+        Canvas {
+            width,
+            height,
+            data: vec![0xFF; 3 * (width * height) as usize],
+        }
+    }
+
+    // Blends the pixel over the canvas, ignoring the pixels outside of it.
+    fn blend(&mut self, x: u32, y: u32, rgb: [u8; 3], alpha: u8) {
+        if x >= self.width || y >= self.height || alpha == 0 {
+            return;
+        }
+        let addr = 3 * (y * self.width + x) as usize;
+        for (i, &c) in rgb.iter().enumerate() {
+            let back = self.data[addr + i] as u32;
+            self.data[addr + i] =
+                ((c as u32 * alpha as u32 + back * (255 - alpha as u32)) / 255) as u8;
+        }
+    }
+
+    fn clear(&mut self, x: u32, y: u32, width: u32, height: u32) {
+        for y in y..(y + height).min(self.height) {
+            for x in x..(x + width).min(self.width) {
+                let addr = 3 * (y * self.width + x) as usize;
+                self.data[addr..addr + 3].fill(0xFF);
+            }
+        }
+    }
+
+    fn to_pixmap(&self, samples: usize) -> Pixmap {
+        let mut pixmap = Pixmap::new(self.width, self.height, samples);
+        if samples >= 3 {
+            pixmap.data.copy_from_slice(&self.data);
+        } else {
+            for (gray, rgb) in pixmap.data.iter_mut().zip(self.data.chunks_exact(3)) {
+                *gray = Color::from_rgb(rgb).gray();
+            }
+        }
+        pixmap
+    }
+}
+
+fn delay_from_centiseconds(delay: u16) -> Duration {
+    if delay == 0 {
+        DEFAULT_DELAY
+    } else {
+        Duration::from_millis(10 * delay as u64)
+    }
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn u8(&mut self) -> Result<u8, Error> {
+        let byte = *self
+            .bytes
+            .get(self.offset)
+            .ok_or_else(|| format_err!("unexpected end of file"))?;
+        self.offset += 1;
+        Ok(byte)
+    }
+
+    fn u16(&mut self) -> Result<u16, Error> {
+        Ok(self.u8()? as u16 | (self.u8()? as u16) << 8)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let bytes = self
+            .bytes
+            .get(self.offset..self.offset + len)
+            .ok_or_else(|| format_err!("unexpected end of file"))?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    // Concatenates the sub-blocks that follow, up to the empty one.
+    fn sub_blocks(&mut self) -> Result<Vec<u8>, Error> {
+        let mut data = Vec::new();
+        loop {
+            let len = self.u8()? as usize;
+            if len == 0 {
+                return Ok(data);
+            }
+            data.extend_from_slice(self.take(len)?);
+        }
+    }
+}
+
+fn decode_gif(bytes: &[u8], max_frames: usize) -> Result<Vec<(Canvas, Duration)>, Error> {
+    let mut reader = ByteReader { bytes, offset: 6 };
+    let width = reader.u16()? as u32;
+    let height = reader.u16()? as u32;
+    let flags = reader.u8()?;
+    reader.u8()?; // The background color is replaced by the white of the canvas.
+    reader.u8()?;
+    let global_palette = if flags & 0x80 != 0 {
+        Some(reader.take(3 << ((flags & 0x07) + 1))?)
+    } else {
+        None
+    };
+
+    let mut canvas = Canvas::new(width, height);
+    let mut frames = Vec::new();
+    // The graphic control of the next image: disposal method, delay and transparent index.
+    let mut control = (0, 0, None);
+
+    while frames.len() < max_frames {
+        match reader.u8() {
+            // The file might be truncated: keep the frames already decoded.
+            Err(..) | Ok(0x3B) => break,
+            Ok(0x21) => {
+                let label = reader.u8()?;
+                let data = reader.sub_blocks()?;
+                if label == 0xF9 && data.len() >= 4 {
+                    let transparent = (data[0] & 0x01 != 0).then_some(data[3]);
+                    control = (
+                        (data[0] >> 2) & 0x07,
+                        data[1] as u16 | (data[2] as u16) << 8,
+                        transparent,
+                    );
+                }
+            }
+            Ok(0x2C) => {
+                let left = reader.u16()? as u32;
+                let top = reader.u16()? as u32;
+                let w = reader.u16()? as u32;
+                let h = reader.u16()? as u32;
+                let flags = reader.u8()?;
+                let palette = if flags & 0x80 != 0 {
+                    Some(reader.take(3 << ((flags & 0x07) + 1))?)
+                } else {
+                    global_palette
+                };
+                let min_code_size = reader.u8()?;
+                let data = reader.sub_blocks()?;
+                let palette = palette.ok_or_else(|| format_err!("missing palette"))?;
+                let indices = decode_lzw(min_code_size, &data, (w * h) as usize)?;
+                let rows = if flags & 0x40 != 0 {
+                    interlaced_rows(h)
+                } else {
+                    (0..h).collect()
+                };
+
+                let (disposal, delay, transparent) = control;
+                let previous = (disposal == 3).then(|| canvas.clone());
+                for (i, &y) in rows.iter().enumerate() {
+                    for x in 0..w {
+                        let index = indices[i * w as usize + x as usize];
+                        if Some(index) == transparent {
+                            continue;
+                        }
+                        let addr = 3 * index as usize;
+                        if let Some(rgb) = palette.get(addr..addr + 3) {
+                            canvas.blend(left + x, top + y, [rgb[0], rgb[1], rgb[2]], 0xFF);
+                        }
+                    }
+                }
+                frames.push((canvas.clone(), delay_from_centiseconds(delay)));
+
+                match (disposal, previous) {
+                    (2, _) => canvas.clear(left, top, w, h),
+                    (3, Some(previous)) => canvas = previous,
+                    _ => (),
+                }
+                control = (0, 0, None);
+            }
+            Ok(byte) => return Err(format_err!("unexpected block {:#04x}", byte)),
+        }
+    }
+
+    Ok(frames)
+}
+
+// Returns the rows of an interlaced image, in the order of their storage.
+fn interlaced_rows(height: u32) -> Vec<u32> {
+    [(0, 8), (4, 8), (2, 4), (1, 2)]
+        .iter()
+        .flat_map(|&(start, step)| (start..height).step_by(step))
+        .collect()
+}
+
+// Decompresses the color indices of an image, padding them to `len` if the data is short.
+fn decode_lzw(min_code_size: u8, data: &[u8], len: usize) -> Result<Vec<u8>, Error> {
+    if !(1..=11).contains(&min_code_size) {
+        return Err(format_err!("invalid code size {}", min_code_size));
+    }
+    let clear = 1usize << min_code_size;
+    let end = clear + 1;
+    // Each entry is the previous entry followed by a color index.
+    let mut prefixes = vec![0u16; MAX_LZW_CODES];
+    let mut suffixes = vec![0u8; MAX_LZW_CODES];
+    let mut firsts = vec![0u8; MAX_LZW_CODES];
+    for i in 0..clear {
+        suffixes[i] = i as u8;
+        firsts[i] = i as u8;
+    }
+
+    let mut output = Vec::with_capacity(len);
+    let mut stack = Vec::new();
+    let mut code_size = min_code_size as u32 + 1;
+    let mut next = end + 1;
+    let mut previous: Option<usize> = None;
+    let (mut bits, mut count) = (0u32, 0u32);
+    let mut bytes = data.iter();
+
+    while output.len() < len {
+        while count < code_size {
+            let Some(&byte) = bytes.next() else {
+                break;
+            };
+            bits |= (byte as u32) << count;
+            count += 8;
+        }
+        if count < code_size {
+            break;
+        }
+        let code = (bits & ((1 << code_size) - 1)) as usize;
+        bits >>= code_size;
+        count -= code_size;
+
+        if code == clear {
+            code_size = min_code_size as u32 + 1;
+            next = end + 1;
+            previous = None;
+            continue;
+        }
+        if code == end {
+            break;
+        }
+
+        let Some(prev) = previous else {
+            if code >= clear {
+                return Err(format_err!("invalid first code {}", code));
+            }
+            output.push(code as u8);
+            previous = Some(code);
+            continue;
+        };
+
+        let first = if code < next {
+            firsts[code]
+        } else if code == next {
+            firsts[prev]
+        } else {
+            return Err(format_err!("invalid code {}", code));
+        };
+        if next < MAX_LZW_CODES {
+            prefixes[next] = prev as u16;
+            suffixes[next] = first;
+            firsts[next] = firsts[prev];
+            next += 1;
+            if next == 1 << code_size && code_size < 12 {
+                code_size += 1;
+            }
+        }
+
+        let mut c = code;
+        stack.clear();
+        while c >= clear {
+            stack.push(suffixes[c]);
+            c = prefixes[c] as usize;
+        }
+        stack.push(c as u8);
+        output.extend(stack.iter().rev());
+        previous = Some(code);
+    }
+
+    output.resize(len, 0);
+    Ok(output)
+}
+
+fn decode_png(bytes: &[u8], max_frames: usize) -> Result<Vec<(Canvas, Duration)>, Error> {
+    let mut decoder = png::Decoder::new(Cursor::new(bytes));
+    decoder.set_transformations(Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info()?;
+    let (width, height) = reader.info().size();
+    let animated = reader.info().animation_control().is_some();
+    let mut buf = vec![
+        0;
+        reader
+            .output_buffer_size()
+            .ok_or_else(|| format_err!("image too large"))?
+    ];
+
+    let mut canvas = Canvas::new(width, height);
+    let mut frames = Vec::new();
+
+    while frames.len() < max_frames {
+        let Ok(info) = reader.next_frame(&mut buf) else {
+            break;
+        };
+        let control = reader.info().frame_control().copied();
+        // The default image isn't part of the animation when it has no frame control.
+        if animated && control.is_none() {
+            continue;
+        }
+        let control = control.unwrap_or(png::FrameControl {
+            width,
+            height,
+            ..Default::default()
+        });
+
+        let samples = match info.color_type {
+            ColorType::Grayscale => 1,
+            ColorType::GrayscaleAlpha => 2,
+            ColorType::Rgb => 3,
+            ColorType::Rgba => 4,
+            ColorType::Indexed => return Err(format_err!("unexpected indexed colors")),
+        };
+        let previous = (control.dispose_op == DisposeOp::Previous).then(|| canvas.clone());
+        if control.blend_op == BlendOp::Source {
+            canvas.clear(
+                control.x_offset,
+                control.y_offset,
+                control.width,
+                control.height,
+            );
+        }
+        for y in 0..info.height {
+            let row = &buf[y as usize * info.line_size..];
+            for x in 0..info.width {
+                let px = &row[samples * x as usize..samples * (x as usize + 1)];
+                let (rgb, alpha) = match samples {
+                    1 => ([px[0]; 3], 0xFF),
+                    2 => ([px[0]; 3], px[1]),
+                    3 => ([px[0], px[1], px[2]], 0xFF),
+                    _ => ([px[0], px[1], px[2]], px[3]),
+                };
+                canvas.blend(control.x_offset + x, control.y_offset + y, rgb, alpha);
+            }
+        }
+
+        let delay = if control.delay_num == 0 {
+            DEFAULT_DELAY
+        } else {
+            let den = if control.delay_den == 0 {
+                100
+            } else {
+                control.delay_den
+            };
+            Duration::from_millis(1000 * control.delay_num as u64 / den as u64)
+        };
+        frames.push((canvas.clone(), delay));
+
+        match (control.dispose_op, previous) {
+            (DisposeOp::Background, _) => canvas.clear(
+                control.x_offset,
+                control.y_offset,
+                control.width,
+                control.height,
+            ),
+            (DisposeOp::Previous, Some(previous)) => canvas = previous,
+            _ => (),
+        }
+    }
+
+    Ok(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::{BLACK, WHITE};
+
+    // Encodes the indices without compression: each one is preceded by a clear code, so that
+    // the codes keep their initial size.
+    fn encode_lzw(indices: &[u8]) -> Vec<u8> {
+        let (mut bits, mut count, mut data) = (0u32, 0, Vec::new());
+        let mut push = |code: u32, data: &mut Vec<u8>| {
+            bits |= code << count;
+            count += 3;
+            while count >= 8 {
+                data.push(bits as u8);
+                bits >>= 8;
+                count -= 8;
+            }
+        };
+        for &index in indices {
+            push(4, &mut data);
+            push(index as u32, &mut data);
+        }
+        push(5, &mut data);
+        if count > 0 {
+            data.push(bits as u8);
+        }
+        data
+    }
+
+    // A 2×2 GIF with a black, white, gray and red palette, whose second frame has a
+    // transparent pixel.
+    fn gif() -> Vec<u8> {
+        let mut bytes = b"GIF89a\x02\x00\x02\x00\x81\x00\x00".to_vec();
+        bytes.extend_from_slice(&[0, 0, 0, 255, 255, 255, 128, 128, 128, 255, 0, 0]);
+        for (delay, indices) in [(50u8, [0, 1, 1, 0]), (0, [2, 2, 3, 2])] {
+            bytes.extend_from_slice(&[0x21, 0xF9, 4, 0x01, delay, 0, 3, 0]);
+            bytes.extend_from_slice(&[0x2C, 0, 0, 0, 0, 2, 0, 2, 0, 0, 2]);
+            let data = encode_lzw(&indices);
+            bytes.push(data.len() as u8);
+            bytes.extend_from_slice(&data);
+            bytes.push(0);
+        }
+        bytes.push(0x3B);
+        bytes
+    }
+
+    #[test]
+    fn test_gif_frames_are_composed() {
+        let animation = Animation::decode(&gif(), 10, 1).unwrap();
+        assert!(animation.is_animated());
+
+        let first = &animation.frames[0];
+        assert_eq!(first.delay, Duration::from_millis(500));
+        assert_eq!(first.pixmap.get_pixel(0, 0), BLACK);
+        assert_eq!(first.pixmap.get_pixel(1, 0), WHITE);
+
+        // The transparent pixel shows the previous frame.
+        let second = &animation.frames[1];
+        assert_eq!(second.delay, DEFAULT_DELAY);
+        assert_eq!(second.pixmap.get_pixel(0, 0), Color::Gray(128));
+        assert_eq!(second.pixmap.get_pixel(0, 1), WHITE);
+
+        let first_only = Animation::decode(&gif(), 1, 3).unwrap();
+        assert!(!first_only.is_animated());
+        assert_eq!(first_only.frames[0].pixmap.samples, 3);
+    }
+
+    #[test]
+    fn test_apng_frames_are_composed() {
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut bytes, 2, 1);
+            encoder.set_color(ColorType::Grayscale);
+            encoder.set_animated(2, 0).unwrap();
+            encoder.set_frame_delay(1, 4).unwrap();
+            let mut writer = encoder.write_header().unwrap();
+            writer.write_image_data(&[0x00, 0xFF]).unwrap();
+            writer.write_image_data(&[0xFF, 0x00]).unwrap();
+        }
+
+        let animation = Animation::decode(&bytes, 5, 1).unwrap();
+        assert_eq!(animation.frames.len(), 2);
+        assert_eq!(animation.frames[0].delay, Duration::from_millis(250));
+        assert_eq!(animation.frames[0].pixmap.get_pixel(0, 0), BLACK);
+        assert_eq!(animation.frames[1].pixmap.get_pixel(0, 0), WHITE);
+        assert_eq!(animation.frames[1].pixmap.get_pixel(1, 0), BLACK);
+    }
+}
@@ -0,0 +1,386 @@
+//! The archives of the application data, and their restoration.
+//!
+//! An archive is a ZIP file named after its creation time, written to the `Backups` directory
+//! of the SD card when one is inserted, or of the onboard storage otherwise. It holds the files
+//! removed by a [reset](crate::reset): the settings, the queues and histories kept next to the
+//! program, and the database of each library, annotations and thumbnail previews included. The
+//! old copies of the settings file come along.
+//!
+//! The libraries are stored by index, and `manifest.json` records their paths, so that a
+//! restoration puts their databases back where they were, whatever the current settings are.
+
+use crate::reset::{self, LIBRARIES_DIRNAME, LIBRARY_FILENAMES, ROOT_FILENAMES};
+use crate::settings::{Settings, EXTERNAL_CARD_ROOT, INTERNAL_CARD_ROOT, SETTINGS_PATH};
+use crate::storage::{self, old_settings};
+use anyhow::{format_err, Context, Error};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+pub const BACKUPS_DIRNAME: &str = "Backups";
+const ARCHIVE_PREFIX: &str = "cadmus-";
+const ARCHIVE_EXTENSION: &str = "zip";
+const MANIFEST_NAME: &str = "manifest.json";
+// Hidden, so that an interrupted restoration isn't taken for an old copy of the settings.
+const STAGING_PREFIX: &str = ".restoring-";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    created: String,
+    /// The paths of the libraries, by index.
+    libraries: Vec<PathBuf>,
+}
+
+/// Returns the directory where the new archives are written.
+pub fn backups_dir() -> PathBuf {
+    let root = if storage::is_mounted(Path::new(EXTERNAL_CARD_ROOT)) {
+        EXTERNAL_CARD_ROOT
+    } else {
+        INTERNAL_CARD_ROOT
+    };
+    Path::new(root).join(BACKUPS_DIRNAME)
+}
+
+/// Lists the archives of the SD card and of the onboard storage, newest first.
+pub fn archives() -> Vec<PathBuf> {
+    let mut archives = [EXTERNAL_CARD_ROOT, INTERNAL_CARD_ROOT]
+        .iter()
+        .flat_map(|root| archives_in(&Path::new(root).join(BACKUPS_DIRNAME)))
+        .collect::<Vec<PathBuf>>();
+    archives.sort_by_key(|path| std::cmp::Reverse(path.file_name().map(|name| name.to_owned())));
+    archives
+}
+
+fn archives_in(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.extension().is_some_and(|ext| ext == ARCHIVE_EXTENSION)
+                && path
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with(ARCHIVE_PREFIX))
+        })
+        .collect()
+}
+
+/// Writes a new archive to `dir` and returns its path.
+///
+/// The settings are taken from memory, since the settings file is only written on exit. The
+/// other files kept next to the program are relative to `root`.
+pub fn create(settings: &Settings, root: &Path, dir: &Path) -> Result<PathBuf, Error> {
+    fs::create_dir_all(dir).with_context(|| format!("can't create {}", dir.display()))?;
+    let now = Local::now();
+    let path = dir.join(format!(
+        "{}{}.{}",
+        ARCHIVE_PREFIX,
+        now.format("%Y%m%d-%H%M%S"),
+        ARCHIVE_EXTENSION
+    ));
+    let file = File::create(&path).with_context(|| format!("can't create {}", path.display()))?;
+    let mut writer = ZipWriter::new(file);
+    let options = SimpleFileOptions::default();
+
+    let manifest = Manifest {
+        created: now.to_rfc3339(),
+        libraries: settings
+            .libraries
+            .iter()
+            .map(|library| library.path.clone())
+            .collect(),
+    };
+    writer.start_file(MANIFEST_NAME, options)?;
+    serde_json::to_writer_pretty(&mut writer, &manifest)?;
+
+    writer.start_file(SETTINGS_PATH, options)?;
+    writer.write_all(toml::to_string(settings)?.as_bytes())?;
+
+    let mut entries = reset::entries(settings, root);
+    entries.retain(|entry| entry.name != Path::new(SETTINGS_PATH));
+    entries.extend(old_settings(root).into_iter().map(|item| {
+        let name = PathBuf::from(item.path.file_name().unwrap_or_default());
+        reset::Entry {
+            path: item.path,
+            name,
+        }
+    }));
+
+    for entry in &entries {
+        for item in WalkDir::new(&entry.path) {
+            let item = item?;
+            if !item.file_type().is_file() {
+                continue;
+            }
+            let relat = item.path().strip_prefix(&entry.path)?;
+            let mut name = entry.name.clone();
+            if !relat.as_os_str().is_empty() {
+                name.push(relat);
+            }
+            writer.start_file(name.to_string_lossy(), options)?;
+            let mut source = File::open(item.path())
+                .with_context(|| format!("can't open {}", item.path().display()))?;
+            io::copy(&mut source, &mut writer)?;
+        }
+    }
+
+    writer.finish()?;
+    Ok(path)
+}
+
+/// Replaces the application data by the content of the archive.
+///
+/// The archive is extracted next to the data first, and the existing files of its entries are
+/// only replaced once it all went well, so that nothing of the current databases is left mixed
+/// with the restored ones, nor lost when the extraction fails.
+pub fn restore(archive: &Path, root: &Path) -> Result<(), Error> {
+    let file = File::open(archive).with_context(|| format!("can't open {}", archive.display()))?;
+    let mut zip = ZipArchive::new(file)?;
+    let manifest: Manifest = {
+        let reader = zip
+            .by_name(MANIFEST_NAME)
+            .map_err(|_| format_err!("the archive has no manifest"))?;
+        serde_json::from_reader(reader)?
+    };
+
+    let mut targets = Vec::new();
+    for index in 0..zip.len() {
+        let item = zip.by_index(index)?;
+        if item.is_dir() || item.name() == MANIFEST_NAME {
+            continue;
+        }
+        let name = item
+            .enclosed_name()
+            .ok_or_else(|| format_err!("invalid name in the archive: {}", item.name()))?;
+        let (path, top) = target_path(&name, &manifest, root)?;
+        targets.push((index, path, top));
+    }
+
+    let mut tops = targets
+        .iter()
+        .map(|(_, _, top)| top.clone())
+        .collect::<Vec<PathBuf>>();
+    tops.sort();
+    tops.dedup();
+
+    for top in &tops {
+        remove(&staging_path(top))?;
+    }
+    if let Err(e) = extract(&mut zip, &targets) {
+        for top in &tops {
+            remove(&staging_path(top)).ok();
+        }
+        return Err(e);
+    }
+
+    for top in &tops {
+        remove(top)?;
+        fs::rename(staging_path(top), top)
+            .with_context(|| format!("can't restore {}", top.display()))?;
+    }
+
+    Ok(())
+}
+
+fn extract(zip: &mut ZipArchive<File>, targets: &[(usize, PathBuf, PathBuf)]) -> Result<(), Error> {
+    for (index, path, top) in targets {
+        let relat = path.strip_prefix(top)?;
+        let mut path = staging_path(top);
+        if !relat.as_os_str().is_empty() {
+            path.push(relat);
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("can't create {}", parent.display()))?;
+        }
+        let mut item = zip.by_index(*index)?;
+        let mut dest =
+            File::create(&path).with_context(|| format!("can't create {}", path.display()))?;
+        io::copy(&mut item, &mut dest).with_context(|| format!("can't extract {}", item.name()))?;
+    }
+    Ok(())
+}
+
+/// Returns where a top-level entry is extracted before replacing the existing one.
+fn staging_path(top: &Path) -> PathBuf {
+    let mut name = OsString::from(STAGING_PREFIX);
+    name.push(top.file_name().unwrap_or_default());
+    top.with_file_name(name)
+}
+
+fn remove(path: &Path) -> Result<(), Error> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else if path.exists() {
+        fs::remove_file(path)
+    } else {
+        Ok(())
+    }
+    .with_context(|| format!("can't remove {}", path.display()))
+}
+
+/// Returns where a file of the archive goes, and the top-level entry it belongs to.
+///
+/// Only the entries written by [`create`] are accepted: any other name is an error, so that
+/// an archive can't replace the program's files or the books.
+fn target_path(name: &Path, manifest: &Manifest, root: &Path) -> Result<(PathBuf, PathBuf), Error> {
+    let invalid = || format_err!("invalid name in the archive: {}", name.display());
+    let mut components = name.components();
+    let first = match components.next() {
+        Some(Component::Normal(first)) => first.to_str().ok_or_else(invalid)?,
+        _ => return Err(invalid()),
+    };
+
+    if first != LIBRARIES_DIRNAME {
+        if components.next().is_some() || !is_root_file(first) {
+            return Err(invalid());
+        }
+        let path = root.join(first);
+        return Ok((path.clone(), path));
+    }
+
+    let index = components
+        .next()
+        .and_then(|c| c.as_os_str().to_str())
+        .and_then(|index| index.parse::<usize>().ok())
+        .ok_or_else(|| format_err!("invalid library in the archive: {}", name.display()))?;
+    let home = manifest
+        .libraries
+        .get(index)
+        .ok_or_else(|| format_err!("unknown library in the archive: {}", index))?;
+    let rest = components.as_path();
+    let file_name = rest
+        .components()
+        .next()
+        .and_then(|c| c.as_os_str().to_str())
+        .filter(|file_name| LIBRARY_FILENAMES.contains(file_name))
+        .ok_or_else(invalid)?;
+    Ok((home.join(rest), home.join(file_name)))
+}
+
+/// Returns whether the name is one of the files kept next to the program, or an old copy of
+/// the settings.
+fn is_root_file(name: &str) -> bool {
+    let stem = Path::new(SETTINGS_PATH)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+    ROOT_FILENAMES.contains(&name) || name.starts_with(stem)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::{METADATA_FILENAME, THUMBNAIL_PREVIEWS_DIRNAME};
+    use crate::settings::LibrarySettings;
+
+    #[test]
+    fn test_restore_puts_back_the_databases() {
+        let root = tempfile::tempdir().unwrap();
+        let home = tempfile::tempdir().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let thumbnails = home.path().join(THUMBNAIL_PREVIEWS_DIRNAME);
+        fs::create_dir(&thumbnails).unwrap();
+        fs::write(thumbnails.join("a.png"), "png").unwrap();
+        fs::write(home.path().join(METADATA_FILENAME), "{\"a\": 1}").unwrap();
+        fs::write(root.path().join("Settings.toml.bak"), "old").unwrap();
+
+        let mut settings = Settings {
+            libraries: vec![LibrarySettings {
+                path: home.path().to_path_buf(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        settings.language = "fr".to_string();
+        let archive = create(&settings, root.path(), dir.path()).unwrap();
+        assert_eq!(archives_in(dir.path()), vec![archive.clone()]);
+
+        fs::write(home.path().join(METADATA_FILENAME), "{}").unwrap();
+        fs::write(thumbnails.join("b.png"), "png").unwrap();
+        restore(&archive, root.path()).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(home.path().join(METADATA_FILENAME)).unwrap(),
+            "{\"a\": 1}"
+        );
+        assert!(thumbnails.join("a.png").exists());
+        assert!(!thumbnails.join("b.png").exists());
+        assert!(root.path().join("Settings.toml.bak").exists());
+        let restored: Settings =
+            crate::helpers::load_toml(root.path().join(SETTINGS_PATH)).unwrap();
+        assert_eq!(restored.language, "fr");
+    }
+
+    #[test]
+    fn test_target_path() {
+        let manifest = Manifest {
+            created: String::new(),
+            libraries: vec![PathBuf::from("/mnt/onboard")],
+        };
+        let root = Path::new("/cadmus");
+        assert_eq!(
+            target_path(
+                Path::new("libraries/0/.reading-states/a.json"),
+                &manifest,
+                root
+            )
+            .unwrap(),
+            (
+                PathBuf::from("/mnt/onboard/.reading-states/a.json"),
+                PathBuf::from("/mnt/onboard/.reading-states")
+            )
+        );
+        assert_eq!(
+            target_path(Path::new("Settings.toml"), &manifest, root).unwrap(),
+            (
+                PathBuf::from("/cadmus/Settings.toml"),
+                PathBuf::from("/cadmus/Settings.toml")
+            )
+        );
+        assert!(target_path(Path::new("libraries/1/.metadata.json"), &manifest, root).is_err());
+        assert!(target_path(Path::new("libraries/0/book.epub"), &manifest, root).is_err());
+        assert!(target_path(Path::new("bin/cadmus"), &manifest, root).is_err());
+        assert!(target_path(Path::new("css/epub.css"), &manifest, root).is_err());
+        assert!(target_path(Path::new("Settings.toml/a"), &manifest, root).is_err());
+        assert!(target_path(Path::new("Settings-2023.toml"), &manifest, root).is_ok());
+    }
+
+    #[test]
+    fn test_failed_restore_keeps_the_data() {
+        let root = tempfile::tempdir().unwrap();
+        let home = tempfile::tempdir().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(home.path().join(METADATA_FILENAME), "{\"a\": 1}").unwrap();
+
+        let archive = dir.path().join("cadmus-20240101-000000.zip");
+        let mut writer = ZipWriter::new(File::create(&archive).unwrap());
+        let options = SimpleFileOptions::default();
+        let manifest = Manifest {
+            created: String::new(),
+            libraries: vec![home.path().to_path_buf()],
+        };
+        writer.start_file(MANIFEST_NAME, options).unwrap();
+        serde_json::to_writer(&mut writer, &manifest).unwrap();
+        // The second file can't be written below the first one.
+        for name in ["libraries/0/.metadata.json", "libraries/0/.metadata.json/a"] {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(b"{}").unwrap();
+        }
+        writer.finish().unwrap();
+
+        assert!(restore(&archive, root.path()).is_err());
+        assert_eq!(
+            fs::read_to_string(home.path().join(METADATA_FILENAME)).unwrap(),
+            "{\"a\": 1}"
+        );
+        assert_eq!(fs::read_dir(home.path()).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,187 @@
+//! The battery level over time.
+//!
+//! A sample is recorded with each battery check, along with the state of the features that
+//! weigh on the drain. The discharge rates are computed from the consecutive samples taken
+//! while unplugged, each interval being counted with the state of its first sample.
+
+use crate::helpers::{load_json, save_json};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::Path;
+use tracing::error;
+
+pub const BATTERY_HISTORY_PATH: &str = "battery-history.json";
+
+/// How long the samples are kept, in seconds.
+const MAX_AGE: i64 = 14 * 24 * 3600;
+
+/// How long a sample identical to the last one is skipped, in seconds.
+const MIN_INTERVAL: i64 = 3600;
+
+/// The shortest discharge from which the rates are trusted, in hours.
+const MIN_HOURS: f32 = 1.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BatterySample {
+    /// Seconds since the Unix epoch.
+    pub time: i64,
+    pub level: f32,
+    pub charging: bool,
+    /// The frontlight intensity, in percents.
+    pub frontlight: f32,
+    pub wifi: bool,
+}
+
+impl BatterySample {
+    fn same_state(&self, other: &BatterySample) -> bool {
+        self.level == other.level
+            && self.charging == other.charging
+            && self.frontlight == other.frontlight
+            && self.wifi == other.wifi
+    }
+}
+
+/// The time spent in a given state, and the discharge rate measured during that time.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Usage {
+    pub hours: f32,
+    /// Percents per hour, if enough time was spent.
+    pub rate: Option<f32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct BatteryHistory {
+    pub samples: VecDeque<BatterySample>,
+}
+
+impl BatteryHistory {
+    /// Loads the history, or returns an empty one if it can't be read.
+    pub fn load<P: AsRef<Path>>(path: P) -> BatteryHistory {
+        let path = path.as_ref();
+        if !path.exists() {
+            return BatteryHistory::default();
+        }
+        load_json(path)
+            .map_err(|e| error!("Can't load battery history: {:#}.", e))
+            .unwrap_or_default()
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) {
+        save_json(self, path)
+            .map_err(|e| error!("Can't save battery history: {:#}.", e))
+            .ok();
+    }
+
+    pub fn push(&mut self, sample: BatterySample) {
+        if let Some(last) = self.samples.back() {
+            if sample.time < last.time {
+                return;
+            }
+            if sample.same_state(last) && sample.time - last.time < MIN_INTERVAL {
+                return;
+            }
+        }
+        let limit = sample.time - MAX_AGE;
+        while self.samples.front().is_some_and(|s| s.time < limit) {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    // The unplugged intervals, as their first sample and their drain.
+    fn discharges(&self) -> impl Iterator<Item = (&BatterySample, f32, f32)> {
+        self.samples
+            .iter()
+            .zip(self.samples.iter().skip(1))
+            .filter(|(a, b)| !a.charging && !b.charging && b.level <= a.level)
+            .map(|(a, b)| (a, (b.time - a.time) as f32 / 3600.0, a.level - b.level))
+    }
+
+    /// Returns the time spent unplugged in the state matching `pred`, and its discharge rate.
+    pub fn usage<F>(&self, pred: F) -> Usage
+    where
+        F: Fn(&BatterySample) -> bool,
+    {
+        let (hours, drain) = self
+            .discharges()
+            .filter(|(a, ..)| pred(a))
+            .fold((0.0, 0.0), |(h, d), (_, hours, drain)| {
+                (h + hours, d + drain)
+            });
+        Usage {
+            hours,
+            rate: (hours >= MIN_HOURS).then(|| drain / hours),
+        }
+    }
+
+    /// Returns the overall discharge rate, in percents per hour.
+    pub fn discharge_rate(&self) -> Option<f32> {
+        self.usage(|_| true).rate
+    }
+
+    /// Returns the number of days the given level will last at the current discharge rate.
+    pub fn days_remaining(&self, level: f32) -> Option<f32> {
+        self.discharge_rate()
+            .filter(|&rate| rate > 0.0)
+            .map(|rate| level / rate / 24.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(hour: i64, level: f32, frontlight: f32, wifi: bool) -> BatterySample {
+        BatterySample {
+            time: hour * 3600,
+            level,
+            charging: false,
+            frontlight,
+            wifi,
+        }
+    }
+
+    #[test]
+    fn test_push_skips_and_prunes() {
+        let mut history = BatteryHistory::default();
+        history.push(sample(0, 90.0, 0.0, false));
+        history.push(BatterySample {
+            time: 600,
+            ..sample(0, 90.0, 0.0, false)
+        });
+        assert_eq!(history.samples.len(), 1);
+        history.push(sample(1, 90.0, 0.0, false));
+        assert_eq!(history.samples.len(), 2);
+        history.push(sample(15 * 24, 50.0, 0.0, false));
+        assert_eq!(history.samples.len(), 1);
+    }
+
+    #[test]
+    fn test_rates_and_usage() {
+        let mut history = BatteryHistory::default();
+        history.push(sample(0, 100.0, 0.0, false));
+        history.push(sample(10, 95.0, 30.0, false));
+        history.push(sample(12, 89.0, 0.0, true));
+        history.push(sample(13, 86.0, 0.0, false));
+        history.push(BatterySample {
+            charging: true,
+            ..sample(14, 85.0, 0.0, false)
+        });
+        history.push(sample(20, 100.0, 0.0, false));
+
+        let frontlight = history.usage(|s| s.frontlight > 0.0);
+        assert_eq!(frontlight.hours, 2.0);
+        assert_eq!(frontlight.rate, Some(3.0));
+
+        let wifi = history.usage(|s| s.wifi);
+        assert_eq!(wifi.hours, 1.0);
+        assert_eq!(wifi.rate, Some(3.0));
+
+        // The charge is ignored.
+        assert_eq!(history.discharge_rate(), Some(14.0 / 13.0));
+        let days = history.days_remaining(14.0 / 13.0 * 24.0 * 3.0).unwrap();
+        assert!((days - 3.0).abs() < 1e-4);
+    }
+}
@@ -1,9 +1,11 @@
 mod fake;
+mod history;
 mod kobo;
 
 use anyhow::Error;
 
 pub use self::fake::FakeBattery;
+pub use self::history::{BatteryHistory, BatterySample, Usage, BATTERY_HISTORY_PATH};
 pub use self::kobo::KoboBattery;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
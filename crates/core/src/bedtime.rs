@@ -0,0 +1,208 @@
+//! Winding down towards the bedtime.
+//!
+//! The bedtime considered is the occurrence of the configured time nearest to the current
+//! time. During the wind-down, the frontlight moves from the levels it had when the wind-down
+//! began to the levels of the settings, unless they're changed by hand. The bedtime stays due
+//! for a while, so that the device still goes to sleep if the bedtime passed while it was
+//! asleep, and each snooze extends that while.
+
+use crate::frontlight::LightLevels;
+use crate::settings::BedtimeSettings;
+use chrono::{Duration, NaiveDateTime, NaiveTime};
+
+/// How long the bedtime stays due, in minutes.
+const DUE_WINDOW: i64 = 60;
+
+/// The difference of levels below which the frontlight is deemed untouched.
+const LEVELS_TOLERANCE: f32 = 1.0;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Phase {
+    /// The frontlight is being dimmed, the progress going from zero to one.
+    WindDown(f32),
+    Due,
+}
+
+#[derive(Debug, Default)]
+pub struct Bedtime {
+    // The last bedtime that was carried out.
+    done: Option<NaiveDateTime>,
+    snoozed_until: Option<NaiveDateTime>,
+    // The levels the wind-down began with, and the last levels it set.
+    start: Option<LightLevels>,
+    last: Option<LightLevels>,
+    // Whether the frontlight was changed by hand during the wind-down.
+    overridden: bool,
+}
+
+/// Parses a time given as `HH:MM`.
+pub fn parse_time(text: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(text.trim(), "%H:%M").ok()
+}
+
+/// Returns the occurrence of the given time nearest to `now`.
+fn nearest(now: NaiveDateTime, time: NaiveTime) -> NaiveDateTime {
+    let today = now.date().and_time(time);
+    [today - Duration::days(1), today, today + Duration::days(1)]
+        .into_iter()
+        .min_by_key(|at| (*at - now).num_seconds().abs())
+        .unwrap_or(today)
+}
+
+fn close(a: LightLevels, b: LightLevels) -> bool {
+    (a.intensity - b.intensity).abs() < LEVELS_TOLERANCE
+        && (a.warmth - b.warmth).abs() < LEVELS_TOLERANCE
+}
+
+impl Bedtime {
+    pub fn new() -> Bedtime {
+        Bedtime::default()
+    }
+
+    fn bedtime(&self, now: NaiveDateTime, settings: &BedtimeSettings) -> Option<NaiveDateTime> {
+        if !settings.enabled {
+            return None;
+        }
+        parse_time(&settings.time).map(|time| nearest(now, time))
+    }
+
+    /// Returns the phase of the bedtime at the given time, if one is under way.
+    pub fn phase(&self, now: NaiveDateTime, settings: &BedtimeSettings) -> Option<Phase> {
+        let at = self.bedtime(now, settings)?;
+        if self.done == Some(at) {
+            return None;
+        }
+        if now >= at {
+            if self.snoozed_until.is_some_and(|until| now < until) {
+                return None;
+            }
+            let end = self.snoozed_until.filter(|until| *until > at).unwrap_or(at)
+                + Duration::minutes(DUE_WINDOW);
+            return (now < end).then_some(Phase::Due);
+        }
+        let wind_down = Duration::minutes(settings.wind_down as i64);
+        if settings.wind_down > 0 && now >= at - wind_down {
+            let left = (at - now).num_seconds() as f32 / wind_down.num_seconds() as f32;
+            return Some(Phase::WindDown(1.0 - left));
+        }
+        None
+    }
+
+    /// Returns the frontlight levels for the given progress of the wind-down, or `None` when
+    /// they shouldn't change.
+    pub fn dim(
+        &mut self,
+        current: LightLevels,
+        progress: f32,
+        settings: &BedtimeSettings,
+    ) -> Option<LightLevels> {
+        if self.overridden {
+            return None;
+        }
+        if self.last.is_some_and(|last| !close(last, current)) {
+            self.overridden = true;
+            return None;
+        }
+        let start = *self.start.get_or_insert(current);
+        let levels = start.interpolate(settings.levels, progress.clamp(0.0, 1.0));
+        self.last = Some(levels);
+        (!close(levels, current)).then_some(levels)
+    }
+
+    /// Whether the frontlight is being dimmed.
+    pub fn is_dimming(&self) -> bool {
+        self.start.is_some() && !self.overridden
+    }
+
+    pub fn snooze(&mut self, now: NaiveDateTime, settings: &BedtimeSettings) {
+        self.snoozed_until = Some(now + Duration::minutes(settings.snooze as i64));
+    }
+
+    /// Marks the current bedtime as carried out.
+    pub fn finish(&mut self, now: NaiveDateTime, settings: &BedtimeSettings) {
+        self.done = self.bedtime(now, settings);
+        self.snoozed_until = None;
+    }
+
+    /// Returns the levels the frontlight had before the wind-down, and ends it.
+    pub fn take_levels(&mut self) -> Option<LightLevels> {
+        self.last = None;
+        self.overridden = false;
+        self.start.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 3, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    fn settings() -> BedtimeSettings {
+        BedtimeSettings {
+            enabled: true,
+            time: "00:15".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_phases_around_midnight() {
+        let settings = settings();
+        let mut bedtime = Bedtime::new();
+        assert_eq!(bedtime.phase(at(1, 23, 30), &settings), None);
+        assert_eq!(
+            bedtime.phase(at(2, 0, 0), &settings),
+            Some(Phase::WindDown(0.5))
+        );
+        assert_eq!(bedtime.phase(at(2, 0, 20), &settings), Some(Phase::Due));
+
+        // A snooze postpones the bedtime, and keeps it due for longer.
+        bedtime.snooze(at(2, 0, 20), &settings);
+        assert_eq!(bedtime.phase(at(2, 0, 30), &settings), None);
+        assert_eq!(bedtime.phase(at(2, 1, 30), &settings), Some(Phase::Due));
+        assert_eq!(bedtime.phase(at(2, 1, 40), &settings), None);
+
+        bedtime.finish(at(2, 0, 40), &settings);
+        assert_eq!(bedtime.phase(at(2, 0, 45), &settings), None);
+        assert_eq!(bedtime.phase(at(2, 9, 0), &settings), None);
+        assert_eq!(
+            bedtime.phase(at(3, 0, 0), &settings),
+            Some(Phase::WindDown(0.5))
+        );
+    }
+
+    #[test]
+    fn test_dimming_stops_when_overridden() {
+        let settings = settings();
+        let mut bedtime = Bedtime::new();
+        let start = LightLevels {
+            intensity: 45.0,
+            warmth: 0.0,
+        };
+        let levels = bedtime.dim(start, 0.5, &settings).unwrap();
+        assert_eq!(levels.intensity, 25.0);
+        assert_eq!(levels.warmth, 40.0);
+        assert!(bedtime.is_dimming());
+
+        let raised = LightLevels {
+            intensity: 60.0,
+            ..levels
+        };
+        assert!(bedtime.dim(raised, 0.6, &settings).is_none());
+        assert!(!bedtime.is_dimming());
+
+        assert_eq!(
+            bedtime.take_levels().map(|levels| levels.intensity),
+            Some(45.0)
+        );
+        assert!(bedtime.dim(raised, 0.0, &settings).is_none());
+        assert!(bedtime.is_dimming());
+    }
+}
@@ -0,0 +1,275 @@
+//! The Bluetooth page-turn remotes.
+//!
+//! The pairing goes through the *BlueZ* scripts, and the paired remotes show up as regular
+//! input devices, once connected. They're recognized by their bus type, and their keys are
+//! forwarded as page turns, whatever keys the remote happens to send: most of them pretend to
+//! be a keyboard, a media controller or a presenter.
+
+use crate::geom::LinearDir;
+use crate::input::{InputEvent, EV_KEY, KEY_REMOTE_BACKWARD, KEY_REMOTE_FORWARD};
+use anyhow::{format_err, Context, Error};
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io::Read;
+use std::mem::{self, MaybeUninit};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::slice;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, Thread};
+use std::time::Duration;
+use tracing::{error, info};
+
+/// The bus type of the input devices connected over Bluetooth.
+pub const BUS_BLUETOOTH: u16 = 0x05;
+
+const SYS_DIR: &str = "/sys";
+const INPUT_DIR: &str = "/dev/input";
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+const BACKWARD_KEYS: [u16; 5] = [
+    103, // KEY_UP
+    104, // KEY_PAGEUP
+    105, // KEY_LEFT
+    115, // KEY_VOLUMEUP
+    165, // KEY_PREVIOUSSONG
+];
+
+const FORWARD_KEYS: [u16; 7] = [
+    108, // KEY_DOWN
+    109, // KEY_PAGEDOWN
+    106, // KEY_RIGHT
+    114, // KEY_VOLUMEDOWN
+    163, // KEY_NEXTSONG
+    28,  // KEY_ENTER
+    57,  // KEY_SPACE
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BluetoothDevice {
+    pub address: String,
+    pub name: String,
+    pub paired: bool,
+}
+
+/// Returns the page-turn direction of a key sent by a remote.
+pub fn remote_direction(code: u16) -> Option<LinearDir> {
+    if BACKWARD_KEYS.contains(&code) {
+        Some(LinearDir::Backward)
+    } else if FORWARD_KEYS.contains(&code) {
+        Some(LinearDir::Forward)
+    } else {
+        None
+    }
+}
+
+/// Parses the `Device <address> <name>` lines printed by *bluetoothctl*.
+pub fn parse_devices(output: &str, paired: bool) -> Vec<BluetoothDevice> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let rest = line.trim().strip_prefix("Device ")?;
+            let (address, name) = rest.split_once(' ').unwrap_or((rest, ""));
+            let name = name.trim();
+            Some(BluetoothDevice {
+                address: address.to_string(),
+                name: if name.is_empty() {
+                    address.to_string()
+                } else {
+                    name.to_string()
+                },
+                paired,
+            })
+        })
+        .collect()
+}
+
+fn run(script: &str, args: &[&str]) -> Result<String, Error> {
+    let output = Command::new(script)
+        .args(args)
+        .output()
+        .with_context(|| format!("can't run {}", script))?;
+    if !output.status.success() {
+        return Err(format_err!("{} failed: {}", script, output.status));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Returns the known devices, the paired ones first.
+pub fn list_devices() -> Result<Vec<BluetoothDevice>, Error> {
+    let mut devices = parse_devices(&run("scripts/bluetooth-devices.sh", &["paired"])?, true);
+    let others = parse_devices(&run("scripts/bluetooth-devices.sh", &[])?, false);
+    for device in others {
+        if devices.iter().all(|d| d.address != device.address) {
+            devices.push(device);
+        }
+    }
+    Ok(devices)
+}
+
+/// Discovers the devices nearby, for a few seconds, and returns the known devices.
+pub fn scan() -> Result<Vec<BluetoothDevice>, Error> {
+    run("scripts/bluetooth-scan.sh", &[])?;
+    list_devices()
+}
+
+pub fn pair(address: &str) -> Result<(), Error> {
+    run("scripts/bluetooth-pair.sh", &[address]).map(|_| ())
+}
+
+pub fn forget(address: &str) -> Result<(), Error> {
+    run("scripts/bluetooth-forget.sh", &[address]).map(|_| ())
+}
+
+/// Returns the event files of the input devices connected over Bluetooth.
+fn remote_inputs(sys_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(sys_dir.join("class/input")) else {
+        return Vec::new();
+    };
+    let mut inputs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("event"))
+        .filter(|entry| {
+            fs::read_to_string(entry.path().join("device/id/bustype"))
+                .ok()
+                .and_then(|s| u16::from_str_radix(s.trim(), 16).ok())
+                == Some(BUS_BLUETOOTH)
+        })
+        .map(|entry| Path::new(INPUT_DIR).join(entry.file_name()))
+        .collect();
+    inputs.sort();
+    inputs
+}
+
+/// Watches the connected remotes and forwards their key presses to the input events.
+pub struct Remotes {
+    enabled: Arc<AtomicBool>,
+    watcher: Thread,
+}
+
+impl Remotes {
+    pub fn new(tx: Sender<InputEvent>) -> Remotes {
+        let enabled = Arc::new(AtomicBool::new(false));
+        let known = Arc::new(Mutex::new(BTreeSet::new()));
+        let enabled2 = Arc::clone(&enabled);
+        let handle = thread::spawn(move || loop {
+            if !enabled2.load(Ordering::Relaxed) {
+                thread::park();
+                continue;
+            }
+            for path in remote_inputs(Path::new(SYS_DIR)) {
+                if !known.lock().unwrap().insert(path.clone()) {
+                    continue;
+                }
+                info!("Bluetooth remote connected: {}", path.display());
+                let tx = tx.clone();
+                let known = Arc::clone(&known);
+                let enabled = Arc::clone(&enabled2);
+                thread::spawn(move || {
+                    if let Err(e) = read_remote(&path, &tx, &enabled) {
+                        error!("Can't read Bluetooth remote {}: {:#}", path.display(), e);
+                    }
+                    known.lock().unwrap().remove(&path);
+                });
+            }
+            thread::sleep(POLL_INTERVAL);
+        });
+        Remotes {
+            enabled,
+            watcher: handle.thread().clone(),
+        }
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+        if enabled {
+            self.watcher.unpark();
+        }
+    }
+}
+
+// Forwards the keys of a remote until it's disconnected.
+fn read_remote(path: &Path, tx: &Sender<InputEvent>, enabled: &AtomicBool) -> Result<(), Error> {
+    let mut file =
+        File::open(path).with_context(|| format!("can't open input file {}", path.display()))?;
+    loop {
+        let mut input_event = MaybeUninit::<InputEvent>::uninit();
+        let mut evt = unsafe {
+            let event_slice = slice::from_raw_parts_mut(
+                input_event.as_mut_ptr() as *mut u8,
+                mem::size_of::<InputEvent>(),
+            );
+            if file.read_exact(event_slice).is_err() {
+                info!("Bluetooth remote disconnected: {}", path.display());
+                return Ok(());
+            }
+            input_event.assume_init()
+        };
+        if evt.kind != EV_KEY || !enabled.load(Ordering::Relaxed) {
+            continue;
+        }
+        if let Some(dir) = remote_direction(evt.code) {
+            evt.code = match dir {
+                LinearDir::Backward => KEY_REMOTE_BACKWARD,
+                LinearDir::Forward => KEY_REMOTE_FORWARD,
+            };
+            tx.send(evt).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_devices() {
+        let output = "Device 00:11:22:33:44:55 Page Turner\n\
+                      [CHG] Controller 66:77:88:99:AA:BB Discovering: yes\n\
+                      Device 12:34:56:78:9A:BC\n";
+        assert_eq!(
+            parse_devices(output, true),
+            vec![
+                BluetoothDevice {
+                    address: "00:11:22:33:44:55".to_string(),
+                    name: "Page Turner".to_string(),
+                    paired: true,
+                },
+                BluetoothDevice {
+                    address: "12:34:56:78:9A:BC".to_string(),
+                    name: "12:34:56:78:9A:BC".to_string(),
+                    paired: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_remote_direction() {
+        assert_eq!(remote_direction(104), Some(LinearDir::Backward));
+        assert_eq!(remote_direction(115), Some(LinearDir::Backward));
+        assert_eq!(remote_direction(109), Some(LinearDir::Forward));
+        assert_eq!(remote_direction(28), Some(LinearDir::Forward));
+        assert_eq!(remote_direction(30), None);
+    }
+
+    #[test]
+    fn test_remote_inputs_are_found_by_bus_type() {
+        let sys_dir = tempfile::tempdir().unwrap();
+        for (name, bus_type) in [("event0", "0019"), ("event3", "0005"), ("mouse0", "0005")] {
+            let dir = sys_dir
+                .path()
+                .join("class/input")
+                .join(name)
+                .join("device/id");
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("bustype"), format!("{}\n", bus_type)).unwrap();
+        }
+        assert_eq!(
+            remote_inputs(sys_dir.path()),
+            vec![PathBuf::from("/dev/input/event3")]
+        );
+    }
+}
@@ -0,0 +1,670 @@
+//! Calibre's wireless device protocol, through which Calibre manages the device as if it
+//! were plugged in.
+//!
+//! The device finds Calibre by broadcasting on the ports it listens to, and connects to it.
+//! Calibre then drives the exchange: each message is a JSON array holding an opcode and its
+//! arguments, preceded by its length. The books are stored in a library, under the paths
+//! chosen by Calibre, and the metadata Calibre sent for them is kept next to them. The read
+//! status of the books is sent back with their metadata, for Calibre to update its columns.
+
+use crate::device::CURRENT_DEVICE;
+use crate::helpers::{load_json, save_json, Fingerprint};
+use crate::library::{FAT32_EPOCH_FILENAME, READING_STATES_DIRNAME};
+use crate::metadata::ReaderInfo;
+use crate::settings::{CalibreSettings, Settings};
+use crate::view::{Event, Hub, NotificationEvent};
+use anyhow::{format_err, Context as ResultExt, Error};
+use chrono::{Local, TimeZone, Utc};
+use fxhash::FxHashSet;
+use serde_json::{json, Map, Value};
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// The ports on which Calibre listens for the broadcasts of the devices.
+const BROADCAST_PORTS: [u16; 5] = [54982, 48123, 39001, 44044, 59678];
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(3);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The metadata of the books received from Calibre, relative to the library.
+const METADATA_FILENAME: &str = ".metadata.calibre";
+/// The information Calibre keeps about the device, relative to the library.
+const DRIVE_INFO_FILENAME: &str = ".driveinfo.calibre";
+
+/// The largest message accepted, the books themselves being sent apart.
+const MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024;
+
+const OK: u64 = 0;
+const SET_CALIBRE_DEVICE_INFO: u64 = 1;
+const SET_CALIBRE_DEVICE_NAME: u64 = 2;
+const GET_DEVICE_INFORMATION: u64 = 3;
+const TOTAL_SPACE: u64 = 4;
+const FREE_SPACE: u64 = 5;
+const GET_BOOK_COUNT: u64 = 6;
+const SEND_BOOKLISTS: u64 = 7;
+const SEND_BOOK: u64 = 8;
+const GET_INITIALIZATION_INFO: u64 = 9;
+const BOOK_DONE: u64 = 11;
+const NOOP: u64 = 12;
+const DELETE_BOOK: u64 = 13;
+const GET_BOOK_FILE_SEGMENT: u64 = 14;
+const GET_BOOK_METADATA: u64 = 15;
+const SEND_BOOK_METADATA: u64 = 16;
+const DISPLAY_MESSAGE: u64 = 17;
+const CALIBRE_BUSY: u64 = 18;
+const SET_LIBRARY_INFO: u64 = 19;
+const ERROR: u64 = 20;
+
+/// The kind of message Calibre displays when the password is wrong.
+const MESSAGE_PASSWORD_ERROR: u64 = 1;
+
+/// The connection, which ends when it's dropped.
+pub struct Calibre {
+    stream: Arc<Mutex<Option<TcpStream>>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Calibre {
+    /// Finds Calibre and serves it in the background.
+    ///
+    /// The hub is told about the books received and removed, and gets
+    /// [`Event::CalibreDisconnected`] once the connection is over.
+    pub fn connect(settings: &Settings, hub: &Hub) -> Result<Calibre, Error> {
+        let root = settings
+            .libraries
+            .get(settings.calibre.library)
+            .map(|library| library.path.clone())
+            .ok_or_else(|| format_err!("no library {}", settings.calibre.library))?;
+        let mut session = Session::new(
+            root,
+            settings.import.allowed_kinds.clone(),
+            settings.calibre.clone(),
+            hub.clone(),
+        );
+        let stream = Arc::new(Mutex::new(None));
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let stream2 = stream.clone();
+        let cancelled2 = cancelled.clone();
+        let address = settings.calibre.address.clone();
+        let hub = hub.clone();
+
+        thread::spawn(move || {
+            let result = locate(address.as_deref()).and_then(|address| {
+                let socket = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)
+                    .with_context(|| format!("can't connect to {}", address))?;
+                *stream2.lock().unwrap() = Some(socket.try_clone()?);
+                if cancelled2.load(Ordering::Relaxed) {
+                    return Ok(());
+                }
+                info!(%address, "connected to Calibre");
+                session.serve(socket)
+            });
+            session.save();
+            let msg = match result {
+                Ok(()) => "Disconnected from Calibre.".to_string(),
+                Err(e) if !cancelled2.load(Ordering::Relaxed) => {
+                    format!("Calibre connection failed: {:#}.", e)
+                }
+                Err(_) => "Disconnected from Calibre.".to_string(),
+            };
+            hub.send(Event::Notification(NotificationEvent::Show(msg)))
+                .ok();
+            hub.send(Event::CalibreDisconnected).ok();
+        });
+
+        Ok(Calibre { stream, cancelled })
+    }
+}
+
+impl Drop for Calibre {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        if let Some(stream) = self.stream.lock().unwrap().as_ref() {
+            stream.shutdown(Shutdown::Both).ok();
+        }
+    }
+}
+
+/// Returns the address of the given `host:port`, or of the Calibre that answers the
+/// broadcast.
+fn locate(address: Option<&str>) -> Result<SocketAddr, Error> {
+    if let Some(address) = address {
+        return address
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| format_err!("can't resolve {}", address));
+    }
+    let socket = UdpSocket::bind(("0.0.0.0", 0))?;
+    socket.set_broadcast(true)?;
+    socket.set_read_timeout(Some(DISCOVERY_TIMEOUT))?;
+    for port in BROADCAST_PORTS {
+        socket.send_to(b"hello", ("255.255.255.255", port)).ok();
+    }
+    let mut buf = [0; 512];
+    let (len, from) = socket
+        .recv_from(&mut buf)
+        .map_err(|_| format_err!("Calibre wasn't found on the network"))?;
+    let reply = String::from_utf8_lossy(&buf[..len]);
+    debug!(%reply, "Calibre answered");
+    let port = parse_reply(&reply).ok_or_else(|| format_err!("invalid answer: {}", reply))?;
+    Ok(SocketAddr::new(from.ip(), port))
+}
+
+/// Extracts the port of the wireless device connection from an answer such as
+/// `calibre wireless device client (on desktop);8080,9090`.
+fn parse_reply(reply: &str) -> Option<u16> {
+    let (_, ports) = reply.rsplit_once(';')?;
+    ports.split(',').nth(1)?.trim().parse().ok()
+}
+
+fn read_message<R: Read>(reader: &mut R) -> Result<Option<(u64, Value)>, Error> {
+    let mut length = 0usize;
+    let mut byte = [0; 1];
+    loop {
+        if reader.read(&mut byte)? == 0 {
+            if length == 0 {
+                return Ok(None);
+            }
+            return Err(format_err!("unexpected end of message"));
+        }
+        match byte[0] {
+            b'0'..=b'9' => length = length * 10 + (byte[0] - b'0') as usize,
+            b'[' => break,
+            _ => return Err(format_err!("invalid message length")),
+        }
+        if length > MAX_MESSAGE_SIZE {
+            return Err(format_err!("message too long"));
+        }
+    }
+    if length == 0 {
+        return Err(format_err!("empty message"));
+    }
+    let mut data = vec![b'['; length];
+    reader.read_exact(&mut data[1..])?;
+    let message: Value = serde_json::from_slice(&data)?;
+    let opcode = message
+        .get(0)
+        .and_then(Value::as_u64)
+        .ok_or_else(|| format_err!("missing opcode"))?;
+    let arguments = message.get(1).cloned().unwrap_or(Value::Null);
+    Ok(Some((opcode, arguments)))
+}
+
+fn write_message<W: Write>(writer: &mut W, opcode: u64, arguments: Value) -> io::Result<()> {
+    let data = serde_json::to_vec(&json!([opcode, arguments]))?;
+    write!(writer, "{}", data.len())?;
+    writer.write_all(&data)?;
+    writer.flush()
+}
+
+/// Appends the segments of a path chosen by Calibre to the root, refusing the hidden ones
+/// and those that could climb out of it.
+fn resolve(root: &Path, lpath: &str) -> Option<PathBuf> {
+    let mut path = root.to_path_buf();
+    let mut empty = true;
+    for segment in lpath.split(['/', '\\']).filter(|s| !s.is_empty()) {
+        if segment.starts_with('.') || segment.contains('\0') {
+            return None;
+        }
+        path.push(segment);
+        empty = false;
+    }
+    (!empty).then_some(path)
+}
+
+struct Session {
+    root: PathBuf,
+    kinds: FxHashSet<String>,
+    settings: CalibreSettings,
+    hub: Hub,
+    // The metadata of the books, as sent by Calibre.
+    books: Vec<Value>,
+    dirty: bool,
+}
+
+impl Session {
+    fn new(
+        root: PathBuf,
+        kinds: FxHashSet<String>,
+        settings: CalibreSettings,
+        hub: Hub,
+    ) -> Session {
+        let books = load_json::<Vec<Value>, _>(root.join(METADATA_FILENAME))
+            .map(|books| {
+                books
+                    .into_iter()
+                    .filter(|book| {
+                        lpath_of(book)
+                            .and_then(|lpath| resolve(&root, lpath))
+                            .is_some_and(|path| path.exists())
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Session {
+            root,
+            kinds,
+            settings,
+            hub,
+            books,
+            dirty: false,
+        }
+    }
+
+    fn save(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        save_json(&self.books, self.root.join(METADATA_FILENAME))
+            .map_err(|e| warn!("Can't save the Calibre metadata: {:#}.", e))
+            .ok();
+        self.dirty = false;
+    }
+
+    fn serve(&mut self, stream: TcpStream) -> Result<(), Error> {
+        let mut writer = stream.try_clone()?;
+        let mut reader = BufReader::new(stream);
+        while let Some((opcode, arguments)) = read_message(&mut reader)? {
+            debug!(opcode, "Calibre message");
+            match opcode {
+                GET_INITIALIZATION_INFO => {
+                    write_message(&mut writer, OK, self.initialization_info())?;
+                }
+                GET_DEVICE_INFORMATION => {
+                    write_message(&mut writer, OK, self.device_information())?;
+                }
+                SET_CALIBRE_DEVICE_INFO => {
+                    save_json(&arguments, self.root.join(DRIVE_INFO_FILENAME))
+                        .map_err(|e| warn!("Can't save the Calibre drive info: {:#}.", e))
+                        .ok();
+                    write_message(&mut writer, OK, json!({}))?;
+                }
+                SET_CALIBRE_DEVICE_NAME | SET_LIBRARY_INFO => {
+                    write_message(&mut writer, OK, json!({}))?;
+                }
+                TOTAL_SPACE | FREE_SPACE => {
+                    let (total, free) = space(&self.root);
+                    let reply = if opcode == TOTAL_SPACE {
+                        json!({ "total_space_on_device": total })
+                    } else {
+                        json!({ "free_space_on_device": free })
+                    };
+                    write_message(&mut writer, OK, reply)?;
+                }
+                GET_BOOK_COUNT => {
+                    write_message(
+                        &mut writer,
+                        OK,
+                        json!({ "count": self.books.len(), "willStream": true, "willScan": true }),
+                    )?;
+                    for index in 0..self.books.len() {
+                        write_message(&mut writer, OK, self.summary(index))?;
+                    }
+                }
+                GET_BOOK_METADATA => {
+                    let index = arguments["priKey"].as_u64().unwrap_or(u64::MAX) as usize;
+                    write_message(&mut writer, OK, self.metadata(index))?;
+                }
+                NOOP => {
+                    if arguments.get("ejecting").is_some() {
+                        write_message(&mut writer, OK, json!({}))?;
+                        return Ok(());
+                    } else if let Some(index) = arguments.get("priKey").and_then(Value::as_u64) {
+                        write_message(&mut writer, OK, self.metadata(index as usize))?;
+                    } else if arguments.get("count").is_none() {
+                        write_message(&mut writer, OK, json!({}))?;
+                    }
+                }
+                SEND_BOOKLISTS | BOOK_DONE | CALIBRE_BUSY => (),
+                SEND_BOOK_METADATA => {
+                    if let Some(book) = arguments.get("data") {
+                        self.update(book.clone());
+                    }
+                }
+                SEND_BOOK => {
+                    self.receive(&arguments, &mut reader, &mut writer)?;
+                }
+                DELETE_BOOK => {
+                    write_message(&mut writer, OK, json!({}))?;
+                    let lpaths = arguments["lpaths"].as_array().cloned().unwrap_or_default();
+                    for lpath in lpaths.iter().filter_map(Value::as_str) {
+                        let uuid = self.delete(lpath);
+                        write_message(&mut writer, OK, json!({ "uuid": uuid }))?;
+                    }
+                }
+                GET_BOOK_FILE_SEGMENT => {
+                    let path = arguments["lpath"]
+                        .as_str()
+                        .and_then(|lpath| resolve(&self.root, lpath));
+                    match path.and_then(|path| File::open(path).ok()) {
+                        Some(mut file) => {
+                            let length = file.metadata()?.len();
+                            write_message(&mut writer, OK, json!({ "fileLength": length }))?;
+                            io::copy(&mut file, &mut writer)?;
+                            writer.flush()?;
+                        }
+                        None => {
+                            write_message(
+                                &mut writer,
+                                ERROR,
+                                json!({ "message": "The book doesn't exist." }),
+                            )?;
+                        }
+                    }
+                }
+                DISPLAY_MESSAGE => {
+                    if arguments["messageKind"].as_u64() == Some(MESSAGE_PASSWORD_ERROR) {
+                        return Err(format_err!("Calibre requires a password"));
+                    }
+                }
+                _ => warn!(opcode, "unknown Calibre message"),
+            }
+        }
+        Ok(())
+    }
+
+    fn initialization_info(&self) -> Value {
+        let mut kinds = self.kinds.iter().cloned().collect::<Vec<_>>();
+        kinds.sort();
+        let path_lengths = kinds
+            .iter()
+            .map(|kind| (kind.clone(), json!(255)))
+            .collect::<Map<_, _>>();
+        let mut info = json!({
+            "appName": "Cadmus",
+            "acceptedExtensions": kinds,
+            "cacheUsesLpaths": true,
+            "canAcceptLibraryInfo": true,
+            "canDeleteMultipleBooks": true,
+            "canReceiveBookBinary": true,
+            "canSendOkToSendbook": true,
+            "canStreamBooks": true,
+            "canStreamMetadata": true,
+            "canUseCachedMetadata": true,
+            "ccVersionNumber": 1,
+            "coverHeight": 240,
+            "deviceKind": CURRENT_DEVICE.model.to_string(),
+            "deviceName": "Cadmus",
+            "extensionPathLengths": path_lengths,
+            "maxBookContentPacketLen": 4096,
+            "passwordHash": "",
+            "useUuidFileNames": false,
+            "versionOK": true,
+        });
+        if let Some(column) = self.settings.read_column.as_ref() {
+            info["isReadSyncCol"] = json!(column);
+        }
+        if let Some(column) = self.settings.read_date_column.as_ref() {
+            info["isReadDateSyncCol"] = json!(column);
+        }
+        info
+    }
+
+    fn device_information(&self) -> Value {
+        let mut drive_info = load_json::<Value, _>(self.root.join(DRIVE_INFO_FILENAME))
+            .unwrap_or_else(|_| json!({}));
+        if drive_info.get("device_store_uuid").is_none() {
+            drive_info["device_store_uuid"] = json!(uuid::Uuid::now_v7().to_string());
+            save_json(&drive_info, self.root.join(DRIVE_INFO_FILENAME)).ok();
+        }
+        drive_info["device_name"] = json!("Cadmus");
+        json!({
+            "device_info": drive_info,
+            "version": env!("CARGO_PKG_VERSION"),
+            "device_version": env!("CARGO_PKG_VERSION"),
+        })
+    }
+
+    /// The fields by which Calibre tells whether its cached metadata is still current.
+    fn summary(&self, index: usize) -> Value {
+        let book = &self.books[index];
+        let mut summary = json!({ "priKey": index });
+        for key in ["uuid", "lpath", "last_modified", "extension"] {
+            if let Some(value) = book.get(key) {
+                summary[key] = value.clone();
+            }
+        }
+        self.add_read_status(&mut summary);
+        summary
+    }
+
+    fn metadata(&self, index: usize) -> Value {
+        let Some(book) = self.books.get(index) else {
+            return json!({});
+        };
+        let mut metadata = book.clone();
+        metadata["priKey"] = json!(index);
+        self.add_read_status(&mut metadata);
+        metadata
+    }
+
+    fn add_read_status(&self, book: &mut Value) {
+        if self.settings.read_column.is_none() && self.settings.read_date_column.is_none() {
+            return;
+        }
+        let Some(reader) = lpath_of(book)
+            .and_then(|lpath| resolve(&self.root, lpath))
+            .and_then(|path| self.reader_info(&path))
+        else {
+            return;
+        };
+        book["_is_read_"] = json!(reader.finished);
+        book["_sync_type_"] = json!("read");
+        if let Some(opened) = Local.from_local_datetime(&reader.opened).single() {
+            book["_last_read_date_"] = json!(opened.with_timezone(&Utc).to_rfc3339());
+        }
+    }
+
+    /// Loads the reading state of the given book, which the library stores by fingerprint.
+    fn reader_info(&self, path: &Path) -> Option<ReaderInfo> {
+        let epoch = self
+            .root
+            .join(FAT32_EPOCH_FILENAME)
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()?;
+        let fp = path.metadata().ok()?.fingerprint(epoch).ok()?;
+        load_json(
+            self.root
+                .join(READING_STATES_DIRNAME)
+                .join(format!("{}.json", fp)),
+        )
+        .ok()
+    }
+
+    /// Replaces the metadata of the book with the same path.
+    fn update(&mut self, book: Value) {
+        let Some(lpath) = lpath_of(&book).map(str::to_string) else {
+            return;
+        };
+        match self
+            .books
+            .iter_mut()
+            .find(|other| lpath_of(other) == Some(lpath.as_str()))
+        {
+            Some(other) => *other = book,
+            None => self.books.push(book),
+        }
+        self.dirty = true;
+    }
+
+    fn receive<R: Read, W: Write>(
+        &mut self,
+        arguments: &Value,
+        reader: &mut R,
+        writer: &mut W,
+    ) -> Result<(), Error> {
+        let lpath = arguments["lpath"].as_str().unwrap_or_default();
+        let length = arguments["length"].as_u64().unwrap_or(0);
+        let Some(path) = resolve(&self.root, lpath) else {
+            write_message(writer, ERROR, json!({ "message": "Invalid path." }))?;
+            // The book still has to be read before the next message.
+            io::copy(&mut reader.by_ref().take(length), &mut io::sink())?;
+            return Ok(());
+        };
+        if arguments["wantsSendOkToSendbook"].as_bool() == Some(true) {
+            write_message(writer, OK, json!({ "lpath": lpath }))?;
+        }
+
+        // The book is hidden from the import until it's complete.
+        let parent = path.parent().unwrap_or(&self.root).to_path_buf();
+        fs::create_dir_all(&parent)?;
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let partial = parent.join(format!(".{}.part", name));
+        let mut file = File::create(&partial)?;
+        let copied = io::copy(&mut reader.by_ref().take(length), &mut file)?;
+        if copied < length {
+            fs::remove_file(&partial).ok();
+            return Err(format_err!("the book was cut short"));
+        }
+        file.sync_all()?;
+        fs::rename(&partial, &path)?;
+        info!(path = %path.display(), "book received from Calibre");
+
+        let mut metadata = arguments["metadata"].clone();
+        if metadata.is_object() {
+            metadata["lpath"] = json!(lpath);
+            self.update(metadata);
+        }
+        self.save();
+        self.hub.send(Event::FileReceived(path)).ok();
+        Ok(())
+    }
+
+    /// Deletes the book at the given path, and returns its UUID.
+    fn delete(&mut self, lpath: &str) -> Value {
+        let uuid = self
+            .books
+            .iter()
+            .find(|book| lpath_of(book) == Some(lpath))
+            .and_then(|book| book.get("uuid").cloned())
+            .unwrap_or(Value::Null);
+        self.books.retain(|book| lpath_of(book) != Some(lpath));
+        self.dirty = true;
+        if let Some(path) = resolve(&self.root, lpath) {
+            if fs::remove_file(&path).is_ok() {
+                info!(path = %path.display(), "book deleted by Calibre");
+                self.hub.send(Event::FileRemoved(path)).ok();
+            }
+        }
+        uuid
+    }
+}
+
+fn lpath_of(book: &Value) -> Option<&str> {
+    book.get("lpath").and_then(Value::as_str)
+}
+
+/// Returns the total and free space of the file system holding the given path.
+// The sizes of the fields of `statvfs` depend on the target.
+#[allow(clippy::unnecessary_cast)]
+fn space(path: &Path) -> (u64, u64) {
+    use nix::sys::statvfs::statvfs;
+    statvfs(path)
+        .map(|stat| {
+            let size = stat.block_size() as u64;
+            (
+                stat.blocks() as u64 * size,
+                stat.blocks_available() as u64 * size,
+            )
+        })
+        .unwrap_or((0, 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages() {
+        let mut data = Vec::new();
+        write_message(&mut data, OK, json!({ "count": 2 })).unwrap();
+        write_message(&mut data, NOOP, json!({})).unwrap();
+        assert!(data.starts_with(b"15[0,{\"count\":2}]"));
+
+        let mut reader = &data[..];
+        let (opcode, arguments) = read_message(&mut reader).unwrap().unwrap();
+        assert_eq!(opcode, OK);
+        assert_eq!(arguments["count"], 2);
+        let (opcode, _) = read_message(&mut reader).unwrap().unwrap();
+        assert_eq!(opcode, NOOP);
+        assert!(read_message(&mut reader).unwrap().is_none());
+
+        assert!(read_message(&mut &b"12[0,{}"[..]).is_err());
+        assert!(read_message(&mut &b"x[0,{}]"[..]).is_err());
+    }
+
+    #[test]
+    fn test_discovery_reply() {
+        assert_eq!(
+            parse_reply("calibre wireless device client (on desktop);8080,9090"),
+            Some(9090)
+        );
+        assert_eq!(parse_reply("calibre wireless device client"), None);
+    }
+
+    #[test]
+    fn test_receive_and_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        let (hub, receiver) = std::sync::mpsc::channel();
+        let kinds = ["epub".to_string()].into_iter().collect();
+        let mut session = Session::new(root.clone(), kinds, CalibreSettings::default(), hub);
+
+        let arguments = json!({
+            "lpath": "Frank Herbert/Dune.epub",
+            "length": 4,
+            "wantsSendOkToSendbook": true,
+            "metadata": { "title": "Dune", "uuid": "1234", "last_modified": "2024-03-01T00:00:00+00:00" },
+        });
+        let mut output = Vec::new();
+        session
+            .receive(&arguments, &mut &b"dune"[..], &mut output)
+            .unwrap();
+        let path = root.join("Frank Herbert/Dune.epub");
+        assert_eq!(output, b"39[0,{\"lpath\":\"Frank Herbert/Dune.epub\"}]");
+        assert_eq!(fs::read(&path).unwrap(), b"dune");
+        assert!(matches!(receiver.try_recv(), Ok(Event::FileReceived(p)) if p == path));
+        assert_eq!(
+            session.summary(0),
+            json!({
+                "priKey": 0,
+                "uuid": "1234",
+                "lpath": "Frank Herbert/Dune.epub",
+                "last_modified": "2024-03-01T00:00:00+00:00",
+            })
+        );
+
+        // The metadata is kept for the next connection.
+        let (hub, receiver) = std::sync::mpsc::channel();
+        let kinds = ["epub".to_string()].into_iter().collect();
+        let mut session = Session::new(root.clone(), kinds, CalibreSettings::default(), hub);
+        assert_eq!(session.metadata(0)["title"], "Dune");
+        assert_eq!(session.delete("Frank Herbert/Dune.epub"), json!("1234"));
+        assert!(!path.exists());
+        assert!(matches!(receiver.try_recv(), Ok(Event::FileRemoved(p)) if p == path));
+        assert!(session.books.is_empty());
+    }
+
+    #[test]
+    fn test_resolve() {
+        let root = Path::new("/mnt/onboard");
+        assert_eq!(
+            resolve(root, "Frank Herbert/Dune - Frank Herbert.epub"),
+            Some(root.join("Frank Herbert/Dune - Frank Herbert.epub"))
+        );
+        assert_eq!(resolve(root, "../etc/passwd"), None);
+        assert_eq!(resolve(root, "a\\..\\..\\b.epub"), None);
+        assert_eq!(resolve(root, ".kobo/KoboRoot.tgz"), None);
+        assert_eq!(resolve(root, "/"), None);
+    }
+}
@@ -0,0 +1,568 @@
+//! The captive portals of the hotel and café networks.
+//!
+//! A portal is detected by asking for a page that answers *204 No Content*: anything else,
+//! usually a redirection to a login page, means the network is held back by a portal. The forms
+//! of the login page are extracted so that they can be filled in and sent. The redirections are
+//! followed by hand, since the cookies set along the way are often what identifies the device.
+//! Each cookie is only sent back to the host, and below the path, that set it, over either
+//! scheme unless it's marked as secure.
+
+use crate::helpers::decode_entities;
+use crate::net;
+use anyhow::{format_err, Error};
+use lazy_static::lazy_static;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use regex::Regex;
+use reqwest::blocking::{Client, RequestBuilder};
+use reqwest::header::{HeaderValue, CONTENT_TYPE, COOKIE, LOCATION, SET_COOKIE};
+use reqwest::redirect::Policy;
+use reqwest::{StatusCode, Url};
+use std::time::Duration;
+
+pub const PROBE_URL: &str = "http://connectivitycheck.gstatic.com/generate_204";
+
+const MAX_REDIRECTS: usize = 10;
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+lazy_static! {
+    static ref TITLE: Regex = Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap();
+    static ref FORM: Regex = Regex::new(r"(?is)<form\b([^>]*)>(.*?)(?:</form>|\z)").unwrap();
+    static ref CONTROL: Regex =
+        Regex::new(r"(?is)<(input|button|textarea|select)\b([^>]*)>").unwrap();
+    static ref LABEL: Regex = Regex::new(r"(?is)<label\b([^>]*)>(.*?)</label>").unwrap();
+    static ref ATTRIBUTE: Regex = Regex::new(
+        r#"(?s)([a-zA-Z_:][-a-zA-Z0-9_:.]*)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+)))?"#
+    )
+    .unwrap();
+    static ref REFRESH: Regex =
+        Regex::new(r#"(?is)<meta\b[^>]*http-equiv\s*=\s*["']?refresh[^>]*>"#).unwrap();
+    static ref TAG: Regex = Regex::new(r"(?s)<[^>]*>").unwrap();
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FieldKind {
+    Text,
+    Password,
+    Hidden,
+    Checkbox,
+    Submit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub kind: FieldKind,
+    pub value: String,
+    pub label: String,
+    pub checked: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Form {
+    pub action: String,
+    pub post: bool,
+    pub fields: Vec<Field>,
+}
+
+/// A page served by a portal, and the cookies of the session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortalPage {
+    pub url: String,
+    pub title: String,
+    pub html: String,
+    pub forms: Vec<Form>,
+    pub cookies: Vec<Cookie>,
+}
+
+/// A cookie, scoped to the host and the path of the response that set it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    pub host: String,
+    pub path: String,
+    /// Only sent over HTTPS.
+    pub secure: bool,
+}
+
+impl Cookie {
+    // The scheme and the port don't matter, as in RFC 6265.
+    fn matches(&self, url: &Url) -> bool {
+        if url.host_str() != Some(self.host.as_str()) || (self.secure && url.scheme() != "https") {
+            return false;
+        }
+        let path = url.path();
+        path == self.path
+            || (path.starts_with(&self.path)
+                && (self.path.ends_with('/') || path[self.path.len()..].starts_with('/')))
+    }
+}
+
+impl PortalPage {
+    /// Returns the index of the form to fill in: the one showing the most fields.
+    pub fn login_form(&self) -> Option<usize> {
+        self.forms
+            .iter()
+            .enumerate()
+            .max_by_key(|(index, form)| {
+                let shown = form
+                    .fields
+                    .iter()
+                    .filter(|field| field.kind != FieldKind::Hidden)
+                    .count();
+                (shown, std::cmp::Reverse(*index))
+            })
+            .map(|(index, _)| index)
+    }
+}
+
+fn attributes(text: &str) -> Vec<(String, String)> {
+    ATTRIBUTE
+        .captures_iter(text)
+        .map(|caps| {
+            let value = caps
+                .get(2)
+                .or_else(|| caps.get(3))
+                .or_else(|| caps.get(4))
+                .map_or("", |m| m.as_str());
+            (caps[1].to_lowercase(), decode_entities(value).into_owned())
+        })
+        .collect()
+}
+
+fn attribute<'a>(attrs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+// Strips the tags and collapses the white space.
+fn plain_text(html: &str) -> String {
+    let text = TAG.replace_all(html, " ");
+    decode_entities(&text)
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+fn parse_form(attrs: &str, body: &str, base: &Url) -> Form {
+    let attrs = attributes(attrs);
+    let action = attribute(&attrs, "action")
+        .filter(|action| !action.is_empty())
+        .and_then(|action| base.join(action).ok())
+        .unwrap_or_else(|| base.clone());
+    let post = attribute(&attrs, "method").is_some_and(|m| m.eq_ignore_ascii_case("post"));
+
+    let labels: Vec<(String, String)> = LABEL
+        .captures_iter(body)
+        .filter_map(|caps| {
+            let attrs = attributes(&caps[1]);
+            attribute(&attrs, "for").map(|id| (id.to_string(), plain_text(&caps[2])))
+        })
+        .collect();
+
+    let fields = CONTROL
+        .captures_iter(body)
+        .filter_map(|caps| {
+            let tag = caps[1].to_lowercase();
+            let attrs = attributes(&caps[2]);
+            let name = attribute(&attrs, "name").unwrap_or_default().to_string();
+            let kind = match tag.as_str() {
+                "button" => {
+                    if attribute(&attrs, "type").is_some_and(|t| !t.eq_ignore_ascii_case("submit"))
+                    {
+                        return None;
+                    }
+                    FieldKind::Submit
+                }
+                "input" => match attribute(&attrs, "type")
+                    .unwrap_or("text")
+                    .to_lowercase()
+                    .as_str()
+                {
+                    "password" => FieldKind::Password,
+                    "hidden" => FieldKind::Hidden,
+                    "checkbox" | "radio" => FieldKind::Checkbox,
+                    "submit" | "image" => FieldKind::Submit,
+                    "button" | "reset" | "file" => return None,
+                    _ => FieldKind::Text,
+                },
+                _ => FieldKind::Text,
+            };
+            if name.is_empty() && kind != FieldKind::Submit {
+                return None;
+            }
+            let value = attribute(&attrs, "value").unwrap_or_default().to_string();
+            let label = attribute(&attrs, "id")
+                .and_then(|id| labels.iter().find(|(key, _)| key == id))
+                .map(|(_, text)| text.clone())
+                .or_else(|| attribute(&attrs, "placeholder").map(String::from))
+                .or_else(|| (kind == FieldKind::Submit && !value.is_empty()).then(|| value.clone()))
+                .filter(|label| !label.is_empty())
+                .unwrap_or_else(|| {
+                    if name.is_empty() {
+                        "Submit".to_string()
+                    } else {
+                        name.clone()
+                    }
+                });
+            Some(Field {
+                name,
+                kind,
+                value,
+                label,
+                checked: attribute(&attrs, "checked").is_some(),
+            })
+        })
+        .collect();
+
+    Form {
+        action: action.to_string(),
+        post,
+        fields,
+    }
+}
+
+/// Extracts the title and the forms of a page.
+pub fn parse_page(url: &Url, html: &str) -> (String, Vec<Form>) {
+    let title = TITLE
+        .captures(html)
+        .map(|caps| plain_text(&caps[1]))
+        .unwrap_or_default();
+    let forms = FORM
+        .captures_iter(html)
+        .map(|caps| parse_form(&caps[1], &caps[2], url))
+        .collect();
+    (title, forms)
+}
+
+/// Returns the target of a `<meta http-equiv="refresh">` tag.
+fn refresh_target(url: &Url, html: &str) -> Option<Url> {
+    let tag = REFRESH.find(html)?;
+    let attrs = attributes(tag.as_str());
+    let content = attribute(&attrs, "content")?;
+    let (_, target) = content.split_once(';')?;
+    let target = target.trim();
+    let target = target
+        .strip_prefix("url=")
+        .or_else(|| target.strip_prefix("URL="))
+        .unwrap_or(target)
+        .trim_matches(|c| c == '\'' || c == '"');
+    url.join(target).ok()
+}
+
+/// Encodes the values sent by a form, as pressed with its `submitter`-th submit field.
+pub fn encode_form(form: &Form, submitter: Option<usize>) -> String {
+    form.fields
+        .iter()
+        .enumerate()
+        .filter(|(index, field)| match field.kind {
+            FieldKind::Checkbox => field.checked,
+            FieldKind::Submit => Some(*index) == submitter && !field.name.is_empty(),
+            _ => true,
+        })
+        .map(|(_, field)| {
+            let value = if field.kind == FieldKind::Checkbox && field.value.is_empty() {
+                "on"
+            } else {
+                &field.value
+            };
+            format!(
+                "{}={}",
+                utf8_percent_encode(&field.name, NON_ALPHANUMERIC),
+                utf8_percent_encode(value, NON_ALPHANUMERIC)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("&")
+}
+
+// The directory of the path of the given URL, the default scope of its cookies.
+fn default_path(url: &Url) -> String {
+    match url.path().rfind('/') {
+        Some(index) if index > 0 => url.path()[..index].to_string(),
+        _ => "/".to_string(),
+    }
+}
+
+/// Stores the cookie set by the response of the given URL.
+fn set_cookie(cookies: &mut Vec<Cookie>, url: &Url, header: &str) {
+    let mut parts = header.split(';');
+    let pair = parts.next().unwrap_or_default();
+    let Some((name, value)) = pair.split_once('=') else {
+        return;
+    };
+
+    let Some(host) = url.host_str() else {
+        return;
+    };
+
+    let mut path = None;
+    let mut expired = false;
+    let mut secure = false;
+    for attr in parts {
+        let (key, value) = attr.split_once('=').unwrap_or((attr, ""));
+        let (key, value) = (key.trim(), value.trim());
+        if key.eq_ignore_ascii_case("path") && value.starts_with('/') {
+            path = Some(value.to_string());
+        } else if key.eq_ignore_ascii_case("max-age") {
+            expired = value.parse::<i64>().is_ok_and(|age| age <= 0);
+        } else if key.eq_ignore_ascii_case("secure") {
+            secure = true;
+        }
+    }
+
+    let cookie = Cookie {
+        name: name.trim().to_string(),
+        value: value.trim().to_string(),
+        host: host.to_string(),
+        path: path.unwrap_or_else(|| default_path(url)),
+        secure,
+    };
+    cookies.retain(|c| (&c.name, &c.host, &c.path) != (&cookie.name, &cookie.host, &cookie.path));
+    if !expired {
+        cookies.push(cookie);
+    }
+}
+
+/// Returns the value of the `Cookie` header of a request to the given URL.
+fn cookie_header(cookies: &[Cookie], url: &Url) -> Option<String> {
+    let pairs = cookies
+        .iter()
+        .filter(|cookie| cookie.matches(url))
+        .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+        .collect::<Vec<String>>();
+    (!pairs.is_empty()).then(|| pairs.join("; "))
+}
+
+fn client() -> Result<Client, Error> {
+    net::client_builder("cadmus", TIMEOUT)
+        .redirect(Policy::none())
+        .build()
+        .map_err(|e| format_err!("can't build HTTP client: {}", e))
+}
+
+/// Sends a request and follows its redirections, returning `None` when the probe answer is
+/// received.
+fn fetch(
+    client: &Client,
+    request: RequestBuilder,
+    mut cookies: Vec<Cookie>,
+) -> Result<Option<PortalPage>, Error> {
+    let mut request = request.build()?;
+    for _ in 0..MAX_REDIRECTS {
+        // The cookies of another host are left out when a redirection crosses over.
+        if let Some(header) = cookie_header(&cookies, request.url()) {
+            request
+                .headers_mut()
+                .insert(COOKIE, HeaderValue::from_str(&header)?);
+        }
+        let response = client.execute(request)?;
+        let url = response.url().clone();
+        for header in response.headers().get_all(SET_COOKIE) {
+            if let Ok(header) = header.to_str() {
+                set_cookie(&mut cookies, &url, header);
+            }
+        }
+        let status = response.status();
+
+        if status == StatusCode::NO_CONTENT {
+            return Ok(None);
+        }
+
+        if status.is_redirection() {
+            let target = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|location| location.to_str().ok())
+                .and_then(|location| url.join(location).ok())
+                .ok_or_else(|| format_err!("redirection without a location"))?;
+            request = client.get(target).build()?;
+            continue;
+        }
+
+        let html = response.text()?;
+        let (title, forms) = parse_page(&url, &html);
+        if forms.is_empty() {
+            if let Some(target) = refresh_target(&url, &html) {
+                request = client.get(target).build()?;
+                continue;
+            }
+        }
+
+        return Ok(Some(PortalPage {
+            url: url.to_string(),
+            title,
+            html,
+            forms,
+            cookies,
+        }));
+    }
+    Err(format_err!("too many redirections"))
+}
+
+/// Checks whether the network is held back by a portal, and returns its login page.
+pub fn detect() -> Result<Option<PortalPage>, Error> {
+    let client = client()?;
+    fetch(&client, client.get(PROBE_URL), Vec::new())
+}
+
+/// Sends a form of a portal page, and checks the network again.
+///
+/// Returns the next page of the portal, or `None` once the network is open.
+pub fn submit(
+    page: &PortalPage,
+    form: &Form,
+    submitter: Option<usize>,
+) -> Result<Option<PortalPage>, Error> {
+    let client = client()?;
+    let body = encode_form(form, submitter);
+    let request = if form.post {
+        client
+            .post(&form.action)
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(body)
+    } else {
+        let mut url = Url::parse(&form.action)?;
+        url.set_query(Some(&body));
+        client.get(url)
+    };
+    match fetch(&client, request, page.cookies.clone())? {
+        // Some portals answer with their own success page, the probe is the judge.
+        Some(next) => {
+            Ok(fetch(&client, client.get(PROBE_URL), next.cookies.clone())?.map(|_| next))
+        }
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOGIN_PAGE: &str = r#"<html><head><title>Hotel &amp; Spa Wi-Fi</title></head>
+<body>
+<form method=POST action="/login">
+  <input type=hidden name=token value="a b">
+  <label for="room">Room number</label> <input id="room" name="room">
+  <input type="password" name="pin" placeholder="PIN">
+  <input type="checkbox" name="terms" checked> I accept the terms
+  <input type="checkbox" name="news" value="yes">
+  <button type="button">Help</button>
+  <input type="submit" name="go" value="Connect">
+</form>
+</body></html>"#;
+
+    #[test]
+    fn test_parse_login_page() {
+        let url = Url::parse("http://portal.example/welcome/").unwrap();
+        let (title, forms) = parse_page(&url, LOGIN_PAGE);
+        assert_eq!(title, "Hotel & Spa Wi-Fi");
+        assert_eq!(forms.len(), 1);
+        let form = &forms[0];
+        assert_eq!(form.action, "http://portal.example/login");
+        assert!(form.post);
+        let summary: Vec<(&str, FieldKind, &str)> = form
+            .fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.kind, f.label.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("token", FieldKind::Hidden, "token"),
+                ("room", FieldKind::Text, "Room number"),
+                ("pin", FieldKind::Password, "PIN"),
+                ("terms", FieldKind::Checkbox, "terms"),
+                ("news", FieldKind::Checkbox, "news"),
+                ("go", FieldKind::Submit, "Connect"),
+            ]
+        );
+        assert_eq!(
+            encode_form(form, Some(5)),
+            "token=a%20b&room=&pin=&terms=on&go=Connect"
+        );
+    }
+
+    #[test]
+    fn test_refresh_and_cookies() {
+        let url = Url::parse("http://192.168.1.1/").unwrap();
+        let html = r#"<meta http-equiv="refresh" content="0; url=/portal?x=1">"#;
+        assert_eq!(
+            refresh_target(&url, html).map(|u| u.to_string()),
+            Some("http://192.168.1.1/portal?x=1".to_string())
+        );
+
+        let mut cookies = Vec::new();
+        set_cookie(&mut cookies, &url, "session=abc; Path=/; HttpOnly");
+        set_cookie(&mut cookies, &url, "lang=en");
+        set_cookie(&mut cookies, &url, "session=def");
+        assert_eq!(
+            cookie_header(&cookies, &url).as_deref(),
+            Some("lang=en; session=def")
+        );
+
+        set_cookie(&mut cookies, &url, "lang=fr; Max-Age=0");
+        assert_eq!(
+            cookie_header(&cookies, &url).as_deref(),
+            Some("session=def")
+        );
+    }
+
+    #[test]
+    fn test_cookies_scope() {
+        let portal = Url::parse("http://portal.example/login/form").unwrap();
+        let mut cookies = Vec::new();
+        set_cookie(&mut cookies, &portal, "session=abc; Path=/");
+        set_cookie(&mut cookies, &portal, "step=2");
+        set_cookie(&mut cookies, &portal, "admin=1; Path=/admin");
+
+        let header = |url: &str| cookie_header(&cookies, &Url::parse(url).unwrap());
+
+        assert_eq!(
+            header("http://portal.example/login/done").as_deref(),
+            Some("session=abc; step=2")
+        );
+        assert_eq!(
+            header("http://portal.example/welcome").as_deref(),
+            Some("session=abc")
+        );
+        assert_eq!(
+            header("http://portal.example/admin/users").as_deref(),
+            Some("session=abc; admin=1")
+        );
+        assert_eq!(
+            header("http://portal.example/administrator").as_deref(),
+            Some("session=abc")
+        );
+        assert_eq!(
+            header("http://portal.example:8080/login/done").as_deref(),
+            Some("session=abc; step=2")
+        );
+        assert_eq!(
+            header("http://connectivitycheck.gstatic.com/generate_204"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_cookies_follow_a_redirection_to_https() {
+        let portal = Url::parse("http://portal.example/login").unwrap();
+        let mut cookies = Vec::new();
+        set_cookie(&mut cookies, &portal, "session=abc; Path=/");
+        let secure = Url::parse("https://portal.example/login").unwrap();
+        set_cookie(&mut cookies, &secure, "token=xyz; Path=/; Secure");
+
+        let header = |url: &str| cookie_header(&cookies, &Url::parse(url).unwrap());
+
+        assert_eq!(
+            header("https://portal.example/welcome").as_deref(),
+            Some("session=abc; token=xyz")
+        );
+        assert_eq!(
+            header("http://portal.example/welcome").as_deref(),
+            Some("session=abc")
+        );
+    }
+}
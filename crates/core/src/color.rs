@@ -75,6 +75,16 @@ impl Color {
         }
     }
 
+    // Multiplies the components, like a highlighter on paper.
+    pub fn tint(&mut self, color: Color) {
+        let [r, g, b] = color.rgb();
+        let mul = |c: u8, t: u8| (c as u16 * t as u16 / 255) as u8;
+        *self = match *self {
+            Color::Gray(level) => Color::Rgb(mul(level, r), mul(level, g), mul(level, b)),
+            Color::Rgb(red, green, blue) => Color::Rgb(mul(red, r), mul(green, g), mul(blue, b)),
+        };
+    }
+
     pub fn shift(&mut self, drift: u8) {
         match self {
             Color::Gray(level) => *level = level.saturating_sub(drift),
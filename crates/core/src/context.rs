@@ -1,17 +1,29 @@
-use crate::battery::Battery;
+use crate::battery::{Battery, BatteryHistory};
 use crate::device::CURRENT_DEVICE;
-use crate::dictionary::{load_dictionary_from_file, Dictionary};
+use crate::dictionary::{
+    convert_yomichan_dictionary, load_dictionary_from_file, load_zim_dictionary, Dictionary,
+};
 use crate::font::Fonts;
 use crate::framebuffer::{Display, Framebuffer};
 use crate::frontlight::Frontlight;
 use crate::geom::Rectangle;
 use crate::helpers::{load_json, IsHidden};
+use crate::i18n::{self, Catalog, Direction, DEFAULT_LANGUAGE};
 use crate::library::Library;
 use crate::lightsensor::LightSensor;
+use crate::net;
 use crate::rtc::Rtc;
+use crate::scripting::Scripts;
 use crate::settings::Settings;
+use crate::theme::{self, Theme};
+use crate::unit::{set_thickness_scale, set_ui_scale};
+use crate::view::backdrop::Elevation;
 use crate::view::keyboard::Layout;
-use crate::view::ViewId;
+use crate::view::notification::NotificationCenter;
+use crate::view::perf_hud::PerfHud;
+use crate::view::pinyin::PinyinTable;
+use crate::view::swipe_typing::WordList;
+use crate::view::{Id, ViewId};
 use chrono::Local;
 use fxhash::FxHashMap;
 use globset::Glob;
@@ -20,14 +32,21 @@ use rand_xoshiro::Xoroshiro128Plus;
 use std::collections::{BTreeMap, VecDeque};
 #[cfg(test)]
 use std::env;
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tracing::error;
 
 use walkdir::WalkDir;
 
 const KEYBOARD_LAYOUTS_DIRNAME: &str = "keyboard-layouts";
 const DICTIONARIES_DIRNAME: &str = "dictionaries";
+const THEMES_DIRNAME: &str = "themes";
+const TRANSLATIONS_DIRNAME: &str = "translations";
 const INPUT_HISTORY_SIZE: usize = 32;
+// The smallest interface scale and the thickness scale of the high contrast mode.
+const HIGH_CONTRAST_UI_SCALE: f32 = 1.25;
+const HIGH_CONTRAST_THICKNESS_SCALE: f32 = 2.0;
 
 pub struct Context {
     pub fb: Box<dyn Framebuffer>,
@@ -38,17 +57,36 @@ pub struct Context {
     pub fonts: Fonts,
     pub dictionaries: BTreeMap<String, Dictionary>,
     pub keyboard_layouts: BTreeMap<String, Layout>,
+    pub themes: BTreeMap<String, Theme>,
+    pub theme: Theme,
+    pub direction: Direction,
     pub input_history: FxHashMap<ViewId, VecDeque<String>>,
     pub frontlight: Box<dyn Frontlight>,
     pub battery: Box<dyn Battery>,
+    pub battery_history: BatteryHistory,
     pub lightsensor: Box<dyn LightSensor>,
     pub notification_index: u8,
+    pub notifications: NotificationCenter,
+    // Index of the last picture shown by a slideshow intermission.
+    pub slideshow_index: Option<usize>,
     pub kb_rect: Rectangle,
+    // The view surrounded by the focus ring of the button navigation.
+    pub focus_ring: Option<Id>,
+    // Whether the rectangles of the views are drawn over them.
+    pub inspector: bool,
+    pub perf_hud: PerfHud,
+    pub scripts: Scripts,
+    // The overlays set apart by the last rendering, bottom to top.
+    pub overlays: Vec<(Rectangle, Elevation)>,
+    // The number of partial updates since the last full refresh.
+    pub partial_updates: u16,
     pub rng: Xoroshiro128Plus,
     pub plugged: bool,
     pub covered: bool,
     pub shared: bool,
     pub online: bool,
+    // Whether the device is connected, or connecting, to Calibre.
+    pub calibre: bool,
 }
 
 impl Context {
@@ -65,6 +103,14 @@ impl Context {
         let dims = fb.dims();
         let rotation = CURRENT_DEVICE.transformed_rotation(fb.rotation());
         let rng = Xoroshiro128Plus::seed_from_u64(Local::now().timestamp_subsec_nanos() as u64);
+        if settings.high_contrast {
+            set_ui_scale(settings.ui_scale.max(HIGH_CONTRAST_UI_SCALE));
+            set_thickness_scale(HIGH_CONTRAST_THICKNESS_SCALE);
+        } else {
+            set_ui_scale(settings.ui_scale);
+            set_thickness_scale(1.0);
+        }
+        net::configure(&settings);
         Context {
             fb,
             rtc,
@@ -74,17 +120,30 @@ impl Context {
             fonts,
             dictionaries: BTreeMap::new(),
             keyboard_layouts: BTreeMap::new(),
+            themes: BTreeMap::new(),
+            theme: Theme::default(),
+            direction: Direction::default(),
             input_history: FxHashMap::default(),
             battery,
+            battery_history: BatteryHistory::default(),
             frontlight,
             lightsensor,
             notification_index: 0,
+            notifications: NotificationCenter::default(),
+            slideshow_index: None,
             kb_rect: Rectangle::default(),
+            focus_ring: None,
+            inspector: false,
+            perf_hud: PerfHud::default(),
+            scripts: Scripts::default(),
+            overlays: Vec::new(),
+            partial_updates: 0,
             rng,
             plugged: false,
             covered: false,
             shared: false,
             online: false,
+            calibre: false,
         }
     }
 
@@ -130,16 +189,136 @@ impl Context {
             if !glob.is_match(path) {
                 continue;
             }
-            if let Ok(layout) = load_json::<Layout, _>(path)
+            if let Ok(mut layout) = load_json::<Layout, _>(path)
                 .map_err(|e| error!("Can't load {}: {:#?}.", path.display(), e))
             {
+                let words_path = path.with_extension("words");
+                if words_path.exists() {
+                    layout.words = WordList::load(&words_path)
+                        .map_err(|e| error!("Can't load {}: {:#}.", words_path.display(), e))
+                        .ok()
+                        .map(Arc::new);
+                }
+                let pinyin_path = path.with_extension("pinyin");
+                if pinyin_path.exists() {
+                    layout.pinyin = PinyinTable::load(&pinyin_path)
+                        .map_err(|e| error!("Can't load {}: {:#}.", pinyin_path.display(), e))
+                        .ok()
+                        .map(Arc::new);
+                }
                 self.keyboard_layouts.insert(layout.name.clone(), layout);
             }
         }
     }
 
+    pub fn load_themes(&mut self) {
+        let glob = Glob::new("**/*.toml").unwrap().compile_matcher();
+
+        #[cfg(test)]
+        let path = Path::new(
+            &env::var("TEST_ROOT_DIR").expect("TEST_ROOT_DIR must be set for test using themes"),
+        )
+        .join(THEMES_DIRNAME);
+
+        #[cfg(not(test))]
+        let path = Path::new(THEMES_DIRNAME);
+
+        for entry in WalkDir::new(path)
+            .min_depth(1)
+            .into_iter()
+            .filter_entry(|e| !e.is_hidden())
+        {
+            if entry.is_err() {
+                continue;
+            }
+            let entry = entry.unwrap();
+            let path = entry.path();
+            if !glob.is_match(path) {
+                continue;
+            }
+            let Some(name) = path.file_stem().map(|s| s.to_string_lossy().into_owned()) else {
+                continue;
+            };
+            match Theme::load(path) {
+                Ok(theme) => {
+                    self.themes.insert(name, theme);
+                }
+                Err(e) => error!("Can't load {}: {:#}.", path.display(), e),
+            }
+        }
+    }
+
+    /// Applies the theme selected in the settings, falling back to the light theme.
+    ///
+    /// User themes take precedence over the built-in themes with the same name, and the high
+    /// contrast mode takes precedence over both.
+    pub fn apply_theme(&mut self) {
+        if self.settings.high_contrast {
+            self.theme = Theme::HIGH_CONTRAST;
+            theme::set_current(self.theme);
+            return;
+        }
+        let name = &self.settings.theme;
+        self.theme = self
+            .themes
+            .get(name)
+            .copied()
+            .or_else(|| Theme::builtin(name))
+            .unwrap_or_else(|| {
+                error!("Unknown theme: {}.", name);
+                Theme::default()
+            });
+        theme::set_current(self.theme);
+    }
+
+    /// Returns the languages of the user interface: English, and those that have a catalog.
+    pub fn available_languages(&self) -> Vec<String> {
+        let mut languages = fs::read_dir(translations_dir())
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|e| e.path()))
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+                    .filter_map(|path| {
+                        path.file_stem()
+                            .map(|stem| stem.to_string_lossy().into_owned())
+                    })
+                    .collect::<Vec<String>>()
+            })
+            .unwrap_or_default();
+        languages.sort();
+        languages.insert(0, DEFAULT_LANGUAGE.to_string());
+        languages
+    }
+
+    /// Applies the translations of the language selected in the settings.
+    ///
+    /// The user interface stays in English, and is laid out from left to right, when the
+    /// language has no catalog.
+    pub fn load_translations(&mut self) {
+        let language = &self.settings.language;
+        self.direction = Direction::LeftToRight;
+        if language == DEFAULT_LANGUAGE {
+            i18n::set_current(None);
+            return;
+        }
+
+        let path = translations_dir().join(format!("{}.toml", language));
+        match Catalog::load(language, &path) {
+            Ok(catalog) => {
+                self.direction = Direction::from_language(language);
+                i18n::set_current(Some(catalog));
+            }
+            Err(e) => {
+                error!("Can't load {}: {:#}.", path.display(), e);
+                i18n::set_current(None);
+            }
+        }
+    }
+
     pub fn load_dictionaries(&mut self) {
         let glob = Glob::new("**/*.index").unwrap().compile_matcher();
+        let yomichan_glob = Glob::new("**/*.zip").unwrap().compile_matcher();
+        let zim_glob = Glob::new("**/*.zim").unwrap().compile_matcher();
 
         #[cfg(test)]
         let path = Path::new(
@@ -161,15 +340,45 @@ impl Context {
                 continue;
             }
             let entry = entry.unwrap();
-            if !glob.is_match(entry.path()) {
+            if zim_glob.is_match(entry.path()) {
+                match load_zim_dictionary(entry.path()) {
+                    Ok(mut dict) => {
+                        let name = dict
+                            .short_name()
+                            .ok()
+                            .filter(|s| !s.is_empty())
+                            .unwrap_or_else(|| {
+                                entry
+                                    .path()
+                                    .file_stem()
+                                    .map(|s| s.to_string_lossy().into_owned())
+                                    .unwrap_or_default()
+                            });
+                        self.dictionaries.insert(name, dict);
+                    }
+                    Err(e) => error!("Can't open {}: {:#}.", entry.path().display(), e),
+                }
                 continue;
             }
-            let index_path = entry.path().to_path_buf();
-            let mut content_path = index_path.clone();
-            content_path.set_extension("dict.dz");
-            if !content_path.exists() {
-                content_path.set_extension("");
-            }
+            let (content_path, index_path) = if yomichan_glob.is_match(entry.path()) {
+                match convert_yomichan_dictionary(entry.path()) {
+                    Ok(paths) => paths,
+                    Err(e) => {
+                        error!("Can't convert {}: {:#}.", entry.path().display(), e);
+                        continue;
+                    }
+                }
+            } else if glob.is_match(entry.path()) && !entry.path().with_extension("zip").exists() {
+                let index_path = entry.path().to_path_buf();
+                let mut content_path = index_path.clone();
+                content_path.set_extension("dict.dz");
+                if !content_path.exists() {
+                    content_path.set_extension("");
+                }
+                (content_path, index_path)
+            } else {
+                continue;
+            };
             if let Ok(mut dict) = load_dictionary_from_file(&content_path, &index_path) {
                 let name = dict.short_name().ok().unwrap_or_else(|| {
                     index_path
@@ -213,6 +422,19 @@ impl Context {
     }
 }
 
+fn translations_dir() -> PathBuf {
+    #[cfg(test)]
+    let path = Path::new(
+        &env::var("TEST_ROOT_DIR").expect("TEST_ROOT_DIR must be set for test using translations"),
+    )
+    .join(TRANSLATIONS_DIRNAME);
+
+    #[cfg(not(test))]
+    let path = PathBuf::from(TRANSLATIONS_DIRNAME);
+
+    path
+}
+
 #[cfg(test)]
 pub mod test_helpers {
     use super::*;
@@ -0,0 +1,174 @@
+//! Crash reports, written when the program panics and offered at the next startup.
+//!
+//! The report holds the panic message and its location, the version of the program, the
+//! model of the device, a backtrace, and the last events taken from the hub. It is saved
+//! in `Crashes/`, and the name of the latest report is kept until the next startup takes it.
+
+use crate::device::CURRENT_DEVICE;
+use crate::helpers::escape_html;
+use crate::kiosk;
+use crate::view::Event;
+use anyhow::{Context, Error};
+use chrono::Local;
+use lazy_static::lazy_static;
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::fs;
+use std::panic::{self, PanicHookInfo};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+
+pub const CRASHES_DIRNAME: &str = "Crashes";
+
+/// Holds the name of the report that wasn't offered yet.
+const PENDING_FILENAME: &str = "pending";
+
+/// The number of events kept for the report.
+const MAX_EVENTS: usize = 32;
+
+/// The length at which the description of an event is cut.
+const MAX_EVENT_LEN: usize = 160;
+
+lazy_static! {
+    static ref EVENTS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::with_capacity(MAX_EVENTS));
+}
+
+/// Installs the panic hook that writes the reports, before calling the previous hook.
+pub fn install_hook(version: &'static str) {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        // The lock might be held by the panicking thread.
+        let events = EVENTS
+            .try_lock()
+            .map(|events| events.iter().cloned().collect::<Vec<String>>())
+            .unwrap_or_default();
+        let report = report(info, version, &Backtrace::force_capture(), &events);
+        match save_report(Path::new(CRASHES_DIRNAME), &report) {
+            Ok(path) => eprintln!("The crash report was saved to {}.", path.display()),
+            Err(e) => eprintln!("Can't save the crash report: {:#}.", e),
+        }
+        previous(info);
+    }));
+}
+
+/// Keeps a description of the event for the next report.
+pub fn record_event(evt: &Event) {
+    let mut description = kiosk::describe(evt);
+    if let Some((index, _)) = description.char_indices().nth(MAX_EVENT_LEN) {
+        description.truncate(index);
+        description.push('…');
+    }
+    let mut events = EVENTS.lock().unwrap_or_else(|e| e.into_inner());
+    if events.len() == MAX_EVENTS {
+        events.pop_front();
+    }
+    events.push_back(description);
+}
+
+/// Returns the report of the last crash, if it wasn't offered yet.
+pub fn take_pending_report() -> Option<PathBuf> {
+    take_pending(Path::new(CRASHES_DIRNAME))
+}
+
+/// Copies the report into the given directory, returning the path of the copy.
+pub fn export_report(path: &Path, dir: &Path) -> Result<PathBuf, Error> {
+    let name = path.file_name().context("the report has no name")?;
+    let target = dir.join(name);
+    fs::copy(path, &target)
+        .with_context(|| format!("can't copy the report to {}", dir.display()))?;
+    Ok(target)
+}
+
+/// Renders the report, to be read on the device.
+pub fn report_as_html(path: &Path) -> Result<String, Error> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("can't read {}", path.display()))?;
+    let text = escape_html(&text);
+    Ok(format!(
+        "<html>\n\t<head>\n\t\t<title>Crash Report</title>\n\t\t\
+         <link rel=\"stylesheet\" type=\"text/css\" href=\"css/sysinfo.css\"/>\n\t</head>\n\t\
+         <body>\n\t\t<pre>{}</pre>\n\t</body>\n</html>",
+        text
+    ))
+}
+
+fn report(info: &PanicHookInfo, version: &str, backtrace: &Backtrace, events: &[String]) -> String {
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown cause".to_string());
+    let location = info
+        .location()
+        .map(|location| location.to_string())
+        .unwrap_or_default();
+
+    let mut buf = String::new();
+    writeln!(buf, "Cadmus {} on a Kobo {}", version, CURRENT_DEVICE.model).ok();
+    writeln!(buf, "Date: {}", Local::now().format("%Y-%m-%d %H:%M:%S")).ok();
+    writeln!(
+        buf,
+        "Thread: {}",
+        thread::current().name().unwrap_or("unnamed")
+    )
+    .ok();
+    writeln!(buf, "Panic: {}", message).ok();
+    writeln!(buf, "Location: {}", location).ok();
+    writeln!(buf, "\nLast events:").ok();
+    for evt in events {
+        writeln!(buf, "  {}", evt).ok();
+    }
+    writeln!(buf, "\nBacktrace:\n{}", backtrace).ok();
+    buf
+}
+
+/// Writes the report in the directory, and marks it as pending.
+fn save_report(dir: &Path, report: &str) -> Result<PathBuf, Error> {
+    fs::create_dir_all(dir).with_context(|| format!("can't create {}", dir.display()))?;
+    let name = format!("crash-{}.txt", Local::now().format("%Y%m%d-%H%M%S"));
+    let path = dir.join(&name);
+    fs::write(&path, report).with_context(|| format!("can't write {}", path.display()))?;
+    fs::write(dir.join(PENDING_FILENAME), &name).context("can't mark the report as pending")?;
+    Ok(path)
+}
+
+fn take_pending(dir: &Path) -> Option<PathBuf> {
+    let marker = dir.join(PENDING_FILENAME);
+    let name = fs::read_to_string(&marker).ok()?;
+    fs::remove_file(&marker).ok();
+    Some(dir.join(name.trim())).filter(|path| path.exists())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_report() {
+        let dir = tempfile::tempdir().unwrap();
+        let crashes = dir.path().join(CRASHES_DIRNAME);
+        assert!(take_pending(&crashes).is_none());
+
+        let path = save_report(&crashes, "Panic: <oops> & co").unwrap();
+        assert_eq!(take_pending(&crashes), Some(path.clone()));
+        assert!(take_pending(&crashes).is_none());
+
+        let html = report_as_html(&path).unwrap();
+        assert!(html.contains("<pre>Panic: &lt;oops&gt; &amp; co</pre>"));
+
+        let copy = export_report(&path, dir.path()).unwrap();
+        assert_eq!(fs::read_to_string(copy).unwrap(), "Panic: <oops> & co");
+    }
+
+    #[test]
+    fn test_record_event_truncates() {
+        record_event(&Event::OpenHtml("x".repeat(1000), None));
+        let events = EVENTS.lock().unwrap();
+        let last = events.back().unwrap();
+        assert_eq!(last.chars().count(), MAX_EVENT_LEN + 1);
+        assert!(last.ends_with('…'));
+    }
+}
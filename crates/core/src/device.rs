@@ -455,10 +455,46 @@ impl Device {
     }
 }
 
+// Returns the product name of the device given its model number, for when the `PRODUCT`
+// variable isn't set, e.g. when launched outside of Nickel.
+fn product_from_model_number(model_number: &str) -> Option<&'static str> {
+    match model_number {
+        "310" | "320" => Some("trilogy"),
+        "330" => Some("kraken"),
+        "340" => Some("pixie"),
+        "350" => Some("dragon"),
+        "360" => Some("phoenix"),
+        "370" => Some("dahlia"),
+        "371" => Some("alyssum"),
+        "372" => Some("pika"),
+        "373" | "381" => Some("daylight"),
+        "374" | "378" => Some("snow"),
+        "375" | "379" => Some("star"),
+        "376" => Some("nova"),
+        "377" | "380" => Some("frost"),
+        "382" => Some("luna"),
+        "383" => Some("cadmus"),
+        "384" => Some("storm"),
+        "386" => Some("goldfinch"),
+        "387" => Some("europa"),
+        "388" => Some("io"),
+        "389" => Some("condor"),
+        "390" => Some("monza"),
+        "391" => Some("spaBW"),
+        "393" => Some("spaColour"),
+        "395" => Some("spaBWTPV"),
+        _ => None,
+    }
+}
+
 lazy_static! {
     pub static ref CURRENT_DEVICE: Device = {
-        let product = env::var("PRODUCT").unwrap_or_default();
         let model_number = env::var("MODEL_NUMBER").unwrap_or_default();
+        let product = env::var("PRODUCT")
+            .ok()
+            .filter(|product| !product.is_empty())
+            .or_else(|| product_from_model_number(&model_number).map(String::from))
+            .unwrap_or_default();
 
         Device::new(&product, &model_number)
     };
@@ -466,7 +502,7 @@ lazy_static! {
 
 #[cfg(test)]
 mod tests {
-    use super::Device;
+    use super::{product_from_model_number, Device, FrontlightKind, Model};
 
     #[test]
     fn test_device_canonical_rotation() {
@@ -481,4 +517,43 @@ mod tests {
             aura_one.from_canonical(2) - aura_one.from_canonical(3)
         );
     }
+
+    #[test]
+    fn test_recent_devices() {
+        let clara_bw = Device::new("spaBWTPV", "395");
+        assert_eq!(clara_bw.model, Model::ClaraBW);
+        assert_eq!((clara_bw.dims, clara_bw.dpi), ((1072, 1448), 300));
+        assert_eq!(clara_bw.color_samples(), 1);
+        assert!(!clara_bw.has_page_turn_buttons());
+
+        let clara_colour = Device::new("spaColour", "393");
+        assert_eq!(clara_colour.color_samples(), 3);
+        assert_eq!(clara_colour.frontlight_kind(), FrontlightKind::Premixed);
+
+        let libra_colour = Device::new("monza", "390");
+        assert_eq!((libra_colour.dims, libra_colour.dpi), ((1264, 1680), 300));
+        assert_eq!(libra_colour.color_samples(), 3);
+        assert!(libra_colour.has_page_turn_buttons() && libra_colour.has_gyroscope());
+
+        let elipsa_2e = Device::new("condor", "389");
+        assert_eq!((elipsa_2e.dims, elipsa_2e.dpi), ((1404, 1872), 227));
+        assert!(elipsa_2e.has_gyroscope() && !elipsa_2e.has_page_turn_buttons());
+        assert_eq!(elipsa_2e.mark(), 11);
+    }
+
+    #[test]
+    fn test_product_from_model_number() {
+        for (model_number, model) in [
+            ("389", Model::Elipsa2E),
+            ("390", Model::LibraColour),
+            ("391", Model::ClaraBW),
+            ("393", Model::ClaraColour),
+            ("395", Model::ClaraBW),
+            ("379", Model::AuraEd2V2),
+        ] {
+            let product = product_from_model_number(model_number).unwrap();
+            assert_eq!(Device::new(product, model_number).model, model);
+        }
+        assert_eq!(product_from_model_number("666"), None);
+    }
 }
@@ -0,0 +1,249 @@
+//! Deinflection of Japanese verbs and adjectives.
+//!
+//! Inflected words are reduced to their dictionary forms by repeatedly swapping suffixes,
+//! following the approach of Yomichan: each rule states the word class it produces and the
+//! classes it can be applied to, e.g. *食べなかった* → *食べない* (past of an i-adjective)
+//! → *食べる* (negative of an ichidan verb).
+
+// Word classes.
+const V1: u16 = 1 << 0; // Ichidan verbs.
+const V5: u16 = 1 << 1; // Godan verbs.
+const VS: u16 = 1 << 2; // Suru verbs.
+const VK: u16 = 1 << 3; // Kuru verb.
+const ADJ_I: u16 = 1 << 4; // I-adjectives.
+const MASU: u16 = 1 << 5; // Polite forms.
+const TE: u16 = 1 << 6; // Te forms.
+const TA: u16 = 1 << 7; // Past forms.
+
+const VERB: u16 = V1 | V5 | VS | VK;
+
+// Deinflections chained on a single word, guards against runaway rules.
+const MAX_DEPTH: usize = 6;
+
+struct Rule {
+    kana_in: &'static str,
+    kana_out: &'static str,
+    // The classes of the inflected word, zero when it can't be inflected further.
+    rules_in: u16,
+    // The class of the deinflected word.
+    rules_out: u16,
+    reason: &'static str,
+}
+
+macro_rules! rules {
+    ($($reason:literal, $rules_in:expr => [$($kana_in:literal, $kana_out:literal, $rules_out:expr);+ $(;)?]),+ $(,)?) => {
+        &[$($(Rule {
+            kana_in: $kana_in,
+            kana_out: $kana_out,
+            rules_in: $rules_in,
+            rules_out: $rules_out,
+            reason: $reason,
+        }),+),+]
+    };
+}
+
+const RULES: &[Rule] = rules![
+    "past", TA => [
+        "た", "る", V1; "いた", "く", V5; "いだ", "ぐ", V5; "した", "す", V5;
+        "った", "う", V5; "った", "つ", V5; "った", "る", V5; "んだ", "ぬ", V5;
+        "んだ", "ぶ", V5; "んだ", "む", V5; "行った", "行く", V5; "いった", "いく", V5;
+        "した", "する", VS; "きた", "くる", VK; "来た", "来る", VK; "かった", "い", ADJ_I;
+    ],
+    "-tara", 0 => ["たら", "た", TA; "だら", "だ", TA],
+    "-tari", 0 => ["たり", "た", TA; "だり", "だ", TA],
+    "-te", TE => [
+        "て", "る", V1; "いて", "く", V5; "いで", "ぐ", V5; "して", "す", V5;
+        "って", "う", V5; "って", "つ", V5; "って", "る", V5; "んで", "ぬ", V5;
+        "んで", "ぶ", V5; "んで", "む", V5; "行って", "行く", V5; "いって", "いく", V5;
+        "して", "する", VS; "きて", "くる", VK; "来て", "来る", VK; "くて", "い", ADJ_I;
+    ],
+    "progressive", V1 => ["ている", "て", TE; "でいる", "で", TE; "てる", "て", TE; "でる", "で", TE],
+    "negative", ADJ_I => [
+        "ない", "る", V1; "わない", "う", V5; "かない", "く", V5; "がない", "ぐ", V5;
+        "さない", "す", V5; "たない", "つ", V5; "なない", "ぬ", V5; "ばない", "ぶ", V5;
+        "まない", "む", V5; "らない", "る", V5; "しない", "する", VS; "こない", "くる", VK;
+        "来ない", "来る", VK; "くない", "い", ADJ_I;
+    ],
+    "polite", MASU => [
+        "ます", "る", V1; "います", "う", V5; "きます", "く", V5; "ぎます", "ぐ", V5;
+        "します", "す", V5; "ちます", "つ", V5; "にます", "ぬ", V5; "びます", "ぶ", V5;
+        "みます", "む", V5; "ります", "る", V5; "します", "する", VS; "きます", "くる", VK;
+        "来ます", "来る", VK;
+    ],
+    "polite past", 0 => ["ました", "ます", MASU],
+    "polite negative", 0 => ["ません", "ます", MASU],
+    "polite volitional", 0 => ["ましょう", "ます", MASU],
+    "-tai", ADJ_I => [
+        "たい", "る", V1; "いたい", "う", V5; "きたい", "く", V5; "ぎたい", "ぐ", V5;
+        "したい", "す", V5; "ちたい", "つ", V5; "にたい", "ぬ", V5; "びたい", "ぶ", V5;
+        "みたい", "む", V5; "りたい", "る", V5; "したい", "する", VS; "きたい", "くる", VK;
+        "来たい", "来る", VK;
+    ],
+    "potential or passive", V1 => ["られる", "る", V1; "こられる", "くる", VK],
+    "potential", V1 => [
+        "える", "う", V5; "ける", "く", V5; "げる", "ぐ", V5; "せる", "す", V5;
+        "てる", "つ", V5; "ねる", "ぬ", V5; "べる", "ぶ", V5; "める", "む", V5;
+        "れる", "る", V5; "できる", "する", VS;
+    ],
+    "passive", V1 => [
+        "われる", "う", V5; "かれる", "く", V5; "がれる", "ぐ", V5; "される", "す", V5;
+        "たれる", "つ", V5; "なれる", "ぬ", V5; "ばれる", "ぶ", V5; "まれる", "む", V5;
+        "られる", "る", V5; "される", "する", VS;
+    ],
+    "causative", V1 => [
+        "させる", "る", V1; "わせる", "う", V5; "かせる", "く", V5; "がせる", "ぐ", V5;
+        "させる", "す", V5; "たせる", "つ", V5; "なせる", "ぬ", V5; "ばせる", "ぶ", V5;
+        "ませる", "む", V5; "らせる", "る", V5; "させる", "する", VS; "こさせる", "くる", VK;
+    ],
+    "volitional", 0 => [
+        "よう", "る", V1; "おう", "う", V5; "こう", "く", V5; "ごう", "ぐ", V5;
+        "そう", "す", V5; "とう", "つ", V5; "のう", "ぬ", V5; "ぼう", "ぶ", V5;
+        "もう", "む", V5; "ろう", "る", V5; "しよう", "する", VS; "こよう", "くる", VK;
+    ],
+    "-ba", 0 => [
+        "れば", "る", V1 | V5; "えば", "う", V5; "けば", "く", V5; "げば", "ぐ", V5;
+        "せば", "す", V5; "てば", "つ", V5; "ねば", "ぬ", V5; "べば", "ぶ", V5;
+        "めば", "む", V5; "すれば", "する", VS; "くれば", "くる", VK; "ければ", "い", ADJ_I;
+    ],
+    "imperative", 0 => [
+        "ろ", "る", V1; "よ", "る", V1; "え", "う", V5; "け", "く", V5; "げ", "ぐ", V5;
+        "せ", "す", V5; "て", "つ", V5; "ね", "ぬ", V5; "べ", "ぶ", V5; "め", "む", V5;
+        "れ", "る", V5; "しろ", "する", VS; "こい", "くる", VK;
+    ],
+    "adverb", 0 => ["く", "い", ADJ_I],
+    "noun", 0 => ["さ", "い", ADJ_I],
+    "-sou", 0 => ["そう", "い", ADJ_I],
+];
+
+/// A dictionary form candidate, with the inflections leading from it to the looked up word.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Deinflection {
+    pub term: String,
+    pub reasons: Vec<&'static str>,
+}
+
+/// Returns the possible dictionary forms of the given word, starting with the word itself.
+///
+/// The candidates aren't checked against any dictionary: most of them don't exist.
+pub fn deinflect(word: &str) -> Vec<Deinflection> {
+    let mut results = vec![(
+        Deinflection {
+            term: word.to_string(),
+            reasons: Vec::new(),
+        },
+        0,
+    )];
+    let mut index = 0;
+
+    while index < results.len() {
+        let (term, rules, reasons) = {
+            let (deinflection, rules) = &results[index];
+            (
+                deinflection.term.clone(),
+                *rules,
+                deinflection.reasons.clone(),
+            )
+        };
+        index += 1;
+
+        if reasons.len() >= MAX_DEPTH {
+            continue;
+        }
+
+        for rule in RULES {
+            if rules != 0 && rules & rule.rules_in == 0 {
+                continue;
+            }
+
+            let Some(stem) = term.strip_suffix(rule.kana_in) else {
+                continue;
+            };
+
+            if stem.is_empty() && rule.rules_out & VERB == 0 {
+                continue;
+            }
+
+            let base = format!("{}{}", stem, rule.kana_out);
+
+            if results
+                .iter()
+                .any(|(d, r)| d.term == base && *r == rule.rules_out)
+            {
+                continue;
+            }
+
+            let mut base_reasons = vec![rule.reason];
+            base_reasons.extend_from_slice(&reasons);
+            results.push((
+                Deinflection {
+                    term: base,
+                    reasons: base_reasons,
+                },
+                rule.rules_out,
+            ));
+        }
+    }
+
+    results.into_iter().map(|(d, _)| d).collect()
+}
+
+/// Returns whether the given text contains kana or kanji.
+pub fn is_japanese(text: &str) -> bool {
+    text.chars().any(|c| {
+        matches!(c,
+        '\u{3040}'..='\u{30FF}' // Hiragana and katakana.
+        | '\u{4E00}'..='\u{9FFF}' // CJK unified ideographs.
+        | '\u{FF66}'..='\u{FF9F}') // Halfwidth katakana.
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(word: &str, term: &str) -> Option<Vec<&'static str>> {
+        deinflect(word)
+            .into_iter()
+            .find(|d| d.term == term)
+            .map(|d| d.reasons)
+    }
+
+    #[test]
+    fn test_deinflect_verbs() {
+        assert_eq!(find("食べた", "食べる"), Some(vec!["past"]));
+        assert_eq!(
+            find("食べなかった", "食べる"),
+            Some(vec!["negative", "past"])
+        );
+        assert_eq!(
+            find("読んでいた", "読む"),
+            Some(vec!["-te", "progressive", "past"])
+        );
+        assert_eq!(
+            find("書きました", "書く"),
+            Some(vec!["polite", "polite past"])
+        );
+        assert_eq!(find("勉強した", "勉強する"), Some(vec!["past"]));
+        assert_eq!(find("来なかった", "来る"), Some(vec!["negative", "past"]));
+    }
+
+    #[test]
+    fn test_deinflect_adjectives() {
+        assert_eq!(find("高かった", "高い"), Some(vec!["past"]));
+        assert_eq!(find("高くない", "高い"), Some(vec!["negative"]));
+    }
+
+    #[test]
+    fn test_deinflect_keeps_word() {
+        let results = deinflect("猫");
+        assert_eq!(results[0].term, "猫");
+        assert!(results[0].reasons.is_empty());
+    }
+
+    #[test]
+    fn test_is_japanese() {
+        assert!(is_japanese("食べる"));
+        assert!(is_japanese("カタカナ"));
+        assert!(!is_japanese("word"));
+    }
+}
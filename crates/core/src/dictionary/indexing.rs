@@ -72,7 +72,7 @@ fn normalize(entries: &[Entry], metadata: &Metadata) -> Vec<Entry> {
         let original = if headword != entry.headword {
             Some(entry.headword.clone())
         } else {
-            None
+            entry.original.clone()
         };
 
         result.insert(
@@ -169,6 +169,20 @@ pub fn decode_number(word: &str) -> Result<u64, DictError> {
     Ok(index)
 }
 
+/// Encode a number in the format described in the module documentation.
+pub fn encode_number(mut number: u64) -> String {
+    const DIGITS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut digits = Vec::new();
+    loop {
+        digits.push(DIGITS[(number % 64) as usize]);
+        number /= 64;
+        if number == 0 {
+            break;
+        }
+    }
+    digits.iter().rev().map(|&d| d as char).collect()
+}
+
 /// Parse a single line from the index file.
 fn parse_line(line: &str, line_number: usize) -> Result<(&str, u64, u64, Option<&str>), DictError> {
     // First column: headword.
@@ -364,4 +378,13 @@ mod tests {
         assert!(!r.is_empty());
         assert_eq!(r.first().unwrap().headword, "Bar");
     }
+
+    #[test]
+    fn test_encode_number() {
+        assert_eq!(encode_number(0), "A");
+        assert_eq!(encode_number(64), "BA");
+        for number in [1, 63, 4_096, 123_456_789] {
+            assert_eq!(decode_number(&encode_number(number)).unwrap(), number);
+        }
+    }
 }
@@ -1,17 +1,24 @@
 //! A dict format (`*.dict`) reader crate.
 //!
 //! This crate can read dictionaries in the dict format, as used by dictd. It supports both
-//! uncompressed and compressed dictionaries.
+//! uncompressed and compressed dictionaries. Yomichan dictionaries are converted to this format,
+//! and the articles of ZIM archives can be looked up too.
 
+pub mod deinflect;
 mod dictreader;
 mod errors;
 mod indexing;
+mod yomichan;
+mod zim;
 
 use std::path::Path;
 
 use self::dictreader::DictReader;
 use self::indexing::IndexReader;
 
+pub use self::yomichan::convert_dictionary as convert_yomichan_dictionary;
+pub use self::zim::load_dictionary as load_zim_dictionary;
+
 /// A dictionary wrapper.
 ///
 /// A dictionary is made up of a `*.dict` or `*.dict.dz` file with the actual content and a
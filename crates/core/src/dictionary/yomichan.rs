@@ -0,0 +1,230 @@
+//! Convert Yomichan dictionaries to the dict format.
+//!
+//! A Yomichan dictionary is a ZIP archive holding an `index.json` file, with the title of the
+//! dictionary, and term banks named `term_bank_N.json`. Each term bank is an array of terms:
+//!
+//! `[expression, reading, definition tags, rules, score, glossary, sequence, term tags]`
+//!
+//! The archive is converted once into `*.dict` and `*.index` files stored alongside it. Each
+//! term can be looked up by its expression or by its reading. EPWING dictionaries can be
+//! converted to this format with *yomichan-import*.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::Value as JsonValue;
+use zip::ZipArchive;
+
+use super::errors::DictError;
+use super::indexing::encode_number;
+use crate::helpers::escape_html;
+
+/// Converts the given Yomichan archive, unless it was already converted.
+///
+/// Returns the paths of the content and index files.
+pub fn convert_dictionary<P: AsRef<Path>>(path: P) -> Result<(PathBuf, PathBuf), DictError> {
+    let path = path.as_ref();
+    let content_path = path.with_extension("dict");
+    let index_path = path.with_extension("index");
+
+    let modified = |p: &Path| fs::metadata(p).and_then(|m| m.modified()).ok();
+    if content_path.exists() && modified(&index_path) >= modified(path) {
+        return Ok((content_path, index_path));
+    }
+
+    let invalid = |message: String| {
+        DictError::InvalidFileFormat(message, Some(path.to_string_lossy().into_owned()))
+    };
+    let mut archive = ZipArchive::new(File::open(path)?).map_err(|e| invalid(e.to_string()))?;
+
+    let title = read_json(&mut archive, "index.json")
+        .ok()
+        .and_then(|index| {
+            index
+                .get("title")
+                .and_then(JsonValue::as_str)
+                .map(String::from)
+        })
+        .or_else(|| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+        })
+        .unwrap_or_default();
+
+    let mut banks = archive
+        .file_names()
+        .filter(|name| name.starts_with("term_bank_") && name.ends_with(".json"))
+        .map(String::from)
+        .collect::<Vec<String>>();
+    banks.sort();
+
+    if banks.is_empty() {
+        return Err(invalid("no term banks".to_string()));
+    }
+
+    let mut content = String::new();
+    let mut entries = Vec::new();
+
+    for (name, value) in [
+        ("00-database-allchars", ""),
+        ("00-database-case-sensitive", ""),
+        ("00-database-short", title.as_str()),
+        ("00-database-utf8", ""),
+    ] {
+        let definition = format!("{}\n{}\n", name, value);
+        entries.push((name.to_string(), content.len(), definition.len(), None));
+        content.push_str(&definition);
+    }
+
+    for name in &banks {
+        let bank = read_json(&mut archive, name)?;
+        for term in bank.as_array().into_iter().flatten() {
+            let Some(expression) = term.get(0).and_then(JsonValue::as_str) else {
+                continue;
+            };
+            let expression = headword(expression);
+            if expression.is_empty() {
+                continue;
+            }
+            let reading = term
+                .get(1)
+                .and_then(JsonValue::as_str)
+                .map(headword)
+                .unwrap_or_default();
+            let tags = term.get(2).and_then(JsonValue::as_str).unwrap_or_default();
+            let glossary = term
+                .get(5)
+                .and_then(JsonValue::as_array)
+                .map(|items| {
+                    items
+                        .iter()
+                        .map(glossary_text)
+                        .filter(|text| !text.is_empty())
+                        .collect::<Vec<String>>()
+                })
+                .unwrap_or_default();
+
+            let definition = definition(&expression, &reading, tags, &glossary);
+            let offset = content.len();
+            content.push_str(&definition);
+
+            if !reading.is_empty() && reading != expression {
+                entries.push((reading, offset, definition.len(), Some(expression.clone())));
+            }
+            entries.push((expression, offset, definition.len(), None));
+        }
+    }
+
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    fs::write(&content_path, content)?;
+    let mut index = BufWriter::new(File::create(&index_path)?);
+    for (headword, offset, size, original) in entries {
+        write!(
+            index,
+            "{}\t{}\t{}",
+            headword,
+            encode_number(offset as u64),
+            encode_number(size as u64)
+        )?;
+        if let Some(original) = original {
+            write!(index, "\t{}", original)?;
+        }
+        writeln!(index)?;
+    }
+    index.flush()?;
+
+    Ok((content_path, index_path))
+}
+
+fn read_json<R: Read + std::io::Seek>(
+    archive: &mut ZipArchive<R>,
+    name: &str,
+) -> Result<JsonValue, DictError> {
+    let invalid = |message: String| DictError::InvalidFileFormat(message, Some(name.to_string()));
+    let mut file = archive.by_name(name).map_err(|e| invalid(e.to_string()))?;
+    let mut text = String::new();
+    file.read_to_string(&mut text)?;
+    serde_json::from_str(&text).map_err(|e| invalid(e.to_string()))
+}
+
+// Tabs and new lines are the separators of the index file.
+fn headword(text: &str) -> String {
+    text.replace(['\t', '\n'], " ").trim().to_string()
+}
+
+// Flattens a glossary item: either a string, or a structured content object.
+fn glossary_text(item: &JsonValue) -> String {
+    match item {
+        JsonValue::String(text) => text.clone(),
+        JsonValue::Array(items) => items.iter().map(glossary_text).collect(),
+        JsonValue::Object(object) => match object.get("type").and_then(JsonValue::as_str) {
+            Some("text") => object
+                .get("text")
+                .and_then(JsonValue::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            Some("image") => String::new(),
+            _ => object.get("content").map(glossary_text).unwrap_or_default(),
+        },
+        _ => String::new(),
+    }
+}
+
+fn definition(expression: &str, reading: &str, tags: &str, glossary: &[String]) -> String {
+    let mut buf = format!("<h2 class=\"headword\">{}", escape_html(expression));
+    if !reading.is_empty() && reading != expression {
+        buf.push_str(&format!("【{}】", escape_html(reading)));
+    }
+    buf.push_str("</h2>\n");
+    if !tags.trim().is_empty() {
+        buf.push_str(&format!("<p><i>{}</i></p>\n", escape_html(tags.trim())));
+    }
+    buf.push_str("<ol>\n");
+    for text in glossary {
+        buf.push_str(&format!("<li>{}</li>\n", escape_html(text)));
+    }
+    buf.push_str("</ol>\n");
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::load_dictionary_from_file;
+    use zip::write::{SimpleFileOptions, ZipWriter};
+
+    const INDEX: &str = r#"{"title": "Tiny", "format": 3, "revision": "1"}"#;
+    const TERM_BANK: &str = r#"[
+        ["食べる", "たべる", "v1", "v1", 0, ["to eat", {"type": "structured-content", "content": ["to ", {"tag": "b", "content": "live on"}]}], 1, ""],
+        ["猫", "ねこ", "n", "", 0, ["cat"], 2, ""]
+    ]"#;
+
+    #[test]
+    fn test_convert_dictionary() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tiny.zip");
+        let mut writer = ZipWriter::new(File::create(&path).unwrap());
+        for (name, text) in [("index.json", INDEX), ("term_bank_1.json", TERM_BANK)] {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(text.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let (content_path, index_path) = convert_dictionary(&path).unwrap();
+        let mut dict = load_dictionary_from_file(&content_path, &index_path).unwrap();
+
+        assert_eq!(dict.short_name().unwrap(), "Tiny");
+
+        let results = dict.lookup("食べる", false).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0][1].contains("<li>to live on</li>"));
+
+        let results = dict.lookup("ねこ", false).unwrap();
+        assert_eq!(results[0][0], "猫");
+        assert!(results[0][1].contains("<li>cat</li>"));
+    }
+}
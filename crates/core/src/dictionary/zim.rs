@@ -0,0 +1,213 @@
+//! Use the articles of a ZIM archive as definitions.
+//!
+//! The headwords are the titles of the articles: a word is looked up as typed, then
+//! capitalized, then as the path of an article. A fuzzy look up returns the articles whose
+//! titles start with the word.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::dictreader::DictReader;
+use super::errors::DictError;
+use super::indexing::{Entry, IndexReader};
+use super::Metadata;
+use crate::document::zim::ZimArchive;
+
+/// The number of articles returned by a fuzzy look up.
+const MAX_FUZZY_RESULTS: usize = 16;
+
+// The queries are kept as typed, since the titles are case sensitive.
+const ALL_CHARS: (&str, u64) = ("00-database-allchars", u64::MAX);
+const CASE_SENSITIVE: (&str, u64) = ("00-database-case-sensitive", u64::MAX - 1);
+const SHORT_NAME: (&str, u64) = ("00-database-short", u64::MAX - 2);
+
+lazy_static! {
+    static ref BODY: Regex = Regex::new(r"(?is)<body[^>]*>(.*)</body>").unwrap();
+    static ref UNWANTED_ELEMENTS: Regex =
+        Regex::new(r"(?is)<(script|style|noscript)\b.*?</(script|style|noscript)>").unwrap();
+    static ref UNWANTED_TAGS: Regex = Regex::new(r"(?i)</?(a|img|link|meta)\b[^>]*>").unwrap();
+}
+
+pub struct ZimIndex {
+    archive: Arc<ZimArchive>,
+}
+
+pub struct ZimContent {
+    archive: Arc<ZimArchive>,
+}
+
+fn capitalize(text: &str) -> Option<String> {
+    let mut chars = text.chars();
+    let first = chars.next().filter(|c| c.is_lowercase())?;
+    Some(first.to_uppercase().chain(chars).collect())
+}
+
+impl ZimIndex {
+    fn entry(&self, index: u32) -> Option<Entry> {
+        let (index, entry) = self.archive.resolve(index).ok()?;
+        if !self.archive.is_article(&entry) {
+            return None;
+        }
+        Some(Entry {
+            headword: entry.title().to_string(),
+            offset: index as u64,
+            size: 0,
+            original: None,
+        })
+    }
+}
+
+impl IndexReader for ZimIndex {
+    fn load_and_find(&mut self, headword: &str, fuzzy: bool, _metadata: &Metadata) -> Vec<Entry> {
+        self.find(headword, fuzzy)
+    }
+
+    fn find(&self, headword: &str, fuzzy: bool) -> Vec<Entry> {
+        if let Some((_, offset)) = [ALL_CHARS, CASE_SENSITIVE, SHORT_NAME]
+            .into_iter()
+            .find(|(name, _)| *name == headword)
+        {
+            return vec![Entry {
+                headword: headword.to_string(),
+                offset,
+                size: 0,
+                original: None,
+            }];
+        }
+
+        let headword = headword.trim();
+        if headword.is_empty() {
+            return Vec::new();
+        }
+        let capitalized = capitalize(headword);
+
+        if fuzzy {
+            let mut entries = self
+                .archive
+                .titles_with_prefix(headword, MAX_FUZZY_RESULTS)
+                .into_iter()
+                .chain(
+                    capitalized
+                        .iter()
+                        .flat_map(|text| self.archive.titles_with_prefix(text, MAX_FUZZY_RESULTS)),
+                )
+                .filter_map(|(index, _)| self.entry(index))
+                .collect::<Vec<Entry>>();
+            entries.dedup_by_key(|entry| entry.offset);
+            return entries;
+        }
+
+        let namespace = self.archive.content_namespace();
+        std::iter::once(headword.to_string())
+            .chain(capitalized)
+            .find_map(|text| {
+                self.archive
+                    .find_by_title(&text)
+                    .or_else(|| self.archive.find_by_url(namespace, &text.replace(' ', "_")))
+            })
+            .and_then(|index| self.entry(index))
+            .into_iter()
+            .collect()
+    }
+}
+
+impl DictReader for ZimContent {
+    fn fetch_definition(&mut self, start_offset: u64, _length: u64) -> Result<String, DictError> {
+        match start_offset {
+            offset if offset == SHORT_NAME.1 => {
+                let title = self.archive.metadata("Title").unwrap_or_default();
+                return Ok(format!("{}\n{}", SHORT_NAME.0, title));
+            }
+            offset if offset == ALL_CHARS.1 || offset == CASE_SENSITIVE.1 => {
+                return Ok(String::new());
+            }
+            _ => (),
+        }
+
+        let index = u32::try_from(start_offset)
+            .map_err(|_| DictError::WordNotFound(start_offset.to_string()))?;
+        let (_, entry) = self
+            .archive
+            .resolve(index)
+            .map_err(|e| DictError::InvalidFileFormat(e.to_string(), None))?;
+        let content = self
+            .archive
+            .content(&entry)
+            .map_err(|e| DictError::InvalidFileFormat(e.to_string(), None))?;
+
+        let html = String::from_utf8_lossy(&content);
+        let body = BODY
+            .captures(&html)
+            .and_then(|caps| caps.get(1))
+            .map_or(html.as_ref(), |body| body.as_str());
+        let body = UNWANTED_ELEMENTS.replace_all(body, "");
+        let body = UNWANTED_TAGS.replace_all(&body, "");
+
+        Ok(format!("<div class=\"article\">{}</div>", body.trim()))
+    }
+}
+
+/// Opens the given ZIM archive as a dictionary.
+pub fn load_dictionary<P: AsRef<Path>>(path: P) -> Result<super::Dictionary, DictError> {
+    let path = path.as_ref();
+    let archive = ZimArchive::open(path).map_err(|e| {
+        DictError::InvalidFileFormat(e.to_string(), Some(path.to_string_lossy().into_owned()))
+    })?;
+    let archive = Arc::new(archive);
+    let index = ZimIndex {
+        archive: Arc::clone(&archive),
+    };
+    let content = ZimContent { archive };
+    Ok(super::load_dictionary(Box::new(content), Box::new(index)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::zim::tests::write_archive;
+
+    #[test]
+    fn test_lookup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wiki.zim");
+        write_archive(
+            &path,
+            &[
+                (
+                    b'C',
+                    "Dune",
+                    "",
+                    "<html><body><p>A <a href=\"Novel\">novel</a>.</p>\
+                     <script>alert()</script></body></html>",
+                ),
+                (
+                    b'C',
+                    "Dune_Messiah",
+                    "Dune Messiah",
+                    "<html><body><p>A sequel.</p></body></html>",
+                ),
+                (b'M', "Title", "", "Wiki"),
+            ],
+        );
+
+        let mut dict = load_dictionary(&path).unwrap();
+        assert_eq!(dict.short_name().unwrap(), "Wiki");
+        assert_eq!(
+            dict.lookup("dune", false).unwrap(),
+            vec![[
+                "Dune".to_string(),
+                "<div class=\"article\"><p>A novel.</p></div>".to_string()
+            ]]
+        );
+        let titles = dict
+            .lookup("dune", true)
+            .unwrap()
+            .into_iter()
+            .map(|[head, _]| head)
+            .collect::<Vec<String>>();
+        assert_eq!(titles, vec!["Dune", "Dune Messiah"]);
+    }
+}
@@ -15,7 +15,7 @@ use crate::unit::pt_to_px;
 use anyhow::{format_err, Error};
 use fxhash::FxHashMap;
 use percent_encoding::percent_decode_str;
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs::{self, File};
 use std::io::{Cursor, Read, Seek};
 use std::path::{Path, PathBuf};
@@ -44,6 +44,7 @@ pub struct EpubDocument<R: Read + Seek> {
     spine: Vec<Chunk>,
     cache: FxHashMap<usize, Vec<Page>>,
     ignore_document_css: bool,
+    user_css: Option<String>,
 }
 
 /// Type alias for file-based EPUB documents (backward compatibility).
@@ -143,6 +144,7 @@ impl<R: Read + Seek> EpubDocument<R> {
             spine,
             cache: FxHashMap::default(),
             ignore_document_css: false,
+            user_css: None,
         })
     }
 
@@ -362,6 +364,142 @@ impl<R: Read + Seek> EpubDocument<R> {
         }
     }
 
+    fn manifest_path<F>(&self, test: F) -> Option<String>
+    where
+        F: Fn(&NodeRef) -> bool,
+    {
+        self.info
+            .root()
+            .find("manifest")
+            .and_then(|manifest| manifest.children().find(|child| test(child)))
+            .and_then(|entry| entry.attribute("href"))
+            .map(|href| {
+                self.parent
+                    .join(href)
+                    .normalize()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+    }
+
+    fn read_text(&mut self, name: &str) -> Option<String> {
+        let mut text = String::new();
+        let mut zf = self.archive.by_name(name).ok()?;
+        zf.read_to_string(&mut text).ok()?;
+        Some(text)
+    }
+
+    // Returns the labels and the URIs of the print pages, as listed by the navigation
+    // document, the NCX or the Adobe page map.
+    fn page_targets(&mut self) -> Vec<(String, String)> {
+        let spine_attribute = |name: &str| {
+            self.info
+                .root()
+                .find("spine")
+                .and_then(|spine| spine.attribute(name))
+                .map(String::from)
+        };
+        let toc_id = spine_attribute("toc");
+        let page_map_id = spine_attribute("page-map");
+
+        let nav_path = self.manifest_path(|child| {
+            child
+                .attribute("properties")
+                .iter()
+                .any(|props| props.split_whitespace().any(|prop| prop == "nav"))
+        });
+
+        let decode = |uri: &str| {
+            percent_decode_str(&decode_entities(uri))
+                .decode_utf8_lossy()
+                .into_owned()
+        };
+
+        let resolve =
+            |dir: &Path, uri: &str| dir.join(decode(uri)).normalize().to_str().map(String::from);
+
+        if let Some(name) = nav_path {
+            if let Some(text) = self.read_text(&name) {
+                let dir = Path::new(&name).parent().unwrap_or_else(|| Path::new(""));
+                let root = XmlParser::new(&text).parse();
+                let targets = root
+                    .root()
+                    .descendants()
+                    .find(|desc| {
+                        desc.tag_name() == Some("nav")
+                            && desc.attribute("epub:type") == Some("page-list")
+                    })
+                    .map(|nav| {
+                        nav.descendants()
+                            .filter(|desc| desc.tag_name() == Some("a"))
+                            .filter_map(|link| {
+                                let label = decode_entities(link.text().trim()).into_owned();
+                                let uri = resolve(dir, link.attribute("href")?)?;
+                                Some((label, uri))
+                            })
+                            .collect::<Vec<(String, String)>>()
+                    })
+                    .unwrap_or_default();
+                if !targets.is_empty() {
+                    return targets;
+                }
+            }
+        }
+
+        let ncx_path =
+            toc_id.and_then(|id| self.manifest_path(|child| child.attribute("id") == Some(&id)));
+
+        if let Some(name) = ncx_path {
+            if let Some(text) = self.read_text(&name) {
+                let dir = Path::new(&name).parent().unwrap_or_else(|| Path::new(""));
+                let root = XmlParser::new(&text).parse();
+                let targets = root
+                    .root()
+                    .find("pageList")
+                    .map(|list| {
+                        list.children()
+                            .filter(|child| child.tag_name() == Some("pageTarget"))
+                            .filter_map(|target| {
+                                let label = target
+                                    .find("navLabel")
+                                    .and_then(|label| label.find("text"))
+                                    .map(|text| decode_entities(text.text().trim()).into_owned())
+                                    .or_else(|| target.attribute("value").map(String::from))?;
+                                let uri = resolve(dir, target.find("content")?.attribute("src")?)?;
+                                Some((label, uri))
+                            })
+                            .collect::<Vec<(String, String)>>()
+                    })
+                    .unwrap_or_default();
+                if !targets.is_empty() {
+                    return targets;
+                }
+            }
+        }
+
+        let page_map_path = page_map_id
+            .and_then(|id| self.manifest_path(|child| child.attribute("id") == Some(&id)));
+
+        if let Some(name) = page_map_path {
+            if let Some(text) = self.read_text(&name) {
+                let dir = Path::new(&name).parent().unwrap_or_else(|| Path::new(""));
+                let root = XmlParser::new(&text).parse();
+                return root
+                    .root()
+                    .descendants()
+                    .filter(|desc| desc.tag_name() == Some("page"))
+                    .filter_map(|page| {
+                        let label = decode_entities(page.attribute("name")?).into_owned();
+                        let uri = resolve(dir, page.attribute("href")?)?;
+                        Some((label, uri))
+                    })
+                    .collect();
+            }
+        }
+
+        Vec::new()
+    }
+
     fn build_display_list(&mut self, index: usize, start_offset: usize) -> Vec<Page> {
         let mut text = String::new();
         let mut spine_dir = PathBuf::default();
@@ -421,6 +559,12 @@ impl<R: Read + Seek> EpubDocument<R> {
             stylesheet.append(&mut inner_css, true);
         }
 
+        // Appended last so its rules win over the publisher's.
+        if let Some(text) = self.user_css.as_ref() {
+            let mut css = CssParser::new(text).parse();
+            stylesheet.append(&mut css, true);
+        }
+
         let mut display_list = Vec::new();
 
         if let Some(body) = root.root().find("body") {
@@ -443,6 +587,7 @@ impl<R: Read + Seek> EpubDocument<R> {
                 )
                 .round() as i32,
                 text_align: self.engine.text_align,
+                font_features: self.engine.vertical.then(|| vec!["vert".to_string()]),
                 start_x: rect.min.x,
                 end_x: rect.max.x,
                 width: rect.max.x - rect.min.x,
@@ -820,6 +965,29 @@ impl<R: Read + Seek> Document for EpubDocument<R> {
         }
     }
 
+    fn page_list(&mut self) -> BTreeMap<usize, String> {
+        let mut cache = FxHashMap::default();
+        let mut page_list = BTreeMap::new();
+
+        for (label, uri) in self.page_targets() {
+            // Resolving a URI without a fragment would require laying out its chapter.
+            let location = if uri.contains('#') {
+                cache
+                    .get(&uri)
+                    .cloned()
+                    .or_else(|| self.resolve_link(&uri, &mut cache))
+            } else {
+                self.vertebra_coordinates_from_name(&uri)
+                    .map(|(_, start_offset)| start_offset)
+            };
+            if let Some(location) = location {
+                page_list.entry(location).or_insert(label);
+            }
+        }
+
+        page_list
+    }
+
     fn chapter<'a>(&mut self, offset: usize, toc: &'a [TocEntry]) -> Option<(&'a TocEntry, f32)> {
         let next_offset = self
             .resolve_location(Location::Next(offset))
@@ -994,7 +1162,7 @@ impl<R: Read + Seek> Document for EpubDocument<R> {
                             text, rect, offset, ..
                         }) => Some(BoundedText {
                             text: text.clone(),
-                            rect: (*rect).into(),
+                            rect: self.engine.page_rect(*rect).into(),
                             location: TextLocation::Dynamic(*offset),
                         }),
                         _ => None,
@@ -1030,7 +1198,7 @@ impl<R: Read + Seek> Document for EpubDocument<R> {
                             uri, rect, offset, ..
                         }) if uri.is_some() => Some(BoundedText {
                             text: uri.clone().unwrap(),
-                            rect: (*rect).into(),
+                            rect: self.engine.page_rect(*rect).into(),
                             location: TextLocation::Dynamic(*offset),
                         }),
                         _ => None,
@@ -1055,7 +1223,9 @@ impl<R: Read + Seek> Document for EpubDocument<R> {
                 display_list[page_index]
                     .iter()
                     .filter_map(|dc| match dc {
-                        DrawCommand::Image(ImageCommand { rect, .. }) => Some((*rect).into()),
+                        DrawCommand::Image(ImageCommand { rect, .. }) => {
+                            Some(self.engine.page_rect(*rect).into())
+                        }
                         _ => None,
                     })
                     .collect(),
@@ -1122,6 +1292,16 @@ impl<R: Read + Seek> Document for EpubDocument<R> {
         self.cache.clear();
     }
 
+    fn set_vertical_text(&mut self, vertical: bool) {
+        self.engine.set_vertical(vertical);
+        self.cache.clear();
+    }
+
+    fn set_user_stylesheet(&mut self, css: Option<String>) {
+        self.user_css = css;
+        self.cache.clear();
+    }
+
     fn title(&self) -> Option<String> {
         self.metadata("dc:title")
     }
@@ -1150,3 +1330,72 @@ impl<R: Read + Seek> Document for EpubDocument<R> {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::{SimpleFileOptions, ZipWriter};
+
+    fn epub_document(files: &[(&str, &str)]) -> EpubDocument<Cursor<Vec<u8>>> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in files {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        let archive = ZipArchive::new(writer.finish().unwrap()).unwrap();
+        EpubDocument::from_archive(archive).unwrap()
+    }
+
+    const CONTAINER: &str = r#"<container><rootfiles><rootfile full-path="OEBPS/content.opf"/></rootfiles></container>"#;
+    const CHAPTER: &str = r#"<html><body><p>One</p><span id="p2"/><p>Two</p></body></html>"#;
+
+    #[test]
+    fn test_page_list_from_navigation_document() {
+        let opf = r#"<package><manifest>
+<item id="nav" href="nav.xhtml" properties="nav"/>
+<item id="c1" href="text/c1.xhtml"/>
+</manifest><spine><itemref idref="c1"/></spine></package>"#;
+        let nav = r#"<html><body>
+<nav epub:type="toc"><ol><li><a href="text/c1.xhtml">One</a></li></ol></nav>
+<nav epub:type="page-list"><ol>
+<li><a href="text/c1.xhtml">1</a></li>
+<li><a href="text/c1.xhtml#p2">2</a></li>
+</ol></nav></body></html>"#;
+        let mut doc = epub_document(&[
+            ("META-INF/container.xml", CONTAINER),
+            ("OEBPS/content.opf", opf),
+            ("OEBPS/nav.xhtml", nav),
+            ("OEBPS/text/c1.xhtml", CHAPTER),
+        ]);
+
+        let page_list = doc.page_list();
+
+        assert_eq!(page_list.len(), 2);
+        assert_eq!(page_list.get(&0).map(String::as_str), Some("1"));
+        let (location, label) = page_list.iter().next_back().unwrap();
+        assert!(*location > 0);
+        assert_eq!(label, "2");
+    }
+
+    #[test]
+    fn test_page_list_from_page_map() {
+        let opf = r#"<package><manifest>
+<item id="map" href="page-map.xml"/>
+<item id="c1" href="c1.xhtml"/>
+</manifest><spine page-map="map"><itemref idref="c1"/></spine></package>"#;
+        let page_map = r#"<page-map><page name="iv" href="c1.xhtml"/><page name="v" href="c1.xhtml#p2"/></page-map>"#;
+        let mut doc = epub_document(&[
+            ("META-INF/container.xml", CONTAINER),
+            ("OEBPS/content.opf", opf),
+            ("OEBPS/page-map.xml", page_map),
+            ("OEBPS/c1.xhtml", CHAPTER),
+        ]);
+
+        let labels = doc.page_list().into_values().collect::<Vec<String>>();
+
+        assert_eq!(labels, vec!["iv".to_string(), "v".to_string()]);
+    }
+}
@@ -431,6 +431,47 @@ impl<'a> NodeRef<'a> {
     pub fn find_by_id(&self, id: &str) -> Option<Self> {
         self.descendants().find(|n| n.id() == Some(id))
     }
+
+    /// Serializes the node and its descendants.
+    pub fn to_xml(&self) -> String {
+        let mut buf = String::new();
+        self.write_xml(&mut buf);
+        buf
+    }
+
+    fn write_xml(&self, buf: &mut String) {
+        match self.node.data {
+            NodeData::Element(ElementData {
+                ref name,
+                ref qualified_name,
+                ref attributes,
+                ..
+            }) => {
+                let name = qualified_name.as_ref().unwrap_or(name);
+                buf.push('<');
+                buf.push_str(name);
+                for (key, value) in attributes {
+                    buf.push_str(&format!(" {}=\"{}\"", key, value.replace('"', "&quot;")));
+                }
+                if self.has_children() {
+                    buf.push('>');
+                    for child in self.children() {
+                        child.write_xml(buf);
+                    }
+                    buf.push_str(&format!("</{}>", name));
+                } else {
+                    buf.push_str("/>");
+                }
+            }
+            NodeData::Text(TextData { ref text, .. })
+            | NodeData::Whitespace(TextData { ref text, .. }) => buf.push_str(text),
+            _ => {
+                for child in self.children() {
+                    child.write_xml(buf);
+                }
+            }
+        }
+    }
 }
 
 impl<'a> NodeMut<'a> {
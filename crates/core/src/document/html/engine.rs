@@ -5,6 +5,7 @@ use super::layout::{Display, Float, ImageElement, ParagraphElement, TextAlign, T
 use super::layout::{DrawCommand, DrawState, FontKind, Fonts, ImageCommand, RootData, TextCommand};
 use super::layout::{ImageMaterial, InlineMaterial, StyleData, TextMaterial};
 use super::layout::{LineStats, ListStyleType, WordSpacing};
+use super::layout::{RubyElement, RubyMaterial};
 use super::layout::{EM_SPACE_RATIOS, FONT_SPACES, WORD_SPACE_RATIOS};
 use super::parse::{parse_color, parse_line_height, parse_list_style_type, parse_vertical_align};
 use super::parse::{parse_display, parse_edge, parse_float, parse_text_align, parse_text_indent};
@@ -15,7 +16,7 @@ use super::parse::{
 use super::parse::{parse_letter_spacing, parse_word_spacing};
 use super::style::{specified_values, StyleSheet};
 use super::xml::XmlExt;
-use crate::document::pdf::PdfOpener;
+use crate::document::pdf::{PdfDocument, PdfOpener};
 use crate::document::{Document, Location};
 use crate::font::{FontFamily, FontOpener};
 use crate::framebuffer::{Framebuffer, Pixmap};
@@ -27,18 +28,22 @@ use crate::settings::{
 use crate::settings::{HYPHEN_PENALTY, STRETCH_TOLERANCE};
 use crate::unit::{mm_to_px, pt_to_px};
 use anyhow::Error;
+use base64::prelude::{Engine as _, BASE64_STANDARD};
 use kl_hyphenate::{Hyphenator, Iter, Standard};
 use paragraph_breaker::{standard_fit, total_fit};
 use paragraph_breaker::{Breakpoint, Item as ParagraphItem, INFINITE_PENALTY};
-use percent_encoding::percent_decode_str;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, NON_ALPHANUMERIC};
 use septem::Roman;
 use std::convert::TryFrom;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use tracing::error;
 use xi_unicode::LineBreakIterator;
 
 const DEFAULT_DPI: u16 = 300;
 const DEFAULT_WIDTH: u32 = 1404;
 const DEFAULT_HEIGHT: u32 = 1872;
+// Size of the ruby annotations relatively to their base.
+const RUBY_FONT_SIZE_RATIO: f32 = 0.5;
 
 pub type Page = Vec<DrawCommand>;
 
@@ -66,6 +71,8 @@ pub struct Engine {
     pub dims: (u32, u32),
     // Device DPI.
     pub dpi: u16,
+    // Lay the text out in columns running from right to left.
+    pub vertical: bool,
 }
 
 impl Engine {
@@ -84,6 +91,7 @@ impl Engine {
             line_height,
             dims: (DEFAULT_WIDTH, DEFAULT_HEIGHT),
             dpi: DEFAULT_DPI,
+            vertical: false,
         }
     }
 
@@ -138,10 +146,34 @@ impl Engine {
         self.line_height = line_height;
     }
 
+    pub fn set_vertical(&mut self, vertical: bool) {
+        self.vertical = vertical;
+    }
+
+    // Vertical pages are laid out sideways and rotated clockwise when rendered.
     #[inline]
     pub fn rect(&self) -> Rectangle {
         let (width, height) = self.dims;
-        rect![0, 0, width as i32, height as i32]
+        if self.vertical {
+            rect![0, 0, height as i32, width as i32]
+        } else {
+            rect![0, 0, width as i32, height as i32]
+        }
+    }
+
+    /// Maps a rectangle of the layout to the rendered page.
+    pub fn page_rect(&self, rect: Rectangle) -> Rectangle {
+        if self.vertical {
+            let width = self.dims.0 as i32;
+            rect![
+                width - rect.max.y,
+                rect.min.x,
+                width - rect.min.y,
+                rect.max.x
+            ]
+        } else {
+            rect
+        }
     }
 
     pub fn build_display_list(
@@ -903,20 +935,41 @@ impl Engine {
                     markers.push(node.offset());
                 }
 
+                if name == "math" && attributes.get("display").map(String::as_str) == Some("block")
+                {
+                    style.display = Display::Block;
+                }
+
+                let image_path = match name.as_ref() {
+                    "img" => Some(
+                        attributes
+                            .get("src")
+                            .and_then(|src| resource_path(spine_dir, src))
+                            .unwrap_or_default(),
+                    ),
+                    "image" => Some(
+                        attributes
+                            .get("xlink:href")
+                            .and_then(|href| resource_path(spine_dir, href))
+                            .unwrap_or_default(),
+                    ),
+                    // Formulas are replaced by their fallback image, when provided.
+                    "math" => attributes
+                        .get("altimg")
+                        .and_then(|src| resource_path(spine_dir, src)),
+                    // Figures referencing external images are handled through their children.
+                    "svg" if !node.descendants().any(|n| n.tag_name() == Some("image")) => {
+                        Some(format!(
+                            "data:image/svg+xml,{}",
+                            utf8_percent_encode(&node.to_xml(), NON_ALPHANUMERIC)
+                        ))
+                    }
+                    _ => None,
+                };
+
                 match name.as_ref() {
-                    "img" | "image" => {
-                        let attr = if name == "img" { "src" } else { "xlink:href" };
-
-                        let path = attributes
-                            .get(attr)
-                            .and_then(|src| {
-                                spine_dir.join(src).normalize().to_str().map(|uri| {
-                                    percent_decode_str(&decode_entities(uri))
-                                        .decode_utf8_lossy()
-                                        .into_owned()
-                                })
-                            })
-                            .unwrap_or_default();
+                    _ if image_path.is_some() => {
+                        let path = image_path.unwrap_or_default();
 
                         style.float = props.get("float").and_then(|value| parse_float(value));
 
@@ -957,6 +1010,39 @@ impl Engine {
                         inlines.push(InlineMaterial::LineBreak);
                         return;
                     }
+                    "math" => {
+                        let text = attributes
+                            .get("alttext")
+                            .map(|text| decode_entities(text).into_owned())
+                            .unwrap_or_else(|| math_text(node));
+                        let is_block = style.display == Display::Block;
+                        if is_block {
+                            inlines.push(InlineMaterial::LineBreak);
+                        }
+                        inlines.push(InlineMaterial::Text(TextMaterial {
+                            offset: *offset,
+                            text,
+                            style,
+                        }));
+                        if is_block {
+                            inlines.push(InlineMaterial::LineBreak);
+                        }
+                        return;
+                    }
+                    "ruby" => {
+                        let mut base = (None, String::new());
+                        let mut annotation = String::new();
+                        gather_ruby_text(node, &mut base, &mut annotation);
+                        if let (Some(offset), false) = (base.0, annotation.trim().is_empty()) {
+                            inlines.push(InlineMaterial::Ruby(RubyMaterial {
+                                offset,
+                                base: base.1.trim().to_string(),
+                                annotation: annotation.trim().to_string(),
+                                style,
+                            }));
+                            return;
+                        }
+                    }
                     _ => {}
                 }
 
@@ -1039,21 +1125,22 @@ impl Engine {
                     let mut scale = 1.0;
                     let dpi = self.dpi;
 
-                    if let Ok(buf) = resource_fetcher.fetch(path) {
-                        if let Some(doc) =
-                            PdfOpener::new().and_then(|opener| opener.open_memory(path, &buf))
-                        {
-                            if let Some((w, h)) = doc.dims(0) {
-                                if width == 0 && height == 0 {
-                                    width = pt_to_px(w, dpi).round() as i32;
-                                    height = pt_to_px(h, dpi).round() as i32;
-                                } else if width != 0 {
-                                    height = (width as f32 * h / w).round() as i32;
-                                } else if height != 0 {
-                                    width = (height as f32 * w / h).round() as i32;
-                                }
-                                scale = width as f32 / w;
+                    if let Some(doc) = open_image(resource_fetcher, path) {
+                        if let Some((w, h)) = doc.dims(0) {
+                            if width == 0 && height == 0 {
+                                width = pt_to_px(w, dpi).round() as i32;
+                                height = pt_to_px(h, dpi).round() as i32;
+                            } else if width != 0 {
+                                height = (width as f32 * h / w).round() as i32;
+                            } else if height != 0 {
+                                width = (height as f32 * w / h).round() as i32;
                             }
+                            scale = width as f32 / w;
+                        }
+
+                        // Images stay upright once vertical pages are rotated.
+                        if self.vertical {
+                            std::mem::swap(&mut width, &mut height);
                         }
 
                         if width * height > 0 {
@@ -1080,6 +1167,25 @@ impl Engine {
                         }
                     }
                 }
+                InlineMaterial::Ruby(RubyMaterial {
+                    offset,
+                    base,
+                    annotation,
+                    style,
+                }) => {
+                    let font_size = (style.font_size * 64.0) as u32;
+                    let base = self.text_element(*offset, base, style, font_size);
+                    let annotation = self.text_element(
+                        *offset,
+                        annotation,
+                        style,
+                        (RUBY_FONT_SIZE_RATIO * font_size as f32) as u32,
+                    );
+                    items.push(ParagraphItem::Box {
+                        width: base.plan.width.max(annotation.plan.width),
+                        data: ParagraphElement::Ruby(RubyElement { base, annotation }),
+                    });
+                }
                 InlineMaterial::Text(TextMaterial {
                     offset,
                     text,
@@ -1805,6 +1911,60 @@ impl Engine {
                                     color: element.color,
                                 }));
                             }
+                            ParagraphElement::Ruby(RubyElement { base, annotation }) => {
+                                let pt = pt!(
+                                    position.x + (width - base.plan.width) / 2,
+                                    position.y - base.vertical_align
+                                );
+                                let rect = rect![
+                                    pt + pt!(0, -ascender),
+                                    pt + pt!(base.plan.width, -descender)
+                                ];
+                                // The annotation sits on top of the base.
+                                let annotation_pt = pt!(
+                                    position.x + (width - annotation.plan.width) / 2,
+                                    pt.y - ascender
+                                );
+                                let annotation_rect = rect![
+                                    annotation_pt + pt!(0, -ascender / 2),
+                                    annotation_pt + pt!(annotation.plan.width, 0)
+                                ];
+                                if let Some(pr) = page_rect.as_mut() {
+                                    pr.absorb(&rect);
+                                } else {
+                                    page_rect = Some(rect);
+                                }
+                                while let Some(offset) = markers.get(markers_index) {
+                                    if *offset < base.offset {
+                                        page.push(DrawCommand::Marker(
+                                            root_data.start_offset + *offset,
+                                        ));
+                                        markers_index += 1;
+                                    } else {
+                                        break;
+                                    }
+                                }
+                                let command = |element: &TextElement, position, rect| TextCommand {
+                                    offset: element.offset + root_data.start_offset,
+                                    position,
+                                    rect,
+                                    text: element.text.clone(),
+                                    plan: element.plan.clone(),
+                                    uri: element.uri.clone(),
+                                    font_kind: element.font_kind,
+                                    font_style: element.font_style,
+                                    font_weight: element.font_weight,
+                                    font_size: element.font_size,
+                                    color: element.color,
+                                };
+                                page.push(DrawCommand::Text(command(base, pt, rect)));
+                                // Only the base can be selected.
+                                page.push(DrawCommand::ExtraText(command(
+                                    annotation,
+                                    annotation_pt,
+                                    annotation_rect,
+                                )));
+                            }
                             ParagraphElement::Image(element) => {
                                 while let Some(offset) = markers.get(markers_index) {
                                     if *offset < element.offset {
@@ -2035,6 +2195,40 @@ impl Engine {
         display_list.push(page);
     }
 
+    fn text_element(
+        &mut self,
+        offset: usize,
+        text: &str,
+        style: &StyleData,
+        font_size: u32,
+    ) -> TextElement {
+        let mut plan = {
+            let font = self.fonts.as_mut().unwrap().get_mut(
+                style.font_kind,
+                style.font_style,
+                style.font_weight,
+            );
+            font.set_size(font_size, self.dpi);
+            font.plan(text, None, style.font_features.as_deref())
+        };
+        plan.space_out(style.letter_spacing);
+        TextElement {
+            offset,
+            language: style.language.clone(),
+            text: text.to_string(),
+            plan,
+            font_features: style.font_features.clone(),
+            font_kind: style.font_kind,
+            font_style: style.font_style,
+            font_weight: style.font_weight,
+            vertical_align: style.vertical_align,
+            letter_spacing: style.letter_spacing,
+            font_size,
+            color: style.color,
+            uri: style.uri.clone(),
+        }
+    }
+
     #[inline]
     fn box_from_chunk(
         &mut self,
@@ -2336,14 +2530,16 @@ impl Engine {
         samples: usize,
         resource_fetcher: &mut dyn ResourceFetcher,
     ) -> Option<Pixmap> {
-        let width = (self.dims.0 as f32 * scale_factor) as u32;
-        let height = (self.dims.1 as f32 * scale_factor) as u32;
+        let rect = self.rect();
+        let width = (rect.width() as f32 * scale_factor) as u32;
+        let height = (rect.height() as f32 * scale_factor) as u32;
         let mut fb = Pixmap::try_new(width, height, samples)?;
 
         for dc in page {
             match dc {
                 DrawCommand::Text(TextCommand {
                     position,
+                    text,
                     plan,
                     font_kind,
                     font_style,
@@ -2354,6 +2550,7 @@ impl Engine {
                 })
                 | DrawCommand::ExtraText(TextCommand {
                     position,
+                    text,
                     plan,
                     font_kind,
                     font_style,
@@ -2371,7 +2568,11 @@ impl Engine {
                     let position = Point::from(scale_factor * Vec2::from(*position));
                     let plan = plan.scale(scale_factor);
                     font.set_size(font_size, self.dpi);
-                    font.render(&mut fb, *color, &plan, position);
+                    if self.vertical {
+                        font.render_vertical(&mut fb, *color, &plan, text, position);
+                    } else {
+                        font.render(&mut fb, *color, &plan, position);
+                    }
                 }
                 DrawCommand::Image(ImageCommand {
                     position,
@@ -2379,14 +2580,15 @@ impl Engine {
                     scale,
                     ..
                 }) => {
-                    if let Ok(buf) = resource_fetcher.fetch(path) {
-                        if let Some((pixmap, _)) = PdfOpener::new()
-                            .and_then(|opener| opener.open_memory(path, &buf))
-                            .and_then(|mut doc| {
-                                doc.pixmap(Location::Exact(0), scale_factor * *scale, samples)
-                            })
-                        {
-                            let position = Point::from(scale_factor * Vec2::from(*position));
+                    if let Some((pixmap, _)) =
+                        open_image(resource_fetcher, path).and_then(|mut doc| {
+                            doc.pixmap(Location::Exact(0), scale_factor * *scale, samples)
+                        })
+                    {
+                        let position = Point::from(scale_factor * Vec2::from(*position));
+                        if self.vertical {
+                            fb.draw_pixmap(&pixmap.rotate(-1), position);
+                        } else {
                             fb.draw_pixmap(&pixmap, position);
                         }
                     }
@@ -2395,10 +2597,94 @@ impl Engine {
             }
         }
 
+        if self.vertical {
+            fb = fb.rotate(1);
+        }
+
         Some(fb)
     }
 }
 
+// Returns the path of a resource within the document, data URIs are returned as is.
+fn resource_path(spine_dir: &Path, uri: &str) -> Option<String> {
+    let uri = decode_entities(uri);
+    if uri.starts_with("data:") {
+        return Some(uri.into_owned());
+    }
+    spine_dir
+        .join(&*uri)
+        .normalize()
+        .to_str()
+        .map(|path| percent_decode_str(path).decode_utf8_lossy().into_owned())
+}
+
+/// Opens the image at the given path within the document, or encoded in the given data URI.
+fn open_image(resource_fetcher: &mut dyn ResourceFetcher, path: &str) -> Option<PdfDocument> {
+    let (magic, buf) = if let Some(data) = path.strip_prefix("data:") {
+        let (header, content) = data.split_once(',')?;
+        if let Some(mime_type) = header.strip_suffix(";base64") {
+            let buf = BASE64_STANDARD
+                .decode(content.trim())
+                .map_err(|e| error!("Can't decode inline image: {:#}.", e))
+                .ok()?;
+            (mime_type, buf)
+        } else {
+            (header, percent_decode_str(content).collect())
+        }
+    } else {
+        (path, resource_fetcher.fetch(path).ok()?)
+    };
+    PdfOpener::new().and_then(|opener| opener.open_memory(magic, &buf))
+}
+
+// Linearizes a formula, e.g. `x^2`, `x_i` or `a/b`.
+fn math_text(node: NodeRef) -> String {
+    let mut buf = String::new();
+    let parent = node.tag_name().unwrap_or_default();
+    let mut index = 0;
+    for child in node.children() {
+        match child.data() {
+            NodeData::Text(TextData { text, .. }) => buf.push_str(decode_entities(text).trim()),
+            NodeData::Element(ElementData { name, .. }) => {
+                if matches!(name.as_str(), "annotation" | "annotation-xml") {
+                    continue;
+                }
+                match (parent, index) {
+                    ("msup", 1) | ("msubsup", 2) => buf.push('^'),
+                    ("msub", 1) | ("msubsup", 1) => buf.push('_'),
+                    ("mfrac", 1) => buf.push('/'),
+                    ("mroot", 1) => buf.push_str("^1/"),
+                    _ => (),
+                }
+                if name == "msqrt" {
+                    buf.push('√');
+                }
+                buf.push_str(&math_text(child));
+                index += 1;
+            }
+            _ => (),
+        }
+    }
+    buf
+}
+
+fn gather_ruby_text(node: NodeRef, base: &mut (Option<usize>, String), annotation: &mut String) {
+    for child in node.children() {
+        match child.data() {
+            NodeData::Element(ElementData { name, .. }) => match name.as_ref() {
+                "rt" | "rtc" => annotation.push_str(&decode_entities(&child.text())),
+                "rp" => (),
+                _ => gather_ruby_text(child, base, annotation),
+            },
+            NodeData::Text(TextData { offset, text }) => {
+                base.0.get_or_insert(*offset);
+                base.1.push_str(&decode_entities(text));
+            }
+            _ => (),
+        }
+    }
+}
+
 fn format_list_prefix(kind: ListStyleType, index: usize) -> Option<String> {
     match kind {
         ListStyleType::None => None,
@@ -2467,3 +2753,49 @@ fn default_fonts() -> Result<Fonts, Error> {
     fonts.monospace.bold_italic.set_variations(&["wght=600"]);
     Ok(fonts)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::html::xml::XmlParser;
+
+    #[test]
+    fn test_gather_ruby_text() {
+        let text = "<ruby><rb>漢字</rb><rp>(</rp><rt>かんじ</rt><rp>)</rp></ruby>";
+        let root = XmlParser::new(text).parse();
+        let ruby = root.root().find("ruby").unwrap();
+        let mut base = (None, String::new());
+        let mut annotation = String::new();
+
+        gather_ruby_text(ruby, &mut base, &mut annotation);
+
+        assert_eq!(base.0, Some(10));
+        assert_eq!(base.1, "漢字");
+        assert_eq!(annotation, "かんじ");
+    }
+
+    #[test]
+    fn test_math_text() {
+        let text = "<math><msup><mi>x</mi><mn>2</mn></msup><mo>+</mo>\
+                    <mfrac><mi>a</mi><msqrt><mi>b</mi></msqrt></mfrac>\
+                    <annotation>x^2+a/sqrt(b)</annotation></math>";
+        let root = XmlParser::new(text).parse();
+        let math = root.root().find("math").unwrap();
+
+        assert_eq!(math_text(math), "x^2+a/√b");
+    }
+
+    #[test]
+    fn test_resource_path() {
+        let spine_dir = Path::new("OEBPS/Text");
+
+        assert_eq!(
+            resource_path(spine_dir, "../Images/a%20b.png").as_deref(),
+            Some("OEBPS/Images/a b.png")
+        );
+        assert_eq!(
+            resource_path(spine_dir, "data:image/png;base64,AAAA").as_deref(),
+            Some("data:image/png;base64,AAAA")
+        );
+    }
+}
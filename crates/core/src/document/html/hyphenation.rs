@@ -0,0 +1,232 @@
+//! Hyphenation dictionaries.
+//!
+//! The dictionary of a language is looked up in [`HYPHENATION_PATTERNS_DIR`], by order of
+//! preference:
+//!
+//! 1. `CODE.standard.bincode`: a precompiled dictionary.
+//! 2. `CODE.tex` or `hyph-CODE.tex`: a TeX pattern file, as distributed by the *hyph-utf8*
+//!    project, holding a `\patterns` group and an optional `\hyphenation` group.
+//! 3. `CODE.pat.txt`: one pattern per line, with the exceptions in `CODE.hyp.txt`.
+//!
+//! `CODE` is the code of the language, e.g. `en-us` or `de-1996`. The hyphenation bounds
+//! can be overridden with a `CODE.bounds` file.
+
+use anyhow::{format_err, Error};
+use atlatl::fst::{Builder, FST};
+use fxhash::FxHashMap;
+use kl_hyphenate::{Language, Load, Standard};
+use kl_hyphenate_commons::dictionary::{Exceptions, Patterns};
+use kl_hyphenate_commons::parse::Parse;
+use std::fs;
+use std::path::Path;
+use tracing::{error, warn};
+
+pub const HYPHENATION_PATTERNS_DIR: &str = "hyphenation-patterns";
+
+/// Loads the dictionary of the given language from `dir`.
+pub fn load_dictionary(dir: &Path, lang: Language) -> Option<Standard> {
+    let base = dir.join(lang.code());
+    let mut dictionary = Standard::from_path(lang, base.with_extension("standard.bincode"))
+        .ok()
+        .or_else(|| load_pattern_files(dir, lang))?;
+
+    if let Ok(pair) = fs::read_to_string(base.with_extension("bounds")) {
+        let bounds = pair
+            .trim_end()
+            .split(' ')
+            .filter_map(|s| s.parse().ok())
+            .collect::<Vec<usize>>();
+        if bounds.len() == 2 {
+            dictionary.minima = (bounds[0], bounds[1]);
+        }
+    }
+
+    Some(dictionary)
+}
+
+fn load_pattern_files(dir: &Path, lang: Language) -> Option<Standard> {
+    let code = lang.code();
+    let tex = [format!("{}.tex", code), format!("hyph-{}.tex", code)]
+        .iter()
+        .find_map(|name| fs::read_to_string(dir.join(name)).ok());
+
+    let result = if let Some(text) = tex {
+        parse_tex(lang, &text)
+    } else {
+        let patterns = fs::read_to_string(dir.join(format!("{}.pat.txt", code))).ok()?;
+        let exceptions =
+            fs::read_to_string(dir.join(format!("{}.hyp.txt", code))).unwrap_or_default();
+        build_dictionary(
+            lang,
+            patterns.split_whitespace(),
+            exceptions.split_whitespace(),
+        )
+    };
+
+    result
+        .map_err(|e| {
+            error!(
+                "Can't build the hyphenation dictionary for {}: {:#}.",
+                code, e
+            )
+        })
+        .ok()
+}
+
+/// Builds a dictionary from the content of a TeX pattern file.
+pub fn parse_tex(lang: Language, text: &str) -> Result<Standard, Error> {
+    let text = text
+        .lines()
+        .map(|line| line.split('%').next().unwrap_or_default())
+        .collect::<Vec<&str>>()
+        .join("\n");
+    let patterns =
+        tex_group(&text, "\\patterns").ok_or_else(|| format_err!("missing \\patterns group"))?;
+    let exceptions = tex_group(&text, "\\hyphenation").unwrap_or_default();
+
+    build_dictionary(
+        lang,
+        patterns.split_whitespace(),
+        exceptions.split_whitespace(),
+    )
+}
+
+fn tex_group<'a>(text: &'a str, command: &str) -> Option<&'a str> {
+    let start = text.find(command)? + command.len();
+    let rest = text[start..].trim_start().strip_prefix('{')?;
+    let end = rest.find('}')?;
+    Some(&rest[..end])
+}
+
+/// Builds a dictionary from Knuth–Liang patterns, e.g. `hy3ph`, and hyphenated
+/// exceptions, e.g. `as-so-ciate`.
+pub fn build_dictionary<'a, P, E>(
+    lang: Language,
+    patterns: P,
+    exceptions: E,
+) -> Result<Standard, Error>
+where
+    P: Iterator<Item = &'a str>,
+    E: Iterator<Item = &'a str>,
+{
+    let mut tally_ids = FxHashMap::default();
+    let mut tallies = Vec::new();
+    let mut pairs = Vec::new();
+
+    let mut skipped = 0;
+
+    // Patterns sharing the same tally point to a single copy of it.
+    for pattern in patterns {
+        let (key, tally) = Patterns::pair(pattern, |s| s.to_string());
+        let id = if let Some(id) = tally_ids.get(&tally) {
+            *id
+        } else if let Ok(id) = u16::try_from(tallies.len()) {
+            tally_ids.insert(tally.clone(), id);
+            tallies.push(tally);
+            id
+        } else {
+            // The automaton can't refer to more tallies.
+            skipped += 1;
+            continue;
+        };
+        pairs.push((key, id));
+    }
+
+    if skipped > 0 {
+        warn!(
+            "Skipped {} hyphenation patterns of {}: too many distinct tallies.",
+            skipped,
+            lang.code()
+        );
+    }
+
+    if pairs.is_empty() {
+        return Err(format_err!("no patterns"));
+    }
+
+    pairs.sort_by(|a, b| a.0.cmp(&b.0));
+    pairs.dedup_by(|a, b| a.0 == b.0);
+
+    let builder: Builder<u32, u16> = Builder::from_iter(pairs)?;
+    let automaton = FST::from_builder(&builder)?;
+
+    let exceptions = Exceptions(
+        exceptions
+            .map(|word| Exceptions::pair(word, |s| s.to_lowercase()))
+            .collect(),
+    );
+
+    Ok(Standard {
+        language: lang,
+        patterns: Patterns { tallies, automaton },
+        exceptions,
+        minima: lang.minima(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kl_hyphenate::Hyphenator;
+
+    const TEX_PATTERNS: &str = r"% A tiny pattern file.
+\patterns{ % Breaks before na.
+1na
+}
+\hyphenation{
+ta-ble
+}
+";
+
+    #[test]
+    fn test_parse_tex_patterns() {
+        let mut dictionary = parse_tex(Language::EnglishUS, TEX_PATTERNS).unwrap();
+        dictionary.minima = (1, 1);
+
+        assert_eq!(dictionary.opportunities("banana"), vec![2, 4]);
+        assert_eq!(dictionary.opportunities("table"), vec![2]);
+    }
+
+    #[test]
+    fn test_parse_tex_without_patterns() {
+        assert!(parse_tex(Language::EnglishUS, "\\hyphenation{ta-ble}").is_err());
+    }
+
+    #[test]
+    fn test_too_many_tallies() {
+        // Every pattern has its own tally, made of six digits between one and nine.
+        let patterns = (0..70_000u32)
+            .map(|mut n| {
+                let mut pattern = String::new();
+                for letter in "abcdef".chars() {
+                    pattern.push(char::from_digit(n % 9 + 1, 10).unwrap());
+                    pattern.push(letter);
+                    n /= 9;
+                }
+                pattern
+            })
+            .collect::<Vec<String>>();
+
+        let dictionary = build_dictionary(
+            Language::EnglishUS,
+            patterns.iter().map(String::as_str),
+            std::iter::empty(),
+        )
+        .unwrap();
+
+        assert_eq!(dictionary.patterns.tallies.len(), u16::MAX as usize + 1);
+    }
+
+    #[test]
+    fn test_load_plain_pattern_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("fr.pat.txt"), "1na\n").unwrap();
+        fs::write(dir.path().join("fr.bounds"), "1 1\n").unwrap();
+
+        let dictionary = load_dictionary(dir.path(), Language::French).unwrap();
+
+        assert_eq!(dictionary.minima, (1, 1));
+        assert_eq!(dictionary.opportunities("banana"), vec![2, 4]);
+        assert!(load_dictionary(dir.path(), Language::German1996).is_none());
+    }
+}
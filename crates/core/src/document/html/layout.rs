@@ -1,13 +1,13 @@
+use super::hyphenation::{load_dictionary, HYPHENATION_PATTERNS_DIR};
 use crate::color::Color;
 use crate::color::BLACK;
 use crate::font::{Font, FontFamily, RenderPlan};
 use crate::geom::{Edge, Point, Rectangle};
 pub use crate::metadata::TextAlign;
 use fxhash::FxHashMap;
-use kl_hyphenate::{Language, Load, Standard};
+use kl_hyphenate::{Language, Standard};
 use lazy_static::lazy_static;
 use std::fmt::Debug;
-use std::fs;
 use std::path::{Path, PathBuf};
 
 pub const DEFAULT_HYPH_LANG: &str = "en";
@@ -202,6 +202,7 @@ impl Default for StyleData {
 pub enum InlineMaterial {
     Text(TextMaterial),
     Image(ImageMaterial),
+    Ruby(RubyMaterial),
     Glue(GlueMaterial),
     Penalty(PenaltyMaterial),
     Box(i32),
@@ -212,7 +213,8 @@ impl InlineMaterial {
     pub fn offset(&self) -> Option<usize> {
         match self {
             InlineMaterial::Text(TextMaterial { offset, .. })
-            | InlineMaterial::Image(ImageMaterial { offset, .. }) => Some(*offset),
+            | InlineMaterial::Image(ImageMaterial { offset, .. })
+            | InlineMaterial::Ruby(RubyMaterial { offset, .. }) => Some(*offset),
             _ => None,
         }
     }
@@ -220,6 +222,7 @@ impl InlineMaterial {
     pub fn text(&self) -> Option<&str> {
         match self {
             InlineMaterial::Text(TextMaterial { ref text, .. }) => Some(text),
+            InlineMaterial::Ruby(RubyMaterial { ref base, .. }) => Some(base),
             _ => None,
         }
     }
@@ -232,6 +235,15 @@ pub struct TextMaterial {
     pub style: StyleData,
 }
 
+// A base text and its annotation, e.g. the reading of a kanji.
+#[derive(Debug, Clone)]
+pub struct RubyMaterial {
+    pub offset: usize,
+    pub base: String,
+    pub annotation: String,
+    pub style: StyleData,
+}
+
 #[derive(Debug, Clone)]
 pub struct ImageMaterial {
     pub offset: usize,
@@ -318,6 +330,7 @@ impl Fonts {
 pub enum ParagraphElement {
     Text(TextElement),
     Image(ImageElement),
+    Ruby(RubyElement),
     Nothing,
 }
 
@@ -338,6 +351,12 @@ pub struct TextElement {
     pub uri: Option<String>,
 }
 
+#[derive(Debug, Clone)]
+pub struct RubyElement {
+    pub base: TextElement,
+    pub annotation: TextElement,
+}
+
 #[derive(Debug, Clone)]
 pub struct ImageElement {
     pub offset: usize,
@@ -554,20 +573,8 @@ pub static ref HYPHENATION_PATTERNS: FxHashMap<Language, Standard> = {
         if map.contains_key(lang) {
             continue;
         }
-        let base = Path::new("hyphenation-patterns")
-                        .join(lang.code());
-        let path = base.with_extension("standard.bincode");
-        if let Ok(mut patterns) = Standard::from_path(*lang, path) {
-            let path = base.with_extension("bounds");
-            if let Ok(pair) = fs::read_to_string(path) {
-                let bounds = pair.trim_end().split(' ')
-                                 .filter_map(|s| s.parse().ok())
-                                 .collect::<Vec<usize>>();
-                if bounds.len() == 2 {
-                    patterns.minima.0 = bounds[0];
-                    patterns.minima.1 = bounds[1];
-                }
-            }
+        let dir = Path::new(HYPHENATION_PATTERNS_DIR);
+        if let Some(patterns) = load_dictionary(dir, *lang) {
             map.insert(*lang, patterns);
         }
     }
@@ -1,6 +1,7 @@
 pub mod css;
 pub mod dom;
 pub mod engine;
+pub mod hyphenation;
 pub mod layout;
 pub mod parse;
 pub mod style;
@@ -29,12 +30,14 @@ const USER_STYLESHEET: &str = "css/html-user.css";
 
 type UriCache = FxHashMap<String, usize>;
 
-pub struct HtmlDocument {
+/// An HTML document, whose resources are fetched relatively to its parent directory unless
+/// another fetcher is given.
+pub struct HtmlDocument<F: ResourceFetcher = PathBuf> {
     text: String,
     content: XmlTree,
     engine: Engine,
     pages: Vec<Page>,
-    parent: PathBuf,
+    parent: F,
     size: usize,
     viewer_stylesheet: PathBuf,
     user_stylesheet: PathBuf,
@@ -50,8 +53,8 @@ impl ResourceFetcher for PathBuf {
     }
 }
 
-unsafe impl Send for HtmlDocument {}
-unsafe impl Sync for HtmlDocument {}
+unsafe impl<F: ResourceFetcher + Send> Send for HtmlDocument<F> {}
+unsafe impl<F: ResourceFetcher + Sync> Sync for HtmlDocument<F> {}
 
 impl HtmlDocument {
     pub fn new<P: AsRef<Path>>(path: P) -> Result<HtmlDocument, Error> {
@@ -77,6 +80,12 @@ impl HtmlDocument {
     }
 
     pub fn new_from_memory(text: &str) -> HtmlDocument {
+        HtmlDocument::with_fetcher(text, PathBuf::default())
+    }
+}
+
+impl<F: ResourceFetcher + Send + Sync> HtmlDocument<F> {
+    pub fn with_fetcher(text: &str, fetcher: F) -> HtmlDocument<F> {
         let size = text.len();
         let mut content = XmlParser::new(text).parse();
         content.wrap_lost_inlines();
@@ -86,7 +95,7 @@ impl HtmlDocument {
             content,
             engine: Engine::new(),
             pages: Vec::new(),
-            parent: PathBuf::default(),
+            parent: fetcher,
             size,
             viewer_stylesheet: PathBuf::from(VIEWER_STYLESHEET),
             user_stylesheet: PathBuf::from(USER_STYLESHEET),
@@ -94,6 +103,10 @@ impl HtmlDocument {
         }
     }
 
+    pub fn fetcher_mut(&mut self) -> &mut F {
+        &mut self.parent
+    }
+
     pub fn update(&mut self, text: &str) {
         self.size = text.len();
         self.content = XmlParser::new(text).parse();
@@ -292,7 +305,7 @@ impl HtmlDocument {
     }
 }
 
-impl Document for HtmlDocument {
+impl<F: ResourceFetcher + Send + Sync> Document for HtmlDocument<F> {
     #[inline]
     fn dims(&self, _index: usize) -> Option<(f32, f32)> {
         Some((self.engine.dims.0 as f32, self.engine.dims.1 as f32))
@@ -203,6 +203,16 @@ mod tests {
         assert_eq!(child.map(|c| c.text()), Some(" ".to_string()));
     }
 
+    #[test]
+    fn test_to_xml() {
+        let text = r#"<svg:svg><svg:rect width='1"'/> <text>a &amp; b</text></svg:svg>"#;
+        let xml = XmlParser::new(text).parse();
+        assert_eq!(
+            xml.root().first_child().unwrap().to_xml(),
+            r#"<svg:svg><svg:rect width="1&quot;"/> <text>a &amp; b</text></svg:svg>"#
+        );
+    }
+
     #[test]
     fn test_central_space() {
         let text = "<a><b> </b></a>";
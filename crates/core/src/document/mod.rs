@@ -2,6 +2,8 @@ pub mod djvu;
 pub mod epub;
 pub mod html;
 pub mod pdf;
+pub mod transcode;
+pub mod zim;
 
 mod djvulibre_sys;
 mod mupdf_sys;
@@ -10,6 +12,7 @@ use self::djvu::DjvuOpener;
 use self::epub::EpubDocument;
 use self::html::HtmlDocument;
 use self::pdf::PdfOpener;
+use self::zim::ZimDocument;
 use crate::device::CURRENT_DEVICE;
 use crate::framebuffer::Pixmap;
 use crate::geom::{Boundary, CycleDir};
@@ -22,7 +25,7 @@ use nix::sys::statvfs;
 use nix::sys::sysinfo;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::env;
 use std::ffi::OsStr;
 use std::fs::{self, File};
@@ -117,6 +120,23 @@ pub trait Document: Send + Sync {
     fn set_stretch_tolerance(&mut self, stretch_tolerance: f32);
     fn set_ignore_document_css(&mut self, ignore: bool);
 
+    /// Sets a stylesheet applied after the document's own stylesheets.
+    fn set_user_stylesheet(&mut self, _css: Option<String>) {}
+
+    /// Lays the text out in columns, from top to bottom and right to left.
+    fn set_vertical_text(&mut self, _vertical: bool) {}
+
+    /// Returns the labels of the print pages, keyed by location.
+    fn page_list(&mut self) -> BTreeMap<usize, String> {
+        BTreeMap::new()
+    }
+
+    /// Returns the entries whose titles start with the given text, for the documents made of
+    /// many articles.
+    fn search_titles(&mut self, _text: &str) -> Option<Vec<TocEntry>> {
+        None
+    }
+
     fn title(&self) -> Option<String>;
     fn author(&self) -> Option<String>;
     fn metadata(&self, key: &str) -> Option<String>;
@@ -172,6 +192,24 @@ pub trait Document: Send + Sync {
     }
 }
 
+/// Returns the file names of the stylesheets found in `dir`.
+pub fn stylesheet_names<P: AsRef<Path>>(dir: P) -> BTreeSet<String> {
+    fs::read_dir(dir.as_ref())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .and_then(OsStr::to_str)
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("css"))
+        })
+        .filter_map(|path| path.file_name().and_then(OsStr::to_str).map(String::from))
+        .collect()
+}
+
 pub fn file_kind<P: AsRef<Path>>(path: P) -> Option<String> {
     path.as_ref()
         .extension()
@@ -195,6 +233,8 @@ pub fn guess_kind<P: AsRef<Path>>(path: P) -> Result<&'static str, Error> {
         return Ok("pdf");
     } else if &magic == b"AT&T" {
         return Ok("djvu");
+    } else if &magic == b"ZIM\x04" {
+        return Ok("zim");
     }
 
     Err(format_err!("Unknown file type"))
@@ -244,6 +284,10 @@ pub fn open<P: AsRef<Path>>(path: P) -> Option<Box<dyn Document>> {
             .map_err(|e| error!("{}: {:#}.", path.as_ref().display(), e))
             .map(|d| Box::new(d) as Box<dyn Document>)
             .ok(),
+        "zim" => ZimDocument::new(&path)
+            .map_err(|e| error!("{}: {:#}.", path.as_ref().display(), e))
+            .map(|d| Box::new(d) as Box<dyn Document>)
+            .ok(),
         "djvu" | "djv" => {
             DjvuOpener::new().and_then(|o| o.open(path).map(|d| Box::new(d) as Box<dyn Document>))
         }
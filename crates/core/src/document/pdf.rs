@@ -1,6 +1,7 @@
 use super::mupdf_sys::*;
 
-use super::{chapter, chapter_relative};
+use super::transcode::{self, TRANSCODED_KINDS};
+use super::{chapter, chapter_relative, file_kind};
 use super::{BoundedText, Document, Location, TextLocation, TocEntry};
 use crate::framebuffer::Pixmap;
 use crate::geom::{Boundary, CycleDir};
@@ -60,6 +61,12 @@ impl PdfOpener {
     }
 
     pub fn open<P: AsRef<Path>>(&self, path: P) -> Option<PdfDocument> {
+        if file_kind(path.as_ref()).is_some_and(|k| TRANSCODED_KINDS.contains(&k.as_str())) {
+            let buf = fs::read(path.as_ref())
+                .map_err(|e| error!("Can't read {}: {:#}.", path.as_ref().display(), e))
+                .ok()?;
+            return self.open_memory("image/png", &buf);
+        }
         unsafe {
             let c_path = CString::new(path.as_ref().as_os_str().as_bytes()).unwrap();
             let doc = mp_open_document((self.0).0, c_path.as_ptr());
@@ -76,6 +83,12 @@ impl PdfOpener {
 
     // *magic* is a filename or a MIME type.
     pub fn open_memory(&self, magic: &str, buf: &[u8]) -> Option<PdfDocument> {
+        if transcode::image_format(buf).is_some() {
+            let png = transcode::to_png(buf)
+                .map_err(|e| error!("Can't transcode image: {:#}.", e))
+                .ok()?;
+            return self.open_memory("image/png", &png);
+        }
         unsafe {
             let stream = fz_open_memory(
                 (self.0).0,
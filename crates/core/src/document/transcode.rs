@@ -0,0 +1,263 @@
+//! Decoding the WebP and AVIF images, which MuPDF can't read.
+//!
+//! The images are converted to PNG before being handed to MuPDF, so that they go through the
+//! same layout, scaling and dithering as the other images. The transparent pixels are composed
+//! over white.
+
+use anyhow::{format_err, Context, Error};
+use image_webp::WebPDecoder;
+use re_rav1d::dav1d::{self, Decoder, PixelLayout, PlanarImageComponent, Settings};
+use re_rav1d::pixel::{MatrixCoefficients, YUVRange};
+use std::io::Cursor;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ImageFormat {
+    WebP,
+    Avif,
+}
+
+/// Recognizes the formats that need to be transcoded from the first bytes of the file.
+pub fn image_format(buf: &[u8]) -> Option<ImageFormat> {
+    if buf.len() < 16 {
+        return None;
+    }
+    if &buf[0..4] == b"RIFF" && &buf[8..12] == b"WEBP" {
+        return Some(ImageFormat::WebP);
+    }
+    if &buf[4..8] == b"ftyp" {
+        // The major brand, followed by the compatible brands, after the minor version.
+        let size = (u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]) as usize).min(buf.len());
+        let brands = std::iter::once(&buf[8..12])
+            .chain(buf.get(16..size).unwrap_or_default().chunks_exact(4));
+        for brand in brands {
+            if brand == b"avif" || brand == b"avis" {
+                return Some(ImageFormat::Avif);
+            }
+        }
+    }
+    None
+}
+
+/// The extensions of the files that are transcoded.
+pub const TRANSCODED_KINDS: [&str; 2] = ["webp", "avif"];
+
+/// Decodes the WebP or AVIF image and encodes it as PNG.
+pub fn to_png(buf: &[u8]) -> Result<Vec<u8>, Error> {
+    let (width, height, rgb) = match image_format(buf) {
+        Some(ImageFormat::WebP) => decode_webp(buf)?,
+        Some(ImageFormat::Avif) => decode_avif(buf)?,
+        None => return Err(format_err!("unknown image format")),
+    };
+
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&rgb)?;
+    writer.finish()?;
+    Ok(png)
+}
+
+// Blends the color over white.
+fn over_white(color: u8, alpha: u8) -> u8 {
+    ((color as u32 * alpha as u32 + 255 * (255 - alpha as u32)) / 255) as u8
+}
+
+fn decode_webp(buf: &[u8]) -> Result<(u32, u32, Vec<u8>), Error> {
+    let mut decoder = WebPDecoder::new(Cursor::new(buf)).context("can't read WebP header")?;
+    let (width, height) = decoder.dimensions();
+    let size = decoder
+        .output_buffer_size()
+        .ok_or_else(|| format_err!("image too large"))?;
+    let mut data = vec![0; size];
+    decoder
+        .read_image(&mut data)
+        .context("can't decode WebP image")?;
+
+    if !decoder.has_alpha() {
+        return Ok((width, height, data));
+    }
+
+    let rgb = data
+        .chunks_exact(4)
+        .flat_map(|px| [0, 1, 2].map(|i| over_white(px[i], px[3])))
+        .collect();
+    Ok((width, height, rgb))
+}
+
+fn decode_av1(data: &[u8]) -> Result<dav1d::Picture, Error> {
+    let mut settings = Settings::new();
+    settings.set_n_threads(1);
+    settings.set_max_frame_delay(1);
+    let mut decoder = Decoder::with_settings(&settings)
+        .map_err(|e| format_err!("can't create AV1 decoder: {}", e))?;
+
+    match decoder.send_data(data.to_vec(), None, None, None) {
+        Ok(()) => (),
+        Err(e) if e.is_again() => (),
+        Err(e) => return Err(format_err!("can't send AV1 data: {}", e)),
+    }
+
+    loop {
+        match decoder.get_picture() {
+            Ok(picture) => return Ok(picture),
+            Err(e) if e.is_again() => match decoder.send_pending_data() {
+                Ok(()) => (),
+                Err(e) if e.is_again() => (),
+                Err(e) => return Err(format_err!("can't send AV1 data: {}", e)),
+            },
+            Err(e) => return Err(format_err!("can't decode AV1 image: {}", e)),
+        }
+    }
+}
+
+// The samples of one plane, reduced to eight bits.
+struct Samples {
+    plane: dav1d::Plane,
+    stride: usize,
+    shift: usize,
+    wide: bool,
+}
+
+impl Samples {
+    fn new(picture: &dav1d::Picture, component: PlanarImageComponent) -> Samples {
+        let depth = picture.bit_depth();
+        Samples {
+            plane: picture.plane(component),
+            stride: picture.stride(component) as usize,
+            shift: depth.saturating_sub(8),
+            wide: depth > 8,
+        }
+    }
+
+    fn get(&self, x: usize, y: usize) -> u8 {
+        if self.wide {
+            let i = y * self.stride + 2 * x;
+            (u16::from_le_bytes([self.plane[i], self.plane[i + 1]]) >> self.shift) as u8
+        } else {
+            self.plane[y * self.stride + x]
+        }
+    }
+}
+
+fn decode_avif(buf: &[u8]) -> Result<(u32, u32, Vec<u8>), Error> {
+    let avif = avif_parse::read_avif(&mut Cursor::new(buf)).context("can't parse AVIF file")?;
+    let color = decode_av1(&avif.primary_item)?;
+    let alpha = avif
+        .alpha_item
+        .as_ref()
+        .map(|item| decode_av1(item))
+        .transpose()?;
+
+    let (width, height) = (color.width() as usize, color.height() as usize);
+    let layout = color.pixel_layout();
+    let (sx, sy) = match layout {
+        PixelLayout::I420 => (1, 1),
+        PixelLayout::I422 => (1, 0),
+        PixelLayout::I400 | PixelLayout::I444 => (0, 0),
+    };
+    let full_range = color.color_range() == YUVRange::Full;
+    // The red and blue luma coefficients.
+    let (kr, kb) = match color.matrix_coefficients() {
+        MatrixCoefficients::BT709 => (0.2126, 0.0722),
+        MatrixCoefficients::BT2020NonConstantLuminance
+        | MatrixCoefficients::BT2020ConstantLuminance => (0.2627, 0.0593),
+        _ => (0.299, 0.114),
+    };
+    let kg = 1.0 - kr - kb;
+    let identity = color.matrix_coefficients() == MatrixCoefficients::Identity;
+
+    let y_plane = Samples::new(&color, PlanarImageComponent::Y);
+    let uv_planes = (layout != PixelLayout::I400).then(|| {
+        (
+            Samples::new(&color, PlanarImageComponent::U),
+            Samples::new(&color, PlanarImageComponent::V),
+        )
+    });
+    let a_plane = alpha
+        .as_ref()
+        .map(|a| (Samples::new(a, PlanarImageComponent::Y), a.color_range()));
+
+    let mut rgb = Vec::with_capacity(3 * width * height);
+    for y in 0..height {
+        for x in 0..width {
+            let mut luma = y_plane.get(x, y) as f32;
+            if !full_range {
+                luma = (luma - 16.0) * 255.0 / 219.0;
+            }
+            let mut pixel = if let Some((u_plane, v_plane)) = uv_planes.as_ref() {
+                let (mut cb, mut cr) = (
+                    u_plane.get(x >> sx, y >> sy) as f32,
+                    v_plane.get(x >> sx, y >> sy) as f32,
+                );
+                if identity {
+                    // The planes hold the green, blue and red components.
+                    [cr, luma, cb]
+                } else {
+                    if full_range {
+                        cb -= 128.0;
+                        cr -= 128.0;
+                    } else {
+                        cb = (cb - 128.0) * 255.0 / 224.0;
+                        cr = (cr - 128.0) * 255.0 / 224.0;
+                    }
+                    let r = luma + 2.0 * (1.0 - kr) * cr;
+                    let b = luma + 2.0 * (1.0 - kb) * cb;
+                    let g = (luma - kr * r - kb * b) / kg;
+                    [r, g, b]
+                }
+            } else {
+                [luma; 3]
+            }
+            .map(|c| c.round().clamp(0.0, 255.0) as u8);
+
+            if let Some((a_plane, range)) = a_plane.as_ref() {
+                let mut a = a_plane.get(x, y) as f32;
+                if *range == YUVRange::Limited {
+                    a = (a - 16.0) * 255.0 / 219.0;
+                }
+                let a = a.round().clamp(0.0, 255.0) as u8;
+                pixel = pixel.map(|c| over_white(c, a));
+            }
+
+            rgb.extend_from_slice(&pixel);
+        }
+    }
+
+    Ok((width as u32, height as u32, rgb))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image_webp::{ColorType, WebPEncoder};
+
+    #[test]
+    fn test_avif_brands_are_recognized() {
+        let mut header = Vec::new();
+        header.extend_from_slice(&24u32.to_be_bytes());
+        header.extend_from_slice(b"ftypmif1\0\0\0\0mif1avif");
+        assert_eq!(image_format(&header), Some(ImageFormat::Avif));
+        header[20..24].copy_from_slice(b"heic");
+        assert_eq!(image_format(&header), None);
+    }
+
+    #[test]
+    fn test_webp_is_transcoded_to_png() {
+        // A red pixel, and a transparent one.
+        let pixels = [255, 0, 0, 255, 0, 0, 0, 0];
+        let mut webp = Vec::new();
+        WebPEncoder::new(&mut webp)
+            .encode(&pixels, 2, 1, ColorType::Rgba8)
+            .unwrap();
+        assert_eq!(image_format(&webp), Some(ImageFormat::WebP));
+
+        let png = to_png(&webp).unwrap();
+        let mut reader = png::Decoder::new(Cursor::new(png)).read_info().unwrap();
+        let mut data = vec![0; reader.output_buffer_size().unwrap()];
+        let info = reader.next_frame(&mut data).unwrap();
+        assert_eq!((info.width, info.height), (2, 1));
+        assert_eq!(&data[..6], &[255, 0, 0, 255, 255, 255]);
+    }
+}
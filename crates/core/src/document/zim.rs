@@ -0,0 +1,1001 @@
+//! ZIM archives, the format of the offline copies of Wikipedia and other wikis.
+//!
+//! An archive holds a directory of entries, sorted by path and by title, and clusters of
+//! compressed blobs. Each article is shown on its own: the locations of a document are made
+//! of the position of an article among the ones opened so far, and of an offset within it.
+
+use super::html::engine::ResourceFetcher;
+use super::html::HtmlDocument;
+use super::{BoundedText, Document, Location, TextLocation, TocEntry};
+use crate::framebuffer::Pixmap;
+use crate::geom::{Boundary, CycleDir};
+use crate::metadata::TextAlign;
+use anyhow::{format_err, Error};
+use percent_encoding::percent_decode_str;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::Read;
+use std::os::unix::fs::FileExt;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tracing::error;
+
+const MAGIC_NUMBER: u32 = 72_173_914;
+const HEADER_SIZE: usize = 80;
+const NO_PAGE: u32 = u32::MAX;
+const REDIRECT: u16 = 0xffff;
+const MAX_REDIRECTS: usize = 8;
+
+/// The number of decompressed clusters kept in memory.
+const CLUSTER_CACHE_SIZE: usize = 4;
+/// The size above which a cluster, compressed or not, is deemed corrupt; the writers make them
+/// of a few megabytes.
+const MAX_CLUSTER_SIZE: u64 = 1 << 26;
+
+/// The range of locations reserved for each opened article.
+const ARTICLE_STRIDE: usize = 1 << 23;
+/// The number of articles a document can hold, so that its locations fit in 32 bits.
+const MAX_ARTICLES: usize = 255;
+
+/// The number of titles returned by a search.
+const MAX_SEARCH_RESULTS: usize = 64;
+
+#[derive(Debug, Clone)]
+pub enum Target {
+    Content {
+        mime_type: u16,
+        cluster: u32,
+        blob: u32,
+    },
+    Redirect(u32),
+    /// The deprecated link targets and deleted entries.
+    Other,
+}
+
+#[derive(Debug, Clone)]
+pub struct DirEntry {
+    pub namespace: u8,
+    pub url: String,
+    pub title: String,
+    pub target: Target,
+}
+
+impl DirEntry {
+    /// The title, which defaults to the URL.
+    pub fn title(&self) -> &str {
+        if self.title.is_empty() {
+            &self.url
+        } else {
+            &self.title
+        }
+    }
+}
+
+struct Cluster {
+    offsets: Vec<usize>,
+    data: Vec<u8>,
+}
+
+pub struct ZimArchive {
+    file: File,
+    major_version: u16,
+    minor_version: u16,
+    entry_count: u32,
+    cluster_count: u32,
+    url_ptr_pos: u64,
+    title_ptr_pos: u64,
+    cluster_ptr_pos: u64,
+    checksum_pos: u64,
+    len: u64,
+    main_page: Option<u32>,
+    mime_types: Vec<String>,
+    clusters: Mutex<VecDeque<(u32, Arc<Cluster>)>>,
+}
+
+fn u16_at(buf: &[u8], pos: usize) -> u16 {
+    u16::from_le_bytes([buf[pos], buf[pos + 1]])
+}
+
+fn u32_at(buf: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes(buf[pos..pos + 4].try_into().unwrap())
+}
+
+fn u64_at(buf: &[u8], pos: usize) -> u64 {
+    u64::from_le_bytes(buf[pos..pos + 8].try_into().unwrap())
+}
+
+impl ZimArchive {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<ZimArchive, Error> {
+        let file = File::open(path.as_ref())?;
+        let len = file.metadata()?.len();
+        let mut header = [0; HEADER_SIZE];
+        file.read_exact_at(&mut header, 0)?;
+
+        if u32_at(&header, 0) != MAGIC_NUMBER {
+            return Err(format_err!("not a ZIM archive"));
+        }
+
+        let main_page = u32_at(&header, 64);
+        let mime_list_pos = u64_at(&header, 56);
+        let url_ptr_pos = u64_at(&header, 32);
+
+        // The MIME types are null-terminated strings, followed by an empty string.
+        let mut mime_list =
+            vec![0; url_ptr_pos.saturating_sub(mime_list_pos).min(1 << 16) as usize];
+        file.read_exact_at(&mut mime_list, mime_list_pos)?;
+        let mime_types = mime_list
+            .split(|&byte| byte == 0)
+            .take_while(|name| !name.is_empty())
+            .map(|name| String::from_utf8_lossy(name).into_owned())
+            .collect();
+
+        Ok(ZimArchive {
+            file,
+            major_version: u16_at(&header, 4),
+            minor_version: u16_at(&header, 6),
+            entry_count: u32_at(&header, 24),
+            cluster_count: u32_at(&header, 28),
+            url_ptr_pos,
+            title_ptr_pos: u64_at(&header, 40),
+            cluster_ptr_pos: u64_at(&header, 48),
+            checksum_pos: u64_at(&header, 72),
+            len,
+            main_page: (main_page != NO_PAGE).then_some(main_page),
+            mime_types,
+            clusters: Mutex::new(VecDeque::new()),
+        })
+    }
+
+    pub fn entry_count(&self) -> u32 {
+        self.entry_count
+    }
+
+    pub fn main_page(&self) -> Option<u32> {
+        self.main_page
+    }
+
+    /// Since version 6.1, the articles and their resources share the `C` namespace, and their
+    /// paths don't start with a namespace.
+    fn has_namespaced_paths(&self) -> bool {
+        self.major_version < 6 || (self.major_version == 6 && self.minor_version == 0)
+    }
+
+    /// The namespace of the articles.
+    pub fn content_namespace(&self) -> u8 {
+        if self.has_namespaced_paths() {
+            b'A'
+        } else {
+            b'C'
+        }
+    }
+
+    fn read_u32(&self, pos: u64) -> Result<u32, Error> {
+        let mut buf = [0; 4];
+        self.file.read_exact_at(&mut buf, pos)?;
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    fn read_u64(&self, pos: u64) -> Result<u64, Error> {
+        let mut buf = [0; 8];
+        self.file.read_exact_at(&mut buf, pos)?;
+        Ok(u64::from_le_bytes(buf))
+    }
+
+    /// Returns the entry at the given position of the list sorted by path.
+    pub fn entry(&self, index: u32) -> Result<DirEntry, Error> {
+        if index >= self.entry_count {
+            return Err(format_err!("no entry {}", index));
+        }
+
+        let pos = self.read_u64(table_pos(self.url_ptr_pos, index as u64, 8)?)?;
+        let mut buf = vec![0; 256];
+        loop {
+            let count = self.file.read_at(&mut buf, pos)?;
+            buf.truncate(count);
+            if count < 12 {
+                return Err(format_err!("truncated entry {}", index));
+            }
+            let mime_type = u16_at(&buf, 0);
+            let start = if mime_type == REDIRECT { 12 } else { 16 };
+            let strings = buf.get(start..).unwrap_or_default();
+            let mut parts = strings.splitn(3, |&byte| byte == 0);
+            if let (Some(url), Some(title), Some(_)) = (parts.next(), parts.next(), parts.next()) {
+                let target = match mime_type {
+                    REDIRECT => Target::Redirect(u32_at(&buf, 8)),
+                    0xfffd | 0xfffe => Target::Other,
+                    _ => Target::Content {
+                        mime_type,
+                        cluster: u32_at(&buf, 8),
+                        blob: u32_at(&buf, 12),
+                    },
+                };
+                return Ok(DirEntry {
+                    namespace: buf[3],
+                    url: String::from_utf8_lossy(url).into_owned(),
+                    title: String::from_utf8_lossy(title).into_owned(),
+                    target,
+                });
+            }
+            if count < buf.capacity() || buf.len() >= 1 << 16 {
+                return Err(format_err!("truncated entry {}", index));
+            }
+            buf = vec![0; 2 * buf.capacity()];
+        }
+    }
+
+    /// Follows the redirections of the given entry.
+    pub fn resolve(&self, mut index: u32) -> Result<(u32, DirEntry), Error> {
+        for _ in 0..MAX_REDIRECTS {
+            let entry = self.entry(index)?;
+            match entry.target {
+                Target::Redirect(target) => index = target,
+                _ => return Ok((index, entry)),
+            }
+        }
+        Err(format_err!("too many redirections from {}", index))
+    }
+
+    pub fn mime_type(&self, entry: &DirEntry) -> Option<&str> {
+        match entry.target {
+            Target::Content { mime_type, .. } => {
+                self.mime_types.get(mime_type as usize).map(String::as_str)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn is_article(&self, entry: &DirEntry) -> bool {
+        entry.namespace == self.content_namespace()
+            && self
+                .mime_type(entry)
+                .is_some_and(|mime_type| mime_type.starts_with("text/html"))
+    }
+
+    /// Returns the content of the given entry.
+    pub fn content(&self, entry: &DirEntry) -> Result<Vec<u8>, Error> {
+        match entry.target {
+            Target::Content { cluster, blob, .. } => self.blob(cluster, blob),
+            _ => Err(format_err!("{} has no content", entry.url)),
+        }
+    }
+
+    fn blob(&self, cluster_index: u32, blob_index: u32) -> Result<Vec<u8>, Error> {
+        let cluster = self.cluster(cluster_index)?;
+        let index = blob_index as usize;
+        if index + 1 >= cluster.offsets.len() {
+            return Err(format_err!(
+                "no blob {} in cluster {}",
+                blob_index,
+                cluster_index
+            ));
+        }
+        let (start, end) = (cluster.offsets[index], cluster.offsets[index + 1]);
+        cluster
+            .data
+            .get(start..end)
+            .map(<[u8]>::to_vec)
+            .ok_or_else(|| format_err!("invalid blob {} in cluster {}", blob_index, cluster_index))
+    }
+
+    fn cluster(&self, index: u32) -> Result<Arc<Cluster>, Error> {
+        if let Ok(cache) = self.clusters.lock() {
+            if let Some((_, cluster)) = cache.iter().find(|(other, _)| *other == index) {
+                return Ok(Arc::clone(cluster));
+            }
+        }
+
+        if index >= self.cluster_count {
+            return Err(format_err!("no cluster {}", index));
+        }
+        let start = self.read_u64(table_pos(self.cluster_ptr_pos, index as u64, 8)?)?;
+        let end = if index + 1 < self.cluster_count {
+            self.read_u64(table_pos(self.cluster_ptr_pos, index as u64 + 1, 8)?)?
+        } else {
+            self.checksum_pos
+        };
+        let end = end.min(self.len);
+        if end < start || end - start > MAX_CLUSTER_SIZE {
+            return Err(format_err!("invalid cluster {}", index));
+        }
+        let mut raw = vec![0; (end - start) as usize];
+        self.file.read_exact_at(&mut raw, start)?;
+        let cluster = Arc::new(parse_cluster(&raw)?);
+
+        if let Ok(mut cache) = self.clusters.lock() {
+            if cache.len() >= CLUSTER_CACHE_SIZE {
+                cache.pop_back();
+            }
+            cache.push_front((index, Arc::clone(&cluster)));
+        }
+
+        Ok(cluster)
+    }
+
+    /// Returns the position, in the list sorted by path, of the entry at the given path.
+    pub fn find_by_url(&self, namespace: u8, url: &str) -> Option<u32> {
+        self.search(self.entry_count, |index| {
+            let entry = self.entry(index).ok()?;
+            Some((entry.namespace, entry.url.as_bytes()).cmp(&(namespace, url.as_bytes())))
+        })
+        .ok()
+    }
+
+    /// Returns the position, in the list sorted by path, of the entry at the given path,
+    /// which is relative to the root of the archive.
+    pub fn find_by_path(&self, path: &str) -> Option<u32> {
+        if self.has_namespaced_paths() {
+            let (namespace, url) = path.split_once('/')?;
+            if namespace.len() != 1 {
+                return None;
+            }
+            self.find_by_url(namespace.as_bytes()[0], url)
+        } else {
+            self.find_by_url(b'C', path)
+        }
+    }
+
+    /// Returns the path of the given entry, relative to the root of the archive.
+    pub fn path(&self, entry: &DirEntry) -> String {
+        if self.has_namespaced_paths() {
+            format!("{}/{}", entry.namespace as char, entry.url)
+        } else {
+            entry.url.clone()
+        }
+    }
+
+    fn title_entry(&self, rank: u32) -> Option<(u32, DirEntry)> {
+        let index = self
+            .read_u32(table_pos(self.title_ptr_pos, rank as u64, 4).ok()?)
+            .ok()?;
+        self.entry(index).ok().map(|entry| (index, entry))
+    }
+
+    /// Returns the position, in the list sorted by path, of the article with the given title.
+    pub fn find_by_title(&self, title: &str) -> Option<u32> {
+        let namespace = self.content_namespace();
+        let rank = self
+            .search(self.entry_count, |rank| {
+                let (_, entry) = self.title_entry(rank)?;
+                Some(
+                    (entry.namespace, entry.title().as_bytes()).cmp(&(namespace, title.as_bytes())),
+                )
+            })
+            .ok()?;
+        self.title_entry(rank).map(|(index, _)| index)
+    }
+
+    /// Returns the articles whose titles start with the given text, in the order of the titles.
+    pub fn titles_with_prefix(&self, prefix: &str, limit: usize) -> Vec<(u32, DirEntry)> {
+        let namespace = self.content_namespace();
+        let start = self
+            .search(self.entry_count, |rank| {
+                let (_, entry) = self.title_entry(rank)?;
+                let ordering = (entry.namespace, entry.title().as_bytes())
+                    .cmp(&(namespace, prefix.as_bytes()));
+                // Find the first title that isn't smaller than the prefix.
+                Some(ordering.then(Ordering::Greater))
+            })
+            .unwrap_or_else(|rank| rank);
+
+        (start..self.entry_count)
+            .map_while(|rank| self.title_entry(rank))
+            .take_while(|(_, entry)| {
+                entry.namespace == namespace && entry.title().starts_with(prefix)
+            })
+            .filter(|(_, entry)| {
+                matches!(entry.target, Target::Redirect(_)) || self.is_article(entry)
+            })
+            .take(limit)
+            .collect()
+    }
+
+    /// Returns the value of the given metadata, e.g. `Title` or `Language`.
+    pub fn metadata(&self, name: &str) -> Option<String> {
+        let index = self.find_by_url(b'M', name)?;
+        let entry = self.entry(index).ok()?;
+        let content = self.content(&entry).ok()?;
+        String::from_utf8(content)
+            .ok()
+            .filter(|value| !value.is_empty())
+    }
+
+    /// A binary search, in the given number of items, where the comparison of an item with
+    /// the target can fail.
+    fn search<F>(&self, count: u32, mut compare: F) -> Result<u32, u32>
+    where
+        F: FnMut(u32) -> Option<Ordering>,
+    {
+        let (mut low, mut high) = (0, count);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            match compare(middle) {
+                Some(Ordering::Less) => low = middle + 1,
+                Some(Ordering::Greater) => high = middle,
+                Some(Ordering::Equal) => return Ok(middle),
+                None => return Err(low),
+            }
+        }
+        Err(low)
+    }
+}
+
+// The position of an item of one of the pointer lists, whose start comes from the header.
+fn table_pos(start: u64, index: u64, size: u64) -> Result<u64, Error> {
+    index
+        .checked_mul(size)
+        .and_then(|offset| start.checked_add(offset))
+        .ok_or_else(|| format_err!("invalid pointer {}", index))
+}
+
+fn parse_cluster(raw: &[u8]) -> Result<Cluster, Error> {
+    let (&info, payload) = raw
+        .split_first()
+        .ok_or_else(|| format_err!("empty cluster"))?;
+    let data = match info & 0x0f {
+        0 | 1 => payload.to_vec(),
+        4 => decompress(lzma_rust2::XzReader::new(payload, false))?,
+        5 => decompress(zstd::stream::read::Decoder::new(payload)?)?,
+        compression => return Err(format_err!("unsupported compression: {}", compression)),
+    };
+
+    let offset_size = if info & 0x10 != 0 { 8 } else { 4 };
+    let read_offset = |pos: usize| -> Option<usize> {
+        let bytes = data.get(pos..pos + offset_size)?;
+        if offset_size == 8 {
+            usize::try_from(u64_at(bytes, 0)).ok()
+        } else {
+            Some(u32_at(bytes, 0) as usize)
+        }
+    };
+    let first = read_offset(0).ok_or_else(|| format_err!("truncated cluster"))?;
+    let offsets = (0..first / offset_size)
+        .map(|index| read_offset(index * offset_size))
+        .collect::<Option<Vec<usize>>>()
+        .ok_or_else(|| format_err!("truncated cluster"))?;
+
+    Ok(Cluster { offsets, data })
+}
+
+fn decompress<R: Read>(reader: R) -> Result<Vec<u8>, Error> {
+    let mut data = Vec::new();
+    reader.take(MAX_CLUSTER_SIZE + 1).read_to_end(&mut data)?;
+    if data.len() as u64 > MAX_CLUSTER_SIZE {
+        return Err(format_err!("cluster too large"));
+    }
+    Ok(data)
+}
+
+/// Resolves a link relatively to the given directory.
+fn join_path(dir: &str, href: &str) -> String {
+    let mut components: Vec<&str> = if href.starts_with('/') {
+        Vec::new()
+    } else {
+        dir.split('/').filter(|c| !c.is_empty()).collect()
+    };
+    for component in href.split('/') {
+        match component {
+            "" | "." => (),
+            ".." => {
+                components.pop();
+            }
+            _ => components.push(component),
+        }
+    }
+    components.join("/")
+}
+
+fn parent_dir(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+/// Fetches the resources of the current article.
+struct ZimFetcher {
+    archive: Arc<ZimArchive>,
+    dir: String,
+}
+
+impl ResourceFetcher for ZimFetcher {
+    fn fetch(&mut self, name: &str) -> Result<Vec<u8>, Error> {
+        // The names are already resolved against the root, dropping the leading `..`.
+        let index = self
+            .archive
+            .find_by_path(&join_path(&self.dir, name))
+            .or_else(|| self.archive.find_by_path(name))
+            .ok_or_else(|| format_err!("no entry at {}", name))?;
+        let (_, entry) = self.archive.resolve(index)?;
+        self.archive.content(&entry)
+    }
+}
+
+pub struct ZimDocument {
+    archive: Arc<ZimArchive>,
+    html: HtmlDocument<ZimFetcher>,
+    /// The articles opened, the position of an article giving the upper part of its
+    /// locations.
+    articles: Vec<u32>,
+    current: Option<usize>,
+}
+
+impl ZimDocument {
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<ZimDocument, Error> {
+        let archive = Arc::new(ZimArchive::open(path)?);
+        let main_page = archive
+            .main_page()
+            .and_then(|index| archive.resolve(index).ok())
+            .map(|(index, _)| index)
+            .or_else(|| {
+                let namespace = archive.content_namespace();
+                (0..archive.entry_count()).find(|&index| {
+                    archive.entry(index).is_ok_and(|entry| {
+                        entry.namespace == namespace && archive.is_article(&entry)
+                    })
+                })
+            })
+            .ok_or_else(|| format_err!("no articles"))?;
+
+        let fetcher = ZimFetcher {
+            archive: Arc::clone(&archive),
+            dir: String::new(),
+        };
+        let mut doc = ZimDocument {
+            archive,
+            html: HtmlDocument::with_fetcher("", fetcher),
+            articles: vec![main_page],
+            current: None,
+        };
+        doc.load(0)?;
+        Ok(doc)
+    }
+
+    fn encode(index: usize, offset: usize) -> usize {
+        (index + 1) * ARTICLE_STRIDE + offset.min(ARTICLE_STRIDE - 1)
+    }
+
+    /// Returns the article and the offset of the given location. The locations outside the
+    /// opened articles, such as the ones of a previous session, fall in the current article.
+    fn decode(&self, location: usize) -> (usize, usize) {
+        let slot = location / ARTICLE_STRIDE;
+        let offset = location % ARTICLE_STRIDE;
+        if slot == 0 || slot > self.articles.len() {
+            (self.current.unwrap_or(0), offset)
+        } else {
+            (slot - 1, offset)
+        }
+    }
+
+    fn load(&mut self, index: usize) -> Result<(), Error> {
+        if self.current == Some(index) {
+            return Ok(());
+        }
+        let entry = self.archive.entry(self.articles[index])?;
+        let content = self.archive.content(&entry)?;
+        self.html.fetcher_mut().dir = parent_dir(&self.archive.path(&entry)).to_string();
+        self.html.update(&String::from_utf8_lossy(&content));
+        self.current = Some(index);
+        Ok(())
+    }
+
+    /// Opens the article at the given path, and returns its position among the opened ones.
+    fn open_article(&mut self, path: &str) -> Option<usize> {
+        let index = self.archive.find_by_path(path)?;
+        let (index, entry) = self.archive.resolve(index).ok()?;
+        if !self.archive.is_article(&entry) {
+            return None;
+        }
+        if let Some(position) = self.articles.iter().position(|&other| other == index) {
+            return Some(position);
+        }
+        if self.articles.len() < MAX_ARTICLES {
+            self.articles.push(index);
+        } else {
+            // Reuse the last position, rather than the ones the history refers to the most.
+            let last = self.articles.len() - 1;
+            self.articles[last] = index;
+            if self.current == Some(last) {
+                self.current = None;
+            }
+        }
+        Some(self.articles.len() - 1)
+    }
+
+    fn follow_link(&mut self, from: usize, uri: &str) -> Option<usize> {
+        if uri.contains("://") || uri.starts_with("mailto:") {
+            return None;
+        }
+        let (path, fragment) = match uri.split_once('#') {
+            Some((path, fragment)) => (path, Some(fragment)),
+            None => (uri, None),
+        };
+        let path = path.split('?').next().unwrap_or_default();
+
+        let index = if path.is_empty() {
+            from
+        } else {
+            let entry = self.archive.entry(self.articles[from]).ok()?;
+            let current_path = self.archive.path(&entry);
+            let path = percent_decode_str(path).decode_utf8_lossy();
+            let target = join_path(parent_dir(&current_path), &path);
+            self.open_article(&target)?
+        };
+
+        self.load(index)
+            .map_err(|e| error!("Can't load article: {:#}.", e))
+            .ok()?;
+        let offset = fragment
+            .and_then(|fragment| {
+                self.html
+                    .resolve_location(Location::Uri(format!("#{}", fragment)))
+            })
+            .or_else(|| self.html.resolve_location(Location::Exact(0)))?;
+        Some(ZimDocument::encode(index, offset))
+    }
+}
+
+impl Document for ZimDocument {
+    fn dims(&self, _index: usize) -> Option<(f32, f32)> {
+        self.html.dims(0)
+    }
+
+    fn pages_count(&self) -> usize {
+        (MAX_ARTICLES + 1) * ARTICLE_STRIDE
+    }
+
+    fn toc(&mut self) -> Option<Vec<TocEntry>> {
+        None
+    }
+
+    fn chapter<'a>(&mut self, _offset: usize, _toc: &'a [TocEntry]) -> Option<(&'a TocEntry, f32)> {
+        None
+    }
+
+    fn chapter_relative<'a>(
+        &mut self,
+        _offset: usize,
+        _dir: CycleDir,
+        _toc: &'a [TocEntry],
+    ) -> Option<&'a TocEntry> {
+        None
+    }
+
+    fn resolve_location(&mut self, loc: Location) -> Option<usize> {
+        match loc {
+            Location::Exact(location) | Location::Previous(location) | Location::Next(location) => {
+                let (index, offset) = self.decode(location);
+                self.load(index)
+                    .map_err(|e| error!("Can't load article: {:#}.", e))
+                    .ok()?;
+                let inner = match loc {
+                    Location::Previous(_) => Location::Previous(offset),
+                    Location::Next(_) => Location::Next(offset),
+                    _ => Location::Exact(offset),
+                };
+                self.html
+                    .resolve_location(inner)
+                    .map(|offset| ZimDocument::encode(index, offset))
+            }
+            Location::LocalUri(location, ref uri) => {
+                let (index, _) = self.decode(location);
+                self.follow_link(index, uri)
+            }
+            Location::Uri(ref uri) => {
+                let index = self.current.unwrap_or(0);
+                self.follow_link(index, uri)
+            }
+        }
+    }
+
+    fn words(&mut self, loc: Location) -> Option<(Vec<BoundedText>, usize)> {
+        let location = self.resolve_location(loc)?;
+        let (index, offset) = self.decode(location);
+        let (mut words, _) = self.html.words(Location::Exact(offset))?;
+        for word in &mut words {
+            word.location =
+                TextLocation::Dynamic(ZimDocument::encode(index, word.location.location()));
+        }
+        Some((words, location))
+    }
+
+    fn lines(&mut self, _loc: Location) -> Option<(Vec<BoundedText>, usize)> {
+        None
+    }
+
+    fn links(&mut self, loc: Location) -> Option<(Vec<BoundedText>, usize)> {
+        let location = self.resolve_location(loc)?;
+        let (index, offset) = self.decode(location);
+        let (mut links, _) = self.html.links(Location::Exact(offset))?;
+        for link in &mut links {
+            link.location =
+                TextLocation::Dynamic(ZimDocument::encode(index, link.location.location()));
+        }
+        Some((links, location))
+    }
+
+    fn images(&mut self, loc: Location) -> Option<(Vec<Boundary>, usize)> {
+        let location = self.resolve_location(loc)?;
+        let (_, offset) = self.decode(location);
+        let (images, _) = self.html.images(Location::Exact(offset))?;
+        Some((images, location))
+    }
+
+    fn pixmap(&mut self, loc: Location, scale: f32, samples: usize) -> Option<(Pixmap, usize)> {
+        let location = self.resolve_location(loc)?;
+        let (_, offset) = self.decode(location);
+        let (pixmap, _) = self.html.pixmap(Location::Exact(offset), scale, samples)?;
+        Some((pixmap, location))
+    }
+
+    fn layout(&mut self, width: u32, height: u32, font_size: f32, dpi: u16) {
+        self.html.layout(width, height, font_size, dpi);
+    }
+
+    fn set_font_family(&mut self, family_name: &str, search_path: &str) {
+        self.html.set_font_family(family_name, search_path);
+    }
+
+    fn set_margin_width(&mut self, width: i32) {
+        self.html.set_margin_width(width);
+    }
+
+    fn set_text_align(&mut self, text_align: TextAlign) {
+        self.html.set_text_align(text_align);
+    }
+
+    fn set_line_height(&mut self, line_height: f32) {
+        self.html.set_line_height(line_height);
+    }
+
+    fn set_hyphen_penalty(&mut self, hyphen_penalty: i32) {
+        self.html.set_hyphen_penalty(hyphen_penalty);
+    }
+
+    fn set_stretch_tolerance(&mut self, stretch_tolerance: f32) {
+        self.html.set_stretch_tolerance(stretch_tolerance);
+    }
+
+    fn set_ignore_document_css(&mut self, ignore: bool) {
+        self.html.set_ignore_document_css(ignore);
+    }
+
+    fn search_titles(&mut self, text: &str) -> Option<Vec<TocEntry>> {
+        let mut entries = self.archive.titles_with_prefix(text, MAX_SEARCH_RESULTS);
+        // The titles usually start with a capital letter.
+        let mut chars = text.chars();
+        if let Some(first) = chars.next().filter(|c| c.is_lowercase()) {
+            let capitalized = first.to_uppercase().chain(chars).collect::<String>();
+            entries.extend(
+                self.archive
+                    .titles_with_prefix(&capitalized, MAX_SEARCH_RESULTS),
+            );
+        }
+        Some(
+            entries
+                .into_iter()
+                .enumerate()
+                .map(|(index, (_, entry))| TocEntry {
+                    title: entry.title().to_string(),
+                    location: Location::Uri(format!("/{}", self.archive.path(&entry))),
+                    index,
+                    children: Vec::new(),
+                })
+                .collect(),
+        )
+    }
+
+    fn title(&self) -> Option<String> {
+        self.archive.metadata("Title")
+    }
+
+    fn author(&self) -> Option<String> {
+        self.archive.metadata("Creator")
+    }
+
+    fn metadata(&self, key: &str) -> Option<String> {
+        let mut chars = key.chars();
+        let name = chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect::<String>())?;
+        self.archive.metadata(&name)
+    }
+
+    fn is_reflowable(&self) -> bool {
+        true
+    }
+
+    fn has_synthetic_page_numbers(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+
+    /// Writes an archive with uncompressed clusters, in the format of version 6.1.
+    pub(crate) fn write_archive(path: &Path, entries: &[(u8, &str, &str, &str)]) {
+        let mut entries = entries.to_vec();
+        entries.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+        let mime_types = b"text/html\0text/plain\0\0";
+
+        // One blob per entry, in a single cluster.
+        let mut cluster = vec![1u8];
+        let offsets_size = 4 * (entries.len() + 1);
+        let mut offset = offsets_size;
+        for (_, _, _, content) in &entries {
+            cluster.extend_from_slice(&(offset as u32).to_le_bytes());
+            offset += content.len();
+        }
+        cluster.extend_from_slice(&(offset as u32).to_le_bytes());
+        for (_, _, _, content) in &entries {
+            cluster.extend_from_slice(content.as_bytes());
+        }
+
+        let mut dirents = Vec::new();
+        let mut dirent_offsets = Vec::new();
+        let dirents_pos = HEADER_SIZE + mime_types.len();
+        for (index, (namespace, url, title, _)) in entries.iter().enumerate() {
+            dirent_offsets.push((dirents_pos + dirents.len()) as u64);
+            let mime_type: u16 = if *namespace == b'M' { 1 } else { 0 };
+            dirents.extend_from_slice(&mime_type.to_le_bytes());
+            dirents.push(0);
+            dirents.push(*namespace);
+            dirents.extend_from_slice(&0u32.to_le_bytes());
+            dirents.extend_from_slice(&0u32.to_le_bytes());
+            dirents.extend_from_slice(&(index as u32).to_le_bytes());
+            dirents.extend_from_slice(url.as_bytes());
+            dirents.push(0);
+            dirents.extend_from_slice(title.as_bytes());
+            dirents.push(0);
+        }
+
+        let mut titles = (0..entries.len() as u32).collect::<Vec<u32>>();
+        titles.sort_by_key(|&index| {
+            let (namespace, url, title, _) = entries[index as usize];
+            (namespace, if title.is_empty() { url } else { title })
+        });
+
+        let url_ptr_pos = dirents_pos + dirents.len();
+        let title_ptr_pos = url_ptr_pos + 8 * entries.len();
+        let cluster_ptr_pos = title_ptr_pos + 4 * entries.len();
+        let cluster_pos = cluster_ptr_pos + 8;
+        let checksum_pos = cluster_pos + cluster.len();
+        let main_page = entries
+            .iter()
+            .position(|(_, url, _, _)| *url == "Main_Page")
+            .map_or(NO_PAGE, |index| index as u32);
+
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&MAGIC_NUMBER.to_le_bytes());
+        buf.extend_from_slice(&6u16.to_le_bytes());
+        buf.extend_from_slice(&1u16.to_le_bytes());
+        buf.extend_from_slice(&[0; 16]);
+        buf.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+        buf.extend_from_slice(&1u32.to_le_bytes());
+        buf.extend_from_slice(&(url_ptr_pos as u64).to_le_bytes());
+        buf.extend_from_slice(&(title_ptr_pos as u64).to_le_bytes());
+        buf.extend_from_slice(&(cluster_ptr_pos as u64).to_le_bytes());
+        buf.extend_from_slice(&(HEADER_SIZE as u64).to_le_bytes());
+        buf.extend_from_slice(&main_page.to_le_bytes());
+        buf.extend_from_slice(&NO_PAGE.to_le_bytes());
+        buf.extend_from_slice(&(checksum_pos as u64).to_le_bytes());
+        buf.extend_from_slice(mime_types);
+        buf.extend_from_slice(&dirents);
+        for offset in dirent_offsets {
+            buf.extend_from_slice(&offset.to_le_bytes());
+        }
+        for index in titles {
+            buf.extend_from_slice(&index.to_le_bytes());
+        }
+        buf.extend_from_slice(&(cluster_pos as u64).to_le_bytes());
+        buf.extend_from_slice(&cluster);
+
+        File::create(path).unwrap().write_all(&buf).unwrap();
+    }
+
+    #[test]
+    fn test_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wiki.zim");
+        write_archive(
+            &path,
+            &[
+                (
+                    b'C',
+                    "Main_Page",
+                    "Main Page",
+                    "<html><body><a href=\"Dune\">Dune</a></body></html>",
+                ),
+                (
+                    b'C',
+                    "Dune",
+                    "",
+                    "<html><body><p>A novel.</p></body></html>",
+                ),
+                (
+                    b'C',
+                    "Dune_Messiah",
+                    "Dune Messiah",
+                    "<html><body><p>A sequel.</p></body></html>",
+                ),
+                (b'M', "Title", "", "Wiki"),
+            ],
+        );
+
+        let archive = ZimArchive::open(&path).unwrap();
+        assert_eq!(archive.metadata("Title").as_deref(), Some("Wiki"));
+        let index = archive.find_by_path("Dune_Messiah").unwrap();
+        let entry = archive.entry(index).unwrap();
+        assert_eq!(
+            archive.content(&entry).unwrap(),
+            b"<html><body><p>A sequel.</p></body></html>"
+        );
+        assert_eq!(archive.find_by_title("Dune"), archive.find_by_path("Dune"));
+        let titles = archive
+            .titles_with_prefix("Dune", 8)
+            .into_iter()
+            .map(|(_, entry)| entry.title().to_string())
+            .collect::<Vec<String>>();
+        assert_eq!(titles, vec!["Dune", "Dune Messiah"]);
+        assert_eq!(join_path("wiki", "../I/cover.png"), "I/cover.png");
+    }
+
+    #[test]
+    fn test_cluster_bounds() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wiki.zim");
+        write_archive(&path, &[(b'C', "Dune", "Dune", "<p>A novel.</p>")]);
+
+        // The last cluster ends at the checksum, which is past the end of the file here.
+        let mut buf = fs::read(&path).unwrap();
+        buf[72..80].copy_from_slice(&u64::MAX.to_le_bytes());
+        fs::write(&path, &buf).unwrap();
+        let archive = ZimArchive::open(&path).unwrap();
+        let entry = archive
+            .entry(archive.find_by_path("Dune").unwrap())
+            .unwrap();
+        assert_eq!(archive.content(&entry).unwrap(), b"<p>A novel.</p>");
+
+        // The pointer of the cluster is past its end.
+        let cluster_ptr_pos = u64_at(&buf, 48) as usize;
+        buf[cluster_ptr_pos..cluster_ptr_pos + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        fs::write(&path, &buf).unwrap();
+        let archive = ZimArchive::open(&path).unwrap();
+        assert!(archive.content(&entry).is_err());
+    }
+
+    #[test]
+    fn test_pointer_lists_overflow() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wiki.zim");
+        write_archive(&path, &[(b'C', "Dune", "Dune", "<p>A novel.</p>")]);
+
+        // The positions of the title and cluster lists.
+        let mut buf = fs::read(&path).unwrap();
+        for pos in [40, 48] {
+            buf[pos..pos + 8].copy_from_slice(&(u64::MAX - 1).to_le_bytes());
+        }
+        fs::write(&path, &buf).unwrap();
+        let archive = ZimArchive::open(&path).unwrap();
+        assert!(archive.cluster(0).is_err());
+        assert!(archive.title_entry(0).is_none());
+
+        assert!(table_pos(u64::MAX - 8, 1, 8).is_ok());
+        assert!(table_pos(u64::MAX - 8, 2, 8).is_err());
+        assert!(table_pos(0, u64::MAX, 8).is_err());
+    }
+
+    #[test]
+    fn test_decompressed_cluster_is_bounded() {
+        let data = vec![0; MAX_CLUSTER_SIZE as usize + 1];
+        let mut raw = vec![5u8];
+        raw.extend(zstd::stream::encode_all(&data[..], 1).unwrap());
+        assert!(raw.len() < 1 << 16);
+        assert!(parse_cluster(&raw).is_err());
+
+        raw.truncate(1);
+        raw.extend(zstd::stream::encode_all(&data[..8], 1).unwrap());
+        assert!(parse_cluster(&raw).is_ok());
+    }
+}
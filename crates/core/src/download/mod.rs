@@ -0,0 +1,697 @@
+//! The downloads of files, queued and made one at a time in the background.
+//!
+//! An interrupted download is resumed where it stopped, with a range request, after a delay
+//! that doubles with each attempt. The queue can be paused and resumed, and the outcome of
+//! each download is appended to a log, shown with the queue.
+
+use crate::document::HumanSize;
+use crate::helpers::escape_html;
+use crate::net;
+use crate::view::{Event, Hub, NotificationEvent, ViewId, ID_FEEDER};
+use anyhow::{format_err, Error};
+use chrono::Local;
+use lazy_static::lazy_static;
+use reqwest::blocking::Client;
+use reqwest::header::RANGE;
+use reqwest::StatusCode;
+use secrecy::{ExposeSecret, SecretString};
+use std::ffi::OsString;
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+
+pub const DOWNLOADS_LOG_PATH: &str = "downloads.log";
+
+/// The number of lines kept in the log.
+const MAX_LOG_LINES: usize = 200;
+
+/// The number of attempts made before a download is given up.
+const MAX_ATTEMPTS: usize = 5;
+
+/// The delay before the second attempt, doubled for each of the following ones.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+const BUFFER_SIZE: usize = 64 * 1024;
+
+pub type DownloadId = u64;
+
+/// A file to download.
+#[derive(Debug, Clone)]
+pub struct Request {
+    /// The name shown in the queue and in the notifications.
+    pub label: String,
+    pub url: String,
+    pub path: PathBuf,
+    /// Sent as a bearer token.
+    pub token: Option<SecretString>,
+    /// Sent as a user name and a password.
+    pub credentials: Option<(String, SecretString)>,
+    /// Whether the file is imported into the current library once downloaded.
+    pub import: bool,
+    /// Whether the progress is kept out of the notifications, for the callers that show it.
+    pub quiet: bool,
+}
+
+impl Request {
+    pub fn new(label: &str, url: &str, path: PathBuf) -> Request {
+        Request {
+            label: label.to_string(),
+            url: url.to_string(),
+            path,
+            token: None,
+            credentials: None,
+            import: false,
+            quiet: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    Queued,
+    Active,
+    /// Waiting before the next attempt.
+    Retrying(Instant),
+    Paused,
+    Failed(String),
+}
+
+/// The progress reported to the owner of a download.
+#[derive(Debug, Clone)]
+pub enum Progress {
+    Transferred { downloaded: u64, total: Option<u64> },
+    Retrying { attempt: usize, error: String },
+    Done(PathBuf),
+    Failed(String),
+}
+
+struct Item {
+    id: DownloadId,
+    request: Request,
+    status: Status,
+    downloaded: u64,
+    total: Option<u64>,
+    attempts: usize,
+    notification: Option<ViewId>,
+    sender: Sender<Progress>,
+    hub: Option<Hub>,
+}
+
+impl Item {
+    fn percent(&self) -> Option<u8> {
+        self.total
+            .filter(|&total| total > 0)
+            .map(|total| (100 * self.downloaded.min(total) / total) as u8)
+    }
+
+    fn notify(&self, message: String) {
+        if let Some(hub) = self.hub.as_ref() {
+            hub.send(Event::Notification(NotificationEvent::Show(message)))
+                .ok();
+        }
+    }
+
+    fn close_notification(&mut self) {
+        if let (Some(id), Some(hub)) = (self.notification.take(), self.hub.as_ref()) {
+            hub.send(Event::Close(id)).ok();
+        }
+    }
+}
+
+#[derive(Default)]
+struct Queue {
+    items: Vec<Item>,
+    paused: bool,
+    running: bool,
+    next_id: DownloadId,
+}
+
+lazy_static! {
+    static ref QUEUE: Mutex<Queue> = Mutex::new(Queue::default());
+    static ref WAKE: Condvar = Condvar::new();
+}
+
+fn queue() -> MutexGuard<'static, Queue> {
+    QUEUE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// The way a download can be followed by the part of the program that requested it.
+pub struct Handle {
+    pub id: DownloadId,
+    receiver: Receiver<Progress>,
+}
+
+impl Handle {
+    /// Waits for the end of the download, passing its progress to the given callback.
+    pub fn wait<F: FnMut(&Progress)>(self, mut callback: F) -> Result<PathBuf, Error> {
+        for progress in self.receiver.iter() {
+            callback(&progress);
+            match progress {
+                Progress::Done(path) => return Ok(path),
+                Progress::Failed(message) => return Err(format_err!(message)),
+                _ => (),
+            }
+        }
+        Err(format_err!("the download was dropped"))
+    }
+}
+
+/// Adds a download to the queue. The notifications and the imports go through the given hub.
+pub fn enqueue(request: Request, hub: Option<&Hub>) -> Handle {
+    let (sender, receiver) = mpsc::channel();
+    let mut queue = queue();
+    queue.next_id += 1;
+    let id = queue.next_id;
+    info!("Queuing the download of {}.", request.url);
+    queue.items.push(Item {
+        id,
+        request,
+        status: Status::Queued,
+        downloaded: 0,
+        total: None,
+        attempts: 0,
+        notification: None,
+        sender,
+        hub: hub.cloned(),
+    });
+    wake(&mut queue);
+    Handle { id, receiver }
+}
+
+pub fn is_paused() -> bool {
+    queue().paused
+}
+
+/// Pauses or resumes the queue. A download paused midway resumes where it stopped.
+pub fn set_paused(paused: bool) {
+    let mut queue = queue();
+    queue.paused = paused;
+    if !paused {
+        for item in &mut queue.items {
+            if item.status == Status::Paused {
+                item.status = Status::Queued;
+            }
+        }
+        wake(&mut queue);
+    }
+}
+
+/// Returns the number of downloads that aren't over.
+pub fn pending_count() -> usize {
+    queue()
+        .items
+        .iter()
+        .filter(|item| !matches!(item.status, Status::Failed(..)))
+        .count()
+}
+
+pub fn failed_count() -> usize {
+    queue()
+        .items
+        .iter()
+        .filter(|item| matches!(item.status, Status::Failed(..)))
+        .count()
+}
+
+/// Queues the failed downloads again, and returns their number.
+pub fn retry_failed() -> usize {
+    let mut queue = queue();
+    let mut count = 0;
+    for item in &mut queue.items {
+        if matches!(item.status, Status::Failed(..)) {
+            item.status = Status::Queued;
+            item.attempts = 0;
+            count += 1;
+        }
+    }
+    wake(&mut queue);
+    count
+}
+
+fn wake(queue: &mut Queue) {
+    if queue.running {
+        WAKE.notify_all();
+    } else if !queue.paused {
+        queue.running = true;
+        thread::spawn(work);
+    }
+}
+
+fn build_client() -> Result<Client, Error> {
+    net::client_builder(concat!("cadmus/", env!("CARGO_PKG_VERSION")), TIMEOUT)
+        .build()
+        .map_err(Error::from)
+}
+
+fn backoff(attempt: usize) -> Duration {
+    INITIAL_BACKOFF
+        .saturating_mul(1 << attempt.saturating_sub(1).min(16))
+        .min(MAX_BACKOFF)
+}
+
+/// Makes the queued downloads, one at a time, until there are none left.
+fn work() {
+    let client = build_client();
+    loop {
+        let (id, request) = {
+            let mut queue = queue();
+            loop {
+                let now = Instant::now();
+                let paused = queue.paused;
+                let next = queue.items.iter_mut().find(|item| match item.status {
+                    Status::Queued => !paused,
+                    Status::Retrying(at) => !paused && at <= now,
+                    _ => false,
+                });
+                if let Some(item) = next {
+                    item.status = Status::Active;
+                    item.attempts += 1;
+                    if item.notification.is_none() && !item.request.quiet {
+                        if let Some(hub) = item.hub.as_ref() {
+                            let view_id = ViewId::MessageNotif(ID_FEEDER.next());
+                            hub.send(Event::Notification(NotificationEvent::ShowPinned(
+                                view_id,
+                                format!("Downloading {}.", item.request.label),
+                            )))
+                            .ok();
+                            item.notification = Some(view_id);
+                        }
+                    }
+                    break (item.id, item.request.clone());
+                }
+                let retry_at = queue
+                    .items
+                    .iter()
+                    .filter_map(|item| match item.status {
+                        Status::Retrying(at) if !paused => Some(at),
+                        _ => None,
+                    })
+                    .min();
+                match retry_at {
+                    Some(at) => {
+                        queue = WAKE
+                            .wait_timeout(queue, at.saturating_duration_since(now))
+                            .unwrap_or_else(|e| e.into_inner())
+                            .0;
+                    }
+                    None => {
+                        queue.running = false;
+                        return;
+                    }
+                }
+            }
+        };
+
+        let result = match client.as_ref() {
+            Ok(client) => transfer(client, id, &request),
+            Err(e) => Err(Failure::Permanent(format_err!("{:#}", e))),
+        };
+
+        let mut queue = queue();
+        let paused = queue.paused;
+        let Some(index) = queue.items.iter().position(|item| item.id == id) else {
+            continue;
+        };
+        match result {
+            Ok(()) => {
+                let mut item = queue.items.remove(index);
+                drop(queue);
+                info!("Downloaded {}.", request.url);
+                item.close_notification();
+                append_log(&format!("Downloaded {}", request.label));
+                item.sender.send(Progress::Done(request.path.clone())).ok();
+                if request.import {
+                    if let Some(hub) = item.hub.as_ref() {
+                        hub.send(Event::FileReceived(request.path.clone())).ok();
+                    }
+                } else if !request.quiet {
+                    item.notify(format!("Downloaded {}.", request.label));
+                }
+            }
+            Err(Failure::Interrupted) => {
+                queue.items[index].status = Status::Paused;
+            }
+            Err(Failure::Transient(e)) if queue.items[index].attempts < MAX_ATTEMPTS => {
+                let item = &mut queue.items[index];
+                let delay = backoff(item.attempts);
+                warn!(
+                    "Download of {} failed (attempt {}): {:#}. Retrying in {}s.",
+                    request.url,
+                    item.attempts,
+                    e,
+                    delay.as_secs()
+                );
+                item.status = if paused {
+                    Status::Paused
+                } else {
+                    Status::Retrying(Instant::now() + delay)
+                };
+                item.sender
+                    .send(Progress::Retrying {
+                        attempt: item.attempts + 1,
+                        error: format!("{:#}", e),
+                    })
+                    .ok();
+                if let (Some(view_id), Some(hub)) = (item.notification, item.hub.as_ref()) {
+                    hub.send(Event::Notification(NotificationEvent::UpdateText(
+                        view_id,
+                        format!("Retrying {} in {}s.", request.label, delay.as_secs()),
+                    )))
+                    .ok();
+                }
+            }
+            Err(Failure::Transient(e)) | Err(Failure::Permanent(e)) => {
+                let message = format!("{:#}", e);
+                error!("Can't download {}: {}.", request.url, message);
+                let item = &mut queue.items[index];
+                item.status = Status::Failed(message.clone());
+                item.close_notification();
+                item.sender.send(Progress::Failed(message.clone())).ok();
+                if !request.quiet {
+                    item.notify(format!("Can't download {}: {}.", request.label, message));
+                }
+                drop(queue);
+                append_log(&format!("Failed {}: {}", request.label, message));
+            }
+        }
+    }
+}
+
+enum Failure {
+    /// The queue was paused.
+    Interrupted,
+    /// Worth another attempt, such as a timeout or a server error.
+    Transient(Error),
+    Permanent(Error),
+}
+
+// Hidden, so that the partial files are neither imported nor synced.
+fn partial_path(path: &Path) -> PathBuf {
+    let mut name = OsString::from(".");
+    name.push(path.file_name().unwrap_or_default());
+    name.push(".part");
+    path.with_file_name(name)
+}
+
+/// Downloads the file, or what's missing from a previous attempt.
+fn transfer(client: &Client, id: DownloadId, request: &Request) -> Result<(), Failure> {
+    let part_path = partial_path(&request.path);
+    let offset = fs::metadata(&part_path).map_or(0, |metadata| metadata.len());
+
+    let mut builder = client.get(&request.url);
+    if let Some(token) = request.token.as_ref() {
+        builder = builder.bearer_auth(token.expose_secret());
+    }
+    if let Some((username, password)) = request.credentials.as_ref() {
+        builder = builder.basic_auth(username, Some(password.expose_secret()));
+    }
+    if offset > 0 {
+        builder = builder.header(RANGE, format!("bytes={}-", offset));
+    }
+    let mut response = builder.send().map_err(|e| Failure::Transient(e.into()))?;
+
+    let status = response.status();
+    if status == StatusCode::RANGE_NOT_SATISFIABLE && offset > 0 {
+        // The previous attempt got everything.
+        return fs::rename(&part_path, &request.path).map_err(|e| Failure::Permanent(e.into()));
+    }
+    if status.is_server_error()
+        || status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::REQUEST_TIMEOUT
+    {
+        return Err(Failure::Transient(format_err!(
+            "the server answered {}",
+            status
+        )));
+    }
+    if !status.is_success() {
+        return Err(Failure::Permanent(format_err!(
+            "the server answered {}",
+            status
+        )));
+    }
+
+    let resumed = status == StatusCode::PARTIAL_CONTENT;
+    let mut downloaded = if resumed { offset } else { 0 };
+    let total = response.content_length().map(|length| length + downloaded);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(&part_path)
+        .map_err(|e| Failure::Permanent(e.into()))?;
+    report(id, downloaded, total);
+
+    let mut buf = vec![0; BUFFER_SIZE];
+    loop {
+        if is_paused() {
+            return Err(Failure::Interrupted);
+        }
+        let count = response
+            .read(&mut buf)
+            .map_err(|e| Failure::Transient(e.into()))?;
+        if count == 0 {
+            break;
+        }
+        file.write_all(&buf[..count])
+            .map_err(|e| Failure::Permanent(e.into()))?;
+        downloaded += count as u64;
+        report(id, downloaded, total);
+    }
+
+    if total.is_some_and(|total| downloaded < total) {
+        return Err(Failure::Transient(format_err!(
+            "the connection was closed after {} bytes",
+            downloaded
+        )));
+    }
+
+    fs::rename(&part_path, &request.path).map_err(|e| Failure::Permanent(e.into()))
+}
+
+fn report(id: DownloadId, downloaded: u64, total: Option<u64>) {
+    let mut queue = queue();
+    let Some(item) = queue.items.iter_mut().find(|item| item.id == id) else {
+        return;
+    };
+    let previous = item.percent();
+    item.downloaded = downloaded;
+    item.total = total;
+    let percent = item.percent();
+    // Report each percent, or each buffer when the size is unknown.
+    if percent.is_some() && percent == previous && downloaded < total.unwrap_or(0) {
+        return;
+    }
+    item.sender
+        .send(Progress::Transferred { downloaded, total })
+        .ok();
+    if let (Some(view_id), Some(hub), Some(percent)) =
+        (item.notification, item.hub.as_ref(), percent)
+    {
+        hub.send(Event::Notification(NotificationEvent::UpdateProgress(
+            view_id, percent,
+        )))
+        .ok();
+    }
+}
+
+#[cfg(not(test))]
+fn log_path() -> PathBuf {
+    PathBuf::from(DOWNLOADS_LOG_PATH)
+}
+
+#[cfg(test)]
+fn log_path() -> PathBuf {
+    std::env::temp_dir().join(DOWNLOADS_LOG_PATH)
+}
+
+/// Appends a message to the log, dropping its oldest lines.
+fn append_log(message: &str) {
+    let path = log_path();
+    let previous = fs::read_to_string(&path).unwrap_or_default();
+    let mut lines = previous
+        .lines()
+        .map(String::from)
+        .chain(std::iter::once(format!(
+            "{}\t{}",
+            Local::now().format("%Y-%m-%d %H:%M:%S"),
+            message
+        )))
+        .collect::<Vec<_>>();
+    let excess = lines.len().saturating_sub(MAX_LOG_LINES);
+    lines.drain(..excess);
+    lines.push(String::new());
+    if let Err(e) = fs::write(&path, lines.join("\n")) {
+        error!("Can't save the downloads log: {:#}.", e);
+    }
+}
+
+/// Renders the queue, followed by the log, the most recent messages first.
+pub fn queue_as_html() -> String {
+    let mut buf = "<html>\n\t<head>\n\t\t<title>Downloads</title>\n\t\t\
+                   <link rel=\"stylesheet\" type=\"text/css\" \
+                   href=\"css/sysinfo.css\"/>\n\t</head>\n\t<body>\n"
+        .to_string();
+
+    {
+        let queue = queue();
+        if queue.items.is_empty() {
+            buf.push_str("\t\t<p>No downloads in progress.</p>\n");
+        } else {
+            if queue.paused {
+                buf.push_str("\t\t<p>The downloads are paused.</p>\n");
+            }
+            buf.push_str("\t\t<table>\n");
+            for item in &queue.items {
+                let progress = match item.total {
+                    Some(total) => {
+                        format!("{} of {}", item.downloaded.human_size(), total.human_size())
+                    }
+                    None => item.downloaded.human_size(),
+                };
+                let status = match item.status {
+                    Status::Queued if queue.paused => "Paused".to_string(),
+                    Status::Queued => "Queued".to_string(),
+                    Status::Active => match item.percent() {
+                        Some(percent) => format!("{}%, {}", percent, progress),
+                        None => progress,
+                    },
+                    Status::Retrying(..) => format!("Retrying, {}", progress),
+                    Status::Paused => format!("Paused, {}", progress),
+                    Status::Failed(ref message) => format!("Failed: {}", message),
+                };
+                buf.push_str("\t\t\t<tr>\n");
+                buf.push_str(&format!(
+                    "\t\t\t\t<td class=\"key\">{}</td>\n",
+                    escape_html(&item.request.label)
+                ));
+                buf.push_str(&format!(
+                    "\t\t\t\t<td class=\"value\">{}</td>\n",
+                    escape_html(&status)
+                ));
+                buf.push_str("\t\t\t</tr>\n");
+            }
+            buf.push_str("\t\t</table>\n");
+        }
+    }
+
+    let log = fs::read_to_string(log_path()).unwrap_or_default();
+    if !log.trim().is_empty() {
+        buf.push_str("\t\t<h2>History</h2>\n\t\t<table>\n");
+        for line in log.lines().rev().filter(|line| !line.is_empty()) {
+            let (time, message) = line.split_once('\t').unwrap_or(("", line));
+            buf.push_str("\t\t\t<tr>\n");
+            buf.push_str(&format!(
+                "\t\t\t\t<td class=\"key\">{}</td>\n",
+                escape_html(time)
+            ));
+            buf.push_str(&format!(
+                "\t\t\t\t<td class=\"value\">{}</td>\n",
+                escape_html(message)
+            ));
+            buf.push_str("\t\t\t</tr>\n");
+        }
+        buf.push_str("\t\t</table>\n");
+    }
+
+    buf.push_str("\t</body>\n</html>");
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufRead;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_backoff() {
+        assert_eq!(backoff(1), Duration::from_secs(2));
+        assert_eq!(backoff(3), Duration::from_secs(8));
+        assert_eq!(backoff(12), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_partial_path() {
+        assert_eq!(
+            partial_path(Path::new("/mnt/onboard/Dune.epub")),
+            PathBuf::from("/mnt/onboard/.Dune.epub.part")
+        );
+    }
+
+    #[test]
+    fn test_resume_interrupted_download() {
+        rustls::crypto::ring::default_provider()
+            .install_default()
+            .ok();
+
+        let body = b"The spice must flow.".to_vec();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/dune.txt", listener.local_addr().unwrap());
+
+        let server_body = body.clone();
+        let server = thread::spawn(move || {
+            let mut ranges = Vec::new();
+            for (attempt, stream) in listener.incoming().take(2).enumerate() {
+                let mut stream = stream.unwrap();
+                let mut reader = std::io::BufReader::new(stream.try_clone().unwrap());
+                let mut range = None;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some(value) = line.to_lowercase().strip_prefix("range: bytes=") {
+                        range = value.trim().trim_end_matches('-').parse::<usize>().ok();
+                    }
+                }
+                ranges.push(range);
+                if attempt == 0 {
+                    // Announces the whole body but closes the connection midway.
+                    write!(
+                        stream,
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n",
+                        server_body.len()
+                    )
+                    .unwrap();
+                    stream.write_all(&server_body[..9]).unwrap();
+                } else {
+                    let start = range.unwrap_or(0);
+                    write!(
+                        stream,
+                        "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\n\r\n",
+                        server_body.len() - start
+                    )
+                    .unwrap();
+                    stream.write_all(&server_body[start..]).unwrap();
+                }
+            }
+            ranges
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dune.txt");
+        let mut request = Request::new("Dune", &url, path.clone());
+        request.quiet = true;
+        let mut retries = 0;
+        let result = enqueue(request, None).wait(|progress| {
+            if let Progress::Retrying { .. } = progress {
+                retries += 1;
+            }
+        });
+
+        assert_eq!(result.unwrap(), path);
+        assert_eq!(retries, 1);
+        assert_eq!(fs::read(&path).unwrap(), body);
+        assert_eq!(server.join().unwrap(), vec![None, Some(9)]);
+    }
+}
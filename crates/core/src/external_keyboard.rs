@@ -0,0 +1,428 @@
+//! The keyboards plugged in through USB.
+//!
+//! They show up as regular input devices, recognized by their bus type and by their letter
+//! keys. While a text field has the focus, the keys are typed into it, with the US layout.
+//! Otherwise, the arrows and the page keys turn the pages, like the buttons of a Bluetooth
+//! remote would, and the other keys drive the focus navigation.
+//!
+//! The emulator translates the keys of the host through the same mapping.
+
+use crate::geom::{CycleDir, LinearDir};
+use crate::input::{
+    DeviceEvent, InputEvent, EV_KEY, KEY_REMOTE_BACKWARD, KEY_REMOTE_FORWARD, VAL_RELEASE,
+};
+use crate::view::{Event, Hub, KeyboardEvent, TextKind};
+use anyhow::{Context, Error};
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io::Read;
+use std::mem::{self, MaybeUninit};
+use std::path::{Path, PathBuf};
+use std::slice;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tracing::{error, info};
+
+/// The bus type of the input devices connected over USB.
+pub const BUS_USB: u16 = 0x03;
+
+const SYS_DIR: &str = "/sys";
+const INPUT_DIR: &str = "/dev/input";
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+// Key codes
+const KEY_ESC: u16 = 1;
+const KEY_BACKSPACE: u16 = 14;
+const KEY_TAB: u16 = 15;
+const KEY_ENTER: u16 = 28;
+const KEY_LEFTCTRL: u16 = 29;
+const KEY_A: u16 = 30;
+const KEY_LEFTSHIFT: u16 = 42;
+const KEY_Z: u16 = 44;
+const KEY_RIGHTSHIFT: u16 = 54;
+const KEY_LEFTALT: u16 = 56;
+const KEY_SPACE: u16 = 57;
+const KEY_CAPSLOCK: u16 = 58;
+const KEY_RIGHTCTRL: u16 = 97;
+const KEY_RIGHTALT: u16 = 100;
+const KEY_HOME: u16 = 102;
+const KEY_UP: u16 = 103;
+const KEY_PAGEUP: u16 = 104;
+const KEY_LEFT: u16 = 105;
+const KEY_RIGHT: u16 = 106;
+const KEY_END: u16 = 107;
+const KEY_DOWN: u16 = 108;
+const KEY_PAGEDOWN: u16 = 109;
+const KEY_DELETE: u16 = 111;
+
+// The characters of the US layout, by rows of consecutive key codes.
+const CHAR_ROWS: [(u16, &str, &str); 5] = [
+    (2, "1234567890-=", "!@#$%^&*()_+"),
+    (16, "qwertyuiop[]", "QWERTYUIOP{}"),
+    (30, "asdfghjkl;'`", "ASDFGHJKL:\"~"),
+    (43, "\\zxcvbnm,./", "|ZXCVBNM<>?"),
+    (57, " ", " "),
+];
+
+// The key codes of the letters, in alphabetical order.
+const LETTER_CODES: [u16; 26] = [
+    30, 48, 46, 32, 18, 33, 34, 35, 23, 36, 37, 38, 50, 49, 24, 25, 16, 19, 31, 20, 22, 47, 17, 45,
+    21, 44,
+];
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub caps_lock: bool,
+}
+
+impl Modifiers {
+    /// Records the state of a modifier key, and returns whether the key was one.
+    pub fn update(&mut self, code: u16, value: i32) -> bool {
+        let pressed = value != VAL_RELEASE;
+        match code {
+            KEY_LEFTSHIFT | KEY_RIGHTSHIFT => self.shift = pressed,
+            KEY_LEFTCTRL | KEY_RIGHTCTRL => self.ctrl = pressed,
+            KEY_LEFTALT | KEY_RIGHTALT => self.alt = pressed,
+            KEY_CAPSLOCK => {
+                if value == 1 {
+                    self.caps_lock = !self.caps_lock;
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Returns the character typed by the given key.
+pub fn key_char(code: u16, modifiers: &Modifiers) -> Option<char> {
+    let (start, lower, upper) = CHAR_ROWS
+        .iter()
+        .find(|(start, lower, _)| (*start..*start + lower.len() as u16).contains(&code))?;
+    let index = (code - start) as usize;
+    let ch = lower.chars().nth(index)?;
+    let shifted = if ch.is_ascii_alphabetic() {
+        modifiers.shift != modifiers.caps_lock
+    } else {
+        modifiers.shift
+    };
+    if shifted {
+        upper.chars().nth(index)
+    } else {
+        Some(ch)
+    }
+}
+
+/// Returns the page-turn direction of a key, outside of the text fields.
+pub fn page_direction(code: u16) -> Option<LinearDir> {
+    match code {
+        KEY_LEFT | KEY_UP | KEY_PAGEUP | KEY_BACKSPACE => Some(LinearDir::Backward),
+        KEY_RIGHT | KEY_DOWN | KEY_PAGEDOWN | KEY_SPACE => Some(LinearDir::Forward),
+        _ => None,
+    }
+}
+
+/// Returns the event of a key press.
+///
+/// `typing` tells whether a text field has the focus. The page turns aren't handled here.
+pub fn key_event(code: u16, modifiers: &Modifiers, typing: bool) -> Option<Event> {
+    if !typing {
+        return match code {
+            KEY_TAB if modifiers.shift => Some(Event::MoveFocus(CycleDir::Previous)),
+            KEY_TAB => Some(Event::MoveFocus(CycleDir::Next)),
+            KEY_ENTER => Some(Event::ActivateFocus),
+            KEY_ESC => Some(Event::Back),
+            _ => None,
+        };
+    }
+
+    let unit = if modifiers.ctrl {
+        TextKind::Word
+    } else {
+        TextKind::Char
+    };
+    let kbd_evt = match code {
+        KEY_ENTER => KeyboardEvent::Submit,
+        KEY_ESC => return Some(Event::Focus(None)),
+        KEY_BACKSPACE => KeyboardEvent::Delete {
+            target: unit,
+            dir: LinearDir::Backward,
+        },
+        KEY_DELETE => KeyboardEvent::Delete {
+            target: unit,
+            dir: LinearDir::Forward,
+        },
+        KEY_LEFT => KeyboardEvent::Move {
+            target: unit,
+            dir: LinearDir::Backward,
+        },
+        KEY_RIGHT => KeyboardEvent::Move {
+            target: unit,
+            dir: LinearDir::Forward,
+        },
+        KEY_HOME => KeyboardEvent::Move {
+            target: TextKind::Extremum,
+            dir: LinearDir::Backward,
+        },
+        KEY_END => KeyboardEvent::Move {
+            target: TextKind::Extremum,
+            dir: LinearDir::Forward,
+        },
+        _ if modifiers.ctrl || modifiers.alt => return None,
+        _ => KeyboardEvent::Append(key_char(code, modifiers)?),
+    };
+    Some(Event::Keyboard(kbd_evt))
+}
+
+/// Returns the key code of a USB HID keyboard usage, as reported by the emulator's host.
+pub fn from_hid_usage(usage: u16) -> Option<u16> {
+    let code = match usage {
+        4..=29 => LETTER_CODES[(usage - 4) as usize],
+        30..=38 => usage - 28,
+        39 => 11,
+        40 => KEY_ENTER,
+        41 => KEY_ESC,
+        42 => KEY_BACKSPACE,
+        43 => KEY_TAB,
+        44 => KEY_SPACE,
+        45 => 12,
+        46 => 13,
+        47 => 26,
+        48 => 27,
+        49 => 43,
+        51 => 39,
+        52 => 40,
+        53 => 41,
+        54 => 51,
+        55 => 52,
+        56 => 53,
+        57 => KEY_CAPSLOCK,
+        74 => KEY_HOME,
+        75 => KEY_PAGEUP,
+        76 => KEY_DELETE,
+        77 => KEY_END,
+        78 => KEY_PAGEDOWN,
+        79 => KEY_RIGHT,
+        80 => KEY_LEFT,
+        81 => KEY_DOWN,
+        82 => KEY_UP,
+        224 => KEY_LEFTCTRL,
+        225 => KEY_LEFTSHIFT,
+        226 => KEY_LEFTALT,
+        228 => KEY_RIGHTCTRL,
+        229 => KEY_RIGHTSHIFT,
+        230 => KEY_RIGHTALT,
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Whether the given key is among the capabilities of an input device.
+///
+/// The capabilities are a bit field written as hexadecimal words, the most significant first.
+fn has_key(capabilities: &str, code: u16) -> bool {
+    let bits = usize::BITS as u16;
+    let words: Vec<&str> = capabilities.split_whitespace().collect();
+    let index = (code / bits) as usize;
+    index < words.len()
+        && usize::from_str_radix(words[words.len() - 1 - index], 16)
+            .is_ok_and(|word| word & (1 << (code % bits)) != 0)
+}
+
+/// Returns the event files of the keyboards connected over USB.
+fn keyboard_inputs(sys_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(sys_dir.join("class/input")) else {
+        return Vec::new();
+    };
+    let mut inputs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("event"))
+        .filter(|entry| {
+            let device = entry.path().join("device");
+            fs::read_to_string(device.join("id/bustype"))
+                .ok()
+                .and_then(|s| u16::from_str_radix(s.trim(), 16).ok())
+                == Some(BUS_USB)
+                && fs::read_to_string(device.join("capabilities/key"))
+                    .is_ok_and(|keys| has_key(&keys, KEY_A) && has_key(&keys, KEY_Z))
+        })
+        .map(|entry| Path::new(INPUT_DIR).join(entry.file_name()))
+        .collect();
+    inputs.sort();
+    inputs
+}
+
+/// Watches the connected keyboards and forwards their keys.
+pub struct Keyboards {
+    typing: Arc<AtomicBool>,
+}
+
+impl Keyboards {
+    /// The page turns are sent to `raw_tx`, along with the other input events, and the rest
+    /// of the keys to `hub`.
+    pub fn new(raw_tx: Sender<InputEvent>, hub: Hub) -> Keyboards {
+        let typing = Arc::new(AtomicBool::new(false));
+        let known = Arc::new(Mutex::new(BTreeSet::new()));
+        let typing2 = Arc::clone(&typing);
+        thread::spawn(move || loop {
+            for path in keyboard_inputs(Path::new(SYS_DIR)) {
+                if !known.lock().unwrap().insert(path.clone()) {
+                    continue;
+                }
+                info!("Keyboard connected: {}", path.display());
+                let raw_tx = raw_tx.clone();
+                let hub = hub.clone();
+                let known = Arc::clone(&known);
+                let typing = Arc::clone(&typing2);
+                thread::spawn(move || {
+                    if let Err(e) = read_keyboard(&path, &raw_tx, &hub, &typing) {
+                        error!("Can't read keyboard {}: {:#}", path.display(), e);
+                    }
+                    known.lock().unwrap().remove(&path);
+                });
+            }
+            thread::sleep(POLL_INTERVAL);
+        });
+        Keyboards { typing }
+    }
+
+    /// Tells whether a text field has the focus.
+    pub fn set_typing(&self, typing: bool) {
+        self.typing.store(typing, Ordering::Relaxed);
+    }
+}
+
+// Forwards the keys of a keyboard until it's disconnected.
+fn read_keyboard(
+    path: &Path,
+    raw_tx: &Sender<InputEvent>,
+    hub: &Hub,
+    typing: &AtomicBool,
+) -> Result<(), Error> {
+    let mut file =
+        File::open(path).with_context(|| format!("can't open input file {}", path.display()))?;
+    let mut modifiers = Modifiers::default();
+    let mut last_activity = -60;
+    loop {
+        let mut input_event = MaybeUninit::<InputEvent>::uninit();
+        let mut evt = unsafe {
+            let event_slice = slice::from_raw_parts_mut(
+                input_event.as_mut_ptr() as *mut u8,
+                mem::size_of::<InputEvent>(),
+            );
+            if file.read_exact(event_slice).is_err() {
+                info!("Keyboard disconnected: {}", path.display());
+                return Ok(());
+            }
+            input_event.assume_init()
+        };
+        if evt.kind != EV_KEY || modifiers.update(evt.code, evt.value) {
+            continue;
+        }
+        let typing = typing.load(Ordering::Relaxed);
+        if !typing {
+            if let Some(dir) = page_direction(evt.code) {
+                evt.code = match dir {
+                    LinearDir::Backward => KEY_REMOTE_BACKWARD,
+                    LinearDir::Forward => KEY_REMOTE_FORWARD,
+                };
+                raw_tx.send(evt).ok();
+                continue;
+            }
+        }
+        if evt.value == VAL_RELEASE {
+            continue;
+        }
+        if (evt.time.tv_sec - last_activity).abs() >= 60 {
+            last_activity = evt.time.tv_sec;
+            hub.send(Event::Device(DeviceEvent::UserActivity)).ok();
+        }
+        if let Some(kbd_evt) = key_event(evt.code, &modifiers, typing) {
+            hub.send(kbd_evt).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_chars() {
+        let mut modifiers = Modifiers::default();
+        assert_eq!(key_char(30, &modifiers), Some('a'));
+        assert_eq!(key_char(2, &modifiers), Some('1'));
+        assert_eq!(key_char(43, &modifiers), Some('\\'));
+        assert_eq!(key_char(KEY_SPACE, &modifiers), Some(' '));
+        assert_eq!(key_char(KEY_ENTER, &modifiers), None);
+
+        modifiers.update(KEY_LEFTSHIFT, 1);
+        assert_eq!(key_char(30, &modifiers), Some('A'));
+        assert_eq!(key_char(2, &modifiers), Some('!'));
+
+        modifiers.update(KEY_LEFTSHIFT, 0);
+        modifiers.update(KEY_CAPSLOCK, 1);
+        modifiers.update(KEY_CAPSLOCK, 0);
+        assert_eq!(key_char(53, &modifiers), Some('/'));
+        assert_eq!(key_char(44, &modifiers), Some('Z'));
+
+        for (usage, ch) in [(4, 'a'), (16, 'm'), (29, 'z'), (30, '1'), (39, '0')] {
+            let code = from_hid_usage(usage).unwrap();
+            assert_eq!(key_char(code, &Modifiers::default()), Some(ch));
+        }
+    }
+
+    #[test]
+    fn test_key_events_depend_on_the_focus() {
+        let ctrl = Modifiers {
+            ctrl: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            key_event(KEY_BACKSPACE, &ctrl, true),
+            Some(Event::Keyboard(KeyboardEvent::Delete {
+                target: TextKind::Word,
+                dir: LinearDir::Backward,
+            }))
+        ));
+        assert!(key_event(30, &ctrl, true).is_none());
+        assert!(matches!(
+            key_event(KEY_ENTER, &Modifiers::default(), false),
+            Some(Event::ActivateFocus)
+        ));
+        assert!(key_event(30, &Modifiers::default(), false).is_none());
+        assert_eq!(page_direction(KEY_PAGEDOWN), Some(LinearDir::Forward));
+    }
+
+    #[test]
+    fn test_keyboard_inputs_are_found_by_bus_type_and_keys() {
+        let sys_dir = tempfile::tempdir().unwrap();
+        // The keys from KEY_A to KEY_Z.
+        let letters = if usize::BITS == 64 {
+            "1fffc0000000\n"
+        } else {
+            "1fff c0000000\n"
+        };
+        for (name, bus_type, keys) in [
+            ("event0", "0019", letters),
+            ("event2", "0003", "0\n"),
+            ("event4", "0003", letters),
+        ] {
+            let dir = sys_dir.path().join("class/input").join(name).join("device");
+            fs::create_dir_all(dir.join("id")).unwrap();
+            fs::create_dir_all(dir.join("capabilities")).unwrap();
+            fs::write(dir.join("id/bustype"), format!("{}\n", bus_type)).unwrap();
+            fs::write(dir.join("capabilities/key"), keys).unwrap();
+        }
+        assert_eq!(
+            keyboard_inputs(sys_dir.path()),
+            vec![PathBuf::from("/dev/input/event4")]
+        );
+    }
+}
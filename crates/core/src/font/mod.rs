@@ -9,6 +9,7 @@ use crate::device::CURRENT_DEVICE;
 use crate::framebuffer::Framebuffer;
 use crate::geom::{Point, Vec2};
 use crate::helpers::IsHidden;
+use crate::unit::ui_scale;
 use anyhow::{format_err, Error};
 use bitflags::bitflags;
 use fxhash::FxHashMap;
@@ -779,7 +780,8 @@ pub fn font_from_style<'a>(fonts: &'a mut Fonts, style: &Style, dpi: u16) -> &'a
         Family::Keyboard => &mut fonts.keyboard,
         Family::Display => &mut fonts.display,
     };
-    font.set_size(style.size, dpi);
+    let size = (style.size as f32 * ui_scale()).round() as u32;
+    font.set_size(size, dpi);
     font
 }
 
@@ -1848,11 +1850,13 @@ impl Font {
             let len = hb_buffer_get_length(buf) as usize;
             let info = hb_buffer_get_glyph_infos(buf, ptr::null_mut());
             let pos = hb_buffer_get_glyph_positions(buf, ptr::null_mut());
+            let rtl = hb_buffer_get_direction(buf) == HB_DIRECTION_RTL;
             let mut glyphs = Vec::with_capacity(len);
 
             for i in 0..len {
-                let pos_i = &*pos.add(i);
-                let info_i = &*info.add(i);
+                let j = if rtl { len - 1 - i } else { i };
+                let pos_i = &*pos.add(j);
+                let info_i = &*info.add(j);
                 render_plan.width += pos_i.x_advance >> 6;
                 glyphs.push(GlyphPlan {
                     codepoint: info_i.codepoint,
@@ -1887,9 +1891,11 @@ impl Font {
                 -1,
             );
 
-            // If the direction is RTL, the clusters are given in reverse order.
-            hb_buffer_set_direction(buf, HB_DIRECTION_LTR);
+            // The direction is guessed from the first strong character: Arabic and Hebrew
+            // texts are shaped from right to left, and their glyphs, given in visual order,
+            // are put back in logical order below.
             hb_buffer_guess_segment_properties(buf);
+            let rtl = hb_buffer_get_direction(buf) == HB_DIRECTION_RTL;
 
             let features_vec: Vec<HbFeature> = features
                 .map(|ftr| {
@@ -1921,12 +1927,16 @@ impl Font {
             let len = hb_buffer_get_length(buf) as usize;
             let info = hb_buffer_get_glyph_infos(buf, ptr::null_mut());
             let pos = hb_buffer_get_glyph_positions(buf, ptr::null_mut());
-            let mut render_plan = RenderPlan::default();
+            let mut render_plan = RenderPlan {
+                rtl,
+                ..Default::default()
+            };
             let mut missing_glyphs = Vec::new();
 
             for i in 0..len {
-                let pos_i = &*pos.add(i);
-                let info_i = &*info.add(i);
+                let j = if rtl { len - 1 - i } else { i };
+                let pos_i = &*pos.add(j);
+                let info_i = &*info.add(j);
                 if info_i.codepoint == 0 {
                     if let Some((start, end)) = missing_glyphs.pop() {
                         if i == end {
@@ -2128,11 +2138,52 @@ impl Font {
         render_plan: &RenderPlan,
         origin: Point,
     ) {
+        self.render_glyphs(fb, color, render_plan, origin, |_| false);
+    }
+
+    /// Renders text meant to be rotated clockwise, as vertical text is.
+    ///
+    /// The glyphs of the upright scripts, e.g. kana and kanji, are rotated counterclockwise
+    /// so that they end up upright, the others end up sideways.
+    pub fn render_vertical(
+        &mut self,
+        fb: &mut dyn Framebuffer,
+        color: Color,
+        render_plan: &RenderPlan,
+        text: &str,
+        origin: Point,
+    ) {
+        self.render_glyphs(fb, color, render_plan, origin, |cluster| {
+            text.get(cluster..)
+                .and_then(|s| s.chars().next())
+                .is_some_and(is_upright)
+        });
+    }
+
+    fn render_glyphs<F>(
+        &mut self,
+        fb: &mut dyn Framebuffer,
+        color: Color,
+        render_plan: &RenderPlan,
+        origin: Point,
+        upright: F,
+    ) where
+        F: Fn(usize) -> bool,
+    {
+        let center_dy = (self.ascender() + self.descender()) / 2;
         unsafe {
-            let mut pos = origin;
+            let mut pos = if render_plan.rtl {
+                origin + pt!(render_plan.width, 0)
+            } else {
+                origin
+            };
             let mut fallback_faces = FxHashMap::default();
 
             for (index, glyph) in render_plan.glyphs.iter().enumerate() {
+                if render_plan.rtl {
+                    pos -= glyph.advance;
+                }
+
                 let face = if let Some(script) = render_plan.scripts.get(&index) {
                     *fallback_faces.entry(script).or_insert_with(|| {
                         let font_data = font_data_from_script(*script);
@@ -2161,17 +2212,25 @@ impl Font {
                 let top_left =
                     pos + glyph.offset + pt!((*glyph_slot).bitmap_left, -(*glyph_slot).bitmap_top);
                 let bitmap = &(*glyph_slot).bitmap;
+                // The center of the glyph's cell, around which upright glyphs are rotated.
+                let center =
+                    upright(glyph.cluster).then(|| pos + pt!(glyph.advance.x / 2, -center_dy));
 
                 for y in 0..bitmap.rows {
                     for x in 0..bitmap.width {
                         let blackness = *bitmap.buffer.offset((bitmap.pitch * y + x) as isize);
                         let alpha = blackness as f32 / 255.0;
-                        let pt = top_left + pt!(x, y);
+                        let mut pt = top_left + pt!(x, y);
+                        if let Some(c) = center {
+                            pt = c + pt!(pt.y - c.y, c.x - pt.x);
+                        }
                         fb.set_blended_pixel(pt.x as u32, pt.y as u32, color, alpha);
                     }
                 }
 
-                pos += glyph.advance;
+                if !render_plan.rtl {
+                    pos += glyph.advance;
+                }
             }
 
             for (_, face) in fallback_faces {
@@ -2213,11 +2272,25 @@ struct GlyphPlan {
     advance: Point,
 }
 
+/// Tells whether a character stays upright in vertical text.
+pub fn is_upright(c: char) -> bool {
+    matches!(c,
+        '\u{1100}'..='\u{11FF}' // Hangul Jamo.
+        | '\u{2E80}'..='\u{A4CF}' // CJK radicals to Yi.
+        | '\u{AC00}'..='\u{D7AF}' // Hangul syllables.
+        | '\u{F900}'..='\u{FAFF}' // CJK compatibility ideographs.
+        | '\u{FE30}'..='\u{FE4F}' // CJK compatibility forms.
+        | '\u{FF00}'..='\u{FFEF}' // Halfwidth and fullwidth forms.
+        | '\u{20000}'..='\u{3FFFF}') // Supplementary ideographic planes.
+}
+
 #[derive(Debug, Clone)]
 pub struct RenderPlan {
     pub width: i32,
     scripts: FxHashMap<usize, HbScript>,
+    // The glyphs are kept in logical order, those of a right-to-left text are drawn backwards.
     glyphs: Vec<GlyphPlan>,
+    rtl: bool,
 }
 
 impl Default for RenderPlan {
@@ -2226,6 +2299,7 @@ impl Default for RenderPlan {
             width: 0,
             scripts: FxHashMap::default(),
             glyphs: Vec::new(),
+            rtl: false,
         }
     }
 }
@@ -2247,6 +2321,7 @@ impl RenderPlan {
             width,
             scripts,
             glyphs,
+            rtl: self.rtl,
         }
     }
 
@@ -2280,6 +2355,7 @@ impl RenderPlan {
             width: next_width,
             scripts: next_scripts,
             glyphs: next_glyphs,
+            rtl: self.rtl,
         }
     }
 
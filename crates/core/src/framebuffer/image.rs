@@ -1,11 +1,15 @@
+use super::transform::{nearest_g16, DITHER_G16_DRIFTS};
 use super::{Framebuffer, UpdateMode};
 use crate::color::{Color, WHITE};
 use crate::geom::{lerp, Rectangle};
+use crate::settings::DitheringKind;
 use anyhow::{format_err, Context, Error};
 use std::fs::File;
 use std::io::BufReader;
 use std::path::Path;
 
+const BAYER_MATRIX: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
 #[derive(Debug, Clone)]
 pub struct Pixmap {
     pub width: u32,
@@ -65,6 +69,147 @@ impl Pixmap {
         Ok(pixmap)
     }
 
+    /// Returns a copy of the pixmap rotated clockwise by the given number of quarter turns.
+    pub fn rotate(&self, quarter_turns: i8) -> Pixmap {
+        let (width, height) = (self.width, self.height);
+        let quarter_turns = quarter_turns.rem_euclid(4);
+
+        if quarter_turns == 0 || self.data.is_empty() {
+            return self.clone();
+        }
+
+        let mut result = if quarter_turns % 2 == 0 {
+            Pixmap::new(width, height, self.samples)
+        } else {
+            Pixmap::new(height, width, self.samples)
+        };
+
+        for y in 0..height {
+            for x in 0..width {
+                let (rx, ry) = match quarter_turns {
+                    1 => (height - 1 - y, x),
+                    2 => (width - 1 - x, height - 1 - y),
+                    _ => (y, width - 1 - x),
+                };
+                result.set_pixel(rx, ry, self.get_pixel(x, y));
+            }
+        }
+
+        result
+    }
+
+    /// Returns a copy of the pixmap shrunk to fit in the given dimensions, keeping its aspect
+    /// ratio. Each pixel of the copy is the average of the pixels it covers.
+    pub fn scale_to_fit(&self, width: u32, height: u32) -> Pixmap {
+        let scale = (width as f32 / self.width as f32)
+            .min(height as f32 / self.height as f32)
+            .min(1.0);
+
+        if scale >= 1.0 || self.data.is_empty() {
+            return self.clone();
+        }
+
+        let (w, h) = (
+            ((self.width as f32 * scale) as u32).max(1),
+            ((self.height as f32 * scale) as u32).max(1),
+        );
+        let samples = self.samples;
+        let mut result = Pixmap::new(w, h, samples);
+
+        for y in 0..h {
+            let y_min = y * self.height / h;
+            let y_max = ((y + 1) * self.height / h).max(y_min + 1);
+            for x in 0..w {
+                let x_min = x * self.width / w;
+                let x_max = ((x + 1) * self.width / w).max(x_min + 1);
+                let count = (y_max - y_min) * (x_max - x_min);
+                for s in 0..samples {
+                    let mut sum = 0;
+                    for sy in y_min..y_max {
+                        for sx in x_min..x_max {
+                            sum += self.data[samples * (sy * self.width + sx) as usize + s] as u32;
+                        }
+                    }
+                    result.data[samples * (y * w + x) as usize + s] = (sum / count) as u8;
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Returns a copy of the pixmap where each pixel is repeated in a square of the given side,
+    /// which keeps the sharp edges of pixel art.
+    pub fn enlarge(&self, factor: u32) -> Pixmap {
+        if factor <= 1 || self.data.is_empty() {
+            return self.clone();
+        }
+
+        let samples = self.samples;
+        let mut result = Pixmap::new(self.width * factor, self.height * factor, samples);
+
+        for y in 0..result.height {
+            for x in 0..result.width {
+                let src = samples * ((y / factor) * self.width + x / factor) as usize;
+                let dst = samples * (y * result.width + x) as usize;
+                result.data[dst..dst + samples].copy_from_slice(&self.data[src..src + samples]);
+            }
+        }
+
+        result
+    }
+
+    /// Reduces each sample to the sixteen levels of the screen, with the given dithering.
+    pub fn dither(&mut self, kind: DitheringKind) {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let samples = self.samples;
+
+        match kind {
+            DitheringKind::None => (),
+            DitheringKind::Ordered | DitheringKind::BlueNoise => {
+                for y in 0..height {
+                    for x in 0..width {
+                        let drift = if kind == DitheringKind::Ordered {
+                            // Map {0 .. 15} to {-8 .. 8}.
+                            ((2 * BAYER_MATRIX[y % 4][x % 4] as i16 + 1) * 17 / 32 - 8) as i8
+                        } else {
+                            DITHER_G16_DRIFTS[(x % 128) + (y % 128) * 128]
+                        };
+                        let addr = samples * (y * width + x);
+                        for c in &mut self.data[addr..addr + samples] {
+                            *c = nearest_g16(*c, drift);
+                        }
+                    }
+                }
+            }
+            DitheringKind::FloydSteinberg => {
+                let mut levels: Vec<i16> = self.data.iter().map(|&c| c as i16).collect();
+                for y in 0..height {
+                    for x in 0..width {
+                        for s in 0..samples {
+                            let addr = samples * (y * width + x) + s;
+                            let level = levels[addr].clamp(0, 255);
+                            let nearest = nearest_g16(level as u8, 0);
+                            let error = level - nearest as i16;
+                            self.data[addr] = nearest;
+                            let mut spread = |dx: isize, dy: usize, weight: i16| {
+                                let nx = x as isize + dx;
+                                if nx >= 0 && (nx as usize) < width && y + dy < height {
+                                    let naddr = samples * ((y + dy) * width + nx as usize) + s;
+                                    levels[naddr] += error * weight / 16;
+                                }
+                            };
+                            spread(1, 0, 7);
+                            spread(-1, 1, 3);
+                            spread(0, 1, 5);
+                            spread(1, 1, 1);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
     #[inline]
     pub fn get_pixel(&self, x: u32, y: u32) -> Color {
         if self.data.is_empty() {
@@ -154,6 +299,26 @@ impl Framebuffer for Pixmap {
         }
     }
 
+    fn tint_region(&mut self, rect: &Rectangle, color: Color) {
+        if self.data.is_empty() {
+            return;
+        }
+        let rgb = color.rgb();
+        let gray = color.gray();
+        for y in rect.min.y..rect.max.y {
+            for x in rect.min.x..rect.max.x {
+                let addr = self.samples * (y * self.width as i32 + x) as usize;
+                if self.samples == 1 {
+                    self.data[addr] = (self.data[addr] as u16 * gray as u16 / 255) as u8;
+                } else {
+                    for (i, c) in self.data[addr..addr + 3].iter_mut().enumerate() {
+                        *c = (*c as u16 * rgb[i] as u16 / 255) as u8;
+                    }
+                }
+            }
+        }
+    }
+
     fn update(&mut self, _rect: &Rectangle, _mode: UpdateMode) -> Result<u32, Error> {
         Ok(1)
     }
@@ -215,3 +380,144 @@ impl Framebuffer for Pixmap {
         self.height
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::{BLACK, GRAY08};
+    use crate::geom::{BorderSpec, CornerSpec};
+
+    #[test]
+    fn test_rotate_quarter_turn() {
+        let mut pixmap = Pixmap::new(3, 2, 1);
+        pixmap.set_pixel(0, 0, BLACK);
+        pixmap.set_pixel(2, 1, GRAY08);
+
+        let rotated = pixmap.rotate(1);
+
+        assert_eq!((rotated.width, rotated.height), (2, 3));
+        assert_eq!(rotated.get_pixel(1, 0), BLACK);
+        assert_eq!(rotated.get_pixel(0, 2), GRAY08);
+        assert_eq!(rotated.rotate(-1).data, pixmap.data);
+    }
+
+    #[test]
+    fn test_scale_to_fit_averages_pixels() {
+        let mut pixmap = Pixmap::new(4, 2, 1);
+        pixmap.set_pixel(0, 0, BLACK);
+        pixmap.set_pixel(1, 1, BLACK);
+
+        let scaled = pixmap.scale_to_fit(2, 2);
+
+        assert_eq!((scaled.width, scaled.height), (2, 1));
+        assert_eq!(scaled.get_pixel(0, 0), Color::Gray(127));
+        assert_eq!(scaled.get_pixel(1, 0), WHITE);
+        assert_eq!(pixmap.scale_to_fit(8, 8).data, pixmap.data);
+    }
+
+    #[test]
+    fn test_enlarge_repeats_pixels() {
+        let mut pixmap = Pixmap::new(2, 1, 1);
+        pixmap.set_pixel(1, 0, BLACK);
+
+        let enlarged = pixmap.enlarge(3);
+
+        assert_eq!((enlarged.width, enlarged.height), (6, 3));
+        assert_eq!(enlarged.get_pixel(2, 2), WHITE);
+        assert_eq!(enlarged.get_pixel(3, 0), BLACK);
+        assert_eq!(enlarged.get_pixel(5, 2), BLACK);
+    }
+
+    #[test]
+    fn test_rotate_full_turn_is_identity() {
+        let mut pixmap = Pixmap::new(3, 2, 1);
+        pixmap.set_pixel(1, 0, BLACK);
+
+        let rotated = (0..4).fold(pixmap.clone(), |p, _| p.rotate(1));
+
+        assert_eq!(rotated.data, pixmap.data);
+    }
+
+    #[test]
+    fn test_tint_keeps_the_colors_of_rgb_pixmaps() {
+        let tint = Color::Rgb(0xFF, 0xCC, 0x00);
+        let mut rgb = Pixmap::new(2, 1, 3);
+        rgb.set_pixel(1, 0, Color::Rgb(0x88, 0x88, 0x88));
+        rgb.tint_region(&rect![0, 0, 1, 1], tint);
+        assert_eq!(rgb.get_pixel(0, 0), tint);
+        assert_eq!(rgb.get_pixel(1, 0), Color::Rgb(0x88, 0x88, 0x88));
+
+        let mut gray = Pixmap::new(1, 1, 1);
+        gray.tint_region(&rect![0, 0, 1, 1], tint);
+        assert_eq!(gray.get_pixel(0, 0), Color::Gray(tint.gray()));
+    }
+
+    #[test]
+    fn test_antialiased_edges_are_blended() {
+        let is_gray = |c: Color| c != BLACK && c != WHITE;
+
+        let mut pixmap = Pixmap::new(40, 40, 1);
+        pixmap.draw_line(pt!(2, 5), pt!(37, 30), 3.0, BLACK);
+        assert_eq!(pixmap.get_pixel(20, 18), BLACK);
+        assert_eq!(pixmap.get_pixel(20, 30), WHITE);
+        assert!((0..40).any(|y| is_gray(pixmap.get_pixel(20, y))));
+
+        let mut pixmap = Pixmap::new(40, 40, 1);
+        pixmap.draw_circle(pt!(20, 20), 15, 2.0, BLACK);
+        assert_eq!(pixmap.get_pixel(20, 20), WHITE);
+        assert_eq!(pixmap.get_pixel(20, 33), BLACK);
+        assert!(is_gray(pixmap.get_pixel(30, 30)));
+
+        let mut pixmap = Pixmap::new(40, 40, 1);
+        let border = BorderSpec {
+            thickness: 2,
+            color: BLACK,
+        };
+        pixmap.draw_rounded_rectangle_outline(
+            &rect![0, 0, 40, 40],
+            &CornerSpec::Uniform(10),
+            &border,
+        );
+        assert_eq!(pixmap.get_pixel(20, 0), BLACK);
+        assert_eq!(pixmap.get_pixel(20, 20), WHITE);
+        assert_eq!(pixmap.get_pixel(0, 0), WHITE);
+        assert!((0..10).any(|i| is_gray(pixmap.get_pixel(i, i))));
+    }
+
+    #[test]
+    fn test_dithering_keeps_the_average_level() {
+        let mut pixmap = Pixmap::new(8, 8, 1);
+        pixmap.data.fill(0x60);
+
+        for kind in [DitheringKind::Ordered, DitheringKind::FloydSteinberg] {
+            let mut dithered = pixmap.clone();
+            dithered.dither(kind);
+            assert!(dithered.data.iter().all(|&c| c % 17 == 0));
+            let average = dithered.data.iter().map(|&c| c as u32).sum::<u32>() / 64;
+            assert!(average.abs_diff(0x60) <= 4, "{:?}: {}", kind, average);
+        }
+
+        let mut rounded = pixmap.clone();
+        rounded.dither(DitheringKind::None);
+        assert_eq!(rounded.data, pixmap.data);
+    }
+
+    #[test]
+    fn test_brightness_offsets_and_clamps() {
+        let mut pixmap = Pixmap::new(3, 1, 1);
+        pixmap.set_pixel(0, 0, BLACK);
+        pixmap.set_pixel(1, 0, Color::Gray(100));
+        let rect = pixmap.rect();
+        let levels = |brightness: f32| {
+            let mut fb = Pixmap::new(3, 1, 1);
+            fb.draw_framed_pixmap_contrast(&pixmap, &rect, pt!(0, 0), 1.0, 224.0, brightness);
+            fb.data
+        };
+
+        assert_eq!(levels(0.0), pixmap.data);
+        assert_eq!(levels(25.5), [25, 125, 255]);
+        assert_eq!(levels(-25.5), [0, 74, 229]);
+        assert_eq!(levels(255.0), [255; 3]);
+        assert_eq!(levels(-255.0), [0; 3]);
+    }
+}
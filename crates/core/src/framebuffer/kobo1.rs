@@ -161,6 +161,16 @@ impl Framebuffer for KoboFramebuffer1 {
         }
     }
 
+    fn tint_region(&mut self, rect: &Rectangle, color: Color) {
+        for y in rect.min.y..rect.max.y {
+            for x in rect.min.x..rect.max.x {
+                let mut c = Color::from_rgb(&(self.get_pixel_rgb)(self, x as u32, y as u32));
+                c.tint(color);
+                (self.set_pixel_rgb)(self, x as u32, y as u32, c.rgb());
+            }
+        }
+    }
+
     // Tell the driver that the screen needs to be redrawn.
     fn update(&mut self, rect: &Rectangle, mode: UpdateMode) -> Result<u32, Error> {
         let update_marker = self.token;
@@ -210,6 +220,15 @@ impl Framebuffer for KoboFramebuffer1 {
                     (UPDATE_MODE_PARTIAL, NTX_WFM_MODE_A2)
                 }
             }
+            UpdateMode::Interactive => {
+                monochrome = true;
+                dithered = true;
+                if mark >= 11 {
+                    (UPDATE_MODE_PARTIAL, HWTCON_WAVEFORM_MODE_A2)
+                } else {
+                    (UPDATE_MODE_PARTIAL, NTX_WFM_MODE_A2)
+                }
+            }
         };
 
         if monochrome {
@@ -247,7 +266,8 @@ impl Framebuffer for KoboFramebuffer1 {
         let result = if mark >= 11 {
             let mut dither_mode = 0;
 
-            if dithered {
+            // The controller dithers the luminance: the colors are dithered by the transform.
+            if dithered && (monochrome || color_samples == 1) {
                 flags |= HWTCON_FLAG_USE_DITHERING;
                 if monochrome {
                     dither_mode = HWTCON_FLAG_USE_DITHERING_Y8_Y1_S
@@ -429,7 +449,13 @@ impl Framebuffer for KoboFramebuffer1 {
 
         self.dithered = enable;
 
-        if CURRENT_DEVICE.mark() < 7 {
+        if CURRENT_DEVICE.color_samples() > 1 {
+            if enable {
+                self.transform = transform_dither_rgb16;
+            } else {
+                self.transform = transform_identity;
+            }
+        } else if CURRENT_DEVICE.mark() < 7 {
             if enable {
                 self.transform = transform_dither_g16;
             } else {
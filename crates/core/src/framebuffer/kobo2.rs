@@ -280,6 +280,16 @@ impl Framebuffer for KoboFramebuffer2 {
         }
     }
 
+    fn tint_region(&mut self, rect: &Rectangle, color: Color) {
+        for y in rect.min.y..rect.max.y {
+            for x in rect.min.x..rect.max.x {
+                let mut c = self.get_pixel(x as u32, y as u32);
+                c.tint(color);
+                self.set_pixel(x as u32, y as u32, c);
+            }
+        }
+    }
+
     // Tell the driver that the screen needs to be redrawn.
     fn update(&mut self, rect: &Rectangle, mode: UpdateMode) -> Result<u32, Error> {
         let mut flags = 0;
@@ -297,6 +307,10 @@ impl Framebuffer for KoboFramebuffer2 {
                 flags |= EINK_MONOCHROME;
                 EINK_A2_MODE
             }
+            UpdateMode::Interactive => {
+                flags |= EINK_DITHERING_Y1;
+                EINK_A2_MODE
+            }
         };
 
         if self.inverted {
@@ -29,6 +29,9 @@ pub enum UpdateMode {
     Full,
     Fast,
     FastMono,
+    /// The frames of a continuous interaction, e.g. dragging or typing: fast and dithered to
+    /// black and white. The view follows up with a quality update when the interaction ends.
+    Interactive,
 }
 
 pub trait Framebuffer {
@@ -36,6 +39,7 @@ pub trait Framebuffer {
     fn set_blended_pixel(&mut self, x: u32, y: u32, color: Color, alpha: f32);
     fn invert_region(&mut self, rect: &Rectangle);
     fn shift_region(&mut self, rect: &Rectangle, drift: u8);
+    fn tint_region(&mut self, rect: &Rectangle, color: Color);
     fn update(&mut self, rect: &Rectangle, mode: UpdateMode) -> Result<u32, Error>;
     fn wait(&self, token: u32) -> Result<i32, Error>;
     fn save(&self, path: &str) -> Result<(), Error>;
@@ -138,6 +142,32 @@ pub trait Framebuffer {
         );
     }
 
+    /// Draws the ring surrounding the view focused by the button navigation.
+    ///
+    /// The ring is an outline of `color` along the inside of `rect`, lined with a thinner
+    /// outline of the inverse color so that it stands out from the borders of the view.
+    fn draw_focus_ring(&mut self, rect: &Rectangle, thickness: u16, color: Color) {
+        self.draw_rectangle_outline(rect, &BorderSpec { thickness, color });
+        let inset = thickness as i32;
+        let inner = rect![
+            rect.min.x + inset,
+            rect.min.y + inset,
+            rect.max.x - inset,
+            rect.max.y - inset
+        ];
+        if !inner.is_empty() {
+            let mut inverse = color;
+            inverse.invert();
+            self.draw_rectangle_outline(
+                &inner,
+                &BorderSpec {
+                    thickness: (thickness / 2).max(1),
+                    color: inverse,
+                },
+            );
+        }
+    }
+
     fn draw_pixmap(&mut self, pixmap: &Pixmap, pt: Point) {
         for y in 0..pixmap.height {
             for x in 0..pixmap.width {
@@ -167,8 +197,9 @@ pub trait Framebuffer {
         pt: Point,
         exponent: f32,
         gray: f32,
+        brightness: f32,
     ) {
-        if (exponent - 1.0).abs() < f32::EPSILON {
+        if (exponent - 1.0).abs() < f32::EPSILON && brightness.abs() < f32::EPSILON {
             self.draw_framed_pixmap(pixmap, rect, pt);
             return;
         }
@@ -181,13 +212,14 @@ pub trait Framebuffer {
                 let raw_color = pixmap.get_pixel(x as u32, y as u32);
                 let color = raw_color.apply(|comp| {
                     let c = comp as f32;
-                    if c < gray {
-                        (gray * (c / gray).powf(exponent)) as u8
+                    let c = if c < gray {
+                        gray * (c / gray).powf(exponent)
                     } else if c > gray {
-                        (gray + rem_gray * ((c - gray) / rem_gray).powf(inv_exponent)) as u8
+                        gray + rem_gray * ((c - gray) / rem_gray).powf(inv_exponent)
                     } else {
-                        gray as u8
-                    }
+                        gray
+                    };
+                    (c + brightness).clamp(0.0, 255.0) as u8
                 });
                 self.set_pixel(px as u32, py as u32, color);
             }
@@ -224,19 +256,7 @@ pub trait Framebuffer {
     }
 
     fn draw_rounded_rectangle(&mut self, rect: &Rectangle, corners: &CornerSpec, color: Color) {
-        let (nw, ne, se, sw) = match *corners {
-            CornerSpec::Uniform(v) => (v, v, v, v),
-            CornerSpec::North(v) => (v, v, 0, 0),
-            CornerSpec::East(v) => (0, v, v, 0),
-            CornerSpec::South(v) => (0, 0, v, v),
-            CornerSpec::West(v) => (v, 0, 0, v),
-            CornerSpec::Detailed {
-                north_west,
-                north_east,
-                south_east,
-                south_west,
-            } => (north_west, north_east, south_east, south_west),
-        };
+        let (nw, ne, se, sw) = corner_radii(corners);
         let nw_c = rect.min + nw;
         let ne_c = pt!(rect.max.x - ne, rect.min.y + ne);
         let se_c = rect.max - se;
@@ -272,19 +292,7 @@ pub trait Framebuffer {
         border: &BorderSpec,
         color: &dyn ColorSource,
     ) {
-        let (nw, ne, se, sw) = match *corners {
-            CornerSpec::Uniform(v) => (v, v, v, v),
-            CornerSpec::North(v) => (v, v, 0, 0),
-            CornerSpec::East(v) => (0, v, v, 0),
-            CornerSpec::South(v) => (0, 0, v, v),
-            CornerSpec::West(v) => (v, 0, 0, v),
-            CornerSpec::Detailed {
-                north_west,
-                north_east,
-                south_east,
-                south_west,
-            } => (north_west, north_east, south_east, south_west),
-        };
+        let (nw, ne, se, sw) = corner_radii(corners);
 
         let BorderSpec {
             thickness: border_thickness,
@@ -447,4 +455,178 @@ pub trait Framebuffer {
             }
         }
     }
+
+    /// Draws a line of the given thickness with rounded caps, blending the pixels of its edges
+    /// with their coverage.
+    fn draw_line(&mut self, start: Point, end: Point, thickness: f32, color: Color) {
+        let half_thickness = thickness / 2.0;
+        let rect = Rectangle::from_segment(
+            start,
+            end,
+            half_thickness.ceil() as i32 + 1,
+            half_thickness.ceil() as i32 + 1,
+        );
+        let a = vec2!(start.x as f32, start.y as f32) + 0.5;
+        let b = vec2!(end.x as f32, end.y as f32) + 0.5;
+
+        for y in rect.min.y..rect.max.y {
+            for x in rect.min.x..rect.max.x {
+                let p = vec2!(x as f32, y as f32) + 0.5;
+                let (n, _) = nearest_segment_point(p, a, b);
+                let v = p - n;
+                let alpha = surface_area(v.length() - half_thickness, v.angle());
+                if alpha > 0.0 {
+                    self.set_blended_pixel(x as u32, y as u32, color, alpha);
+                }
+            }
+        }
+    }
+
+    /// Draws the outline of a circle: the ring between `radius - thickness` and `radius`.
+    fn draw_circle(&mut self, center: Point, radius: i32, thickness: f32, color: Color) {
+        let rect = Rectangle::from_disk(center, radius + 1);
+        let inner_radius = (radius as f32 - thickness).max(0.0);
+
+        for y in rect.min.y..rect.max.y {
+            for x in rect.min.x..rect.max.x {
+                let v = vec2!((x - center.x) as f32, (y - center.y) as f32) + 0.5;
+                let angle = v.angle();
+                let dist = v.length();
+                let alpha = surface_area(dist - radius as f32, angle)
+                    - surface_area(dist - inner_radius, angle);
+                if alpha > 0.0 {
+                    self.set_blended_pixel(x as u32, y as u32, color, alpha);
+                }
+            }
+        }
+    }
+
+    /// Darkens the pixels of the rectangle that lie outside of the holes by blending them with
+    /// black.
+    fn dim_rectangle(&mut self, rect: &Rectangle, holes: &[Rectangle], alpha: f32) {
+        for y in rect.min.y..rect.max.y {
+            for x in rect.min.x..rect.max.x {
+                if holes.iter().any(|hole| hole.includes(pt!(x, y))) {
+                    continue;
+                }
+                self.set_blended_pixel(x as u32, y as u32, BLACK, alpha);
+            }
+        }
+    }
+
+    /// Draws the soft shadow cast by the rectangle, within *clip*. The shadow is shifted
+    /// downwards by half of its *size*, over which it fades out, and leaves the rectangle
+    /// untouched.
+    fn draw_shadow(&mut self, rect: &Rectangle, clip: &Rectangle, size: i32, alpha: f32) {
+        if size <= 0 {
+            return;
+        }
+        let offset = size / 2;
+        let shadow = rect![
+            rect.min.x,
+            rect.min.y + offset,
+            rect.max.x,
+            rect.max.y + offset
+        ];
+        let Some(area) = rect![shadow.min - size, shadow.max + size].intersection(clip) else {
+            return;
+        };
+
+        for y in area.min.y..area.max.y {
+            for x in area.min.x..area.max.x {
+                if rect.includes(pt!(x, y)) {
+                    continue;
+                }
+                let dx = (shadow.min.x - x).max(x + 1 - shadow.max.x).max(0);
+                let dy = (shadow.min.y - y).max(y + 1 - shadow.max.y).max(0);
+                let dist = ((dx * dx + dy * dy) as f32).sqrt();
+                if dist >= size as f32 {
+                    continue;
+                }
+                let fade = 1.0 - dist / size as f32;
+                self.set_blended_pixel(x as u32, y as u32, BLACK, alpha * fade * fade);
+            }
+        }
+    }
+
+    /// Draws the border of a rounded rectangle, leaving its inside untouched.
+    fn draw_rounded_rectangle_outline(
+        &mut self,
+        rect: &Rectangle,
+        corners: &CornerSpec,
+        border: &BorderSpec,
+    ) {
+        let (nw, ne, se, sw) = corner_radii(corners);
+        let thickness = border.thickness as i32;
+        let inner_rect = rect![rect.min + thickness, rect.max - thickness];
+        let inner_radii = (
+            (nw - thickness).max(0),
+            (ne - thickness).max(0),
+            (se - thickness).max(0),
+            (sw - thickness).max(0),
+        );
+
+        for y in rect.min.y..rect.max.y {
+            for x in rect.min.x..rect.max.x {
+                let mut alpha = rounded_rectangle_coverage(x, y, rect, (nw, ne, se, sw));
+                if !inner_rect.is_empty() {
+                    alpha -= rounded_rectangle_coverage(x, y, &inner_rect, inner_radii);
+                }
+                if alpha > 0.0 {
+                    self.set_blended_pixel(x as u32, y as u32, border.color, alpha);
+                }
+            }
+        }
+    }
+}
+
+// Returns the radii of the north-west, north-east, south-east and south-west corners.
+fn corner_radii(corners: &CornerSpec) -> (i32, i32, i32, i32) {
+    match *corners {
+        CornerSpec::Uniform(v) => (v, v, v, v),
+        CornerSpec::North(v) => (v, v, 0, 0),
+        CornerSpec::East(v) => (0, v, v, 0),
+        CornerSpec::South(v) => (0, 0, v, v),
+        CornerSpec::West(v) => (v, 0, 0, v),
+        CornerSpec::Detailed {
+            north_west,
+            north_east,
+            south_east,
+            south_west,
+        } => (north_west, north_east, south_east, south_west),
+    }
+}
+
+// The fraction of the pixel at (x, y) covered by the rounded rectangle.
+fn rounded_rectangle_coverage(
+    x: i32,
+    y: i32,
+    rect: &Rectangle,
+    (nw, ne, se, sw): (i32, i32, i32, i32),
+) -> f32 {
+    if !rect.includes(pt!(x, y)) {
+        return 0.0;
+    }
+    let nw_c = rect.min + nw;
+    let ne_c = pt!(rect.max.x - ne, rect.min.y + ne);
+    let se_c = rect.max - se;
+    let sw_c = pt!(rect.min.x + sw, rect.max.y - sw);
+    let pole = if x < nw_c.x && y < nw_c.y {
+        Some((nw_c, nw))
+    } else if x >= ne_c.x && y < ne_c.y {
+        Some((ne_c, ne))
+    } else if x >= se_c.x && y >= se_c.y {
+        Some((se_c, se))
+    } else if x < sw_c.x && y >= sw_c.y {
+        Some((sw_c, sw))
+    } else {
+        None
+    };
+    match pole {
+        Some((center, radius)) => {
+            let v = vec2!((x - center.x) as f32, (y - center.y) as f32) + 0.5;
+            surface_area(v.length() - radius as f32, v.angle())
+        }
+        None => 1.0,
+    }
 }
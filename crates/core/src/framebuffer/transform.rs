@@ -42,16 +42,36 @@ pub fn transform_dither_g16(x: u32, y: u32, color: Color) -> Color {
     let gray = color.gray();
     // Get the address of the drift value.
     let addr = (x % DITHER_PITCH) + (y % DITHER_PITCH) * DITHER_PITCH;
-    // Apply the drift to the input color.
-    let c = (gray as i16 + DITHER_G16_DRIFTS[addr as usize] as i16).clamp(0, 255);
-    // Compute the distance to the previous color in G16.
+    Color::Gray(nearest_g16(gray, DITHER_G16_DRIFTS[addr as usize]))
+}
+
+// Ordered dithering of each component, for the color filters of the Kaleido screens.
+// The input components are in {0 .. 255}.
+// The output components are in G16.
+pub fn transform_dither_rgb16(x: u32, y: u32, color: Color) -> Color {
+    let addr = (x % DITHER_PITCH) + (y % DITHER_PITCH) * DITHER_PITCH;
+    let drift = DITHER_G16_DRIFTS[addr as usize];
+    match color {
+        Color::Gray(level) => Color::Gray(nearest_g16(level, drift)),
+        Color::Rgb(red, green, blue) => Color::Rgb(
+            nearest_g16(red, drift),
+            nearest_g16(green, drift),
+            nearest_g16(blue, drift),
+        ),
+    }
+}
+
+pub fn nearest_g16(level: u8, drift: i8) -> u8 {
+    // Apply the drift to the input level.
+    let c = (level as i16 + drift as i16).clamp(0, 255);
+    // Compute the distance to the previous level in G16.
     let d = c % 17;
-    // Return the nearest color in G16.
-    Color::Gray(if d < 9 {
+    // Return the nearest level in G16.
+    if d < 9 {
         (c - d) as u8
     } else {
         (c + (17 - d)) as u8
-    })
+    }
 }
 
 // Ordered dithering.
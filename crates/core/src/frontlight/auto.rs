@@ -0,0 +1,111 @@
+//! Following the ambient light with the frontlight.
+//!
+//! The level of the light sensor is mapped to an intensity through the response curve of the
+//! settings. The target only changes once the level has moved away from the level it was
+//! computed for by more than the hysteresis, so that a passing shadow doesn't make the light
+//! flicker, and the intensity is moved towards the target a step at a time.
+
+use crate::geom::lerp;
+use crate::settings::AutoBrightnessSettings;
+
+/// The largest change of intensity applied at once, in percents.
+const MAX_STEP: f32 = 8.0;
+
+/// The smallest change of the sensor level that moves the target.
+const MIN_LEVEL_DELTA: u16 = 2;
+
+#[derive(Debug, Default)]
+pub struct AutoBrightness {
+    // The sensor level the target was computed for.
+    reference: Option<u16>,
+    target: f32,
+}
+
+/// Returns the intensity of the response curve for the given sensor level.
+///
+/// The curve is interpolated linearly between its points, and extended flat beyond them.
+pub fn response(curve: &[(u16, f32)], level: u16) -> f32 {
+    let Some(&(first_level, first_intensity)) = curve.first() else {
+        return 0.0;
+    };
+    if level <= first_level {
+        return first_intensity;
+    }
+    for pair in curve.windows(2) {
+        let ((l0, i0), (l1, i1)) = (pair[0], pair[1]);
+        if level <= l1 {
+            let t = (level - l0) as f32 / (l1 - l0).max(1) as f32;
+            return lerp(i0, i1, t);
+        }
+    }
+    curve[curve.len() - 1].1
+}
+
+impl AutoBrightness {
+    pub fn new() -> AutoBrightness {
+        AutoBrightness::default()
+    }
+
+    /// Returns the next intensity, given the sensor level and the current intensity, or `None`
+    /// when the intensity doesn't need to change.
+    pub fn adjust(
+        &mut self,
+        level: u16,
+        intensity: f32,
+        settings: &AutoBrightnessSettings,
+    ) -> Option<f32> {
+        let moved = self.reference.is_none_or(|reference| {
+            let threshold = (reference as f32 * settings.hysteresis / 100.0) as u16;
+            level.abs_diff(reference) > threshold.max(MIN_LEVEL_DELTA)
+        });
+
+        if moved {
+            self.reference = Some(level);
+            self.target = response(&settings.curve, level).clamp(0.0, 100.0);
+        }
+
+        let delta = self.target - intensity;
+        if delta.abs() < 0.5 {
+            return None;
+        }
+
+        Some(intensity + delta.clamp(-MAX_STEP, MAX_STEP))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_curve() {
+        let curve = [(0, 5.0), (100, 25.0), (300, 65.0)];
+        assert_eq!(response(&curve, 0), 5.0);
+        assert_eq!(response(&curve, 50), 15.0);
+        assert_eq!(response(&curve, 200), 45.0);
+        assert_eq!(response(&curve, 1000), 65.0);
+        assert_eq!(response(&[], 1000), 0.0);
+    }
+
+    #[test]
+    fn test_adjustments_follow_the_hysteresis() {
+        let settings = AutoBrightnessSettings {
+            enabled: true,
+            curve: vec![(0, 0.0), (100, 20.0)],
+            hysteresis: 10.0,
+        };
+        let mut auto = AutoBrightness::new();
+
+        // The intensity moves towards the target in steps.
+        assert_eq!(auto.adjust(100, 0.0, &settings), Some(MAX_STEP));
+        assert_eq!(auto.adjust(100, 16.0, &settings), Some(20.0));
+        assert_eq!(auto.adjust(100, 20.0, &settings), None);
+
+        // A small change of the sensor level keeps the target.
+        assert_eq!(auto.adjust(95, 20.0, &settings), None);
+
+        // A larger one moves it.
+        assert_eq!(auto.adjust(50, 20.0, &settings), Some(12.0));
+        assert_eq!(auto.adjust(50, 12.0, &settings), Some(10.0));
+    }
+}
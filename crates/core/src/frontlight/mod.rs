@@ -1,7 +1,9 @@
+mod auto;
 mod natural;
 mod premixed;
 mod standard;
 
+pub use self::auto::{response, AutoBrightness};
 pub use self::natural::NaturalFrontlight;
 pub use self::premixed::PremixedFrontlight;
 pub use self::standard::StandardFrontlight;
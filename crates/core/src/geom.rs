@@ -5,7 +5,7 @@ use std::f32::consts;
 use std::fmt;
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 pub enum Dir {
     North,
     East,
@@ -42,7 +42,7 @@ impl fmt::Display for Dir {
     }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 pub enum DiagDir {
     NorthWest,
     NorthEast,
@@ -72,7 +72,7 @@ impl fmt::Display for DiagDir {
     }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Axis {
     Horizontal,
     Vertical,
@@ -462,7 +462,7 @@ impl From<Vec2> for Point {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Vec2 {
     pub x: f32,
     pub y: f32,
@@ -741,6 +741,12 @@ impl Rectangle {
         self.max.x -= edges.right;
         self.max.y -= edges.bottom;
     }
+
+    /// Reflects the rectangle across the vertical axis of `container`.
+    pub fn mirror(&self, container: &Rectangle) -> Rectangle {
+        let sum = container.min.x + container.max.x;
+        rect![sum - self.max.x, self.min.y, sum - self.min.x, self.max.y]
+    }
 }
 
 impl Default for Rectangle {
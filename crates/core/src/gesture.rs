@@ -4,6 +4,7 @@ use crate::input::{ButtonCode, ButtonStatus, DeviceEvent, FingerStatus};
 use crate::unit::mm_to_px;
 use crate::view::Event;
 use fxhash::FxHashMap;
+use serde::{Deserialize, Serialize};
 use std::f64;
 use std::fmt;
 use std::sync::mpsc::{self, Receiver, Sender};
@@ -16,7 +17,7 @@ pub const HOLD_JITTER_MM: f32 = 1.5;
 pub const HOLD_DELAY_SHORT: Duration = Duration::from_millis(666);
 pub const HOLD_DELAY_LONG: Duration = Duration::from_millis(1333);
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum GestureEvent {
     Tap(Point),
     MultiTap([Point; 2]),
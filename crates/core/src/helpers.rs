@@ -64,6 +64,22 @@ pub fn decode_entities(text: &str) -> Cow<'_, str> {
     Cow::Owned(buf)
 }
 
+/// Escapes the characters that have a meaning in HTML and XML.
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 pub fn load_json<T, P: AsRef<Path>>(path: P) -> Result<T, Error>
 where
     for<'a> T: Deserialize<'a>,
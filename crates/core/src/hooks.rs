@@ -0,0 +1,196 @@
+//! The external programs run from the command palette, or when some events occur.
+//!
+//! A hook runs in the directory of its program. It reads a JSON object describing what
+//! triggered it on its standard input, which is closed afterwards:
+//!
+//! ```json
+//! {"event": "document-opened", "path": "/mnt/onboard/Books/a.epub", "library": "/mnt/onboard",
+//!  "model": "Libra 2", "wifi": true, "online": true}
+//! ```
+//!
+//! The event is `menu` when the hook is run from the command palette, and `path` is only
+//! given for `document-opened`. Each line written on the standard output is a JSON message
+//! with a `type`: `notify` shows its `message`, `setWifi` turns the Wi-Fi on or off according
+//! to `enable`, and `openHtml` shows its `html`. A hook isn't started again while it runs.
+
+use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::settings::{CommandHook, HookTrigger};
+use crate::view::{Event, Hub, NotificationEvent};
+use anyhow::{format_err, Error};
+use lazy_static::lazy_static;
+use serde_json::{json, Value as JsonValue};
+use std::collections::HashSet;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::thread;
+use tracing::{error, info, warn};
+
+lazy_static! {
+    static ref RUNNING: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+}
+
+/// Runs the hooks bound to the event, then calls the matching functions of the scripts.
+pub fn trigger(trigger: HookTrigger, path: Option<&Path>, context: &Context, hub: &Hub) {
+    for hook in &context.settings.hooks {
+        if hook.events.contains(&trigger) {
+            if let Err(e) = run(hook, trigger.as_str(), path, context, hub) {
+                error!("Can't run the hook {}: {:#}.", hook.name, e);
+            }
+        }
+    }
+    context.scripts.trigger(trigger, path, context, hub);
+}
+
+/// Runs the hook for the given event, unless it's already running.
+pub fn run(
+    hook: &CommandHook,
+    event: &str,
+    path: Option<&Path>,
+    context: &Context,
+    hub: &Hub,
+) -> Result<(), Error> {
+    let request = json!({
+        "event": event,
+        "path": path,
+        "library": context.library.home,
+        "model": CURRENT_DEVICE.model.to_string(),
+        "wifi": context.settings.wifi,
+        "online": context.online,
+    });
+    spawn(&hook.name, &hook.program, &request, hub)
+}
+
+fn spawn(name: &str, program: &Path, request: &JsonValue, hub: &Hub) -> Result<(), Error> {
+    if !RUNNING
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(name.to_string())
+    {
+        info!("The hook {} is already running.", name);
+        return Ok(());
+    }
+
+    let result = start(name, program, request, hub);
+    if result.is_err() {
+        RUNNING
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(name);
+    }
+    result
+}
+
+fn start(name: &str, program: &Path, request: &JsonValue, hub: &Hub) -> Result<(), Error> {
+    let path = program.canonicalize()?;
+    let parent = path.parent().unwrap_or_else(|| Path::new(""));
+    let mut process = Command::new(&path)
+        .current_dir(parent)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+
+    // Dropping the handle closes the standard input.
+    if let Some(mut stdin) = process.stdin.take() {
+        writeln!(stdin, "{}", request)
+            .map_err(|e| warn!("Can't write the request of the hook {}: {}.", name, e))
+            .ok();
+    }
+
+    let stdout = process
+        .stdout
+        .take()
+        .ok_or_else(|| format_err!("can't take stdout"))?;
+    let name = name.to_string();
+    let hub = hub.clone();
+
+    thread::spawn(move || {
+        let reader = BufReader::new(stdout);
+        for line in reader.lines().map_while(Result::ok) {
+            match parse_message(&line) {
+                Some(evt) => {
+                    hub.send(evt).ok();
+                }
+                None => warn!("The hook {} sent an invalid message: {}.", name, line),
+            }
+        }
+        match process.wait() {
+            Ok(status) if !status.success() => warn!("The hook {} exited with {}.", name, status),
+            Err(e) => error!("Can't wait for the hook {}: {}.", name, e),
+            _ => (),
+        }
+        RUNNING
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&name);
+    });
+
+    Ok(())
+}
+
+fn parse_message(line: &str) -> Option<Event> {
+    let message = serde_json::from_str::<JsonValue>(line).ok()?;
+    match message.get("type").and_then(JsonValue::as_str)? {
+        "notify" => message
+            .get("message")
+            .and_then(JsonValue::as_str)
+            .map(|msg| Event::Notification(NotificationEvent::Show(msg.to_string()))),
+        "setWifi" => message
+            .get("enable")
+            .and_then(JsonValue::as_bool)
+            .map(Event::SetWifi),
+        "openHtml" => message
+            .get("html")
+            .and_then(JsonValue::as_str)
+            .map(|html| Event::OpenHtml(html.to_string(), None)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::sync::mpsc::channel;
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse_message() {
+        assert!(matches!(
+            parse_message(r#"{"type": "notify", "message": "Done."}"#),
+            Some(Event::Notification(NotificationEvent::Show(msg))) if msg == "Done."
+        ));
+        assert!(matches!(
+            parse_message(r#"{"type": "setWifi", "enable": true}"#),
+            Some(Event::SetWifi(true))
+        ));
+        assert!(parse_message(r#"{"type": "setWifi"}"#).is_none());
+        assert!(parse_message(r#"{"type": "reboot"}"#).is_none());
+        assert!(parse_message("done").is_none());
+    }
+
+    #[test]
+    fn test_hook_answers_the_request() {
+        let dir = tempfile::tempdir().unwrap();
+        let program = dir.path().join("hook.sh");
+        fs::write(
+            &program,
+            "#!/bin/sh\nif grep -q '\"event\":\"resume\"'; then\n\
+             echo '{\"type\": \"notify\", \"message\": \"Resumed.\"}'\nfi\n",
+        )
+        .unwrap();
+        fs::set_permissions(&program, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let (hub, receiver) = channel();
+        let request = json!({"event": "resume", "path": null});
+        spawn("test", &program, &request, &hub).unwrap();
+
+        let evt = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(
+            matches!(evt, Event::Notification(NotificationEvent::Show(msg)) if msg == "Resumed.")
+        );
+    }
+}
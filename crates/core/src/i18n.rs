@@ -0,0 +1,362 @@
+//! Translations of the user interface.
+//!
+//! The strings of the user interface are written in English and looked up in the catalog of
+//! the language selected by the `language` setting, with the [`tr!`] and [`trn!`] macros.
+//! Each catalog is a TOML file of the `translations` directory, named after its language,
+//! that maps the English strings to their translations:
+//!
+//! ```toml
+//! "Settings" = "Paramètres"
+//! "{count} result" = { one = "{count} résultat", other = "{count} résultats" }
+//! ```
+//!
+//! The strings missing from the catalog are shown in English. Named arguments, such as
+//! `{count}`, are substituted after the lookup, so a translation can move them around.
+
+use anyhow::{Context as ResultExt, Error};
+use fxhash::FxHashMap;
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::RwLock;
+
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// The plural forms of a message, at least one of which is `other`.
+#[derive(Debug, Clone, Deserialize)]
+struct PluralForms {
+    one: Option<String>,
+    few: Option<String>,
+    many: Option<String>,
+    other: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum Message {
+    Text(String),
+    Plural(PluralForms),
+}
+
+/// The direction in which the user interface is laid out.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum Direction {
+    #[default]
+    LeftToRight,
+    RightToLeft,
+}
+
+impl Direction {
+    /// Returns the writing direction of the given language.
+    pub fn from_language(language: &str) -> Direction {
+        let language = language.split(['-', '_']).next().unwrap_or(language);
+        match language {
+            "ar" | "fa" | "he" | "ps" | "ur" | "yi" => Direction::RightToLeft,
+            _ => Direction::LeftToRight,
+        }
+    }
+
+    pub fn is_rtl(self) -> bool {
+        self == Direction::RightToLeft
+    }
+}
+
+/// The plural categories of a count, as defined by the Unicode CLDR.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PluralCategory {
+    One,
+    Few,
+    Many,
+    Other,
+}
+
+impl PluralCategory {
+    /// Returns the category of `count` in the given language.
+    ///
+    /// Only the rules of the languages which don't follow the English one are known.
+    pub fn new(language: &str, count: usize) -> PluralCategory {
+        let language = language.split(['-', '_']).next().unwrap_or(language);
+        let (units, tens) = (count % 10, count % 100);
+        match language {
+            "ja" | "ko" | "th" | "vi" | "zh" => PluralCategory::Other,
+            "fr" if count <= 1 => PluralCategory::One,
+            "fr" => PluralCategory::Other,
+            "be" | "ru" | "uk" if units == 1 && tens != 11 => PluralCategory::One,
+            "be" | "ru" | "uk" if (2..=4).contains(&units) && !(12..=14).contains(&tens) => {
+                PluralCategory::Few
+            }
+            "be" | "ru" | "uk" => PluralCategory::Many,
+            "pl" if count == 1 => PluralCategory::One,
+            "pl" if (2..=4).contains(&units) && !(12..=14).contains(&tens) => PluralCategory::Few,
+            "pl" => PluralCategory::Many,
+            "cs" | "sk" if count == 1 => PluralCategory::One,
+            "cs" | "sk" if (2..=4).contains(&count) => PluralCategory::Few,
+            _ if count == 1 => PluralCategory::One,
+            _ => PluralCategory::Other,
+        }
+    }
+}
+
+/// The translations of one language.
+#[derive(Debug, Clone)]
+pub struct Catalog {
+    language: String,
+    messages: FxHashMap<String, Message>,
+}
+
+impl Catalog {
+    pub fn new(language: &str, text: &str) -> Result<Catalog, Error> {
+        let messages = toml::from_str(text)?;
+        Ok(Catalog {
+            language: language.to_string(),
+            messages,
+        })
+    }
+
+    pub fn load<P: AsRef<Path>>(language: &str, path: P) -> Result<Catalog, Error> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("can't read translations from {}", path.display()))?;
+        Catalog::new(language, &text)
+            .with_context(|| format!("can't parse translations from {}", path.display()))
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Returns the translation of `msgid`.
+    pub fn get(&self, msgid: &str) -> Option<&str> {
+        match self.messages.get(msgid)? {
+            Message::Text(text) => Some(text),
+            Message::Plural(forms) => Some(&forms.other),
+        }
+    }
+
+    /// Returns the translation of `msgid` that agrees with `count`.
+    pub fn get_plural(&self, msgid: &str, count: usize) -> Option<&str> {
+        match self.messages.get(msgid)? {
+            Message::Text(text) => Some(text),
+            Message::Plural(forms) => {
+                let form = match PluralCategory::new(&self.language, count) {
+                    PluralCategory::One => forms.one.as_ref(),
+                    PluralCategory::Few => forms.few.as_ref(),
+                    PluralCategory::Many => forms.many.as_ref(),
+                    PluralCategory::Other => None,
+                };
+                Some(form.unwrap_or(&forms.other))
+            }
+        }
+    }
+}
+
+// Views render without a context: they read the translations applied to the context from here.
+static CURRENT: RwLock<Option<Catalog>> = RwLock::new(None);
+
+/// Returns the language of the user interface.
+pub fn current_language() -> String {
+    CURRENT
+        .read()
+        .unwrap()
+        .as_ref()
+        .map_or_else(|| DEFAULT_LANGUAGE.to_string(), |c| c.language.clone())
+}
+
+/// Returns the direction of the user interface.
+pub fn current_direction() -> Direction {
+    CURRENT
+        .read()
+        .unwrap()
+        .as_ref()
+        .map_or(Direction::LeftToRight, |c| {
+            Direction::from_language(&c.language)
+        })
+}
+
+pub(crate) fn set_current(catalog: Option<Catalog>) {
+    *CURRENT.write().unwrap() = catalog;
+}
+
+/// Translates `msgid`, prefer the [`tr!`] macro.
+pub fn translate(msgid: &str) -> String {
+    CURRENT
+        .read()
+        .unwrap()
+        .as_ref()
+        .and_then(|c| c.get(msgid))
+        .unwrap_or(msgid)
+        .to_string()
+}
+
+/// Translates the form of a message that agrees with `count`, prefer the [`trn!`] macro.
+///
+/// The singular is the key of the message in the catalogs.
+pub fn translate_plural(singular: &str, plural: &str, count: usize) -> String {
+    let english = if count == 1 { singular } else { plural };
+    CURRENT
+        .read()
+        .unwrap()
+        .as_ref()
+        .and_then(|c| c.get_plural(singular, count))
+        .unwrap_or(english)
+        .to_string()
+}
+
+/// Returns the name of the language in itself, or its code when it isn't known.
+pub fn language_name(language: &str) -> &str {
+    match language {
+        "de" => "Deutsch",
+        "en" => "English",
+        "es" => "Español",
+        "fr" => "Français",
+        "it" => "Italiano",
+        "nl" => "Nederlands",
+        "pt" => "Português",
+        "ru" => "Русский",
+        "ja" => "日本語",
+        "zh" => "中文",
+        _ => language,
+    }
+}
+
+/// Replaces the named arguments of `template`, written `{name}`, by their values.
+///
+/// The unknown arguments are left as is.
+pub fn format(template: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let value = rest.find('}').and_then(|end| {
+            let name = &rest[1..end];
+            args.iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, value)| (end, value))
+        });
+        if let Some((end, value)) = value {
+            result.push_str(&value.to_string());
+            rest = &rest[end + 1..];
+        } else {
+            result.push('{');
+            rest = &rest[1..];
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Translates a string of the user interface.
+///
+/// The named arguments that follow the string replace its `{name}` placeholders:
+/// `tr!("Loading {title}", title = name)`.
+#[macro_export]
+macro_rules! tr {
+    ($msgid:expr $(,)?) => {
+        $crate::i18n::translate($msgid)
+    };
+    ($msgid:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::format(
+            &$crate::i18n::translate($msgid),
+            &[$((stringify!($name), &$value as &dyn ::std::fmt::Display)),+],
+        )
+    };
+}
+
+/// Translates a string of the user interface that depends on a count.
+///
+/// The count replaces the `{count}` placeholder, and the named arguments that follow it
+/// replace the others: `trn!("{count} book", "{count} books", books.len())`.
+#[macro_export]
+macro_rules! trn {
+    ($singular:expr, $plural:expr, $count:expr $(, $name:ident = $value:expr)* $(,)?) => {{
+        let count: usize = $count;
+        $crate::i18n::format(
+            &$crate::i18n::translate_plural($singular, $plural, count),
+            &[
+                ("count", &count as &dyn ::std::fmt::Display)
+                $(, (stringify!($name), &$value as &dyn ::std::fmt::Display))*
+            ],
+        )
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_lookup() {
+        let catalog = Catalog::new(
+            "fr",
+            r#"
+"Settings" = "Paramètres"
+"{count} result" = { one = "{count} résultat", other = "{count} résultats" }
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(catalog.get("Settings"), Some("Paramètres"));
+        assert_eq!(catalog.get("Quit"), None);
+        assert_eq!(
+            catalog.get_plural("{count} result", 0),
+            Some("{count} résultat")
+        );
+        assert_eq!(
+            catalog.get_plural("{count} result", 2),
+            Some("{count} résultats")
+        );
+    }
+
+    #[test]
+    fn test_plural_categories() {
+        assert_eq!(PluralCategory::new("en", 1), PluralCategory::One);
+        assert_eq!(PluralCategory::new("en", 0), PluralCategory::Other);
+        assert_eq!(PluralCategory::new("fr", 0), PluralCategory::One);
+        assert_eq!(PluralCategory::new("ru", 21), PluralCategory::One);
+        assert_eq!(PluralCategory::new("ru", 12), PluralCategory::Many);
+        assert_eq!(PluralCategory::new("ru-RU", 23), PluralCategory::Few);
+        assert_eq!(PluralCategory::new("pl", 22), PluralCategory::Few);
+        assert_eq!(PluralCategory::new("ja", 1), PluralCategory::Other);
+    }
+
+    #[test]
+    fn test_direction_from_language() {
+        assert!(Direction::from_language("ar").is_rtl());
+        assert!(Direction::from_language("he-IL").is_rtl());
+        assert!(!Direction::from_language("fr").is_rtl());
+        assert!(!Direction::from_language(DEFAULT_LANGUAGE).is_rtl());
+    }
+
+    #[test]
+    fn test_format_named_arguments() {
+        assert_eq!(
+            format("{count} of {total}", &[("count", &3), ("total", &"7")]),
+            "3 of 7"
+        );
+        assert_eq!(format("{unknown} {", &[("count", &3)]), "{unknown} {");
+        assert_eq!(trn!("{count} result", "{count} results", 2), "2 results");
+        assert_eq!(tr!("Hello {name}", name = "you"), "Hello you");
+    }
+
+    #[test]
+    fn test_bundled_catalogs_parse() {
+        let path = Path::new(
+            &std::env::var("TEST_ROOT_DIR")
+                .expect("TEST_ROOT_DIR must be set for test using translations"),
+        )
+        .join("translations");
+        for entry in fs::read_dir(path).unwrap() {
+            let path = entry.unwrap().path();
+            let language = path.file_stem().unwrap().to_string_lossy();
+            assert!(
+                Catalog::load(&language, &path).is_ok(),
+                "{}",
+                path.display()
+            );
+        }
+    }
+}
@@ -4,6 +4,7 @@ use crate::geom::{LinearDir, Point};
 use crate::settings::ButtonScheme;
 use anyhow::{Context, Error};
 use fxhash::FxHashMap;
+use serde::{Deserialize, Serialize};
 use std::ffi::CString;
 use std::fs::File;
 use std::io::Read;
@@ -67,6 +68,9 @@ pub const BTN_TOUCH: u16 = 330;
 // software toggles within this design
 pub const KEY_ROTATE_DISPLAY: u16 = 0xffff;
 pub const KEY_BUTTON_SCHEME: u16 = 0xfffe;
+// The page-turn keys of the Bluetooth remotes, which don't follow the rotation.
+pub const KEY_REMOTE_BACKWARD: u16 = 0xfffd;
+pub const KEY_REMOTE_FORWARD: u16 = 0xfffc;
 
 pub const SINGLE_TOUCH_CODES: TouchCodes = TouchCodes {
     pressure: ABS_PRESSURE,
@@ -109,14 +113,14 @@ pub enum TouchProto {
     MultiC,
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum FingerStatus {
     Down,
     Motion,
     Up,
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum ButtonStatus {
     Pressed,
     Released,
@@ -134,7 +138,7 @@ impl ButtonStatus {
     }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum ButtonCode {
     Power,
     Home,
@@ -210,7 +214,7 @@ pub fn button_scheme_event(v: i32) -> InputEvent {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum DeviceEvent {
     Finger {
         id: i32,
@@ -232,7 +236,7 @@ pub enum DeviceEvent {
     UserActivity,
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum PowerSource {
     Host,
     Wall,
@@ -528,6 +532,25 @@ pub fn parse_device_events(
                     mirror_x = should_mirror.0;
                     mirror_y = should_mirror.1;
                 }
+            } else if evt.code == KEY_REMOTE_BACKWARD || evt.code == KEY_REMOTE_FORWARD {
+                // The reports of the remotes aren't forwarded, since they would end the touches.
+                if (evt.time.tv_sec - last_activity).abs() >= 60 {
+                    last_activity = evt.time.tv_sec;
+                    ty.send(DeviceEvent::UserActivity).ok();
+                }
+                if let Some(button_status) = ButtonStatus::try_from_raw(evt.value) {
+                    let code = if evt.code == KEY_REMOTE_FORWARD {
+                        ButtonCode::Forward
+                    } else {
+                        ButtonCode::Backward
+                    };
+                    ty.send(DeviceEvent::Button {
+                        time: seconds(evt.time),
+                        code,
+                        status: button_status,
+                    })
+                    .ok();
+                }
             } else if evt.code != BTN_TOUCH {
                 if let Some(button_status) = ButtonStatus::try_from_raw(evt.value) {
                     ty.send(DeviceEvent::Button {
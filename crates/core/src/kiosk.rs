@@ -0,0 +1,149 @@
+//! The kiosk mode: a restricted use of the device, for children or as a single-purpose reader.
+//!
+//! While it's on, the library can't be switched, and the settings, the updates, the file
+//! management and sharing, the synchronizations that add or remove files, the hooks, the
+//! backups and the application's reset are unavailable. The restricted events are dropped,
+//! wherever they come from, and a toast tells why. Turning the mode off takes the PIN chosen
+//! when it was turned on.
+
+use crate::context::Context;
+use crate::settings::Settings;
+use crate::view::{AppCmd, EntryId, Event, Hub, NotificationEvent, ViewId};
+use sha2::{Digest, Sha256};
+
+/// Returns whether the event is unavailable in kiosk mode.
+pub fn is_restricted(evt: &Event) -> bool {
+    match evt {
+        Event::Select(id) => matches!(
+            id,
+            EntryId::Launch(AppCmd::SettingsEditor)
+                | EntryId::CheckForUpdates
+                | EntryId::LoadLibrary(..)
+                | EntryId::Import
+                | EntryId::CleanUp
+                | EntryId::EmptyTrash
+                | EntryId::Rename(..)
+                | EntryId::Remove(..)
+                | EntryId::CopyTo(..)
+                | EntryId::MoveTo(..)
+                | EntryId::AddDirectory(..)
+                | EntryId::CleanUpStorage
+                | EntryId::Reclaim(..)
+                | EntryId::ReclaimAll
+                | EntryId::ShareStorage
+                | EntryId::ToggleWebServer
+                | EntryId::ToggleCalibre
+                | EntryId::SyncWebdav
+                | EntryId::CheckMail
+                | EntryId::RunHook(..)
+                | EntryId::CreateBackup
+                | EntryId::ShowBackups
+                | EntryId::RestoreBackup(..)
+                | EntryId::ResetCadmus { .. }
+                | EntryId::Setup(..)
+                | EntryId::Quit
+        ),
+        Event::Show(ViewId::SetupDialog | ViewId::ResetDialog) => true,
+        Event::PrepareShare | Event::AddLibrary | Event::DeleteLibrary(..) => true,
+        _ => false,
+    }
+}
+
+/// Drops the event, and says so, when it's restricted and the kiosk mode is on.
+pub fn blocks(evt: &Event, hub: &Hub, context: &Context) -> bool {
+    if !context.settings.kiosk.enabled || !is_restricted(evt) {
+        return false;
+    }
+    hub.send(Event::Notification(NotificationEvent::Show(
+        "Unavailable in kiosk mode.".to_string(),
+    )))
+    .ok();
+    true
+}
+
+/// Describes the event for the logs and the crash reports, leaving the PIN out.
+pub fn describe(evt: &Event) -> String {
+    match evt {
+        Event::Submit(ViewId::KioskPin, _) => "Submit(KioskPin, …)".to_string(),
+        _ => format!("{:?}", evt),
+    }
+}
+
+/// Turns the kiosk mode on, keeping the device to the selected library.
+pub fn lock(pin: &str, settings: &mut Settings) {
+    settings.kiosk.pin = Some(digest(pin, settings));
+    settings.kiosk.library = settings.selected_library;
+    settings.kiosk.enabled = true;
+}
+
+/// Turns the kiosk mode off if the PIN is the right one, and returns whether it was.
+pub fn unlock(pin: &str, settings: &mut Settings) -> bool {
+    let valid = settings
+        .kiosk
+        .pin
+        .as_ref()
+        .is_some_and(|expected| *expected == digest(pin, settings));
+    if valid {
+        settings.kiosk.enabled = false;
+    }
+    valid
+}
+
+fn digest(pin: &str, settings: &Settings) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(settings.sync.device_id.as_bytes());
+    hasher.update(pin.as_bytes());
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlock_takes_the_pin() {
+        let mut settings = Settings {
+            selected_library: 1,
+            ..Default::default()
+        };
+        lock("1234", &mut settings);
+        assert!(settings.kiosk.enabled);
+        assert_eq!(settings.kiosk.library, 1);
+        assert_ne!(settings.kiosk.pin.as_deref(), Some("1234"));
+
+        assert!(!unlock("4321", &mut settings));
+        assert!(settings.kiosk.enabled);
+        assert!(unlock("1234", &mut settings));
+        assert!(!settings.kiosk.enabled);
+    }
+
+    #[test]
+    fn test_is_restricted() {
+        assert!(is_restricted(&Event::Select(EntryId::Launch(
+            AppCmd::SettingsEditor
+        ))));
+        assert!(is_restricted(&Event::Select(EntryId::LoadLibrary(1))));
+        assert!(is_restricted(&Event::Select(EntryId::ToggleCalibre)));
+        assert!(is_restricted(&Event::Select(EntryId::SyncWebdav)));
+        assert!(is_restricted(&Event::Select(EntryId::CheckMail)));
+        assert!(is_restricted(&Event::Select(EntryId::RunHook(0))));
+        assert!(!is_restricted(&Event::Select(EntryId::Launch(
+            AppCmd::Dashboard
+        ))));
+        assert!(!is_restricted(&Event::Select(EntryId::ToggleKioskMode)));
+    }
+
+    #[test]
+    fn test_describe_leaves_the_pin_out() {
+        let description = describe(&Event::Submit(ViewId::KioskPin, "1234".to_string()));
+        assert!(!description.contains("1234"));
+        assert_eq!(
+            describe(&Event::Submit(ViewId::GoToPage, "12".to_string())),
+            "Submit(GoToPage, \"12\")"
+        );
+    }
+}
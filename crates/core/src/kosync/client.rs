@@ -0,0 +1,243 @@
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::StatusCode;
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+
+use crate::net;
+use crate::settings::SyncSettings;
+
+/// Media type of the version of the protocol implemented by the client.
+const ACCEPT: &str = "application/vnd.koreader.v1+json";
+
+/// Timeout of each request in seconds.
+const TIMEOUT_SECS: u64 = 15;
+
+/// Client of a KOReader sync server.
+///
+/// Every request, except the registration, is authenticated with the user name
+/// and the user key: the MD5 digest of the password.
+pub struct KosyncClient {
+    client: Client,
+    server: String,
+    username: String,
+    userkey: SecretString,
+}
+
+/// Error types that can occur while talking to the sync server.
+#[derive(thiserror::Error, Debug)]
+pub enum KosyncError {
+    /// The server, the user name or the password isn't configured
+    #[error("Sync account not configured")]
+    NotConfigured,
+
+    /// The server rejected the credentials
+    #[error("Unauthorized")]
+    Unauthorized,
+
+    /// The server answered with an error message
+    #[error("Server error: {0}")]
+    Server(String),
+
+    /// HTTP request failed during communication with the server
+    #[error("HTTP request error: {0}")]
+    Request(#[from] reqwest::Error),
+
+    /// TLS/SSL configuration failed when setting up HTTPS client
+    #[error("TLS configuration error: {0}")]
+    TlsConfig(String),
+}
+
+/// The reading position of a document, as stored by the server.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Progress {
+    pub document: String,
+    /// Page number for documents with pages, XPointer for reflowable documents
+    /// when reported by KOReader.
+    #[serde(deserialize_with = "string_or_number")]
+    pub progress: String,
+    /// Fraction of the document that was read, between 0 and 1.
+    pub percentage: f32,
+    pub device: String,
+    pub device_id: String,
+    /// Seconds since the epoch of the last update, set by the server.
+    #[serde(default, skip_serializing)]
+    pub timestamp: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorResponse {
+    message: String,
+}
+
+impl KosyncClient {
+    /// Creates a client for the account configured in the given settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KosyncError::NotConfigured` if the server, the user name or the user key is
+    /// missing, and `KosyncError::TlsConfig` if the HTTP client fails to initialize.
+    pub fn new(settings: &SyncSettings) -> Result<Self, KosyncError> {
+        let userkey = settings
+            .userkey
+            .clone()
+            .filter(|key| !key.expose_secret().is_empty())
+            .ok_or(KosyncError::NotConfigured)?;
+
+        if settings.server.is_empty() || settings.username.is_empty() {
+            return Err(KosyncError::NotConfigured);
+        }
+
+        let client = net::client_builder("cadmus-kosync", Duration::from_secs(TIMEOUT_SECS))
+            .build()
+            .map_err(|e| KosyncError::TlsConfig(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(Self {
+            client,
+            server: settings.server.trim_end_matches('/').to_string(),
+            username: settings.username.clone(),
+            userkey,
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.server, path)
+    }
+
+    fn authenticated(&self, request: RequestBuilder) -> RequestBuilder {
+        request
+            .header("Accept", ACCEPT)
+            .header("x-auth-user", &self.username)
+            .header("x-auth-key", self.userkey.expose_secret())
+    }
+
+    /// Creates the account on the server.
+    pub fn register(&self) -> Result<(), KosyncError> {
+        let response = self
+            .client
+            .post(self.url("/users/create"))
+            .header("Accept", ACCEPT)
+            .json(&json!({
+                "username": self.username,
+                "password": self.userkey.expose_secret(),
+            }))
+            .send()?;
+
+        check(response).map(|_| ())
+    }
+
+    /// Checks the credentials against the server.
+    pub fn authorize(&self) -> Result<(), KosyncError> {
+        let response = self
+            .authenticated(self.client.get(self.url("/users/auth")))
+            .send()?;
+
+        check(response).map(|_| ())
+    }
+
+    /// Stores the reading position of a document.
+    pub fn update_progress(&self, progress: &Progress) -> Result<(), KosyncError> {
+        let response = self
+            .authenticated(self.client.put(self.url("/syncs/progress")))
+            .json(progress)
+            .send()?;
+
+        check(response).map(|_| ())
+    }
+
+    /// Fetches the last reading position of a document.
+    ///
+    /// Returns `None` if no device has reported a position for this document yet.
+    pub fn get_progress(&self, document: &str) -> Result<Option<Progress>, KosyncError> {
+        let response = self
+            .authenticated(
+                self.client
+                    .get(self.url(&format!("/syncs/progress/{}", document))),
+            )
+            .send()?;
+
+        let response = check(response)?;
+        let value: serde_json::Value = response.json()?;
+
+        // The server answers with an empty object for unknown documents.
+        if value.get("percentage").is_none() {
+            return Ok(None);
+        }
+
+        serde_json::from_value(value)
+            .map(Some)
+            .map_err(|e| KosyncError::Server(e.to_string()))
+    }
+}
+
+// Some clients report page numbers as JSON numbers.
+fn string_or_number<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(text) => Ok(text),
+        value => Ok(value.to_string()),
+    }
+}
+
+fn check(response: Response) -> Result<Response, KosyncError> {
+    let status = response.status();
+
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    if status == StatusCode::UNAUTHORIZED {
+        return Err(KosyncError::Unauthorized);
+    }
+
+    let message = response
+        .json::<ErrorResponse>()
+        .map(|e| e.message)
+        .unwrap_or_else(|_| status.to_string());
+
+    Err(KosyncError::Server(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_serialization() {
+        let progress = Progress {
+            document: "0123".to_string(),
+            progress: "12".to_string(),
+            percentage: 0.25,
+            device: "Cadmus".to_string(),
+            device_id: "abcd".to_string(),
+            timestamp: Some(1_700_000_000),
+        };
+
+        let value = serde_json::to_value(&progress).unwrap();
+
+        assert_eq!(value["document"], "0123");
+        assert_eq!(value["device_id"], "abcd");
+        assert!(value.get("timestamp").is_none());
+
+        let progress: Progress = serde_json::from_str(
+            r#"{"document":"0123","progress":"/body/DocFragment[3]/body/p[2]/text().0",
+                "percentage":0.5,"device":"KOReader","device_id":"ef","timestamp":1700000000}"#,
+        )
+        .unwrap();
+
+        assert_eq!(progress.percentage, 0.5);
+        assert_eq!(progress.timestamp, Some(1_700_000_000));
+    }
+
+    #[test]
+    fn test_client_requires_account() {
+        let settings = SyncSettings::default();
+        assert!(matches!(
+            KosyncClient::new(&settings),
+            Err(KosyncError::NotConfigured)
+        ));
+    }
+}
@@ -0,0 +1,78 @@
+use md5::{Digest, Md5};
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+// Size of each sample read by the partial digest.
+const SAMPLE_SIZE: usize = 1024;
+
+/// Returns the digest as a lowercase hexadecimal string.
+fn hex(digest: &[u8]) -> String {
+    digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Returns the key sent to the server in place of the password: the MD5 digest of the latter.
+pub fn userkey(password: &str) -> String {
+    hex(&Md5::digest(password.as_bytes()))
+}
+
+/// Computes the identifier of a document, as KOReader does.
+///
+/// Instead of reading the whole file, samples of 1 KiB are taken at the offsets 0, 1 KiB,
+/// 4 KiB, 16 KiB, … up to 1 GiB, and their concatenation is digested.
+pub fn document_hash<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let size = file.metadata()?.len();
+    let mut digest = Md5::new();
+    let mut sample = vec![0u8; SAMPLE_SIZE];
+
+    let offsets = std::iter::once(0).chain((0..=10).map(|i| (SAMPLE_SIZE as u64) << (2 * i)));
+
+    for offset in offsets {
+        if offset >= size {
+            break;
+        }
+        file.seek(SeekFrom::Start(offset))?;
+        let mut count = 0;
+        while count < SAMPLE_SIZE {
+            let n = file.read(&mut sample[count..])?;
+            if n == 0 {
+                break;
+            }
+            count += n;
+        }
+        digest.update(&sample[..count]);
+    }
+
+    Ok(hex(&digest.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_userkey() {
+        assert_eq!(userkey(""), "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(userkey("abc"), "900150983cd24fb0d6963f7d28e17f72");
+    }
+
+    #[test]
+    fn test_document_hash() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        let data = (0..20_000u32).map(|i| (i % 251) as u8).collect::<Vec<u8>>();
+        file.write_all(&data).unwrap();
+
+        let mut samples = Vec::new();
+        for offset in [0, 1024, 4096, 16384] {
+            let end = (offset + SAMPLE_SIZE).min(data.len());
+            samples.extend_from_slice(&data[offset..end]);
+        }
+
+        assert_eq!(
+            document_hash(file.path()).unwrap(),
+            hex(&Md5::digest(&samples))
+        );
+    }
+}
@@ -0,0 +1,79 @@
+//! Reading progress synchronization through a KOReader sync server (kosync).
+//!
+//! The server keeps, for each account and document, the last position reported by any
+//! device. Documents are identified by a partial MD5 digest of their content, computed
+//! the same way as KOReader does, so that both readers can share their progress.
+//!
+//! KOReader reports the positions within reflowable documents as XPointers, which can't be
+//! resolved here: the percentage is used instead.
+
+mod client;
+mod hash;
+
+pub use client::{KosyncClient, KosyncError, Progress};
+pub use hash::{document_hash, userkey};
+
+/// Returns the fraction of the document read at the given location.
+///
+/// For documents with pages, the current page counts as read, like in KOReader.
+pub fn percentage(current_page: usize, pages_count: usize, reflowable: bool) -> f32 {
+    let current = if reflowable {
+        current_page
+    } else {
+        current_page + 1
+    };
+    (current as f32 / pages_count.max(1) as f32).clamp(0.0, 1.0)
+}
+
+/// Returns the position reported to the server for the given location.
+pub fn progress(current_page: usize, reflowable: bool) -> String {
+    if reflowable {
+        current_page.to_string()
+    } else {
+        (current_page + 1).to_string()
+    }
+}
+
+/// Returns the location corresponding to a synced position.
+pub fn location(progress: &Progress, pages_count: usize, reflowable: bool) -> usize {
+    if !reflowable {
+        if let Ok(page) = progress.progress.trim().parse::<usize>() {
+            return page.saturating_sub(1).min(pages_count.saturating_sub(1));
+        }
+    }
+
+    let location = (progress.percentage.clamp(0.0, 1.0) * pages_count as f32) as usize;
+
+    if reflowable {
+        location.min(pages_count.saturating_sub(1))
+    } else {
+        location
+            .saturating_sub(1)
+            .min(pages_count.saturating_sub(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_location() {
+        let mut remote = Progress {
+            progress: "12".to_string(),
+            percentage: 0.12,
+            ..Default::default()
+        };
+
+        assert_eq!(location(&remote, 100, false), 11);
+        assert_eq!(progress(11, false), "12");
+        assert_eq!(percentage(11, 100, false), 0.12);
+
+        remote.progress = "/body/DocFragment[3]/body/p[2]/text().0".to_string();
+        remote.percentage = 0.5;
+
+        assert_eq!(location(&remote, 1000, true), 500);
+        assert_eq!(location(&remote, 100, false), 49);
+        assert_eq!(percentage(500, 1000, true), 0.5);
+    }
+}
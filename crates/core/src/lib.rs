@@ -1,30 +1,57 @@
 #[macro_use]
 pub mod geom;
+#[macro_use]
+pub mod i18n;
 
+pub mod animation;
 pub mod assets;
+pub mod backup;
 pub mod battery;
+pub mod bedtime;
+pub mod bluetooth;
+pub mod calibre;
+pub mod captive;
 pub mod color;
 pub mod context;
+pub mod crash;
 pub mod device;
 mod dictionary;
 pub mod document;
+pub mod download;
+pub mod external_keyboard;
 pub mod font;
 pub mod framebuffer;
 pub mod frontlight;
 pub mod gesture;
 pub mod helpers;
+pub mod hooks;
 pub mod input;
+pub mod kiosk;
+pub mod kosync;
 pub mod library;
 pub mod lightsensor;
 pub mod logging;
+pub mod mail;
 pub mod metadata;
+pub mod net;
 pub mod ota;
+pub mod recorder;
+pub mod reset;
 pub mod rtc;
+pub mod scripting;
 pub mod settings;
+pub mod speech;
+pub mod storage;
+pub mod svg;
+pub mod sync_queue;
 #[cfg(feature = "otel")]
 pub mod telemetry;
+pub mod theme;
+pub mod tls;
 mod unit;
 pub mod view;
+pub mod web_server;
+pub mod webdav;
 
 pub use anyhow;
 pub use chrono;
@@ -59,6 +59,7 @@
 //!     max_files: 3,
 //!     directory: "logs".into(),
 //!     otlp_endpoint: None,
+//!     record_events: false,
 //! };
 //!
 //! // Initialize at application startup
@@ -259,6 +260,7 @@ fn is_run_log_entry(entry: &DirEntry) -> bool {
 ///     max_files: 5,
 ///     directory: "logs".into(),
 ///     otlp_endpoint: Some("http://localhost:4318".to_string()),
+///     record_events: false,
 /// };
 ///
 /// init_logging(&settings)?;
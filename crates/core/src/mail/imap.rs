@@ -0,0 +1,252 @@
+//! A minimal IMAP client, speaking just enough of the protocol to fetch the unread emails
+//! of a mailbox and mark them as read.
+
+use crate::tls;
+use rustls::pki_types::ServerName;
+use rustls::{ClientConnection, StreamOwned};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The largest literal accepted, which bounds the size of the emails.
+const MAX_LITERAL_SIZE: usize = 64 * 1024 * 1024;
+
+#[derive(Error, Debug)]
+pub enum ImapError {
+    #[error("network error: {0}")]
+    Io(#[from] io::Error),
+    #[error("TLS error: {0}")]
+    Tls(#[from] rustls::Error),
+    #[error("invalid server name: {0}")]
+    ServerName(String),
+    #[error("the server refused the command: {0}")]
+    Refused(String),
+    #[error("unexpected answer from the server: {0}")]
+    Protocol(String),
+}
+
+/// An untagged answer of the server, with the literals it holds.
+#[derive(Debug, Default)]
+pub struct Answer {
+    pub text: String,
+    pub literals: Vec<Vec<u8>>,
+}
+
+pub struct ImapClient<S: Read + Write> {
+    stream: BufReader<S>,
+    tag: usize,
+}
+
+impl ImapClient<StreamOwned<ClientConnection, TcpStream>> {
+    /// Opens a TLS connection to the server and waits for its greeting.
+    pub fn connect(host: &str, port: u16) -> Result<Self, ImapError> {
+        let config = tls::client_config(tls::root_store());
+        let name = ServerName::try_from(host.to_string())
+            .map_err(|_| ImapError::ServerName(host.to_string()))?;
+        let connection = ClientConnection::new(Arc::new(config), name)?;
+
+        let address = (host, port).to_socket_addrs()?.next().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, format!("can't resolve {}", host))
+        })?;
+        let socket = TcpStream::connect_timeout(&address, TIMEOUT)?;
+        socket.set_read_timeout(Some(TIMEOUT))?;
+        socket.set_write_timeout(Some(TIMEOUT))?;
+
+        ImapClient::new(StreamOwned::new(connection, socket))
+    }
+}
+
+impl<S: Read + Write> ImapClient<S> {
+    pub fn new(stream: S) -> Result<Self, ImapError> {
+        let mut client = ImapClient {
+            stream: BufReader::new(stream),
+            tag: 0,
+        };
+        let (greeting, _) = client.read_line()?;
+        if !greeting.starts_with("* OK") && !greeting.starts_with("* PREAUTH") {
+            return Err(ImapError::Protocol(greeting));
+        }
+        Ok(client)
+    }
+
+    pub fn login(&mut self, username: &str, password: &str) -> Result<(), ImapError> {
+        self.command(&format!("LOGIN {} {}", quote(username), quote(password)))
+            .map(|_| ())
+    }
+
+    pub fn select(&mut self, mailbox: &str) -> Result<(), ImapError> {
+        self.command(&format!("SELECT {}", quote(mailbox)))
+            .map(|_| ())
+    }
+
+    /// Returns the UIDs of the unread emails of the selected mailbox.
+    pub fn unseen(&mut self) -> Result<Vec<u32>, ImapError> {
+        let answers = self.command("UID SEARCH UNSEEN")?;
+        Ok(answers
+            .iter()
+            .filter_map(|answer| answer.text.strip_prefix("* SEARCH"))
+            .flat_map(|uids| uids.split_whitespace())
+            .filter_map(|uid| uid.parse().ok())
+            .collect())
+    }
+
+    /// Returns the size of the given email, in bytes.
+    pub fn size(&mut self, uid: u32) -> Result<usize, ImapError> {
+        let answers = self.command(&format!("UID FETCH {} (RFC822.SIZE)", uid))?;
+        answers
+            .iter()
+            .filter_map(|answer| answer.text.split("RFC822.SIZE ").nth(1))
+            .filter_map(|rest| {
+                rest.split(|c: char| !c.is_ascii_digit())
+                    .next()
+                    .and_then(|size| size.parse().ok())
+            })
+            .next()
+            .ok_or_else(|| ImapError::Protocol(format!("no size for {}", uid)))
+    }
+
+    /// Returns the raw content of the given email, without marking it as read.
+    pub fn fetch(&mut self, uid: u32) -> Result<Vec<u8>, ImapError> {
+        let answers = self.command(&format!("UID FETCH {} (BODY.PEEK[])", uid))?;
+        answers
+            .into_iter()
+            .filter(|answer| answer.text.contains("FETCH"))
+            .find_map(|mut answer| answer.literals.pop())
+            .ok_or_else(|| ImapError::Protocol(format!("no content for {}", uid)))
+    }
+
+    pub fn mark_seen(&mut self, uid: u32) -> Result<(), ImapError> {
+        self.command(&format!("UID STORE {} +FLAGS.SILENT (\\Seen)", uid))
+            .map(|_| ())
+    }
+
+    pub fn logout(&mut self) -> Result<(), ImapError> {
+        self.command("LOGOUT").map(|_| ())
+    }
+
+    /// Sends a command and collects the untagged answers that precede its completion.
+    fn command(&mut self, command: &str) -> Result<Vec<Answer>, ImapError> {
+        self.tag += 1;
+        let tag = format!("a{}", self.tag);
+        let stream = self.stream.get_mut();
+        stream.write_all(format!("{} {}\r\n", tag, command).as_bytes())?;
+        stream.flush()?;
+
+        let mut answers = Vec::new();
+        loop {
+            let answer = self.read_answer()?;
+            if let Some(status) = answer.text.strip_prefix(&tag) {
+                let status = status.trim_start();
+                if status.starts_with("OK") {
+                    return Ok(answers);
+                }
+                return Err(ImapError::Refused(status.to_string()));
+            }
+            answers.push(answer);
+        }
+    }
+
+    /// Reads an answer, which spans several lines when it holds literals.
+    fn read_answer(&mut self) -> Result<Answer, ImapError> {
+        let mut answer = Answer::default();
+        loop {
+            let (line, literal_size) = self.read_line()?;
+            answer.text.push_str(&line);
+            let Some(size) = literal_size else {
+                return Ok(answer);
+            };
+            if size > MAX_LITERAL_SIZE {
+                return Err(ImapError::Protocol(format!("literal too large: {}", size)));
+            }
+            let mut literal = vec![0; size];
+            self.stream.read_exact(&mut literal)?;
+            answer.literals.push(literal);
+        }
+    }
+
+    /// Reads a line, and the size of the literal announced at its end, if any.
+    fn read_line(&mut self) -> Result<(String, Option<usize>), ImapError> {
+        let mut buf = Vec::new();
+        self.stream
+            .by_ref()
+            .take(64 * 1024)
+            .read_until(b'\n', &mut buf)?;
+        if buf.is_empty() {
+            return Err(ImapError::Io(io::ErrorKind::UnexpectedEof.into()));
+        }
+        let line = String::from_utf8_lossy(&buf).trim_end().to_string();
+        let literal_size = line
+            .strip_suffix('}')
+            .and_then(|start| start.rsplit_once('{'))
+            .and_then(|(_, size)| size.parse().ok());
+        Ok((line, literal_size))
+    }
+}
+
+/// Writes a quoted string.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// A stream replaying the answers of a server and recording the commands.
+    struct Replay {
+        answers: Cursor<Vec<u8>>,
+        commands: Vec<u8>,
+    }
+
+    impl Read for Replay {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.answers.read(buf)
+        }
+    }
+
+    impl Write for Replay {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.commands.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_fetch_unseen() {
+        let answers = b"* OK IMAP4rev1 ready\r\n\
+                        a1 OK LOGIN completed\r\n\
+                        * SEARCH 4 7\r\n\
+                        a2 OK SEARCH completed\r\n\
+                        * 2 FETCH (UID 7 BODY[] {10}\r\nHello\r\nBye)\r\n\
+                        a3 OK FETCH completed\r\n\
+                        a4 NO [READ-ONLY] Mailbox is read-only\r\n"
+            .to_vec();
+        let stream = Replay {
+            answers: Cursor::new(answers),
+            commands: Vec::new(),
+        };
+        let mut client = ImapClient::new(stream).unwrap();
+
+        client.login("reader", "pass\"word").unwrap();
+        assert_eq!(client.unseen().unwrap(), vec![4, 7]);
+        assert_eq!(client.fetch(7).unwrap(), b"Hello\r\nBye");
+        assert!(matches!(client.mark_seen(7), Err(ImapError::Refused(_))));
+
+        let commands = String::from_utf8(client.stream.into_inner().commands).unwrap();
+        assert_eq!(
+            commands,
+            "a1 LOGIN \"reader\" \"pass\\\"word\"\r\n\
+             a2 UID SEARCH UNSEEN\r\n\
+             a3 UID FETCH 7 (BODY.PEEK[])\r\n\
+             a4 UID STORE 7 +FLAGS.SILENT (\\Seen)\r\n"
+        );
+    }
+}
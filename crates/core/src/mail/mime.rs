@@ -0,0 +1,379 @@
+//! Just enough of MIME to find the sender and the attachments of an email.
+
+use base64::prelude::{Engine as _, BASE64_STANDARD};
+use percent_encoding::percent_decode_str;
+
+/// The maximum depth of the nested parts.
+const MAX_DEPTH: usize = 8;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attachment {
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+/// A header field, with its parameters.
+#[derive(Debug, Default)]
+struct Field {
+    value: String,
+    params: Vec<(String, String)>,
+}
+
+impl Field {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+struct Part<'a> {
+    headers: Vec<(String, String)>,
+    body: &'a [u8],
+}
+
+impl<'a> Part<'a> {
+    fn parse(data: &'a [u8]) -> Part<'a> {
+        let (head, body) = find(data, b"\r\n\r\n")
+            .map(|index| (&data[..index], &data[index + 4..]))
+            .or_else(|| find(data, b"\n\n").map(|index| (&data[..index], &data[index + 2..])))
+            .unwrap_or((data, &[]));
+
+        let mut headers: Vec<(String, String)> = Vec::new();
+        for line in String::from_utf8_lossy(head).lines() {
+            if line.starts_with([' ', '\t']) {
+                if let Some((_, value)) = headers.last_mut() {
+                    value.push(' ');
+                    value.push_str(line.trim());
+                }
+            } else if let Some((name, value)) = line.split_once(':') {
+                headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+            }
+        }
+
+        Part { headers, body }
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn field(&self, name: &str) -> Field {
+        self.header(name).map(parse_field).unwrap_or_default()
+    }
+}
+
+/// Returns the address of the sender of the email, in lowercase.
+pub fn sender(message: &[u8]) -> Option<String> {
+    let from = Part::parse(message).header("from")?.to_string();
+    let address = match (from.rfind('<'), from.rfind('>')) {
+        (Some(start), Some(end)) if start < end => &from[start + 1..end],
+        _ => from.as_str(),
+    };
+    let address = address.trim().to_lowercase();
+    if address.contains('@') {
+        Some(address)
+    } else {
+        None
+    }
+}
+
+/// Returns the attachments of the email, in order.
+pub fn attachments(message: &[u8]) -> Vec<Attachment> {
+    let mut attachments = Vec::new();
+    collect(&Part::parse(message), 0, &mut attachments);
+    attachments
+}
+
+fn collect(part: &Part, depth: usize, attachments: &mut Vec<Attachment>) {
+    let content_type = part.field("content-type");
+
+    if content_type.value.starts_with("multipart/") {
+        let Some(boundary) = content_type.param("boundary") else {
+            return;
+        };
+        if depth >= MAX_DEPTH {
+            return;
+        }
+        for body in split(part.body, boundary) {
+            collect(&Part::parse(body), depth + 1, attachments);
+        }
+        return;
+    }
+
+    let disposition = part.field("content-disposition");
+    let Some(name) = disposition
+        .param("filename")
+        .or_else(|| content_type.param("name"))
+        .map(decode_words)
+        .filter(|name| !name.is_empty())
+    else {
+        return;
+    };
+
+    let encoding = part
+        .header("content-transfer-encoding")
+        .unwrap_or_default()
+        .to_lowercase();
+    let data = match encoding.as_str() {
+        "base64" => {
+            let text = part
+                .body
+                .iter()
+                .filter(|byte| !byte.is_ascii_whitespace())
+                .copied()
+                .collect::<Vec<u8>>();
+            match BASE64_STANDARD.decode(text) {
+                Ok(data) => data,
+                Err(_) => return,
+            }
+        }
+        "quoted-printable" => decode_quoted_printable(part.body, false),
+        _ => part.body.to_vec(),
+    };
+
+    attachments.push(Attachment { name, data });
+}
+
+/// Splits the body of a multipart part into the bodies of its parts.
+fn split<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let mut parts = Vec::new();
+    let mut start = None;
+    let mut offset = 0;
+
+    while offset < body.len() {
+        let end = find(&body[offset..], b"\n")
+            .map(|index| offset + index + 1)
+            .unwrap_or(body.len());
+        let line = body[offset..end].trim_ascii_end();
+        if let Some(rest) = line.strip_prefix(delimiter.as_bytes()) {
+            if let Some(start) = start {
+                // The line break before the delimiter belongs to it.
+                let mut stop = offset;
+                if body[..stop].ends_with(b"\r\n") {
+                    stop -= 2;
+                } else if body[..stop].ends_with(b"\n") {
+                    stop -= 1;
+                }
+                parts.push(&body[start..stop.max(start)]);
+            }
+            if rest.starts_with(b"--") {
+                return parts;
+            }
+            start = Some(end);
+        }
+        offset = end;
+    }
+
+    parts
+}
+
+/// Parses a field such as `attachment; filename="Dune.epub"`.
+fn parse_field(text: &str) -> Field {
+    let mut chunks = split_unquoted(text, ';').into_iter();
+    let value = chunks.next().unwrap_or_default().trim().to_lowercase();
+    let mut params: Vec<(String, String)> = Vec::new();
+
+    for chunk in chunks {
+        let Some((key, value)) = chunk.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_lowercase();
+        let value = unquote(value.trim());
+        // RFC 2231: `filename*=UTF-8''Dune%20Messiah.epub`, possibly split in sections.
+        // The sections are gathered under the name followed by a star.
+        let (key, text) = if let Some(name) = key.strip_suffix('*') {
+            let text = value.splitn(3, '\'').nth(2).unwrap_or(&value);
+            let text = percent_decode_str(text).decode_utf8_lossy().into_owned();
+            (format!("{}*", name.split('*').next().unwrap_or(name)), text)
+        } else if let Some((name, _)) = key.split_once('*') {
+            (format!("{}*", name), value)
+        } else {
+            (key, value)
+        };
+        match params.iter_mut().find(|(other, _)| *other == key) {
+            Some((_, value)) if key.ends_with('*') => value.push_str(&text),
+            Some((_, value)) => *value = text,
+            None => params.push((key, text)),
+        }
+    }
+
+    // The extended values take precedence over the plain ones.
+    let (extended, mut params): (Vec<_>, Vec<_>) =
+        params.into_iter().partition(|(key, _)| key.ends_with('*'));
+    for (key, text) in extended {
+        let key = key.trim_end_matches('*').to_string();
+        params.retain(|(other, _)| *other != key);
+        params.push((key, text));
+    }
+
+    Field { value, params }
+}
+
+fn split_unquoted(text: &str, separator: char) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut quoted = false;
+    let mut escaped = false;
+    let mut start = 0;
+    for (index, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            _ if c == separator && !quoted => {
+                chunks.push(&text[start..index]);
+                start = index + 1;
+            }
+            _ => (),
+        }
+    }
+    chunks.push(&text[start..]);
+    chunks
+}
+
+fn unquote(text: &str) -> String {
+    match text
+        .strip_prefix('"')
+        .and_then(|text| text.strip_suffix('"'))
+    {
+        Some(text) => text.replace("\\\"", "\"").replace("\\\\", "\\"),
+        None => text.to_string(),
+    }
+}
+
+/// Decodes the encoded words of a header, such as `=?UTF-8?B?RHVuZS5lcHVi?=`.
+fn decode_words(text: &str) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+    let mut after_word = false;
+
+    while let Some(start) = rest.find("=?") {
+        let decoded = rest[start + 2..].splitn(4, '?').collect::<Vec<&str>>();
+        let [charset, encoding, encoded, tail] = decoded[..] else {
+            break;
+        };
+        let Some(tail) = tail.strip_prefix('=') else {
+            break;
+        };
+        let bytes = match encoding.to_ascii_lowercase().as_str() {
+            "b" => BASE64_STANDARD.decode(encoded).ok(),
+            "q" => Some(decode_quoted_printable(encoded.as_bytes(), true)),
+            _ => None,
+        };
+        let Some(bytes) = bytes else {
+            break;
+        };
+        // The white space between two encoded words is dropped.
+        let gap = &rest[..start];
+        if !(after_word && gap.trim().is_empty()) {
+            result.push_str(gap);
+        }
+        result.push_str(&decode_charset(charset, &bytes));
+        rest = tail;
+        after_word = true;
+    }
+
+    result.push_str(rest);
+    result
+}
+
+fn decode_charset(charset: &str, bytes: &[u8]) -> String {
+    let charset = charset.split('*').next().unwrap_or(charset);
+    if charset.eq_ignore_ascii_case("iso-8859-1") || charset.eq_ignore_ascii_case("latin1") {
+        bytes.iter().map(|&byte| byte as char).collect()
+    } else {
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+}
+
+/// Decodes quoted-printable data, or the *Q* encoding of the header words.
+fn decode_quoted_printable(data: &[u8], header: bool) -> Vec<u8> {
+    let mut result = Vec::with_capacity(data.len());
+    let mut index = 0;
+    while index < data.len() {
+        match data[index] {
+            b'=' if data[index + 1..].starts_with(b"\r\n") => index += 3,
+            b'=' if data[index + 1..].starts_with(b"\n") => index += 2,
+            b'=' if index + 2 < data.len() => {
+                let hex = std::str::from_utf8(&data[index + 1..index + 3]).unwrap_or_default();
+                match u8::from_str_radix(hex, 16) {
+                    Ok(byte) => {
+                        result.push(byte);
+                        index += 3;
+                    }
+                    Err(_) => {
+                        result.push(b'=');
+                        index += 1;
+                    }
+                }
+            }
+            b'_' if header => {
+                result.push(b' ');
+                index += 1;
+            }
+            byte => {
+                result.push(byte);
+                index += 1;
+            }
+        }
+    }
+    result
+}
+
+fn find(data: &[u8], needle: &[u8]) -> Option<usize> {
+    data.windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attachments() {
+        let message = "From: Paul Atreides <Paul@Arrakis.example>\r\n\
+                       Subject: Books\r\n\
+                       Content-Type: multipart/mixed;\r\n boundary=\"outer\"\r\n\
+                       \r\n\
+                       --outer\r\n\
+                       Content-Type: text/plain\r\n\
+                       \r\n\
+                       Enjoy.\r\n\
+                       --outer\r\n\
+                       Content-Type: application/epub+zip; name=\"=?UTF-8?B?RHVuZS5lcHVi?=\"\r\n\
+                       Content-Disposition: attachment\r\n\
+                       Content-Transfer-Encoding: base64\r\n\
+                       \r\n\
+                       UEsDBA==\r\n\
+                       --outer\r\n\
+                       Content-Type: application/pdf\r\n\
+                       Content-Disposition: attachment;\r\n filename*=UTF-8''Children%20of%20Dune.pdf\r\n\
+                       Content-Transfer-Encoding: quoted-printable\r\n\
+                       \r\n\
+                       %PDF=3D=\r\n1.7\r\n\
+                       --outer--\r\n";
+
+        assert_eq!(
+            sender(message.as_bytes()).as_deref(),
+            Some("paul@arrakis.example")
+        );
+        assert_eq!(
+            attachments(message.as_bytes()),
+            vec![
+                Attachment {
+                    name: "Dune.epub".to_string(),
+                    data: b"PK\x03\x04".to_vec(),
+                },
+                Attachment {
+                    name: "Children of Dune.pdf".to_string(),
+                    data: b"%PDF=1.7".to_vec(),
+                },
+            ]
+        );
+    }
+}
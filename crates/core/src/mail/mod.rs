@@ -0,0 +1,196 @@
+//! Books sent to the device by email.
+//!
+//! A dedicated mailbox is polled over IMAP: the attachments of its unread emails are saved
+//! in a library, when their kind is allowed by the import settings, and the emails are then
+//! marked as read. The attachments of the emails of unknown senders are ignored.
+
+mod imap;
+mod mime;
+
+use self::imap::ImapClient;
+use crate::net;
+use crate::settings::Settings;
+use crate::view::{Event, Hub, NotificationEvent};
+use anyhow::{format_err, Context as ResultExt, Error};
+use secrecy::ExposeSecret;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use tracing::{info, warn};
+
+/// The largest email fetched.
+const MAX_MESSAGE_SIZE: usize = 48 * 1024 * 1024;
+
+static CHECKING: AtomicBool = AtomicBool::new(false);
+
+/// Saves the attachments of the unread emails, and returns their paths.
+pub fn check(settings: &Settings) -> Result<Vec<PathBuf>, Error> {
+    let mail = &settings.mail;
+    if mail.server.is_empty() {
+        return Err(format_err!("no IMAP server configured"));
+    }
+    let library = settings
+        .libraries
+        .get(mail.library)
+        .map(|library| library.path.clone())
+        .ok_or_else(|| format_err!("no library {}", mail.library))?;
+
+    let mut client = ImapClient::connect(&mail.server, mail.port)
+        .with_context(|| format!("can't connect to {}", mail.server))?;
+    let password = mail
+        .password
+        .as_ref()
+        .map(|password| password.expose_secret())
+        .unwrap_or_default();
+    client
+        .login(&mail.username, password)
+        .context("can't log in")?;
+    client
+        .select(&mail.mailbox)
+        .with_context(|| format!("can't open {}", mail.mailbox))?;
+
+    let mut paths = Vec::new();
+    for uid in client.unseen()? {
+        let size = client.size(uid)?;
+        if size > MAX_MESSAGE_SIZE {
+            warn!(
+                "Skipping the email {}: it's too large ({} bytes).",
+                uid, size
+            );
+            client.mark_seen(uid)?;
+            continue;
+        }
+
+        let message = client.fetch(uid)?;
+        let sender = mime::sender(&message).unwrap_or_default();
+        if !mail.senders.is_empty()
+            && !mail
+                .senders
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(&sender))
+        {
+            warn!("Ignoring the email {} from {}.", uid, sender);
+            client.mark_seen(uid)?;
+            continue;
+        }
+
+        for attachment in mime::attachments(&message) {
+            let Some(name) = Path::new(&attachment.name)
+                .file_name()
+                .and_then(|name| name.to_str())
+                .filter(|name| !name.starts_with('.'))
+            else {
+                continue;
+            };
+            let allowed = Path::new(name)
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .map(|extension| {
+                    settings
+                        .import
+                        .allowed_kinds
+                        .contains(&extension.to_lowercase())
+                })
+                .unwrap_or(false);
+            if !allowed {
+                continue;
+            }
+
+            let path = unique_path(&library, name);
+            fs::write(&path, &attachment.data)
+                .with_context(|| format!("can't save {}", path.display()))?;
+            info!("Received {} from {}.", path.display(), sender);
+            paths.push(path);
+        }
+
+        client.mark_seen(uid)?;
+    }
+
+    client.logout().ok();
+    Ok(paths)
+}
+
+/// Returns a path, in the given directory, that doesn't exist yet.
+fn unique_path(dir: &Path, name: &str) -> PathBuf {
+    let path = dir.join(name);
+    if !path.exists() {
+        return path;
+    }
+    let name = Path::new(name);
+    let stem = name
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default();
+    let extension = name
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+    (2..)
+        .map(|index| dir.join(format!("{} ({}){}", stem, index, extension)))
+        .find(|path| !path.exists())
+        .unwrap_or(path)
+}
+
+/// Checks the mailbox in the background, unless a check is already running.
+///
+/// Each saved attachment is announced by an `Event::FileReceived`. Unless `quiet` is set,
+/// a notification is shown when there was nothing to save. Returns whether a check was
+/// started.
+pub fn spawn_check(settings: &Settings, hub: &Hub, quiet: bool) -> bool {
+    if CHECKING.swap(true, Ordering::AcqRel) {
+        return false;
+    }
+
+    let settings = settings.clone();
+    let hub = hub.clone();
+
+    thread::spawn(move || {
+        let _job = net::Job::start();
+        let result = check(&settings);
+        CHECKING.store(false, Ordering::Release);
+        match result {
+            Ok(paths) => {
+                if paths.is_empty() && !quiet {
+                    hub.send(Event::Notification(NotificationEvent::Show(
+                        "No new books in the mailbox.".to_string(),
+                    )))
+                    .ok();
+                }
+                for path in paths {
+                    hub.send(Event::FileReceived(path)).ok();
+                }
+            }
+            Err(e) => {
+                warn!("Can't check the mailbox: {:#}.", e);
+                hub.send(Event::Notification(NotificationEvent::Show(format!(
+                    "Can't check the mailbox: {:#}.",
+                    e
+                ))))
+                .ok();
+            }
+        }
+    });
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique_path() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            unique_path(dir.path(), "Dune.epub"),
+            dir.path().join("Dune.epub")
+        );
+        fs::write(dir.path().join("Dune.epub"), b"").unwrap();
+        fs::write(dir.path().join("Dune (2).epub"), b"").unwrap();
+        assert_eq!(
+            unique_path(dir.path(), "Dune.epub"),
+            dir.path().join("Dune (3).epub")
+        );
+    }
+}
@@ -3,6 +3,7 @@ use crate::document::djvu::DjvuOpener;
 use crate::document::epub::EpubDocument;
 use crate::document::html::HtmlDocument;
 use crate::document::pdf::PdfOpener;
+use crate::document::zim::ZimArchive;
 use crate::document::{Document, SimpleTocEntry, TextLocation};
 use crate::geom::Point;
 use crate::helpers::datetime_format;
@@ -22,6 +23,7 @@ use tracing::{error, warn};
 
 pub const DEFAULT_CONTRAST_EXPONENT: f32 = 1.0;
 pub const DEFAULT_CONTRAST_GRAY: f32 = 224.0;
+pub const DEFAULT_BRIGHTNESS: f32 = 0.0;
 
 pub type Metadata = Vec<Info>;
 
@@ -232,6 +234,12 @@ pub struct ReaderInfo {
     pub contrast_exponent: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub contrast_gray: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub brightness: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stylesheet: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vertical_text: Option<bool>,
     #[serde(skip_serializing_if = "BTreeMap::is_empty")]
     pub page_names: BTreeMap<usize, String>,
     #[serde(skip_serializing_if = "BTreeSet::is_empty")]
@@ -293,6 +301,9 @@ impl Default for ReaderInfo {
             line_height: None,
             contrast_exponent: None,
             contrast_gray: None,
+            brightness: None,
+            stylesheet: None,
+            vertical_text: None,
             page_names: BTreeMap::new(),
             bookmarks: BTreeSet::new(),
             annotations: Vec::new(),
@@ -914,6 +925,19 @@ pub fn extract_metadata_from_document(prefix: &Path, info: &mut Info) {
             }
             None => error!("Can't open {}.", info.file.path.display()),
         },
+        "zim" => match ZimArchive::open(&path) {
+            Ok(archive) => {
+                info.title = archive.metadata("Title").unwrap_or_default();
+                info.author = archive.metadata("Creator").unwrap_or_default();
+                info.publisher = archive.metadata("Publisher").unwrap_or_default();
+                info.language = archive.metadata("Language").unwrap_or_default();
+                info.year = archive
+                    .metadata("Date")
+                    .and_then(|date| date.get(..4).map(String::from))
+                    .unwrap_or_default();
+            }
+            Err(e) => error!("Can't open {}: {:#}.", info.file.path.display(), e),
+        },
         _ => {
             warn!(
                 "Don't know how to extract metadata from {}.",
@@ -0,0 +1,126 @@
+//! The construction of the HTTP clients, which share their TLS configuration and proxy.
+
+use crate::settings::{ProxySettings, Settings};
+use crate::tls;
+use lazy_static::lazy_static;
+use reqwest::blocking::{Client, ClientBuilder};
+use reqwest::{NoProxy, Proxy};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::RwLock;
+use std::time::Duration;
+use tracing::warn;
+
+lazy_static! {
+    static ref PROXY: RwLock<ProxySettings> = RwLock::new(ProxySettings::default());
+}
+
+// The number of network jobs running in the background.
+static JOBS: AtomicUsize = AtomicUsize::new(0);
+
+/// A network job running in the background, such as a sync: the Wi-Fi isn't turned off for
+/// inactivity until it's dropped.
+pub struct Job(());
+
+impl Job {
+    pub fn start() -> Job {
+        JOBS.fetch_add(1, Ordering::AcqRel);
+        Job(())
+    }
+}
+
+impl Drop for Job {
+    fn drop(&mut self) {
+        JOBS.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Returns whether network jobs are running in the background.
+pub fn is_busy() -> bool {
+    JOBS.load(Ordering::Acquire) > 0
+}
+
+/// Sets the TLS configuration and the proxy of the clients built from now on.
+pub fn configure(settings: &Settings) {
+    tls::configure(&settings.tls);
+    *PROXY.write().unwrap() = settings.proxy.clone();
+}
+
+/// Starts building a client with the given user agent, which gives up on the requests
+/// that last longer than the timeout.
+pub fn client_builder(user_agent: &str, timeout: Duration) -> ClientBuilder {
+    builder(user_agent, timeout, &PROXY.read().unwrap())
+}
+
+fn builder(user_agent: &str, timeout: Duration, settings: &ProxySettings) -> ClientBuilder {
+    let builder = Client::builder()
+        .use_preconfigured_tls(tls::client_config(tls::root_store()))
+        .user_agent(user_agent)
+        .connect_timeout(timeout)
+        .timeout(timeout);
+    match proxy(settings) {
+        Some(proxy) => builder.proxy(proxy),
+        None => builder,
+    }
+}
+
+/// Returns the proxy of the settings, if one is set and valid.
+fn proxy(settings: &ProxySettings) -> Option<Proxy> {
+    let url = settings.url.as_deref().filter(|url| !url.is_empty())?;
+    match Proxy::all(url) {
+        Ok(proxy) => Some(proxy.no_proxy(NoProxy::from_string(&settings.exclude.join(",")))),
+        Err(e) => {
+            warn!("invalid proxy {}: {}", url, e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    #[test]
+    fn test_requests_go_through_the_proxy() {
+        rustls::crypto::ring::default_provider()
+            .install_default()
+            .ok();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut line = String::new();
+            BufReader::new(&stream).read_line(&mut line).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+                .unwrap();
+            line
+        });
+
+        let settings = ProxySettings {
+            url: Some(format!("http://127.0.0.1:{}", port)),
+            exclude: vec!["localhost".to_string()],
+        };
+        let client = builder("cadmus-test", Duration::from_secs(5), &settings)
+            .build()
+            .unwrap();
+        let body = client
+            .get("http://books.example.org/feed")
+            .send()
+            .and_then(|response| response.text())
+            .unwrap();
+        assert_eq!(body, "ok");
+        assert_eq!(
+            server.join().unwrap().trim_end(),
+            "GET http://books.example.org/feed HTTP/1.1"
+        );
+
+        let settings = ProxySettings {
+            url: Some("not a url".to_string()),
+            ..Default::default()
+        };
+        assert!(proxy(&settings).is_none());
+    }
+}
@@ -1,6 +1,5 @@
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 use reqwest::blocking::Client;
-use rustls::RootCertStore;
 use secrecy::{ExposeSecret, SecretString};
 use serde::Deserialize;
 use std::fs::File;
@@ -9,17 +8,14 @@ use std::path::PathBuf;
 use std::time::Duration;
 use zip::ZipArchive;
 
+use crate::download;
+use crate::net;
+
 #[cfg(all(not(test), not(feature = "emulator")))]
 use crate::settings::INTERNAL_CARD_ROOT;
 
-/// Size of each download chunk in bytes (10 MB)
-const CHUNK_SIZE: usize = 10 * 1024 * 1024;
-
-/// Timeout for each chunk download attempt in seconds
-const CHUNK_TIMEOUT_SECS: u64 = 30;
-
-/// Maximum number of retry attempts for failed chunks
-const MAX_RETRIES: usize = 3;
+/// Timeout for each API request in seconds
+const REQUEST_TIMEOUT_SECS: u64 = 30;
 
 /// HTTP client for downloading OTA updates from GitHub.
 ///
@@ -194,24 +190,9 @@ impl OtaClient {
     /// The token is stored securely and will never appear in debug output or logs.
     /// It is only exposed when making authenticated API requests.
     pub fn new(github_token: Option<SecretString>) -> Result<Self, OtaError> {
-        tracing::debug!("Initializing OTA client with webpki-roots certificates");
-
-        let root_store = create_webpki_root_store();
-        tracing::debug!(
-            certificate_count = root_store.len(),
-            "Created root certificate store"
-        );
+        tracing::debug!("Initializing OTA client");
 
-        let tls_config = rustls::ClientConfig::builder()
-            .with_root_certificates(root_store)
-            .with_no_client_auth();
-
-        tracing::debug!("Built TLS configuration");
-
-        let client = Client::builder()
-            .use_preconfigured_tls(tls_config)
-            .user_agent("cadmus-ota")
-            .timeout(Duration::from_secs(CHUNK_TIMEOUT_SECS))
+        let client = net::client_builder("cadmus-ota", Duration::from_secs(REQUEST_TIMEOUT_SECS))
             .build()
             .map_err(|e| OtaError::TlsConfig(format!("Failed to build HTTP client: {}", e)))?;
 
@@ -903,10 +884,10 @@ impl OtaClient {
             })
     }
 
-    /// Downloads a file from a URL with chunked transfer and progress reporting.
+    /// Downloads a file from a URL through the download queue, with progress reporting.
     ///
-    /// Uses HTTP Range headers to request the file in chunks for resilience
-    /// against network interruptions.
+    /// The queue resumes the transfer with HTTP Range headers when the network is
+    /// interrupted, and retries with an exponential backoff.
     ///
     /// # Arguments
     ///
@@ -939,41 +920,27 @@ impl OtaClient {
         tracing::debug!(url = %url, "Downloading file");
         tracing::debug!(path = ?download_path, "Download destination");
 
-        let mut file = File::create(download_path)?;
-
-        let mut downloaded = 0u64;
-
-        tracing::debug!(
-            chunk_size_mb = CHUNK_SIZE / (1024 * 1024),
-            "Starting chunked download"
-        );
-
-        while downloaded < total_size {
-            let chunk_start = downloaded;
-            let chunk_end = std::cmp::min(downloaded + CHUNK_SIZE as u64 - 1, total_size - 1);
-
-            tracing::debug!(chunk_start, chunk_end, total_size, "Downloading chunk");
-
-            let chunk_data =
-                self.download_chunk_with_retries(url, chunk_start, chunk_end, use_auth)?;
-
-            file.write_all(&chunk_data)?;
-            downloaded += chunk_data.len() as u64;
-
-            progress_callback(OtaProgress::DownloadingArtifact {
-                downloaded,
-                total: total_size,
-            });
-
-            tracing::debug!(
-                downloaded,
-                total_size,
-                progress_percent = (downloaded as f64 / total_size as f64) * 100.0,
-                "Download progress"
-            );
+        let mut request = download::Request::new("the update", url, download_path.clone());
+        request.quiet = true;
+        if use_auth {
+            request.token = Some(self.get_token()?.clone());
         }
 
-        tracing::debug!(bytes = downloaded, "Download complete");
+        download::enqueue(request, None)
+            .wait(|progress| match progress {
+                download::Progress::Transferred { downloaded, total } => {
+                    progress_callback(OtaProgress::DownloadingArtifact {
+                        downloaded: *downloaded,
+                        total: total.unwrap_or(total_size),
+                    });
+                }
+                download::Progress::Retrying { attempt, error } => {
+                    tracing::warn!(attempt, error = %error, "Download interrupted, retrying");
+                }
+                _ => (),
+            })
+            .map_err(|e| OtaError::Api(format!("Failed to download: {:#}", e)))?;
+
         tracing::debug!(path = ?download_path, "Saved file");
 
         Ok(())
@@ -1005,114 +972,6 @@ impl OtaClient {
         )
     }
 
-    /// Downloads a specific byte range of a file with automatic retry logic.
-    ///
-    /// Uses HTTP Range headers to request a specific chunk of the artifact.
-    /// Implements exponential backoff retry strategy for failed downloads.
-    ///
-    /// # Arguments
-    ///
-    /// * `url` - The download URL
-    /// * `start` - Starting byte offset (inclusive)
-    /// * `end` - Ending byte offset (inclusive)
-    ///
-    /// # Returns
-    ///
-    /// The downloaded chunk data as a byte vector.
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if all retry attempts fail.
-    fn download_chunk_with_retries(
-        &self,
-        url: &str,
-        start: u64,
-        end: u64,
-        use_auth: bool,
-    ) -> Result<Vec<u8>, OtaError> {
-        let mut last_error = None;
-
-        for attempt in 1..=MAX_RETRIES {
-            match self.download_chunk(url, start, end, use_auth) {
-                Ok(data) => {
-                    if attempt > 1 {
-                        tracing::debug!(
-                            attempt,
-                            max_retries = MAX_RETRIES,
-                            "Chunk download succeeded after retry"
-                        );
-                    }
-                    return Ok(data);
-                }
-                Err(e) => {
-                    tracing::warn!(
-                        attempt,
-                        max_retries = MAX_RETRIES,
-                        error = %e,
-                        "Chunk download failed"
-                    );
-                    last_error = Some(e);
-
-                    if attempt < MAX_RETRIES {
-                        let backoff_ms = 1000 * (2u64.pow(attempt as u32 - 1));
-                        tracing::debug!(backoff_ms, "Retrying after backoff");
-                        std::thread::sleep(Duration::from_millis(backoff_ms));
-                    }
-                }
-            }
-        }
-
-        Err(last_error.unwrap_or_else(|| {
-            OtaError::Api("Failed to download chunk after all retries".to_string())
-        }))
-    }
-
-    /// Downloads a specific byte range from a URL using HTTP Range header.
-    ///
-    /// # Arguments
-    ///
-    /// * `url` - The download URL
-    /// * `start` - Starting byte offset (inclusive)
-    /// * `end` - Ending byte offset (inclusive)
-    /// * `use_auth` - Whether to include Authorization header
-    ///
-    /// # Returns
-    ///
-    /// The downloaded chunk data as a byte vector.
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if the download fails or times out.
-    fn download_chunk(
-        &self,
-        url: &str,
-        start: u64,
-        end: u64,
-        use_auth: bool,
-    ) -> Result<Vec<u8>, OtaError> {
-        let range_header = format!("bytes={}-{}", start, end);
-
-        let mut request = self.client.get(url).header("Range", range_header);
-
-        if use_auth {
-            if let Some(ref token) = self.token {
-                request =
-                    request.header("Authorization", format!("Bearer {}", token.expose_secret()));
-            } else {
-                tracing::error!("Attempted authenticated download without token");
-                return Err(OtaError::NoToken);
-            }
-        }
-
-        let response = request
-            .send()?
-            .error_for_status()
-            .map_err(|e| OtaError::Api(format!("Failed to download chunk: {}", e)))?;
-
-        let bytes = response.bytes()?;
-        Ok(bytes.to_vec())
-    }
-
     /// Downloads a release asset to the specified path with chunked transfer and progress reporting.
     ///
     /// GitHub authentication is not required for this operation as release
@@ -1168,47 +1027,11 @@ fn check_disk_space(path: &str) -> Result<(), OtaError> {
     Ok(())
 }
 
-/// Creates a root certificate store with Mozilla's trusted CA certificates.
-///
-/// Uses the webpki-roots crate which embeds Mozilla's CA certificate bundle
-/// for verifying HTTPS connections to GitHub's API.
-fn create_webpki_root_store() -> RootCertStore {
-    tracing::debug!("Loading Mozilla root certificates from webpki-roots");
-    let mut root_store = RootCertStore::empty();
-
-    root_store.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
-
-    tracing::debug!(
-        certificate_count = root_store.len(),
-        "Loaded root certificates from webpki-roots"
-    );
-    root_store
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use tempfile::TempDir;
 
-    #[test]
-    fn test_create_webpki_root_store() {
-        let root_store = create_webpki_root_store();
-        assert!(
-            !root_store.is_empty(),
-            "Root certificate store should not be empty"
-        );
-    }
-
-    #[test]
-    fn test_create_webpki_root_store_has_certificates() {
-        let root_store = create_webpki_root_store();
-        assert!(
-            root_store.len() > 50,
-            "Expected at least 50 root certificates, got {}",
-            root_store.len()
-        );
-    }
-
     #[test]
     fn test_ota_error_from_reqwest_error() {
         let reqwest_error = reqwest::blocking::Client::new()
@@ -0,0 +1,207 @@
+//! Recording the events received by the view tree, and replaying them.
+//!
+//! When `logging.record-events` is enabled, every event taken from the hub is appended to
+//! `events-<run_id>.jsonl` in the log directory, one JSON record per line, along with the
+//! number of milliseconds elapsed since the recording started:
+//!
+//! ```text
+//! {"time":1520,"event":{"gesture":{"Tap":{"x":312,"y":870}}}}
+//! {"time":1521,"event":{"other":"Select(ToggleFrontlight)"}}
+//! ```
+//!
+//! The device events and the gestures are stored as is. The other events are only described,
+//! for reading: the views emit them again when the inputs are replayed.
+//!
+//! The emulator replays a recording with `./run-emulator.sh -- --replay <path>`, sending the
+//! inputs through the hub at the pace they were recorded.
+
+use crate::gesture::GestureEvent;
+use crate::input::DeviceEvent;
+use crate::kiosk;
+use crate::logging::get_run_id;
+use crate::settings::LoggingSettings;
+use crate::view::{Event, Hub};
+use anyhow::{Context, Error};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const EVENTS_FILE_PREFIX: &str = "events-";
+const EVENTS_FILE_SUFFIX: &str = "jsonl";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RecordedEvent {
+    Device(DeviceEvent),
+    Gesture(GestureEvent),
+    Other(String),
+}
+
+impl RecordedEvent {
+    pub fn new(evt: &Event) -> RecordedEvent {
+        match evt {
+            Event::Device(de) => RecordedEvent::Device(*de),
+            Event::Gesture(ge) => RecordedEvent::Gesture(*ge),
+            _ => RecordedEvent::Other(kiosk::describe(evt)),
+        }
+    }
+
+    /// Returns the event to send when replaying, if it is an input.
+    pub fn to_event(&self) -> Option<Event> {
+        match self {
+            RecordedEvent::Device(de) => Some(Event::Device(*de)),
+            RecordedEvent::Gesture(ge) => Some(Event::Gesture(*ge)),
+            RecordedEvent::Other(..) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Record {
+    /// The number of milliseconds elapsed since the start of the recording.
+    pub time: u64,
+    pub event: RecordedEvent,
+}
+
+pub struct Recorder {
+    start: Instant,
+    file: File,
+}
+
+impl Recorder {
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Recorder, Error> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("can't create directory {}", parent.display()))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("can't open events file {}", path.display()))?;
+        Ok(Recorder {
+            start: Instant::now(),
+            file,
+        })
+    }
+
+    /// Returns a recorder writing to the log directory, if the setting is enabled.
+    pub fn from_settings(settings: &LoggingSettings) -> Result<Option<Recorder>, Error> {
+        if !settings.record_events {
+            return Ok(None);
+        }
+        Recorder::new(events_path(&settings.directory)).map(Some)
+    }
+
+    pub fn record(&mut self, evt: &Event) -> Result<(), Error> {
+        let record = Record {
+            time: self.start.elapsed().as_millis() as u64,
+            event: RecordedEvent::new(evt),
+        };
+        // Each line is written at once, so that a crash can't truncate the previous ones.
+        let mut line = serde_json::to_string(&record)?;
+        line.push('\n');
+        self.file
+            .write_all(line.as_bytes())
+            .context("can't write event")
+    }
+}
+
+pub fn events_path<P: AsRef<Path>>(directory: P) -> PathBuf {
+    directory.as_ref().join(format!(
+        "{}{}.{}",
+        EVENTS_FILE_PREFIX,
+        get_run_id(),
+        EVENTS_FILE_SUFFIX
+    ))
+}
+
+pub fn load_records<P: AsRef<Path>>(path: P) -> Result<Vec<Record>, Error> {
+    let path = path.as_ref();
+    let file =
+        File::open(path).with_context(|| format!("can't open events file {}", path.display()))?;
+    let mut records = Vec::new();
+
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str(&line)
+            .with_context(|| format!("can't parse line {} of {}", index + 1, path.display()))?;
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+/// Sends the recorded inputs through the hub, with the delays that separated them.
+pub fn replay(records: Vec<Record>, hub: Hub) {
+    thread::spawn(move || {
+        let start = Instant::now();
+        for record in records {
+            let Some(evt) = record.event.to_event() else {
+                continue;
+            };
+            let due = Duration::from_millis(record.time);
+            if let Some(delay) = due.checked_sub(start.elapsed()) {
+                thread::sleep(delay);
+            }
+            if hub.send(evt).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::Dir;
+    use crate::view::EntryId;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_recorded_inputs_are_replayed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.jsonl");
+        let mut recorder = Recorder::new(&path).unwrap();
+
+        let swipe = GestureEvent::Swipe {
+            dir: Dir::West,
+            start: pt!(500, 300),
+            end: pt!(100, 310),
+        };
+        recorder.record(&Event::Gesture(swipe)).unwrap();
+        recorder
+            .record(&Event::Select(EntryId::ToggleInspector))
+            .unwrap();
+        recorder
+            .record(&Event::Device(DeviceEvent::RotateScreen(1)))
+            .unwrap();
+
+        let records = load_records(&path).unwrap();
+        assert_eq!(records.len(), 3);
+        assert!(matches!(
+            records[1].event,
+            RecordedEvent::Other(ref text) if text == "Select(ToggleInspector)"
+        ));
+
+        let (hub, receiver) = channel();
+        replay(records, hub);
+        let events: Vec<Event> = receiver.iter().collect();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            events[0],
+            Event::Gesture(GestureEvent::Swipe { dir: Dir::West, end, .. }) if end == pt!(100, 310)
+        ));
+        assert!(matches!(
+            events[1],
+            Event::Device(DeviceEvent::RotateScreen(1))
+        ));
+    }
+}
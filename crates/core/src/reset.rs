@@ -0,0 +1,116 @@
+//! Resetting the application to its first-run state.
+//!
+//! A reset removes the settings, the queues and histories kept next to the program, and the
+//! database of each library: its metadata, reading states and thumbnail previews. The books
+//! themselves are left untouched. The removed files can first be saved in an
+//! [archive](crate::backup).
+
+use crate::battery::BATTERY_HISTORY_PATH;
+use crate::library::{
+    FAT32_EPOCH_FILENAME, METADATA_FILENAME, READING_STATES_DIRNAME, THUMBNAIL_PREVIEWS_DIRNAME,
+};
+use crate::settings::{Settings, SETTINGS_PATH};
+use crate::sync_queue::SYNC_QUEUE_PATH;
+use anyhow::{Context, Error};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The directory of the archives holding the databases of the libraries, by index.
+pub const LIBRARIES_DIRNAME: &str = "libraries";
+
+/// The files kept next to the program.
+pub const ROOT_FILENAMES: [&str; 3] = [SETTINGS_PATH, SYNC_QUEUE_PATH, BATTERY_HISTORY_PATH];
+
+/// The files, and directories, of the database of a library.
+pub const LIBRARY_FILENAMES: [&str; 4] = [
+    METADATA_FILENAME,
+    FAT32_EPOCH_FILENAME,
+    READING_STATES_DIRNAME,
+    THUMBNAIL_PREVIEWS_DIRNAME,
+];
+
+/// A file, or directory, removed by a reset.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Entry {
+    pub path: PathBuf,
+    /// The path of the copy in a backup archive.
+    pub name: PathBuf,
+}
+
+/// Returns the existing entries removed by a reset.
+///
+/// The paths of the files kept next to the program are relative to `root`.
+pub fn entries(settings: &Settings, root: &Path) -> Vec<Entry> {
+    let mut entries = Vec::new();
+
+    for file_name in ROOT_FILENAMES {
+        entries.push(Entry {
+            path: root.join(file_name),
+            name: PathBuf::from(file_name),
+        });
+    }
+
+    for (index, library) in settings.libraries.iter().enumerate() {
+        for file_name in LIBRARY_FILENAMES {
+            entries.push(Entry {
+                path: library.path.join(file_name),
+                name: Path::new(LIBRARIES_DIRNAME)
+                    .join(index.to_string())
+                    .join(file_name),
+            });
+        }
+    }
+
+    entries.retain(|entry| entry.path.exists());
+    entries
+}
+
+/// Removes the entries.
+pub fn wipe(entries: &[Entry]) -> Result<(), Error> {
+    for entry in entries {
+        if entry.path.is_dir() {
+            fs::remove_dir_all(&entry.path)
+        } else {
+            fs::remove_file(&entry.path)
+        }
+        .with_context(|| format!("can't remove {}", entry.path.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::LibrarySettings;
+
+    #[test]
+    fn test_reset_keeps_the_books() {
+        let root = tempfile::tempdir().unwrap();
+        let home = tempfile::tempdir().unwrap();
+        fs::write(root.path().join(SETTINGS_PATH), "language = \"fr\"\n").unwrap();
+        fs::write(home.path().join(METADATA_FILENAME), "{}").unwrap();
+        fs::create_dir(home.path().join(READING_STATES_DIRNAME)).unwrap();
+        fs::write(
+            home.path().join(READING_STATES_DIRNAME).join("a.json"),
+            "{}",
+        )
+        .unwrap();
+        fs::write(home.path().join("book.epub"), "").unwrap();
+
+        let settings = Settings {
+            libraries: vec![LibrarySettings {
+                path: home.path().to_path_buf(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let entries = entries(&settings, root.path());
+        assert_eq!(entries.len(), 3);
+
+        wipe(&entries).unwrap();
+
+        assert!(!root.path().join(SETTINGS_PATH).exists());
+        assert!(!home.path().join(READING_STATES_DIRNAME).exists());
+        assert!(home.path().join("book.epub").exists());
+    }
+}
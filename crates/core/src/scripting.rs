@@ -0,0 +1,442 @@
+//! The Rhai scripts that automate some behaviors.
+//!
+//! The `.rhai` files of the `Scripts` directory are compiled at startup. A script reacts to
+//! the events by defining some of the following functions:
+//!
+//! ```rhai
+//! fn on_startup() {}
+//! fn on_network_up() {}
+//! fn on_document_opened(path) {}
+//! fn on_suspend() {}
+//! fn on_resume() {}
+//! fn on_clock(now) {}
+//! ```
+//!
+//! `on_clock` is called once per minute with the current time, a map holding `year`, `month`,
+//! `day`, `weekday` (from 1 for Monday to 7), `hour`, `minute` and the `date` as `YYYY-MM-DD`.
+//!
+//! The functions can call `toast(text)`, `open(path)`, `library_search(query)`, which returns
+//! up to 100 books of the current library matching the query of the home search bar, each a
+//! map holding `title`, `author`, `year`, `series`, `kind` and `path`, and `setting(key)`, which
+//! returns the value of a setting given by its dotted name, as in `"reader.font-size"`. The
+//! secrets of the settings aren't readable, and the scripts can't reach the files nor the
+//! network. A script that runs for too long is stopped.
+
+use crate::context::Context;
+use crate::metadata::{BookQuery, Info};
+use crate::settings::HookTrigger;
+use crate::view::{Event, Hub, NotificationEvent};
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, Timelike};
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{Array, CallFnOptions, Dynamic, Engine, Map, Scope, AST};
+use serde_json::Value as JsonValue;
+use std::cell::{Cell, RefCell};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use tracing::{error, info, warn};
+
+pub const SCRIPTS_DIRNAME: &str = "Scripts";
+
+/// The number of operations after which a call is stopped.
+const MAX_OPERATIONS: u64 = 500_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_STRING_SIZE: usize = 64 * 1024;
+const MAX_ARRAY_SIZE: usize = 10_000;
+
+/// The number of books returned by `library_search`.
+const MAX_SEARCH_RESULTS: usize = 100;
+
+/// The settings holding these words can't be read.
+const SECRET_KEYS: [&str; 3] = ["password", "token", "userkey"];
+
+/// The settings with these names can't be read, such as the digest of the kiosk PIN, which
+/// could be brute-forced with the device identifier it's salted with.
+const SECRET_NAMES: [&str; 1] = ["pin"];
+
+/// The tables of the settings that can't be read at all.
+const SECRET_TABLES: [&str; 1] = ["kiosk"];
+
+struct Script {
+    name: String,
+    ast: AST,
+    // Whether the library and the settings are read, to only gather them for these scripts.
+    searches_library: bool,
+    reads_settings: bool,
+}
+
+/// What the functions of the API see and produce during a call.
+#[derive(Default)]
+struct State {
+    home: PathBuf,
+    books: Vec<Info>,
+    settings: JsonValue,
+    events: Vec<Event>,
+}
+
+pub struct Scripts {
+    engine: Engine,
+    scripts: Vec<Script>,
+    state: Rc<RefCell<State>>,
+    // The minute of the last call of `on_clock`.
+    last_minute: Cell<Option<NaiveDateTime>>,
+}
+
+impl Default for Scripts {
+    fn default() -> Self {
+        let state = Rc::new(RefCell::new(State::default()));
+        Scripts {
+            engine: engine(&state),
+            scripts: Vec::new(),
+            state,
+            last_minute: Cell::new(None),
+        }
+    }
+}
+
+impl Scripts {
+    /// Compiles the scripts of the directory, skipping those that don't compile.
+    pub fn load(dir: &Path) -> Scripts {
+        let mut scripts = Scripts::default();
+        let Ok(entries) = fs::read_dir(dir) else {
+            return scripts;
+        };
+        let mut paths = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "rhai"))
+            .collect::<Vec<PathBuf>>();
+        paths.sort();
+
+        for path in paths {
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let source = match fs::read_to_string(&path) {
+                Ok(source) => source,
+                Err(e) => {
+                    error!("Can't read the script {}: {}.", name, e);
+                    continue;
+                }
+            };
+            match scripts.engine.compile(&source) {
+                Ok(ast) => {
+                    info!(script = name, "compiled script");
+                    scripts.scripts.push(Script {
+                        name,
+                        ast,
+                        searches_library: source.contains("library_search"),
+                        reads_settings: source.contains("setting"),
+                    });
+                }
+                Err(e) => error!("Can't compile the script {}: {}.", name, e),
+            }
+        }
+
+        scripts
+    }
+
+    /// Calls the function of each script bound to the event.
+    pub fn trigger(&self, trigger: HookTrigger, path: Option<&Path>, context: &Context, hub: &Hub) {
+        let function = format!("on_{}", trigger.as_str().replace('-', "_"));
+        let args = path
+            .map(|path| vec![Dynamic::from(path.to_string_lossy().into_owned())])
+            .unwrap_or_default();
+        self.call(&function, args, context, hub);
+    }
+
+    /// Calls `on_clock`, unless it was already called during the current minute.
+    pub fn clock(&self, now: DateTime<Local>, context: &Context, hub: &Hub) {
+        let minute = now
+            .naive_local()
+            .with_second(0)
+            .and_then(|time| time.with_nanosecond(0));
+        if minute.is_none() || self.last_minute.get() == minute {
+            return;
+        }
+        self.last_minute.set(minute);
+        let mut time = Map::new();
+        time.insert("year".into(), (now.year() as i64).into());
+        time.insert("month".into(), (now.month() as i64).into());
+        time.insert("day".into(), (now.day() as i64).into());
+        time.insert(
+            "weekday".into(),
+            (now.weekday().number_from_monday() as i64).into(),
+        );
+        time.insert("hour".into(), (now.hour() as i64).into());
+        time.insert("minute".into(), (now.minute() as i64).into());
+        time.insert("date".into(), now.format("%Y-%m-%d").to_string().into());
+        self.call("on_clock", vec![Dynamic::from_map(time)], context, hub);
+    }
+
+    fn call(&self, function: &str, args: Vec<Dynamic>, context: &Context, hub: &Hub) {
+        for script in &self.scripts {
+            if !script
+                .ast
+                .iter_functions()
+                .any(|f| f.name == function && f.params.len() == args.len())
+            {
+                continue;
+            }
+
+            self.prepare(script, context);
+            let options = CallFnOptions::new().eval_ast(false);
+            let result = self.engine.call_fn_with_options::<Dynamic>(
+                options,
+                &mut Scope::new(),
+                &script.ast,
+                function,
+                args.clone(),
+            );
+            if let Err(e) = result {
+                error!("The script {} failed in {}: {}.", script.name, function, e);
+            }
+
+            let mut state = self.state.borrow_mut();
+            for evt in state.events.drain(..) {
+                hub.send(evt).ok();
+            }
+            state.books.clear();
+            state.settings = JsonValue::Null;
+        }
+    }
+
+    fn prepare(&self, script: &Script, context: &Context) {
+        let mut state = self.state.borrow_mut();
+        state.home = context.library.home.clone();
+        if script.searches_library {
+            let (books, _) =
+                context
+                    .library
+                    .list(&context.library.home, Some(&BookQuery::default()), false);
+            state.books = books;
+        }
+        if script.reads_settings {
+            state.settings = serde_json::to_value(&context.settings)
+                .map(|mut value| {
+                    redact(&mut value);
+                    value
+                })
+                .unwrap_or_else(|e| {
+                    warn!("Can't expose the settings to the scripts: {}.", e);
+                    JsonValue::Null
+                });
+        }
+    }
+}
+
+fn engine(state: &Rc<RefCell<State>>) -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_string_size(MAX_STRING_SIZE)
+        .set_max_array_size(MAX_ARRAY_SIZE)
+        .set_max_map_size(MAX_ARRAY_SIZE)
+        .set_module_resolver(DummyModuleResolver::new())
+        .on_print(|text| info!(target: "script", "{}", text))
+        .on_debug(|text, _, _| info!(target: "script", "{}", text));
+
+    let state2 = Rc::clone(state);
+    engine.register_fn("toast", move |text: &str| {
+        state2
+            .borrow_mut()
+            .events
+            .push(Event::Notification(NotificationEvent::Show(
+                text.to_string(),
+            )));
+    });
+
+    let state2 = Rc::clone(state);
+    engine.register_fn("open", move |path: &str| {
+        let mut state = state2.borrow_mut();
+        let path = Path::new(path);
+        let path = path.strip_prefix(&state.home).unwrap_or(path).to_path_buf();
+        state.events.push(Event::RemoteOpen(path));
+    });
+
+    let state2 = Rc::clone(state);
+    engine.register_fn("library_search", move |query: &str| -> Array {
+        let state = state2.borrow();
+        let query = BookQuery::new(query);
+        state
+            .books
+            .iter()
+            .filter(|info| {
+                query.as_ref().is_none_or(|query| {
+                    query.is_match(info)
+                        || (info.title.is_empty()
+                            && info
+                                .file
+                                .path
+                                .to_str()
+                                .is_some_and(|path| query.is_simple_match(path)))
+                })
+            })
+            .take(MAX_SEARCH_RESULTS)
+            .map(book)
+            .collect()
+    });
+
+    let state2 = Rc::clone(state);
+    engine.register_fn("setting", move |key: &str| -> Dynamic {
+        let state = state2.borrow();
+        key.split('.')
+            .try_fold(&state.settings, |value, name| value.get(name))
+            .and_then(|value| rhai::serde::to_dynamic(value).ok())
+            .unwrap_or(Dynamic::UNIT)
+    });
+
+    engine
+}
+
+fn book(info: &Info) -> Dynamic {
+    let title = if info.title.is_empty() {
+        info.file
+            .path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    } else {
+        info.title.clone()
+    };
+    let mut map = Map::new();
+    map.insert("title".into(), title.into());
+    map.insert("author".into(), info.author.clone().into());
+    map.insert("year".into(), info.year.clone().into());
+    map.insert("series".into(), info.series.clone().into());
+    map.insert("kind".into(), info.file.kind.clone().into());
+    map.insert(
+        "path".into(),
+        info.file.path.to_string_lossy().into_owned().into(),
+    );
+    Dynamic::from_map(map)
+}
+
+// Removes the secrets from the settings.
+fn redact(value: &mut JsonValue) {
+    match value {
+        JsonValue::Object(map) => {
+            map.retain(|key, _| {
+                !SECRET_KEYS.iter().any(|secret| key.contains(secret))
+                    && !SECRET_NAMES.contains(&key.as_str())
+                    && !SECRET_TABLES.contains(&key.as_str())
+            });
+            map.values_mut().for_each(redact);
+        }
+        JsonValue::Array(values) => values.iter_mut().for_each(redact),
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_helpers::create_test_context;
+    use crate::library::Library;
+    use crate::settings::{LibraryMode, Settings};
+    use chrono::TimeZone;
+    use std::sync::mpsc::{channel, Receiver};
+
+    fn notifications(receiver: &Receiver<Event>) -> Vec<String> {
+        receiver
+            .try_iter()
+            .filter_map(|evt| match evt {
+                Event::Notification(NotificationEvent::Show(text)) => Some(text),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_scripts_react_to_the_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let scripts_dir = dir.path().join(SCRIPTS_DIRNAME);
+        let library_dir = dir.path().join("Books");
+        fs::create_dir_all(library_dir.join("Digests")).unwrap();
+        fs::write(library_dir.join("Digests").join("news-2026-10-14.epub"), "").unwrap();
+        fs::write(library_dir.join("novel.epub"), "").unwrap();
+        fs::create_dir(&scripts_dir).unwrap();
+        fs::write(
+            scripts_dir.join("digest.rhai"),
+            r#"
+            fn on_clock(now) {
+                if now.hour == 7 && now.minute == 0 {
+                    let books = library_search("news-" + now.date);
+                    if books.len() > 0 {
+                        open(books[0].path);
+                    }
+                }
+            }
+            fn on_startup() {
+                toast("Time format: " + setting("time-format"));
+                if setting("time-format.missing") == () {
+                    toast("No such setting.");
+                }
+            }
+            "#,
+        )
+        .unwrap();
+        fs::write(scripts_dir.join("broken.rhai"), "fn on_startup( {").unwrap();
+        fs::write(
+            scripts_dir.join("endless.rhai"),
+            "fn on_resume() { loop {} }",
+        )
+        .unwrap();
+
+        let mut context = create_test_context();
+        context.library = Library::new(&library_dir, LibraryMode::Filesystem).unwrap();
+        let (hub, receiver) = channel();
+        let scripts = Scripts::load(&scripts_dir);
+        assert_eq!(scripts.scripts.len(), 2);
+
+        scripts.trigger(HookTrigger::Startup, None, &context, &hub);
+        assert_eq!(
+            notifications(&receiver),
+            vec!["Time format: %H:%M", "No such setting."]
+        );
+
+        scripts.trigger(HookTrigger::Resume, None, &context, &hub);
+        assert!(receiver.try_recv().is_err());
+
+        let morning = Local.with_ymd_and_hms(2026, 10, 14, 7, 0, 12).unwrap();
+        scripts.clock(morning, &context, &hub);
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(Event::RemoteOpen(path)) if path == Path::new("Digests/news-2026-10-14.epub")
+        ));
+
+        // Once per minute.
+        scripts.clock(morning + chrono::Duration::seconds(30), &context, &hub);
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_redact_removes_the_secrets() {
+        let mut value = serde_json::json!({
+            "sync": {"username": "reader", "password": "secret", "userkey": "abcd"},
+            "ota": {"github-token": "ghp"},
+            "kiosk": {"enabled": true, "pin": "abcd"},
+        });
+        redact(&mut value);
+        assert_eq!(
+            value,
+            serde_json::json!({"sync": {"username": "reader"}, "ota": {}})
+        );
+    }
+
+    #[test]
+    fn test_redact_hides_the_kiosk_pin() {
+        let mut settings = Settings::default();
+        settings.kiosk.pin = Some("abcd".to_string());
+        let mut value = serde_json::to_value(&settings).unwrap();
+        assert!(value.pointer("/kiosk/pin").is_some());
+
+        redact(&mut value);
+        assert!(value.pointer("/kiosk/pin").is_none());
+
+        let mut value = serde_json::json!({"lock": {"pin": "abcd", "swipe-typing": true}});
+        redact(&mut value);
+        assert_eq!(value, serde_json::json!({"lock": {"swipe-typing": true}}));
+    }
+}
@@ -0,0 +1,233 @@
+//! Text-to-speech through an external synthesizer.
+//!
+//! Each sentence is synthesized by *espeak-ng* or *piper* into a WAV stream that's piped to
+//! the configured player, e.g. `aplay`. The audio output (USB, or Bluetooth through BlueALSA)
+//! is the one the player is set up to use.
+
+use crate::settings::{SpeechEngine, SpeechSettings};
+use crate::view::{Event, Hub};
+use anyhow::{format_err, Error};
+use std::io::Write;
+use std::ops::Range;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tracing::error;
+
+// Delay between two checks of the state of the player.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+// The speaking rate of piper's voices, in words per minute.
+const PIPER_RATE: f32 = 175.0;
+
+static SPEAKER_IDS: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SpeechEvent {
+    /// The sentence at the given index is being spoken.
+    Sentence(usize),
+    /// Every sentence was spoken.
+    Finished,
+    /// The synthesizer or the player couldn't be run.
+    Failed,
+}
+
+/// Speaks a list of sentences from a background thread.
+///
+/// The progress is reported through [`Event::Speech`], tagged with the speaker's id.
+/// Dropping the speaker interrupts the current sentence.
+pub struct Speaker {
+    id: u32,
+    running: Arc<AtomicBool>,
+}
+
+impl Speaker {
+    pub fn new(
+        settings: &SpeechSettings,
+        language: &str,
+        sentences: Vec<String>,
+        start: usize,
+        hub: &Hub,
+    ) -> Speaker {
+        let id = SPEAKER_IDS.fetch_add(1, Ordering::Relaxed);
+        let running = Arc::new(AtomicBool::new(true));
+        let running2 = running.clone();
+        let settings = settings.clone();
+        let voice = if settings.voice.is_empty() {
+            language.to_string()
+        } else {
+            settings.voice.clone()
+        };
+        let hub2 = hub.clone();
+
+        thread::spawn(move || {
+            for (index, text) in sentences.iter().enumerate().skip(start) {
+                if !running2.load(Ordering::Relaxed) {
+                    return;
+                }
+                hub2.send(Event::Speech(id, SpeechEvent::Sentence(index)))
+                    .ok();
+                if let Err(e) = speak(&settings, &voice, text, &running2) {
+                    error!("Can't speak: {:#}.", e);
+                    hub2.send(Event::Speech(id, SpeechEvent::Failed)).ok();
+                    return;
+                }
+            }
+            if running2.load(Ordering::Relaxed) {
+                hub2.send(Event::Speech(id, SpeechEvent::Finished)).ok();
+            }
+        });
+
+        Speaker { id, running }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+impl Drop for Speaker {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
+
+fn synthesizer(settings: &SpeechSettings, voice: &str) -> Command {
+    let mut command = Command::new(&settings.program);
+    match settings.engine {
+        SpeechEngine::EspeakNg => {
+            command
+                .arg("--stdin")
+                .arg("--stdout")
+                .arg("-s")
+                .arg(settings.rate.to_string());
+            if !voice.is_empty() {
+                command.arg("-v").arg(voice);
+            }
+        }
+        SpeechEngine::Piper => {
+            command
+                .arg("--model")
+                .arg(voice)
+                .arg("--output_file")
+                .arg("-")
+                .arg("--length_scale")
+                .arg((PIPER_RATE / settings.rate.max(1) as f32).to_string());
+        }
+    }
+    command
+}
+
+fn stop(processes: &mut [Child]) {
+    for process in processes {
+        process.kill().ok();
+        process.wait().ok();
+    }
+}
+
+// Speaks the given text, returns when it's done or when `running` is unset.
+fn speak(
+    settings: &SpeechSettings,
+    voice: &str,
+    text: &str,
+    running: &AtomicBool,
+) -> Result<(), Error> {
+    let (program, args) = settings
+        .player
+        .split_first()
+        .ok_or_else(|| format_err!("empty player command"))?;
+    let mut synthesizer = synthesizer(settings, voice)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let audio = synthesizer
+        .stdout
+        .take()
+        .ok_or_else(|| format_err!("can't take stdout"))?;
+    let player = Command::new(program)
+        .args(args)
+        .stdin(audio)
+        .stderr(Stdio::null())
+        .spawn();
+    let mut processes = match player {
+        Ok(player) => [synthesizer, player],
+        Err(e) => {
+            stop(&mut [synthesizer]);
+            return Err(e.into());
+        }
+    };
+
+    // Closing the standard input signals the end of the text.
+    if let Some(mut stdin) = processes[0].stdin.take() {
+        if let Err(e) = stdin.write_all(text.as_bytes()) {
+            stop(&mut processes);
+            return Err(e.into());
+        }
+    }
+
+    loop {
+        if !running.load(Ordering::Relaxed) {
+            stop(&mut processes);
+            return Ok(());
+        }
+        if let Some(status) = processes[1].try_wait()? {
+            processes[0].wait().ok();
+            if !status.success() {
+                return Err(format_err!("the player exited with {}", status));
+            }
+            return Ok(());
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Returns whether the given word ends a sentence.
+pub fn ends_sentence(word: &str) -> bool {
+    word.trim_end_matches(['"', '\'', ')', ']', '»', '”', '’', '」', '』', '）'])
+        .ends_with(['.', '!', '?', '…', '。', '！', '？'])
+}
+
+/// Groups consecutive words into sentences.
+///
+/// Returns the ranges of the word indices of each sentence. The last range is left open
+/// when the last word doesn't end a sentence.
+pub fn sentences<S: AsRef<str>>(words: &[S]) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+
+    for (index, word) in words.iter().enumerate() {
+        if ends_sentence(word.as_ref()) {
+            ranges.push(start..index + 1);
+            start = index + 1;
+        }
+    }
+
+    if start < words.len() {
+        ranges.push(start..words.len());
+    }
+
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ends_sentence() {
+        assert!(ends_sentence("end."));
+        assert!(ends_sentence("what?”"));
+        assert!(ends_sentence("です。"));
+        assert!(!ends_sentence("comma,"));
+        assert!(!ends_sentence("word"));
+    }
+
+    #[test]
+    fn test_sentences() {
+        let words = ["It", "rains.", "“Really?”", "she", "asked", "and"];
+        assert_eq!(sentences(&words), vec![0..2, 2..3, 3..6]);
+        assert!(sentences::<&str>(&[]).is_empty());
+    }
+}
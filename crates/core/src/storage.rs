@@ -0,0 +1,244 @@
+//! The free space of the onboard partition, and the files that can be removed to reclaim some.
+//!
+//! The reclaimable items are the thumbnail previews and the trash of each library, the update
+//! archives left by the OTA client, and the old copies of the settings file. Emptying an item
+//! removes its files but keeps its directory, since the libraries expect it.
+
+use crate::document::HumanSize;
+use crate::library::THUMBNAIL_PREVIEWS_DIRNAME;
+use crate::settings::{Settings, SETTINGS_PATH};
+use crate::view::home::TRASH_DIRNAME;
+use anyhow::{Context, Error};
+use nix::sys::statvfs::statvfs;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// The directory where the OTA client downloads the update archives.
+const OTA_DOWNLOADS_DIR: &str = "/tmp";
+const OTA_ARCHIVE_PREFIX: &str = "cadmus-ota-";
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ItemKind {
+    ThumbnailPreviews,
+    Trash,
+    UpdateArchive,
+    OldSettings,
+}
+
+impl fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ItemKind::ThumbnailPreviews => write!(f, "Thumbnail Previews"),
+            ItemKind::Trash => write!(f, "Trash"),
+            ItemKind::UpdateArchive => write!(f, "Update Archive"),
+            ItemKind::OldSettings => write!(f, "Old Settings"),
+        }
+    }
+}
+
+/// A file, or a directory whose files, can be removed.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Item {
+    pub kind: ItemKind,
+    pub path: PathBuf,
+    /// The space taken, in bytes.
+    pub size: u64,
+    /// The name of the library the item belongs to, if any.
+    pub library: Option<String>,
+}
+
+impl Item {
+    /// Returns the text of the item in the cleanup menu.
+    pub fn label(&self) -> String {
+        let name = match self.library.as_ref() {
+            Some(library) => format!("{} of {}", self.kind, library),
+            None if self.kind == ItemKind::UpdateArchive || self.kind == ItemKind::OldSettings => {
+                let file_name = self.path.file_name().unwrap_or_default();
+                format!("{} {}", self.kind, file_name.to_string_lossy())
+            }
+            None => self.kind.to_string(),
+        };
+        format!("{} ({})", name, self.size.human_size())
+    }
+}
+
+/// Returns the free and the total space of the partition holding the path, in bytes.
+pub fn space(path: &Path) -> Option<(u64, u64)> {
+    let stat = statvfs(path).ok()?;
+    let fragment_size = stat.fragment_size() as u64;
+    Some((
+        stat.blocks_available() as u64 * fragment_size,
+        stat.blocks() as u64 * fragment_size,
+    ))
+}
+
+/// Returns whether a file system is mounted on the path, as opposed to an empty mount point.
+pub fn is_mounted(path: &Path) -> bool {
+    fs::read_to_string("/proc/mounts").is_ok_and(|mounts| {
+        mounts
+            .lines()
+            .filter_map(|line| line.split_whitespace().nth(1))
+            .any(|mount_point| Path::new(mount_point) == path)
+    })
+}
+
+/// Lists the items that free some space once emptied, largest first.
+pub fn reclaimable_items(settings: &Settings) -> Vec<Item> {
+    let mut items = Vec::new();
+
+    for library in &settings.libraries {
+        for (kind, dirname) in [
+            (ItemKind::ThumbnailPreviews, THUMBNAIL_PREVIEWS_DIRNAME),
+            (ItemKind::Trash, TRASH_DIRNAME),
+        ] {
+            let path = library.path.join(dirname);
+            let size = size_of(&path);
+            if size > 0 {
+                items.push(Item {
+                    kind,
+                    path,
+                    size,
+                    library: Some(library.name.clone()),
+                });
+            }
+        }
+    }
+
+    items.extend(files_with_prefix(
+        Path::new(OTA_DOWNLOADS_DIR),
+        OTA_ARCHIVE_PREFIX,
+        ItemKind::UpdateArchive,
+    ));
+    items.extend(old_settings(Path::new(".")));
+
+    items.sort_by_key(|item| std::cmp::Reverse(item.size));
+    items
+}
+
+/// Removes the file of the item, or the files of its directory, returning the space freed.
+pub fn reclaim(item: &Item) -> Result<u64, Error> {
+    if item.path.is_dir() {
+        for entry in fs::read_dir(&item.path)
+            .with_context(|| format!("can't read {}", item.path.display()))?
+        {
+            let path = entry?.path();
+            if path.is_dir() {
+                fs::remove_dir_all(&path)
+            } else {
+                fs::remove_file(&path)
+            }
+            .with_context(|| format!("can't remove {}", path.display()))?;
+        }
+    } else {
+        fs::remove_file(&item.path)
+            .with_context(|| format!("can't remove {}", item.path.display()))?;
+    }
+    Ok(item.size)
+}
+
+/// The copies of the settings file left next to it, like `Settings.toml.bak`,
+/// `Settings.toml.old` or `Settings-2023.toml`.
+pub(crate) fn old_settings(dir: &Path) -> Vec<Item> {
+    let stem = Path::new(SETTINGS_PATH)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+    let mut items = files_with_prefix(dir, stem, ItemKind::OldSettings);
+    items.retain(|item| !item.path.ends_with(SETTINGS_PATH));
+    items
+}
+
+fn files_with_prefix(dir: &Path, prefix: &str, kind: ItemKind) -> Vec<Item> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(prefix))
+        .filter_map(|entry| {
+            let metadata = entry
+                .metadata()
+                .ok()
+                .filter(|metadata| metadata.is_file())?;
+            Some(Item {
+                kind,
+                path: entry.path(),
+                size: metadata.len(),
+                library: None,
+            })
+        })
+        .collect()
+}
+
+// The total size of the files of a directory, or the size of a file.
+fn size_of(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::LibrarySettings;
+
+    #[test]
+    fn test_reclaim_items() {
+        let dir = tempfile::tempdir().unwrap();
+        let library = dir.path().join("Books");
+        let previews = library.join(THUMBNAIL_PREVIEWS_DIRNAME);
+        fs::create_dir_all(previews.join("nested")).unwrap();
+        fs::write(previews.join("a.png"), [0; 300]).unwrap();
+        fs::write(previews.join("nested").join("b.png"), [0; 200]).unwrap();
+        fs::create_dir_all(library.join(TRASH_DIRNAME)).unwrap();
+
+        let settings = Settings {
+            libraries: vec![LibrarySettings {
+                name: "Books".to_string(),
+                path: library.clone(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let items = reclaimable_items(&settings)
+            .into_iter()
+            .filter(|item| item.library.is_some())
+            .collect::<Vec<Item>>();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].kind, ItemKind::ThumbnailPreviews);
+        assert_eq!(items[0].size, 500);
+        assert_eq!(items[0].label(), "Thumbnail Previews of Books (500 B)");
+
+        assert_eq!(reclaim(&items[0]).unwrap(), 500);
+        assert!(previews.is_dir());
+        assert_eq!(size_of(&previews), 0);
+    }
+
+    #[test]
+    fn test_old_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(SETTINGS_PATH), "").unwrap();
+        fs::write(dir.path().join("Settings.toml.bak"), "wifi = true").unwrap();
+        fs::write(dir.path().join("Settings-sample.toml"), "").unwrap();
+        fs::write(dir.path().join("Other.toml"), "").unwrap();
+
+        let mut names = old_settings(dir.path())
+            .into_iter()
+            .map(|item| {
+                item.path
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect::<Vec<String>>();
+        names.sort();
+        assert_eq!(names, vec!["Settings-sample.toml", "Settings.toml.bak"]);
+    }
+}
@@ -0,0 +1,858 @@
+//! Rasterizing the icons.
+//!
+//! This is a small SVG renderer covering what the files of the `icons` directory use: the
+//! `path`, `rect`, `circle`, `ellipse`, `line`, `polyline`, `polygon` and `g` elements, plain
+//! colors for `fill` and `stroke`, `fill-rule`, `stroke-width` and `transform`. Each shape is
+//! flattened into polygons, in device space, which are filled with the exact horizontal
+//! coverage of a few sub-scanlines, so that the edges are anti-aliased at any size. Strokes
+//! always have round caps and joins.
+
+use crate::color::{Color, BLACK, WHITE};
+use crate::document::html::dom::{NodeRef, XmlTree};
+use crate::document::html::xml::XmlParser;
+use crate::framebuffer::{Framebuffer, Pixmap};
+use crate::geom::{nearest_segment_point, Vec2};
+use anyhow::{format_err, Context, Error};
+use std::f32::consts::PI;
+use std::fs;
+use std::path::Path;
+
+// The number of samples taken vertically in each row of pixels.
+const SUBSCANLINES: usize = 5;
+// The largest distance, in pixels, between a curve and its flattening.
+const TOLERANCE: f32 = 0.2;
+
+pub struct Svg {
+    tree: XmlTree,
+    width: f32,
+    height: f32,
+    view_box: [f32; 4],
+}
+
+impl Svg {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Svg, Error> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("can't read SVG file {}", path.display()))?;
+        Svg::parse(&text).with_context(|| format!("can't parse SVG file {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Svg, Error> {
+        let tree = XmlParser::new(text).parse();
+        let root = tree
+            .root()
+            .find("svg")
+            .ok_or_else(|| format_err!("no svg element"))?;
+        let view_box = root.attribute("viewBox").and_then(|v| {
+            let numbers = Scanner::new(v).numbers();
+            (numbers.len() == 4).then(|| [numbers[0], numbers[1], numbers[2], numbers[3]])
+        });
+        let width = root.attribute("width").and_then(length);
+        let height = root.attribute("height").and_then(length);
+        let (width, height, view_box) = match (width, height, view_box) {
+            (Some(w), Some(h), Some(vb)) => (w, h, vb),
+            (Some(w), Some(h), None) => (w, h, [0.0, 0.0, w, h]),
+            (_, _, Some(vb)) => (vb[2], vb[3], vb),
+            _ => return Err(format_err!("unknown dimensions")),
+        };
+        if width <= 0.0 || height <= 0.0 || view_box[2] <= 0.0 || view_box[3] <= 0.0 {
+            return Err(format_err!("empty dimensions"));
+        }
+        Ok(Svg {
+            tree,
+            width,
+            height,
+            view_box,
+        })
+    }
+
+    pub fn dims(&self) -> (f32, f32) {
+        (self.width, self.height)
+    }
+
+    /// Renders the image in gray levels, at `scale` pixels per unit, over a white background.
+    pub fn render(&self, scale: f32) -> Pixmap {
+        let width = (self.width * scale - 0.001).ceil().max(1.0) as u32;
+        let height = (self.height * scale - 0.001).ceil().max(1.0) as u32;
+        let mut pixmap = Pixmap::new(width, height, 1);
+        let [x, y, w, h] = self.view_box;
+        let transform = Transform::scale(scale * self.width / w, scale * self.height / h)
+            .multiply(&Transform::translate(-x, -y));
+        if let Some(root) = self.tree.root().find("svg") {
+            render_node(root, &transform, &Style::default(), &mut pixmap);
+        }
+        pixmap
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Transform([f32; 6]);
+
+impl Transform {
+    const IDENTITY: Transform = Transform([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+
+    fn translate(x: f32, y: f32) -> Transform {
+        Transform([1.0, 0.0, 0.0, 1.0, x, y])
+    }
+
+    fn scale(x: f32, y: f32) -> Transform {
+        Transform([x, 0.0, 0.0, y, 0.0, 0.0])
+    }
+
+    fn rotate(angle: f32) -> Transform {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        Transform([cos, sin, -sin, cos, 0.0, 0.0])
+    }
+
+    // Returns the transform that applies `other` first, and then `self`.
+    fn multiply(&self, other: &Transform) -> Transform {
+        let [a, b, c, d, e, f] = self.0;
+        let [g, h, i, j, k, l] = other.0;
+        Transform([
+            a * g + c * h,
+            b * g + d * h,
+            a * i + c * j,
+            b * i + d * j,
+            a * k + c * l + e,
+            b * k + d * l + f,
+        ])
+    }
+
+    fn apply(&self, p: Vec2) -> Vec2 {
+        let [a, b, c, d, e, f] = self.0;
+        vec2!(a * p.x + c * p.y + e, b * p.x + d * p.y + f)
+    }
+
+    // The mean scaling factor, used to convert lengths.
+    fn factor(&self) -> f32 {
+        let [a, b, c, d, ..] = self.0;
+        (a * d - b * c).abs().sqrt()
+    }
+
+    // Parses a list such as `translate(10 20) scale(2)`.
+    fn parse(text: &str) -> Transform {
+        let mut transform = Transform::IDENTITY;
+        for item in text.split(')') {
+            let Some((name, args)) = item.split_once('(') else {
+                continue;
+            };
+            let v = Scanner::new(args).numbers();
+            let item = match (
+                name.trim_matches(|c: char| c.is_whitespace() || c == ','),
+                &v[..],
+            ) {
+                ("matrix", &[a, b, c, d, e, f]) => Transform([a, b, c, d, e, f]),
+                ("translate", &[x]) => Transform::translate(x, 0.0),
+                ("translate", &[x, y]) => Transform::translate(x, y),
+                ("scale", &[s]) => Transform::scale(s, s),
+                ("scale", &[x, y]) => Transform::scale(x, y),
+                ("rotate", &[a]) => Transform::rotate(a),
+                ("rotate", &[a, x, y]) => Transform::translate(x, y)
+                    .multiply(&Transform::rotate(a))
+                    .multiply(&Transform::translate(-x, -y)),
+                _ => continue,
+            };
+            transform = transform.multiply(&item);
+        }
+        transform
+    }
+}
+
+// The presentation attributes, inherited by the children of an element.
+#[derive(Debug, Copy, Clone)]
+struct Style {
+    fill: Option<Color>,
+    stroke: Option<Color>,
+    stroke_width: f32,
+    even_odd: bool,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            fill: Some(BLACK),
+            stroke: None,
+            stroke_width: 1.0,
+            even_odd: false,
+        }
+    }
+}
+
+impl Style {
+    fn inherit(&self, node: &NodeRef) -> Style {
+        let mut style = *self;
+        if let Some(fill) = node.attribute("fill").and_then(paint) {
+            style.fill = fill;
+        }
+        if let Some(stroke) = node.attribute("stroke").and_then(paint) {
+            style.stroke = stroke;
+        }
+        if let Some(width) = node.attribute("stroke-width").and_then(length) {
+            style.stroke_width = width;
+        }
+        if let Some(rule) = node.attribute("fill-rule") {
+            style.even_odd = rule.trim() == "evenodd";
+        }
+        style
+    }
+}
+
+// Returns `None` for unknown values, and `Some(None)` for `none`.
+fn paint(text: &str) -> Option<Option<Color>> {
+    let text = text.trim();
+    match text {
+        "none" => Some(None),
+        "black" | "currentColor" => Some(Some(BLACK)),
+        "white" => Some(Some(WHITE)),
+        _ => {
+            let hex = text.strip_prefix('#')?;
+            let value = u32::from_str_radix(hex, 16).ok()?;
+            let (r, g, b) = match hex.len() {
+                3 => (
+                    ((value >> 8) & 0xF) * 0x11,
+                    ((value >> 4) & 0xF) * 0x11,
+                    (value & 0xF) * 0x11,
+                ),
+                6 => ((value >> 16) & 0xFF, (value >> 8) & 0xFF, value & 0xFF),
+                _ => return None,
+            };
+            Some(Some(Color::Rgb(r as u8, g as u8, b as u8)))
+        }
+    }
+}
+
+// Parses a length, ignoring its unit.
+fn length(text: &str) -> Option<f32> {
+    Scanner::new(text).number()
+}
+
+fn render_node(node: NodeRef, transform: &Transform, style: &Style, pixmap: &mut Pixmap) {
+    let transform = match node.attribute("transform") {
+        Some(text) => transform.multiply(&Transform::parse(text)),
+        None => *transform,
+    };
+    let style = style.inherit(&node);
+    let number = |name: &str| node.attribute(name).and_then(length);
+    let mut path = PathBuilder::new(transform);
+
+    match node.tag_name() {
+        Some("svg") | Some("g") => {
+            for child in node.children().filter(|c| c.is_element()) {
+                render_node(child, &transform, &style, pixmap);
+            }
+            return;
+        }
+        Some("path") => {
+            if let Some(d) = node.attribute("d") {
+                path.parse(d);
+            }
+        }
+        Some("rect") => {
+            let (x, y) = (number("x").unwrap_or(0.0), number("y").unwrap_or(0.0));
+            let (w, h) = (
+                number("width").unwrap_or(0.0),
+                number("height").unwrap_or(0.0),
+            );
+            let (rx, ry) = match (number("rx"), number("ry")) {
+                (Some(rx), Some(ry)) => (rx, ry),
+                (Some(r), None) | (None, Some(r)) => (r, r),
+                (None, None) => (0.0, 0.0),
+            };
+            if w > 0.0 && h > 0.0 {
+                path.rectangle(x, y, w, h, rx.min(w / 2.0), ry.min(h / 2.0));
+            }
+        }
+        Some("circle") => {
+            let r = number("r").unwrap_or(0.0);
+            path.ellipse(
+                number("cx").unwrap_or(0.0),
+                number("cy").unwrap_or(0.0),
+                r,
+                r,
+            );
+        }
+        Some("ellipse") => {
+            path.ellipse(
+                number("cx").unwrap_or(0.0),
+                number("cy").unwrap_or(0.0),
+                number("rx").unwrap_or(0.0),
+                number("ry").unwrap_or(0.0),
+            );
+        }
+        Some("line") => {
+            path.move_to(vec2!(
+                number("x1").unwrap_or(0.0),
+                number("y1").unwrap_or(0.0)
+            ));
+            path.line_to(vec2!(
+                number("x2").unwrap_or(0.0),
+                number("y2").unwrap_or(0.0)
+            ));
+        }
+        Some(name @ ("polyline" | "polygon")) => {
+            let numbers = Scanner::new(node.attribute("points").unwrap_or("")).numbers();
+            for (i, p) in numbers.chunks_exact(2).enumerate() {
+                if i == 0 {
+                    path.move_to(vec2!(p[0], p[1]));
+                } else {
+                    path.line_to(vec2!(p[0], p[1]));
+                }
+            }
+            if name == "polygon" {
+                path.close();
+            }
+        }
+        _ => return,
+    }
+
+    if let Some(color) = style.fill {
+        fill(pixmap, &path.subpaths, style.even_odd, color);
+    }
+    if let Some(color) = style.stroke {
+        let width = style.stroke_width * transform.factor();
+        stroke(pixmap, &path.subpaths, width, color);
+    }
+}
+
+// The points are in device space.
+struct Subpath {
+    points: Vec<Vec2>,
+    closed: bool,
+}
+
+struct PathBuilder {
+    transform: Transform,
+    subpaths: Vec<Subpath>,
+    // The current and the initial points of the current subpath, in user space.
+    current: Vec2,
+    start: Vec2,
+}
+
+impl PathBuilder {
+    fn new(transform: Transform) -> PathBuilder {
+        PathBuilder {
+            transform,
+            subpaths: Vec::new(),
+            current: vec2!(0.0, 0.0),
+            start: vec2!(0.0, 0.0),
+        }
+    }
+
+    fn move_to(&mut self, p: Vec2) {
+        self.subpaths.push(Subpath {
+            points: vec![self.transform.apply(p)],
+            closed: false,
+        });
+        self.current = p;
+        self.start = p;
+    }
+
+    // A segment following a `close` starts a new subpath, at the same point.
+    fn ensure_open(&mut self) {
+        if self.subpaths.last().is_none_or(|s| s.closed) {
+            self.move_to(self.current);
+        }
+    }
+
+    fn line_to(&mut self, p: Vec2) {
+        self.ensure_open();
+        let q = self.transform.apply(p);
+        if let Some(subpath) = self.subpaths.last_mut() {
+            subpath.points.push(q);
+        }
+        self.current = p;
+    }
+
+    fn close(&mut self) {
+        if let Some(subpath) = self.subpaths.last_mut() {
+            subpath.closed = true;
+        }
+        self.current = self.start;
+    }
+
+    fn quad_to(&mut self, c: Vec2, p: Vec2) {
+        self.ensure_open();
+        let [a, b, d] = [self.current, c, p].map(|v| self.transform.apply(v));
+        let dd = (a - b * 2.0 + d).length();
+        let n = ((dd / (4.0 * TOLERANCE)).sqrt().ceil() as usize).clamp(1, 100);
+        for i in 1..n {
+            let t = i as f32 / n as f32;
+            let u = 1.0 - t;
+            let q = a * (u * u) + b * (2.0 * u * t) + d * (t * t);
+            self.push_device(q);
+        }
+        self.line_to(p);
+    }
+
+    fn cubic_to(&mut self, c1: Vec2, c2: Vec2, p: Vec2) {
+        self.ensure_open();
+        let [a, b, c, d] = [self.current, c1, c2, p].map(|v| self.transform.apply(v));
+        let dd = (a - b * 2.0 + c).length().max((b - c * 2.0 + d).length());
+        let n = ((3.0 * dd / (4.0 * TOLERANCE)).sqrt().ceil() as usize).clamp(1, 100);
+        for i in 1..n {
+            let t = i as f32 / n as f32;
+            let u = 1.0 - t;
+            let q =
+                a * (u * u * u) + b * (3.0 * u * u * t) + c * (3.0 * u * t * t) + d * (t * t * t);
+            self.push_device(q);
+        }
+        self.line_to(p);
+    }
+
+    fn push_device(&mut self, q: Vec2) {
+        if let Some(subpath) = self.subpaths.last_mut() {
+            subpath.points.push(q);
+        }
+    }
+
+    // The elliptical arc from the current point to `p`, as described in the appendix F.6 of
+    // the SVG specification.
+    fn arc_to(&mut self, rx: f32, ry: f32, rotation: f32, large: bool, sweep: bool, p: Vec2) {
+        let p0 = self.current;
+        let (mut rx, mut ry) = (rx.abs(), ry.abs());
+        if p0 == p {
+            return;
+        }
+        if rx < f32::EPSILON || ry < f32::EPSILON {
+            self.line_to(p);
+            return;
+        }
+        self.ensure_open();
+
+        let (sin, cos) = rotation.to_radians().sin_cos();
+        let half = (p0 - p) * 0.5;
+        let x1 = cos * half.x + sin * half.y;
+        let y1 = -sin * half.x + cos * half.y;
+
+        let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+        if lambda > 1.0 {
+            rx *= lambda.sqrt();
+            ry *= lambda.sqrt();
+        }
+
+        let num = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+        let den = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+        let sign = if large == sweep { -1.0 } else { 1.0 };
+        let coef = sign * (num / den).max(0.0).sqrt();
+        let cx1 = coef * rx * y1 / ry;
+        let cy1 = -coef * ry * x1 / rx;
+        let mid = (p0 + p) * 0.5;
+        let center = vec2!(cos * cx1 - sin * cy1 + mid.x, sin * cx1 + cos * cy1 + mid.y);
+
+        let theta = ((y1 - cy1) / ry).atan2((x1 - cx1) / rx);
+        let mut delta = ((-y1 - cy1) / ry).atan2((-x1 - cx1) / rx) - theta;
+        if sweep && delta < 0.0 {
+            delta += 2.0 * PI;
+        } else if !sweep && delta > 0.0 {
+            delta -= 2.0 * PI;
+        }
+
+        let radius = rx.max(ry) * self.transform.factor();
+        let step = 2.0 * (1.0 - TOLERANCE / radius.max(TOLERANCE)).acos();
+        let n = ((delta.abs() / step.max(0.01)).ceil() as usize).clamp(1, 200);
+        for i in 1..n {
+            let (s, c) = (theta + delta * i as f32 / n as f32).sin_cos();
+            let q = vec2!(
+                center.x + rx * c * cos - ry * s * sin,
+                center.y + rx * c * sin + ry * s * cos
+            );
+            self.push_device(self.transform.apply(q));
+        }
+        self.line_to(p);
+    }
+
+    fn ellipse(&mut self, cx: f32, cy: f32, rx: f32, ry: f32) {
+        if rx <= 0.0 || ry <= 0.0 {
+            return;
+        }
+        self.move_to(vec2!(cx + rx, cy));
+        self.arc_to(rx, ry, 0.0, false, true, vec2!(cx - rx, cy));
+        self.arc_to(rx, ry, 0.0, false, true, vec2!(cx + rx, cy));
+        self.close();
+    }
+
+    fn rectangle(&mut self, x: f32, y: f32, w: f32, h: f32, rx: f32, ry: f32) {
+        self.move_to(vec2!(x + rx, y));
+        self.line_to(vec2!(x + w - rx, y));
+        self.arc_to(rx, ry, 0.0, false, true, vec2!(x + w, y + ry));
+        self.line_to(vec2!(x + w, y + h - ry));
+        self.arc_to(rx, ry, 0.0, false, true, vec2!(x + w - rx, y + h));
+        self.line_to(vec2!(x + rx, y + h));
+        self.arc_to(rx, ry, 0.0, false, true, vec2!(x, y + h - ry));
+        self.line_to(vec2!(x, y + ry));
+        self.arc_to(rx, ry, 0.0, false, true, vec2!(x + rx, y));
+        self.close();
+    }
+
+    // Parses path data such as `m10 20h30v40z`.
+    fn parse(&mut self, data: &str) {
+        let mut scanner = Scanner::new(data);
+        let mut command = None;
+        // The second control points of the previous cubic or quadratic curve.
+        let mut last_cubic: Option<Vec2> = None;
+        let mut last_quad: Option<Vec2> = None;
+
+        loop {
+            scanner.skip_separators();
+            let Some(c) = scanner.peek() else {
+                break;
+            };
+            if c.is_ascii_alphabetic() {
+                scanner.advance();
+                command = Some(c);
+                if c == b'z' || c == b'Z' {
+                    self.close();
+                    last_cubic = None;
+                    last_quad = None;
+                    continue;
+                }
+            }
+            let Some(cmd) = command else {
+                break;
+            };
+            let relative = cmd.is_ascii_lowercase();
+            let origin = if relative {
+                self.current
+            } else {
+                vec2!(0.0, 0.0)
+            };
+            let point = |scanner: &mut Scanner| -> Option<Vec2> {
+                let x = scanner.number()?;
+                let y = scanner.number()?;
+                Some(origin + vec2!(x, y))
+            };
+
+            let parsed = match cmd.to_ascii_uppercase() {
+                b'M' => point(&mut scanner).map(|p| {
+                    self.move_to(p);
+                    // The following pairs are implicit line commands.
+                    command = Some(if relative { b'l' } else { b'L' });
+                }),
+                b'L' => point(&mut scanner).map(|p| self.line_to(p)),
+                b'H' => scanner.number().map(|x| {
+                    let x = if relative { self.current.x + x } else { x };
+                    self.line_to(vec2!(x, self.current.y));
+                }),
+                b'V' => scanner.number().map(|y| {
+                    let y = if relative { self.current.y + y } else { y };
+                    self.line_to(vec2!(self.current.x, y));
+                }),
+                b'C' => point(&mut scanner)
+                    .zip(point(&mut scanner))
+                    .zip(point(&mut scanner))
+                    .map(|((c1, c2), p)| {
+                        self.cubic_to(c1, c2, p);
+                        last_cubic = Some(c2);
+                    }),
+                b'S' => point(&mut scanner).zip(point(&mut scanner)).map(|(c2, p)| {
+                    let c1 = last_cubic.map_or(self.current, |c| self.current * 2.0 - c);
+                    self.cubic_to(c1, c2, p);
+                    last_cubic = Some(c2);
+                }),
+                b'Q' => point(&mut scanner).zip(point(&mut scanner)).map(|(c, p)| {
+                    self.quad_to(c, p);
+                    last_quad = Some(c);
+                }),
+                b'T' => point(&mut scanner).map(|p| {
+                    let c = last_quad.map_or(self.current, |c| self.current * 2.0 - c);
+                    self.quad_to(c, p);
+                    last_quad = Some(c);
+                }),
+                b'A' => scanner.arc().map(|(rx, ry, rotation, large, sweep, x, y)| {
+                    self.arc_to(rx, ry, rotation, large, sweep, origin + vec2!(x, y))
+                }),
+                _ => None,
+            };
+
+            // Stop at the first error, like browsers do.
+            if parsed.is_none() {
+                break;
+            }
+            if !matches!(cmd, b'C' | b'c' | b'S' | b's') {
+                last_cubic = None;
+            }
+            if !matches!(cmd, b'Q' | b'q' | b'T' | b't') {
+                last_quad = None;
+            }
+        }
+    }
+}
+
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(text: &'a str) -> Scanner<'a> {
+        Scanner {
+            bytes: text.as_bytes(),
+            offset: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.offset).cloned()
+    }
+
+    fn advance(&mut self) {
+        self.offset += 1;
+    }
+
+    fn skip_separators(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_ascii_whitespace() || c == b',') {
+            self.advance();
+        }
+    }
+
+    fn skip_digits(&mut self) -> usize {
+        let start = self.offset;
+        while matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+            self.advance();
+        }
+        self.offset - start
+    }
+
+    // Numbers can be glued together: `1.5.5` is `1.5` followed by `.5`.
+    fn number(&mut self) -> Option<f32> {
+        self.skip_separators();
+        let start = self.offset;
+        if matches!(self.peek(), Some(b'+' | b'-')) {
+            self.advance();
+        }
+        let mut digits = self.skip_digits();
+        if self.peek() == Some(b'.') {
+            self.advance();
+            digits += self.skip_digits();
+        }
+        if digits == 0 {
+            self.offset = start;
+            return None;
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            let mark = self.offset;
+            self.advance();
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.advance();
+            }
+            if self.skip_digits() == 0 {
+                self.offset = mark;
+            }
+        }
+        std::str::from_utf8(&self.bytes[start..self.offset])
+            .ok()?
+            .parse()
+            .ok()
+    }
+
+    // The flags of the arcs are single digits, which aren't always separated.
+    fn flag(&mut self) -> Option<bool> {
+        self.skip_separators();
+        let flag = match self.peek()? {
+            b'0' => false,
+            b'1' => true,
+            _ => return None,
+        };
+        self.advance();
+        Some(flag)
+    }
+
+    // The arguments of an arc: radii, rotation, flags and end point.
+    #[allow(clippy::type_complexity)]
+    fn arc(&mut self) -> Option<(f32, f32, f32, bool, bool, f32, f32)> {
+        Some((
+            self.number()?,
+            self.number()?,
+            self.number()?,
+            self.flag()?,
+            self.flag()?,
+            self.number()?,
+            self.number()?,
+        ))
+    }
+
+    fn numbers(&mut self) -> Vec<f32> {
+        let mut numbers = Vec::new();
+        while let Some(n) = self.number() {
+            numbers.push(n);
+        }
+        numbers
+    }
+}
+
+// Fills the polygons, implicitly closed, according to the non-zero or the even-odd rule.
+fn fill(pixmap: &mut Pixmap, subpaths: &[Subpath], even_odd: bool, color: Color) {
+    let mut edges = Vec::new();
+    for subpath in subpaths {
+        let points = &subpath.points;
+        for i in 0..points.len() {
+            let (a, b) = (points[i], points[(i + 1) % points.len()]);
+            if a.y != b.y {
+                edges.push((a, b));
+            }
+        }
+    }
+
+    let (mut y_min, mut y_max) = (f32::MAX, f32::MIN);
+    for &(a, b) in &edges {
+        y_min = y_min.min(a.y).min(b.y);
+        y_max = y_max.max(a.y).max(b.y);
+    }
+    if edges.is_empty() {
+        return;
+    }
+
+    let width = pixmap.width as usize;
+    let y_start = y_min.floor().max(0.0) as u32;
+    let y_end = (y_max.ceil().max(0.0) as u32).min(pixmap.height);
+    let weight = 1.0 / SUBSCANLINES as f32;
+    let mut coverage = vec![0.0f32; width];
+    let mut crossings: Vec<(f32, i32)> = Vec::new();
+
+    for y in y_start..y_end {
+        coverage.fill(0.0);
+        for s in 0..SUBSCANLINES {
+            let sy = y as f32 + (s as f32 + 0.5) * weight;
+            crossings.clear();
+            for &(a, b) in &edges {
+                if (a.y <= sy) != (b.y <= sy) {
+                    let x = a.x + (sy - a.y) * (b.x - a.x) / (b.y - a.y);
+                    crossings.push((x, if b.y > a.y { 1 } else { -1 }));
+                }
+            }
+            crossings.sort_by(|u, v| u.0.total_cmp(&v.0));
+            let mut winding = 0;
+            for pair in crossings.windows(2) {
+                winding += pair[0].1;
+                let inside = if even_odd {
+                    winding % 2 != 0
+                } else {
+                    winding != 0
+                };
+                if inside {
+                    add_span(&mut coverage, pair[0].0, pair[1].0, weight);
+                }
+            }
+        }
+        for (x, &alpha) in coverage.iter().enumerate() {
+            if alpha > 0.0 {
+                pixmap.set_blended_pixel(x as u32, y, color, alpha.min(1.0));
+            }
+        }
+    }
+}
+
+fn add_span(coverage: &mut [f32], start: f32, end: f32, weight: f32) {
+    let start = start.max(0.0);
+    let end = end.min(coverage.len() as f32);
+    if end <= start {
+        return;
+    }
+    let first = start.floor() as usize;
+    let last = (end.ceil() as usize).min(coverage.len());
+    for (x, c) in coverage.iter_mut().enumerate().take(last).skip(first) {
+        let covered = end.min(x as f32 + 1.0) - start.max(x as f32);
+        *c += covered * weight;
+    }
+}
+
+// Strokes the polylines, with round caps and joins.
+fn stroke(pixmap: &mut Pixmap, subpaths: &[Subpath], width: f32, color: Color) {
+    let half_width = width / 2.0;
+    let (w, h) = (pixmap.width as usize, pixmap.height as usize);
+    let mut coverage = vec![0.0f32; w * h];
+
+    for subpath in subpaths {
+        let points = &subpath.points;
+        let count = if subpath.closed || points.len() == 1 {
+            points.len()
+        } else {
+            points.len() - 1
+        };
+        for i in 0..count {
+            let (a, b) = (points[i], points[(i + 1) % points.len()]);
+            let margin = half_width + 1.0;
+            let x_start = (a.x.min(b.x) - margin).floor().max(0.0) as usize;
+            let x_end = ((a.x.max(b.x) + margin).ceil().max(0.0) as usize).min(w);
+            let y_start = (a.y.min(b.y) - margin).floor().max(0.0) as usize;
+            let y_end = ((a.y.max(b.y) + margin).ceil().max(0.0) as usize).min(h);
+            for y in y_start..y_end {
+                for x in x_start..x_end {
+                    let p = vec2!(x as f32 + 0.5, y as f32 + 0.5);
+                    let (n, _) = nearest_segment_point(p, a, b);
+                    let alpha = (half_width + 0.5 - (p - n).length()).clamp(0.0, 1.0);
+                    let c = &mut coverage[y * w + x];
+                    *c = c.max(alpha);
+                }
+            }
+        }
+    }
+
+    for (i, &alpha) in coverage.iter().enumerate() {
+        if alpha > 0.0 {
+            pixmap.set_blended_pixel((i % w) as u32, (i / w) as u32, color, alpha);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_data_is_parsed() {
+        let mut path = PathBuilder::new(Transform::IDENTITY);
+        path.parse("m10 20h30v40l-.5.5L1e1 5z M0,0 l 5 5");
+        assert_eq!(path.subpaths.len(), 2);
+        assert_eq!(
+            path.subpaths[0].points,
+            vec![
+                vec2!(10.0, 20.0),
+                vec2!(40.0, 20.0),
+                vec2!(40.0, 60.0),
+                vec2!(39.5, 60.5),
+                vec2!(10.0, 5.0)
+            ]
+        );
+        assert!(path.subpaths[0].closed);
+        assert_eq!(
+            path.subpaths[1].points,
+            vec![vec2!(0.0, 0.0), vec2!(5.0, 5.0)]
+        );
+
+        let transform = Transform::parse("matrix(-1 0 0 1 100 0) translate(10)");
+        assert_eq!(transform.apply(vec2!(5.0, 7.0)), vec2!(85.0, 7.0));
+    }
+
+    #[test]
+    fn test_shapes_are_rendered_at_scale() {
+        let svg = Svg::parse(
+            r##"<svg width="20" height="10" viewBox="0 0 40 20" xmlns="http://www.w3.org/2000/svg"><rect width="20" height="20"/><path d="m28 10a4 4 0 0 1 8 0a4 4 0 0 1-8 0" fill="none" stroke="#888" stroke-width="2"/></svg>"##,
+        )
+        .unwrap();
+        assert_eq!(svg.dims(), (20.0, 10.0));
+
+        let pixmap = svg.render(2.0);
+        assert_eq!((pixmap.width, pixmap.height), (40, 20));
+        assert_eq!(pixmap.get_pixel(5, 5), BLACK);
+        assert_eq!(pixmap.get_pixel(25, 10), WHITE);
+        // The center of the circle, and its outline.
+        assert_eq!(pixmap.get_pixel(32, 10), WHITE);
+        assert_eq!(pixmap.get_pixel(28, 10), Color::Gray(0x88));
+    }
+
+    #[test]
+    fn test_even_odd_rule_makes_holes() {
+        let text = |rule| {
+            format!(
+                r#"<svg viewBox="0 0 10 10"><path d="m0 0h10v10h-10zm2 2h6v6h-6z" fill-rule="{}"/></svg>"#,
+                rule
+            )
+        };
+        let nonzero = Svg::parse(&text("nonzero")).unwrap().render(1.0);
+        let evenodd = Svg::parse(&text("evenodd")).unwrap().render(1.0);
+        assert_eq!(nonzero.get_pixel(5, 5), BLACK);
+        assert_eq!(evenodd.get_pixel(5, 5), WHITE);
+        assert_eq!(evenodd.get_pixel(1, 1), BLACK);
+    }
+}
@@ -0,0 +1,242 @@
+//! The sync operations that couldn't be made while the device was offline.
+//!
+//! The reading positions are queued for the progress sync server, and the books whose
+//! annotations changed for the WebDAV export. The queue is saved to a file, so it outlives
+//! the session, and is flushed when the network comes back up. Only the last position of
+//! a document, and one entry per book, are kept.
+
+use crate::kosync::{KosyncClient, KosyncError, Progress};
+use crate::net;
+use crate::settings::Settings;
+use crate::view::{Event, Hub, NotificationEvent};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+use tracing::{error, info, warn};
+
+pub const SYNC_QUEUE_PATH: &str = "sync-queue.json";
+
+static FLUSHING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum Operation {
+    /// Sends a reading position to the progress sync server.
+    Progress(Progress),
+    /// Exports the annotations of the book at the given path, relative to its library.
+    Annotations { path: PathBuf },
+}
+
+impl Operation {
+    /// Tells whether the other operation makes this one obsolete.
+    fn is_superseded_by(&self, other: &Operation) -> bool {
+        match (self, other) {
+            (Operation::Progress(a), Operation::Progress(b)) => a.document == b.document,
+            (Operation::Annotations { path: a }, Operation::Annotations { path: b }) => a == b,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+struct Queue {
+    operations: Vec<Operation>,
+}
+
+impl Queue {
+    fn load(path: &Path) -> Queue {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|text| {
+                serde_json::from_str(&text)
+                    .map_err(|e| warn!("Can't parse the sync queue: {}.", e))
+                    .ok()
+            })
+            .unwrap_or_default()
+    }
+
+    fn save(&self, path: &Path) {
+        if self.operations.is_empty() {
+            fs::remove_file(path).ok();
+            return;
+        }
+        let result = serde_json::to_string_pretty(self)
+            .map_err(anyhow::Error::from)
+            .and_then(|text| fs::write(path, text).map_err(anyhow::Error::from));
+        if let Err(e) = result {
+            error!("Can't save the sync queue: {:#}.", e);
+        }
+    }
+
+    fn push(&mut self, operation: Operation) {
+        self.operations
+            .retain(|op| !op.is_superseded_by(&operation));
+        self.operations.push(operation);
+    }
+}
+
+lazy_static! {
+    static ref QUEUE: Mutex<Queue> = Mutex::new(Queue::load(&queue_path()));
+}
+
+fn queue() -> MutexGuard<'static, Queue> {
+    QUEUE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(not(test))]
+fn queue_path() -> PathBuf {
+    PathBuf::from(SYNC_QUEUE_PATH)
+}
+
+#[cfg(test)]
+fn queue_path() -> PathBuf {
+    std::env::temp_dir().join(SYNC_QUEUE_PATH)
+}
+
+/// Queues an operation, replacing the one it makes obsolete.
+pub fn push(operation: Operation) {
+    let mut queue = queue();
+    queue.push(operation);
+    queue.save(&queue_path());
+}
+
+/// Returns the number of operations waiting to be made.
+pub fn pending_count() -> usize {
+    queue().operations.len()
+}
+
+/// Tells whether some annotations are waiting to be exported.
+pub fn has_pending_annotations() -> bool {
+    queue()
+        .operations
+        .iter()
+        .any(|op| matches!(op, Operation::Annotations { .. }))
+}
+
+/// Forgets the annotations waiting to be exported, once the WebDAV export went through.
+pub fn clear_annotations() {
+    let mut queue = queue();
+    let count = queue.operations.len();
+    queue
+        .operations
+        .retain(|op| !matches!(op, Operation::Annotations { .. }));
+    if queue.operations.len() != count {
+        queue.save(&queue_path());
+    }
+}
+
+/// Sends the queued reading positions in the background, unless it's already being done.
+///
+/// The positions that can't be sent stay queued. The annotations are exported by the
+/// WebDAV sync, which clears them from the queue.
+pub fn flush(settings: &Settings, hub: &Hub) {
+    let operations = queue()
+        .operations
+        .iter()
+        .filter(|op| matches!(op, Operation::Progress(_)))
+        .cloned()
+        .collect::<Vec<Operation>>();
+
+    if operations.is_empty() || FLUSHING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+
+    let settings = settings.sync.clone();
+    let hub = hub.clone();
+
+    thread::spawn(move || {
+        let _job = net::Job::start();
+        let mut sent = 0;
+        match KosyncClient::new(&settings) {
+            Ok(client) => {
+                for operation in &operations {
+                    let Operation::Progress(progress) = operation else {
+                        continue;
+                    };
+                    match client.update_progress(progress) {
+                        Ok(()) => {
+                            let mut queue = queue();
+                            // A newer position of the document might have been queued since.
+                            queue.operations.retain(|op| op != operation);
+                            queue.save(&queue_path());
+                            sent += 1;
+                        }
+                        // The network is still down: the next positions would fail too.
+                        Err(KosyncError::Request(e)) => {
+                            warn!("Can't send the queued progress: {}.", e);
+                            break;
+                        }
+                        Err(e) => error!("Can't send the queued progress: {}.", e),
+                    }
+                }
+            }
+            Err(e) => warn!("Can't flush the sync queue: {}.", e),
+        }
+        FLUSHING.store(false, Ordering::Release);
+        if sent > 0 {
+            info!(sent, "flushed the sync queue");
+            let msg = if sent == 1 {
+                "Synced the reading position of 1 book.".to_string()
+            } else {
+                format!("Synced the reading positions of {} books.", sent)
+            };
+            hub.send(Event::Notification(NotificationEvent::Show(msg)))
+                .ok();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(document: &str, percentage: f32) -> Operation {
+        Operation::Progress(Progress {
+            document: document.to_string(),
+            progress: "3".to_string(),
+            percentage,
+            device: "Cadmus".to_string(),
+            device_id: "abcd".to_string(),
+            timestamp: None,
+        })
+    }
+
+    #[test]
+    fn test_queue_keeps_the_last_operations() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SYNC_QUEUE_PATH);
+        let mut queue = Queue::default();
+        queue.push(progress("0123", 0.25));
+        queue.push(Operation::Annotations {
+            path: PathBuf::from("a.epub"),
+        });
+        queue.push(progress("4567", 0.1));
+        queue.push(progress("0123", 0.5));
+        queue.push(Operation::Annotations {
+            path: PathBuf::from("a.epub"),
+        });
+
+        assert_eq!(
+            queue.operations,
+            vec![
+                progress("4567", 0.1),
+                progress("0123", 0.5),
+                Operation::Annotations {
+                    path: PathBuf::from("a.epub")
+                },
+            ]
+        );
+
+        queue.save(&path);
+        assert_eq!(Queue::load(&path), queue);
+
+        queue.operations.clear();
+        queue.save(&path);
+        assert!(!path.exists());
+        assert_eq!(Queue::load(&path), Queue::default());
+    }
+}
@@ -0,0 +1,201 @@
+//! Colors of the user interface.
+//!
+//! The built-in themes are *light*, *dark* and *sepia*. Additional themes are read from the
+//! `themes` directory: each TOML file defines a theme named after the file, and the colors it
+//! doesn't set are taken from the light theme.
+
+use crate::color::*;
+use anyhow::{Context as ResultExt, Error};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::RwLock;
+
+pub const DEFAULT_THEME: &str = "light";
+
+/// The colors used by the views.
+///
+/// The schemes are arrays of three colors: the background, the foreground, and the
+/// foreground of disabled items.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Theme {
+    pub background: Color,
+    pub foreground: Color,
+    pub text_normal: [Color; 3],
+    pub text_bump_small: [Color; 3],
+    pub text_bump_large: [Color; 3],
+    /// Scheme of the selected items.
+    pub text_inverted_soft: [Color; 3],
+    /// Scheme of the active items.
+    pub text_inverted_hard: [Color; 3],
+    pub separator_normal: Color,
+    pub separator_strong: Color,
+    pub keyboard_bg: Color,
+    pub battery_fill: Color,
+    pub reading_progress: Color,
+    pub progress_full: Color,
+    pub progress_empty: Color,
+    pub progress_value: Color,
+}
+
+impl Theme {
+    pub const LIGHT: Theme = Theme {
+        background: WHITE,
+        foreground: BLACK,
+        text_normal: TEXT_NORMAL,
+        text_bump_small: TEXT_BUMP_SMALL,
+        text_bump_large: TEXT_BUMP_LARGE,
+        text_inverted_soft: TEXT_INVERTED_SOFT,
+        text_inverted_hard: TEXT_INVERTED_HARD,
+        separator_normal: SEPARATOR_NORMAL,
+        separator_strong: SEPARATOR_STRONG,
+        keyboard_bg: KEYBOARD_BG,
+        battery_fill: BATTERY_FILL,
+        reading_progress: READING_PROGRESS,
+        progress_full: PROGRESS_FULL,
+        progress_empty: PROGRESS_EMPTY,
+        progress_value: PROGRESS_VALUE,
+    };
+
+    pub const DARK: Theme = Theme {
+        background: BLACK,
+        foreground: WHITE,
+        text_normal: [BLACK, WHITE, GRAY07],
+        text_bump_small: [GRAY01, WHITE, GRAY08],
+        text_bump_large: [GRAY04, WHITE, WHITE],
+        text_inverted_soft: [GRAY10, BLACK, BLACK],
+        text_inverted_hard: [WHITE, BLACK, GRAY09],
+        separator_normal: GRAY05,
+        separator_strong: GRAY08,
+        keyboard_bg: GRAY03,
+        battery_fill: GRAY03,
+        reading_progress: GRAY08,
+        progress_full: GRAY10,
+        progress_empty: GRAY02,
+        progress_value: GRAY09,
+    };
+
+    pub const SEPIA: Theme = Theme {
+        background: Color::Rgb(0xF4, 0xEC, 0xD8),
+        foreground: Color::Rgb(0x3B, 0x2F, 0x2F),
+        text_normal: [
+            Color::Rgb(0xF4, 0xEC, 0xD8),
+            Color::Rgb(0x3B, 0x2F, 0x2F),
+            Color::Rgb(0x9A, 0x8C, 0x7A),
+        ],
+        text_bump_small: [
+            Color::Rgb(0xE8, 0xDC, 0xC2),
+            Color::Rgb(0x3B, 0x2F, 0x2F),
+            Color::Rgb(0x86, 0x78, 0x66),
+        ],
+        text_bump_large: [
+            Color::Rgb(0xCF, 0xBF, 0xA0),
+            Color::Rgb(0x3B, 0x2F, 0x2F),
+            Color::Rgb(0x3B, 0x2F, 0x2F),
+        ],
+        text_inverted_soft: [
+            Color::Rgb(0x6B, 0x5A, 0x4B),
+            Color::Rgb(0xF4, 0xEC, 0xD8),
+            Color::Rgb(0xF4, 0xEC, 0xD8),
+        ],
+        text_inverted_hard: [
+            Color::Rgb(0x3B, 0x2F, 0x2F),
+            Color::Rgb(0xF4, 0xEC, 0xD8),
+            Color::Rgb(0x80, 0x70, 0x60),
+        ],
+        separator_normal: Color::Rgb(0xB8, 0xA8, 0x8E),
+        separator_strong: Color::Rgb(0x86, 0x78, 0x66),
+        keyboard_bg: Color::Rgb(0xDD, 0xCF, 0xB3),
+        battery_fill: Color::Rgb(0xDD, 0xCF, 0xB3),
+        reading_progress: Color::Rgb(0x86, 0x78, 0x66),
+        progress_full: Color::Rgb(0x6B, 0x5A, 0x4B),
+        progress_empty: Color::Rgb(0xE8, 0xDC, 0xC2),
+        progress_value: Color::Rgb(0x80, 0x70, 0x60),
+    };
+
+    /// Pure black on white, applied instead of the selected theme by the `high-contrast`
+    /// setting.
+    pub const HIGH_CONTRAST: Theme = Theme {
+        background: WHITE,
+        foreground: BLACK,
+        text_normal: [WHITE, BLACK, GRAY06],
+        text_bump_small: [WHITE, BLACK, GRAY06],
+        text_bump_large: [BLACK, WHITE, WHITE],
+        text_inverted_soft: [BLACK, WHITE, WHITE],
+        text_inverted_hard: [BLACK, WHITE, GRAY10],
+        separator_normal: BLACK,
+        separator_strong: BLACK,
+        keyboard_bg: WHITE,
+        battery_fill: BLACK,
+        reading_progress: BLACK,
+        progress_full: BLACK,
+        progress_empty: WHITE,
+        progress_value: BLACK,
+    };
+
+    /// Returns the built-in theme with the given name.
+    pub fn builtin(name: &str) -> Option<Theme> {
+        match name {
+            "light" => Some(Theme::LIGHT),
+            "dark" => Some(Theme::DARK),
+            "sepia" => Some(Theme::SEPIA),
+            _ => None,
+        }
+    }
+
+    /// Reads a user theme from a TOML file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Theme, Error> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("can't read theme from {}", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("can't parse theme from {}", path.display()))
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::LIGHT
+    }
+}
+
+// Views render without a context: they read the colors of the theme applied to the context
+// from here.
+static CURRENT: RwLock<Theme> = RwLock::new(Theme::LIGHT);
+
+/// Returns the theme applied to the user interface.
+pub fn current() -> Theme {
+    *CURRENT.read().unwrap()
+}
+
+pub(crate) fn set_current(theme: Theme) {
+    *CURRENT.write().unwrap() = theme;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_theme_defaults_to_light() {
+        let theme: Theme = toml::from_str(
+            r#"
+background = { rgb = [250, 240, 220] }
+text-normal = [{ rgb = [250, 240, 220] }, { gray = 32 }, { gray = 128 }]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(theme.background, Color::Rgb(250, 240, 220));
+        assert_eq!(theme.text_normal[1], Color::Gray(32));
+        assert_eq!(theme.foreground, Theme::LIGHT.foreground);
+        assert_eq!(theme.separator_normal, Theme::LIGHT.separator_normal);
+    }
+
+    #[test]
+    fn test_builtin_themes() {
+        assert_eq!(Theme::builtin(DEFAULT_THEME), Some(Theme::LIGHT));
+        assert_eq!(Theme::builtin("dark").map(|t| t.background), Some(BLACK));
+        assert!(Theme::builtin("solarized").is_none());
+    }
+}
@@ -0,0 +1,355 @@
+//! The TLS configuration shared by the HTTPS clients.
+//!
+//! Besides Mozilla's authorities, the servers can be vouched for by the PEM certificates of
+//! a directory, for the self-hosted servers signed by a private authority. The certificates
+//! of some hosts can also be pinned: the chain such a host presents must include a
+//! certificate with one of the given SHA-256 fingerprints. Only the certificates sent by the
+//! server are checked, i.e. its own and the intermediate ones: the root authorities are
+//! usually left out of the chain, and pinning them has no effect.
+
+use crate::settings::TlsSettings;
+use fxhash::FxHashMap;
+use lazy_static::lazy_static;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::WebPkiServerVerifier;
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{
+    CertificateError, ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme,
+};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use tracing::{debug, warn};
+
+pub const CERTIFICATES_DIRNAME: &str = "Certificates";
+
+type Fingerprint = [u8; 32];
+
+lazy_static! {
+    static ref SETTINGS: RwLock<TlsSettings> = RwLock::new(TlsSettings::default());
+}
+
+/// Sets the certificates and the pins used by the clients built from now on.
+pub fn configure(settings: &TlsSettings) {
+    *SETTINGS.write().unwrap() = settings.clone();
+}
+
+/// Returns Mozilla's authorities and those of the certificates directory.
+pub fn root_store() -> RootCertStore {
+    let mut store = RootCertStore::empty();
+    store.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let dir = SETTINGS.read().unwrap().certificates.clone();
+    let count = load_certificates(&dir, &mut store);
+    if count > 0 {
+        debug!(count, dir = %dir.display(), "loaded additional certificates");
+    }
+    store
+}
+
+/// Builds a client configuration that trusts the given authorities and enforces the pins.
+pub fn client_config(roots: RootCertStore) -> ClientConfig {
+    let pins = pins(&SETTINGS.read().unwrap());
+    if pins.is_empty() {
+        return ClientConfig::builder()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+    }
+    ClientConfig::builder()
+        .dangerous()
+        .with_custom_certificate_verifier(pinning_verifier(roots, pins))
+        .with_no_client_auth()
+}
+
+// Every connection is rejected when the pins can't be enforced.
+fn pinning_verifier(
+    roots: RootCertStore,
+    pins: FxHashMap<String, Vec<Fingerprint>>,
+) -> Arc<dyn ServerCertVerifier> {
+    match WebPkiServerVerifier::builder(Arc::new(roots)).build() {
+        Ok(inner) => Arc::new(PinningVerifier { inner, pins }),
+        Err(e) => {
+            warn!("can't enforce the certificate pins: {}", e);
+            Arc::new(RejectingVerifier)
+        }
+    }
+}
+
+/// Adds the certificates of the PEM files of the given directory, returning their number.
+fn load_certificates(dir: &Path, store: &mut RootCertStore) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    let mut count = 0;
+    for path in entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+    {
+        let is_pem = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ["pem", "crt"].contains(&ext.to_lowercase().as_str()));
+        if !is_pem {
+            continue;
+        }
+        let certificates = match CertificateDer::pem_file_iter(&path) {
+            Ok(iter) => iter.collect::<Result<Vec<_>, _>>(),
+            Err(e) => Err(e),
+        };
+        match certificates {
+            Ok(certificates) => {
+                let (added, ignored) = store.add_parsable_certificates(certificates);
+                if ignored > 0 {
+                    warn!(path = %path.display(), ignored, "ignored invalid certificates");
+                }
+                count += added;
+            }
+            Err(e) => warn!(path = %path.display(), "can't read the certificates: {}", e),
+        }
+    }
+    count
+}
+
+/// Parses the fingerprints of the settings, by host.
+fn pins(settings: &TlsSettings) -> FxHashMap<String, Vec<Fingerprint>> {
+    settings
+        .pins
+        .iter()
+        .map(|(host, fingerprints)| {
+            let fingerprints = fingerprints
+                .iter()
+                .filter_map(|text| {
+                    let fingerprint = parse_fingerprint(text);
+                    if fingerprint.is_none() {
+                        warn!(host, "invalid certificate fingerprint: {}", text);
+                    }
+                    fingerprint
+                })
+                .collect();
+            (host.to_lowercase(), fingerprints)
+        })
+        .collect()
+}
+
+/// Parses a SHA-256 fingerprint written in hexadecimal, with or without colons.
+fn parse_fingerprint(text: &str) -> Option<Fingerprint> {
+    let digits = text
+        .chars()
+        .filter(|&c| c != ':')
+        .map(|c| c.to_digit(16).map(|d| d as u8))
+        .collect::<Option<Vec<u8>>>()?;
+    if digits.len() != 64 {
+        return None;
+    }
+    let mut fingerprint = [0; 32];
+    for (byte, pair) in fingerprint.iter_mut().zip(digits.chunks(2)) {
+        *byte = pair[0] << 4 | pair[1];
+    }
+    Some(fingerprint)
+}
+
+fn fingerprint(certificate: &CertificateDer<'_>) -> Fingerprint {
+    Sha256::digest(certificate.as_ref()).into()
+}
+
+/// Verifies the certificates as usual, then checks the pins of the host against the leaf
+/// and intermediate certificates it presented.
+#[derive(Debug)]
+struct PinningVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+    pins: FxHashMap<String, Vec<Fingerprint>>,
+}
+
+impl ServerCertVerifier for PinningVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let verified = self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        )?;
+        let host = server_name.to_str().to_lowercase();
+        if let Some(pins) = self.pins.get(&host) {
+            if !std::iter::once(end_entity)
+                .chain(intermediates)
+                .any(|certificate| pins.contains(&fingerprint(certificate)))
+            {
+                warn!(host, "no certificate matches the pins");
+                return Err(rustls::Error::InvalidCertificate(
+                    CertificateError::ApplicationVerificationFailure,
+                ));
+            }
+        }
+        Ok(verified)
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+/// Rejects every certificate.
+#[derive(Debug)]
+struct RejectingVerifier;
+
+impl ServerCertVerifier for RejectingVerifier {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Err(rustls::Error::InvalidCertificate(
+            CertificateError::ApplicationVerificationFailure,
+        ))
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        Err(rustls::Error::InvalidCertificate(
+            CertificateError::ApplicationVerificationFailure,
+        ))
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        Err(rustls::Error::InvalidCertificate(
+            CertificateError::ApplicationVerificationFailure,
+        ))
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        CryptoProvider::get_default()
+            .map(|provider| {
+                provider
+                    .signature_verification_algorithms
+                    .supported_schemes()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A self-signed authority, whose SHA-256 fingerprint is `CA_FINGERPRINT`.
+    const CA: &str = "-----BEGIN CERTIFICATE-----
+MIIBhzCCAS2gAwIBAgIUZcr7S94SuPb2gsocOPgSdp04CkAwCgYIKoZIzj0EAwIw
+GTEXMBUGA1UEAwwOQ2FkbXVzIFRlc3QgQ0EwHhcNMjYxMDE0MTY0OTE5WhcNMzYx
+MDExMTY0OTE5WjAZMRcwFQYDVQQDDA5DYWRtdXMgVGVzdCBDQTBZMBMGByqGSM49
+AgEGCCqGSM49AwEHA0IABLg2n28UR/pwWb4p6AeYcnH74rT/aFOqb3W8u8fVC//O
+J3hTdHsGH/8zT3eQKni9JfNbnGfDZa+vOro0G3zk/RGjUzBRMB0GA1UdDgQWBBSQ
+gXSUiBHNDx2UEHA1mwQwTyU1vTAfBgNVHSMEGDAWgBSQgXSUiBHNDx2UEHA1mwQw
+TyU1vTAPBgNVHRMBAf8EBTADAQH/MAoGCCqGSM49BAMCA0gAMEUCIQDZYMkTWAej
+OMELr2QmzLTLPcZndlYYkfQQb9xsELk9LgIgK6RAg+cfK1aJ9vMfLdN/6v266uJo
+ba8LXifLpJH65jY=
+-----END CERTIFICATE-----
+";
+    const CA_FINGERPRINT: &str = "FD:3D:AC:49:1D:34:AD:44:79:1B:F5:34:FE:D6:32:D2:\
+                                  D9:26:BE:0E:59:92:32:C0:8C:9E:04:B4:F1:12:C7:A9";
+
+    #[test]
+    fn test_root_store_has_mozilla_authorities() {
+        let root_store = root_store();
+        assert!(
+            root_store.len() > 50,
+            "Expected at least 50 root certificates, got {}",
+            root_store.len()
+        );
+    }
+
+    #[test]
+    fn test_load_certificates() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("home.pem"), CA).unwrap();
+        fs::write(dir.path().join("notes.txt"), CA).unwrap();
+        fs::write(
+            dir.path().join("broken.crt"),
+            "-----BEGIN CERTIFICATE-----\n",
+        )
+        .unwrap();
+        let mut store = RootCertStore::empty();
+        assert_eq!(load_certificates(dir.path(), &mut store), 1);
+        assert_eq!(
+            load_certificates(&dir.path().join("missing"), &mut store),
+            0
+        );
+
+        let certificate = CertificateDer::from_pem_slice(CA.as_bytes()).unwrap();
+        assert_eq!(
+            Some(fingerprint(&certificate)),
+            parse_fingerprint(CA_FINGERPRINT)
+        );
+    }
+
+    #[test]
+    fn test_parse_fingerprint() {
+        let text = "AB:".repeat(31) + "cd";
+        let fingerprint = parse_fingerprint(&text).unwrap();
+        assert_eq!(fingerprint[0], 0xAB);
+        assert_eq!(fingerprint[31], 0xCD);
+        assert!(parse_fingerprint("AB:CD").is_none());
+        assert!(parse_fingerprint(&"zz".repeat(32)).is_none());
+    }
+
+    #[test]
+    fn test_pins_fail_closed() {
+        rustls::crypto::ring::default_provider()
+            .install_default()
+            .ok();
+        let certificate = CertificateDer::from_pem_slice(CA.as_bytes()).unwrap();
+        let pins = [(
+            "cloud.example.org".to_string(),
+            vec![fingerprint(&certificate)],
+        )]
+        .into_iter()
+        .collect();
+        let name = ServerName::try_from("cloud.example.org").unwrap();
+
+        // Without authorities, the chains can't be verified.
+        let verifier = pinning_verifier(RootCertStore::empty(), pins);
+        assert!(verifier
+            .verify_server_cert(&certificate, &[], &name, &[], UnixTime::now())
+            .is_err());
+        assert!(!verifier.supported_verify_schemes().is_empty());
+    }
+}
@@ -1,8 +1,39 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
 pub const MILLIMETERS_PER_INCH: f32 = 25.4;
 pub const CENTIMETERS_PER_INCH: f32 = 2.54;
 pub const POINTS_PER_INCH: f32 = 72.0;
 pub const PICAS_PER_INCH: f32 = 6.0;
 const BASE_DPI: f32 = 300.0;
+pub const MIN_UI_SCALE: f32 = 1.0;
+pub const MAX_UI_SCALE: f32 = 2.0;
+
+// The bits of the scale factor of the user interface.
+static UI_SCALE: AtomicU32 = AtomicU32::new(1.0f32.to_bits());
+
+/// Sets the factor by which the dimensions of the user interface are multiplied.
+pub fn set_ui_scale(scale: f32) {
+    let scale = scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+    UI_SCALE.store(scale.to_bits(), Ordering::Relaxed);
+}
+
+#[inline]
+pub fn ui_scale() -> f32 {
+    f32::from_bits(UI_SCALE.load(Ordering::Relaxed))
+}
+
+// The bits of the factor by which the thicknesses of the lines are multiplied.
+static THICKNESS_SCALE: AtomicU32 = AtomicU32::new(1.0f32.to_bits());
+
+/// Sets the factor by which the thicknesses of the separators and borders are multiplied.
+pub fn set_thickness_scale(scale: f32) {
+    THICKNESS_SCALE.store(scale.max(1.0).to_bits(), Ordering::Relaxed);
+}
+
+#[inline]
+pub fn thickness_scale() -> f32 {
+    f32::from_bits(THICKNESS_SCALE.load(Ordering::Relaxed))
+}
 
 #[inline]
 pub fn pt_to_px(pt: f32, dpi: u16) -> f32 {
@@ -26,10 +57,16 @@ pub fn mm_to_px(mm: f32, dpi: u16) -> f32 {
 
 #[inline]
 pub fn scale_by_dpi_raw(x: f32, dpi: u16) -> f32 {
-    x * (dpi as f32) / BASE_DPI
+    x * (dpi as f32) / BASE_DPI * ui_scale()
 }
 
 #[inline]
 pub fn scale_by_dpi(x: f32, dpi: u16) -> f32 {
     scale_by_dpi_raw(x, dpi).round().max(1.0)
 }
+
+/// Scales a thickness, such as one of the `THICKNESS_*` constants of the views.
+#[inline]
+pub fn scale_thickness(x: f32, dpi: u16) -> f32 {
+    scale_by_dpi(x * thickness_scale(), dpi)
+}
@@ -1,10 +1,10 @@
 use super::label::Label;
 use super::{Align, Bus, Event, Hub, Id, RenderQueue, View, ID_FEEDER};
-use crate::color::{TEXT_INVERTED_HARD, TEXT_NORMAL};
 use crate::context::Context;
 use crate::framebuffer::Framebuffer;
 use crate::geom::Rectangle;
 use crate::input::{DeviceEvent, FingerStatus};
+use crate::theme;
 
 /// A label that provides visual feedback when touched by inverting its colors.
 ///
@@ -65,9 +65,9 @@ impl ActionLabel {
     /// Updates the label's color scheme based on the active state.
     fn update_label_scheme(&mut self, rq: &mut RenderQueue) {
         let scheme = if self.active {
-            TEXT_INVERTED_HARD
+            theme::current().text_inverted_hard
         } else {
-            TEXT_NORMAL
+            theme::current().text_normal
         };
 
         if let Some(label) = self.children[0].downcast_mut::<Label>() {
@@ -122,6 +122,10 @@ impl View for ActionLabel {
     fn render(&self, _fb: &mut dyn Framebuffer, _rect: Rectangle, _fonts: &mut crate::font::Fonts) {
     }
 
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
     fn rect(&self) -> &Rectangle {
         &self.rect
     }
@@ -146,6 +150,7 @@ impl View for ActionLabel {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::color::TEXT_INVERTED_HARD;
     use crate::geom::Point;
     use std::collections::VecDeque;
     use std::sync::mpsc::channel;
@@ -0,0 +1,295 @@
+//! Setting the overlays apart from the views beneath them.
+//!
+//! When the `display.dim-overlays` setting is enabled, the screen around the topmost menu or
+//! dialog is darkened, and when `display.overlay-shadows` is enabled, the menus, the dialogs
+//! and the keyboard cast a soft shadow. The backdrop is drawn after the views, over the
+//! rectangles being updated, like the focus ring.
+//!
+//! Opening or closing an overlay renders the screen again, since the dimming spans all of it.
+
+use super::View;
+use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::framebuffer::Framebuffer;
+use crate::geom::Rectangle;
+use crate::settings::DisplaySettings;
+use crate::unit::scale_by_dpi;
+use std::mem;
+
+/// The opacity of the black blended with the views beneath a modal overlay.
+pub const DIM_ALPHA: f32 = 0.35;
+
+/// The opacity of the shadows, next to the overlays.
+pub const SHADOW_ALPHA: f32 = 0.5;
+
+// The distance over which the shadows fade out, in pixels at 300 DPI.
+const SHADOW_SIZE: f32 = 18.0;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Elevation {
+    /// Part of the screen, like most views.
+    Flat,
+    /// Above the other views, which remain usable, e.g. the keyboard: casts a shadow.
+    Raised,
+    /// Captures the interaction until it's closed, e.g. a menu or a dialog: casts a shadow
+    /// and dims the screen around it.
+    Modal,
+}
+
+/// Returns the rectangles and elevations of the overlays the settings set apart, bottom to top.
+pub fn overlays(view: &dyn View, settings: &DisplaySettings) -> Vec<(Rectangle, Elevation)> {
+    let mut overlays = Vec::new();
+    if settings.dim_overlays || settings.overlay_shadows {
+        collect_overlays(view, settings, &mut overlays);
+    }
+    overlays
+}
+
+fn collect_overlays(
+    view: &dyn View,
+    settings: &DisplaySettings,
+    overlays: &mut Vec<(Rectangle, Elevation)>,
+) {
+    let elevation = view.elevation();
+    if elevation == Elevation::Modal || (elevation == Elevation::Raised && settings.overlay_shadows)
+    {
+        overlays.push((*view.rect(), elevation));
+    }
+    for child in view.children() {
+        collect_overlays(child.as_ref(), settings, overlays);
+    }
+}
+
+fn shadow_size() -> i32 {
+    scale_by_dpi(SHADOW_SIZE, CURRENT_DEVICE.dpi) as i32
+}
+
+/// Returns the rectangles to render again because overlays were opened or closed since the
+/// last rendering.
+pub fn track_overlays(view: &dyn View, context: &mut Context) -> Vec<Rectangle> {
+    let overlays = overlays(view, &context.settings.display);
+    if overlays == context.overlays {
+        return Vec::new();
+    }
+
+    let previous = mem::replace(&mut context.overlays, overlays);
+    let dims = |overlays: &[(Rectangle, Elevation)]| {
+        context.settings.display.dim_overlays
+            && overlays.iter().any(|(_, e)| *e == Elevation::Modal)
+    };
+
+    if dims(&previous) || dims(&context.overlays) {
+        return vec![context.fb.rect()];
+    }
+
+    let size = shadow_size();
+    previous
+        .iter()
+        .chain(context.overlays.iter())
+        .map(|(rect, _)| {
+            let offset = size / 2;
+            rect![
+                rect.min.x - size,
+                rect.min.y + offset - size,
+                rect.max.x + size,
+                rect.max.y + offset + size
+            ]
+        })
+        .collect()
+}
+
+/// Dims the views beneath the topmost modal overlay and draws the shadows of the overlays,
+/// within the rectangles about to be updated.
+pub fn draw_backdrop(rects: &[Rectangle], context: &mut Context) {
+    if context.overlays.is_empty() {
+        return;
+    }
+    paint_backdrop(
+        context.fb.as_mut(),
+        &context.overlays,
+        &context.settings.display,
+        rects,
+    );
+}
+
+fn paint_backdrop(
+    fb: &mut dyn Framebuffer,
+    overlays: &[(Rectangle, Elevation)],
+    settings: &DisplaySettings,
+    rects: &[Rectangle],
+) {
+    let top = overlays
+        .iter()
+        .rposition(|(_, e)| settings.dim_overlays && *e == Elevation::Modal);
+    // The views beneath the topmost modal overlay, shadows included, are dimmed.
+    let above: Vec<Rectangle> = overlays[top.unwrap_or(0)..]
+        .iter()
+        .map(|(rect, _)| *rect)
+        .collect();
+
+    if top.is_some() {
+        for rect in rects {
+            fb.dim_rectangle(rect, &above, DIM_ALPHA);
+        }
+    }
+
+    if settings.overlay_shadows {
+        let size = shadow_size();
+        for overlay in &above {
+            for rect in rects {
+                fb.draw_shadow(overlay, rect, size, SHADOW_ALPHA);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::{BLACK, WHITE};
+    use crate::context::test_helpers::create_test_context;
+    use crate::font::Fonts;
+    use crate::framebuffer::Pixmap;
+    use crate::view::filler::Filler;
+    use crate::view::{Bus, Event, Hub, Id, RenderQueue, ID_FEEDER};
+
+    #[test]
+    fn test_modal_overlays_dim_the_screen() {
+        let mut context = create_test_context();
+        let overlay = Layer::new(rect![100, 100, 300, 200], Elevation::Modal, Vec::new());
+        let mut root = Layer::new(
+            rect![0, 0, 600, 800],
+            Elevation::Flat,
+            vec![
+                Box::new(Filler::new(rect![0, 0, 600, 800], WHITE)) as Box<dyn View>,
+                Box::new(overlay) as Box<dyn View>,
+            ],
+        );
+
+        assert!(track_overlays(&root, &mut context).is_empty());
+
+        context.settings.display.dim_overlays = true;
+        assert_eq!(
+            track_overlays(&root, &mut context),
+            vec![rect![0, 0, 600, 800]]
+        );
+        assert!(track_overlays(&root, &mut context).is_empty());
+
+        let mut fb = Pixmap::new(600, 800, 1);
+        fb.clear(WHITE);
+        paint_backdrop(
+            &mut fb,
+            &context.overlays,
+            &context.settings.display,
+            &[rect![0, 0, 600, 800]],
+        );
+        let dimmed = fb.get_pixel(10, 10).gray();
+        assert!(dimmed < WHITE.gray() && dimmed > BLACK.gray());
+        assert_eq!(fb.get_pixel(150, 150), WHITE);
+
+        // Closing the overlay brings the screen back.
+        root.children.pop();
+        assert_eq!(
+            track_overlays(&root, &mut context),
+            vec![rect![0, 0, 600, 800]]
+        );
+        assert!(context.overlays.is_empty());
+    }
+
+    #[test]
+    fn test_raised_overlays_cast_shadows() {
+        let mut context = create_test_context();
+        context.settings.display.overlay_shadows = true;
+        let root = Layer::new(
+            rect![0, 0, 600, 800],
+            Elevation::Flat,
+            vec![Box::new(Layer::new(
+                rect![0, 500, 600, 800],
+                Elevation::Raised,
+                Vec::new(),
+            )) as Box<dyn View>],
+        );
+
+        let size = shadow_size();
+        assert_eq!(
+            track_overlays(&root, &mut context),
+            vec![rect![
+                -size,
+                500 + size / 2 - size,
+                600 + size,
+                800 + size / 2 + size
+            ]]
+        );
+
+        let mut fb = Pixmap::new(600, 800, 1);
+        fb.clear(WHITE);
+        paint_backdrop(
+            &mut fb,
+            &context.overlays,
+            &context.settings.display,
+            &[rect![0, 400, 600, 800]],
+        );
+        let near = fb.get_pixel(300, 499).gray();
+        let far = fb.get_pixel(300, (499 - size / 4) as u32).gray();
+        assert!(near < far && far < WHITE.gray());
+        assert_eq!(fb.get_pixel(300, 420), WHITE);
+        assert_eq!(fb.get_pixel(300, 600), WHITE);
+    }
+
+    struct Layer {
+        id: Id,
+        rect: Rectangle,
+        elevation: Elevation,
+        children: Vec<Box<dyn View>>,
+    }
+
+    impl Layer {
+        fn new(rect: Rectangle, elevation: Elevation, children: Vec<Box<dyn View>>) -> Layer {
+            Layer {
+                id: ID_FEEDER.next(),
+                rect,
+                elevation,
+                children,
+            }
+        }
+    }
+
+    impl View for Layer {
+        fn handle_event(
+            &mut self,
+            _evt: &Event,
+            _hub: &Hub,
+            _bus: &mut Bus,
+            _rq: &mut RenderQueue,
+            _context: &mut Context,
+        ) -> bool {
+            false
+        }
+
+        fn render(&self, _fb: &mut dyn Framebuffer, _rect: Rectangle, _fonts: &mut Fonts) {}
+
+        fn rect(&self) -> &Rectangle {
+            &self.rect
+        }
+
+        fn rect_mut(&mut self) -> &mut Rectangle {
+            &mut self.rect
+        }
+
+        fn children(&self) -> &Vec<Box<dyn View>> {
+            &self.children
+        }
+
+        fn children_mut(&mut self) -> &mut Vec<Box<dyn View>> {
+            &mut self.children
+        }
+
+        fn id(&self) -> Id {
+            self.id
+        }
+
+        fn elevation(&self) -> Elevation {
+            self.elevation
+        }
+    }
+}
@@ -2,14 +2,14 @@ use super::icon::ICONS_PIXMAPS;
 use super::{Bus, Event, Hub, Id, RenderData, RenderQueue, View, ViewId, ID_FEEDER};
 use super::{BORDER_RADIUS_SMALL, THICKNESS_LARGE, THICKNESS_MEDIUM};
 use crate::battery::Status;
-use crate::color::{BATTERY_FILL, BLACK, WHITE};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::Fonts;
 use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::{BorderSpec, CornerSpec, Rectangle};
 use crate::gesture::GestureEvent;
-use crate::unit::scale_by_dpi;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
 
 const BUMP_HEIGHT: f32 = 5.0 * THICKNESS_LARGE;
 const BUMP_WIDTH: f32 = 4.0 * THICKNESS_LARGE;
@@ -70,7 +70,7 @@ impl View for Battery {
         let dpi = CURRENT_DEVICE.dpi;
 
         let border_radius = scale_by_dpi(BORDER_RADIUS_SMALL, dpi) as i32;
-        let border_thickness = scale_by_dpi(THICKNESS_LARGE, dpi) as i32;
+        let border_thickness = scale_thickness(THICKNESS_LARGE, dpi) as i32;
 
         let batt_width = scale_by_dpi(BATTERY_WIDTH, dpi) as i32;
         let batt_height = scale_by_dpi(BATTERY_HEIGHT, dpi) as i32;
@@ -78,7 +78,7 @@ impl View for Battery {
         let bump_width = scale_by_dpi(BUMP_WIDTH, dpi) as i32;
         let bump_height = scale_by_dpi(BUMP_HEIGHT, dpi) as i32;
 
-        let edge_width = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let edge_width = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
 
         let dx = (self.rect.width() as i32 - (batt_width + bump_width - border_thickness)) / 2;
         let dy = (self.rect.height() as i32 - batt_height) / 2;
@@ -86,7 +86,7 @@ impl View for Battery {
         let mut pt = self.rect.min + pt!(dx, dy);
         let batt_rect = rect![pt, pt + pt!(batt_width, batt_height)];
 
-        fb.draw_rectangle(&self.rect, WHITE);
+        fb.draw_rectangle(&self.rect, theme::current().background);
 
         let max_fill_width = batt_width - 2 * border_thickness;
         let fill_width = (self.capacity.clamp(0.0, 100.0) / 100.0 * max_fill_width as f32) as i32;
@@ -99,15 +99,15 @@ impl View for Battery {
             &CornerSpec::Uniform(border_radius),
             &BorderSpec {
                 thickness: border_thickness as u16,
-                color: BLACK,
+                color: theme::current().foreground,
             },
             &|x, _| {
                 if x <= x_offset_fill {
-                    BATTERY_FILL
+                    theme::current().battery_fill
                 } else if x <= x_offset_edge {
-                    BLACK
+                    theme::current().foreground
                 } else {
-                    WHITE
+                    theme::current().background
                 }
             },
         );
@@ -123,9 +123,9 @@ impl View for Battery {
             &CornerSpec::East(border_radius / 2),
             &BorderSpec {
                 thickness: border_thickness as u16,
-                color: BLACK,
+                color: theme::current().foreground,
             },
-            &WHITE,
+            &theme::current().background,
         );
 
         pt = self.rect.min + pt!(dx, dy) + pt!(border_thickness);
@@ -141,7 +141,7 @@ impl View for Battery {
                 (max_fill_width - pixmap.width as i32) / 2,
                 (fill_height - pixmap.height as i32) / 2
             );
-            fb.draw_blended_pixmap(pixmap, pt, BLACK);
+            fb.draw_blended_pixmap(pixmap, pt, theme::current().foreground);
         }
     }
 
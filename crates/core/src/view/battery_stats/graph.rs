@@ -0,0 +1,177 @@
+use crate::battery::BatterySample;
+use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::font::{font_from_style, Fonts, NORMAL_STYLE};
+use crate::framebuffer::Framebuffer;
+use crate::geom::{lerp, Rectangle};
+use crate::theme;
+use crate::unit::scale_thickness;
+use crate::view::{Bus, Event, Hub, Id, RenderQueue, View, ID_FEEDER, THICKNESS_MEDIUM};
+
+/// The battery level over the recorded period, the charges standing out.
+pub struct Graph {
+    id: Id,
+    rect: Rectangle,
+    children: Vec<Box<dyn View>>,
+    samples: Vec<BatterySample>,
+    end: i64,
+}
+
+impl Graph {
+    pub fn new(rect: Rectangle, samples: Vec<BatterySample>, end: i64) -> Graph {
+        Graph {
+            id: ID_FEEDER.next(),
+            rect,
+            children: Vec::new(),
+            samples,
+            end,
+        }
+    }
+}
+
+/// Returns the level at the given time, and whether the battery was charging.
+fn level_at(samples: &[BatterySample], time: i64) -> Option<(f32, bool)> {
+    let index = samples.partition_point(|s| s.time <= time);
+    if index == 0 {
+        return None;
+    }
+    let a = &samples[index - 1];
+    let Some(b) = samples.get(index) else {
+        return Some((a.level, a.charging));
+    };
+    let t = (time - a.time) as f32 / (b.time - a.time).max(1) as f32;
+    Some((lerp(a.level, b.level, t), a.charging && b.charging))
+}
+
+impl View for Graph {
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _evt, _hub, _bus, _rq, _context), fields(event = ?_evt), ret(level=tracing::Level::TRACE)))]
+    fn handle_event(
+        &mut self,
+        _evt: &Event,
+        _hub: &Hub,
+        _bus: &mut Bus,
+        _rq: &mut RenderQueue,
+        _context: &mut Context,
+    ) -> bool {
+        false
+    }
+
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, fb, fonts, _rect), fields(rect = ?_rect)))]
+    fn render(&self, fb: &mut dyn Framebuffer, _rect: Rectangle, fonts: &mut Fonts) {
+        let dpi = CURRENT_DEVICE.dpi;
+        let theme = theme::current();
+
+        fb.draw_rectangle(&self.rect, theme.background);
+
+        let font = font_from_style(fonts, &NORMAL_STYLE, dpi);
+        let padding = font.em() as i32;
+        let x_height = font.x_heights.0 as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
+
+        let area = rect![
+            self.rect.min.x + padding,
+            self.rect.min.y + padding,
+            self.rect.max.x - padding,
+            self.rect.max.y - padding
+        ];
+
+        if self.samples.len() < 2 {
+            let plan = font.plan("Not enough data", Some(area.width() as i32), None);
+            let pt = pt!(
+                self.rect.min.x + (self.rect.width() as i32 - plan.width) / 2,
+                self.rect.min.y + (self.rect.height() as i32 + x_height) / 2
+            );
+            font.render(fb, theme.foreground, &plan, pt);
+            return;
+        }
+
+        let y_level = |level: f32| area.max.y - (level / 100.0 * area.height() as f32) as i32;
+
+        for level in [0.0, 50.0, 100.0] {
+            let y = y_level(level);
+            fb.draw_rectangle(
+                &rect![
+                    area.min.x,
+                    y - thickness / 2,
+                    area.max.x,
+                    y + thickness - thickness / 2
+                ],
+                theme.separator_normal,
+            );
+        }
+
+        let start = self.samples[0].time;
+        let span = (self.end - start).max(1);
+        let width = area.width().max(1) as i64;
+
+        for x in area.min.x..area.max.x {
+            let time = start + (x - area.min.x) as i64 * span / width;
+            let Some((level, charging)) = level_at(&self.samples, time) else {
+                continue;
+            };
+            let y = y_level(level.clamp(0.0, 100.0));
+            let color = if charging {
+                theme.progress_empty
+            } else {
+                theme.battery_fill
+            };
+            fb.draw_rectangle(&rect![x, y, x + 1, area.max.y], color);
+            fb.draw_rectangle(&rect![x, y - thickness, x + 1, y], theme.foreground);
+        }
+
+        let days = span as f32 / (24.0 * 3600.0);
+        let text = if days < 1.0 {
+            format!("{:.0} h", span as f32 / 3600.0)
+        } else {
+            format!("{:.1} d", days)
+        };
+        let plan = font.plan(&text, None, None);
+        let pt = pt!(area.min.x, area.min.y + x_height);
+        font.render(fb, theme.foreground, &plan, pt);
+    }
+
+    fn rect(&self) -> &Rectangle {
+        &self.rect
+    }
+
+    fn rect_mut(&mut self) -> &mut Rectangle {
+        &mut self.rect
+    }
+
+    fn children(&self) -> &Vec<Box<dyn View>> {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<Box<dyn View>> {
+        &mut self.children
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_at() {
+        let sample = |time: i64, level: f32, charging: bool| BatterySample {
+            time,
+            level,
+            charging,
+            frontlight: 0.0,
+            wifi: false,
+        };
+        let samples = [
+            sample(100, 80.0, false),
+            sample(200, 60.0, true),
+            sample(300, 90.0, true),
+        ];
+        assert_eq!(level_at(&samples, 50), None);
+        assert_eq!(level_at(&samples, 150), Some((70.0, false)));
+        assert_eq!(level_at(&samples, 250), Some((75.0, true)));
+        assert_eq!(level_at(&samples, 400), Some((90.0, true)));
+    }
+}
@@ -0,0 +1,263 @@
+//! The battery level over time, and what drains it.
+//!
+//! ```text
+//! BatteryStats
+//!   ├── TopBar (Back button, "Battery" title)
+//!   ├── Graph (level over the recorded period)
+//!   └── Labels (level, discharge rate, days remaining and drain hints)
+//! ```
+
+mod graph;
+
+use self::graph::Graph;
+use crate::battery::{BatteryHistory, Usage};
+use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::framebuffer::{Framebuffer, UpdateMode};
+use crate::geom::{halves, Rectangle};
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
+use crate::view::common::toggle_main_menu;
+use crate::view::filler::Filler;
+use crate::view::label::Label;
+use crate::view::top_bar::{TopBar, TopBarVariant};
+use crate::view::{Align, Bus, Event, Hub, Id, RenderData, RenderQueue, View, ViewId, ID_FEEDER};
+use crate::view::{SMALL_BAR_HEIGHT, THICKNESS_MEDIUM};
+use chrono::Local;
+
+/// A feature increases the drain noticeably past this ratio.
+const NOTABLE_DRAIN_RATIO: f32 = 1.5;
+
+pub struct BatteryStats {
+    id: Id,
+    rect: Rectangle,
+    children: Vec<Box<dyn View>>,
+}
+
+impl BatteryStats {
+    pub fn new(rect: Rectangle, rq: &mut RenderQueue, context: &mut Context) -> BatteryStats {
+        let id = ID_FEEDER.next();
+        let children = Self::build_children(rect, context);
+        rq.add(RenderData::new(id, rect, UpdateMode::Full));
+
+        BatteryStats { id, rect, children }
+    }
+
+    fn build_children(rect: Rectangle, context: &mut Context) -> Vec<Box<dyn View>> {
+        let dpi = CURRENT_DEVICE.dpi;
+        let small_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
+        let (small_thickness, big_thickness) = halves(thickness);
+        let padding = small_height / 3;
+
+        let mut children = Vec::new();
+
+        let top_bar = TopBar::new(
+            rect![
+                rect.min.x,
+                rect.min.y,
+                rect.max.x,
+                rect.min.y + small_height - small_thickness
+            ],
+            TopBarVariant::Back,
+            "Battery".to_string(),
+            context,
+        );
+        children.push(Box::new(top_bar) as Box<dyn View>);
+
+        let separator = Filler::new(
+            rect![
+                rect.min.x,
+                rect.min.y + small_height - small_thickness,
+                rect.max.x,
+                rect.min.y + small_height + big_thickness
+            ],
+            theme::current().foreground,
+        );
+        children.push(Box::new(separator) as Box<dyn View>);
+
+        let mut y_pos = rect.min.y + small_height + big_thickness;
+        let graph_height = rect.height() as i32 / 3;
+        let graph = Graph::new(
+            rect![rect.min.x, y_pos, rect.max.x, y_pos + graph_height],
+            context.battery_history.samples.iter().cloned().collect(),
+            Local::now().timestamp(),
+        );
+        children.push(Box::new(graph) as Box<dyn View>);
+        y_pos += graph_height;
+
+        let separator = Filler::new(
+            rect![rect.min.x, y_pos, rect.max.x, y_pos + thickness],
+            theme::current().separator_normal,
+        );
+        children.push(Box::new(separator) as Box<dyn View>);
+        y_pos += thickness;
+
+        let level = context.battery.capacity().map_or(0.0, |v| v[0]);
+        let mut lines = vec![format!("Level: {:.0}%", level)];
+        let history = &context.battery_history;
+        lines.push(match history.discharge_rate() {
+            Some(rate) => format!("Discharge Rate: {:.1}% per hour", rate),
+            None => "Discharge Rate: not enough data".to_string(),
+        });
+        if let Some(days) = history.days_remaining(level) {
+            lines.push(format!("Estimated Time Remaining: {:.1} days", days));
+        }
+        lines.extend(drain_hints(history));
+
+        for line in lines {
+            if y_pos + small_height > rect.max.y {
+                break;
+            }
+            let label = Label::new(
+                rect![rect.min.x, y_pos, rect.max.x, y_pos + small_height],
+                line,
+                Align::Left(padding),
+            );
+            children.push(Box::new(label) as Box<dyn View>);
+            y_pos += small_height;
+        }
+
+        if y_pos < rect.max.y {
+            let filler = Filler::new(
+                rect![rect.min.x, y_pos, rect.max.x, rect.max.y],
+                theme::current().background,
+            );
+            children.push(Box::new(filler) as Box<dyn View>);
+        }
+
+        children
+    }
+
+    fn reseed(&mut self, rq: &mut RenderQueue, context: &mut Context) {
+        self.children = Self::build_children(self.rect, context);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+    }
+}
+
+/// Describes the time spent with the frontlight and the Wi-Fi on, and how much faster they
+/// drain the battery.
+fn drain_hints(history: &BatteryHistory) -> Vec<String> {
+    let mut hints = Vec::new();
+    let features: [(&str, Usage, Usage); 2] = [
+        (
+            "Frontlight",
+            history.usage(|s| s.frontlight > 0.0),
+            history.usage(|s| s.frontlight == 0.0),
+        ),
+        (
+            "Wi-Fi",
+            history.usage(|s| s.wifi),
+            history.usage(|s| !s.wifi),
+        ),
+    ];
+
+    for (name, on, off) in features {
+        if on.hours == 0.0 {
+            continue;
+        }
+        hints.push(format!("{} On: {:.0} h", name, on.hours));
+        if let Some((on_rate, off_rate)) = on.rate.zip(off.rate).filter(|(_, off)| *off > 0.0) {
+            let ratio = on_rate / off_rate;
+            if ratio >= NOTABLE_DRAIN_RATIO {
+                hints.push(format!(
+                    "The {} drains the battery {:.1} times faster.",
+                    name.to_lowercase(),
+                    ratio
+                ));
+            }
+        }
+    }
+
+    hints
+}
+
+impl View for BatteryStats {
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _hub, _bus, rq, context), fields(event = ?evt), ret(level=tracing::Level::TRACE)))]
+    fn handle_event(
+        &mut self,
+        evt: &Event,
+        _hub: &Hub,
+        _bus: &mut Bus,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        match *evt {
+            // The tick isn't captured: the battery of the top bar needs it too.
+            Event::BatteryTick => {
+                self.reseed(rq, context);
+                false
+            }
+            Event::ToggleNear(ViewId::MainMenu, rect) => {
+                toggle_main_menu(self, rect, None, rq, context);
+                true
+            }
+            Event::Close(ViewId::MainMenu) => {
+                toggle_main_menu(self, Rectangle::default(), Some(false), rq, context);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _fb, _fonts), fields(rect = ?_rect)))]
+    fn render(&self, _fb: &mut dyn Framebuffer, _rect: Rectangle, _fonts: &mut crate::font::Fonts) {
+    }
+
+    fn resize(&mut self, rect: Rectangle, _hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
+        self.rect = rect;
+        self.children = Self::build_children(rect, context);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Full));
+    }
+
+    fn rect(&self) -> &Rectangle {
+        &self.rect
+    }
+
+    fn rect_mut(&mut self) -> &mut Rectangle {
+        &mut self.rect
+    }
+
+    fn children(&self) -> &Vec<Box<dyn View>> {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<Box<dyn View>> {
+        &mut self.children
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn is_background(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::battery::BatterySample;
+
+    #[test]
+    fn test_drain_hints() {
+        let mut history = BatteryHistory::default();
+        for (hour, level, frontlight) in [(0, 100.0, 0.0), (10, 95.0, 40.0), (12, 89.0, 0.0)] {
+            history.push(BatterySample {
+                time: hour * 3600,
+                level,
+                charging: false,
+                frontlight,
+                wifi: false,
+            });
+        }
+        assert_eq!(
+            drain_hints(&history),
+            [
+                "Frontlight On: 2 h",
+                "The frontlight drains the battery 6.0 times faster."
+            ]
+        );
+    }
+}
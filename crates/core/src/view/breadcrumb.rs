@@ -1,10 +1,19 @@
-use crate::color::TEXT_NORMAL;
+//! A path shown as a row of tappable segments.
+//!
+//! Each ancestor of the current directory is a segment: tapping it sends an
+//! [`Event::SelectDirectory`] event with its path. When the path doesn't fit, the leading
+//! segments are replaced by an ellipsis.
+//!
+//! The segments start at the filesystem root, or at the directory given to
+//! [`Breadcrumb::root`], shown under its own label, e.g. the name of a library.
+
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::{font_from_style, Fonts, NORMAL_STYLE};
 use crate::framebuffer::Framebuffer;
 use crate::geom::Rectangle;
 use crate::gesture::GestureEvent;
+use crate::theme;
 use crate::unit::scale_by_dpi;
 use crate::view::{Bus, Event, Hub, Id, RenderQueue, View, ID_FEEDER};
 use std::path::{Path, PathBuf};
@@ -14,6 +23,7 @@ pub struct Breadcrumb {
     rect: Rectangle,
     children: Vec<Box<dyn View>>,
     path: PathBuf,
+    root: Option<(PathBuf, String)>,
 }
 
 struct BreadcrumbEntry {
@@ -67,11 +77,11 @@ impl View for BreadcrumbEntry {
         let font = font_from_style(fonts, &NORMAL_STYLE, dpi);
 
         let plan = font.plan(&self.text, None, None);
-        let dx = (self.rect.width() as i32 - plan.width as i32) / 2;
+        let dx = (self.rect.width() as i32 - plan.width) / 2;
         let dy = (self.rect.height() as i32 - font.x_heights.0 as i32) / 2;
         let pt = pt!(self.rect.min.x + dx, self.rect.max.y - dy);
 
-        font.render(fb, TEXT_NORMAL[1], &plan, pt);
+        font.render(fb, theme::current().text_normal[1], &plan, pt);
     }
 
     fn rect(&self) -> &Rectangle {
@@ -111,14 +121,30 @@ impl Breadcrumb {
             rect,
             children,
             path: path.to_path_buf(),
+            root: None,
         }
     }
 
-    fn build_path_components(path: &Path) -> Vec<PathBuf> {
+    /// Starts the segments at `path`, shown as `label`.
+    ///
+    /// Paths outside of `path` are shown in full.
+    pub fn root(mut self, path: &Path, label: String) -> Breadcrumb {
+        self.root = Some((path.to_path_buf(), label));
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn build_path_components(path: &Path, root: Option<&Path>) -> Vec<PathBuf> {
         let mut components: Vec<PathBuf> = Vec::new();
         let mut current = path;
 
         while let Some(parent) = current.parent() {
+            if root == Some(current) {
+                break;
+            }
             components.push(current.to_path_buf());
             current = parent;
         }
@@ -129,22 +155,26 @@ impl Breadcrumb {
 
     fn create_component_data(
         components: &[PathBuf],
+        root: Option<&(PathBuf, String)>,
         font: &mut crate::font::Font,
     ) -> Vec<ComponentData> {
         let mut component_data: Vec<ComponentData> = Vec::new();
 
         for (i, component_path) in components.iter().enumerate() {
-            let name = component_path
-                .file_name()
-                .unwrap_or_else(|| {
-                    if component_path.as_os_str() == "/" {
-                        std::ffi::OsStr::new("/")
-                    } else {
-                        component_path.as_os_str()
-                    }
-                })
-                .to_string_lossy()
-                .to_string();
+            let name = match root {
+                Some((path, label)) if path == component_path => label.clone(),
+                _ => component_path
+                    .file_name()
+                    .unwrap_or_else(|| {
+                        if component_path.as_os_str() == "/" {
+                            std::ffi::OsStr::new("/")
+                        } else {
+                            component_path.as_os_str()
+                        }
+                    })
+                    .to_string_lossy()
+                    .to_string(),
+            };
 
             let text = if i == components.len() - 1 {
                 name.clone()
@@ -248,14 +278,15 @@ impl Breadcrumb {
         let font = font_from_style(fonts, &NORMAL_STYLE, dpi);
         let padding = scale_by_dpi(8.0, dpi) as i32;
 
-        let components = Self::build_path_components(path);
-        let component_data = Self::create_component_data(&components, font);
+        let root = self.root.as_ref().map(|(path, _)| path.as_path());
+        let components = Self::build_path_components(path, root);
+        let component_data = Self::create_component_data(&components, self.root.as_ref(), font);
 
         let available_width = self.rect.width() as i32 - 2 * padding;
         let start_index = Self::calculate_start_index(&component_data, available_width, font);
 
         if start_index > 0 {
-            let ellipsis_width = font.plan("... / ", None, None).width as i32;
+            let ellipsis_width = font.plan("... / ", None, None).width;
             self.add_ellipsis_entry(ellipsis_width, padding);
         }
 
@@ -276,8 +307,24 @@ impl View for Breadcrumb {
         false
     }
 
-    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _fb, _fonts), fields(rect = ?_rect)))]
-    fn render(&self, _fb: &mut dyn Framebuffer, _rect: Rectangle, _fonts: &mut Fonts) {}
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, fb, _fonts), fields(rect = ?rect)))]
+    fn render(&self, fb: &mut dyn Framebuffer, rect: Rectangle, _fonts: &mut Fonts) {
+        if let Some(region) = rect.intersection(&self.rect) {
+            fb.draw_rectangle(&region, theme::current().background);
+        }
+    }
+
+    fn resize(
+        &mut self,
+        rect: Rectangle,
+        _hub: &Hub,
+        _rq: &mut RenderQueue,
+        context: &mut Context,
+    ) {
+        self.rect = rect;
+        let path = self.path.clone();
+        self.set_path(&path, &mut context.fonts);
+    }
 
     fn rect(&self) -> &Rectangle {
         &self.rect
@@ -299,3 +346,31 @@ impl View for Breadcrumb {
         self.id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_helpers::create_test_context;
+
+    fn segments(breadcrumb: &Breadcrumb) -> Vec<String> {
+        breadcrumb
+            .children()
+            .iter()
+            .filter_map(|child| child.downcast_ref::<BreadcrumbEntry>())
+            .map(|entry| entry.text.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_segments_start_at_root() {
+        let mut context = create_test_context();
+        let mut breadcrumb = Breadcrumb::new(rect![0, 0, 600, 60], Path::new("/"))
+            .root(Path::new("/mnt/onboard"), "Books".to_string());
+
+        breadcrumb.set_path(Path::new("/mnt/onboard/Comics/Ongoing"), &mut context.fonts);
+        assert_eq!(segments(&breadcrumb), ["Books / ", "Comics / ", "Ongoing"]);
+
+        breadcrumb.set_path(Path::new("/tmp"), &mut context.fonts);
+        assert_eq!(segments(&breadcrumb), ["/ ", "tmp"]);
+    }
+}
@@ -1,6 +1,5 @@
 use super::{Bus, Event, Hub, Id, RenderData, RenderQueue, View, ID_FEEDER};
 use super::{BORDER_RADIUS_LARGE, THICKNESS_MEDIUM};
-use crate::color::{TEXT_INVERTED_HARD, TEXT_NORMAL};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::{font_from_style, Fonts, NORMAL_STYLE};
@@ -8,7 +7,8 @@ use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::{BorderSpec, CornerSpec, Rectangle};
 use crate::gesture::GestureEvent;
 use crate::input::{DeviceEvent, FingerStatus};
-use crate::unit::scale_by_dpi;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
 
 pub struct Button {
     id: Id,
@@ -37,6 +37,13 @@ impl Button {
         self.disabled = value;
         self
     }
+
+    pub fn update(&mut self, text: &str, rq: &mut RenderQueue) {
+        if self.text != text {
+            self.text = text.to_string();
+            rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        }
+    }
 }
 
 impl View for Button {
@@ -80,14 +87,14 @@ impl View for Button {
         let dpi = CURRENT_DEVICE.dpi;
 
         let scheme = if self.active {
-            TEXT_INVERTED_HARD
+            theme::current().text_inverted_hard
         } else {
-            TEXT_NORMAL
+            theme::current().text_normal
         };
         let foreground = if self.disabled { scheme[2] } else { scheme[1] };
 
         let border_radius = scale_by_dpi(BORDER_RADIUS_LARGE, dpi) as i32;
-        let border_thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as u16;
+        let border_thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as u16;
 
         fb.draw_rounded_rectangle_with_border(
             &self.rect,
@@ -113,6 +120,10 @@ impl View for Button {
         font.render(fb, foreground, &plan, pt);
     }
 
+    fn is_focusable(&self) -> bool {
+        !self.disabled
+    }
+
     fn rect(&self) -> &Rectangle {
         &self.rect
     }
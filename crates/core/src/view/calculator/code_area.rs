@@ -1,5 +1,4 @@
 use super::{Line, LineOrigin};
-use crate::color::TEXT_NORMAL;
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::Fonts;
@@ -7,6 +6,7 @@ use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::{CycleDir, Dir, Rectangle};
 use crate::gesture::GestureEvent;
 use crate::input::{ButtonCode, ButtonStatus, DeviceEvent};
+use crate::theme;
 use crate::unit::mm_to_px;
 use crate::view::{Bus, Event, Hub, Id, RenderQueue, View, ID_FEEDER};
 
@@ -119,7 +119,7 @@ impl View for CodeArea {
         let dpi = CURRENT_DEVICE.dpi;
 
         if let Some(irect) = self.rect.intersection(&rect) {
-            fb.draw_rectangle(&irect, TEXT_NORMAL[0]);
+            fb.draw_rectangle(&irect, theme::current().text_normal[0]);
         }
 
         let font = &mut fonts.monospace.regular;
@@ -147,7 +147,7 @@ impl View for CodeArea {
                 }
                 if y >= rect.min.y {
                     let plan = font.plan(&c.to_string(), None, None);
-                    font.render(fb, TEXT_NORMAL[1], &plan, pt!(x, y));
+                    font.render(fb, theme::current().text_normal[1], &plan, pt!(x, y));
                 }
                 x += char_width;
             }
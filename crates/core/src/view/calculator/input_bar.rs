@@ -1,4 +1,3 @@
-use crate::color::{SEPARATOR_NORMAL, TEXT_BUMP_SMALL};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::Fonts;
@@ -6,7 +5,8 @@ use crate::framebuffer::Framebuffer;
 use crate::geom::{CycleDir, Rectangle};
 use crate::gesture::GestureEvent;
 use crate::input::DeviceEvent;
-use crate::unit::scale_by_dpi;
+use crate::theme;
+use crate::unit::scale_thickness;
 use crate::view::filler::Filler;
 use crate::view::icon::Icon;
 use crate::view::input_field::InputField;
@@ -23,7 +23,7 @@ impl InputBar {
         let id = ID_FEEDER.next();
         let mut children = Vec::new();
         let dpi = CURRENT_DEVICE.dpi;
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let side = rect.height() as i32;
 
         let prev_icon = Icon::new(
@@ -31,7 +31,7 @@ impl InputBar {
             rect![rect.min, rect.min + side],
             Event::History(CycleDir::Previous, false),
         )
-        .background(TEXT_BUMP_SMALL[0]);
+        .background(theme::current().text_bump_small[0]);
         children.push(Box::new(prev_icon) as Box<dyn View>);
 
         let separator = Filler::new(
@@ -39,7 +39,7 @@ impl InputBar {
                 pt!(rect.min.x + side, rect.min.y),
                 pt!(rect.min.x + side + thickness, rect.max.y)
             ],
-            SEPARATOR_NORMAL,
+            theme::current().separator_normal,
         );
         children.push(Box::new(separator) as Box<dyn View>);
 
@@ -60,7 +60,7 @@ impl InputBar {
                 pt!(rect.max.x - side - thickness, rect.min.y),
                 pt!(rect.max.x - side, rect.max.y)
             ],
-            SEPARATOR_NORMAL,
+            theme::current().separator_normal,
         );
         children.push(Box::new(separator) as Box<dyn View>);
 
@@ -72,7 +72,7 @@ impl InputBar {
             ],
             Event::History(CycleDir::Next, false),
         )
-        .background(TEXT_BUMP_SMALL[0]);
+        .background(theme::current().text_bump_small[0]);
         children.push(Box::new(next_icon) as Box<dyn View>);
 
         InputBar { id, rect, children }
@@ -128,7 +128,7 @@ impl View for InputBar {
 
     fn resize(&mut self, rect: Rectangle, hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
         let dpi = CURRENT_DEVICE.dpi;
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let side = rect.height() as i32;
         self.children[0].resize(rect![rect.min, rect.min + side], hub, rq, context);
         self.children[1].resize(
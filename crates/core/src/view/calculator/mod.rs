@@ -5,14 +5,14 @@ mod input_bar;
 use self::bottom_bar::BottomBar;
 use self::code_area::CodeArea;
 use self::input_bar::InputBar;
-use crate::color::BLACK;
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::Fonts;
 use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::{halves, CycleDir, Rectangle};
 use crate::gesture::GestureEvent;
-use crate::unit::{mm_to_px, scale_by_dpi};
+use crate::theme;
+use crate::unit::{mm_to_px, scale_by_dpi, scale_thickness};
 use crate::view::common::locate_by_id;
 use crate::view::common::{toggle_battery_menu, toggle_clock_menu, toggle_main_menu};
 use crate::view::filler::Filler;
@@ -129,7 +129,7 @@ impl Calculator {
             scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32,
             scale_by_dpi(BIG_BAR_HEIGHT, dpi) as i32,
         );
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let (small_thickness, big_thickness) = halves(thickness);
         let side = small_height;
 
@@ -160,7 +160,7 @@ impl Calculator {
                 rect.max.x,
                 rect.min.y + side + big_thickness
             ],
-            BLACK,
+            theme::current().foreground,
         );
         children.push(Box::new(separator) as Box<dyn View>);
 
@@ -208,12 +208,12 @@ impl Calculator {
         let code_area = CodeArea::new(code_area_rect, font_size, margin_width);
         children.push(Box::new(code_area) as Box<dyn View>);
 
-        let separator = Filler::new(sp_rect2, BLACK);
+        let separator = Filler::new(sp_rect2, theme::current().foreground);
         children.push(Box::new(separator) as Box<dyn View>);
 
         children.push(Box::new(input_bar) as Box<dyn View>);
 
-        let separator = Filler::new(sp_rect, BLACK);
+        let separator = Filler::new(sp_rect, theme::current().foreground);
         children.push(Box::new(separator) as Box<dyn View>);
 
         children.push(Box::new(keyboard) as Box<dyn View>);
@@ -225,7 +225,7 @@ impl Calculator {
                 rect.max.x,
                 rect.max.y - side + big_thickness
             ],
-            BLACK,
+            theme::current().foreground,
         );
         children.push(Box::new(separator) as Box<dyn View>);
 
@@ -769,7 +769,7 @@ impl View for Calculator {
             scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32,
             scale_by_dpi(BIG_BAR_HEIGHT, dpi) as i32,
         );
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let (small_thickness, big_thickness) = halves(thickness);
         let side = small_height;
 
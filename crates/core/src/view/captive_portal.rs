@@ -0,0 +1,450 @@
+//! Signing in to the captive portal of a network.
+//!
+//! ```text
+//! CaptivePortal
+//!   ├── TopBar (Back button, "Wi-Fi Login" title)
+//!   ├── Title of the portal page, or the state of the check
+//!   ├── One row per field of the login form
+//!   ├── Buttons (Retry, View Page)
+//!   └── ToggleableKeyboard
+//! ```
+//!
+//! The fields are edited through a named input, and the page itself can be read in the
+//! reader, since the login conditions are often written there. Each answer of the portal is
+//! received as an [`Event::PortalPage`], which is `None` once the network is open.
+
+use crate::captive::{self, FieldKind, PortalPage};
+use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::framebuffer::{Framebuffer, UpdateMode};
+use crate::geom::{halves, Rectangle};
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
+use crate::view::action_label::ActionLabel;
+use crate::view::button::Button;
+use crate::view::common::{locate, locate_by_id, toggle_main_menu};
+use crate::view::filler::Filler;
+use crate::view::label::Label;
+use crate::view::named_input::NamedInput;
+use crate::view::toggleable_keyboard::ToggleableKeyboard;
+use crate::view::top_bar::{TopBar, TopBarVariant};
+use crate::view::{Align, Bus, EntryId, Event, Hub, Id, NotificationEvent, RenderData};
+use crate::view::{RenderQueue, View, ViewId, ID_FEEDER, SMALL_BAR_HEIGHT, THICKNESS_MEDIUM};
+use std::thread;
+
+pub struct CaptivePortal {
+    id: Id,
+    rect: Rectangle,
+    children: Vec<Box<dyn View>>,
+    page: Option<PortalPage>,
+    status: String,
+    // The field being edited, in the login form.
+    editing: Option<usize>,
+    focus: Option<ViewId>,
+}
+
+impl CaptivePortal {
+    pub fn new(
+        rect: Rectangle,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> CaptivePortal {
+        let id = ID_FEEDER.next();
+        let status = "Checking the network.".to_string();
+        let children = Self::build_children(rect, None, &status, context);
+        detect(hub);
+        rq.add(RenderData::new(id, rect, UpdateMode::Full));
+
+        CaptivePortal {
+            id,
+            rect,
+            children,
+            page: None,
+            status,
+            editing: None,
+            focus: None,
+        }
+    }
+
+    fn build_children(
+        rect: Rectangle,
+        page: Option<&PortalPage>,
+        status: &str,
+        context: &mut Context,
+    ) -> Vec<Box<dyn View>> {
+        let dpi = CURRENT_DEVICE.dpi;
+        let small_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
+        let (small_thickness, big_thickness) = halves(thickness);
+        let padding = small_height / 3;
+        let middle_x = rect.min.x + rect.width() as i32 / 2;
+
+        let mut children = Vec::new();
+
+        let top_bar = TopBar::new(
+            rect![
+                rect.min.x,
+                rect.min.y,
+                rect.max.x,
+                rect.min.y + small_height - small_thickness
+            ],
+            TopBarVariant::Back,
+            "Wi-Fi Login".to_string(),
+            context,
+        );
+        children.push(Box::new(top_bar) as Box<dyn View>);
+
+        let separator = Filler::new(
+            rect![
+                rect.min.x,
+                rect.min.y + small_height - small_thickness,
+                rect.max.x,
+                rect.min.y + small_height + big_thickness
+            ],
+            theme::current().foreground,
+        );
+        children.push(Box::new(separator) as Box<dyn View>);
+
+        let mut y_pos = rect.min.y + small_height + big_thickness;
+        let buttons_y = rect.max.y - small_height;
+
+        let title = page
+            .map(|page| page.title.clone())
+            .filter(|title| !title.is_empty())
+            .unwrap_or_else(|| status.to_string());
+        let label = Label::new(
+            rect![rect.min.x, y_pos, rect.max.x, y_pos + small_height],
+            title,
+            Align::Left(padding),
+        )
+        .scheme(theme::current().text_bump_small);
+        children.push(Box::new(label) as Box<dyn View>);
+        y_pos += small_height;
+
+        let form = page.and_then(|page| page.login_form().map(|index| &page.forms[index]));
+        let mut has_submit = false;
+
+        for (index, field) in form.iter().flat_map(|form| form.fields.iter().enumerate()) {
+            if field.kind == FieldKind::Hidden {
+                continue;
+            }
+            if y_pos + small_height > buttons_y - small_height {
+                break;
+            }
+            let row = rect![rect.min.x, y_pos, rect.max.x, y_pos + small_height];
+            if field.kind == FieldKind::Submit {
+                has_submit = true;
+                let button = Button::new(
+                    row,
+                    Event::Select(EntryId::SubmitPortalForm(Some(index))),
+                    field.label.clone(),
+                );
+                children.push(Box::new(button) as Box<dyn View>);
+            } else {
+                let name = Label::new(
+                    rect![rect.min.x, y_pos, middle_x, y_pos + small_height],
+                    field.label.clone(),
+                    Align::Left(padding),
+                );
+                children.push(Box::new(name) as Box<dyn View>);
+                let (value, entry) = match field.kind {
+                    FieldKind::Checkbox => (
+                        if field.checked { "Yes" } else { "No" }.to_string(),
+                        EntryId::TogglePortalField(index),
+                    ),
+                    FieldKind::Password => (
+                        "•".repeat(field.value.chars().count()),
+                        EntryId::EditPortalField(index),
+                    ),
+                    _ => (field.value.clone(), EntryId::EditPortalField(index)),
+                };
+                let value = ActionLabel::new(
+                    rect![middle_x, y_pos, rect.max.x, y_pos + small_height],
+                    if value.is_empty() {
+                        "Edit".to_string()
+                    } else {
+                        value
+                    },
+                    Align::Right(padding),
+                )
+                .event(Some(Event::Select(entry)));
+                children.push(Box::new(value) as Box<dyn View>);
+            }
+            y_pos += small_height;
+        }
+
+        if form.is_some() && !has_submit {
+            let button = Button::new(
+                rect![rect.min.x, y_pos, rect.max.x, y_pos + small_height],
+                Event::Select(EntryId::SubmitPortalForm(None)),
+                "Sign In".to_string(),
+            );
+            children.push(Box::new(button) as Box<dyn View>);
+            y_pos += small_height;
+        }
+
+        if page.is_some() {
+            let message = Label::new(
+                rect![rect.min.x, y_pos, rect.max.x, y_pos + small_height],
+                status.to_string(),
+                Align::Center,
+            );
+            children.push(Box::new(message) as Box<dyn View>);
+            y_pos += small_height;
+        }
+
+        if y_pos < buttons_y - thickness {
+            let filler = Filler::new(
+                rect![rect.min.x, y_pos, rect.max.x, buttons_y - thickness],
+                theme::current().background,
+            );
+            children.push(Box::new(filler) as Box<dyn View>);
+        }
+
+        let separator = Filler::new(
+            rect![rect.min.x, buttons_y - thickness, rect.max.x, buttons_y],
+            theme::current().separator_normal,
+        );
+        children.push(Box::new(separator) as Box<dyn View>);
+
+        let retry = Button::new(
+            rect![rect.min.x, buttons_y, middle_x, rect.max.y],
+            Event::Select(EntryId::CheckPortal),
+            "Retry".to_string(),
+        );
+        children.push(Box::new(retry) as Box<dyn View>);
+
+        let view_page = match page {
+            Some(page) => Event::OpenHtml(page.html.clone(), None),
+            None => Event::Select(EntryId::CheckPortal),
+        };
+        let view_page = Button::new(
+            rect![middle_x, buttons_y, rect.max.x, rect.max.y],
+            view_page,
+            "View Page".to_string(),
+        )
+        .disabled(page.is_none());
+        children.push(Box::new(view_page) as Box<dyn View>);
+
+        let keyboard = ToggleableKeyboard::new(rect, false);
+        children.push(Box::new(keyboard) as Box<dyn View>);
+
+        children
+    }
+
+    fn reseed(&mut self, hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
+        if self.focus.is_some() {
+            self.focus = None;
+            self.editing = None;
+            hub.send(Event::Focus(None)).ok();
+        }
+        self.children = Self::build_children(self.rect, self.page.as_ref(), &self.status, context);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+    }
+
+    fn set_status(&mut self, status: &str, hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
+        self.status = status.to_string();
+        self.reseed(hub, rq, context);
+    }
+
+    fn field_mut(&mut self, index: usize) -> Option<&mut captive::Field> {
+        let page = self.page.as_mut()?;
+        let form = page.login_form()?;
+        page.forms[form].fields.get_mut(index)
+    }
+
+    fn edit_field(&mut self, index: usize, hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
+        let Some(field) = self.field_mut(index) else {
+            return;
+        };
+        let (label, value) = (field.label.clone(), field.value.clone());
+        let mut input = NamedInput::new(
+            label,
+            ViewId::CaptivePortalInput,
+            ViewId::CaptivePortalInput,
+            16,
+            context,
+        );
+        input.set_text(&value, rq, context);
+        self.children.push(Box::new(input) as Box<dyn View>);
+        self.editing = Some(index);
+        hub.send(Event::Focus(Some(ViewId::CaptivePortalInput)))
+            .ok();
+    }
+
+    fn submit(
+        &mut self,
+        submitter: Option<usize>,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) {
+        let Some(page) = self.page.clone() else {
+            return;
+        };
+        let Some(form) = page.login_form().map(|index| page.forms[index].clone()) else {
+            return;
+        };
+        self.set_status("Signing in.", hub, rq, context);
+        let hub2 = hub.clone();
+        thread::spawn(move || match captive::submit(&page, &form, submitter) {
+            Ok(next) => {
+                hub2.send(Event::PortalPage(next.map(Box::new))).ok();
+            }
+            Err(e) => {
+                hub2.send(Event::Notification(NotificationEvent::Show(format!(
+                    "Can't sign in: {:#}.",
+                    e
+                ))))
+                .ok();
+                hub2.send(Event::PortalPage(Some(Box::new(page)))).ok();
+            }
+        });
+    }
+
+    fn toggle_keyboard(
+        &mut self,
+        visible: bool,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) {
+        if let Some(index) = locate::<ToggleableKeyboard>(self) {
+            if let Some(keyboard) = self.children[index].downcast_mut::<ToggleableKeyboard>() {
+                keyboard.set_visible(visible, hub, rq, context);
+            }
+        }
+    }
+}
+
+/// Checks the network in the background, the answer is sent as an [`Event::PortalPage`].
+pub fn detect(hub: &Hub) {
+    let hub = hub.clone();
+    thread::spawn(move || match captive::detect() {
+        Ok(page) => {
+            hub.send(Event::PortalPage(page.map(Box::new))).ok();
+        }
+        Err(e) => {
+            hub.send(Event::Notification(NotificationEvent::Show(format!(
+                "Can't check the network: {:#}.",
+                e
+            ))))
+            .ok();
+        }
+    });
+}
+
+impl View for CaptivePortal {
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, hub, _bus, rq, context), fields(event = ?evt), ret(level=tracing::Level::TRACE)))]
+    fn handle_event(
+        &mut self,
+        evt: &Event,
+        hub: &Hub,
+        _bus: &mut Bus,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        match evt {
+            Event::PortalPage(Some(page)) => {
+                self.page = Some(*page.clone());
+                self.set_status("Fill in the form to sign in.", hub, rq, context);
+                true
+            }
+            Event::PortalPage(None) => {
+                self.page = None;
+                self.set_status("The network is open.", hub, rq, context);
+                hub.send(Event::Notification(NotificationEvent::Show(
+                    "Signed in to the network.".to_string(),
+                )))
+                .ok();
+                true
+            }
+            Event::Select(EntryId::CheckPortal) => {
+                self.set_status("Checking the network.", hub, rq, context);
+                detect(hub);
+                true
+            }
+            Event::Select(EntryId::EditPortalField(index)) => {
+                self.edit_field(*index, hub, rq, context);
+                true
+            }
+            Event::Select(EntryId::TogglePortalField(index)) => {
+                if let Some(field) = self.field_mut(*index) {
+                    field.checked = !field.checked;
+                }
+                self.reseed(hub, rq, context);
+                true
+            }
+            Event::Select(EntryId::SubmitPortalForm(submitter)) => {
+                self.submit(*submitter, hub, rq, context);
+                true
+            }
+            Event::Submit(ViewId::CaptivePortalInput, text) => {
+                if let Some(index) = self.editing.take() {
+                    if let Some(field) = self.field_mut(index) {
+                        field.value = text.clone();
+                    }
+                }
+                true
+            }
+            Event::Close(ViewId::CaptivePortalInput) => {
+                if let Some(index) = locate_by_id(self, ViewId::CaptivePortalInput) {
+                    self.children.remove(index);
+                }
+                self.editing = None;
+                self.reseed(hub, rq, context);
+                true
+            }
+            Event::Focus(view_id) => {
+                if self.focus != *view_id {
+                    self.focus = *view_id;
+                    self.toggle_keyboard(view_id.is_some(), hub, rq, context);
+                }
+                true
+            }
+            Event::ToggleNear(ViewId::MainMenu, rect) => {
+                toggle_main_menu(self, *rect, None, rq, context);
+                true
+            }
+            Event::Close(ViewId::MainMenu) => {
+                toggle_main_menu(self, Rectangle::default(), Some(false), rq, context);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _fb, _fonts), fields(rect = ?_rect)))]
+    fn render(&self, _fb: &mut dyn Framebuffer, _rect: Rectangle, _fonts: &mut crate::font::Fonts) {
+    }
+
+    fn resize(&mut self, rect: Rectangle, hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
+        self.rect = rect;
+        self.reseed(hub, rq, context);
+    }
+
+    fn rect(&self) -> &Rectangle {
+        &self.rect
+    }
+
+    fn rect_mut(&mut self) -> &mut Rectangle {
+        &mut self.rect
+    }
+
+    fn children(&self) -> &Vec<Box<dyn View>> {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<Box<dyn View>> {
+        &mut self.children
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn is_background(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,552 @@
+//! A searchable list of the actions available from the current screen.
+//!
+//! Holding the title of a top bar opens the palette. Its [`ActionRegistry`] starts with the
+//! global actions, such as opening the settings or inverting the colors, followed by the
+//! ones contributed by the views on screen through [`View::contribute_actions`].
+//!
+//! Submitting a query keeps the actions whose labels contain its characters in order, the
+//! best matches first. Tapping an action closes the palette and sends the action's event,
+//! which travels up the view hierarchy like the event of a menu entry.
+
+use super::common::locate_by_id;
+use super::filler::Filler;
+use super::icon::Icon;
+use super::input_field::InputField;
+use super::label::Label;
+use super::toggleable_keyboard::ToggleableKeyboard;
+use super::virtual_list::{ListProvider, VirtualList};
+use super::{Align, AppCmd, Bus, EntryId, Event, Hub, Id, RenderData, RenderQueue, View, ViewId};
+use super::{BIG_BAR_HEIGHT, ID_FEEDER, SMALL_BAR_HEIGHT, THICKNESS_MEDIUM};
+use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::download;
+use crate::font::{font_from_style, Fonts, NORMAL_STYLE};
+use crate::framebuffer::{Framebuffer, UpdateMode};
+use crate::geom::Rectangle;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
+use std::cmp::Reverse;
+
+const LIST_INDEX: usize = 5;
+const KEYBOARD_INDEX: usize = 6;
+
+/// An entry of the command palette.
+#[derive(Debug, Clone)]
+pub struct Action {
+    pub label: String,
+    /// Sent when the action is tapped.
+    pub event: Event,
+}
+
+/// The actions listed by the command palette, in registration order.
+#[derive(Debug, Clone, Default)]
+pub struct ActionRegistry {
+    actions: Vec<Action>,
+}
+
+impl ActionRegistry {
+    /// Returns a registry holding the actions available whatever the current view.
+    pub fn global(context: &Context) -> ActionRegistry {
+        let mut registry = ActionRegistry::default();
+        registry.register(
+            "Settings",
+            Event::Select(EntryId::Launch(AppCmd::SettingsEditor)),
+        );
+        registry.register("Invert Colors", Event::Select(EntryId::ToggleInverted));
+        registry.register("Toggle Frontlight", Event::ToggleFrontlight);
+        let wifi = if context.settings.wifi {
+            "Disable WiFi"
+        } else {
+            "Enable WiFi"
+        };
+        registry.register(wifi, Event::Select(EntryId::ToggleWifi));
+        let web_server = if context.settings.web_server.enabled {
+            "Stop Web Server"
+        } else {
+            "Start Web Server"
+        };
+        registry.register(web_server, Event::Select(EntryId::ToggleWebServer));
+        let calibre = if context.calibre {
+            "Disconnect from Calibre"
+        } else {
+            "Connect to Calibre"
+        };
+        registry.register(calibre, Event::Select(EntryId::ToggleCalibre));
+        registry.register("Check for Updates", Event::Select(EntryId::CheckForUpdates));
+        if context.settings.mail.enabled {
+            registry.register("Check Mail", Event::Select(EntryId::CheckMail));
+        }
+        for (index, hook) in context.settings.hooks.iter().enumerate() {
+            if hook.menu {
+                registry.register(&hook.name, Event::Select(EntryId::RunHook(index)));
+            }
+        }
+        registry.register("Downloads", Event::Select(EntryId::ShowDownloads));
+        if download::pending_count() > 0 {
+            let toggle = if download::is_paused() {
+                "Resume Downloads"
+            } else {
+                "Pause Downloads"
+            };
+            registry.register(toggle, Event::Select(EntryId::ToggleDownloads));
+        }
+        if download::failed_count() > 0 {
+            registry.register(
+                "Retry Failed Downloads",
+                Event::Select(EntryId::RetryDownloads),
+            );
+        }
+        registry.register("Take Screenshot", Event::Select(EntryId::TakeScreenshot));
+        registry.register("Full Refresh", Event::Select(EntryId::FullRefresh));
+        registry.register("Notifications", Event::Select(EntryId::Notifications));
+        registry.register("Free Up Space", Event::Select(EntryId::CleanUpStorage));
+        registry.register("System Info", Event::Select(EntryId::SystemInfo));
+        registry.register("About", Event::Select(EntryId::About));
+        registry.register(
+            "Dashboard",
+            Event::Select(EntryId::Launch(AppCmd::Dashboard)),
+        );
+        registry.register(
+            "Dictionary",
+            Event::Select(EntryId::Launch(AppCmd::Dictionary {
+                query: String::new(),
+                language: String::new(),
+            })),
+        );
+        registry.register(
+            "Calculator",
+            Event::Select(EntryId::Launch(AppCmd::Calculator)),
+        );
+        registry.register("Sketch", Event::Select(EntryId::Launch(AppCmd::Sketch)));
+        registry.register(
+            "Screenshots",
+            Event::Select(EntryId::Launch(AppCmd::Screenshots)),
+        );
+        registry.register(
+            "Battery Statistics",
+            Event::Select(EntryId::Launch(AppCmd::BatteryStats)),
+        );
+        registry.register(
+            "Wi-Fi Login",
+            Event::Select(EntryId::Launch(AppCmd::CaptivePortal)),
+        );
+        registry.register(
+            "Share Storage via USB",
+            Event::Select(EntryId::ShareStorage),
+        );
+        registry.register("Back Up Now", Event::Select(EntryId::CreateBackup));
+        registry.register("Restore Backup", Event::Select(EntryId::ShowBackups));
+        registry.register("Kiosk Mode", Event::Select(EntryId::ToggleKioskMode));
+        registry.register("Set Up Cadmus", Event::Show(ViewId::SetupDialog));
+        registry.register("Reset Cadmus", Event::Show(ViewId::ResetDialog));
+        registry.register("Restart", Event::Select(EntryId::Restart));
+        registry.register("Reboot", Event::Select(EntryId::Reboot));
+        registry.register("Quit", Event::Select(EntryId::Quit));
+        registry
+    }
+
+    /// Adds an action, unless an action with the same label is already registered.
+    ///
+    /// The label is written in English and translated here.
+    pub fn register(&mut self, label: &str, event: Event) {
+        let label = tr!(label);
+        if self.actions.iter().all(|action| action.label != label) {
+            self.actions.push(Action { label, event });
+        }
+    }
+
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    /// Returns the actions matching `query`, the best matches first.
+    pub fn search(&self, query: &str) -> Vec<Action> {
+        let mut matches = self
+            .actions
+            .iter()
+            .filter_map(|action| fuzzy_score(query, &action.label).map(|score| (score, action)))
+            .collect::<Vec<(u32, &Action)>>();
+        matches.sort_by_key(|&(score, _)| Reverse(score));
+        matches
+            .into_iter()
+            .map(|(_, action)| action.clone())
+            .collect()
+    }
+}
+
+/// Scores how well `query` matches `label`.
+///
+/// Returns `None` unless the characters of the query appear in the label in the same order,
+/// regardless of case. Consecutive characters and characters starting a word raise the score.
+pub fn fuzzy_score(query: &str, label: &str) -> Option<u32> {
+    let label = label
+        .chars()
+        .flat_map(char::to_lowercase)
+        .collect::<Vec<char>>();
+    let mut score = 0;
+    let mut start = 0;
+    let mut last = None;
+
+    for c in query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter(|c| !c.is_whitespace())
+    {
+        let index = start + label[start..].iter().position(|&l| l == c)?;
+        score += 1;
+        if last.is_some_and(|last| last + 1 == index) {
+            score += 4;
+        }
+        if index == 0 || !label[index - 1].is_alphanumeric() {
+            score += 2;
+        }
+        last = Some(index);
+        start = index + 1;
+    }
+
+    Some(score)
+}
+
+/// Gathers the actions contributed by `view` and its descendants.
+pub fn collect_actions(view: &dyn View, registry: &mut ActionRegistry, context: &Context) {
+    view.contribute_actions(registry, context);
+    for child in view.children() {
+        collect_actions(child.as_ref(), registry, context);
+    }
+}
+
+/// Opens the command palette above the other children of the given root view.
+pub fn show_command_palette(
+    view: &mut dyn View,
+    hub: &Hub,
+    rq: &mut RenderQueue,
+    context: &mut Context,
+) {
+    if locate_by_id(view, ViewId::CommandPalette).is_some() {
+        return;
+    }
+    let mut registry = ActionRegistry::global(context);
+    collect_actions(view, &mut registry, context);
+    let palette = CommandPalette::new(context.fb.rect(), registry, hub, rq, context);
+    view.children_mut().push(Box::new(palette) as Box<dyn View>);
+}
+
+struct Matches(Vec<Action>);
+
+impl ListProvider for Matches {
+    fn item_count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn row(&self, index: usize, rect: Rectangle, context: &mut Context) -> Box<dyn View> {
+        let font = font_from_style(&mut context.fonts, &NORMAL_STYLE, CURRENT_DEVICE.dpi);
+        let padding = font.em() as i32;
+        let label = Label::new(rect, self.0[index].label.clone(), Align::Left(padding))
+            .event(Some(Event::Select(EntryId::RunAction(index))));
+        Box::new(label) as Box<dyn View>
+    }
+}
+
+pub struct CommandPalette {
+    id: Id,
+    rect: Rectangle,
+    children: Vec<Box<dyn View>>,
+    registry: ActionRegistry,
+    query: String,
+}
+
+impl CommandPalette {
+    /// Creates a palette listing every action of `registry`, the input is focused.
+    pub fn new(
+        rect: Rectangle,
+        registry: ActionRegistry,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> CommandPalette {
+        let id = ID_FEEDER.next();
+        let actions = registry.actions().to_vec();
+        let children = Self::build_children(rect, "", actions, context);
+
+        hub.send(Event::Focus(Some(ViewId::CommandPaletteInput)))
+            .ok();
+        rq.add(RenderData::new(id, rect, UpdateMode::Gui));
+
+        CommandPalette {
+            id,
+            rect,
+            children,
+            registry,
+            query: String::new(),
+        }
+    }
+
+    fn build_children(
+        rect: Rectangle,
+        query: &str,
+        actions: Vec<Action>,
+        context: &mut Context,
+    ) -> Vec<Box<dyn View>> {
+        let dpi = CURRENT_DEVICE.dpi;
+        let side = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
+
+        let background = Filler::new(rect, theme::current().background);
+        let input_field = InputField::new(
+            rect![
+                rect.min.x,
+                rect.min.y,
+                rect.max.x - side - thickness,
+                rect.min.y + side
+            ],
+            ViewId::CommandPaletteInput,
+        )
+        .border(false)
+        .text(query, context)
+        .placeholder("Search commands");
+        let separator = Filler::new(
+            rect![
+                rect.max.x - side - thickness,
+                rect.min.y,
+                rect.max.x - side,
+                rect.min.y + side
+            ],
+            theme::current().separator_normal,
+        );
+        let close_icon = Icon::new(
+            "close",
+            rect![rect.max.x - side, rect.min.y, rect.max.x, rect.min.y + side],
+            Event::Close(ViewId::CommandPalette),
+        );
+        let bottom_separator = Filler::new(
+            rect![
+                rect.min.x,
+                rect.min.y + side,
+                rect.max.x,
+                rect.min.y + side + thickness
+            ],
+            theme::current().separator_normal,
+        );
+        let list = VirtualList::new(Self::list_rect(rect, false), Matches(actions), context);
+        let keyboard = ToggleableKeyboard::new(rect, false);
+
+        vec![
+            Box::new(background) as Box<dyn View>,
+            Box::new(input_field) as Box<dyn View>,
+            Box::new(separator) as Box<dyn View>,
+            Box::new(close_icon) as Box<dyn View>,
+            Box::new(bottom_separator) as Box<dyn View>,
+            Box::new(list) as Box<dyn View>,
+            Box::new(keyboard) as Box<dyn View>,
+        ]
+    }
+
+    // The list ends above the keyboard when it's shown.
+    fn list_rect(rect: Rectangle, keyboard: bool) -> Rectangle {
+        let dpi = CURRENT_DEVICE.dpi;
+        let small_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
+        let big_height = scale_by_dpi(BIG_BAR_HEIGHT, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
+        let max_y = if keyboard {
+            rect.max.y - (small_height + 3 * big_height)
+        } else {
+            rect.max.y
+        };
+        rect![
+            rect.min.x,
+            rect.min.y + small_height + thickness,
+            rect.max.x,
+            max_y
+        ]
+    }
+
+    fn list(&self) -> &VirtualList<Matches> {
+        self.children[LIST_INDEX]
+            .downcast_ref::<VirtualList<Matches>>()
+            .unwrap()
+    }
+
+    fn keyboard_visible(&self) -> bool {
+        self.children[KEYBOARD_INDEX]
+            .downcast_ref::<ToggleableKeyboard>()
+            .is_some_and(ToggleableKeyboard::is_visible)
+    }
+
+    fn set_keyboard_visible(
+        &mut self,
+        visible: bool,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) {
+        if self.keyboard_visible() == visible {
+            return;
+        }
+        if let Some(keyboard) = self.children[KEYBOARD_INDEX].downcast_mut::<ToggleableKeyboard>() {
+            keyboard.set_visible(visible, hub, rq, context);
+        }
+        let rect = Self::list_rect(self.rect, visible);
+        self.children[LIST_INDEX].resize(rect, hub, rq, context);
+        rq.add(RenderData::new(
+            self.children[LIST_INDEX].id(),
+            rect,
+            UpdateMode::Gui,
+        ));
+    }
+
+    fn search(&mut self, query: &str, hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
+        self.query = query.to_string();
+        let matches = self.registry.search(query);
+        if let Some(list) = self.children[LIST_INDEX].downcast_mut::<VirtualList<Matches>>() {
+            list.provider_mut().0 = matches;
+            list.go_to_page(0, rq, context);
+            list.reload(rq, context);
+        }
+        self.set_keyboard_visible(false, hub, rq, context);
+    }
+}
+
+impl View for CommandPalette {
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, hub, bus, rq, context), fields(event = ?evt), ret(level=tracing::Level::TRACE)))]
+    fn handle_event(
+        &mut self,
+        evt: &Event,
+        hub: &Hub,
+        bus: &mut Bus,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        match *evt {
+            Event::Submit(ViewId::CommandPaletteInput, ref text) => {
+                self.search(text, hub, rq, context);
+                true
+            }
+            Event::Select(EntryId::RunAction(index)) => {
+                if let Some(action) = self.list().provider().0.get(index) {
+                    bus.push_back(Event::Close(ViewId::CommandPalette));
+                    bus.push_back(action.event.clone());
+                }
+                true
+            }
+            Event::Focus(focus) => {
+                let visible = focus == Some(ViewId::CommandPaletteInput);
+                self.set_keyboard_visible(visible, hub, rq, context);
+                true
+            }
+            Event::Gesture(..) => true,
+            _ => false,
+        }
+    }
+
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _fb, _fonts, _rect), fields(rect = ?_rect)))]
+    fn render(&self, _fb: &mut dyn Framebuffer, _rect: Rectangle, _fonts: &mut Fonts) {}
+
+    fn resize(&mut self, rect: Rectangle, hub: &Hub, _rq: &mut RenderQueue, context: &mut Context) {
+        let keyboard = self.keyboard_visible();
+        let actions = self.list().provider().0.clone();
+        self.rect = rect;
+        self.children = Self::build_children(rect, &self.query, actions, context);
+        if keyboard {
+            hub.send(Event::Focus(Some(ViewId::CommandPaletteInput)))
+                .ok();
+        }
+    }
+
+    fn is_background(&self) -> bool {
+        true
+    }
+
+    fn rect(&self) -> &Rectangle {
+        &self.rect
+    }
+
+    fn rect_mut(&mut self) -> &mut Rectangle {
+        &mut self.rect
+    }
+
+    fn children(&self) -> &Vec<Box<dyn View>> {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<Box<dyn View>> {
+        &mut self.children
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn view_id(&self) -> Option<ViewId> {
+        Some(ViewId::CommandPalette)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_helpers::create_test_context;
+    use std::collections::VecDeque;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_search_ranks_best_matches_first() {
+        let mut registry = ActionRegistry::default();
+        registry.register("Take Screenshot", Event::Select(EntryId::TakeScreenshot));
+        registry.register(
+            "Settings",
+            Event::Select(EntryId::Launch(AppCmd::SettingsEditor)),
+        );
+        registry.register("Invert Colors", Event::Select(EntryId::ToggleInverted));
+        registry.register("Settings", Event::Select(EntryId::Quit));
+
+        assert_eq!(registry.actions().len(), 3);
+        let labels = |query| {
+            registry
+                .search(query)
+                .into_iter()
+                .map(|action| action.label)
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(labels("set"), ["Settings", "Take Screenshot"]);
+        assert_eq!(labels("IC"), ["Invert Colors"]);
+        assert_eq!(labels("").len(), 3);
+        assert!(labels("xyz").is_empty());
+    }
+
+    #[test]
+    fn test_share_storage_action() {
+        let context = create_test_context();
+        let registry = ActionRegistry::global(&context);
+        let actions = registry.search("share storage");
+        assert_eq!(actions[0].label, "Share Storage via USB");
+        assert!(matches!(
+            actions[0].event,
+            Event::Select(EntryId::ShareStorage)
+        ));
+    }
+
+    #[test]
+    fn test_tapping_an_action_closes_the_palette() {
+        let mut context = create_test_context();
+        let (hub, _receiver) = channel();
+        let mut bus = VecDeque::new();
+        let mut rq = RenderQueue::new();
+        let registry = ActionRegistry::global(&context);
+        let mut palette =
+            CommandPalette::new(rect![0, 0, 600, 800], registry, &hub, &mut rq, &mut context);
+
+        let submit = Event::Submit(ViewId::CommandPaletteInput, "updates".to_string());
+        assert!(palette.handle_event(&submit, &hub, &mut bus, &mut rq, &mut context));
+        assert_eq!(palette.list().provider().0[0].label, "Check for Updates");
+
+        let run = Event::Select(EntryId::RunAction(0));
+        palette.handle_event(&run, &hub, &mut bus, &mut rq, &mut context);
+        assert!(matches!(
+            bus.make_contiguous(),
+            [
+                Event::Close(ViewId::CommandPalette),
+                Event::Select(EntryId::CheckForUpdates)
+            ]
+        ));
+    }
+}
@@ -1,12 +1,17 @@
+use super::home::Home;
 use super::menu::{Menu, MenuKind};
 use super::notification::Notification;
-use super::{AppCmd, EntryId, EntryKind, RenderData, RenderQueue, View, ViewId};
+use super::reader::Reader;
+use super::{AppCmd, EntryId, EntryKind, Event, Hub, RenderData, RenderQueue, View, ViewId};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::framebuffer::UpdateMode;
-use crate::geom::{Point, Rectangle};
-use crate::settings::{ButtonScheme, RotationLock};
+use crate::geom::{CycleDir, Point, Rectangle};
+use crate::gesture::GestureEvent;
+use crate::input::{ButtonCode, ButtonStatus, DeviceEvent};
+use crate::settings::{ButtonScheme, Gesture, GestureAction, RotationLock};
 use chrono::Local;
+use fxhash::FxHashMap;
 use std::sync::mpsc;
 
 pub fn shift(view: &mut dyn View, delta: Point) {
@@ -96,6 +101,134 @@ pub fn find_notification_mut(view: &mut dyn View, id: ViewId) -> Option<&mut Not
     None
 }
 
+/// Returns the action mapped to the gesture in the given root view, the tables of the home
+/// and the reader taking precedence over the global mapping.
+pub fn gesture_action(gesture: Gesture, view: &dyn View, context: &Context) -> GestureAction {
+    let gestures = &context.settings.gestures;
+    let overrides = if view.is::<Reader>() {
+        Some(&gestures.reader)
+    } else if view.is::<Home>() {
+        Some(&gestures.home)
+    } else {
+        None
+    };
+    overrides
+        .and_then(|overrides| overrides.get(&gesture))
+        .copied()
+        .unwrap_or(gestures[gesture])
+}
+
+fn action_event(action: GestureAction, context: &Context) -> Option<Event> {
+    let rotation = CURRENT_DEVICE.to_canonical(context.display.rotation);
+
+    match action {
+        GestureAction::FullRefresh => Some(Event::Select(EntryId::FullRefresh)),
+        GestureAction::TakeScreenshot => Some(Event::Select(EntryId::TakeScreenshot)),
+        GestureAction::ToggleFrontlight => Some(Event::ToggleFrontlight),
+        GestureAction::ToggleInverted => Some(Event::Select(EntryId::ToggleInverted)),
+        GestureAction::OpenSettings => Some(Event::Select(EntryId::Launch(AppCmd::SettingsEditor))),
+        GestureAction::RotateClockwise => Some(Event::Select(EntryId::Rotate(
+            CURRENT_DEVICE.from_canonical((rotation + 1) % 4),
+        ))),
+        GestureAction::RotateCounterclockwise => Some(Event::Select(EntryId::Rotate(
+            CURRENT_DEVICE.from_canonical((rotation + 3) % 4),
+        ))),
+        GestureAction::PreviousPage => Some(Event::Page(CycleDir::Previous)),
+        GestureAction::NextPage => Some(Event::Page(CycleDir::Next)),
+        GestureAction::PreviousChapter => Some(Event::Chapter(CycleDir::Previous)),
+        GestureAction::NextChapter => Some(Event::Chapter(CycleDir::Next)),
+        GestureAction::ToggleInspector => Some(Event::Select(EntryId::ToggleInspector)),
+        GestureAction::TogglePerformanceHud => Some(Event::Select(EntryId::TogglePerfHud)),
+        GestureAction::None => None,
+    }
+}
+
+/// Returns the event triggered by the gesture according to the gesture map.
+///
+/// The application consults the map before handing a gesture to the views: `None` means
+/// the gesture isn't mapped and the views should handle it.
+pub fn gesture_map_event(ge: &GestureEvent, view: &dyn View, context: &Context) -> Option<Event> {
+    let gesture = match *ge {
+        GestureEvent::MultiTap(points) => {
+            Gesture::from_taps(points, context.fb.rect(), &context.settings.reader)?
+        }
+        GestureEvent::MultiSwipe { dir, .. } => Gesture::MultiSwipe(dir),
+        // Slanted swipes across the keys type words.
+        GestureEvent::SlantedSwipe { start, .. }
+            if context.settings.swipe_typing && context.kb_rect.includes(start) =>
+        {
+            return None;
+        }
+        GestureEvent::SlantedSwipe { dir, .. } => Gesture::SlantedSwipe(dir),
+        _ => return None,
+    };
+
+    action_event(gesture_action(gesture, view, context), context)
+}
+
+// Sends the event of the action mapped to the gesture, and returns whether there was one.
+fn trigger(gesture: Gesture, view: &dyn View, hub: &Hub, context: &Context) -> bool {
+    match action_event(gesture_action(gesture, view, context), context) {
+        Some(evt) => {
+            hub.send(evt).ok();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Turns the presses and holds of the hardware buttons into the events of the actions the
+/// gesture map binds them to.
+#[derive(Debug, Default)]
+pub struct ButtonMapping {
+    // The buttons being held, and whether their hold was mapped.
+    held: FxHashMap<ButtonCode, bool>,
+}
+
+impl ButtonMapping {
+    /// Returns whether the event was consumed, the resulting event is sent through the hub.
+    ///
+    /// The release of a held button is consumed when the hold was, so that the views don't
+    /// take it for a press.
+    pub fn handle_event(
+        &mut self,
+        evt: &Event,
+        view: &dyn View,
+        hub: &Hub,
+        context: &Context,
+    ) -> bool {
+        match *evt {
+            Event::Gesture(GestureEvent::HoldButtonShort(code)) => {
+                let gesture = match code {
+                    ButtonCode::Backward => Gesture::HoldBackward,
+                    ButtonCode::Forward => Gesture::HoldForward,
+                    _ => return false,
+                };
+                let mapped = trigger(gesture, view, hub, context);
+                self.held.insert(code, mapped);
+                mapped
+            }
+            Event::Device(DeviceEvent::Button {
+                code,
+                status: ButtonStatus::Released,
+                ..
+            }) => {
+                if let Some(mapped) = self.held.remove(&code) {
+                    return mapped;
+                }
+                let gesture = match code {
+                    ButtonCode::Backward => Gesture::PressBackward,
+                    ButtonCode::Forward => Gesture::PressForward,
+                    ButtonCode::Power => Gesture::PressPower,
+                    _ => return false,
+                };
+                trigger(gesture, view, hub, context)
+            }
+            _ => false,
+        }
+    }
+}
+
 pub fn toggle_main_menu(
     view: &mut dyn View,
     rect: Rectangle,
@@ -129,36 +262,32 @@ pub fn toggle_main_menu(
             .collect::<Vec<EntryKind>>();
 
         let apps = vec![
+            EntryKind::Command(tr!("Dashboard"), EntryId::Launch(AppCmd::Dashboard)),
             EntryKind::Command(
-                "Dictionary".to_string(),
+                tr!("Dictionary"),
                 EntryId::Launch(AppCmd::Dictionary {
                     query: "".to_string(),
                     language: "".to_string(),
                 }),
             ),
-            EntryKind::Command(
-                "Calculator".to_string(),
-                EntryId::Launch(AppCmd::Calculator),
-            ),
-            EntryKind::Command("Sketch".to_string(), EntryId::Launch(AppCmd::Sketch)),
+            EntryKind::Command(tr!("Calculator"), EntryId::Launch(AppCmd::Calculator)),
+            EntryKind::Command(tr!("Sketch"), EntryId::Launch(AppCmd::Sketch)),
+            EntryKind::Command(tr!("Screenshots"), EntryId::Launch(AppCmd::Screenshots)),
+            EntryKind::Command(tr!("Battery"), EntryId::Launch(AppCmd::BatteryStats)),
+            EntryKind::Command(tr!("Wi-Fi Login"), EntryId::Launch(AppCmd::CaptivePortal)),
             EntryKind::Separator,
+            EntryKind::Command(tr!("Touch Events"), EntryId::Launch(AppCmd::TouchEvents)),
             EntryKind::Command(
-                "Touch Events".to_string(),
-                EntryId::Launch(AppCmd::TouchEvents),
-            ),
-            EntryKind::Command(
-                "Rotation Values".to_string(),
+                tr!("Rotation Values"),
                 EntryId::Launch(AppCmd::RotationValues),
             ),
         ];
         let mut entries = vec![
-            EntryKind::Command("About".to_string(), EntryId::About),
-            EntryKind::Command("System Info".to_string(), EntryId::SystemInfo),
-            EntryKind::Command(
-                "Settings".to_string(),
-                EntryId::Launch(AppCmd::SettingsEditor),
-            ),
-            EntryKind::Command("Check for Updates".to_string(), EntryId::CheckForUpdates),
+            EntryKind::Command(tr!("About"), EntryId::About),
+            EntryKind::Command(tr!("System Info"), EntryId::SystemInfo),
+            EntryKind::Command(tr!("Notifications"), EntryId::Notifications),
+            EntryKind::Command(tr!("Settings"), EntryId::Launch(AppCmd::SettingsEditor)),
+            EntryKind::Command(tr!("Check for Updates"), EntryId::CheckForUpdates),
             EntryKind::Separator,
         ];
 
@@ -166,28 +295,28 @@ pub fn toggle_main_menu(
             let rotation_lock = context.settings.rotation_lock;
             let gyro = vec![
                 EntryKind::RadioButton(
-                    "Auto".to_string(),
+                    tr!("Auto"),
                     EntryId::SetRotationLock(None),
                     rotation_lock.is_none(),
                 ),
                 EntryKind::Separator,
                 EntryKind::RadioButton(
-                    "Portrait".to_string(),
+                    tr!("Portrait"),
                     EntryId::SetRotationLock(Some(RotationLock::Portrait)),
                     rotation_lock == Some(RotationLock::Portrait),
                 ),
                 EntryKind::RadioButton(
-                    "Landscape".to_string(),
+                    tr!("Landscape"),
                     EntryId::SetRotationLock(Some(RotationLock::Landscape)),
                     rotation_lock == Some(RotationLock::Landscape),
                 ),
                 EntryKind::RadioButton(
-                    "Ignore".to_string(),
+                    tr!("Ignore"),
                     EntryId::SetRotationLock(Some(RotationLock::Current)),
                     rotation_lock == Some(RotationLock::Current),
                 ),
             ];
-            entries.push(EntryKind::SubMenu("Gyroscope".to_string(), gyro));
+            entries.push(EntryKind::SubMenu(tr!("Gyroscope"), gyro));
         }
 
         if CURRENT_DEVICE.has_page_turn_buttons() {
@@ -204,32 +333,40 @@ pub fn toggle_main_menu(
                     button_scheme == ButtonScheme::Inverted,
                 ),
             ];
-            entries.push(EntryKind::SubMenu(
-                "Button Scheme".to_string(),
-                button_schemes,
-            ));
+            entries.push(EntryKind::SubMenu(tr!("Button Scheme"), button_schemes));
         }
 
         entries.extend(vec![
             EntryKind::CheckBox(
-                "Invert Colors".to_string(),
+                tr!("Invert Colors"),
                 EntryId::ToggleInverted,
                 context.fb.inverted(),
             ),
             EntryKind::CheckBox(
-                "Enable WiFi".to_string(),
+                tr!("Enable WiFi"),
                 EntryId::ToggleWifi,
                 context.settings.wifi,
             ),
+            EntryKind::CheckBox(
+                tr!("Web Server"),
+                EntryId::ToggleWebServer,
+                context.settings.web_server.enabled,
+            ),
+            EntryKind::CheckBox(
+                tr!("Connect to Calibre"),
+                EntryId::ToggleCalibre,
+                context.calibre,
+            ),
             EntryKind::Separator,
-            EntryKind::SubMenu("Rotate".to_string(), rotate),
-            EntryKind::Command("Take Screenshot".to_string(), EntryId::TakeScreenshot),
+            EntryKind::SubMenu(tr!("Rotate"), rotate),
+            EntryKind::Command(tr!("Take Screenshot"), EntryId::TakeScreenshot),
+            EntryKind::Command(tr!("Share Storage"), EntryId::ShareStorage),
             EntryKind::Separator,
-            EntryKind::SubMenu("Applications".to_string(), apps),
+            EntryKind::SubMenu(tr!("Applications"), apps),
             EntryKind::Separator,
-            EntryKind::Command("Restart".to_string(), EntryId::Restart),
-            EntryKind::Command("Reboot".to_string(), EntryId::Reboot),
-            EntryKind::Command("Quit".to_string(), EntryId::Quit),
+            EntryKind::Command(tr!("Restart"), EntryId::Restart),
+            EntryKind::Command(tr!("Reboot"), EntryId::Reboot),
+            EntryKind::Command(tr!("Quit"), EntryId::Quit),
         ]);
 
         let main_menu = Menu::new(rect, ViewId::MainMenu, MenuKind::DropDown, entries, context);
@@ -285,13 +422,16 @@ pub fn toggle_battery_menu(
                 }
             }
             _ => {
-                entries.push(EntryKind::Message(
-                    "Information Unavailable".to_string(),
-                    None,
-                ));
+                entries.push(EntryKind::Message(tr!("Information Unavailable"), None));
             }
         }
 
+        entries.push(EntryKind::Separator);
+        entries.push(EntryKind::Command(
+            tr!("Statistics"),
+            EntryId::Launch(AppCmd::BatteryStats),
+        ));
+
         let battery_menu = Menu::new(
             rect,
             ViewId::BatteryMenu,
@@ -440,3 +580,48 @@ pub fn toggle_keyboard_layout_menu(
             .push(Box::new(keyboard_layout_menu) as Box<dyn View>);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::WHITE;
+    use crate::context::test_helpers::create_test_context;
+    use crate::view::filler::Filler;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_mapped_hold_consumes_the_release() {
+        let mut context = create_test_context();
+        context.settings.gestures.press_forward = GestureAction::NextChapter;
+        context.settings.gestures.hold_forward = GestureAction::ToggleFrontlight;
+        let view = Filler::new(rect![0, 0, 600, 800], WHITE);
+        let (hub, receiver) = channel();
+        let mut mapping = ButtonMapping::default();
+        let release = |code| {
+            Event::Device(DeviceEvent::Button {
+                time: 0.0,
+                code,
+                status: ButtonStatus::Released,
+            })
+        };
+
+        assert!(mapping.handle_event(&release(ButtonCode::Forward), &view, &hub, &context));
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(Event::Chapter(CycleDir::Next))
+        ));
+
+        let hold = Event::Gesture(GestureEvent::HoldButtonShort(ButtonCode::Forward));
+        assert!(mapping.handle_event(&hold, &view, &hub, &context));
+        assert!(mapping.handle_event(&release(ButtonCode::Forward), &view, &hub, &context));
+        assert!(matches!(receiver.try_recv(), Ok(Event::ToggleFrontlight)));
+        assert!(receiver.try_recv().is_err());
+
+        // The unmapped buttons are left to the views, along with the release of their holds.
+        let hold = Event::Gesture(GestureEvent::HoldButtonShort(ButtonCode::Backward));
+        assert!(!mapping.handle_event(&hold, &view, &hub, &context));
+        assert!(!mapping.handle_event(&release(ButtonCode::Backward), &view, &hub, &context));
+        assert!(!mapping.handle_event(&release(ButtonCode::Power), &view, &hub, &context));
+        assert!(receiver.try_recv().is_err());
+    }
+}
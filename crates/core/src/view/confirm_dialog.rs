@@ -0,0 +1,216 @@
+//! A dialog asking the user to confirm a destructive action.
+//!
+//! The dialog shows a title, an optional message and two buttons: *Cancel* and a confirm
+//! button labeled after the action. Tapping the confirm button sends the confirm event,
+//! tapping *Cancel* or outside of the dialog sends the cancel event, when one is set.
+//! In both cases an [`Event::Close`] with the dialog's [`ViewId`] follows, so the view
+//! owning the dialog can remove it.
+//!
+//! Every event goes through the bus: the owner of the dialog sees them first, wherever the
+//! dialog sits in the view hierarchy.
+//!
+//! ```no_run
+//! use cadmus_core::view::confirm_dialog::ConfirmDialog;
+//! use cadmus_core::view::{Event, ViewId};
+//!
+//! # let mut context = unsafe { std::mem::zeroed() };
+//! let dialog = ConfirmDialog::new(
+//!     ViewId::ConfirmDialog,
+//!     "Delete the library?",
+//!     "Books",
+//!     "Delete",
+//!     Event::DeleteLibrary(0),
+//!     &mut context,
+//! );
+//! ```
+
+use super::backdrop::Elevation;
+use super::dialog::Dialog;
+use super::{Bus, Event, Hub, Id, RenderQueue, View, ViewId};
+use crate::context::Context;
+use crate::font::Fonts;
+use crate::framebuffer::Framebuffer;
+use crate::geom::Rectangle;
+use crate::gesture::GestureEvent;
+
+pub struct ConfirmDialog {
+    view_id: ViewId,
+    // The dialog is used for its layout and rendering: its children are ours, and the
+    // events are handled here.
+    dialog: Dialog,
+    on_confirm: Event,
+    on_cancel: Option<Event>,
+}
+
+impl ConfirmDialog {
+    /// Creates a dialog centered on the display.
+    ///
+    /// # Arguments
+    ///
+    /// * `view_id` - Identifier of the dialog, sent with the [`Event::Close`] event
+    /// * `title` - The question asked
+    /// * `message` - Details shown below the title, may be empty
+    /// * `confirm_label` - The text of the confirm button, e.g. *Delete*
+    /// * `on_confirm` - The event sent when the action is confirmed
+    /// * `context` - The rendering context, used for font metrics and display dimensions
+    pub fn new(
+        view_id: ViewId,
+        title: &str,
+        message: &str,
+        confirm_label: &str,
+        on_confirm: Event,
+        context: &mut Context,
+    ) -> ConfirmDialog {
+        let text = if message.is_empty() {
+            title.to_string()
+        } else {
+            format!("{}\n{}", title, message)
+        };
+
+        let dialog = Dialog::builder(view_id, text)
+            .add_button("Cancel", Event::Cancel)
+            .add_button(confirm_label, Event::Validate)
+            .build(context);
+
+        ConfirmDialog {
+            view_id,
+            dialog,
+            on_confirm,
+            on_cancel: None,
+        }
+    }
+
+    /// Sets the event sent when the action is cancelled.
+    pub fn on_cancel(mut self, event: Event) -> ConfirmDialog {
+        self.on_cancel = Some(event);
+        self
+    }
+
+    fn close(&self, event: Option<Event>, bus: &mut Bus) {
+        if let Some(event) = event {
+            bus.push_back(event);
+        }
+        bus.push_back(Event::Close(self.view_id));
+    }
+}
+
+impl View for ConfirmDialog {
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _hub, bus, _rq, _context), fields(event = ?evt), ret(level=tracing::Level::TRACE)))]
+    fn handle_event(
+        &mut self,
+        evt: &Event,
+        _hub: &Hub,
+        bus: &mut Bus,
+        _rq: &mut RenderQueue,
+        _context: &mut Context,
+    ) -> bool {
+        match *evt {
+            Event::Validate => {
+                self.close(Some(self.on_confirm.clone()), bus);
+                true
+            }
+            Event::Cancel => {
+                self.close(self.on_cancel.clone(), bus);
+                true
+            }
+            Event::Gesture(GestureEvent::Tap(center)) if !self.rect().includes(center) => {
+                self.close(self.on_cancel.clone(), bus);
+                true
+            }
+            Event::Gesture(..) => true,
+            _ => false,
+        }
+    }
+
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, fb, fonts), fields(rect = ?rect)))]
+    fn render(&self, fb: &mut dyn Framebuffer, rect: Rectangle, fonts: &mut Fonts) {
+        self.dialog.render(fb, rect, fonts);
+    }
+
+    fn resize(&mut self, rect: Rectangle, hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
+        self.dialog.resize(rect, hub, rq, context);
+    }
+
+    fn is_background(&self) -> bool {
+        true
+    }
+
+    fn elevation(&self) -> Elevation {
+        Elevation::Modal
+    }
+
+    fn rect(&self) -> &Rectangle {
+        self.dialog.rect()
+    }
+
+    fn rect_mut(&mut self) -> &mut Rectangle {
+        self.dialog.rect_mut()
+    }
+
+    fn children(&self) -> &Vec<Box<dyn View>> {
+        self.dialog.children()
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<Box<dyn View>> {
+        self.dialog.children_mut()
+    }
+
+    fn id(&self) -> Id {
+        self.dialog.id()
+    }
+
+    fn view_id(&self) -> Option<ViewId> {
+        Some(self.view_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_helpers::create_test_context;
+    use std::collections::VecDeque;
+    use std::sync::mpsc::channel;
+
+    fn tap_button(dialog: &mut ConfirmDialog, index: usize, context: &mut Context) -> Vec<Event> {
+        let (hub, _receiver) = channel();
+        let mut bus = VecDeque::new();
+        let mut rq = RenderQueue::new();
+        let button = *dialog.children()[index].rect();
+        crate::view::handle_event(
+            dialog,
+            &Event::Gesture(GestureEvent::Tap(button.center())),
+            &hub,
+            &mut bus,
+            &mut rq,
+            context,
+        );
+        bus.into_iter().collect()
+    }
+
+    #[test]
+    fn test_confirm_and_cancel_events() {
+        let mut context = create_test_context();
+        let mut dialog = ConfirmDialog::new(
+            ViewId::ConfirmDialog,
+            "Delete the library?",
+            "Books",
+            "Delete",
+            Event::DeleteLibrary(0),
+            &mut context,
+        )
+        .on_cancel(Event::Back);
+
+        // Two lines of text come before the buttons.
+        let confirm = tap_button(&mut dialog, 3, &mut context);
+        assert!(matches!(
+            confirm[..],
+            [Event::DeleteLibrary(0), Event::Close(ViewId::ConfirmDialog)]
+        ));
+
+        let cancel = tap_button(&mut dialog, 2, &mut context);
+        assert!(matches!(
+            cancel[..],
+            [Event::Back, Event::Close(ViewId::ConfirmDialog)]
+        ));
+    }
+}
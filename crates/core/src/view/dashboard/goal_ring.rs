@@ -0,0 +1,120 @@
+use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::font::{font_from_style, Fonts, NORMAL_STYLE};
+use crate::framebuffer::Framebuffer;
+use crate::geom::Rectangle;
+use crate::theme;
+use crate::view::{Bus, Event, Hub, Id, RenderQueue, View, ID_FEEDER};
+use std::f32::consts::PI;
+
+/// A ring filled in proportion to the books finished during the year.
+pub struct GoalRing {
+    id: Id,
+    rect: Rectangle,
+    children: Vec<Box<dyn View>>,
+    finished: usize,
+    goal: usize,
+    year: i32,
+}
+
+impl GoalRing {
+    pub fn new(rect: Rectangle, finished: usize, goal: usize, year: i32) -> GoalRing {
+        GoalRing {
+            id: ID_FEEDER.next(),
+            rect,
+            children: Vec::new(),
+            finished,
+            goal,
+            year,
+        }
+    }
+
+    fn progress(&self) -> f32 {
+        if self.goal == 0 {
+            return 1.0;
+        }
+        (self.finished as f32 / self.goal as f32).min(1.0)
+    }
+}
+
+impl View for GoalRing {
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _evt, _hub, _bus, _rq, _context), fields(event = ?_evt), ret(level=tracing::Level::TRACE)))]
+    fn handle_event(
+        &mut self,
+        _evt: &Event,
+        _hub: &Hub,
+        _bus: &mut Bus,
+        _rq: &mut RenderQueue,
+        _context: &mut Context,
+    ) -> bool {
+        false
+    }
+
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, fb, fonts, _rect), fields(rect = ?_rect)))]
+    fn render(&self, fb: &mut dyn Framebuffer, _rect: Rectangle, fonts: &mut Fonts) {
+        let dpi = CURRENT_DEVICE.dpi;
+        let theme = theme::current();
+
+        fb.draw_rectangle(&self.rect, theme.background);
+
+        let side = self.rect.height() as i32;
+        let radius = side / 2 - side / 12;
+        let thickness = (radius / 4).max(1);
+        let center = pt!(self.rect.min.x + side / 2, self.rect.min.y + side / 2);
+        let limit = 2.0 * PI * self.progress();
+
+        // The arc starts at the top and runs clockwise.
+        for y in center.y - radius..=center.y + radius {
+            for x in center.x - radius..=center.x + radius {
+                let (dx, dy) = ((x - center.x) as f32, (y - center.y) as f32);
+                let distance = dx.hypot(dy);
+                if distance > radius as f32 || distance < (radius - thickness) as f32 {
+                    continue;
+                }
+                let angle = dx.atan2(-dy).rem_euclid(2.0 * PI);
+                let color = if angle <= limit {
+                    theme.reading_progress
+                } else {
+                    theme.progress_empty
+                };
+                fb.set_pixel(x as u32, y as u32, color);
+            }
+        }
+
+        let font = font_from_style(fonts, &NORMAL_STYLE, dpi);
+        let x_height = font.x_heights.0 as i32;
+
+        let count = format!("{}/{}", self.finished, self.goal);
+        let plan = font.plan(&count, Some(2 * (radius - thickness)), None);
+        let pt = pt!(center.x - plan.width / 2, center.y + x_height / 2);
+        font.render(fb, theme.foreground, &plan, pt);
+
+        let padding = font.em() as i32;
+        let start_x = self.rect.min.x + side + padding;
+        let max_width = self.rect.max.x - start_x;
+        let text = format!("Books finished in {}", self.year);
+        let plan = font.plan(&text, Some(max_width), None);
+        let pt = pt!(start_x, center.y + x_height / 2);
+        font.render(fb, theme.foreground, &plan, pt);
+    }
+
+    fn rect(&self) -> &Rectangle {
+        &self.rect
+    }
+
+    fn rect_mut(&mut self) -> &mut Rectangle {
+        &mut self.rect
+    }
+
+    fn children(&self) -> &Vec<Box<dyn View>> {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<Box<dyn View>> {
+        &mut self.children
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+}
@@ -0,0 +1,365 @@
+//! A start screen composed of widgets.
+//!
+//! ```text
+//! Dashboard
+//!   ├── TopBar (Back button, "Dashboard" title)
+//!   └── One section per widget, in the order of the settings:
+//!       ├── Status (time, date and battery level)
+//!       ├── Continue Reading (books being read)
+//!       ├── Recently Added (latest books of the library)
+//!       └── Reading Goal (books finished this year)
+//! ```
+//!
+//! Going back from the dashboard shows the library.
+
+mod goal_ring;
+mod status;
+
+use self::goal_ring::GoalRing;
+use self::status::Status;
+use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::framebuffer::{Framebuffer, UpdateMode};
+use crate::geom::{halves, Rectangle};
+use crate::metadata::{BookQuery, Info, SimpleStatus};
+use crate::settings::DashboardWidget;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
+use crate::view::common::toggle_main_menu;
+use crate::view::filler::Filler;
+use crate::view::home::book::Book;
+use crate::view::label::Label;
+use crate::view::top_bar::{TopBar, TopBarVariant};
+use crate::view::{Align, Bus, Event, Hub, Id, RenderData, RenderQueue, View, ViewId, ID_FEEDER};
+use crate::view::{BIG_BAR_HEIGHT, SMALL_BAR_HEIGHT, THICKNESS_MEDIUM};
+use chrono::{Datelike, Local};
+use std::cmp::Reverse;
+
+pub struct Dashboard {
+    id: Id,
+    rect: Rectangle,
+    children: Vec<Box<dyn View>>,
+}
+
+impl Dashboard {
+    pub fn new(rect: Rectangle, rq: &mut RenderQueue, context: &mut Context) -> Dashboard {
+        let id = ID_FEEDER.next();
+        let children = Self::build_children(rect, context);
+        rq.add(RenderData::new(id, rect, UpdateMode::Full));
+
+        Dashboard { id, rect, children }
+    }
+
+    fn build_children(rect: Rectangle, context: &mut Context) -> Vec<Box<dyn View>> {
+        let dpi = CURRENT_DEVICE.dpi;
+        let small_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
+        let big_height = scale_by_dpi(BIG_BAR_HEIGHT, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
+        let (small_thickness, big_thickness) = halves(thickness);
+        let padding = small_height / 3;
+
+        let mut children = Vec::new();
+
+        let top_bar = TopBar::new(
+            rect![
+                rect.min.x,
+                rect.min.y,
+                rect.max.x,
+                rect.min.y + small_height - small_thickness
+            ],
+            TopBarVariant::Back,
+            "Dashboard".to_string(),
+            context,
+        );
+        children.push(Box::new(top_bar) as Box<dyn View>);
+
+        let separator = Filler::new(
+            rect![
+                rect.min.x,
+                rect.min.y + small_height - small_thickness,
+                rect.max.x,
+                rect.min.y + small_height + big_thickness
+            ],
+            theme::current().foreground,
+        );
+        children.push(Box::new(separator) as Box<dyn View>);
+
+        let (books, _) =
+            context
+                .library
+                .list(&context.library.home, Some(&BookQuery::default()), false);
+        let dashboard = context.settings.home.dashboard.clone();
+        let library = context
+            .settings
+            .libraries
+            .get(context.settings.selected_library)
+            .cloned()
+            .unwrap_or_default();
+        let (first_column, second_column) = (library.first_column, library.second_column);
+        let thumbnail_previews = library.thumbnail_previews;
+
+        let mut y_pos = rect.min.y + small_height + big_thickness;
+
+        for widget in &dashboard.widgets {
+            let listed = match widget {
+                DashboardWidget::ContinueReading => {
+                    Some(continue_reading(&books, dashboard.books_count))
+                }
+                DashboardWidget::RecentlyAdded => {
+                    Some(recently_added(&books, dashboard.books_count))
+                }
+                _ => None,
+            };
+
+            let height = match (widget, &listed) {
+                (DashboardWidget::Status, _) => 2 * big_height,
+                (DashboardWidget::ReadingGoal, _) => small_height + 2 * big_height,
+                (_, Some(listed)) => small_height + listed.len().max(1) as i32 * big_height,
+                (_, None) => 0,
+            };
+
+            if y_pos + height > rect.max.y {
+                break;
+            }
+
+            let section_rect = rect![rect.min.x, y_pos, rect.max.x, y_pos + height];
+
+            match widget {
+                DashboardWidget::Status => {
+                    let status = Status::new(section_rect, context);
+                    children.push(Box::new(status) as Box<dyn View>);
+                }
+                DashboardWidget::ReadingGoal => {
+                    children.push(Self::header(section_rect, small_height, "Reading Goal"));
+                    let year = Local::now().year();
+                    let ring = GoalRing::new(
+                        rect![
+                            rect.min.x + padding,
+                            y_pos + small_height,
+                            rect.max.x - padding,
+                            section_rect.max.y
+                        ],
+                        finished_in(&books, year),
+                        dashboard.yearly_goal,
+                        year,
+                    );
+                    children.push(Box::new(ring) as Box<dyn View>);
+                }
+                DashboardWidget::ContinueReading | DashboardWidget::RecentlyAdded => {
+                    let title = if *widget == DashboardWidget::ContinueReading {
+                        "Continue Reading"
+                    } else {
+                        "Recently Added"
+                    };
+                    children.push(Self::header(section_rect, small_height, title));
+
+                    let listed = listed.unwrap_or_default();
+                    let mut y_book = y_pos + small_height;
+
+                    if listed.is_empty() {
+                        let label = Label::new(
+                            rect![rect.min.x, y_book, rect.max.x, y_book + big_height],
+                            "No books".to_string(),
+                            Align::Center,
+                        );
+                        children.push(Box::new(label) as Box<dyn View>);
+                    }
+
+                    for (index, info) in listed.into_iter().enumerate() {
+                        let preview_path = if thumbnail_previews {
+                            Some(context.library.thumbnail_preview(&info.file.path))
+                                .filter(|path| path.exists())
+                        } else {
+                            None
+                        };
+                        let book = Book::new(
+                            rect![rect.min.x, y_book, rect.max.x, y_book + big_height],
+                            info,
+                            index,
+                            first_column,
+                            second_column,
+                            preview_path,
+                        );
+                        children.push(Box::new(book) as Box<dyn View>);
+                        y_book += big_height;
+                    }
+                }
+            }
+
+            y_pos += height;
+
+            let separator = Filler::new(
+                rect![rect.min.x, y_pos, rect.max.x, y_pos + thickness],
+                theme::current().separator_normal,
+            );
+            children.push(Box::new(separator) as Box<dyn View>);
+            y_pos += thickness;
+        }
+
+        if y_pos < rect.max.y {
+            let filler = Filler::new(
+                rect![rect.min.x, y_pos, rect.max.x, rect.max.y],
+                theme::current().background,
+            );
+            children.push(Box::new(filler) as Box<dyn View>);
+        }
+
+        children
+    }
+
+    fn header(rect: Rectangle, height: i32, title: &str) -> Box<dyn View> {
+        let label = Label::new(
+            rect![rect.min.x, rect.min.y, rect.max.x, rect.min.y + height],
+            title.to_string(),
+            Align::Left(height / 3),
+        )
+        .scheme(theme::current().text_bump_small);
+        Box::new(label) as Box<dyn View>
+    }
+
+    fn reseed(&mut self, rq: &mut RenderQueue, context: &mut Context) {
+        self.children = Self::build_children(self.rect, context);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+    }
+}
+
+/// Returns the books being read, the most recently opened first.
+fn continue_reading(books: &[Info], count: usize) -> Vec<Info> {
+    let mut reading = books
+        .iter()
+        .filter(|info| info.simple_status() == SimpleStatus::Reading)
+        .cloned()
+        .collect::<Vec<Info>>();
+    reading.sort_by_key(|info| Reverse(info.reader.as_ref().map(|r| r.opened)));
+    reading.truncate(count);
+    reading
+}
+
+/// Returns the most recently added books.
+fn recently_added(books: &[Info], count: usize) -> Vec<Info> {
+    let mut added = books.to_vec();
+    added.sort_by_key(|info| Reverse(info.added));
+    added.truncate(count);
+    added
+}
+
+/// Counts the books finished during the given year.
+fn finished_in(books: &[Info], year: i32) -> usize {
+    books
+        .iter()
+        .filter_map(|info| info.reader.as_ref())
+        .filter(|reader| reader.finished && reader.opened.year() == year)
+        .count()
+}
+
+impl View for Dashboard {
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _hub, _bus, rq, context), fields(event = ?evt), ret(level=tracing::Level::TRACE)))]
+    fn handle_event(
+        &mut self,
+        evt: &Event,
+        _hub: &Hub,
+        _bus: &mut Bus,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        match *evt {
+            Event::Reseed => {
+                self.reseed(rq, context);
+                true
+            }
+            // The books of the dashboard have no contextual menu.
+            Event::ToggleBookMenu(..) => true,
+            Event::ToggleNear(ViewId::MainMenu, rect) => {
+                toggle_main_menu(self, rect, None, rq, context);
+                true
+            }
+            Event::Close(ViewId::MainMenu) => {
+                toggle_main_menu(self, Rectangle::default(), Some(false), rq, context);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _fb, _fonts), fields(rect = ?_rect)))]
+    fn render(&self, _fb: &mut dyn Framebuffer, _rect: Rectangle, _fonts: &mut crate::font::Fonts) {
+    }
+
+    fn resize(&mut self, rect: Rectangle, _hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
+        self.rect = rect;
+        self.children = Self::build_children(rect, context);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Full));
+    }
+
+    fn rect(&self) -> &Rectangle {
+        &self.rect
+    }
+
+    fn rect_mut(&mut self) -> &mut Rectangle {
+        &mut self.rect
+    }
+
+    fn children(&self) -> &Vec<Box<dyn View>> {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<Box<dyn View>> {
+        &mut self.children
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn is_background(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::ReaderInfo;
+    use chrono::NaiveDate;
+    use std::path::PathBuf;
+
+    fn book(title: &str, day: u32, reader: Option<(u32, bool)>) -> Info {
+        let date = |day: u32| {
+            NaiveDate::from_ymd_opt(2026, 3, day)
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap()
+        };
+        Info {
+            title: title.to_string(),
+            file: crate::metadata::FileInfo {
+                path: PathBuf::from(format!("{}.epub", title)),
+                ..Default::default()
+            },
+            added: date(day),
+            reader: reader.map(|(day, finished)| ReaderInfo {
+                opened: date(day),
+                finished,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_widget_books() {
+        let books = vec![
+            book("a", 1, Some((10, false))),
+            book("b", 2, None),
+            book("c", 3, Some((12, false))),
+            book("d", 4, Some((11, true))),
+        ];
+
+        let titles = |books: Vec<Info>| books.into_iter().map(|i| i.title).collect::<Vec<_>>();
+
+        assert_eq!(titles(continue_reading(&books, 3)), ["c", "a"]);
+        assert_eq!(titles(recently_added(&books, 2)), ["d", "c"]);
+        assert_eq!(finished_in(&books, 2026), 1);
+        assert_eq!(finished_in(&books, 2025), 0);
+    }
+}
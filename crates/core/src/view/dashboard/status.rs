@@ -0,0 +1,113 @@
+use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::font::{font_from_style, Fonts, DISPLAY_STYLE, NORMAL_STYLE};
+use crate::framebuffer::{Framebuffer, UpdateMode};
+use crate::geom::Rectangle;
+use crate::theme;
+use crate::view::{Bus, Event, Hub, Id, RenderData, RenderQueue, View, ID_FEEDER};
+use chrono::{DateTime, Local};
+
+/// The time, the date and the battery level, in large print.
+pub struct Status {
+    id: Id,
+    rect: Rectangle,
+    children: Vec<Box<dyn View>>,
+    time: DateTime<Local>,
+    time_format: String,
+    date_format: String,
+    capacity: f32,
+}
+
+impl Status {
+    pub fn new(rect: Rectangle, context: &mut Context) -> Status {
+        Status {
+            id: ID_FEEDER.next(),
+            rect,
+            children: Vec::new(),
+            time: Local::now(),
+            time_format: context.settings.time_format.clone(),
+            date_format: context.settings.date_format.clone(),
+            capacity: context.battery.capacity().map_or(0.0, |v| v[0]),
+        }
+    }
+}
+
+impl View for Status {
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _hub, _bus, rq, context), fields(event = ?evt), ret(level=tracing::Level::TRACE)))]
+    fn handle_event(
+        &mut self,
+        evt: &Event,
+        _hub: &Hub,
+        _bus: &mut Bus,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        // The ticks aren't captured: the clock and the battery of the top bar need them too.
+        match *evt {
+            Event::ClockTick => {
+                self.time = Local::now();
+                rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+                false
+            }
+            Event::BatteryTick => {
+                self.capacity = context.battery.capacity().map_or(self.capacity, |v| v[0]);
+                rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+                false
+            }
+            _ => false,
+        }
+    }
+
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, fb, fonts, _rect), fields(rect = ?_rect)))]
+    fn render(&self, fb: &mut dyn Framebuffer, _rect: Rectangle, fonts: &mut Fonts) {
+        let dpi = CURRENT_DEVICE.dpi;
+        let theme = theme::current();
+
+        fb.draw_rectangle(&self.rect, theme.background);
+
+        let (padding, time_width) = {
+            let font = font_from_style(fonts, &DISPLAY_STYLE, dpi);
+            let padding = font.em() as i32 / 2;
+            let plan = font.plan(self.time.format(&self.time_format).to_string(), None, None);
+            let dy = (self.rect.height() as i32 - font.x_heights.1 as i32) / 2;
+            let pt = pt!(self.rect.min.x + padding, self.rect.max.y - dy);
+            font.render(fb, theme.foreground, &plan, pt);
+            (padding, plan.width)
+        };
+
+        let font = font_from_style(fonts, &NORMAL_STYLE, dpi);
+        let x_height = font.x_heights.0 as i32;
+        let start_x = self.rect.min.x + 2 * padding + time_width;
+        let max_width = self.rect.max.x - padding - start_x;
+
+        let date = self.time.format(&self.date_format).to_string();
+        let battery = format!("Battery {}%", self.capacity.round() as u8);
+
+        for (index, text) in [date, battery].iter().enumerate() {
+            let plan = font.plan(text, Some(max_width), None);
+            let y = self.rect.min.y + (2 * index as i32 + 1) * self.rect.height() as i32 / 4;
+            let pt = pt!(self.rect.max.x - padding - plan.width, y + x_height / 2);
+            font.render(fb, theme.foreground, &plan, pt);
+        }
+    }
+
+    fn rect(&self) -> &Rectangle {
+        &self.rect
+    }
+
+    fn rect_mut(&mut self) -> &mut Rectangle {
+        &mut self.rect
+    }
+
+    fn children(&self) -> &Vec<Box<dyn View>> {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<Box<dyn View>> {
+        &mut self.children
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+}
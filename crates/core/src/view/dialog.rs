@@ -47,18 +47,19 @@
 //!
 //! [`Event`]: super::Event
 
+use super::backdrop::Elevation;
 use super::button::Button;
 use super::label::Label;
 use super::{Align, Bus, Event, Hub, Id, RenderQueue, View, ViewId, ID_FEEDER};
 use super::{BORDER_RADIUS_MEDIUM, THICKNESS_LARGE};
-use crate::color::{BLACK, WHITE};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::{font_from_style, Fonts, NORMAL_STYLE};
 use crate::framebuffer::Framebuffer;
 use crate::geom::{BorderSpec, CornerSpec, Rectangle};
 use crate::gesture::GestureEvent;
-use crate::unit::scale_by_dpi;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
 
 /// Builder for constructing a [`Dialog`] with custom buttons and message.
 ///
@@ -345,16 +346,16 @@ impl View for Dialog {
         let dpi = CURRENT_DEVICE.dpi;
 
         let border_radius = scale_by_dpi(BORDER_RADIUS_MEDIUM, dpi) as i32;
-        let border_thickness = scale_by_dpi(THICKNESS_LARGE, dpi) as u16;
+        let border_thickness = scale_thickness(THICKNESS_LARGE, dpi) as u16;
 
         fb.draw_rounded_rectangle_with_border(
             &self.rect,
             &CornerSpec::Uniform(border_radius),
             &BorderSpec {
                 thickness: border_thickness,
-                color: BLACK,
+                color: theme::current().foreground,
             },
-            &WHITE,
+            &theme::current().background,
         );
     }
 
@@ -379,6 +380,10 @@ impl View for Dialog {
         true
     }
 
+    fn elevation(&self) -> Elevation {
+        Elevation::Modal
+    }
+
     fn rect(&self) -> &Rectangle {
         &self.rect
     }
@@ -1,10 +1,10 @@
-use crate::color::WHITE;
 use crate::context::Context;
 use crate::font::Fonts;
 use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::{CycleDir, Rectangle};
 use crate::gesture::GestureEvent;
 use crate::input::DeviceEvent;
+use crate::theme;
 use crate::view::filler::Filler;
 use crate::view::icon::Icon;
 use crate::view::label::Label;
@@ -30,7 +30,7 @@ impl BottomBar {
             let prev_icon = Icon::new("arrow-left", prev_rect, Event::Page(CycleDir::Previous));
             children.push(Box::new(prev_icon) as Box<dyn View>);
         } else {
-            let prev_filler = Filler::new(prev_rect, WHITE);
+            let prev_filler = Filler::new(prev_rect, theme::current().background);
             children.push(Box::new(prev_filler) as Box<dyn View>);
         }
 
@@ -53,7 +53,7 @@ impl BottomBar {
             );
             children.push(Box::new(next_icon) as Box<dyn View>);
         } else {
-            let next_filler = Filler::new(next_rect, WHITE);
+            let next_filler = Filler::new(next_rect, theme::current().background);
             children.push(Box::new(next_filler) as Box<dyn View>);
         }
 
@@ -74,7 +74,7 @@ impl BottomBar {
                 let prev_icon = Icon::new("arrow-left", prev_rect, Event::Page(CycleDir::Previous));
                 self.children[index] = Box::new(prev_icon) as Box<dyn View>;
             } else {
-                let prev_filler = Filler::new(prev_rect, WHITE);
+                let prev_filler = Filler::new(prev_rect, theme::current().background);
                 self.children[index] = Box::new(prev_filler) as Box<dyn View>;
             }
             self.has_prev = has_prev;
@@ -88,7 +88,7 @@ impl BottomBar {
                 let next_icon = Icon::new("arrow-right", next_rect, Event::Page(CycleDir::Next));
                 self.children[index] = Box::new(next_icon) as Box<dyn View>;
             } else {
-                let next_filler = Filler::new(next_rect, WHITE);
+                let next_filler = Filler::new(next_rect, theme::current().background);
                 self.children[index] = Box::new(next_filler) as Box<dyn View>;
             }
             self.has_next = has_next;
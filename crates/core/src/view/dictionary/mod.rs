@@ -1,9 +1,10 @@
 mod bottom_bar;
 
 use self::bottom_bar::BottomBar;
-use crate::color::BLACK;
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
+use crate::dictionary;
+use crate::dictionary::deinflect::{deinflect, is_japanese, Deinflection};
 use crate::document::html::HtmlDocument;
 use crate::document::{Document, Location};
 use crate::font::Fonts;
@@ -11,7 +12,8 @@ use crate::framebuffer::{Framebuffer, Pixmap, UpdateMode};
 use crate::geom::{halves, CycleDir, Dir, Point, Rectangle};
 use crate::gesture::GestureEvent;
 use crate::input::{ButtonCode, ButtonStatus, DeviceEvent};
-use crate::unit::scale_by_dpi;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
 use crate::view::common::{locate, locate_by_id};
 use crate::view::common::{toggle_battery_menu, toggle_clock_menu, toggle_main_menu};
 use crate::view::filler::Filler;
@@ -65,18 +67,34 @@ fn query_to_content(
             continue;
         }
 
-        if let Some(results) = dict
+        let mut deinflection = None;
+        let mut results = dict
             .lookup(query, fuzzy)
             .map_err(|e| error!("Can't search dictionary: {:#}.", e))
             .ok()
-            .filter(|r| !r.is_empty())
-        {
+            .filter(|r| !r.is_empty());
+
+        if results.is_none() && !fuzzy && (language == "ja" || is_japanese(query)) {
+            if let Some((d, r)) = lookup_deinflected(dict, query) {
+                deinflection = Some(d);
+                results = Some(r);
+            }
+        }
+
+        if let Some(results) = results {
             if target.is_none() {
                 content.push_str(&format!(
                     "<h1 class=\"dictname\">{}</h1>\n",
                     name.replace('<', "&lt;").replace('>', "&gt;")
                 ));
             }
+            if let Some(d) = deinflection.filter(|d| !d.reasons.is_empty()) {
+                content.push_str(&format!(
+                    "<p class=\"info\">{} ({})</p>\n",
+                    d.term.replace('<', "&lt;").replace('>', "&gt;"),
+                    d.reasons.join(" « ")
+                ));
+            }
             for [head, body] in results {
                 if !body.trim_start().starts_with("<h2") {
                     content.push_str(&format!(
@@ -107,6 +125,31 @@ fn query_to_content(
     content
 }
 
+// Looks up the dictionary forms of the longest inflected word the query starts with.
+fn lookup_deinflected(
+    dict: &mut dictionary::Dictionary,
+    query: &str,
+) -> Option<(Deinflection, Vec<[String; 2]>)> {
+    let ends = query
+        .char_indices()
+        .map(|(i, c)| i + c.len_utf8())
+        .collect::<Vec<usize>>();
+
+    for end in ends.into_iter().rev() {
+        for candidate in deinflect(&query[..end]) {
+            if let Some(results) = dict
+                .lookup(&candidate.term, false)
+                .ok()
+                .filter(|r| !r.is_empty())
+            {
+                return Some((candidate, results));
+            }
+        }
+    }
+
+    None
+}
+
 impl Dictionary {
     pub fn new(
         rect: Rectangle,
@@ -120,7 +163,7 @@ impl Dictionary {
         let mut children = Vec::new();
         let dpi = CURRENT_DEVICE.dpi;
         let small_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let (small_thickness, big_thickness) = halves(thickness);
 
         let top_bar = TopBar::new(
@@ -143,7 +186,7 @@ impl Dictionary {
                 rect.max.x,
                 rect.min.y + small_height + big_thickness
             ],
-            BLACK,
+            theme::current().foreground,
         );
         children.push(Box::new(separator) as Box<dyn View>);
 
@@ -168,7 +211,7 @@ impl Dictionary {
                 rect.max.x,
                 rect.min.y + 2 * small_height + big_thickness
             ],
-            BLACK,
+            theme::current().foreground,
         );
         children.push(Box::new(separator) as Box<dyn View>);
 
@@ -216,7 +259,7 @@ impl Dictionary {
                 rect.max.x,
                 rect.max.y - small_height + big_thickness
             ],
-            BLACK,
+            theme::current().foreground,
         );
         children.push(Box::new(separator) as Box<dyn View>);
 
@@ -426,7 +469,7 @@ impl Dictionary {
                 scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32,
                 scale_by_dpi(BIG_BAR_HEIGHT, dpi) as i32,
             );
-            let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+            let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
             let (small_thickness, big_thickness) = halves(thickness);
 
             let mut kb_rect = rect![
@@ -450,7 +493,7 @@ impl Dictionary {
                     self.rect.max.x,
                     kb_rect.min.y
                 ],
-                BLACK,
+                theme::current().foreground,
             );
             self.children
                 .insert(index, Box::new(separator) as Box<dyn View>);
@@ -597,7 +640,7 @@ impl Dictionary {
     fn underlying_word(&mut self, pt: Point) -> Option<String> {
         let dpi = CURRENT_DEVICE.dpi;
         let small_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let (_, big_thickness) = halves(thickness);
         let offset = pt!(
             self.rect.min.x,
@@ -619,7 +662,7 @@ impl Dictionary {
     fn follow_link(&mut self, pt: Point, rq: &mut RenderQueue, context: &mut Context) {
         let dpi = CURRENT_DEVICE.dpi;
         let small_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let (_, big_thickness) = halves(thickness);
         let offset = pt!(
             self.rect.min.x,
@@ -824,7 +867,7 @@ impl View for Dictionary {
             scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32,
             scale_by_dpi(BIG_BAR_HEIGHT, dpi) as i32,
         );
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let (small_thickness, big_thickness) = halves(thickness);
 
         self.children[0].resize(
@@ -1,20 +1,21 @@
 use super::FileEntryData;
-use crate::color::{TEXT_NORMAL, WHITE};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::{font_from_style, Fonts, NORMAL_STYLE};
 use crate::framebuffer::Framebuffer;
 use crate::geom::Rectangle;
 use crate::gesture::GestureEvent;
+use crate::theme;
 use crate::view::label::Label;
 use crate::view::{Align, Bus, EntryId, Event, Hub, Id, RenderQueue, View, ID_FEEDER};
 use chrono::{DateTime, Local};
+use std::path::Path;
 
 /// A visual entry representing a file or directory in the file browser.
 ///
 /// `FileEntry` displays file metadata in a horizontal layout with an icon, name, size, and date.
 /// It handles user interactions such as taps to select files and long presses to perform actions
-/// on directories.
+/// on the entry.
 ///
 /// # Fields
 ///
@@ -35,7 +36,7 @@ impl FileEntry {
     /// # Layout
     ///
     /// The entry displays file information in a left-to-right layout:
-    /// - **Icon** (left): Directory folder (📁) or file (📄) emoji
+    /// - **Icon** (left): Directory folder (📁) or file (📄) emoji, or a checkbox (☑ or ☐)
     /// - **Name** (center-left): File or directory name, truncated if necessary
     /// - **Size** (center-right): Formatted file size (e.g., "1.5 MB") or "-" if unavailable
     /// - **Date** (right): Last modified date in format "Mon DD, YYYY HH:MM" or "-" if unavailable
@@ -47,8 +48,14 @@ impl FileEntry {
     ///
     /// * `rect` - The bounding rectangle for the entire entry
     /// * `data` - The file entry data containing name, size, modification date, and directory flag
+    /// * `checked` - `Some` to show a checkbox instead of the file icon
     /// * `context` - Mutable reference to the application context for font access
-    pub fn new(rect: Rectangle, data: FileEntryData, context: &mut Context) -> FileEntry {
+    pub fn new(
+        rect: Rectangle,
+        data: FileEntryData,
+        checked: Option<bool>,
+        context: &mut Context,
+    ) -> FileEntry {
         let mut children: Vec<Box<dyn View>> = Vec::new();
         let dpi = CURRENT_DEVICE.dpi;
         let font = font_from_style(&mut context.fonts, &NORMAL_STYLE, dpi);
@@ -59,13 +66,14 @@ impl FileEntry {
         } else {
             Some(Event::Select(EntryId::FileEntry(data.path.clone())))
         };
-        let hold_event = if data.is_dir {
-            Some(Event::Hold(EntryId::FileEntry(data.path.clone())))
-        } else {
-            None
-        };
+        let hold_event = Some(Event::Hold(EntryId::FileEntry(data.path.clone())));
 
-        let icon = if data.is_dir { "📁" } else { "📄" };
+        let icon = match checked {
+            _ if data.is_dir => "📁",
+            Some(true) => "☑",
+            Some(false) => "☐",
+            None => "📄",
+        };
         let size_text = data
             .size
             .map(Self::format_size)
@@ -94,7 +102,11 @@ impl FileEntry {
         let icon_rect = rect![x, rect.min.y, x + icon_width, rect.max.y];
         children.push(Box::new(
             Label::new(icon_rect, icon.to_string(), Align::Left(0))
-                .scheme([WHITE, TEXT_NORMAL[1], TEXT_NORMAL[2]])
+                .scheme([
+                    theme::current().background,
+                    theme::current().text_normal[1],
+                    theme::current().text_normal[2],
+                ])
                 .event(event.clone())
                 .hold_event(hold_event.clone()),
         ));
@@ -103,7 +115,11 @@ impl FileEntry {
         let name_rect = rect![x, rect.min.y, x + name_plan.width + padding, rect.max.y];
         children.push(Box::new(
             Label::new(name_rect, data.name.clone(), Align::Left(0))
-                .scheme([WHITE, TEXT_NORMAL[1], TEXT_NORMAL[2]])
+                .scheme([
+                    theme::current().background,
+                    theme::current().text_normal[1],
+                    theme::current().text_normal[2],
+                ])
                 .event(event.clone())
                 .hold_event(hold_event.clone()),
         ));
@@ -117,7 +133,11 @@ impl FileEntry {
         ];
         children.push(Box::new(
             Label::new(size_rect, size_text, Align::Left(0))
-                .scheme([WHITE, TEXT_NORMAL[1], TEXT_NORMAL[2]])
+                .scheme([
+                    theme::current().background,
+                    theme::current().text_normal[1],
+                    theme::current().text_normal[2],
+                ])
                 .event(event.clone())
                 .hold_event(hold_event.clone()),
         ));
@@ -126,7 +146,11 @@ impl FileEntry {
         let date_rect = rect![date_x, rect.min.y, rect.max.x, rect.max.y];
         children.push(Box::new(
             Label::new(date_rect, date_text, Align::Left(0))
-                .scheme([WHITE, TEXT_NORMAL[1], TEXT_NORMAL[2]])
+                .scheme([
+                    theme::current().background,
+                    theme::current().text_normal[1],
+                    theme::current().text_normal[2],
+                ])
                 .event(event.clone())
                 .hold_event(hold_event.clone()),
         ));
@@ -139,6 +163,10 @@ impl FileEntry {
         }
     }
 
+    pub fn path(&self) -> &Path {
+        &self.data.path
+    }
+
     fn format_size(size: u64) -> String {
         const KB: u64 = 1024;
         const MB: u64 = KB * 1024;
@@ -167,8 +195,8 @@ impl View for FileEntry {
     /// This method processes user interactions with the file entry:
     /// - **Tap gesture**: If the tap is within the entry's bounds, it pushes either a
     ///   `SelectDirectory` event (for directories) or a `Select` event (for files) to the bus.
-    /// - **Hold gesture** (short): If the hold is within the entry's bounds and the entry
-    ///   represents a directory, it pushes a `Hold` event to the bus.
+    /// - **Hold gesture** (short): If the hold is within the entry's bounds, it pushes a `Hold`
+    ///   event to the bus.
     /// - **Other events**: Returns `false` and does not process other event types.
     ///
     /// # Arguments
@@ -201,7 +229,7 @@ impl View for FileEntry {
                 true
             }
             Event::Gesture(GestureEvent::HoldFingerShort(center, _id))
-                if self.rect.includes(*center) && self.data.is_dir =>
+                if self.rect.includes(*center) =>
             {
                 bus.push_back(Event::Hold(EntryId::FileEntry(self.data.path.clone())));
                 true
@@ -212,7 +240,7 @@ impl View for FileEntry {
 
     #[cfg_attr(feature = "otel", tracing::instrument(skip(self, fb, _fonts), fields(rect = ?_rect)))]
     fn render(&self, fb: &mut dyn Framebuffer, _rect: Rectangle, _fonts: &mut Fonts) {
-        fb.draw_rectangle(&self.rect, WHITE);
+        fb.draw_rectangle(&self.rect, theme::current().background);
     }
 
     fn rect(&self) -> &Rectangle {
@@ -1,28 +1,45 @@
-mod breadcrumb;
 mod file_entry;
 
-use self::breadcrumb::Breadcrumb;
 pub use self::file_entry::FileEntry;
 
-use crate::color::{BLACK, WHITE};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::Fonts;
 use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::{halves, CycleDir, Rectangle};
 use crate::gesture::GestureEvent;
-use crate::unit::scale_by_dpi;
+use crate::settings::FileSortMethod;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
+use crate::view::breadcrumb::Breadcrumb;
+use crate::view::common::{locate, locate_by_id};
+use crate::view::confirm_dialog::ConfirmDialog;
 use crate::view::filler::Filler;
 use crate::view::icon::Icon;
+use crate::view::input_field::InputField;
 use crate::view::label::Label;
+use crate::view::menu::{Menu, MenuKind};
+use crate::view::menu_entry::MenuEntry;
+use crate::view::named_input::NamedInput;
 use crate::view::page_label::PageLabel;
-use crate::view::top_bar::{TopBar, TopBarVariant};
-use crate::view::{Bus, EntryId, Event, Hub, Id, RenderData, RenderQueue, View, ViewId, ID_FEEDER};
+use crate::view::toggleable_keyboard::ToggleableKeyboard;
+use crate::view::top_bar::{TopBar, TopBarAction, TopBarVariant};
+use crate::view::{
+    Bus, EntryId, EntryKind, Event, Hub, Id, NotificationEvent, RenderData, RenderQueue, View,
+    ViewId, ID_FEEDER,
+};
 use crate::view::{SMALL_BAR_HEIGHT, THICKNESS_MEDIUM};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::OsStr;
 use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
+/// The maximum number of extension chips shown next to the filter field.
+const MAX_EXTENSION_CHIPS: usize = 4;
+
 #[derive(Debug, Clone)]
 pub struct FileEntryData {
     pub path: PathBuf,
@@ -37,6 +54,8 @@ pub enum SelectionMode {
     File,
     Directory,
     Both,
+    /// Files are checked one by one, possibly in different directories, and chosen together.
+    Multiple,
 }
 
 struct FileChooserLayout {
@@ -49,7 +68,7 @@ struct FileChooserLayout {
 
 impl FileChooserLayout {
     fn new(dpi: u16) -> Self {
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let (small_thickness, big_thickness) = halves(thickness);
         let small_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
         let big_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
@@ -99,6 +118,55 @@ impl FileChooserLayout {
             rect.min.y + 2 * self.small_height + self.big_thickness
         ]
     }
+
+    fn filter_rect(&self, rect: &Rectangle) -> Rectangle {
+        rect![
+            rect.min.x,
+            rect.min.y + 2 * self.small_height + self.big_thickness,
+            rect.max.x,
+            rect.min.y + 3 * self.small_height + self.big_thickness - self.thickness
+        ]
+    }
+
+    fn third_separator_rect(&self, rect: &Rectangle) -> Rectangle {
+        rect![
+            rect.min.x,
+            rect.min.y + 3 * self.small_height + self.big_thickness - self.thickness,
+            rect.max.x,
+            rect.min.y + 3 * self.small_height + self.big_thickness
+        ]
+    }
+
+    /// The row of the multiple selection actions, above the bottom bar.
+    fn actions_rect(&self, rect: &Rectangle) -> Rectangle {
+        rect![
+            rect.min.x,
+            rect.max.y - 2 * self.small_height,
+            rect.max.x,
+            rect.max.y - self.small_height - self.thickness
+        ]
+    }
+
+    /// Splits the filter row between the input field and the extension chips.
+    ///
+    /// There are no chips when only directories are listed.
+    fn filter_columns(&self, rect: &Rectangle, mode: SelectionMode) -> (Rectangle, Rectangle) {
+        let filter_rect = self.filter_rect(rect);
+        if mode == SelectionMode::Directory {
+            return (filter_rect, Rectangle::default());
+        }
+        let x_mid = filter_rect.min.x + filter_rect.width() as i32 / 2;
+        (
+            rect![
+                filter_rect.min,
+                pt!(x_mid - self.small_thickness, filter_rect.max.y)
+            ],
+            rect![
+                pt!(x_mid + self.big_thickness, filter_rect.min.y),
+                filter_rect.max
+            ],
+        )
+    }
 }
 
 pub struct FileChooser {
@@ -111,9 +179,19 @@ pub struct FileChooser {
     pages_count: usize,
     mode: SelectionMode,
     breadcrumb_index: usize,
+    filter_index: usize,
     entries_start_index: usize,
     error_message: Option<String>,
 
+    /// The lowercase text the entry names must contain.
+    filter: String,
+    /// The lowercase extensions the listed files must have, any if empty.
+    extensions: BTreeSet<String>,
+    /// The files checked in multiple selection mode.
+    checked_paths: BTreeSet<PathBuf>,
+    /// The entry being renamed.
+    target_path: Option<PathBuf>,
+
     /// The path that was selected by the user.
     /// This is used to determine how the file chooser should be closed.
     selected_path: Option<PathBuf>,
@@ -123,7 +201,7 @@ pub struct FileChooser {
 
 impl FileChooser {
     fn create_separator(rect: Rectangle) -> Box<dyn View> {
-        Box::new(Filler::new(rect, BLACK))
+        Box::new(Filler::new(rect, theme::current().foreground))
     }
 
     fn get_title_for_mode(mode: SelectionMode) -> &'static str {
@@ -131,6 +209,7 @@ impl FileChooser {
             SelectionMode::File => "Select File",
             SelectionMode::Directory => "Select Folder",
             SelectionMode::Both => "Select File or Folder",
+            SelectionMode::Multiple => "Select Files",
         }
     }
 
@@ -141,10 +220,10 @@ impl FileChooser {
         mode: SelectionMode,
         layout: &FileChooserLayout,
         context: &mut Context,
-    ) -> (Vec<Box<dyn View>>, usize) {
+    ) -> (Vec<Box<dyn View>>, usize, usize) {
         let mut children = Vec::new();
 
-        let background = Filler::new(rect, WHITE);
+        let background = Filler::new(rect, theme::current().background);
         children.push(Box::new(background) as Box<dyn View>);
 
         let title = Self::get_title_for_mode(mode);
@@ -153,7 +232,14 @@ impl FileChooser {
             TopBarVariant::Cancel(Event::Close(ViewId::FileChooser)),
             title.to_string(),
             context,
-        );
+        )
+        .actions(vec![
+            TopBarAction::new("plus", Event::Show(ViewId::FileChooserNewFolder)),
+            TopBarAction::new(
+                "sort",
+                Event::ToggleNear(ViewId::FileChooserSortMenu, Rectangle::default()),
+            ),
+        ]);
         children.push(Box::new(top_bar) as Box<dyn View>);
 
         children.push(Self::create_separator(layout.first_separator_rect(&rect)));
@@ -164,7 +250,24 @@ impl FileChooser {
 
         children.push(Self::create_separator(layout.second_separator_rect(&rect)));
 
-        (children, breadcrumb_index)
+        let (input_rect, chips_rect) = layout.filter_columns(&rect, mode);
+        let filter_index = children.len();
+        let input_field =
+            InputField::new(input_rect, ViewId::FileChooserFilterInput).placeholder("Filter");
+        children.push(Box::new(input_field) as Box<dyn View>);
+
+        if !chips_rect.is_empty() {
+            children.push(Self::create_separator(rect![
+                input_rect.max.x,
+                input_rect.min.y,
+                chips_rect.min.x,
+                input_rect.max.y
+            ]));
+        }
+
+        children.push(Self::create_separator(layout.third_separator_rect(&rect)));
+
+        (children, breadcrumb_index, filter_index)
     }
 
     pub fn new(
@@ -179,10 +282,14 @@ impl FileChooser {
         let dpi = CURRENT_DEVICE.dpi;
         let layout = FileChooserLayout::new(dpi);
 
-        let (children, breadcrumb_index) =
+        let (mut children, breadcrumb_index, filter_index) =
             Self::build_children(rect, &initial_path, mode, &layout, context);
         let entries_start_index = children.len();
 
+        // The keyboard and the sort menu stay above the entries.
+        let keyboard = ToggleableKeyboard::new(rect, false);
+        children.push(Box::new(keyboard) as Box<dyn View>);
+
         rq.add(RenderData::new(id, rect, UpdateMode::Gui));
 
         let mut file_chooser = FileChooser {
@@ -195,8 +302,13 @@ impl FileChooser {
             pages_count: 1,
             mode,
             breadcrumb_index,
+            filter_index,
             entries_start_index,
             error_message: None,
+            filter: String::new(),
+            extensions: BTreeSet::new(),
+            checked_paths: BTreeSet::new(),
+            target_path: None,
             selected_path: None,
             bottom_bar_rect: Rectangle::default(),
         };
@@ -256,19 +368,38 @@ impl FileChooser {
             }
         }
 
-        entries.sort_by(|a, b| match (a.is_dir, b.is_dir) {
-            (true, false) => std::cmp::Ordering::Less,
-            (false, true) => std::cmp::Ordering::Greater,
-            _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-        });
-
         Ok(entries)
     }
 
     fn navigate_to(&mut self, path: PathBuf, rq: &mut RenderQueue, context: &mut Context) {
         self.current_path = path;
+        self.load_entries(context);
+        self.current_page = 0;
+
+        // The extensions filter applies to the whole tree, the name filter to a single directory.
+        if !self.filter.is_empty() {
+            self.filter.clear();
+            if let Some(input_field) = self.children[self.filter_index].downcast_mut::<InputField>()
+            {
+                input_field.set_text("", true, rq, context);
+            }
+        }
+
+        self.update_breadcrumb(context);
+        self.update_entries_list(rq, context);
+    }
+
+    /// Lists the current directory again, after its content was changed.
+    fn reload(&mut self, rq: &mut RenderQueue, context: &mut Context) {
+        self.load_entries(context);
+        self.update_entries_list(rq, context);
+    }
+
+    fn load_entries(&mut self, context: &Context) {
         match self.list_directory(&self.current_path) {
-            Ok(entries) => {
+            Ok(mut entries) => {
+                let settings = &context.settings.file_chooser;
+                sort_entries(&mut entries, settings.sort_method, settings.reverse_order);
                 self.entries = entries;
                 self.error_message = None;
             }
@@ -277,12 +408,202 @@ impl FileChooser {
                 self.error_message = Some(err);
             }
         }
+    }
+
+    fn matches_filter(&self, entry: &FileEntryData) -> bool {
+        if !self.filter.is_empty() && !entry.name.to_lowercase().contains(&self.filter) {
+            return false;
+        }
+        entry.is_dir
+            || self.extensions.is_empty()
+            || file_extension(&entry.path).is_some_and(|ext| self.extensions.contains(&ext))
+    }
+
+    fn filtered_entries(&self) -> Vec<FileEntryData> {
+        self.entries
+            .iter()
+            .filter(|entry| self.matches_filter(entry))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the extensions offered as chips: the selected ones, then the most frequent
+    /// ones of the current directory.
+    fn extension_chips(&self) -> Vec<String> {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for ext in self
+            .entries
+            .iter()
+            .filter(|entry| !entry.is_dir)
+            .filter_map(|entry| file_extension(&entry.path))
+        {
+            *counts.entry(ext).or_default() += 1;
+        }
+
+        let mut others: Vec<(String, usize)> = counts
+            .into_iter()
+            .filter(|(ext, _)| !self.extensions.contains(ext))
+            .collect();
+        others.sort_by_key(|(_, count)| Reverse(*count));
+
+        self.extensions
+            .iter()
+            .cloned()
+            .chain(others.into_iter().map(|(ext, _)| ext))
+            .take(MAX_EXTENSION_CHIPS)
+            .collect()
+    }
+
+    fn add_extension_chips(&mut self, layout: &FileChooserLayout) {
+        let (_, chips_rect) = layout.filter_columns(&self.rect, self.mode);
+        let chips = self.extension_chips();
+        if chips_rect.is_empty() || chips.is_empty() {
+            return;
+        }
+
+        let t = theme::current();
+        let width = chips_rect.width() as i32 / MAX_EXTENSION_CHIPS as i32;
+        for (i, ext) in chips.into_iter().enumerate() {
+            let x_min = chips_rect.min.x + i as i32 * width;
+            let rect = rect![x_min, chips_rect.min.y, x_min + width, chips_rect.max.y];
+            let scheme = if self.extensions.contains(&ext) {
+                t.text_inverted_hard
+            } else {
+                t.text_normal
+            };
+            let label = Label::new(rect, format!(".{}", ext), crate::view::Align::Center)
+                .scheme(scheme)
+                .event(Some(Event::Select(EntryId::FileExtension(ext))));
+            self.children.push(Box::new(label) as Box<dyn View>);
+        }
+    }
+
+    fn toggle_extension(&mut self, ext: &str, rq: &mut RenderQueue, context: &mut Context) {
+        if !self.extensions.remove(ext) {
+            self.extensions.insert(ext.to_string());
+        }
         self.current_page = 0;
+        self.update_entries_list(rq, context);
+    }
 
-        self.update_breadcrumb(context);
+    fn set_filter(&mut self, text: &str, rq: &mut RenderQueue, context: &mut Context) {
+        self.filter = text.trim().to_lowercase();
+        self.current_page = 0;
         self.update_entries_list(rq, context);
     }
 
+    fn toggle_sort_menu(
+        &mut self,
+        rect: Rectangle,
+        enable: Option<bool>,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) {
+        if let Some(index) = locate_by_id(self, ViewId::FileChooserSortMenu) {
+            if let Some(true) = enable {
+                return;
+            }
+            rq.add(RenderData::expose(
+                *self.children[index].rect(),
+                UpdateMode::Gui,
+            ));
+            self.children.remove(index);
+        } else {
+            if let Some(false) = enable {
+                return;
+            }
+            let settings = &context.settings.file_chooser;
+            let mut entries: Vec<EntryKind> = [
+                FileSortMethod::Name,
+                FileSortMethod::Date,
+                FileSortMethod::Size,
+                FileSortMethod::Type,
+            ]
+            .into_iter()
+            .map(|method| {
+                EntryKind::RadioButton(
+                    method.label().to_string(),
+                    EntryId::FileSort(method),
+                    settings.sort_method == method,
+                )
+            })
+            .collect();
+            entries.push(EntryKind::Separator);
+            entries.push(EntryKind::CheckBox(
+                "Reverse Order".to_string(),
+                EntryId::ReverseOrder,
+                settings.reverse_order,
+            ));
+            let sort_menu = Menu::new(
+                rect,
+                ViewId::FileChooserSortMenu,
+                MenuKind::DropDown,
+                entries,
+                context,
+            );
+            rq.add(RenderData::new(
+                sort_menu.id(),
+                *sort_menu.rect(),
+                UpdateMode::Gui,
+            ));
+            self.children.push(Box::new(sort_menu) as Box<dyn View>);
+        }
+    }
+
+    fn set_sort_method(
+        &mut self,
+        sort_method: FileSortMethod,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) {
+        let reverse_order = sort_method.reverse_order();
+        context.settings.file_chooser.sort_method = sort_method;
+        context.settings.file_chooser.reverse_order = reverse_order;
+
+        if let Some(index) = locate_by_id(self, ViewId::FileChooserSortMenu) {
+            if let Some(entry) = self.children[index]
+                .children_mut()
+                .last_mut()
+                .and_then(|child| child.downcast_mut::<MenuEntry>())
+            {
+                entry.update(reverse_order, rq);
+            }
+        }
+
+        self.sort(rq, context);
+    }
+
+    fn toggle_reverse_order(&mut self, rq: &mut RenderQueue, context: &mut Context) {
+        let settings = &mut context.settings.file_chooser;
+        settings.reverse_order = !settings.reverse_order;
+        self.sort(rq, context);
+    }
+
+    fn sort(&mut self, rq: &mut RenderQueue, context: &mut Context) {
+        let settings = &context.settings.file_chooser;
+        sort_entries(
+            &mut self.entries,
+            settings.sort_method,
+            settings.reverse_order,
+        );
+        self.current_page = 0;
+        self.update_entries_list(rq, context);
+    }
+
+    fn set_keyboard_visible(
+        &mut self,
+        visible: bool,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) {
+        if let Some(keyboard) = locate::<ToggleableKeyboard>(self)
+            .and_then(|index| self.children[index].downcast_mut::<ToggleableKeyboard>())
+        {
+            keyboard.set_visible(visible, hub, rq, context);
+        }
+    }
+
     fn update_breadcrumb(&mut self, context: &mut Context) {
         let breadcrumb = self.children[self.breadcrumb_index]
             .as_mut()
@@ -311,14 +632,14 @@ impl FileChooser {
         rect![self.rect.min.x, y_min, self.rect.max.x, y_max]
     }
 
-    fn add_error_label(&mut self, breadcrumb_bottom: i32, thickness: i32, big_height: i32) {
+    fn add_error_label(&mut self, header_bottom: i32, thickness: i32, big_height: i32) {
         if let Some(error_msg) = &self.error_message {
             let label = Label::new(
                 rect![
                     self.rect.min.x,
-                    breadcrumb_bottom + thickness,
+                    header_bottom + thickness,
                     self.rect.max.x,
-                    breadcrumb_bottom + thickness + big_height * 2
+                    header_bottom + thickness + big_height * 2
                 ],
                 format!("Error: {}", error_msg),
                 crate::view::Align::Center,
@@ -327,15 +648,15 @@ impl FileChooser {
         }
     }
 
-    fn add_empty_label(&mut self, breadcrumb_bottom: i32, thickness: i32, big_height: i32) {
+    fn add_empty_label(&mut self, text: &str, header_bottom: i32, thickness: i32, big_height: i32) {
         let label = Label::new(
             rect![
                 self.rect.min.x,
-                breadcrumb_bottom + thickness,
+                header_bottom + thickness,
                 self.rect.max.x,
-                breadcrumb_bottom + thickness + big_height
+                header_bottom + thickness + big_height
             ],
-            "Empty directory".to_string(),
+            text.to_string(),
             crate::view::Align::Center,
         );
         self.children.push(Box::new(label) as Box<dyn View>);
@@ -354,9 +675,8 @@ impl FileChooser {
     /// - [`Filler`]: Used as a separator between file entries for visual clarity.
     ///
     /// # Arguments
-    /// * `start_idx` - The starting index of the entries to display.
-    /// * `end_idx` - The ending index (exclusive) of the entries to display.
-    /// * `breadcrumb_bottom` - The y-coordinate below the breadcrumb bar.
+    /// * `entries` - The entries to display.
+    /// * `header_bottom` - The y-coordinate below the filter bar.
     /// * `thickness` - The thickness of the separator lines.
     /// * `big_height` - The height of each file entry row.
     /// * `big_thickness` - The thickness of the separator between entries.
@@ -365,9 +685,8 @@ impl FileChooser {
     /// * `context`
     fn add_file_entries(
         &mut self,
-        start_idx: usize,
-        end_idx: usize,
-        breadcrumb_bottom: i32,
+        entries: &[FileEntryData],
+        header_bottom: i32,
         thickness: i32,
         big_height: i32,
         big_thickness: i32,
@@ -375,9 +694,9 @@ impl FileChooser {
         max_lines: usize,
         context: &mut Context,
     ) {
-        let mut y_pos = breadcrumb_bottom + thickness;
+        let mut y_pos = header_bottom + thickness;
 
-        for (i, entry_data) in self.entries[start_idx..end_idx].iter().enumerate() {
+        for (i, entry_data) in entries.iter().enumerate() {
             let entry_rect = self.calculate_entry_rect(
                 y_pos,
                 i,
@@ -387,7 +706,9 @@ impl FileChooser {
                 small_thickness,
             );
 
-            let file_entry = FileEntry::new(entry_rect, entry_data.clone(), context);
+            let checked = (self.mode == SelectionMode::Multiple)
+                .then(|| self.checked_paths.contains(&entry_data.path));
+            let file_entry = FileEntry::new(entry_rect, entry_data.clone(), checked, context);
             self.children.push(Box::new(file_entry) as Box<dyn View>);
 
             let y_max = entry_rect.max.y;
@@ -399,35 +720,56 @@ impl FileChooser {
     }
 
     fn update_entries_list(&mut self, rq: &mut RenderQueue, context: &mut Context) {
-        self.children.drain(self.entries_start_index..);
+        let overlays_index = locate::<ToggleableKeyboard>(self).unwrap_or(self.children.len());
+        let overlays = self.children.split_off(overlays_index);
+        self.children.truncate(self.entries_start_index);
 
         let layout = FileChooserLayout::new(CURRENT_DEVICE.dpi);
-        let breadcrumb_bottom = self.children[self.breadcrumb_index].rect().max.y;
-        let available_height =
-            self.rect.max.y - breadcrumb_bottom - layout.thickness - layout.small_height;
+        self.add_extension_chips(&layout);
+
+        let header_bottom = layout.filter_rect(&self.rect).max.y;
+        let mut available_height =
+            self.rect.max.y - header_bottom - layout.thickness - layout.small_height;
+        if self.mode == SelectionMode::Multiple {
+            available_height -= layout.small_height;
+        }
         let max_lines = (available_height / layout.big_height).max(1) as usize;
 
-        self.pages_count = (self.entries.len() as f32 / max_lines as f32).ceil() as usize;
+        let entries = self.filtered_entries();
+
+        self.pages_count = (entries.len() as f32 / max_lines as f32).ceil() as usize;
         if self.pages_count == 0 {
             self.pages_count = 1;
         }
+        self.current_page = self.current_page.min(self.pages_count - 1);
 
         let start_idx = self.current_page * max_lines;
-        let end_idx = (start_idx + max_lines).min(self.entries.len());
+        let end_idx = (start_idx + max_lines).min(entries.len());
 
         if self.error_message.is_some() {
-            self.add_error_label(breadcrumb_bottom, layout.thickness, layout.big_height);
+            self.add_error_label(header_bottom, layout.thickness, layout.big_height);
         } else if self.entries.is_empty() {
             if self.mode == SelectionMode::Directory {
                 // don't show "Empty directory" when selecting directories
             } else {
-                self.add_empty_label(breadcrumb_bottom, layout.thickness, layout.big_height);
+                self.add_empty_label(
+                    "Empty directory",
+                    header_bottom,
+                    layout.thickness,
+                    layout.big_height,
+                );
             }
+        } else if entries.is_empty() {
+            self.add_empty_label(
+                "No matching entries",
+                header_bottom,
+                layout.thickness,
+                layout.big_height,
+            );
         } else {
             self.add_file_entries(
-                start_idx,
-                end_idx,
-                breadcrumb_bottom,
+                &entries[start_idx..end_idx],
+                header_bottom,
                 layout.thickness,
                 layout.big_height,
                 layout.big_thickness,
@@ -445,15 +787,76 @@ impl FileChooser {
         ];
         self.children.push(Self::create_separator(separator_rect));
 
+        if self.mode == SelectionMode::Multiple {
+            self.add_actions(&layout, &entries);
+        }
+
         self.create_bottom_bar();
+        self.children.extend(overlays);
 
         rq.add(RenderData::new(self.id, self.rect, UpdateMode::Partial));
     }
 
+    /// Adds the row with the "select all in folder" toggle and the button closing the chooser
+    /// with the checked files.
+    fn add_actions(&mut self, layout: &FileChooserLayout, entries: &[FileEntryData]) {
+        let actions_rect = layout.actions_rect(&self.rect);
+        let separator_rect = rect![
+            self.rect.min.x,
+            actions_rect.min.y - layout.thickness,
+            self.rect.max.x,
+            actions_rect.min.y
+        ];
+        self.children.push(Self::create_separator(separator_rect));
+
+        let all_checked = Self::all_checked(&self.checked_paths, entries);
+        let x_mid = actions_rect.min.x + actions_rect.width() as i32 / 2;
+        let select_all = Label::new(
+            rect![actions_rect.min, pt!(x_mid, actions_rect.max.y)],
+            if all_checked {
+                "Deselect All".to_string()
+            } else {
+                "Select All".to_string()
+            },
+            crate::view::Align::Center,
+        )
+        .event(Some(Event::Select(EntryId::FileChooserSelectAll)));
+        self.children.push(Box::new(select_all) as Box<dyn View>);
+
+        let done = Label::new(
+            rect![pt!(x_mid, actions_rect.min.y), actions_rect.max],
+            format!("Done ({})", self.checked_paths.len()),
+            crate::view::Align::Center,
+        )
+        .event(Some(Event::Validate));
+        self.children.push(Box::new(done) as Box<dyn View>);
+    }
+
+    /// Whether all the listed files are checked, false if there are none.
+    fn all_checked(checked_paths: &BTreeSet<PathBuf>, entries: &[FileEntryData]) -> bool {
+        let mut files = entries.iter().filter(|entry| !entry.is_dir).peekable();
+        files.peek().is_some() && files.all(|entry| checked_paths.contains(&entry.path))
+    }
+
+    /// Checks all the files listed in the current directory, or unchecks them if they
+    /// already are.
+    fn toggle_all(&mut self, rq: &mut RenderQueue, context: &mut Context) {
+        let entries = self.filtered_entries();
+        let all_checked = Self::all_checked(&self.checked_paths, &entries);
+        for entry in entries.into_iter().filter(|entry| !entry.is_dir) {
+            if all_checked {
+                self.checked_paths.remove(&entry.path);
+            } else {
+                self.checked_paths.insert(entry.path);
+            }
+        }
+        self.update_entries_list(rq, context);
+    }
+
     fn create_bottom_bar(&mut self) {
         let dpi = CURRENT_DEVICE.dpi;
         let small_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let (_, big_thickness) = halves(thickness);
 
         let bottom_bar_rect = rect![
@@ -471,7 +874,7 @@ impl FileChooser {
 
         let prev_rect = rect![bottom_bar_rect.min, bottom_bar_rect.min + side];
         if is_prev_disabled {
-            let prev_filler = Filler::new(prev_rect, WHITE);
+            let prev_filler = Filler::new(prev_rect, theme::current().background);
             self.children.push(Box::new(prev_filler) as Box<dyn View>);
         } else {
             let prev_icon = Icon::new("arrow-left", prev_rect, Event::Page(CycleDir::Previous));
@@ -493,7 +896,7 @@ impl FileChooser {
 
         let next_rect = rect![bottom_bar_rect.max - side, bottom_bar_rect.max];
         if is_next_disabled {
-            let next_filler = Filler::new(next_rect, WHITE);
+            let next_filler = Filler::new(next_rect, theme::current().background);
             self.children.push(Box::new(next_filler) as Box<dyn View>);
         } else {
             let next_icon = Icon::new("arrow-right", next_rect, Event::Page(CycleDir::Next));
@@ -503,22 +906,199 @@ impl FileChooser {
 
     /// Selects the given item if it matches the selection mode.
     /// Sends FileChooserClosed event with the selected path to the bus.
-    fn select_item(&mut self, path: PathBuf, bus: &mut Bus) {
+    /// In multiple selection mode, toggles the file instead.
+    fn select_item(
+        &mut self,
+        path: PathBuf,
+        bus: &mut Bus,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) {
         let is_dir = path.is_dir();
 
-        let can_select = match self.mode {
-            SelectionMode::File => !is_dir,
-            SelectionMode::Directory => is_dir,
-            SelectionMode::Both => true,
-        };
+        if self.mode == SelectionMode::Multiple {
+            if !is_dir && !self.checked_paths.remove(&path) {
+                self.checked_paths.insert(path);
+            }
+            self.update_entries_list(rq, context);
+            return;
+        }
 
-        if can_select {
+        if self.can_select(is_dir) {
             self.selected_path = Some(path);
             bus.push_back(Event::FileChooserClosed(self.selected_path.clone()));
             bus.push_back(Event::Close(self.view_id().unwrap()));
         }
     }
 
+    fn can_select(&self, is_dir: bool) -> bool {
+        match self.mode {
+            SelectionMode::File | SelectionMode::Multiple => !is_dir,
+            SelectionMode::Directory => is_dir,
+            SelectionMode::Both => true,
+        }
+    }
+
+    /// Shows the actions on an entry below it: selecting, renaming and deleting it.
+    fn show_entry_menu(&mut self, path: &Path, rq: &mut RenderQueue, context: &mut Context) {
+        let Some(rect) = self.children[self.entries_start_index..]
+            .iter()
+            .filter_map(|child| child.downcast_ref::<FileEntry>())
+            .find(|entry| entry.path() == path)
+            .map(|entry| *entry.rect())
+        else {
+            return;
+        };
+
+        let mut entries = Vec::new();
+        if self.can_select(path.is_dir()) {
+            entries.push(EntryKind::Command(
+                "Select".to_string(),
+                EntryId::FileEntry(path.to_path_buf()),
+            ));
+        }
+        entries.push(EntryKind::Command(
+            "Rename".to_string(),
+            EntryId::Rename(path.to_path_buf()),
+        ));
+        entries.push(EntryKind::Command(
+            "Delete".to_string(),
+            EntryId::Remove(path.to_path_buf()),
+        ));
+
+        let menu = Menu::new(
+            rect,
+            ViewId::FileEntryMenu,
+            MenuKind::Contextual,
+            entries,
+            context,
+        );
+        rq.add(RenderData::new(menu.id(), *menu.rect(), UpdateMode::Gui));
+        self.children.push(Box::new(menu) as Box<dyn View>);
+    }
+
+    fn show_name_input(
+        &mut self,
+        view_id: ViewId,
+        input_id: ViewId,
+        text: &str,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) {
+        let title = if view_id == ViewId::FileChooserRename {
+            "Rename"
+        } else {
+            "New Folder"
+        };
+        let mut name_input = NamedInput::new(title.to_string(), view_id, input_id, 21, context);
+        name_input.set_text(text, rq, context);
+        rq.add(RenderData::new(
+            name_input.id(),
+            *name_input.rect(),
+            UpdateMode::Gui,
+        ));
+        self.children.push(Box::new(name_input) as Box<dyn View>);
+        hub.send(Event::Focus(Some(input_id))).ok();
+    }
+
+    fn confirm_delete(&mut self, path: &Path, rq: &mut RenderQueue, context: &mut Context) {
+        let (title, message) = if path.is_dir() {
+            ("Delete the folder?", "Its content will be deleted too.")
+        } else {
+            ("Delete the file?", "")
+        };
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let dialog = ConfirmDialog::new(
+            ViewId::ConfirmDialog,
+            title,
+            &if message.is_empty() {
+                name.into_owned()
+            } else {
+                format!("{}\n{}", name, message)
+            },
+            "Delete",
+            Event::DeleteFile(path.to_path_buf()),
+            context,
+        );
+        rq.add(RenderData::new(
+            dialog.id(),
+            *dialog.rect(),
+            UpdateMode::Gui,
+        ));
+        self.children.push(Box::new(dialog) as Box<dyn View>);
+    }
+
+    fn close_overlay(&mut self, view_id: ViewId, hub: &Hub, rq: &mut RenderQueue) -> bool {
+        let Some(index) = locate_by_id(self, view_id) else {
+            return false;
+        };
+        rq.add(RenderData::expose(
+            *self.children[index].rect(),
+            UpdateMode::Gui,
+        ));
+        self.children.remove(index);
+        if matches!(
+            view_id,
+            ViewId::FileChooserNewFolder | ViewId::FileChooserRename
+        ) {
+            hub.send(Event::Focus(None)).ok();
+        }
+        true
+    }
+
+    /// Runs an operation on the file system and lists the current directory again, or notifies
+    /// the user of its failure.
+    fn apply<F>(&mut self, operation: F, hub: &Hub, rq: &mut RenderQueue, context: &mut Context)
+    where
+        F: FnOnce(&mut FileChooser) -> io::Result<()>,
+    {
+        if let Err(err) = operation(self) {
+            hub.send(Event::Notification(NotificationEvent::Show(
+                err.to_string(),
+            )))
+            .ok();
+        }
+        self.reload(rq, context);
+    }
+
+    fn create_folder(&mut self, name: &str) -> io::Result<()> {
+        let path = self.current_path.join(checked_name(name)?);
+        fs::create_dir(path)
+    }
+
+    fn rename(&mut self, path: &Path, name: &str) -> io::Result<()> {
+        let Some(parent) = path.parent() else {
+            return Ok(());
+        };
+        let new_path = parent.join(checked_name(name)?);
+        if new_path == path {
+            return Ok(());
+        }
+        if new_path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} already exists", new_path.display()),
+            ));
+        }
+        fs::rename(path, &new_path)?;
+        if self.checked_paths.remove(path) {
+            self.checked_paths.insert(new_path);
+        }
+        Ok(())
+    }
+
+    fn delete(&mut self, path: &Path) -> io::Result<()> {
+        if path.is_dir() {
+            fs::remove_dir_all(path)?;
+        } else {
+            fs::remove_file(path)?;
+        }
+        self.checked_paths
+            .retain(|checked| !checked.starts_with(path));
+        Ok(())
+    }
+
     fn go_to_page(&mut self, dir: CycleDir, rq: &mut RenderQueue, context: &mut Context) {
         match dir {
             CycleDir::Next => {
@@ -537,26 +1117,123 @@ impl FileChooser {
 }
 
 impl View for FileChooser {
-    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _hub, bus, rq, context), fields(event = ?evt), ret(level=tracing::Level::TRACE)))]
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, hub, bus, rq, context), fields(event = ?evt), ret(level=tracing::Level::TRACE)))]
     fn handle_event(
         &mut self,
         evt: &Event,
-        _hub: &Hub,
+        hub: &Hub,
         bus: &mut Bus,
         rq: &mut RenderQueue,
         context: &mut Context,
     ) -> bool {
         match evt {
+            Event::Submit(ViewId::FileChooserFilterInput, text) => {
+                self.set_filter(text, rq, context);
+                self.set_keyboard_visible(false, hub, rq, context);
+                true
+            }
+            Event::Focus(focus) => {
+                let visible = matches!(
+                    focus,
+                    Some(
+                        ViewId::FileChooserFilterInput
+                            | ViewId::FileChooserNewFolderInput
+                            | ViewId::FileChooserRenameInput
+                    )
+                );
+                self.set_keyboard_visible(visible, hub, rq, context);
+                true
+            }
+            Event::Select(EntryId::FileExtension(ext)) => {
+                self.toggle_extension(ext, rq, context);
+                true
+            }
             Event::SelectDirectory(path) => {
                 self.navigate_to(path.clone(), rq, context);
                 true
             }
             Event::Select(EntryId::FileEntry(path)) => {
-                self.select_item(path.clone(), bus);
+                self.select_item(path.clone(), bus, rq, context);
                 true
             }
             Event::Hold(EntryId::FileEntry(path)) => {
-                self.select_item(path.clone(), bus);
+                self.show_entry_menu(path, rq, context);
+                true
+            }
+            Event::Show(ViewId::FileChooserNewFolder) => {
+                self.show_name_input(
+                    ViewId::FileChooserNewFolder,
+                    ViewId::FileChooserNewFolderInput,
+                    "",
+                    hub,
+                    rq,
+                    context,
+                );
+                true
+            }
+            Event::Select(EntryId::Rename(path)) => {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                self.show_name_input(
+                    ViewId::FileChooserRename,
+                    ViewId::FileChooserRenameInput,
+                    &name,
+                    hub,
+                    rq,
+                    context,
+                );
+                self.target_path = Some(path.clone());
+                true
+            }
+            Event::Select(EntryId::Remove(path)) => {
+                self.confirm_delete(path, rq, context);
+                true
+            }
+            Event::Submit(ViewId::FileChooserNewFolderInput, name) => {
+                self.apply(|chooser| chooser.create_folder(name), hub, rq, context);
+                true
+            }
+            Event::Submit(ViewId::FileChooserRenameInput, name) => {
+                if let Some(path) = self.target_path.take() {
+                    self.apply(|chooser| chooser.rename(&path, name), hub, rq, context);
+                }
+                true
+            }
+            Event::DeleteFile(path) => {
+                self.apply(|chooser| chooser.delete(path), hub, rq, context);
+                true
+            }
+            Event::Close(
+                view_id @ (ViewId::FileEntryMenu
+                | ViewId::FileChooserNewFolder
+                | ViewId::FileChooserRename
+                | ViewId::ConfirmDialog),
+            ) => self.close_overlay(*view_id, hub, rq),
+            Event::ToggleNear(ViewId::FileChooserSortMenu, rect) => {
+                self.toggle_sort_menu(*rect, None, rq, context);
+                true
+            }
+            Event::Close(ViewId::FileChooserSortMenu) => {
+                self.toggle_sort_menu(Rectangle::default(), Some(false), rq, context);
+                true
+            }
+            Event::Select(EntryId::FileSort(sort_method)) => {
+                self.set_sort_method(*sort_method, rq, context);
+                true
+            }
+            Event::Select(EntryId::ReverseOrder) => {
+                self.toggle_reverse_order(rq, context);
+                true
+            }
+            Event::Select(EntryId::FileChooserSelectAll) => {
+                self.toggle_all(rq, context);
+                true
+            }
+            Event::Validate if self.mode == SelectionMode::Multiple => {
+                if !self.checked_paths.is_empty() {
+                    let paths = self.checked_paths.iter().cloned().collect();
+                    bus.push_back(Event::FileChooserClosedMultiple(paths));
+                    bus.push_back(Event::Close(ViewId::FileChooser));
+                }
                 true
             }
             Event::Page(dir) => {
@@ -598,11 +1275,60 @@ impl View for FileChooser {
     }
 }
 
+/// Returns the name of a new entry, if it can name a file.
+fn checked_name(name: &str) -> io::Result<&str> {
+    let name = name.trim();
+    if name.is_empty() || name == "." || name == ".." || name.contains('/') {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Invalid name: {:?}", name),
+        ));
+    }
+    Ok(name)
+}
+
+/// Sorts the entries, the directories first.
+///
+/// The entries that compare equal are sorted by name.
+fn sort_entries(entries: &mut [FileEntryData], sort_method: FileSortMethod, reverse_order: bool) {
+    let by_name =
+        |a: &FileEntryData, b: &FileEntryData| a.name.to_lowercase().cmp(&b.name.to_lowercase());
+    entries.sort_by(|a, b| {
+        b.is_dir.cmp(&a.is_dir).then_with(|| {
+            let order = match sort_method {
+                FileSortMethod::Name => Ordering::Equal,
+                FileSortMethod::Date => a.modified.cmp(&b.modified),
+                FileSortMethod::Size => a.size.cmp(&b.size),
+                FileSortMethod::Type => file_extension(&a.path).cmp(&file_extension(&b.path)),
+            }
+            .then_with(|| by_name(a, b));
+            if reverse_order {
+                order.reverse()
+            } else {
+                order
+            }
+        })
+    });
+}
+
+fn file_extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(OsStr::to_str)
+        .map(str::to_lowercase)
+}
+
 #[cfg(test)]
 impl FileChooser {
     pub fn bottom_bar_rect(&self) -> Rectangle {
         self.bottom_bar_rect
     }
+
+    pub fn filtered_names(&self) -> Vec<String> {
+        self.filtered_entries()
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -711,6 +1437,232 @@ mod tests {
         assert!(consumed, "Page event should still be handled correctly");
     }
 
+    #[test]
+    fn test_entries_filtered_by_name_and_extension() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        for name in ["Cover.png", "back.PNG", "notes.txt", "cover.jpg"] {
+            fs::write(temp_dir.path().join(name), b"").unwrap();
+        }
+        fs::create_dir(temp_dir.path().join("covers")).unwrap();
+
+        let mut rq = RenderQueue::new();
+        let mut context = create_test_context();
+        let (hub, _receiver) = channel();
+        let mut bus = VecDeque::new();
+        let mut file_chooser = FileChooser::new(
+            rect![0, 0, 600, 800],
+            temp_dir.path().to_path_buf(),
+            SelectionMode::File,
+            &hub,
+            &mut rq,
+            &mut context,
+        );
+
+        assert_eq!(file_chooser.extension_chips(), vec!["png", "jpg", "txt"]);
+
+        let submit = Event::Submit(ViewId::FileChooserFilterInput, " COVER ".to_string());
+        file_chooser.handle_event(&submit, &hub, &mut bus, &mut rq, &mut context);
+        assert_eq!(
+            file_chooser.filtered_names(),
+            vec!["covers", "cover.jpg", "Cover.png"]
+        );
+
+        let toggle = Event::Select(EntryId::FileExtension("png".to_string()));
+        file_chooser.handle_event(&toggle, &hub, &mut bus, &mut rq, &mut context);
+        assert_eq!(file_chooser.filtered_names(), vec!["covers", "Cover.png"]);
+
+        file_chooser.navigate_to(temp_dir.path().to_path_buf(), &mut rq, &mut context);
+        assert_eq!(
+            file_chooser.filtered_names(),
+            vec!["covers", "back.PNG", "Cover.png"]
+        );
+
+        file_chooser.handle_event(&toggle, &hub, &mut bus, &mut rq, &mut context);
+        assert_eq!(file_chooser.filtered_names().len(), 5);
+    }
+
+    #[test]
+    fn test_multiple_selection_returns_checked_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let sub_dir = temp_dir.path().join("sub");
+        fs::create_dir(&sub_dir).unwrap();
+        for path in [
+            temp_dir.path().join("a.epub"),
+            temp_dir.path().join("b.epub"),
+            sub_dir.join("c.epub"),
+        ] {
+            fs::write(path, b"").unwrap();
+        }
+
+        let mut rq = RenderQueue::new();
+        let mut context = create_test_context();
+        let (hub, _receiver) = channel();
+        let mut bus = VecDeque::new();
+        let mut file_chooser = FileChooser::new(
+            rect![0, 0, 600, 800],
+            temp_dir.path().to_path_buf(),
+            SelectionMode::Multiple,
+            &hub,
+            &mut rq,
+            &mut context,
+        );
+
+        let select_all = Event::Select(EntryId::FileChooserSelectAll);
+        file_chooser.handle_event(&select_all, &hub, &mut bus, &mut rq, &mut context);
+        let uncheck = Event::Select(EntryId::FileEntry(temp_dir.path().join("b.epub")));
+        file_chooser.handle_event(&uncheck, &hub, &mut bus, &mut rq, &mut context);
+        let sub_dir_event = Event::Select(EntryId::FileEntry(sub_dir.clone()));
+        file_chooser.handle_event(&sub_dir_event, &hub, &mut bus, &mut rq, &mut context);
+        assert!(bus.is_empty());
+
+        file_chooser.navigate_to(sub_dir.clone(), &mut rq, &mut context);
+        file_chooser.handle_event(&select_all, &hub, &mut bus, &mut rq, &mut context);
+        file_chooser.handle_event(&Event::Validate, &hub, &mut bus, &mut rq, &mut context);
+
+        assert!(matches!(
+            bus.pop_front(),
+            Some(Event::FileChooserClosedMultiple(paths))
+                if paths == vec![temp_dir.path().join("a.epub"), sub_dir.join("c.epub")]
+        ));
+        assert!(matches!(
+            bus.pop_front(),
+            Some(Event::Close(ViewId::FileChooser))
+        ));
+    }
+
+    #[test]
+    fn test_sort_order_is_kept_across_file_choosers() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        for (name, size) in [("a.txt", 3), ("b.pdf", 1), ("c.epub", 2)] {
+            fs::write(temp_dir.path().join(name), vec![0; size]).unwrap();
+        }
+        fs::create_dir(temp_dir.path().join("d")).unwrap();
+
+        let mut rq = RenderQueue::new();
+        let mut context = create_test_context();
+        let (hub, _receiver) = channel();
+        let mut bus = VecDeque::new();
+        let mut open = |context: &mut Context| {
+            FileChooser::new(
+                rect![0, 0, 600, 800],
+                temp_dir.path().to_path_buf(),
+                SelectionMode::File,
+                &hub,
+                &mut rq,
+                context,
+            )
+        };
+
+        let mut file_chooser = open(&mut context);
+        assert_eq!(
+            file_chooser.filtered_names(),
+            vec!["d", "a.txt", "b.pdf", "c.epub"]
+        );
+
+        let sort = Event::Select(EntryId::FileSort(FileSortMethod::Size));
+        file_chooser.handle_event(&sort, &hub, &mut bus, &mut RenderQueue::new(), &mut context);
+        assert_eq!(
+            file_chooser.filtered_names(),
+            vec!["d", "a.txt", "c.epub", "b.pdf"]
+        );
+
+        let reverse = Event::Select(EntryId::ReverseOrder);
+        file_chooser.handle_event(
+            &reverse,
+            &hub,
+            &mut bus,
+            &mut RenderQueue::new(),
+            &mut context,
+        );
+
+        let file_chooser = open(&mut context);
+        assert_eq!(
+            file_chooser.filtered_names(),
+            vec!["d", "b.pdf", "c.epub", "a.txt"]
+        );
+
+        context.settings.file_chooser.sort_method = FileSortMethod::Type;
+        let file_chooser = open(&mut context);
+        assert_eq!(
+            file_chooser.filtered_names(),
+            vec!["d", "c.epub", "b.pdf", "a.txt"]
+        );
+    }
+
+    #[test]
+    fn test_folders_are_created_renamed_and_deleted() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("book.epub"), b"").unwrap();
+
+        let mut rq = RenderQueue::new();
+        let mut context = create_test_context();
+        let (hub, receiver) = channel();
+        let mut bus = VecDeque::new();
+        let mut file_chooser = FileChooser::new(
+            rect![0, 0, 600, 800],
+            temp_dir.path().to_path_buf(),
+            SelectionMode::Directory,
+            &hub,
+            &mut rq,
+            &mut context,
+        );
+
+        let mut send = |file_chooser: &mut FileChooser, event: Event| {
+            file_chooser.handle_event(&event, &hub, &mut bus, &mut rq, &mut context)
+        };
+
+        send(&mut file_chooser, Event::Show(ViewId::FileChooserNewFolder));
+        assert!(locate_by_id(&file_chooser, ViewId::FileChooserNewFolder).is_some());
+        send(
+            &mut file_chooser,
+            Event::Submit(ViewId::FileChooserNewFolderInput, "Comics ".to_string()),
+        );
+        send(
+            &mut file_chooser,
+            Event::Close(ViewId::FileChooserNewFolder),
+        );
+        assert!(locate_by_id(&file_chooser, ViewId::FileChooserNewFolder).is_none());
+        assert_eq!(file_chooser.filtered_names(), vec!["Comics"]);
+
+        let comics = temp_dir.path().join("Comics");
+        send(
+            &mut file_chooser,
+            Event::Hold(EntryId::FileEntry(comics.clone())),
+        );
+        assert!(locate_by_id(&file_chooser, ViewId::FileEntryMenu).is_some());
+        send(&mut file_chooser, Event::Close(ViewId::FileEntryMenu));
+
+        send(
+            &mut file_chooser,
+            Event::Select(EntryId::Rename(comics.clone())),
+        );
+        send(
+            &mut file_chooser,
+            Event::Submit(ViewId::FileChooserRenameInput, "Manga".to_string()),
+        );
+        assert_eq!(file_chooser.filtered_names(), vec!["Manga"]);
+
+        send(&mut file_chooser, Event::Show(ViewId::FileChooserNewFolder));
+        send(
+            &mut file_chooser,
+            Event::Submit(ViewId::FileChooserNewFolderInput, "../escape".to_string()),
+        );
+        assert!(matches!(
+            receiver.try_iter().last(),
+            Some(Event::Notification(NotificationEvent::Show(_)))
+        ));
+
+        let manga = temp_dir.path().join("Manga");
+        send(
+            &mut file_chooser,
+            Event::Select(EntryId::Remove(manga.clone())),
+        );
+        assert!(locate_by_id(&file_chooser, ViewId::ConfirmDialog).is_some());
+        send(&mut file_chooser, Event::DeleteFile(manga.clone()));
+        assert!(!manga.exists());
+        assert!(file_chooser.filtered_names().is_empty());
+    }
+
     #[test]
     fn test_tap_on_bottom_bar_edge_is_consumed() {
         let mut rq = RenderQueue::new();
@@ -0,0 +1,228 @@
+//! Moving between the interactive views without touching the screen.
+//!
+//! When the `button-navigation` setting is enabled, releasing a page-turn button moves the
+//! focus ring to the next or the previous focusable view, in reading order, and holding a
+//! button taps the view surrounded by the ring. The emulator maps *Tab*, *Shift+Tab* and
+//! *Return* to the same actions.
+//!
+//! The ring moves within the topmost overlay, such as a menu or a dialog, when one is open,
+//! and across the whole screen otherwise. In the reader, the buttons keep turning the pages
+//! unless an overlay is open.
+
+use super::reader::Reader;
+use super::{Event, Hub, Id, RenderData, RenderQueue, View, THICKNESS_LARGE};
+use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::framebuffer::UpdateMode;
+use crate::geom::{CycleDir, Rectangle};
+use crate::gesture::GestureEvent;
+use crate::input::{ButtonCode, ButtonStatus, DeviceEvent};
+use crate::theme;
+use crate::unit::scale_thickness;
+
+/// Turns the page-turn buttons into [`Event::MoveFocus`] and [`Event::ActivateFocus`].
+#[derive(Debug, Default)]
+pub struct ButtonNavigation {
+    // Whether the button being released was held, and has already activated a view.
+    held: bool,
+}
+
+impl ButtonNavigation {
+    /// Returns whether the event was consumed, the resulting event is sent through the hub.
+    pub fn handle_event(&mut self, evt: &Event, hub: &Hub) -> bool {
+        match *evt {
+            Event::Device(DeviceEvent::Button { code, status, .. }) => {
+                let dir = match code {
+                    ButtonCode::Forward => CycleDir::Next,
+                    ButtonCode::Backward => CycleDir::Previous,
+                    _ => return false,
+                };
+                if status == ButtonStatus::Released && !std::mem::take(&mut self.held) {
+                    hub.send(Event::MoveFocus(dir)).ok();
+                }
+                true
+            }
+            Event::Gesture(GestureEvent::HoldButtonShort(
+                ButtonCode::Forward | ButtonCode::Backward,
+            )) => {
+                self.held = true;
+                hub.send(Event::ActivateFocus).ok();
+                true
+            }
+            Event::Gesture(GestureEvent::HoldButtonLong(
+                ButtonCode::Forward | ButtonCode::Backward,
+            )) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Returns the view within which the focus ring moves: the topmost overlay of the root view,
+/// or the root view itself.
+pub fn focus_scope(view: &dyn View) -> &dyn View {
+    view.children()
+        .iter()
+        .rev()
+        .find(|child| child.is_background() && child.view_id().is_some())
+        .map_or(view, |child| child.as_ref())
+}
+
+/// Returns whether the page-turn buttons should move the focus ring of the given root view.
+pub fn navigates(view: &dyn View) -> bool {
+    !view.is::<Reader>() || focus_scope(view).id() != view.id()
+}
+
+/// Returns the identifiers and rectangles of the focusable views, in reading order.
+pub fn focusable_views(view: &dyn View) -> Vec<(Id, Rectangle)> {
+    let mut targets = Vec::new();
+    collect_focusable_views(focus_scope(view), &mut targets);
+    targets.sort_by_key(|(_, rect)| (rect.min.y, rect.min.x));
+    targets
+}
+
+fn collect_focusable_views(view: &dyn View, targets: &mut Vec<(Id, Rectangle)>) {
+    if view.is_focusable() && !view.rect().is_empty() {
+        targets.push((view.id(), *view.rect()));
+    }
+    for child in view.children() {
+        collect_focusable_views(child.as_ref(), targets);
+    }
+}
+
+fn find_rect(view: &dyn View, id: Id) -> Option<Rectangle> {
+    if view.id() == id {
+        return Some(*view.rect());
+    }
+    view.children()
+        .iter()
+        .find_map(|child| find_rect(child.as_ref(), id))
+}
+
+/// Moves the focus ring to the next or the previous focusable view, wrapping around.
+pub fn move_focus(view: &dyn View, dir: CycleDir, rq: &mut RenderQueue, context: &mut Context) {
+    let targets = focusable_views(view);
+
+    if let Some(id) = context.focus_ring.take() {
+        if let Some(rect) = find_rect(view, id) {
+            rq.add(RenderData::new(id, rect, UpdateMode::Gui));
+        }
+
+        if targets.is_empty() {
+            return;
+        }
+
+        let current = targets.iter().position(|(target, _)| *target == id);
+        let index = match (current, dir) {
+            (Some(index), CycleDir::Next) => (index + 1) % targets.len(),
+            (Some(index), CycleDir::Previous) => (index + targets.len() - 1) % targets.len(),
+            (None, CycleDir::Next) => 0,
+            (None, CycleDir::Previous) => targets.len() - 1,
+        };
+        let (id, rect) = targets[index];
+        context.focus_ring = Some(id);
+        rq.add(RenderData::new(id, rect, UpdateMode::Gui));
+    } else if let Some(&(id, rect)) = match dir {
+        CycleDir::Next => targets.first(),
+        CycleDir::Previous => targets.last(),
+    } {
+        context.focus_ring = Some(id);
+        rq.add(RenderData::new(id, rect, UpdateMode::Gui));
+    }
+}
+
+/// Taps the center of the view surrounded by the focus ring.
+pub fn activate_focus(view: &dyn View, hub: &Hub, context: &Context) {
+    let focused = context.focus_ring.and_then(|id| {
+        focusable_views(view)
+            .into_iter()
+            .find(|(target, _)| *target == id)
+    });
+    if let Some((_, rect)) = focused {
+        hub.send(Event::Gesture(GestureEvent::Tap(rect.center())))
+            .ok();
+    }
+}
+
+/// Draws the focus ring, if it intersects one of the rectangles about to be updated.
+pub fn draw_focus_ring(view: &dyn View, rects: &[Rectangle], context: &mut Context) {
+    let Some(id) = context.focus_ring else {
+        return;
+    };
+    if let Some(rect) = find_rect(focus_scope(view), id) {
+        if rects.iter().any(|r| r.overlaps(&rect)) {
+            let thickness = scale_thickness(THICKNESS_LARGE, CURRENT_DEVICE.dpi) as u16;
+            context
+                .fb
+                .draw_focus_ring(&rect, thickness, theme::current().foreground);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_helpers::create_test_context;
+    use crate::view::top_bar::{TopBar, TopBarVariant};
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_focus_moves_in_reading_order() {
+        let mut context = create_test_context();
+        let mut rq = RenderQueue::new();
+        let (hub, receiver) = channel();
+        let top_bar = TopBar::new(
+            rect![0, 0, 600, 60],
+            TopBarVariant::Back,
+            "Title".to_string(),
+            &mut context,
+        );
+
+        let targets = focusable_views(&top_bar);
+        assert!(targets.len() > 2);
+        assert!(targets.windows(2).all(|w| w[0].1.min.x < w[1].1.min.x));
+
+        move_focus(&top_bar, CycleDir::Previous, &mut rq, &mut context);
+        assert_eq!(context.focus_ring, Some(targets[targets.len() - 1].0));
+        move_focus(&top_bar, CycleDir::Next, &mut rq, &mut context);
+        assert_eq!(context.focus_ring, Some(targets[0].0));
+
+        activate_focus(&top_bar, &hub, &context);
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(Event::Gesture(GestureEvent::Tap(center))) if center == targets[0].1.center()
+        ));
+    }
+
+    #[test]
+    fn test_holding_a_button_activates_instead_of_moving() {
+        let (hub, receiver) = channel();
+        let mut navigation = ButtonNavigation::default();
+        let button = |status| {
+            Event::Device(DeviceEvent::Button {
+                time: 0.0,
+                code: ButtonCode::Forward,
+                status,
+            })
+        };
+
+        assert!(navigation.handle_event(&button(ButtonStatus::Pressed), &hub));
+        assert!(navigation.handle_event(&button(ButtonStatus::Released), &hub));
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(Event::MoveFocus(CycleDir::Next))
+        ));
+
+        let hold = Event::Gesture(GestureEvent::HoldButtonShort(ButtonCode::Forward));
+        assert!(navigation.handle_event(&hold, &hub));
+        assert!(navigation.handle_event(&button(ButtonStatus::Released), &hub));
+        assert!(matches!(receiver.try_recv(), Ok(Event::ActivateFocus)));
+        assert!(receiver.try_recv().is_err());
+
+        let light = Event::Device(DeviceEvent::Button {
+            time: 0.0,
+            code: ButtonCode::Light,
+            status: ButtonStatus::Pressed,
+        });
+        assert!(!navigation.handle_event(&light, &hub));
+    }
+}
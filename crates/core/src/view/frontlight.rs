@@ -1,3 +1,4 @@
+use super::backdrop::Elevation;
 use super::button::Button;
 use super::common::shift;
 use super::icon::Icon;
@@ -8,7 +9,6 @@ use super::{
     Align, Bus, EntryId, Event, Hub, Id, RenderData, RenderQueue, SliderId, View, ViewId, ID_FEEDER,
 };
 use super::{BORDER_RADIUS_MEDIUM, SMALL_BAR_HEIGHT, THICKNESS_LARGE};
-use crate::color::{BLACK, WHITE};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::{font_from_style, Fonts, NORMAL_STYLE};
@@ -17,15 +17,28 @@ use crate::frontlight::LightLevels;
 use crate::geom::{BorderSpec, CornerSpec, Rectangle};
 use crate::gesture::GestureEvent;
 use crate::settings::{guess_frontlight, LightPreset};
-use crate::unit::scale_by_dpi;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
 
 const LABEL_SAVE: &str = "Save";
 const LABEL_GUESS: &str = "Guess";
+const LABEL_AUTO_ON: &str = "Auto: On";
+const LABEL_AUTO_OFF: &str = "Auto: Off";
+
+fn auto_label(enabled: bool) -> &'static str {
+    if enabled {
+        LABEL_AUTO_ON
+    } else {
+        LABEL_AUTO_OFF
+    }
+}
 
 pub struct FrontlightWindow {
     id: Id,
     rect: Rectangle,
     children: Vec<Box<dyn View>>,
+    // The index of the auto-brightness button, on the devices with a light sensor.
+    auto_index: Option<usize>,
 }
 
 impl FrontlightWindow {
@@ -38,7 +51,7 @@ impl FrontlightWindow {
         let dpi = CURRENT_DEVICE.dpi;
         let (width, height) = context.display.dims;
         let small_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
-        let thickness = scale_by_dpi(THICKNESS_LARGE, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_LARGE, dpi) as i32;
         let border_radius = scale_by_dpi(BORDER_RADIUS_MEDIUM, dpi) as i32;
 
         let (x_height, padding) = {
@@ -167,7 +180,7 @@ impl FrontlightWindow {
 
         let max_label_width = {
             let font = font_from_style(fonts, &NORMAL_STYLE, dpi);
-            [LABEL_SAVE, LABEL_GUESS]
+            [LABEL_SAVE, LABEL_GUESS, LABEL_AUTO_OFF]
                 .iter()
                 .map(|t| font.plan(t, None, None).width)
                 .max()
@@ -188,6 +201,24 @@ impl FrontlightWindow {
         );
         children.push(Box::new(button_save) as Box<dyn View>);
 
+        let mut auto_index = None;
+        if CURRENT_DEVICE.has_lightsensor() {
+            let middle_x = rect.min.x + rect.width() as i32 / 2;
+            let half_width = padding + max_label_width / 2;
+            let button_auto = Button::new(
+                rect![
+                    middle_x - half_width,
+                    button_y + small_height - button_height,
+                    middle_x + half_width,
+                    button_y + small_height
+                ],
+                Event::ToggleAutoBrightness,
+                auto_label(context.settings.auto_brightness.enabled).to_string(),
+            );
+            auto_index = Some(children.len());
+            children.push(Box::new(button_auto) as Box<dyn View>);
+        }
+
         let button_guess = Button::new(
             rect![
                 rect.max.x - 5 * padding - max_label_width,
@@ -213,7 +244,21 @@ impl FrontlightWindow {
             children.push(Box::new(presets_list) as Box<dyn View>);
         }
 
-        FrontlightWindow { id, rect, children }
+        FrontlightWindow {
+            id,
+            rect,
+            children,
+            auto_index,
+        }
+    }
+
+    fn set_auto_brightness(&mut self, enabled: bool, rq: &mut RenderQueue, context: &mut Context) {
+        context.settings.auto_brightness.enabled = enabled;
+        if let Some(index) = self.auto_index {
+            if let Some(button_auto) = self.child_mut(index).downcast_mut::<Button>() {
+                button_auto.update(auto_label(enabled), rq);
+            }
+        }
     }
 
     fn toggle_presets(&mut self, enable: bool, rq: &mut RenderQueue, context: &mut Context) {
@@ -221,7 +266,7 @@ impl FrontlightWindow {
         let small_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
 
         if enable {
-            let thickness = scale_by_dpi(THICKNESS_LARGE, dpi) as i32;
+            let thickness = scale_thickness(THICKNESS_LARGE, dpi) as i32;
             let padding = {
                 let font = font_from_style(&mut context.fonts, &NORMAL_STYLE, dpi);
                 font.em() as i32
@@ -257,6 +302,7 @@ impl FrontlightWindow {
         context: &mut Context,
     ) {
         let LightLevels { intensity, warmth } = frontlight_levels;
+        self.set_auto_brightness(false, rq, context);
         context.frontlight.set_intensity(intensity);
         context.frontlight.set_warmth(warmth);
         if CURRENT_DEVICE.has_natural_light() {
@@ -291,9 +337,16 @@ impl View for FrontlightWindow {
     ) -> bool {
         match *evt {
             Event::Slider(SliderId::LightIntensity, value, _) => {
+                // Setting the intensity by hand takes over from the light sensor.
+                self.set_auto_brightness(false, rq, context);
                 context.frontlight.set_intensity(value);
                 true
             }
+            Event::ToggleAutoBrightness => {
+                let enabled = !context.settings.auto_brightness.enabled;
+                self.set_auto_brightness(enabled, rq, context);
+                true
+            }
             Event::Slider(SliderId::LightWarmth, value, _) => {
                 context.frontlight.set_warmth(value);
                 true
@@ -389,16 +442,16 @@ impl View for FrontlightWindow {
         let dpi = CURRENT_DEVICE.dpi;
 
         let border_radius = scale_by_dpi(BORDER_RADIUS_MEDIUM, dpi) as i32;
-        let border_thickness = scale_by_dpi(THICKNESS_LARGE, dpi) as u16;
+        let border_thickness = scale_thickness(THICKNESS_LARGE, dpi) as u16;
 
         fb.draw_rounded_rectangle_with_border(
             &self.rect,
             &CornerSpec::Uniform(border_radius),
             &BorderSpec {
                 thickness: border_thickness,
-                color: BLACK,
+                color: theme::current().foreground,
             },
-            &WHITE,
+            &theme::current().background,
         );
     }
 
@@ -406,7 +459,7 @@ impl View for FrontlightWindow {
         let dpi = CURRENT_DEVICE.dpi;
         let (width, height) = context.display.dims;
         let small_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
-        let thickness = scale_by_dpi(THICKNESS_LARGE, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_LARGE, dpi) as i32;
 
         let (x_height, padding) = {
             let font = font_from_style(&mut context.fonts, &NORMAL_STYLE, dpi);
@@ -510,7 +563,7 @@ impl View for FrontlightWindow {
 
         let max_label_width = {
             let font = font_from_style(&mut context.fonts, &NORMAL_STYLE, dpi);
-            [LABEL_SAVE, LABEL_GUESS]
+            [LABEL_SAVE, LABEL_GUESS, LABEL_AUTO_OFF]
                 .iter()
                 .map(|t| font.plan(t, None, None).width)
                 .max()
@@ -532,6 +585,23 @@ impl View for FrontlightWindow {
         );
         index += 1;
 
+        if self.auto_index.is_some() {
+            let middle_x = rect.min.x + rect.width() as i32 / 2;
+            let half_width = padding + max_label_width / 2;
+            self.children[index].resize(
+                rect![
+                    middle_x - half_width,
+                    button_y + small_height - button_height,
+                    middle_x + half_width,
+                    button_y + small_height
+                ],
+                hub,
+                rq,
+                context,
+            );
+            index += 1;
+        }
+
         self.children[index].resize(
             rect![
                 rect.max.x - 5 * padding - max_label_width,
@@ -560,6 +630,10 @@ impl View for FrontlightWindow {
         true
     }
 
+    fn elevation(&self) -> Elevation {
+        Elevation::Modal
+    }
+
     fn rect(&self) -> &Rectangle {
         &self.rect
     }
@@ -1,5 +1,3 @@
-use crate::color::{BLACK, READING_PROGRESS, WHITE};
-use crate::color::{TEXT_INVERTED_HARD, TEXT_NORMAL};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::document::pdf::PdfOpener;
@@ -11,7 +9,8 @@ use crate::geom::{halves, BorderSpec, CornerSpec, Rectangle};
 use crate::gesture::GestureEvent;
 use crate::metadata::{Info, Status};
 use crate::settings::{FirstColumn, SecondColumn};
-use crate::unit::scale_by_dpi;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
 use crate::view::{Bus, Event, Hub, Id, RenderData, RenderQueue, View, ID_FEEDER, THICKNESS_SMALL};
 use std::path::PathBuf;
 
@@ -102,9 +101,9 @@ impl View for Book {
         let dpi = CURRENT_DEVICE.dpi;
 
         let scheme = if self.active {
-            TEXT_INVERTED_HARD
+            theme::current().text_inverted_hard
         } else {
-            TEXT_NORMAL
+            theme::current().text_normal
         };
 
         fb.draw_rectangle(&self.rect, scheme[0]);
@@ -239,7 +238,7 @@ impl View for Book {
             }
             SecondColumn::Progress => {
                 let progress_height = scale_by_dpi(PROGRESS_HEIGHT, dpi) as i32;
-                let thickness = scale_by_dpi(THICKNESS_SMALL, dpi) as u16;
+                let thickness = scale_thickness(THICKNESS_SMALL, dpi) as u16;
                 let (small_radius, big_radius) = halves(progress_height);
                 let center = pt!(
                     self.rect.min.x + first_width + second_width / 2,
@@ -248,9 +247,9 @@ impl View for Book {
                 match self.info.status() {
                     Status::New | Status::Finished => {
                         let color = if self.info.reader.is_none() {
-                            WHITE
+                            theme::current().background
                         } else {
-                            BLACK
+                            theme::current().foreground
                         };
                         fb.draw_rounded_rectangle_with_border(
                             &rect![
@@ -260,7 +259,7 @@ impl View for Book {
                             &CornerSpec::Uniform(small_radius),
                             &BorderSpec {
                                 thickness,
-                                color: BLACK,
+                                color: theme::current().foreground,
                             },
                             &color,
                         );
@@ -278,13 +277,13 @@ impl View for Book {
                             &CornerSpec::Uniform(small_radius),
                             &BorderSpec {
                                 thickness,
-                                color: BLACK,
+                                color: theme::current().foreground,
                             },
                             &|x, _| {
                                 if x < x_offset {
-                                    READING_PROGRESS
+                                    theme::current().reading_progress
                                 } else {
-                                    WHITE
+                                    theme::current().background
                                 }
                             },
                         );
@@ -320,6 +319,10 @@ impl View for Book {
         }
     }
 
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
     fn rect(&self) -> &Rectangle {
         &self.rect
     }
@@ -1,9 +1,9 @@
 use super::library_label::LibraryLabel;
-use crate::color::WHITE;
 use crate::context::Context;
 use crate::font::Fonts;
 use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::{halves, CycleDir, Rectangle};
+use crate::theme;
 use crate::view::filler::Filler;
 use crate::view::icon::Icon;
 use crate::view::page_label::PageLabel;
@@ -35,7 +35,7 @@ impl BottomBar {
         let prev_rect = rect![rect.min, rect.min + side];
 
         if is_prev_disabled {
-            let prev_filler = Filler::new(prev_rect, WHITE);
+            let prev_filler = Filler::new(prev_rect, theme::current().background);
             children.push(Box::new(prev_filler) as Box<dyn View>);
         } else {
             let prev_icon = Icon::new("arrow-left", prev_rect, Event::Page(CycleDir::Previous));
@@ -72,7 +72,7 @@ impl BottomBar {
         let next_rect = rect![rect.max - side, rect.max];
 
         if is_next_disabled {
-            let next_filler = Filler::new(next_rect, WHITE);
+            let next_filler = Filler::new(next_rect, theme::current().background);
             children.push(Box::new(next_filler) as Box<dyn View>);
         } else {
             let next_icon = Icon::new(
@@ -126,7 +126,7 @@ impl BottomBar {
             let index = 0;
             let prev_rect = *self.child(index).rect();
             if is_prev_disabled {
-                let prev_filler = Filler::new(prev_rect, WHITE);
+                let prev_filler = Filler::new(prev_rect, theme::current().background);
                 self.children[index] = Box::new(prev_filler) as Box<dyn View>;
             } else {
                 let prev_icon = Icon::new("arrow-left", prev_rect, Event::Page(CycleDir::Previous));
@@ -142,7 +142,7 @@ impl BottomBar {
             let index = self.len() - 1;
             let next_rect = *self.child(index).rect();
             if is_next_disabled {
-                let next_filler = Filler::new(next_rect, WHITE);
+                let next_filler = Filler::new(next_rect, theme::current().background);
                 self.children[index] = Box::new(next_filler) as Box<dyn View>;
             } else {
                 let next_icon = Icon::new("arrow-right", next_rect, Event::Page(CycleDir::Next));
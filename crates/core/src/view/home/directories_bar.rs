@@ -1,12 +1,12 @@
 use super::directory::Directory;
-use crate::color::TEXT_BUMP_SMALL;
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::{font_from_style, Font, Fonts, NORMAL_STYLE};
 use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::{big_half, divide, small_half, CycleDir, Dir, Point, Rectangle};
 use crate::gesture::GestureEvent;
-use crate::unit::scale_by_dpi;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
 use crate::view::filler::Filler;
 use crate::view::icon::{Icon, ICONS_PIXMAPS};
 use crate::view::{Align, Bus, Event, Hub, Id, RenderData, RenderQueue, View, ID_FEEDER};
@@ -155,7 +155,7 @@ impl DirectoriesBar {
         fonts: &mut Fonts,
     ) {
         let dpi = CURRENT_DEVICE.dpi;
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let min_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32 - thickness;
         let mut start_index = 0;
         let mut font = font_from_style(fonts, &NORMAL_STYLE, dpi);
@@ -357,7 +357,7 @@ impl DirectoriesBar {
             max_line_width,
             max_lines,
         } = *layout;
-        let background = TEXT_BUMP_SMALL[0];
+        let background = theme::current().text_bump_small[0];
         let vertical_space = self.rect.height() as i32 - max_lines as i32 * x_height;
         let baselines = divide(vertical_space, max_lines as i32 + 1);
         let directories_count = directories.len();
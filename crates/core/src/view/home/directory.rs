@@ -1,11 +1,11 @@
-use crate::color::{BLACK, TEXT_BUMP_SMALL, WHITE};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::{font_from_style, Fonts, NORMAL_STYLE};
 use crate::framebuffer::Framebuffer;
 use crate::geom::{BorderSpec, CornerSpec, Rectangle};
 use crate::gesture::GestureEvent;
-use crate::unit::scale_by_dpi;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
 use crate::view::{Align, Bus, Event, Hub, Id, RenderQueue, View, ID_FEEDER};
 use crate::view::{BORDER_RADIUS_SMALL, THICKNESS_SMALL};
 use std::path::{Path, PathBuf};
@@ -61,13 +61,23 @@ impl View for Directory {
                 bus.push_back(Event::ToggleSelectDirectory(self.path.clone()));
                 true
             }
+            Event::Gesture(GestureEvent::HoldFingerShort(center, ..))
+                if self.rect.includes(center) =>
+            {
+                let pt = pt!(center.x, self.rect.center().y);
+                bus.push_back(Event::ToggleDirectoryMenu(
+                    Rectangle::from_point(pt),
+                    self.path.clone(),
+                ));
+                true
+            }
             _ => false,
         }
     }
     #[cfg_attr(feature = "otel", tracing::instrument(skip(self, fb, fonts, _rect), fields(rect = ?_rect)))]
     fn render(&self, fb: &mut dyn Framebuffer, _rect: Rectangle, fonts: &mut Fonts) {
         let dpi = CURRENT_DEVICE.dpi;
-        fb.draw_rectangle(&self.rect, TEXT_BUMP_SMALL[0]);
+        fb.draw_rectangle(&self.rect, theme::current().text_bump_small[0]);
         let font = font_from_style(fonts, &NORMAL_STYLE, dpi);
         let x_height = font.x_heights.0 as i32;
         let text = self.path.file_name().unwrap().to_string_lossy();
@@ -86,20 +96,24 @@ impl View for Directory {
             let pt = self.rect.min + pt!(x_offset, y_offset);
             let bg_rect = rect![pt, pt + pt!(bg_width, bg_height)];
             let border_radius = scale_by_dpi(BORDER_RADIUS_SMALL, dpi) as i32;
-            let border_thickness = scale_by_dpi(THICKNESS_SMALL, dpi) as u16;
+            let border_thickness = scale_thickness(THICKNESS_SMALL, dpi) as u16;
             fb.draw_rounded_rectangle_with_border(
                 &bg_rect,
                 &CornerSpec::Uniform(border_radius),
                 &BorderSpec {
                     thickness: border_thickness,
-                    color: BLACK,
+                    color: theme::current().foreground,
                 },
-                &WHITE,
+                &theme::current().background,
             );
         }
 
         let pt = pt!(self.rect.min.x + dx, self.rect.max.y - dy);
-        font.render(fb, TEXT_BUMP_SMALL[1], &plan, pt);
+        font.render(fb, theme::current().text_bump_small[1], &plan, pt);
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
     }
 
     fn rect(&self) -> &Rectangle {
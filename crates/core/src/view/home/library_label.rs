@@ -1,10 +1,10 @@
-use crate::color::{BLACK, WHITE};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::{font_from_style, Fonts, NORMAL_STYLE};
 use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::Rectangle;
 use crate::gesture::GestureEvent;
+use crate::theme;
 use crate::view::{Bus, Event, Hub, Id, RenderData, RenderQueue, View, ViewId, ID_FEEDER};
 
 pub struct LibraryLabel {
@@ -99,8 +99,12 @@ impl View for LibraryLabel {
         let dx = padding + (max_width - plan.width) / 2;
         let dy = (self.rect.height() as i32 - font.x_heights.0 as i32) / 2;
         let pt = pt!(self.rect.min.x + dx, self.rect.max.y - dy);
-        fb.draw_rectangle(&self.rect, WHITE);
-        font.render(fb, BLACK, &plan, pt);
+        fb.draw_rectangle(&self.rect, theme::current().background);
+        font.render(fb, theme::current().foreground, &plan, pt);
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
     }
 
     fn rect(&self) -> &Rectangle {
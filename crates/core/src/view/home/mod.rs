@@ -1,5 +1,5 @@
 mod address_bar;
-mod book;
+pub mod book;
 mod bottom_bar;
 pub mod directories_bar;
 mod directory;
@@ -8,9 +8,8 @@ mod shelf;
 
 use self::address_bar::AddressBar;
 use self::bottom_bar::BottomBar;
-use self::shelf::Shelf;
+use self::shelf::{Shelf, COVER_SCALE_STEP, MAX_COVER_SCALE, MIN_COVER_SCALE};
 use super::top_bar::{TopBar, TopBarVariant};
-use crate::color::BLACK;
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::Fonts;
@@ -21,8 +20,11 @@ use crate::input::{ButtonCode, ButtonStatus, DeviceEvent};
 use crate::library::Library;
 use crate::metadata::{sort, BookQuery, Info, Metadata, SimpleStatus, SortMethod};
 use crate::settings::{FirstColumn, Hook, LibraryMode, SecondColumn};
-use crate::unit::scale_by_dpi;
-use crate::view::common::{locate, locate_by_id, rlocate};
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
+use crate::view::breadcrumb::Breadcrumb;
+use crate::view::command_palette::ActionRegistry;
+use crate::view::common::{locate, locate_by_id, rlocate, shift};
 use crate::view::common::{toggle_battery_menu, toggle_clock_menu, toggle_main_menu};
 use crate::view::filler::Filler;
 use crate::view::keyboard::Keyboard;
@@ -33,7 +35,7 @@ use crate::view::navigation::providers::directory::DirectoryNavigationProvider;
 use crate::view::navigation::StackNavigationBar;
 use crate::view::notification::Notification;
 use crate::view::search_bar::SearchBar;
-use crate::view::{Bus, Event, Hub, RenderData, RenderQueue, View};
+use crate::view::{Bus, Event, Hub, NotificationEvent, RenderData, RenderQueue, View};
 use crate::view::{EntryId, EntryKind, Id, ViewId, ID_FEEDER};
 use crate::view::{BIG_BAR_HEIGHT, SMALL_BAR_HEIGHT, THICKNESS_MEDIUM};
 use anyhow::{format_err, Error};
@@ -88,7 +90,7 @@ impl Home {
         let dpi = CURRENT_DEVICE.dpi;
         let mut children = Vec::new();
 
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let (small_thickness, big_thickness) = halves(thickness);
         let (small_height, big_height) = (
             scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32,
@@ -129,7 +131,7 @@ impl Home {
                 rect.max.x,
                 rect.min.y + small_height + big_thickness
             ],
-            BLACK,
+            theme::current().foreground,
         );
         children.push(Box::new(separator) as Box<dyn View>);
 
@@ -151,14 +153,36 @@ impl Home {
 
             let separator = Filler::new(
                 rect![rect.min.x, y_start, rect.max.x, y_start + thickness],
-                BLACK,
+                theme::current().foreground,
             );
             children.push(Box::new(separator) as Box<dyn View>);
             y_start += thickness;
             shelf_index += 2;
         }
 
-        if context.settings.home.navigation_bar {
+        if context.settings.home.navigation_bar && context.settings.home.breadcrumb {
+            let breadcrumb = Self::new_breadcrumb(
+                rect![
+                    rect.min.x,
+                    y_start,
+                    rect.max.x,
+                    y_start + small_height - thickness
+                ],
+                &current_directory,
+                context,
+            );
+            y_start = breadcrumb.rect().max.y;
+
+            children.push(Box::new(breadcrumb) as Box<dyn View>);
+
+            let separator = Filler::new(
+                rect![rect.min.x, y_start, rect.max.x, y_start + thickness],
+                theme::current().foreground,
+            );
+            children.push(Box::new(separator) as Box<dyn View>);
+            y_start += thickness;
+            shelf_index += 2;
+        } else if context.settings.home.navigation_bar {
             let provider = DirectoryNavigationProvider;
             let mut nav_bar = StackNavigationBar::new(
                 rect![
@@ -180,7 +204,7 @@ impl Home {
 
             let separator = Filler::new(
                 rect![rect.min.x, y_start, rect.max.x, y_start + thickness],
-                BLACK,
+                theme::current().foreground,
             );
             children.push(Box::new(separator) as Box<dyn View>);
             y_start += thickness;
@@ -200,6 +224,7 @@ impl Home {
             library_settings.first_column,
             library_settings.second_column,
             library_settings.thumbnail_previews,
+            context.settings.home.cover_scale,
         );
 
         let max_lines = shelf.max_lines;
@@ -223,7 +248,7 @@ impl Home {
                 rect.max.x,
                 rect.max.y - small_height + big_thickness
             ],
-            BLACK,
+            theme::current().foreground,
         );
         children.push(Box::new(separator) as Box<dyn View>);
 
@@ -301,7 +326,14 @@ impl Home {
             index += 2;
         }
 
-        if context.settings.home.navigation_bar {
+        if let Some(breadcrumb) = self.children[index].downcast_mut::<Breadcrumb>() {
+            breadcrumb.set_path(&self.current_directory, &mut context.fonts);
+            rq.add(RenderData::new(
+                breadcrumb.id(),
+                *breadcrumb.rect(),
+                UpdateMode::Partial,
+            ));
+        } else if context.settings.home.navigation_bar {
             let nav_bar = self.children[index]
                 .as_mut()
                 .downcast_mut::<StackNavigationBar<DirectoryNavigationProvider>>()
@@ -328,9 +360,7 @@ impl Home {
         let separator_index = self.shelf_index - 1;
         let shelf_index = self.shelf_index;
 
-        let target_separator_min_y = if let Some(nav_bar_index) =
-            locate::<StackNavigationBar<DirectoryNavigationProvider>>(self)
-        {
+        let target_separator_min_y = if let Some(nav_bar_index) = self.navigation_bar_index() {
             self.children[nav_bar_index].rect().max.y
         } else {
             self.children[separator_index].rect().min.y
@@ -342,6 +372,20 @@ impl Home {
         self.children[shelf_index].rect_mut().min.y = self.children[separator_index].rect().max.y;
     }
 
+    /// Returns the index of the navigation bar, or of the breadcrumb replacing it.
+    fn navigation_bar_index(&self) -> Option<usize> {
+        locate::<StackNavigationBar<DirectoryNavigationProvider>>(self)
+            .or_else(|| locate::<Breadcrumb>(self))
+    }
+
+    fn new_breadcrumb(rect: Rectangle, path: &Path, context: &mut Context) -> Breadcrumb {
+        let selected_library = context.settings.selected_library;
+        let name = context.settings.libraries[selected_library].name.clone();
+        let mut breadcrumb = Breadcrumb::new(rect, path).root(&context.library.home, name);
+        breadcrumb.set_path(path, &mut context.fonts);
+        breadcrumb
+    }
+
     fn toggle_select_directory(
         &mut self,
         path: &Path,
@@ -504,6 +548,24 @@ impl Home {
         self.update_shelf(false, hub, rq, context);
     }
 
+    // Spreading two fingers on the shelf enlarges the covers, pinching shrinks them.
+    fn scale_covers(&mut self, dir: i8, hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
+        let scale = (context.settings.home.cover_scale + dir as f32 * COVER_SCALE_STEP)
+            .clamp(MIN_COVER_SCALE, MAX_COVER_SCALE);
+        if (scale - context.settings.home.cover_scale).abs() < f32::EPSILON {
+            return;
+        }
+
+        context.settings.home.cover_scale = scale;
+        self.children[self.shelf_index]
+            .as_mut()
+            .downcast_mut::<Shelf>()
+            .unwrap()
+            .set_cover_scale(scale);
+        self.update_shelf(true, hub, rq, context);
+        self.update_bottom_bar(rq, context);
+    }
+
     fn update_shelf(
         &mut self,
         was_resized: bool,
@@ -511,14 +573,11 @@ impl Home {
         rq: &mut RenderQueue,
         context: &mut Context,
     ) {
-        let dpi = CURRENT_DEVICE.dpi;
-        let big_height = scale_by_dpi(BIG_BAR_HEIGHT, dpi) as i32;
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
         let shelf = self.children[self.shelf_index]
             .as_mut()
             .downcast_mut::<Shelf>()
             .unwrap();
-        let max_lines = ((shelf.rect.height() as i32 + thickness) / big_height) as usize;
+        let max_lines = shelf.lines_count();
 
         if was_resized {
             let page_position = if self.visible_books.is_empty() {
@@ -596,7 +655,7 @@ impl Home {
             scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32,
             scale_by_dpi(BIG_BAR_HEIGHT, dpi) as i32,
         );
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let (small_thickness, big_thickness) = halves(thickness);
         let has_search_bar = self.children[self.shelf_index + 2].is::<SearchBar>();
 
@@ -651,7 +710,7 @@ impl Home {
                     self.rect.max.x,
                     kb_rect.min.y
                 ],
-                BLACK,
+                theme::current().foreground,
             );
             self.children
                 .insert(index, Box::new(separator) as Box<dyn View>);
@@ -715,7 +774,7 @@ impl Home {
             scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32,
             scale_by_dpi(BIG_BAR_HEIGHT, dpi) as i32,
         );
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
 
         if let Some(index) = locate::<AddressBar>(self) {
             if let Some(true) = enable {
@@ -740,10 +799,14 @@ impl Home {
 
             // Move the navigation bar up.
             if context.settings.home.navigation_bar {
-                let nav_bar = self.children[self.shelf_index - 2]
+                let index = self.shelf_index - 2;
+                if let Some(nav_bar) = self.children[index]
                     .downcast_mut::<StackNavigationBar<DirectoryNavigationProvider>>()
-                    .unwrap();
-                nav_bar.shift(pt!(0, -small_height));
+                {
+                    nav_bar.shift(pt!(0, -small_height));
+                } else {
+                    shift(self.children[index].as_mut(), pt!(0, -small_height));
+                }
             }
 
             // Move the separator above the shelf up.
@@ -762,7 +825,7 @@ impl Home {
 
             let sp_rect = *self.child(1).rect() + pt!(0, small_height);
 
-            let separator = Filler::new(sp_rect, BLACK);
+            let separator = Filler::new(sp_rect, theme::current().foreground);
             self.children
                 .insert(2, Box::new(separator) as Box<dyn View>);
 
@@ -790,17 +853,21 @@ impl Home {
             if context.settings.home.navigation_bar {
                 let rect = *self.children[self.shelf_index].rect();
                 let y_shift = rect.height() as i32 - (big_height - thickness);
-                let nav_bar = self.children[self.shelf_index - 2]
+                let index = self.shelf_index - 2;
+                if let Some(nav_bar) = self.children[index]
                     .downcast_mut::<StackNavigationBar<DirectoryNavigationProvider>>()
-                    .unwrap();
-                // Move the navigation bar down.
-                nav_bar.shift(pt!(0, small_height));
-
-                // Shrink the nav bar.
-                if y_shift < 0 {
-                    let y_shift = nav_bar.shrink(y_shift, &mut context.fonts);
-                    self.children[self.shelf_index].rect_mut().min.y += y_shift;
-                    *self.children[self.shelf_index - 1].rect_mut() += pt!(0, y_shift);
+                {
+                    // Move the navigation bar down.
+                    nav_bar.shift(pt!(0, small_height));
+
+                    // Shrink the nav bar.
+                    if y_shift < 0 {
+                        let y_shift = nav_bar.shrink(y_shift, &mut context.fonts);
+                        self.children[self.shelf_index].rect_mut().min.y += y_shift;
+                        *self.children[self.shelf_index - 1].rect_mut() += pt!(0, y_shift);
+                    }
+                } else {
+                    shift(self.children[index].as_mut(), pt!(0, small_height));
                 }
 
                 self.adjust_shelf_top_edge();
@@ -834,10 +901,10 @@ impl Home {
             scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32,
             scale_by_dpi(BIG_BAR_HEIGHT, dpi) as i32,
         );
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let (small_thickness, _) = halves(thickness);
 
-        if let Some(index) = locate::<StackNavigationBar<DirectoryNavigationProvider>>(self) {
+        if let Some(index) = self.navigation_bar_index() {
             if let Some(true) = enable {
                 return;
             }
@@ -865,27 +932,36 @@ impl Home {
             };
             let sp_rect = *self.child(sep_index).rect() + pt!(0, small_height);
 
-            let separator = Filler::new(sp_rect, BLACK);
+            let separator = Filler::new(sp_rect, theme::current().foreground);
             self.children
                 .insert(sep_index + 1, Box::new(separator) as Box<dyn View>);
 
-            let provider = DirectoryNavigationProvider;
-            let mut nav_bar = StackNavigationBar::new(
-                rect![
-                    self.rect.min.x,
-                    sp_rect.min.y - small_height + thickness,
-                    self.rect.max.x,
-                    sp_rect.min.y
-                ],
-                self.rect.max.y - small_height - big_height - small_thickness,
-                context.settings.home.max_levels,
-                provider,
-                self.current_directory.clone(),
-            );
+            let nav_bar_rect = rect![
+                self.rect.min.x,
+                sp_rect.min.y - small_height + thickness,
+                self.rect.max.x,
+                sp_rect.min.y
+            ];
 
-            nav_bar.set_selected(self.current_directory.clone(), rq, context);
-            self.children
-                .insert(sep_index + 1, Box::new(nav_bar) as Box<dyn View>);
+            if context.settings.home.breadcrumb {
+                let breadcrumb =
+                    Self::new_breadcrumb(nav_bar_rect, &self.current_directory, context);
+                self.children
+                    .insert(sep_index + 1, Box::new(breadcrumb) as Box<dyn View>);
+            } else {
+                let provider = DirectoryNavigationProvider;
+                let mut nav_bar = StackNavigationBar::new(
+                    nav_bar_rect,
+                    self.rect.max.y - small_height - big_height - small_thickness,
+                    context.settings.home.max_levels,
+                    provider,
+                    self.current_directory.clone(),
+                );
+
+                nav_bar.set_selected(self.current_directory.clone(), rq, context);
+                self.children
+                    .insert(sep_index + 1, Box::new(nav_bar) as Box<dyn View>);
+            }
 
             self.shelf_index += 2;
             context.settings.home.navigation_bar = true;
@@ -920,7 +996,7 @@ impl Home {
             scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32,
             scale_by_dpi(BIG_BAR_HEIGHT, dpi) as i32,
         );
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let delta_y = small_height;
         let search_visible: bool;
         let mut has_keyboard = false;
@@ -947,10 +1023,9 @@ impl Home {
             // Move the shelf's bottom edge.
             self.children[self.shelf_index].rect_mut().max.y += delta_y;
 
-            if context.settings.home.navigation_bar {
-                let nav_bar = self.children[self.shelf_index - 2]
-                    .downcast_mut::<StackNavigationBar<DirectoryNavigationProvider>>()
-                    .unwrap();
+            if let Some(nav_bar) = self.children[self.shelf_index - 2]
+                .downcast_mut::<StackNavigationBar<DirectoryNavigationProvider>>()
+            {
                 nav_bar.vertical_limit += delta_y;
             }
 
@@ -977,19 +1052,18 @@ impl Home {
             self.children
                 .insert(self.shelf_index + 1, Box::new(search_bar) as Box<dyn View>);
 
-            let separator = Filler::new(sp_rect, BLACK);
+            let separator = Filler::new(sp_rect, theme::current().foreground);
             self.children
                 .insert(self.shelf_index + 1, Box::new(separator) as Box<dyn View>);
 
             // Move the shelf's bottom edge.
             self.children[self.shelf_index].rect_mut().max.y -= delta_y;
 
-            if context.settings.home.navigation_bar {
-                let rect = *self.children[self.shelf_index].rect();
+            let rect = *self.children[self.shelf_index].rect();
+            if let Some(nav_bar) = self.children[self.shelf_index - 2]
+                .downcast_mut::<StackNavigationBar<DirectoryNavigationProvider>>()
+            {
                 let y_shift = rect.height() as i32 - (big_height - thickness);
-                let nav_bar = self.children[self.shelf_index - 2]
-                    .downcast_mut::<StackNavigationBar<DirectoryNavigationProvider>>()
-                    .unwrap();
                 nav_bar.vertical_limit -= delta_y;
 
                 // Shrink the nav bar.
@@ -1272,6 +1346,104 @@ impl Home {
         (index_lower + index).min(self.visible_books.len())
     }
 
+    fn toggle_directory_menu(
+        &mut self,
+        path: &Path,
+        rect: Rectangle,
+        enable: Option<bool>,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) {
+        if let Some(index) = locate_by_id(self, ViewId::DirectoryMenu) {
+            if let Some(true) = enable {
+                return;
+            }
+            rq.add(RenderData::expose(
+                *self.child(index).rect(),
+                UpdateMode::Gui,
+            ));
+            self.children.remove(index);
+        } else {
+            if let Some(false) = enable {
+                return;
+            }
+
+            let Ok(relative_path) = path.strip_prefix(&context.library.home) else {
+                return;
+            };
+
+            let selected_library = context.settings.selected_library;
+            let pinned = context.settings.libraries[selected_library]
+                .pinned_directories
+                .iter()
+                .any(|dir| dir == relative_path);
+
+            let entry = if pinned {
+                EntryKind::Command(
+                    "Unpin".to_string(),
+                    EntryId::UnpinDirectory(relative_path.to_path_buf()),
+                )
+            } else {
+                EntryKind::Command(
+                    "Pin".to_string(),
+                    EntryId::PinDirectory(relative_path.to_path_buf()),
+                )
+            };
+
+            let directory_menu = Menu::new(
+                rect,
+                ViewId::DirectoryMenu,
+                MenuKind::Contextual,
+                vec![entry],
+                context,
+            );
+            rq.add(RenderData::new(
+                directory_menu.id(),
+                *directory_menu.rect(),
+                UpdateMode::Gui,
+            ));
+            self.children
+                .push(Box::new(directory_menu) as Box<dyn View>);
+        }
+    }
+
+    /// Adds or removes a pinned directory of the current library, and rebuilds the
+    /// navigation bar to show the change.
+    fn set_pinned_directory(
+        &mut self,
+        path: &Path,
+        pinned: bool,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) {
+        let selected_library = context.settings.selected_library;
+        let pinned_directories =
+            &mut context.settings.libraries[selected_library].pinned_directories;
+        pinned_directories.retain(|dir| dir != path);
+        if pinned {
+            pinned_directories.push(path.to_path_buf());
+        }
+
+        if let Some(index) = locate::<StackNavigationBar<DirectoryNavigationProvider>>(self) {
+            let nav_bar = self.children[index]
+                .as_mut()
+                .downcast_mut::<StackNavigationBar<DirectoryNavigationProvider>>()
+                .unwrap();
+            nav_bar.set_selected(self.current_directory.clone(), rq, context);
+            self.adjust_shelf_top_edge();
+            for i in index..=index + 1 {
+                rq.add(RenderData::new(
+                    self.child(i).id(),
+                    *self.child(i).rect(),
+                    UpdateMode::Gui,
+                ));
+            }
+            self.update_shelf(true, hub, rq, context);
+            self.update_bottom_bar(rq, context);
+        }
+    }
+
     fn toggle_book_menu(
         &mut self,
         index: usize,
@@ -1301,17 +1473,23 @@ impl Home {
             let mut entries = Vec::new();
 
             if let Some(parent) = path.parent() {
-                entries.push(EntryKind::Command(
-                    "Select Parent".to_string(),
-                    EntryId::SelectDirectory(context.library.home.join(parent)),
-                ));
+                entries.push(
+                    EntryKind::Command(
+                        "Select Parent".to_string(),
+                        EntryId::SelectDirectory(context.library.home.join(parent)),
+                    )
+                    .with_icon("arrow-up"),
+                );
             }
 
             if !info.author.is_empty() {
-                entries.push(EntryKind::Command(
-                    "Search Author".to_string(),
-                    EntryId::SearchAuthor(info.author.clone()),
-                ));
+                entries.push(
+                    EntryKind::Command(
+                        "Search Author".to_string(),
+                        EntryId::SearchAuthor(info.author.clone()),
+                    )
+                    .with_icon("search"),
+                );
             }
 
             if !entries.is_empty() {
@@ -1328,7 +1506,8 @@ impl Home {
                 .iter()
                 .map(|s| EntryKind::Command(s.to_string(), EntryId::SetStatus(path.clone(), *s)))
                 .collect();
-            entries.push(EntryKind::SubMenu("Mark As".to_string(), submenu));
+            entries
+                .push(EntryKind::SubMenu("Mark As".to_string(), submenu).with_icon("check_mark"));
             entries.push(EntryKind::Separator);
 
             let selected_library = context.settings.selected_library;
@@ -1361,10 +1540,10 @@ impl Home {
                 "Rename".to_string(),
                 EntryId::Rename(path.clone()),
             ));
-            entries.push(EntryKind::Command(
-                "Remove".to_string(),
-                EntryId::Remove(path.clone()),
-            ));
+            entries.push(
+                EntryKind::Command("Remove".to_string(), EntryId::Remove(path.clone()))
+                    .with_icon("close"),
+            );
 
             let book_menu = Menu::new(
                 rect,
@@ -1918,7 +2097,10 @@ impl Home {
                             Some("notify") => {
                                 if let Some(msg) = event.get("message").and_then(JsonValue::as_str)
                                 {
-                                    hub2.send(Event::Notify(msg.to_string())).ok();
+                                    hub2.send(Event::Notification(NotificationEvent::Show(
+                                        msg.to_string(),
+                                    )))
+                                    .ok();
                                 }
                             }
                             Some("setWifi") => {
@@ -2031,6 +2213,18 @@ impl View for Home {
                 }
                 true
             }
+            Event::Gesture(GestureEvent::Spread { center, .. })
+                if self.children[self.shelf_index].rect().includes(center) =>
+            {
+                self.scale_covers(1, hub, rq, context);
+                true
+            }
+            Event::Gesture(GestureEvent::Pinch { center, .. })
+                if self.children[self.shelf_index].rect().includes(center) =>
+            {
+                self.scale_covers(-1, hub, rq, context);
+                true
+            }
             Event::Gesture(GestureEvent::Rotate { quarter_turns, .. }) if quarter_turns != 0 => {
                 let (_, dir) = CURRENT_DEVICE.mirroring_scheme();
                 let n = (4 + (context.display.rotation - dir * quarter_turns)) % 4;
@@ -2092,6 +2286,10 @@ impl View for Home {
                 self.toggle_book_menu(index, rect, None, rq, context);
                 true
             }
+            Event::ToggleDirectoryMenu(rect, ref path) => {
+                self.toggle_directory_menu(path, rect, None, rq, context);
+                true
+            }
             Event::ToggleNear(ViewId::MainMenu, rect) => {
                 toggle_main_menu(self, rect, None, rq, context);
                 true
@@ -2298,6 +2496,14 @@ impl View for Home {
                 self.toggle_select_directory(path, hub, rq, context);
                 true
             }
+            Event::Select(EntryId::PinDirectory(ref path)) => {
+                self.set_pinned_directory(path, true, hub, rq, context);
+                true
+            }
+            Event::Select(EntryId::UnpinDirectory(ref path)) => {
+                self.set_pinned_directory(path, false, hub, rq, context);
+                true
+            }
             Event::Select(EntryId::SearchAuthor(ref author)) => {
                 let text = format!("'a {}", author);
                 let query = BookQuery::new(&text);
@@ -2430,7 +2636,7 @@ impl View for Home {
 
     fn resize(&mut self, rect: Rectangle, hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
         let dpi = CURRENT_DEVICE.dpi;
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let (small_thickness, big_thickness) = halves(thickness);
         let (small_height, big_height) = (
             scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32,
@@ -2492,30 +2698,29 @@ impl View for Home {
             } else {
                 1
             };
-            let nav_bar = self.children[index]
+            let nav_bar_rect = rect![
+                rect.min.x,
+                shelf_min_y,
+                rect.max.x,
+                shelf_min_y + small_height - thickness
+            ];
+            if let Some(nav_bar) = self.children[index]
                 .as_mut()
                 .downcast_mut::<StackNavigationBar<DirectoryNavigationProvider>>()
-                .unwrap();
-            nav_bar.clear();
-            nav_bar.resize(
-                rect![
-                    rect.min.x,
-                    shelf_min_y,
-                    rect.max.x,
-                    shelf_min_y + small_height - thickness
-                ],
-                hub,
-                rq,
-                context,
-            );
-            nav_bar.vertical_limit =
-                rect.max.y - count * small_height - big_height - small_thickness;
-            nav_bar.set_selected(
-                self.current_directory.clone(),
-                &mut RenderQueue::new(),
-                context,
-            );
-            shelf_min_y += nav_bar.rect().height() as i32;
+            {
+                nav_bar.clear();
+                nav_bar.resize(nav_bar_rect, hub, rq, context);
+                nav_bar.vertical_limit =
+                    rect.max.y - count * small_height - big_height - small_thickness;
+                nav_bar.set_selected(
+                    self.current_directory.clone(),
+                    &mut RenderQueue::new(),
+                    context,
+                );
+            } else {
+                self.children[index].resize(nav_bar_rect, hub, rq, context);
+            }
+            shelf_min_y += self.children[index].rect().height() as i32;
             index += 1;
 
             self.children[index].resize(
@@ -2623,6 +2828,17 @@ impl View for Home {
     fn id(&self) -> Id {
         self.id
     }
+
+    fn contribute_actions(&self, registry: &mut ActionRegistry, context: &Context) {
+        let selected_library = context.settings.selected_library;
+        match context.settings.libraries[selected_library].mode {
+            LibraryMode::Database => registry.register("Import", Event::Select(EntryId::Import)),
+            LibraryMode::Filesystem => {
+                registry.register("Clean Up", Event::Select(EntryId::CleanUp))
+            }
+        }
+        registry.register("Flush", Event::Select(EntryId::Flush));
+    }
 }
 
 #[cfg(test)]
@@ -2720,4 +2936,31 @@ mod tests {
             "Navigation bar and separator should remain aligned after disabling address bar"
         );
     }
+
+    #[test]
+    fn test_breadcrumb_replaces_navigation_bar() {
+        let mut context = create_test_context();
+        let (hub, _rx) = std::sync::mpsc::channel();
+        let mut rq = RenderQueue::new();
+
+        context.settings.home.navigation_bar = false;
+        context.settings.home.address_bar = false;
+        context.settings.home.breadcrumb = true;
+
+        let rect = rect![0, 0, 600, 800];
+        let mut home = Home::new(rect, &hub, &mut rq, &mut context).unwrap();
+
+        home.toggle_navigation_bar(Some(true), false, &hub, &mut rq, &mut context);
+        assert!(locate::<StackNavigationBar<DirectoryNavigationProvider>>(&home).is_none());
+
+        let index = locate::<Breadcrumb>(&home).unwrap();
+        assert_eq!(home.navigation_bar_index(), Some(index));
+        assert_eq!(
+            home.children[index].rect().max.y,
+            home.children[home.shelf_index - 1].rect().min.y
+        );
+
+        home.toggle_navigation_bar(Some(false), false, &hub, &mut rq, &mut context);
+        assert!(locate::<Breadcrumb>(&home).is_none());
+    }
 }
@@ -1,5 +1,4 @@
 use super::book::Book;
-use crate::color::{SEPARATOR_NORMAL, WHITE};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::document::open;
@@ -10,7 +9,8 @@ use crate::geom::{halves, CycleDir, Dir, Rectangle};
 use crate::gesture::GestureEvent;
 use crate::metadata::Info;
 use crate::settings::{FirstColumn, SecondColumn};
-use crate::unit::scale_by_dpi;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
 use crate::view::filler::Filler;
 use crate::view::{Bus, Event, Hub, Id, RenderData, RenderQueue, View, ID_FEEDER};
 use crate::view::{BIG_BAR_HEIGHT, THICKNESS_MEDIUM};
@@ -23,6 +23,11 @@ lazy_static! {
     static ref EXCLUSIVE_ACCESS: Mutex<u8> = Mutex::new(0);
 }
 
+pub const MIN_COVER_SCALE: f32 = 1.0;
+pub const MAX_COVER_SCALE: f32 = 3.0;
+// Change of the cover scale for each pinch or spread.
+pub const COVER_SCALE_STEP: f32 = 0.5;
+
 pub struct Shelf {
     id: Id,
     pub rect: Rectangle,
@@ -31,6 +36,7 @@ pub struct Shelf {
     first_column: FirstColumn,
     second_column: SecondColumn,
     thumbnail_previews: bool,
+    cover_scale: f32,
 }
 
 impl Shelf {
@@ -39,20 +45,37 @@ impl Shelf {
         first_column: FirstColumn,
         second_column: SecondColumn,
         thumbnail_previews: bool,
+        cover_scale: f32,
     ) -> Shelf {
-        let dpi = CURRENT_DEVICE.dpi;
-        let big_height = scale_by_dpi(BIG_BAR_HEIGHT, dpi) as i32;
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
-        let max_lines = ((rect.height() as i32 + thickness) / big_height) as usize;
-        Shelf {
+        let mut shelf = Shelf {
             id: ID_FEEDER.next(),
             rect,
             children: Vec::new(),
-            max_lines,
+            max_lines: 0,
             first_column,
             second_column,
             thumbnail_previews,
-        }
+            cover_scale: cover_scale.clamp(MIN_COVER_SCALE, MAX_COVER_SCALE),
+        };
+        shelf.max_lines = shelf.lines_count();
+        shelf
+    }
+
+    /// Returns the height of a row, before dividing the shelf among its rows.
+    pub fn line_height(&self) -> i32 {
+        let dpi = CURRENT_DEVICE.dpi;
+        (self.cover_scale * scale_by_dpi(BIG_BAR_HEIGHT, dpi)) as i32
+    }
+
+    /// Returns the number of rows that fit in the shelf.
+    pub fn lines_count(&self) -> usize {
+        let dpi = CURRENT_DEVICE.dpi;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
+        (((self.rect.height() as i32 + thickness) / self.line_height()) as usize).max(1)
+    }
+
+    pub fn set_cover_scale(&mut self, cover_scale: f32) {
+        self.cover_scale = cover_scale.clamp(MIN_COVER_SCALE, MAX_COVER_SCALE);
     }
 
     pub fn set_first_column(&mut self, first_column: FirstColumn) {
@@ -76,13 +99,12 @@ impl Shelf {
     ) {
         self.children.clear();
         let dpi = CURRENT_DEVICE.dpi;
-        let big_height = scale_by_dpi(BIG_BAR_HEIGHT, dpi) as i32;
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let (small_thickness, big_thickness) = halves(thickness);
-        let max_lines = ((self.rect.height() as i32 + thickness) / big_height) as usize;
+        let max_lines = self.lines_count();
         let book_heights = divide(self.rect.height() as i32, max_lines as i32);
         let mut y_pos = self.rect.min.y;
-        let th = big_height;
+        let th = self.line_height();
         let tw = 3 * th / 4;
 
         for (index, info) in metadata.iter().enumerate() {
@@ -101,6 +123,7 @@ impl Shelf {
                     let thumb_path2 = thumb_path.to_string_lossy().into_owned();
                     let path = info.file.path.clone();
                     let full_path = context.library.home.join(&info.file.path);
+                    let dithering = context.settings.display.cover_dithering;
                     thread::spawn(move || {
                         // This is a hack to circumvent a segfault (EXC_BAD_ACCESS)
                         // triggered by loading multiple jp2 pixmaps in parallel.
@@ -113,7 +136,8 @@ impl Shelf {
                                     CURRENT_DEVICE.color_samples(),
                                 )
                             })
-                            .map(|pixmap| {
+                            .map(|mut pixmap| {
+                                pixmap.dither(dithering);
                                 if pixmap.save(&thumb_path2).is_ok() {
                                     hub2.send(Event::RefreshBookPreview(
                                         path,
@@ -144,7 +168,7 @@ impl Shelf {
             if index < max_lines - 1 {
                 let separator = Filler::new(
                     rect![self.rect.min.x, y_max, self.rect.max.x, y_max + thickness],
-                    SEPARATOR_NORMAL,
+                    theme::current().separator_normal,
                 );
                 self.children.push(Box::new(separator) as Box<dyn View>);
             }
@@ -156,7 +180,7 @@ impl Shelf {
             let y_start = y_pos + if metadata.is_empty() { 0 } else { thickness };
             let filler = Filler::new(
                 rect![self.rect.min.x, y_start, self.rect.max.x, self.rect.max.y],
-                WHITE,
+                theme::current().background,
             );
             self.children.push(Box::new(filler) as Box<dyn View>);
         }
@@ -217,3 +241,29 @@ impl View for Shelf {
         self.id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cover_scale_reduces_lines() {
+        let dpi = CURRENT_DEVICE.dpi;
+        let big_height = scale_by_dpi(BIG_BAR_HEIGHT, dpi) as i32;
+        let rect = rect![0, 0, 600, 6 * big_height];
+        let shelf = |scale| {
+            Shelf::new(
+                rect,
+                FirstColumn::TitleAndAuthor,
+                SecondColumn::Progress,
+                true,
+                scale,
+            )
+        };
+
+        assert_eq!(shelf(1.0).max_lines, 6);
+        assert_eq!(shelf(2.0).max_lines, 3);
+        assert_eq!(shelf(MAX_COVER_SCALE + 1.0).max_lines, 2);
+        assert_eq!(shelf(0.5).max_lines, 6);
+    }
+}
@@ -1,84 +1,59 @@
 use super::{Align, Bus, Event, Hub, Id, RenderData, RenderQueue, View, ViewId, ID_FEEDER};
-use crate::color::{Color, TEXT_INVERTED_HARD, TEXT_NORMAL};
+use crate::color::Color;
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
-use crate::document::pdf::PdfOpener;
 use crate::font::Fonts;
 use crate::framebuffer::{Framebuffer, Pixmap, UpdateMode};
 use crate::geom::{CornerSpec, Rectangle};
 use crate::gesture::GestureEvent;
 use crate::input::{DeviceEvent, FingerStatus};
+use crate::svg::Svg;
+use crate::theme;
 use crate::unit::scale_by_dpi_raw;
 use fxhash::FxHashMap;
 use lazy_static::lazy_static;
-use std::path::Path;
+use std::fs;
+use std::path::PathBuf;
+use tracing::error;
 
 const ICON_SCALE: f32 = 1.0 / 32.0;
 
 lazy_static! {
-    pub static ref ICONS_PIXMAPS: FxHashMap<&'static str, Pixmap> = {
+    /// The icons of the `icons` directory, by file stem, rendered for the screen's density.
+    pub static ref ICONS_PIXMAPS: FxHashMap<String, Pixmap> = {
         let mut m = FxHashMap::default();
         let scale = scale_by_dpi_raw(ICON_SCALE, CURRENT_DEVICE.dpi);
-        let dir = Path::new("icons");
-        for name in [
-            "home",
-            "search",
-            "back",
-            "frontlight",
-            "frontlight-disabled",
-            "menu",
-            "angle-left",
-            "angle-right",
-            "angle-left-small",
-            "angle-right-small",
-            "return",
-            "shift",
-            "combine",
-            "alternate",
-            "delete-backward",
-            "delete-forward",
-            "move-backward",
-            "move-backward-short",
-            "move-forward",
-            "move-forward-short",
-            "close",
-            "check_mark-small",
-            "check_mark",
-            "check_mark-large",
-            "bullet",
-            "arrow-left",
-            "arrow-right",
-            "angle-down",
-            "angle-up",
-            "crop",
-            "toc",
-            "font_family",
-            "font_size",
-            "line_height",
-            "align-justify",
-            "align-left",
-            "align-right",
-            "align-center",
-            "margin",
-            "plug",
-            "cover",
-            "enclosed_menu",
-            "contrast",
-            "gray",
-            "plus",
-        ]
-        .iter()
-        .cloned()
-        {
-            let path = dir.join(&format!("{}.svg", name));
-            let doc = PdfOpener::new().and_then(|o| o.open(path)).unwrap();
-            let pixmap = doc.page(0).and_then(|p| p.pixmap(scale, 1)).unwrap();
-            m.insert(name, pixmap);
+        let mut paths: Vec<_> = fs::read_dir(icons_dir())
+            .map(|entries| entries.filter_map(|e| e.ok().map(|e| e.path())).collect())
+            .unwrap_or_default();
+        paths.sort();
+        for path in paths {
+            if path.extension().is_none_or(|ext| ext != "svg") {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            match Svg::open(&path) {
+                Ok(svg) => {
+                    m.insert(name.to_string(), svg.render(scale));
+                }
+                Err(e) => error!("Can't load icon: {:#}.", e),
+            }
         }
         m
     };
 }
 
+// The tests run from the crate's directory, the icons are at the root of the repository.
+fn icons_dir() -> PathBuf {
+    #[cfg(test)]
+    if let Some(root) = std::env::var_os("TEST_ROOT_DIR") {
+        return PathBuf::from(root).join("icons");
+    }
+    PathBuf::from("icons")
+}
+
 pub struct Icon {
     id: Id,
     pub rect: Rectangle,
@@ -98,7 +73,7 @@ impl Icon {
             rect,
             children: Vec::new(),
             name: name.to_string(),
-            background: TEXT_NORMAL[0],
+            background: theme::current().text_normal[0],
             align: Align::Center,
             corners: None,
             event,
@@ -177,9 +152,9 @@ impl View for Icon {
     #[cfg_attr(feature = "otel", tracing::instrument(skip(self, fb, _fonts, _rect), fields(rect = ?_rect)))]
     fn render(&self, fb: &mut dyn Framebuffer, _rect: Rectangle, _fonts: &mut Fonts) {
         let scheme = if self.active {
-            TEXT_INVERTED_HARD
+            theme::current().text_inverted_hard
         } else {
-            TEXT_NORMAL
+            theme::current().text_normal
         };
 
         let pixmap = ICONS_PIXMAPS.get(&self.name[..]).unwrap();
@@ -217,6 +192,10 @@ impl View for Icon {
         self.rect = rect;
     }
 
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
     fn rect(&self) -> &Rectangle {
         &self.rect
     }
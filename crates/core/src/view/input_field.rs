@@ -3,14 +3,19 @@ use super::{
     Bus, EntryId, Event, Hub, Id, KeyboardEvent, RenderData, RenderQueue, TextKind, View, ViewId,
     ID_FEEDER,
 };
-use crate::color::{BLACK, TEXT_NORMAL};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::{font_from_style, Fonts, FONT_SIZES, NORMAL_STYLE};
 use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::{halves, BorderSpec, LinearDir, Point, Rectangle};
 use crate::gesture::GestureEvent;
-use crate::unit::scale_by_dpi;
+use crate::theme;
+use crate::unit::scale_thickness;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// The pause after which the typing is considered finished.
+const SETTLE_DELAY: Duration = Duration::from_millis(600);
 
 pub struct InputField {
     id: Id,
@@ -23,6 +28,7 @@ pub struct InputField {
     cursor: usize,
     border: bool,
     focused: bool,
+    last_edit: Option<Instant>,
 }
 
 fn closest_char_boundary(text: &str, index: usize, dir: LinearDir) -> Option<usize> {
@@ -97,6 +103,7 @@ impl InputField {
             cursor: 0,
             border: true,
             focused: false,
+            last_edit: None,
         }
     }
 
@@ -296,7 +303,29 @@ impl View for InputField {
                         context.record_input(&self.text, self.view_id);
                     }
                 };
-                rq.add(RenderData::no_wait(self.id, self.rect, UpdateMode::Gui));
+                rq.add(RenderData::no_wait(
+                    self.id,
+                    self.rect,
+                    UpdateMode::Interactive,
+                ));
+                self.last_edit = Some(Instant::now());
+                let hub2 = hub.clone();
+                let id = self.id;
+                thread::spawn(move || {
+                    thread::sleep(SETTLE_DELAY);
+                    hub2.send(Event::Settle(id)).ok();
+                });
+                true
+            }
+            Event::Settle(id) if id == self.id => {
+                // Only the last key stroke of a burst triggers the quality update.
+                if self
+                    .last_edit
+                    .is_some_and(|last_edit| last_edit.elapsed() >= SETTLE_DELAY)
+                {
+                    self.last_edit = None;
+                    rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+                }
                 true
             }
             Event::Select(EntryId::SetInputText(view_id, ref text)) => {
@@ -323,16 +352,16 @@ impl View for InputField {
         let cursor_height = 2 * x_height;
         let max_width = self.rect.width().saturating_sub(2 * padding as u32) as i32;
 
-        fb.draw_rectangle(&self.rect, TEXT_NORMAL[0]);
+        fb.draw_rectangle(&self.rect, theme::current().text_normal[0]);
 
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
 
         if self.border {
             fb.draw_rectangle_outline(
                 &self.rect,
                 &BorderSpec {
                     thickness: thickness as u16,
-                    color: BLACK,
+                    color: theme::current().foreground,
                 },
             );
         }
@@ -340,12 +369,12 @@ impl View for InputField {
         let (mut plan, foreground) = if self.text.is_empty() {
             (
                 font.plan(&self.placeholder, Some(max_width), None),
-                TEXT_NORMAL[2],
+                theme::current().text_normal[2],
             )
         } else {
             (
                 font.plan(&self.text, None, Some(&["-liga".to_string()])),
-                TEXT_NORMAL[1],
+                theme::current().text_normal[1],
             )
         };
 
@@ -381,7 +410,7 @@ impl View for InputField {
                 self.rect.min.x + padding + dx + thickness,
                 self.rect.max.y - big_dy
             ],
-            BLACK,
+            theme::current().foreground,
         );
 
         if !self.partial.is_empty() {
@@ -392,7 +421,7 @@ impl View for InputField {
                 self.rect.min.x + padding + dx + 3 * thickness,
                 self.rect.max.y - big_dy + x_height
             );
-            font.render(fb, TEXT_NORMAL[1], &plan, pt);
+            font.render(fb, theme::current().text_normal[1], &plan, pt);
         }
     }
 
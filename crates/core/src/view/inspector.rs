@@ -0,0 +1,127 @@
+//! Drawing the layout of the view tree over the screen.
+//!
+//! When the `view-inspector` setting is enabled, the `toggle-inspector` gesture action shows
+//! the outline of every view, along with its type and its identifier. The overlay is drawn
+//! after the views, over the rectangles being updated, like the focus ring.
+
+use super::{Id, RenderData, RenderQueue, View};
+use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::font::{font_from_style, FONT_SIZES, NORMAL_STYLE};
+use crate::framebuffer::UpdateMode;
+use crate::geom::Rectangle;
+use crate::theme;
+
+/// Shows or hides the overlay, if the `view-inspector` setting allows it.
+pub fn toggle_inspector(rq: &mut RenderQueue, context: &mut Context) {
+    if !context.settings.view_inspector && !context.inspector {
+        return;
+    }
+    context.inspector = !context.inspector;
+    rq.add(RenderData::expose(context.fb.rect(), UpdateMode::Gui));
+}
+
+/// Returns the rectangle, type name and identifier of every view of the tree, parents first.
+pub fn view_outlines(view: &dyn View) -> Vec<(Rectangle, &'static str, Id)> {
+    let mut outlines = Vec::new();
+    collect_outlines(view, &mut outlines);
+    outlines
+}
+
+fn collect_outlines(view: &dyn View, outlines: &mut Vec<(Rectangle, &'static str, Id)>) {
+    outlines.push((*view.rect(), short_type_name(view.type_name()), view.id()));
+    for child in view.children() {
+        collect_outlines(child.as_ref(), outlines);
+    }
+}
+
+// Strips the module paths, e.g. `cadmus_core::view::label::Label` becomes `Label`.
+fn short_type_name(name: &'static str) -> &'static str {
+    let end = name.find('<').unwrap_or(name.len());
+    name[..end].rsplit("::").next().unwrap_or(name)
+}
+
+/// Draws the outlines of the views that intersect the rectangles about to be updated.
+pub fn draw_view_tree(view: &dyn View, rects: &[Rectangle], context: &mut Context) {
+    if !context.inspector {
+        return;
+    }
+
+    let dpi = CURRENT_DEVICE.dpi;
+    let mut style = NORMAL_STYLE;
+    style.size = FONT_SIZES[0];
+    let font = font_from_style(&mut context.fonts, &style, dpi);
+    let padding = font.em() as i32 / 4;
+    let label_height = font.x_heights.1 as i32 + 2 * padding;
+    let [background, foreground, _] = theme::current().text_inverted_hard;
+
+    for (rect, name, id) in view_outlines(view) {
+        if rect.is_empty() {
+            continue;
+        }
+
+        // The outline is clipped to the updated rectangles, the rest of the screen isn't
+        // refreshed.
+        let edges = [
+            rect![rect.min.x, rect.min.y, rect.max.x, rect.min.y + 1],
+            rect![rect.min.x, rect.max.y - 1, rect.max.x, rect.max.y],
+            rect![rect.min.x, rect.min.y, rect.min.x + 1, rect.max.y],
+            rect![rect.max.x - 1, rect.min.y, rect.max.x, rect.max.y],
+        ];
+        for edge in &edges {
+            for r in rects {
+                if let Some(visible) = edge.intersection(r) {
+                    context.fb.draw_rectangle(&visible, background);
+                }
+            }
+        }
+
+        let text = format!("{} {}", name, id);
+        let plan = font.plan(&text, Some(rect.width() as i32 - 2 * padding), None);
+        let label_rect = rect![
+            rect.min.x,
+            rect.min.y,
+            rect.min.x + plan.width + 2 * padding,
+            rect.min.y + label_height
+        ];
+        if plan.width > 0 && rects.iter().any(|r| r.contains(&label_rect)) {
+            context.fb.draw_rectangle(&label_rect, background);
+            let pt = pt!(
+                label_rect.min.x + padding,
+                label_rect.max.y - padding - font.descender() / 2
+            );
+            font.render(context.fb.as_mut(), foreground, &plan, pt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_helpers::create_test_context;
+    use crate::view::top_bar::{TopBar, TopBarVariant};
+
+    #[test]
+    fn test_outlines_name_every_view() {
+        let mut context = create_test_context();
+        let top_bar = TopBar::new(
+            rect![0, 0, 600, 60],
+            TopBarVariant::Back,
+            "Title".to_string(),
+            &mut context,
+        );
+
+        let outlines = view_outlines(&top_bar);
+        assert_eq!(outlines.len(), 1 + top_bar.children().len());
+        assert_eq!(outlines[0], (*top_bar.rect(), "TopBar", top_bar.id()));
+        assert!(outlines.iter().any(|(_, name, _)| *name == "Label"));
+
+        let mut rq = RenderQueue::new();
+        toggle_inspector(&mut rq, &mut context);
+        assert!(!context.inspector);
+        context.settings.view_inspector = true;
+        toggle_inspector(&mut rq, &mut context);
+        assert!(context.inspector);
+        assert!(!rq.is_empty());
+    }
+}
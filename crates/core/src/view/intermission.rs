@@ -1,21 +1,39 @@
-use super::{Bus, Event, Hub, Id, RenderQueue, View, ID_FEEDER};
-use crate::color::{TEXT_INVERTED_HARD, TEXT_NORMAL};
+use super::{Bus, Event, Hub, Id, RenderData, RenderQueue, View, ID_FEEDER};
+use crate::animation::{is_animated_kind, Animation, Frame};
+use crate::color::Color;
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
-use crate::document::{open, Location};
-use crate::font::{font_from_style, Fonts, DISPLAY_STYLE};
-use crate::framebuffer::Framebuffer;
+use crate::document::{file_kind, open, Location};
+use crate::font::{font_from_style, Fonts, DISPLAY_STYLE, NORMAL_STYLE};
+use crate::framebuffer::{Framebuffer, Pixmap, UpdateMode};
 use crate::geom::Rectangle;
-use crate::metadata::{sort, BookQuery, SortMethod};
-use crate::settings::{IntermKind, IntermissionDisplay};
-use std::path::PathBuf;
+use crate::metadata::{sort, BookQuery, Info, SortMethod};
+use crate::settings::{DitheringKind, SlideshowOrder};
+use crate::settings::{IntermKind, IntermissionDisplay, IntermissionOverlay, OverlayPosition};
+use crate::svg::Svg;
+use crate::theme;
+use rand_core::Rng;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tracing::error;
 
 pub struct Intermission {
     id: Id,
     rect: Rectangle,
     children: Vec<Box<dyn View>>,
     message: Message,
+    overlay: Vec<String>,
+    overlay_settings: IntermissionOverlay,
+    cover_dithering: DitheringKind,
     halt: bool,
+    // The decoded frames of an animated image, and the index of the one shown.
+    frames: Vec<Frame>,
+    frame_index: usize,
+    animating: Arc<AtomicBool>,
 }
 
 pub enum Message {
@@ -24,57 +42,271 @@ pub enum Message {
     Cover(PathBuf),
 }
 
+// Extensions of the images shown by a folder slideshow.
+const SLIDESHOW_IMAGE_KINDS: [&str; 7] = ["png", "apng", "gif", "jpg", "jpeg", "webp", "avif"];
+
+// The largest number of frames of an animated intermission.
+const MAX_FRAMES: usize = 120;
+
+// The screen shows at most one frame per second, whatever the pace of the animation.
+const MIN_FRAME_DELAY: Duration = Duration::from_secs(1);
+
 impl Intermission {
-    pub fn new(rect: Rectangle, kind: IntermKind, context: &Context) -> Intermission {
-        let message = match &context.settings.intermissions[kind] {
+    pub fn new(rect: Rectangle, kind: IntermKind, context: &mut Context) -> Intermission {
+        let message = match context.settings.intermissions[kind].clone() {
             IntermissionDisplay::Logo => Message::Text(kind.text().to_string()),
-            IntermissionDisplay::Cover => {
-                let query = BookQuery {
-                    reading: Some(true),
-                    ..Default::default()
-                };
-                let (mut files, _) =
-                    context
-                        .library
-                        .list(&context.library.home, Some(&query), false);
-                sort(&mut files, SortMethod::Opened, true);
-                if !files.is_empty() {
-                    Message::Cover(context.library.home.join(&files[0].file.path))
+            IntermissionDisplay::Cover => match current_book(context) {
+                Some(info) => Message::Cover(context.library.home.join(&info.file.path)),
+                None => Message::Text(kind.text().to_string()),
+            },
+            IntermissionDisplay::Image(path) => Message::Image(path),
+            IntermissionDisplay::Slideshow(folder) => {
+                let mut messages = if let Some(folder) = folder {
+                    folder_images(&folder)
+                        .into_iter()
+                        .map(Message::Image)
+                        .collect::<Vec<Message>>()
                 } else {
+                    recent_covers(context)
+                        .into_iter()
+                        .map(Message::Cover)
+                        .collect::<Vec<Message>>()
+                };
+                if messages.is_empty() {
                     Message::Text(kind.text().to_string())
+                } else {
+                    let index = next_slide(messages.len(), context);
+                    messages.swap_remove(index)
                 }
             }
-            IntermissionDisplay::Image(path) => Message::Image(path.clone()),
+        };
+        let frames = match message {
+            Message::Image(ref path)
+                if file_kind(path).is_some_and(|kind| is_animated_kind(&kind)) =>
+            {
+                let max_frames =
+                    if kind == IntermKind::Suspend && context.settings.intermissions.animate {
+                        MAX_FRAMES
+                    } else {
+                        1
+                    };
+                Animation::open(path, max_frames, CURRENT_DEVICE.color_samples())
+                    .map(|animation| animation.frames)
+                    .map_err(|e| error!("Can't decode the frames: {:#}.", e))
+                    .unwrap_or_default()
+            }
+            _ => Vec::new(),
+        };
+        let overlay = if kind == IntermKind::Share {
+            Vec::new()
+        } else {
+            overlay_lines(context)
         };
         Intermission {
             id: ID_FEEDER.next(),
             rect,
             children: Vec::new(),
             message,
+            overlay,
+            overlay_settings: context.settings.intermission_overlay.clone(),
+            cover_dithering: context.settings.display.cover_dithering,
             halt: kind == IntermKind::PowerOff,
+            frames,
+            frame_index: 0,
+            animating: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Starts cycling through the frames of the image, if it's animated.
+    pub fn animate(&self, hub: &Hub) {
+        if self.frames.len() < 2 || self.animating.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        let delays: Vec<Duration> = self
+            .frames
+            .iter()
+            .map(|frame| frame.delay.max(MIN_FRAME_DELAY))
+            .collect();
+        let animating = Arc::clone(&self.animating);
+        let hub = hub.clone();
+        let id = self.id;
+        thread::spawn(move || {
+            for delay in delays.iter().cycle() {
+                thread::sleep(*delay);
+                if !animating.load(Ordering::Relaxed) || hub.send(Event::NextFrame(id)).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+impl Drop for Intermission {
+    fn drop(&mut self) {
+        self.animating.store(false, Ordering::Relaxed);
+    }
+}
+
+// Shrinks the frame to fit in the rectangle, or enlarges it by a whole factor.
+fn fit_frame(pixmap: &Pixmap, rect: &Rectangle) -> Pixmap {
+    let factor = (rect.width() / pixmap.width.max(1)).min(rect.height() / pixmap.height.max(1));
+    if factor >= 1 {
+        pixmap.enlarge(factor)
+    } else {
+        pixmap.scale_to_fit(rect.width(), rect.height())
+    }
+}
+
+// Returns the most recently opened book among the books being read.
+fn current_book(context: &Context) -> Option<Info> {
+    let query = BookQuery {
+        reading: Some(true),
+        ..Default::default()
+    };
+    let (mut files, _) = context
+        .library
+        .list(&context.library.home, Some(&query), false);
+    sort(&mut files, SortMethod::Opened, true);
+    files.into_iter().next()
+}
+
+// Returns the lines of text drawn over the image, in the order of the settings.
+fn overlay_lines(context: &mut Context) -> Vec<String> {
+    let settings = context.settings.intermission_overlay.clone();
+    let mut lines = Vec::new();
+
+    if settings.title || settings.progress {
+        if let Some(info) = current_book(context) {
+            if settings.title {
+                lines.push(info.title());
+            }
+            if let (true, Some(reader)) = (settings.progress, info.reader.as_ref()) {
+                let progress = reader.current_page as f32 / reader.pages_count.max(1) as f32;
+                lines.push(format!("{:.0}%", 100.0 * progress));
+            }
+        }
+    }
+
+    if settings.battery {
+        if let Ok(capacity) = context.battery.capacity() {
+            lines.push(format!("Battery: {:.0}%", capacity[0]));
+        }
+    }
+
+    if let Some(message) = settings.message.filter(|m| !m.is_empty()) {
+        lines.extend(message.lines().map(String::from));
+    }
+
+    lines
+}
+
+// Returns the paths of the recently opened books, the most recent first.
+fn recent_covers(context: &Context) -> Vec<PathBuf> {
+    let (mut files, _) = context.library.list(&context.library.home, None, false);
+    files.retain(|info| info.reader.is_some());
+    sort(&mut files, SortMethod::Opened, true);
+    files
+        .iter()
+        .take(context.settings.slideshow.books_count)
+        .map(|info| context.library.home.join(&info.file.path))
+        .collect()
+}
+
+// Returns the paths of the images of the given folder, sorted by name.
+fn folder_images(folder: &Path) -> Vec<PathBuf> {
+    let mut images = fs::read_dir(folder)
+        .map_err(|e| error!("Can't read {}: {:#}.", folder.display(), e))
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            file_kind(path).is_some_and(|kind| SLIDESHOW_IMAGE_KINDS.contains(&kind.as_str()))
+        })
+        .collect::<Vec<PathBuf>>();
+    images.sort();
+    images
+}
+
+// Returns the index of the next picture among `count` pictures.
+fn next_slide(count: usize, context: &mut Context) -> usize {
+    let index = match (context.settings.slideshow.order, context.slideshow_index) {
+        (SlideshowOrder::Sequential, Some(last)) => (last + 1) % count,
+        (SlideshowOrder::Sequential, None) => 0,
+        // Never show the same picture twice in a row.
+        (SlideshowOrder::Random, Some(last)) if count > 1 => {
+            let offset = 1 + (context.rng.next_u64() % (count as u64 - 1)) as usize;
+            (last % count + offset) % count
+        }
+        (SlideshowOrder::Random, _) => (context.rng.next_u64() % count as u64) as usize,
+    };
+    context.slideshow_index = Some(index);
+    index
+}
+
+impl Intermission {
+    fn render_overlay(&self, fb: &mut dyn Framebuffer, scheme: [Color; 3], fonts: &mut Fonts) {
+        if self.overlay.is_empty() {
+            return;
+        }
+
+        let dpi = CURRENT_DEVICE.dpi;
+        let font = font_from_style(fonts, &NORMAL_STYLE, dpi);
+        font.set_size((64.0 * self.overlay_settings.font_size) as u32, dpi);
+        let padding = font.em() as i32;
+        let line_height = font.line_height();
+        let max_width = self.rect.width() as i32 - 2 * padding;
+        let block_height = self.overlay.len() as i32 * line_height + padding;
+
+        let y_min = match self.overlay_settings.position {
+            OverlayPosition::Top => self.rect.min.y,
+            OverlayPosition::Bottom => self.rect.max.y - block_height,
+        };
+
+        // Keep the text readable over the images.
+        fb.draw_rectangle(
+            &rect![
+                self.rect.min.x,
+                y_min,
+                self.rect.max.x,
+                y_min + block_height
+            ],
+            scheme[0],
+        );
+
+        for (index, line) in self.overlay.iter().enumerate() {
+            let plan = font.plan(line, Some(max_width), None);
+            let dx = (self.rect.width() as i32 - plan.width) / 2;
+            let dy = y_min + padding / 2 + index as i32 * line_height + font.ascender();
+            font.render(fb, scheme[1], &plan, pt!(self.rect.min.x + dx, dy));
         }
     }
 }
 
 impl View for Intermission {
-    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _evt, _hub, _bus, _rq, _context), fields(event = ?_evt), ret(level=tracing::Level::TRACE)))]
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _hub, _bus, rq, _context), fields(event = ?evt), ret(level=tracing::Level::TRACE)))]
     fn handle_event(
         &mut self,
-        _evt: &Event,
+        evt: &Event,
         _hub: &Hub,
         _bus: &mut Bus,
-        _rq: &mut RenderQueue,
+        rq: &mut RenderQueue,
         _context: &mut Context,
     ) -> bool {
+        if let Event::NextFrame(id) = *evt {
+            if id == self.id && !self.frames.is_empty() {
+                self.frame_index = (self.frame_index + 1) % self.frames.len();
+                rq.add(RenderData::new(self.id, self.rect, UpdateMode::Partial));
+            }
+        }
         true
     }
 
     #[cfg_attr(feature = "otel", tracing::instrument(skip(self, fb, fonts, _rect), fields(rect = ?_rect)))]
     fn render(&self, fb: &mut dyn Framebuffer, _rect: Rectangle, fonts: &mut Fonts) {
         let scheme = if self.halt {
-            TEXT_INVERTED_HARD
+            theme::current().text_inverted_hard
         } else {
-            TEXT_NORMAL
+            theme::current().text_normal
         };
 
         fb.draw_rectangle(&self.rect, scheme[0]);
@@ -102,15 +334,30 @@ impl View for Intermission {
 
                 font.render(fb, scheme[1], &plan, pt!(dx, dy));
 
-                let mut doc = open("icons/dodecahedron.svg").unwrap();
-                let (width, height) = doc.dims(0).unwrap();
-                let scale = (plan.width as f32 / width.max(height) as f32) / 4.0;
-                let (pixmap, _) = doc.pixmap(Location::Exact(0), scale, 1).unwrap();
+                match Svg::open("icons/dodecahedron.svg") {
+                    Ok(svg) => {
+                        let (width, height) = svg.dims();
+                        let scale = (plan.width as f32 / width.max(height)) / 4.0;
+                        let pixmap = svg.render(scale);
+                        let dx = (self.rect.width() as i32 - pixmap.width as i32) / 2;
+                        let dy = dy + 2 * x_height;
+                        let pt = self.rect.min + pt!(dx, dy);
+
+                        fb.draw_blended_pixmap(&pixmap, pt, scheme[1]);
+                    }
+                    Err(e) => error!("Can't load the logo: {:#}.", e),
+                }
+            }
+            Message::Image(_) if !self.frames.is_empty() => {
+                let pixmap = fit_frame(&self.frames[self.frame_index].pixmap, &self.rect);
                 let dx = (self.rect.width() as i32 - pixmap.width as i32) / 2;
-                let dy = dy + 2 * x_height;
+                let dy = (self.rect.height() as i32 - pixmap.height as i32) / 2;
                 let pt = self.rect.min + pt!(dx, dy);
-
-                fb.draw_blended_pixmap(&pixmap, pt, scheme[1]);
+                fb.draw_pixmap(&pixmap, pt);
+                if fb.inverted() {
+                    let rect = pixmap.rect() + pt;
+                    fb.invert_region(&rect);
+                }
             }
             Message::Image(ref path) => {
                 if let Some(mut doc) = open(path) {
@@ -135,11 +382,12 @@ impl View for Intermission {
             }
             Message::Cover(ref path) => {
                 if let Some(mut doc) = open(path) {
-                    if let Some(pixmap) = doc.preview_pixmap(
+                    if let Some(mut pixmap) = doc.preview_pixmap(
                         self.rect.width() as f32,
                         self.rect.height() as f32,
                         CURRENT_DEVICE.color_samples(),
                     ) {
+                        pixmap.dither(self.cover_dithering);
                         let dx = (self.rect.width() as i32 - pixmap.width as i32) / 2;
                         let dy = (self.rect.height() as i32 - pixmap.height as i32) / 2;
                         let pt = self.rect.min + pt!(dx, dy);
@@ -152,6 +400,8 @@ impl View for Intermission {
                 }
             }
         }
+
+        self.render_overlay(fb, scheme, fonts);
     }
 
     fn might_rotate(&self) -> bool {
@@ -178,3 +428,47 @@ impl View for Intermission {
         self.id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_helpers::create_test_context;
+
+    #[test]
+    fn test_next_slide_order() {
+        let mut context = create_test_context();
+
+        context.settings.slideshow.order = SlideshowOrder::Sequential;
+        let slides = (0..4)
+            .map(|_| next_slide(3, &mut context))
+            .collect::<Vec<usize>>();
+        assert_eq!(slides, [0, 1, 2, 0]);
+
+        context.settings.slideshow.order = SlideshowOrder::Random;
+        let mut last = next_slide(3, &mut context);
+        for _ in 0..16 {
+            let index = next_slide(3, &mut context);
+            assert!(index < 3);
+            assert_ne!(
+                index, last,
+                "the same picture shouldn't be shown twice in a row"
+            );
+            last = index;
+        }
+    }
+
+    #[test]
+    fn test_overlay_lines() {
+        let mut context = create_test_context();
+        assert!(overlay_lines(&mut context).is_empty());
+
+        context.settings.intermission_overlay.battery = true;
+        context.settings.intermission_overlay.message =
+            Some("Please return to\nowner@example.com".to_string());
+        let lines = overlay_lines(&mut context);
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("Battery: "));
+        assert_eq!(lines[1..], ["Please return to", "owner@example.com"]);
+    }
+}
@@ -3,7 +3,7 @@ use super::BORDER_RADIUS_LARGE;
 use super::{
     Bus, Event, Hub, Id, KeyboardEvent, RenderData, RenderQueue, TextKind, View, ViewId, ID_FEEDER,
 };
-use crate::color::{Color, KEYBOARD_BG, TEXT_INVERTED_HARD, TEXT_NORMAL};
+use crate::color::Color;
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::{font_from_style, Fonts, KBD_CHAR, KBD_LABEL};
@@ -11,6 +11,7 @@ use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::{CornerSpec, LinearDir, Rectangle};
 use crate::gesture::GestureEvent;
 use crate::input::{DeviceEvent, FingerStatus};
+use crate::theme;
 use crate::unit::scale_by_dpi;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -243,8 +244,10 @@ impl View for Key {
                 bus.push_back(Event::Key(self.kind));
                 true
             }
+            // The keyboard offers the alternates of the held output keys.
             Event::Gesture(GestureEvent::HoldFingerShort(center, ..))
-                if self.rect.includes(center) =>
+                if self.rect.includes(center)
+                    && !matches!(self.kind, KeyKind::Output(ch) if ch != ' ') =>
             {
                 match self.kind {
                     KeyKind::Delete(dir) => {
@@ -276,11 +279,11 @@ impl View for Key {
     #[cfg_attr(feature = "otel", tracing::instrument(skip(self, fb, fonts, _rect), fields(rect = ?_rect)))]
     fn render(&self, fb: &mut dyn Framebuffer, _rect: Rectangle, fonts: &mut Fonts) {
         let dpi = CURRENT_DEVICE.dpi;
-        fb.draw_rectangle(&self.rect, KEYBOARD_BG);
+        fb.draw_rectangle(&self.rect, theme::current().keyboard_bg);
         let scheme: [Color; 3] = if self.active ^ (self.pressure == 2) {
-            TEXT_INVERTED_HARD
+            theme::current().text_inverted_hard
         } else {
-            TEXT_NORMAL
+            theme::current().text_normal
         };
 
         let border_radius = scale_by_dpi(BORDER_RADIUS_LARGE, dpi) as i32;
@@ -0,0 +1,146 @@
+use super::key::KeyKind;
+use super::{
+    Bus, Event, Hub, Id, RenderData, RenderQueue, View, ViewId, BORDER_RADIUS_LARGE, ID_FEEDER,
+};
+use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::font::{font_from_style, Fonts, KBD_CHAR};
+use crate::framebuffer::{Framebuffer, UpdateMode};
+use crate::geom::{CornerSpec, Point, Rectangle};
+use crate::input::{DeviceEvent, FingerStatus};
+use crate::theme;
+use crate::unit::scale_by_dpi;
+
+/// The characters offered by a held key, shown above it.
+///
+/// The character under the finger is highlighted as it slides, and typed when it's lifted.
+/// Lifting the finger far from the popup types nothing.
+pub struct KeyAlternates {
+    id: Id,
+    rect: Rectangle,
+    children: Vec<Box<dyn View>>,
+    chars: Vec<char>,
+    selected: Option<usize>,
+}
+
+impl KeyAlternates {
+    pub fn new(rect: Rectangle, chars: Vec<char>) -> KeyAlternates {
+        KeyAlternates {
+            id: ID_FEEDER.next(),
+            rect,
+            children: Vec::new(),
+            chars,
+            selected: Some(0),
+        }
+    }
+
+    fn cell_rect(&self, index: usize) -> Rectangle {
+        let width = self.rect.width() as i32 / self.chars.len() as i32;
+        let x_min = self.rect.min.x + index as i32 * width;
+        rect![x_min, self.rect.min.y, x_min + width, self.rect.max.y]
+    }
+
+    // The finger selects the cell above or below which it stands, and nothing once it's
+    // further than the height of the popup.
+    fn select_at(&mut self, position: Point, rq: &mut RenderQueue) {
+        let margin = self.rect.height() as i32;
+        let selected =
+            if position.y < self.rect.min.y - margin || position.y >= self.rect.max.y + margin {
+                None
+            } else {
+                let width = (self.rect.width() as i32 / self.chars.len() as i32).max(1);
+                let index = (position.x - self.rect.min.x).div_euclid(width);
+                Some(index.clamp(0, self.chars.len() as i32 - 1) as usize)
+            };
+        if selected != self.selected {
+            self.selected = selected;
+            rq.add(RenderData::no_wait(self.id, self.rect, UpdateMode::Fast));
+        }
+    }
+}
+
+impl View for KeyAlternates {
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _hub, bus, rq, _context), fields(event = ?evt), ret(level=tracing::Level::TRACE)))]
+    fn handle_event(
+        &mut self,
+        evt: &Event,
+        _hub: &Hub,
+        bus: &mut Bus,
+        rq: &mut RenderQueue,
+        _context: &mut Context,
+    ) -> bool {
+        match *evt {
+            Event::Device(DeviceEvent::Finger {
+                status: FingerStatus::Motion,
+                position,
+                ..
+            }) => {
+                self.select_at(position, rq);
+                true
+            }
+            // Let the held key release itself.
+            Event::Device(DeviceEvent::Finger {
+                status: FingerStatus::Up,
+                position,
+                ..
+            }) => {
+                self.select_at(position, rq);
+                if let Some(index) = self.selected {
+                    bus.push_back(Event::Key(KeyKind::Output(self.chars[index])));
+                }
+                bus.push_back(Event::Close(ViewId::KeyAlternates));
+                false
+            }
+            _ => false,
+        }
+    }
+
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, fb, fonts), fields(rect = ?_rect)))]
+    fn render(&self, fb: &mut dyn Framebuffer, _rect: Rectangle, fonts: &mut Fonts) {
+        let dpi = CURRENT_DEVICE.dpi;
+        let border_radius = scale_by_dpi(BORDER_RADIUS_LARGE, dpi) as i32;
+        let font = font_from_style(fonts, &KBD_CHAR, dpi);
+        let t = theme::current();
+
+        fb.draw_rectangle(&self.rect, t.keyboard_bg);
+
+        for (index, ch) in self.chars.iter().enumerate() {
+            let rect = self.cell_rect(index);
+            let scheme = if self.selected == Some(index) {
+                t.text_inverted_hard
+            } else {
+                t.text_normal
+            };
+            fb.draw_rounded_rectangle(&rect, &CornerSpec::Uniform(border_radius), scheme[0]);
+            let plan = font.plan(ch.to_string(), None, None);
+            let dx = (rect.width() as i32 - plan.width) / 2;
+            let dy = (rect.height() - font.x_heights.0) as i32 / 2;
+            let pt = pt!(rect.min.x + dx, rect.max.y - dy);
+            font.render(fb, scheme[1], &plan, pt);
+        }
+    }
+
+    fn rect(&self) -> &Rectangle {
+        &self.rect
+    }
+
+    fn rect_mut(&mut self) -> &mut Rectangle {
+        &mut self.rect
+    }
+
+    fn children(&self) -> &Vec<Box<dyn View>> {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<Box<dyn View>> {
+        &mut self.children
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn view_id(&self) -> Option<ViewId> {
+        Some(ViewId::KeyAlternates)
+    }
+}
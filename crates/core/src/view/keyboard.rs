@@ -1,22 +1,39 @@
+use super::backdrop::Elevation;
 use super::key::{Key, KeyKind};
+use super::key_alternates::KeyAlternates;
+use super::pinyin::{Candidate, PinyinTable};
+use super::suggestion_strip::SuggestionStrip;
+use super::swipe_typing::WordList;
 use super::BIG_BAR_HEIGHT;
 use super::{
-    Bus, EntryId, Event, Hub, Id, KeyboardEvent, RenderData, RenderQueue, TextKind, View, ID_FEEDER,
+    Bus, EntryId, Event, Hub, Id, KeyboardEvent, RenderData, RenderQueue, TextKind, View, ViewId,
+    ID_FEEDER,
 };
-use crate::color::KEYBOARD_BG;
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::Fonts;
 use crate::framebuffer::{Framebuffer, UpdateMode};
-use crate::geom::Rectangle;
+use crate::geom::{LinearDir, Point, Rectangle};
 use crate::gesture::GestureEvent;
-use crate::input::DeviceEvent;
+use crate::input::{DeviceEvent, FingerStatus};
+use crate::theme;
 use crate::unit::scale_by_dpi;
 use fxhash::FxHashMap;
 use lazy_static::lazy_static;
 use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::mem;
+use std::sync::Arc;
 
 const PADDING_RATIO: f32 = 0.06;
+// The number of words proposed by a swipe: the one typed and its alternatives.
+const SWIPE_CANDIDATES_COUNT: usize = 4;
+// The number of completions of the word being typed.
+const COMPLETIONS_COUNT: usize = 3;
+// The number of words proposed for a pinyin composition.
+const PINYIN_CANDIDATES_COUNT: usize = 5;
+// The pause, in seconds, between two motions of a finger that starts a new swipe.
+const SWIPE_PAUSE: f64 = 0.25;
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -25,6 +42,46 @@ pub struct Layout {
     pub outputs: [Vec<Vec<char>>; 4],
     pub keys: Vec<Vec<KeyKind>>,
     pub widths: Vec<Vec<f32>>,
+    /// The characters offered by holding an output key, e.g. `"e": "éèêë"`.
+    #[serde(default)]
+    pub alternates: BTreeMap<char, String>,
+    /// The words typed by swiping, read from the `.words` file next to the layout.
+    #[serde(skip)]
+    pub words: Option<Arc<WordList>>,
+    /// The spellings composed into Chinese words, read from the `.pinyin` file next to the
+    /// layout.
+    #[serde(skip)]
+    pub pinyin: Option<Arc<PinyinTable>>,
+}
+
+impl Layout {
+    /// Returns the alternates of a character, those of an uppercase letter default to the
+    /// uppercase alternates of the lowercase letter.
+    pub fn alternates(&self, ch: char) -> Vec<char> {
+        if let Some(alternates) = self.alternates.get(&ch) {
+            return alternates.chars().collect();
+        }
+        let mut lower = ch.to_lowercase();
+        match (lower.next(), lower.next()) {
+            (Some(lower), None) if lower != ch => self
+                .alternates
+                .get(&lower)
+                .map(|alternates| {
+                    alternates
+                        .chars()
+                        .filter_map(|c| {
+                            let mut upper = c.to_uppercase();
+                            match (upper.next(), upper.next()) {
+                                (Some(upper), None) => Some(upper),
+                                _ => None,
+                            }
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        }
+    }
 }
 
 #[derive(Default, Debug)]
@@ -41,6 +98,19 @@ pub struct Keyboard {
     layout: Layout,
     state: State,
     combine_buffer: String,
+    // Set when swipe typing is enabled.
+    words: Option<Arc<WordList>>,
+    // Set when the word being typed is completed.
+    terms: Option<WordList>,
+    // The letters typed since the beginning of the current word.
+    prefix: String,
+    // The pinyin letters being composed, and their candidates.
+    composition: String,
+    candidates: Vec<Candidate>,
+    path: Vec<Point>,
+    path_time: f64,
+    // The last word typed by swiping, and whether it was capitalized.
+    swiped: Option<(String, bool)>,
 }
 
 impl Keyboard {
@@ -50,6 +120,15 @@ impl Keyboard {
         let dpi = CURRENT_DEVICE.dpi;
 
         let layout = context.keyboard_layouts[&context.settings.keyboard_layout].clone();
+        let words = layout
+            .words
+            .clone()
+            .filter(|_| context.settings.swipe_typing);
+        let terms = context
+            .settings
+            .word_suggestions
+            .then(|| WordList::from_library(&context.library));
+        let strip_rows = usize::from(words.is_some() || terms.is_some() || layout.pinyin.is_some());
 
         let mut state = State::default();
 
@@ -75,7 +154,7 @@ impl Keyboard {
             .expect("Missing row widths");
 
         let kh_1 = (rect.width() as f32) / max_width;
-        let rows_count = layout.keys.len();
+        let rows_count = layout.keys.len() + strip_rows;
         let kh_2 =
             (rect.height() as f32) / (rows_count as f32 + PADDING_RATIO * (rows_count + 1) as f32);
         let key_height = kh_1.min(kh_2);
@@ -90,7 +169,7 @@ impl Keyboard {
         let start_y = rect.min.y as f32 + padding + (rect.height() as f32 - rows_height) / 2.0;
 
         for (i, row) in layout.keys.iter().enumerate() {
-            let y = start_y + i as f32 * (padding + key_height);
+            let y = start_y + (i + strip_rows) as f32 * (padding + key_height);
             let row_width = (layout.widths[i].len() + 1) as f32 * padding
                 + layout.widths[i].iter().sum::<f32>() * key_height;
             let start_x = rect.min.x as f32 + padding + (rect.width() as f32 - row_width) / 2.0;
@@ -124,6 +203,16 @@ impl Keyboard {
             }
         }
 
+        if strip_rows > 0 {
+            let strip_rect = rect![
+                (rect.min.x as f32 + padding).round() as i32,
+                start_y.round() as i32,
+                (rect.max.x as f32 - padding).round() as i32,
+                (start_y + key_height).round() as i32
+            ];
+            children.push(Box::new(SuggestionStrip::new(strip_rect)) as Box<dyn View>);
+        }
+
         Keyboard {
             id,
             rect: *rect,
@@ -131,6 +220,14 @@ impl Keyboard {
             layout,
             state,
             combine_buffer: String::new(),
+            words,
+            terms,
+            prefix: String::new(),
+            composition: String::new(),
+            candidates: Vec::new(),
+            path: Vec::new(),
+            path_time: 0.0,
+            swiped: None,
         }
     }
 
@@ -208,6 +305,323 @@ impl Keyboard {
             }
         }
     }
+
+    fn show_alternates(&mut self, center: Point, rq: &mut RenderQueue) {
+        let held = self.children.iter().find_map(|child| {
+            let key = child.downcast_ref::<Key>()?;
+            match *key.kind() {
+                KeyKind::Output(ch) if ch != ' ' && key.rect().includes(center) => {
+                    Some((*key.rect(), ch))
+                }
+                _ => None,
+            }
+        });
+        let Some((key_rect, ch)) = held else {
+            return;
+        };
+
+        let alternates = self.layout.alternates(ch);
+        if alternates.is_empty() {
+            return;
+        }
+
+        let mut chars = vec![ch];
+        chars.extend(alternates);
+
+        // Above the key, within the keyboard.
+        let width = key_rect.width() as i32 * chars.len() as i32;
+        let height = key_rect.height() as i32;
+        let padding = (PADDING_RATIO * height as f32).round() as i32;
+        let x_min = (key_rect.center().x - width / 2)
+            .min(self.rect.max.x - width)
+            .max(self.rect.min.x);
+        let y_min = (key_rect.min.y - padding - height).max(self.rect.min.y);
+        let rect = rect![x_min, y_min, x_min + width, y_min + height];
+
+        self.close_alternates(rq);
+        let popup = KeyAlternates::new(rect, chars);
+        rq.add(RenderData::new(popup.id(), rect, UpdateMode::Gui));
+        self.children.push(Box::new(popup) as Box<dyn View>);
+    }
+
+    fn close_alternates(&mut self, rq: &mut RenderQueue) {
+        if let Some(index) = self
+            .children
+            .iter()
+            .position(|child| child.is::<KeyAlternates>())
+        {
+            let popup = self.children.remove(index);
+            rq.add(RenderData::expose(*popup.rect(), UpdateMode::Gui));
+        }
+    }
+
+    // Returns the letters of the keys crossed by the path, without consecutive duplicates.
+    fn swiped_letters(&self, path: &[Point]) -> Vec<char> {
+        let keys: Vec<(Rectangle, char)> = self
+            .children
+            .iter()
+            .filter_map(|child| {
+                let key = child.downcast_ref::<Key>()?;
+                match *key.kind() {
+                    KeyKind::Output(ch) if ch.is_alphabetic() => Some((*key.rect(), ch)),
+                    _ => None,
+                }
+            })
+            .collect();
+        // The path is sampled often enough not to miss the narrowest key.
+        let step = keys
+            .iter()
+            .map(|(rect, _)| rect.width())
+            .min()
+            .unwrap_or(1)
+            .max(4) as f32
+            / 4.0;
+
+        let mut letters = Vec::new();
+        let mut visit = |pt: Point| {
+            if let Some(&(_, ch)) = keys.iter().find(|(rect, _)| rect.includes(pt)) {
+                for c in ch.to_lowercase() {
+                    if letters.last() != Some(&c) {
+                        letters.push(c);
+                    }
+                }
+            }
+        };
+
+        for pair in path.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            let n = ((b - a).length() / step).ceil().max(1.0) as i32;
+            for k in 0..n {
+                visit(a + (b - a) * k / n);
+            }
+        }
+
+        if let Some(&last) = path.last() {
+            visit(last);
+        }
+
+        letters
+    }
+
+    fn type_swipe(&mut self, start: Point, end: Point, hub: &Hub, rq: &mut RenderQueue) {
+        let Some(words) = self.words.clone() else {
+            return;
+        };
+
+        let mut path = mem::take(&mut self.path);
+        path.insert(0, start);
+        path.push(end);
+
+        let letters = self.swiped_letters(&path);
+        if letters.len() < 2 {
+            return;
+        }
+
+        let mut candidates = words.candidates(&letters, SWIPE_CANDIDATES_COUNT);
+        if candidates.is_empty() {
+            return;
+        }
+
+        let word = candidates.remove(0);
+        let capitalized = self.state.shift > 0;
+        self.prefix.clear();
+        self.release_modifiers(rq);
+        type_word(&word, capitalized, hub);
+        self.swiped = Some((word, capitalized));
+        self.set_suggestions(candidates, rq);
+    }
+
+    // Replaces the last word typed by swiping with one of its alternatives.
+    fn choose_suggestion(&mut self, word: &str, hub: &Hub, rq: &mut RenderQueue) {
+        if !self.composition.is_empty() {
+            let consumed = self
+                .candidates
+                .iter()
+                .find(|c| c.text == word)
+                .map_or(self.composition.len(), |c| c.consumed);
+            self.commit(word, consumed, hub, rq);
+            return;
+        }
+
+        let Some((swiped, capitalized)) = self.swiped.take() else {
+            self.complete(word, hub, rq);
+            return;
+        };
+
+        // The word was followed by a space.
+        for _ in 0..=swiped.chars().count() {
+            hub.send(Event::Keyboard(KeyboardEvent::Delete {
+                target: TextKind::Char,
+                dir: LinearDir::Backward,
+            }))
+            .ok();
+        }
+        type_word(word, capitalized, hub);
+
+        let suggestions = self
+            .suggestions()
+            .iter()
+            .map(|s| if s == word { swiped.clone() } else { s.clone() })
+            .collect();
+        self.swiped = Some((word.to_string(), capitalized));
+        self.set_suggestions(suggestions, rq);
+    }
+
+    // Handles the keys of a layout with a pinyin table, returns whether the key was consumed
+    // by the composition.
+    fn compose(&mut self, kind: KeyKind, hub: &Hub, rq: &mut RenderQueue) -> bool {
+        if self.layout.pinyin.is_none() || self.state.combine {
+            return false;
+        }
+
+        match kind {
+            KeyKind::Output(ch) if ch.is_ascii_lowercase() => {
+                self.composition.push(ch);
+                self.update_candidates(rq);
+                true
+            }
+            _ if self.composition.is_empty() => false,
+            KeyKind::Output(' ') => {
+                let (text, consumed) = self.candidates.first().map_or_else(
+                    || (self.composition.clone(), self.composition.len()),
+                    |c| (c.text.clone(), c.consumed),
+                );
+                self.commit(&text, consumed, hub, rq);
+                true
+            }
+            KeyKind::Delete(LinearDir::Backward) => {
+                self.composition.pop();
+                self.update_candidates(rq);
+                true
+            }
+            KeyKind::Return => {
+                let text = self.composition.clone();
+                self.commit(&text, text.len(), hub, rq);
+                true
+            }
+            KeyKind::Shift | KeyKind::Alternate | KeyKind::Combine => false,
+            // Other keys end the composition with its best candidates.
+            _ => {
+                while !self.composition.is_empty() {
+                    let (text, consumed) = self.candidates.first().map_or_else(
+                        || (self.composition.clone(), self.composition.len()),
+                        |c| (c.text.clone(), c.consumed),
+                    );
+                    self.commit(&text, consumed, hub, rq);
+                }
+                false
+            }
+        }
+    }
+
+    // Types a word for the first letters of the composition.
+    fn commit(&mut self, text: &str, consumed: usize, hub: &Hub, rq: &mut RenderQueue) {
+        for ch in text.chars() {
+            hub.send(Event::Keyboard(KeyboardEvent::Append(ch))).ok();
+        }
+        self.composition
+            .drain(..consumed.min(self.composition.len()));
+        self.update_candidates(rq);
+    }
+
+    // Shows the composition followed by its candidates.
+    fn update_candidates(&mut self, rq: &mut RenderQueue) {
+        let Some(table) = self.layout.pinyin.as_ref() else {
+            return;
+        };
+        self.candidates = table.candidates(&self.composition, PINYIN_CANDIDATES_COUNT);
+        let suggestions = if self.composition.is_empty() {
+            Vec::new()
+        } else {
+            [self.composition.clone()]
+                .into_iter()
+                .chain(self.candidates.iter().map(|c| c.text.clone()))
+                .collect()
+        };
+        self.set_suggestions(suggestions, rq);
+    }
+
+    // Types the rest of a completion of the current word.
+    fn complete(&mut self, word: &str, hub: &Hub, rq: &mut RenderQueue) {
+        let prefix = mem::take(&mut self.prefix);
+        if word.to_lowercase().starts_with(&prefix.to_lowercase()) {
+            let rest: String = word.chars().skip(prefix.chars().count()).collect();
+            for ch in rest.chars().chain([' ']) {
+                hub.send(Event::Keyboard(KeyboardEvent::Append(ch))).ok();
+            }
+        } else {
+            type_word(word, false, hub);
+        }
+        self.set_suggestions(Vec::new(), rq);
+    }
+
+    // Follows the current word through the typed keys, and proposes its completions.
+    fn track_prefix(&mut self, kind: KeyKind, rq: &mut RenderQueue) {
+        let Some(terms) = self.terms.as_ref() else {
+            return;
+        };
+
+        // The combined characters aren't followed.
+        if self.state.combine {
+            self.prefix.clear();
+            self.set_suggestions(Vec::new(), rq);
+            return;
+        }
+
+        match kind {
+            KeyKind::Output(ch) if ch.is_alphanumeric() || ch == '\'' => self.prefix.push(ch),
+            KeyKind::Delete(LinearDir::Backward) => {
+                self.prefix.pop();
+            }
+            KeyKind::Shift | KeyKind::Alternate | KeyKind::Combine => return,
+            _ => self.prefix.clear(),
+        }
+
+        let mut completions = terms.completions(&self.prefix, COMPLETIONS_COUNT);
+        if let Some(words) = self.layout.words.as_ref() {
+            for word in words.completions(&self.prefix, COMPLETIONS_COUNT) {
+                if !completions.iter().any(|c| c.eq_ignore_ascii_case(&word)) {
+                    completions.push(word);
+                }
+            }
+        }
+        completions.truncate(COMPLETIONS_COUNT);
+        self.set_suggestions(completions, rq);
+    }
+
+    fn suggestions(&self) -> &[String] {
+        self.children
+            .iter()
+            .find_map(|child| child.downcast_ref::<SuggestionStrip>())
+            .map_or(&[], |strip| strip.suggestions())
+    }
+
+    fn set_suggestions(&mut self, suggestions: Vec<String>, rq: &mut RenderQueue) {
+        if let Some(strip) = self
+            .children
+            .iter_mut()
+            .find_map(|child| child.downcast_mut::<SuggestionStrip>())
+        {
+            strip.update(suggestions, rq);
+        }
+    }
+}
+
+fn type_word(word: &str, capitalized: bool, hub: &Hub) {
+    let mut text = String::with_capacity(word.len() + 1);
+    let mut chars = word.chars();
+    if let Some(first) = chars.next() {
+        if capitalized {
+            text.extend(first.to_uppercase());
+        } else {
+            text.push(first);
+        }
+    }
+    text.extend(chars);
+    text.push(' ');
+    for ch in text.chars() {
+        hub.send(Event::Keyboard(KeyboardEvent::Append(ch))).ok();
+    }
 }
 
 impl View for Keyboard {
@@ -222,6 +636,14 @@ impl View for Keyboard {
     ) -> bool {
         match *evt {
             Event::Key(k) => {
+                self.path.clear();
+                if self.swiped.take().is_some() {
+                    self.set_suggestions(Vec::new(), rq);
+                }
+                if self.compose(k, hub, rq) {
+                    return true;
+                }
+                self.track_prefix(k, rq);
                 match k {
                     KeyKind::Output(ch) => {
                         if self.state.combine {
@@ -286,14 +708,50 @@ impl View for Keyboard {
                 }
                 true
             }
-            Event::Gesture(GestureEvent::Tap(center))
-            | Event::Gesture(GestureEvent::HoldFingerShort(center, ..))
+            Event::Select(EntryId::Suggestion(ref word)) => {
+                self.choose_suggestion(word, hub, rq);
+                true
+            }
+            Event::Close(ViewId::KeyAlternates) => {
+                self.close_alternates(rq);
+                true
+            }
+            Event::Gesture(GestureEvent::Tap(center)) if self.rect.includes(center) => {
+                self.path.clear();
+                true
+            }
+            Event::Gesture(GestureEvent::HoldFingerShort(center, ..))
                 if self.rect.includes(center) =>
             {
+                self.path.clear();
+                self.show_alternates(center, rq);
                 true
             }
-            Event::Gesture(GestureEvent::Swipe { start, .. }) if self.rect.includes(start) => true,
-            Event::Device(DeviceEvent::Finger { position, .. }) if self.rect.includes(position) => {
+            Event::Gesture(GestureEvent::Swipe { start, end, .. }) if self.rect.includes(start) => {
+                self.type_swipe(start, end, hub, rq);
+                true
+            }
+            Event::Gesture(GestureEvent::SlantedSwipe { start, end, .. })
+            | Event::Gesture(GestureEvent::Arrow { start, end, .. })
+            | Event::Gesture(GestureEvent::Corner { start, end, .. })
+                if self.words.is_some() && self.rect.includes(start) =>
+            {
+                self.type_swipe(start, end, hub, rq);
+                true
+            }
+            Event::Device(DeviceEvent::Finger {
+                position,
+                status,
+                time,
+                ..
+            }) if self.rect.includes(position) => {
+                if status == FingerStatus::Motion && self.words.is_some() {
+                    if time - self.path_time > SWIPE_PAUSE {
+                        self.path.clear();
+                    }
+                    self.path_time = time;
+                    self.path.push(position);
+                }
                 true
             }
             _ => false,
@@ -319,7 +777,7 @@ impl View for Keyboard {
         }
 
         if let Some(region) = rect.intersection(&self.rect) {
-            fb.draw_rectangle(&region, KEYBOARD_BG);
+            fb.draw_rectangle(&region, theme::current().keyboard_bg);
         }
     }
 
@@ -334,6 +792,7 @@ impl View for Keyboard {
         rq: &mut RenderQueue,
         context: &mut Context,
     ) {
+        self.close_alternates(rq);
         let dpi = CURRENT_DEVICE.dpi;
         let max_width = self
             .layout
@@ -344,7 +803,10 @@ impl View for Keyboard {
             .expect("Missing row widths");
 
         let kh_1 = (rect.width() as f32) / max_width;
-        let rows_count = self.layout.keys.len();
+        let strip_rows = usize::from(
+            self.words.is_some() || self.terms.is_some() || self.layout.pinyin.is_some(),
+        );
+        let rows_count = self.layout.keys.len() + strip_rows;
         let kh_2 =
             (rect.height() as f32) / (rows_count as f32 + PADDING_RATIO * (rows_count + 1) as f32);
         let key_height = kh_1.min(kh_2);
@@ -359,7 +821,7 @@ impl View for Keyboard {
         let mut index = 0;
 
         for (i, row) in self.layout.keys.iter().enumerate() {
-            let y = start_y + i as f32 * (padding + key_height);
+            let y = start_y + (i + strip_rows) as f32 * (padding + key_height);
             let row_width = (self.layout.widths[i].len() + 1) as f32 * padding
                 + self.layout.widths[i].iter().sum::<f32>() * key_height;
             let start_x = rect.min.x as f32 + padding + (rect.width() as f32 - row_width) / 2.0;
@@ -380,6 +842,16 @@ impl View for Keyboard {
             }
         }
 
+        if strip_rows > 0 {
+            let strip_rect = rect![
+                (rect.min.x as f32 + padding).round() as i32,
+                start_y.round() as i32,
+                (rect.max.x as f32 - padding).round() as i32,
+                (start_y + key_height).round() as i32
+            ];
+            self.children[index].resize(strip_rect, hub, rq, context);
+        }
+
         self.rect = rect;
         context.kb_rect = rect;
     }
@@ -388,6 +860,10 @@ impl View for Keyboard {
         true
     }
 
+    fn elevation(&self) -> Elevation {
+        Elevation::Raised
+    }
+
     fn rect(&self) -> &Rectangle {
         &self.rect
     }
@@ -561,3 +1037,202 @@ lazy_static! {
         m
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_helpers::create_test_context;
+    use crate::geom::Dir;
+    use crate::view::handle_event;
+    use std::collections::VecDeque;
+    use std::sync::mpsc::channel;
+
+    fn key_center(keyboard: &Keyboard, ch: char) -> Point {
+        keyboard
+            .children
+            .iter()
+            .filter_map(|child| child.downcast_ref::<Key>())
+            .find(|key| *key.kind() == KeyKind::Output(ch))
+            .map(|key| key.rect().center())
+            .expect("missing key")
+    }
+
+    #[test]
+    fn test_swiping_across_the_keys_types_a_word() {
+        let mut context = create_test_context();
+        context.load_keyboard_layouts();
+        context.settings.swipe_typing = true;
+        let mut rect = rect![0, 400, 600, 800];
+        let mut keyboard = Keyboard::new(&mut rect, false, &mut context);
+        assert!(keyboard.children.last().unwrap().is::<SuggestionStrip>());
+
+        let (hub, receiver) = channel();
+        let mut bus = VecDeque::new();
+        let mut rq = RenderQueue::new();
+        let path: Vec<Point> = "helo".chars().map(|c| key_center(&keyboard, c)).collect();
+
+        for (i, &position) in path.iter().enumerate() {
+            let motion = Event::Device(DeviceEvent::Finger {
+                id: 0,
+                time: i as f64 * 0.05,
+                status: FingerStatus::Motion,
+                position,
+            });
+            keyboard.handle_event(&motion, &hub, &mut bus, &mut rq, &mut context);
+        }
+
+        let swipe = Event::Gesture(GestureEvent::Swipe {
+            dir: Dir::East,
+            start: path[0],
+            end: path[3],
+        });
+        assert!(keyboard.handle_event(&swipe, &hub, &mut bus, &mut rq, &mut context));
+
+        let typed: String = receiver
+            .try_iter()
+            .filter_map(|evt| match evt {
+                Event::Keyboard(KeyboardEvent::Append(ch)) => Some(ch),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(typed, "hello ");
+        assert!(keyboard.path.is_empty());
+    }
+
+    #[test]
+    fn test_the_current_word_is_completed() {
+        let mut context = create_test_context();
+        context.load_keyboard_layouts();
+        context.settings.word_suggestions = true;
+        let mut rect = rect![0, 400, 600, 800];
+        let mut keyboard = Keyboard::new(&mut rect, false, &mut context);
+        let (hub, receiver) = channel();
+        let mut bus = VecDeque::new();
+        let mut rq = RenderQueue::new();
+
+        for ch in ['t', 'h'] {
+            let evt = Event::Key(KeyKind::Output(ch));
+            keyboard.handle_event(&evt, &hub, &mut bus, &mut rq, &mut context);
+        }
+        assert_eq!(keyboard.suggestions()[0], "the");
+
+        let evt = Event::Select(EntryId::Suggestion("the".to_string()));
+        assert!(keyboard.handle_event(&evt, &hub, &mut bus, &mut rq, &mut context));
+        let typed: String = receiver
+            .try_iter()
+            .filter_map(|evt| match evt {
+                Event::Keyboard(KeyboardEvent::Append(ch)) => Some(ch),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(typed, "the ");
+        assert!(keyboard.suggestions().is_empty());
+    }
+
+    #[test]
+    fn test_pinyin_is_composed_into_chinese() {
+        let mut context = create_test_context();
+        context.load_keyboard_layouts();
+        context.settings.keyboard_layout = "Pinyin".to_string();
+        let mut rect = rect![0, 400, 600, 800];
+        let mut keyboard = Keyboard::new(&mut rect, false, &mut context);
+        let (hub, receiver) = channel();
+        let mut bus = VecDeque::new();
+        let mut rq = RenderQueue::new();
+        let mut press = |keyboard: &mut Keyboard, kind| {
+            let evt = Event::Key(kind);
+            keyboard.handle_event(&evt, &hub, &mut bus, &mut rq, &mut context);
+        };
+
+        for ch in "nihao".chars() {
+            press(&mut keyboard, KeyKind::Output(ch));
+        }
+        assert_eq!(keyboard.suggestions()[..2], ["nihao", "你好"]);
+        assert!(receiver.try_recv().is_err());
+
+        press(&mut keyboard, KeyKind::Delete(LinearDir::Backward));
+        assert_eq!(keyboard.suggestions()[0], "niha");
+        press(&mut keyboard, KeyKind::Output('o'));
+        press(&mut keyboard, KeyKind::Output(' '));
+        assert!(keyboard.suggestions().is_empty());
+
+        press(&mut keyboard, KeyKind::Output('w'));
+        press(&mut keyboard, KeyKind::Output('o'));
+        press(&mut keyboard, KeyKind::Output('!'));
+
+        let typed: String = receiver
+            .try_iter()
+            .filter_map(|evt| match evt {
+                Event::Keyboard(KeyboardEvent::Append(ch)) => Some(ch),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(typed, "你好我!");
+    }
+
+    #[test]
+    fn test_holding_a_key_offers_its_alternates() {
+        let mut context = create_test_context();
+        context.load_keyboard_layouts();
+        let layout = &context.keyboard_layouts["English"];
+        assert_eq!(layout.alternates('e')[0], 'é');
+        assert_eq!(layout.alternates('E')[0], 'É');
+        assert!(layout.alternates('q').is_empty());
+
+        let mut rect = rect![0, 400, 600, 800];
+        let mut keyboard = Keyboard::new(&mut rect, false, &mut context);
+        let (hub, receiver) = channel();
+        let mut bus = VecDeque::new();
+        let mut rq = RenderQueue::new();
+        let center = key_center(&keyboard, 'e');
+
+        let hold = Event::Gesture(GestureEvent::HoldFingerShort(center, 0));
+        assert!(handle_event(
+            &mut keyboard,
+            &hold,
+            &hub,
+            &mut bus,
+            &mut rq,
+            &mut context
+        ));
+        let popup = *keyboard.children.last().unwrap().rect();
+        assert!(keyboard.children.last().unwrap().is::<KeyAlternates>());
+        assert!(popup.max.y <= center.y);
+
+        let cell_width = popup.width() as i32 / 7;
+        let finger = |status, position| {
+            Event::Device(DeviceEvent::Finger {
+                id: 0,
+                time: 1.0,
+                status,
+                position,
+            })
+        };
+        let position = pt!(popup.min.x + cell_width * 3 / 2, popup.center().y);
+        handle_event(
+            &mut keyboard,
+            &finger(FingerStatus::Motion, position),
+            &hub,
+            &mut bus,
+            &mut rq,
+            &mut context,
+        );
+        handle_event(
+            &mut keyboard,
+            &finger(FingerStatus::Up, position),
+            &hub,
+            &mut bus,
+            &mut rq,
+            &mut context,
+        );
+
+        assert!(!keyboard
+            .children
+            .iter()
+            .any(|child| child.is::<KeyAlternates>()));
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(Event::Keyboard(KeyboardEvent::Append('é')))
+        ));
+    }
+}
@@ -1,11 +1,12 @@
 use super::{Align, Bus, Event, Hub, Id, RenderData, RenderQueue, View, ID_FEEDER};
-use crate::color::{Color, TEXT_NORMAL};
+use crate::color::Color;
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::{font_from_style, Fonts, NORMAL_STYLE};
 use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::Rectangle;
 use crate::gesture::GestureEvent;
+use crate::theme;
 
 /// A text label widget that displays a single line of text.
 ///
@@ -41,7 +42,7 @@ impl Label {
             children: Vec::new(),
             text,
             align,
-            scheme: TEXT_NORMAL,
+            scheme: theme::current().text_normal,
             event: None,
             hold_event: None,
         }
@@ -201,6 +202,10 @@ impl View for Label {
         self.rect = rect;
     }
 
+    fn is_focusable(&self) -> bool {
+        self.event.is_some()
+    }
+
     fn rect(&self) -> &Rectangle {
         &self.rect
     }
@@ -1,17 +1,21 @@
+use super::backdrop::Elevation;
 use super::common::locate_by_id;
 use super::filler::Filler;
+use super::icon::ICONS_PIXMAPS;
 use super::menu_entry::MenuEntry;
 use super::{Bus, Event, Hub, RenderData, RenderQueue, View};
 use super::{EntryKind, Id, ViewId, CLOSE_IGNITION_DELAY, ID_FEEDER};
 use super::{BORDER_RADIUS_MEDIUM, SMALL_BAR_HEIGHT, THICKNESS_LARGE, THICKNESS_MEDIUM};
-use crate::color::{BLACK, SEPARATOR_NORMAL, SEPARATOR_STRONG, WHITE};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::{font_from_style, Fonts, NORMAL_STYLE};
 use crate::framebuffer::{Framebuffer, UpdateMode};
-use crate::geom::{big_half, small_half, BorderSpec, CornerSpec, Point, Rectangle};
+use crate::geom::{big_half, small_half, BorderSpec, CornerSpec, CycleDir, Dir, Point, Rectangle};
 use crate::gesture::GestureEvent;
-use crate::unit::scale_by_dpi;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
+use std::mem;
+use std::ops::Range;
 use std::thread;
 
 pub struct Menu {
@@ -20,10 +24,17 @@ pub struct Menu {
     children: Vec<Box<dyn View>>,
     view_id: ViewId,
     kind: MenuKind,
+    target: Rectangle,
     center: Point,
     root: bool,
     sub_id: u8,
     dir: i32,
+    entries: Vec<EntryKind>,
+    // The ranges of the entries shown by each page.
+    pages: Vec<Range<usize>>,
+    current_page: usize,
+    // The row of the page indicator, empty when all the entries fit on the display.
+    pager_rect: Rectangle,
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -33,6 +44,15 @@ pub enum MenuKind {
     Contextual,
 }
 
+// The placement of the entries, shared by all the pages.
+struct Metrics {
+    dir: i32,
+    y_start: i32,
+    entry_height: i32,
+    border_space: i32,
+    max_entries: usize,
+}
+
 // TOP MENU       C
 //    ───         B
 //  ↓  A       ↑  A
@@ -40,35 +60,57 @@ pub enum MenuKind {
 //     C     BOTTOM MENU
 
 impl Menu {
+    /// Creates a menu next to the target.
+    ///
+    /// When the entries don't fit between the target and the edge of the display, they're
+    /// split into pages browsed with the arrows of the last row, or by swiping left and right.
     pub fn new(
         target: Rectangle,
         view_id: ViewId,
         kind: MenuKind,
-        mut entries: Vec<EntryKind>,
+        entries: Vec<EntryKind>,
         context: &mut Context,
     ) -> Menu {
-        let id = ID_FEEDER.next();
-        let mut children = Vec::new();
-        let dpi = CURRENT_DEVICE.dpi;
-        let (width, height) = context.display.dims;
-        let small_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
-
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
-        let border_thickness = scale_by_dpi(THICKNESS_LARGE, dpi) as i32;
-        let border_radius = scale_by_dpi(BORDER_RADIUS_MEDIUM - THICKNESS_LARGE, dpi) as i32;
+        let metrics = Self::metrics(&target, kind, context);
+        let pages = paginate(&entries, metrics.max_entries);
 
-        let sep_color = if context.fb.monochrome() {
-            SEPARATOR_STRONG
-        } else {
-            SEPARATOR_NORMAL
+        let mut menu = Menu {
+            id: ID_FEEDER.next(),
+            rect: Rectangle::default(),
+            children: Vec::new(),
+            view_id,
+            kind,
+            target,
+            center: target.center(),
+            root: true,
+            sub_id: 0,
+            dir: metrics.dir,
+            entries,
+            pages,
+            current_page: 0,
+            pager_rect: Rectangle::default(),
         };
+
+        menu.layout(&metrics, context);
+        menu
+    }
+
+    pub fn root(mut self, root: bool) -> Menu {
+        self.root = root;
+        self
+    }
+
+    fn metrics(target: &Rectangle, kind: MenuKind, context: &mut Context) -> Metrics {
+        let dpi = CURRENT_DEVICE.dpi;
+        let (_, height) = context.display.dims;
+        let small_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
+        let border_thickness = scale_thickness(THICKNESS_LARGE, dpi) as i32;
         let font = font_from_style(&mut context.fonts, &NORMAL_STYLE, dpi);
         let entry_height = font.x_heights.0 as i32 * 5;
-        let padding = 4 * font.em() as i32;
 
         let north_space = target.min.y;
         let south_space = height as i32 - target.max.y;
-        let center = target.center();
 
         let (dir, y_start): (i32, i32) = if kind == MenuKind::SubMenu {
             if north_space < south_space {
@@ -99,32 +141,56 @@ impl Menu {
             2 * border_thickness
         };
 
-        let max_entries = ((usable_space - border_space) / entry_height) as usize;
-        let total_entries = entries.iter().filter(|e| !e.is_separator()).count();
+        // A page holds at least one entry and the page indicator.
+        let max_entries = ((usable_space - border_space) / entry_height).max(2) as usize;
 
-        if total_entries > max_entries {
-            let mut kind_counts = [0, 0];
-            for e in &entries {
-                kind_counts[e.is_separator() as usize] += 1;
-                if kind_counts[0] >= max_entries {
-                    break;
-                }
-            }
-            let index = kind_counts[0] + kind_counts[1] - 1;
-            let more = entries.drain(index..).collect::<Vec<EntryKind>>();
-            entries.push(EntryKind::More(more));
+        Metrics {
+            dir,
+            y_start,
+            entry_height,
+            border_space,
+            max_entries,
         }
+    }
+
+    // Builds the entries of the current page and computes the rectangle of the menu.
+    fn layout(&mut self, metrics: &Metrics, context: &mut Context) {
+        let dpi = CURRENT_DEVICE.dpi;
+        let (width, _) = context.display.dims;
+        let dir = metrics.dir;
+        let entry_height = metrics.entry_height;
+        let border_space = metrics.border_space;
+        let target = self.target;
+        let kind = self.kind;
+
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
+        let border_thickness = scale_thickness(THICKNESS_LARGE, dpi) as i32;
+        let border_radius = scale_by_dpi(BORDER_RADIUS_MEDIUM - THICKNESS_LARGE, dpi) as i32;
+
+        let sep_color = if context.fb.monochrome() {
+            theme::current().separator_strong
+        } else {
+            theme::current().separator_normal
+        };
+        let font = font_from_style(&mut context.fonts, &NORMAL_STYLE, dpi);
+        let padding = 4 * font.em() as i32;
+
+        let paged = self.pages.len() > 1;
+        let entries = &self.entries[self.pages[self.current_page].clone()];
+        let mut children = Vec::new();
 
-        let mut y_pos = y_start + dir * (border_space - border_thickness);
+        let mut y_pos = metrics.y_start + dir * (border_space - border_thickness);
 
+        // The width doesn't change from one page to the next.
         let max_width = 2 * width as i32 / 3;
         let free_width = padding
             + 2 * border_thickness
-            + entries
+            + self
+                .entries
                 .iter()
                 .map(|e| font.plan(e.text(), None, None).width)
                 .max()
-                .unwrap();
+                .unwrap_or(0);
 
         let entry_width = free_width.min(max_width);
 
@@ -138,8 +204,8 @@ impl Menu {
             }
         } else {
             (
-                center.x - small_half(entry_width),
-                center.x + big_half(entry_width),
+                self.center.x - small_half(entry_width),
+                self.center.x + big_half(entry_width),
             )
         };
 
@@ -197,9 +263,9 @@ impl Menu {
                     }
                 }
 
-                let corner_spec = if kind != MenuKind::DropDown && entries_count == 1 {
+                let corner_spec = if kind != MenuKind::DropDown && entries_count == 1 && !paged {
                     Some(CornerSpec::Uniform(border_radius))
-                } else if i == entries_count - 1 {
+                } else if i == entries_count - 1 && !paged {
                     if dir.is_positive() {
                         Some(CornerSpec::South(border_radius))
                     } else {
@@ -223,40 +289,173 @@ impl Menu {
             }
         }
 
+        let mut rows_count = entries.iter().filter(|e| !e.is_separator()).count() as i32;
+
+        self.pager_rect = if paged {
+            rows_count += 1;
+            let (y_min, y_max) = if dir.is_positive() {
+                (y_pos, y_pos + entry_height)
+            } else {
+                (y_pos - entry_height, y_pos)
+            };
+            rect![
+                x_min + border_thickness,
+                y_min,
+                x_max - border_thickness,
+                y_max
+            ]
+        } else {
+            Rectangle::default()
+        };
+
         let triangle_space = if kind == MenuKind::Contextual {
             font.x_heights.1 as i32
         } else {
             0
         };
 
-        let total_entries = entries.iter().filter(|e| !e.is_separator()).count();
-        let menu_height = total_entries as i32 * entry_height + border_space;
+        let menu_height = rows_count * entry_height + border_space;
 
         let (y_min, y_max) = if dir.is_positive() {
-            (y_start - triangle_space, y_start + menu_height)
+            (
+                metrics.y_start - triangle_space,
+                metrics.y_start + menu_height,
+            )
         } else {
-            (y_start - menu_height, y_start + triangle_space)
+            (
+                metrics.y_start - menu_height,
+                metrics.y_start + triangle_space,
+            )
         };
 
-        let rect = rect![x_min, y_min, x_max, y_max];
+        self.rect = rect![x_min, y_min, x_max, y_max];
+        self.children = children;
+    }
 
-        Menu {
-            id,
-            rect,
-            children,
-            view_id,
-            kind,
-            center,
-            root: true,
-            sub_id: 0,
-            dir,
+    // Keeps the values of the check boxes and radio buttons of the current page, which are
+    // held by its entries.
+    fn save_entries(&mut self) {
+        let range = self.pages[self.current_page].clone();
+        let kinds = self
+            .children
+            .iter()
+            .filter_map(|child| child.downcast_ref::<MenuEntry>())
+            .map(|entry| entry.kind().clone());
+        for (entry, kind) in self.entries[range]
+            .iter_mut()
+            .filter(|e| !e.is_separator())
+            .zip(kinds)
+        {
+            *entry = kind;
         }
     }
 
-    pub fn root(mut self, root: bool) -> Menu {
-        self.root = root;
-        self
+    fn go_to_page(&mut self, dir: CycleDir, rq: &mut RenderQueue, context: &mut Context) {
+        self.save_entries();
+        let count = self.pages.len();
+        self.current_page = match dir {
+            CycleDir::Next => (self.current_page + 1) % count,
+            CycleDir::Previous => (self.current_page + count - 1) % count,
+        };
+        let mut rect = self.rect;
+        let metrics = Self::metrics(&self.target, self.kind, context);
+        self.layout(&metrics, context);
+        rect.absorb(&self.rect);
+        rq.add(RenderData::expose(rect, UpdateMode::Gui));
+    }
+
+    // Draws the current page number between the arrows, apart from the entries.
+    fn render_pager(&self, fb: &mut dyn Framebuffer, fonts: &mut Fonts) {
+        let dpi = CURRENT_DEVICE.dpi;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
+        let font = font_from_style(fonts, &NORMAL_STYLE, dpi);
+        let padding = 2 * font.em() as i32;
+        let rect = self.pager_rect;
+        let t = theme::current();
+        let sep_color = if fb.monochrome() {
+            t.separator_strong
+        } else {
+            t.separator_normal
+        };
+
+        let y_sep = if self.dir.is_positive() {
+            rect.min.y
+        } else {
+            rect.max.y
+        };
+        fb.draw_rectangle(
+            &rect![
+                rect.min.x,
+                y_sep - small_half(thickness),
+                rect.max.x,
+                y_sep + big_half(thickness)
+            ],
+            sep_color,
+        );
+
+        let text = format!("{}/{}", self.current_page + 1, self.pages.len());
+        let plan = font.plan(&text, None, None);
+        let dx = (rect.width() as i32 - plan.width) / 2;
+        let dy = (rect.height() as i32 - font.x_heights.0 as i32) / 2;
+        font.render(
+            fb,
+            t.foreground,
+            &plan,
+            pt!(rect.min.x + dx, rect.max.y - dy),
+        );
+
+        for (name, x_center) in [
+            ("angle-left-small", rect.min.x + padding / 2),
+            ("angle-right-small", rect.max.x - padding / 2),
+        ] {
+            if let Some(pixmap) = ICONS_PIXMAPS.get(name) {
+                let pt = pt!(
+                    x_center - pixmap.width as i32 / 2,
+                    rect.min.y + (rect.height() as i32 - pixmap.height as i32) / 2
+                );
+                fb.draw_blended_pixmap(pixmap, pt, t.foreground);
+            }
+        }
+    }
+}
+
+// Splits the entries into pages of at most `max_entries` rows, the page indicator included.
+// The separators at the edges of the pages are dropped.
+fn paginate(entries: &[EntryKind], max_entries: usize) -> Vec<Range<usize>> {
+    let total_entries = entries.iter().filter(|e| !e.is_separator()).count();
+    if total_entries <= max_entries {
+        return vec![Range {
+            start: 0,
+            end: entries.len(),
+        }];
+    }
+
+    let per_page = max_entries.saturating_sub(1).max(1);
+    let mut pages = Vec::new();
+    let mut start = 0;
+
+    while start < entries.len() {
+        if entries[start].is_separator() {
+            start += 1;
+            continue;
+        }
+        let mut end = start;
+        let mut count = 0;
+        while end < entries.len() && count < per_page {
+            if !entries[end].is_separator() {
+                count += 1;
+            }
+            end += 1;
+        }
+        let mut last = end;
+        while entries[last - 1].is_separator() {
+            last -= 1;
+        }
+        pages.push(start..last);
+        start = end;
     }
+
+    pages
 }
 
 impl View for Menu {
@@ -280,12 +479,20 @@ impl View for Menu {
                 );
                 false
             }
-            Event::PropagateSelect(..) => {
+            Event::PropagateSelect(ref other_id) => {
                 for c in &mut self.children {
                     if c.handle_event(evt, hub, bus, rq, context) {
                         break;
                     }
                 }
+                // The radio buttons of the other pages.
+                for entry in &mut self.entries {
+                    if let EntryKind::RadioButton(_, ref id, ref mut value) = *entry.inner_mut() {
+                        if mem::discriminant(id) == mem::discriminant(other_id) && id != other_id {
+                            *value = false;
+                        }
+                    }
+                }
                 true
             }
             Event::Validate if self.root => {
@@ -334,6 +541,28 @@ impl View for Menu {
                 }
                 true
             }
+            Event::Gesture(GestureEvent::Tap(center)) if self.pager_rect.includes(center) => {
+                let dir = if center.x < self.pager_rect.min.x + self.pager_rect.width() as i32 / 3 {
+                    CycleDir::Previous
+                } else {
+                    CycleDir::Next
+                };
+                self.go_to_page(dir, rq, context);
+                true
+            }
+            Event::Gesture(GestureEvent::Swipe { dir, start, .. })
+                if !self.pager_rect.is_empty()
+                    && self.rect.includes(start)
+                    && matches!(dir, Dir::West | Dir::East) =>
+            {
+                let dir = if dir == Dir::West {
+                    CycleDir::Next
+                } else {
+                    CycleDir::Previous
+                };
+                self.go_to_page(dir, rq, context);
+                true
+            }
             Event::Gesture(..) => true,
             _ => false,
         }
@@ -343,7 +572,7 @@ impl View for Menu {
     fn render(&self, fb: &mut dyn Framebuffer, _rect: Rectangle, fonts: &mut Fonts) {
         let dpi = CURRENT_DEVICE.dpi;
         let border_radius = scale_by_dpi(BORDER_RADIUS_MEDIUM, dpi) as i32;
-        let border_thickness = scale_by_dpi(THICKNESS_LARGE, dpi) as u16;
+        let border_thickness = scale_thickness(THICKNESS_LARGE, dpi) as u16;
 
         let corners = if self.kind == MenuKind::DropDown {
             if self.dir.is_positive() {
@@ -371,9 +600,9 @@ impl View for Menu {
                 &corners,
                 &BorderSpec {
                     thickness: border_thickness,
-                    color: BLACK,
+                    color: theme::current().foreground,
                 },
-                &WHITE,
+                &theme::current().background,
             );
 
             let y_b = if self.dir.is_positive() {
@@ -393,31 +622,39 @@ impl View for Menu {
             let mut a = b + pt!(-side, self.dir * side);
             let mut c = a + pt!(2 * side, 0);
 
-            fb.draw_triangle(&[a, b, c], BLACK);
+            fb.draw_triangle(&[a, b, c], theme::current().foreground);
             let drift = (border_thickness as f32 * ::std::f32::consts::SQRT_2) as i32;
 
             b += pt!(0, self.dir * drift);
             a += pt!(drift, 0);
             c -= pt!(drift, 0);
 
-            fb.draw_triangle(&[a, b, c], WHITE);
+            fb.draw_triangle(&[a, b, c], theme::current().background);
         } else {
             fb.draw_rounded_rectangle_with_border(
                 &self.rect,
                 &corners,
                 &BorderSpec {
                     thickness: border_thickness,
-                    color: BLACK,
+                    color: theme::current().foreground,
                 },
-                &WHITE,
+                &theme::current().background,
             );
         }
+
+        if !self.pager_rect.is_empty() {
+            self.render_pager(fb, fonts);
+        }
     }
 
     fn is_background(&self) -> bool {
         true
     }
 
+    fn elevation(&self) -> Elevation {
+        Elevation::Modal
+    }
+
     fn rect(&self) -> &Rectangle {
         &self.rect
     }
@@ -442,3 +679,42 @@ impl View for Menu {
         Some(self.view_id)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::EntryId;
+
+    fn command(name: &str) -> EntryKind {
+        EntryKind::Command(name.to_string(), EntryId::Quit)
+    }
+
+    #[test]
+    fn test_pages_keep_a_row_for_the_indicator() {
+        let entries = vec![
+            command("a"),
+            command("b"),
+            EntryKind::Separator,
+            command("c"),
+            command("d"),
+            command("e"),
+        ];
+
+        assert_eq!(paginate(&entries, 5), vec![Range { start: 0, end: 6 }]);
+        assert_eq!(paginate(&entries, 3), vec![0..2, 3..5, 5..6]);
+        assert_eq!(paginate(&entries, 4), vec![0..4, 4..6]);
+    }
+
+    #[test]
+    fn test_icons_wrap_the_entries() {
+        let mut entry = EntryKind::CheckBox("Fuzzy".to_string(), EntryId::ToggleFuzzy, false)
+            .with_icon("search")
+            .with_icon("plus");
+        assert_eq!(entry.icon(), Some("plus"));
+        assert_eq!(entry.text(), "Fuzzy");
+        entry.set(true);
+        assert_eq!(entry.get(), Some(true));
+        assert!(matches!(entry.inner(), EntryKind::CheckBox(..)));
+        assert!(!entry.is_separator());
+    }
+}
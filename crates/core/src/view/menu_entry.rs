@@ -1,6 +1,5 @@
 use super::icon::ICONS_PIXMAPS;
 use super::{Bus, EntryKind, Event, Hub, Id, RenderData, RenderQueue, View, ID_FEEDER};
-use crate::color::{TEXT_INVERTED_HARD, TEXT_NORMAL};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::{font_from_style, Fonts, NORMAL_STYLE, SPECIAL_STYLE};
@@ -8,6 +7,7 @@ use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::{CornerSpec, Rectangle};
 use crate::gesture::GestureEvent;
 use crate::input::{DeviceEvent, FingerStatus};
+use crate::theme;
 use std::mem;
 
 pub struct MenuEntry {
@@ -40,6 +40,10 @@ impl MenuEntry {
         }
     }
 
+    pub fn kind(&self) -> &EntryKind {
+        &self.kind
+    }
+
     pub fn update(&mut self, value: bool, rq: &mut RenderQueue) {
         if let Some(v) = self.kind.get() {
             if v != value {
@@ -88,7 +92,7 @@ impl View for MenuEntry {
             | Event::Gesture(GestureEvent::HoldFingerShort(center, ..))
                 if self.rect.includes(center) && !self.disabled =>
             {
-                match self.kind {
+                match *self.kind.inner_mut() {
                     EntryKind::CheckBox(_, _, ref mut value) => {
                         *value = !*value;
                         rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
@@ -99,7 +103,7 @@ impl View for MenuEntry {
                     }
                     _ => (),
                 };
-                match self.kind {
+                match *self.kind.inner() {
                     EntryKind::Command(_, ref id)
                     | EntryKind::CheckBox(_, ref id, _)
                     | EntryKind::RadioButton(_, ref id, _) => {
@@ -118,7 +122,7 @@ impl View for MenuEntry {
                 };
                 true
             }
-            Event::PropagateSelect(ref other_id) => match self.kind {
+            Event::PropagateSelect(ref other_id) => match *self.kind.inner_mut() {
                 EntryKind::RadioButton(_, ref id, ref mut value) if *value => {
                     if mem::discriminant(id) == mem::discriminant(other_id) && id != other_id {
                         *value = false;
@@ -137,7 +141,7 @@ impl View for MenuEntry {
     #[cfg_attr(feature = "otel", tracing::instrument(skip(self, fb, fonts, _rect), fields(rect = ?_rect)))]
     fn render(&self, fb: &mut dyn Framebuffer, _rect: Rectangle, fonts: &mut Fonts) {
         let dpi = CURRENT_DEVICE.dpi;
-        let style = if matches!(self.kind, EntryKind::More(..)) {
+        let style = if matches!(self.kind.inner(), EntryKind::More(..)) {
             SPECIAL_STYLE
         } else {
             NORMAL_STYLE
@@ -147,9 +151,9 @@ impl View for MenuEntry {
         let padding = 4 * font.em() as i32;
 
         let scheme = if self.active {
-            TEXT_INVERTED_HARD
+            theme::current().text_inverted_hard
         } else {
-            TEXT_NORMAL
+            theme::current().text_normal
         };
         let foreground = if self.disabled { scheme[2] } else { scheme[1] };
 
@@ -166,7 +170,7 @@ impl View for MenuEntry {
 
         font.render(fb, foreground, &plan, pt);
 
-        let (icon_name, x_offset) = match self.kind {
+        let (icon_name, x_offset) = match *self.kind.inner() {
             EntryKind::CheckBox(_, _, value) if value => ("check_mark", 0),
             EntryKind::RadioButton(_, _, value) if value => ("bullet", 0),
             EntryKind::Message(_, Some(ref name)) => (name.as_str(), 0),
@@ -176,6 +180,19 @@ impl View for MenuEntry {
             _ => ("", 0),
         };
 
+        // The check marks and the bullets take the place of the icon.
+        if let Some(name) = self.kind.icon() {
+            if x_offset > 0 || icon_name.is_empty() {
+                if let Some(pixmap) = ICONS_PIXMAPS.get(name) {
+                    let dx = (padding / 2 - pixmap.width as i32) / 2;
+                    let dy = (self.rect.height() as i32 - pixmap.height as i32) / 2;
+                    let pt = self.rect.min + pt!(dx, dy);
+
+                    fb.draw_blended_pixmap(pixmap, pt, foreground);
+                }
+            }
+        }
+
         if let Some(pixmap) = ICONS_PIXMAPS.get(icon_name) {
             let dx = x_offset + (padding / 2 - pixmap.width as i32) / 2;
             let dy = (self.rect.height() as i32 - pixmap.height as i32) / 2;
@@ -185,6 +202,10 @@ impl View for MenuEntry {
         }
     }
 
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
     fn rect(&self) -> &Rectangle {
         &self.rect
     }
@@ -10,22 +10,32 @@
 //! iterations.
 
 pub mod action_label;
+pub mod backdrop;
 pub mod battery;
+pub mod battery_stats;
+pub mod breadcrumb;
 pub mod button;
 pub mod calculator;
+pub mod captive_portal;
 pub mod clock;
+pub mod command_palette;
 pub mod common;
+pub mod confirm_dialog;
+pub mod dashboard;
 pub mod dialog;
 pub mod dictionary;
 pub mod file_chooser;
 pub mod filler;
+pub mod focus;
 pub mod frontlight;
 pub mod home;
 pub mod icon;
 pub mod image;
 pub mod input_field;
+pub mod inspector;
 pub mod intermission;
 pub mod key;
+pub mod key_alternates;
 pub mod keyboard;
 pub mod label;
 pub mod labeled_icon;
@@ -38,22 +48,38 @@ pub mod ota;
 
 pub use self::notification::NotificationEvent;
 pub mod page_label;
+pub mod perf_hud;
+pub mod pin_pad;
+pub mod pinyin;
 pub mod preset;
 pub mod presets_list;
+pub mod progress_dialog;
+pub use self::progress_dialog::ProgressEvent;
 pub mod reader;
 pub mod rotation_values;
 pub mod rounded_button;
+pub mod screenshots;
 pub mod search_bar;
 pub mod settings_editor;
+pub mod setup_wizard;
 pub mod sketch;
 pub mod slider;
+#[cfg(test)]
+pub mod snapshot;
+pub mod suggestion_strip;
+pub mod swipe_typing;
 pub mod toggle;
 pub mod toggleable_keyboard;
 pub mod top_bar;
 pub mod touch_events;
+pub mod virtual_list;
 
+use self::backdrop::Elevation;
 use self::calculator::LineOrigin;
+use self::command_palette::ActionRegistry;
 use self::key::KeyKind;
+use self::setup_wizard::SetupChoice;
+use crate::bluetooth::BluetoothDevice;
 use crate::color::Color;
 use crate::context::Context;
 use crate::document::{Location, TextLocation};
@@ -61,11 +87,18 @@ use crate::font::Fonts;
 use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::{Boundary, CycleDir, LinearDir, Rectangle};
 use crate::gesture::GestureEvent;
+use crate::i18n::current_direction;
 use crate::input::{DeviceEvent, FingerStatus};
+use crate::kiosk;
+use crate::kosync;
 use crate::metadata::{
     Info, Margin, PageScheme, ScrollMode, SimpleStatus, SortMethod, TextAlign, ZoomMode,
 };
-use crate::settings::{self, ButtonScheme, FirstColumn, RotationLock, SecondColumn};
+use crate::settings::{
+    self, ButtonScheme, FileSortMethod, FirstColumn, Gesture, GestureAction, RotationLock,
+    SecondColumn,
+};
+use crate::speech::SpeechEvent;
 use crate::view::ota::OtaEntryId;
 use downcast_rs::{impl_downcast, Downcast};
 use fxhash::FxHashMap;
@@ -114,6 +147,11 @@ pub trait View: Downcast {
     fn children_mut(&mut self) -> &mut Vec<Box<dyn View>>;
     fn id(&self) -> Id;
 
+    /// The name of the type of the view, shown by the [`inspector`].
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
     fn render_rect(&self, _rect: &Rectangle) -> Rectangle {
         *self.rect()
     }
@@ -155,6 +193,19 @@ pub trait View: Downcast {
     fn view_id(&self) -> Option<ViewId> {
         None
     }
+
+    /// How the view is set apart from the views beneath it, see [`backdrop`].
+    fn elevation(&self) -> Elevation {
+        Elevation::Flat
+    }
+
+    /// Whether the button navigation can move the focus ring to this view.
+    fn is_focusable(&self) -> bool {
+        false
+    }
+
+    /// Adds the actions offered by this view to the command palette.
+    fn contribute_actions(&self, _registry: &mut ActionRegistry, _context: &Context) {}
 }
 
 impl_downcast!(View);
@@ -196,6 +247,9 @@ pub fn handle_event(
             }
         }
 
+        // The events restricted by the kiosk mode go no further than the view that sent them.
+        child_bus.retain(|child_evt| !kiosk::blocks(child_evt, hub, context));
+
         let mut temp_bus: Bus = VecDeque::with_capacity(1);
 
         child_bus
@@ -305,11 +359,15 @@ pub fn process_render_queue(
     context: &mut Context,
     updating: &mut Vec<UpdateData>,
 ) {
-    for ((mode, wait), pairs) in rq.drain() {
+    context.perf_hud.record_queue_depth(rq.len());
+
+    for ((mode, wait), pairs) in rq.batches() {
         let mut ids = FxHashMap::default();
         let mut rects = Vec::new();
         let mut bgs = Vec::new();
 
+        let exposed = pairs.iter().any(|(id, _)| id.is_none());
+
         for (id, rect) in pairs.into_iter().rev() {
             if let Some(id) = id {
                 ids.entry(id).or_insert_with(Vec::new).push(rect);
@@ -318,6 +376,10 @@ pub fn process_render_queue(
             }
         }
 
+        // The backdrop follows the overlays that were opened or closed.
+        bgs.extend(backdrop::track_overlays(view, context));
+
+        let started = Instant::now();
         render(
             view,
             wait,
@@ -328,6 +390,19 @@ pub fn process_render_queue(
             &mut context.fonts,
             updating,
         );
+        if let Some(region) = rects.iter().copied().reduce(|mut a, b| {
+            a.absorb(&b);
+            a
+        }) {
+            context.perf_hud.record_render(region, started.elapsed());
+        }
+
+        backdrop::draw_backdrop(&rects, context);
+        focus::draw_focus_ring(view, &rects, context);
+        inspector::draw_view_tree(view, &rects, context);
+        perf_hud::draw_perf_hud(&mut rects, context);
+
+        let mode = refresh_mode(mode, exposed, rects.len(), context);
 
         for rect in rects {
             match context.fb.update(&rect, mode) {
@@ -346,6 +421,36 @@ pub fn process_render_queue(
     }
 }
 
+// Turns partial updates into a full refresh, following the display settings, to clear the
+// ghosting they leave. The exposed rectangles are left by the closed overlays.
+fn refresh_mode(
+    mode: UpdateMode,
+    exposed: bool,
+    count: usize,
+    context: &mut Context,
+) -> UpdateMode {
+    match mode {
+        UpdateMode::Full => {
+            context.partial_updates = 0;
+            mode
+        }
+        UpdateMode::Gui | UpdateMode::Partial => {
+            let settings = &context.settings.display;
+            context.partial_updates = context.partial_updates.saturating_add(count as u16);
+            let interval = settings.full_refresh_interval;
+            if (exposed && settings.full_refresh_on_close)
+                || (interval > 0 && context.partial_updates >= interval)
+            {
+                context.partial_updates = 0;
+                UpdateMode::Full
+            } else {
+                mode
+            }
+        }
+        UpdateMode::Fast | UpdateMode::FastMono | UpdateMode::Interactive => mode,
+    }
+}
+
 #[inline]
 pub fn wait_for_all(updating: &mut Vec<UpdateData>, context: &mut Context) {
     for update in updating.drain(..) {
@@ -375,12 +480,14 @@ pub enum Event {
     Open(Box<Info>),
     OpenHtml(String, Option<String>),
     LoadPixmap(usize),
+    RefineImage(u32),
     Update(UpdateMode),
     RefreshBookPreview(PathBuf, Option<PathBuf>),
     Invalid(PathBuf),
     Notification(NotificationEvent),
     #[deprecated(note = "Use Event::Notification(NotificationEvent::Show) instead")]
     Notify(String),
+    Progress(ProgressEvent),
     Page(CycleDir),
     ResultsPage(CycleDir),
     GoTo(usize),
@@ -433,8 +540,14 @@ pub enum Event {
     /// hub.send(Event::Focus(None)).ok();
     /// ```
     Focus(Option<ViewId>),
+    /// Moves the focus ring of the button navigation to the next or previous focusable view.
+    MoveFocus(CycleDir),
+    /// Taps the view surrounded by the focus ring.
+    ActivateFocus,
     Select(EntryId),
     PropagateSelect(EntryId),
+    /// Selects the value of the given index of the toggle with the given id.
+    SelectToggleValue(Id, usize),
     EditLanguages,
     Define(String),
     Submit(ViewId, String),
@@ -442,6 +555,7 @@ pub enum Event {
     ToggleNear(ViewId, Rectangle),
     ToggleInputHistoryMenu(ViewId, Rectangle),
     ToggleBookMenu(Rectangle, usize),
+    ToggleDirectoryMenu(Rectangle, PathBuf),
     TogglePresetMenu(Rectangle, usize),
     SubMenu(Rectangle, Vec<EntryKind>),
     OpenSettingsCategory(settings_editor::Category),
@@ -451,6 +565,7 @@ pub enum Event {
     UpdateLibrary(usize, Box<settings::LibrarySettings>),
     AddLibrary,
     DeleteLibrary(usize),
+    DeleteFile(PathBuf),
     ProcessLine(LineOrigin, String),
     History(CycleDir, bool),
     #[deprecated(note = "Use Event::NewToggle(ToggleEvent::View(ViewID)) instead")]
@@ -475,16 +590,56 @@ pub enum Event {
     Finished,
     ClockTick,
     BatteryTick,
+    AutoPageTurnTick,
+    /// Sent to the view with the given id once an interaction that used
+    /// [`UpdateMode::Interactive`] might have ended, to refresh it with a quality update.
+    Settle(Id),
+    /// Shows the next frame of the animation of the view with the given id.
+    NextFrame(Id),
+    Speech(u32, SpeechEvent),
+    SyncProgress(PathBuf, Box<kosync::Progress>),
     ToggleFrontlight,
     Load(PathBuf),
     LoadPreset(usize),
     Scroll(i32),
     Save,
     Guess,
+    ToggleAutoBrightness,
+    /// Time to compare the frontlight with the ambient light.
+    AmbientLightTick,
     CheckBattery,
     SetWifi(bool),
+    SetBluetooth(bool),
+    /// The known Bluetooth devices, once they've been listed or scanned.
+    BluetoothDevices(Vec<BluetoothDevice>),
     MightSuspend,
+    /// Time to check whether the Wi-Fi has been idle for too long.
+    MightDisableWifi,
+    /// Time to check whether the mailbox is due for a check.
+    MightCheckMail,
+    /// Time to check whether the bedtime approaches.
+    BedtimeTick,
+    /// Refreshes the metrics of the performance HUD.
+    PerfHudTick,
+    /// Time to check whether the onboard partition is running out of space.
+    CheckStorage,
+    /// The page of the captive portal in the way, or `None` once the network is open.
+    PortalPage(Option<Box<crate::captive::PortalPage>>),
+    /// A file was added to a library from another device.
+    FileReceived(PathBuf),
+    /// A file was removed from a library by another device.
+    FileRemoved(PathBuf),
+    /// The connection to Calibre is over.
+    CalibreDisconnected,
+    /// A sync with the WebDAV server is over.
+    WebdavSynced(crate::webdav::Report),
+    /// Opens the book with the given path, relative to the current library, for the remote API.
+    RemoteOpen(PathBuf),
+    /// Asks the application for the state of the device, on behalf of the remote API.
+    RemoteStatus(Sender<crate::web_server::api::Status>),
     PrepareSuspend,
+    /// The sleep cover stayed closed for the suspend delay.
+    CoverSuspend,
     Suspend,
     Share,
     PrepareShare,
@@ -498,6 +653,9 @@ pub enum Event {
     /// The file chooser was closed.
     ///  The `Option<PathBuf>` contains the selected path, if any.
     FileChooserClosed(Option<PathBuf>),
+    /// The file chooser was closed in multiple selection mode.
+    ///  The `Vec<PathBuf>` contains the checked files, in order.
+    FileChooserClosedMultiple(Vec<PathBuf>),
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -506,8 +664,12 @@ pub enum AppCmd {
     Calculator,
     Dictionary { query: String, language: String },
     SettingsEditor,
+    Dashboard,
+    BatteryStats,
+    CaptivePortal,
     TouchEvents,
     RotationValues,
+    Screenshots,
 }
 
 #[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
@@ -524,6 +686,7 @@ pub enum ViewId {
     SearchTargetMenu,
     InputHistoryMenu,
     KeyboardLayoutMenu,
+    KeyAlternates,
     Frontlight,
     Dictionary,
     FontSizeMenu,
@@ -540,15 +703,28 @@ pub enum ViewId {
     PresetMenu,
     MarginCropperMenu,
     SearchMenu,
+    CommandPalette,
+    CommandPaletteInput,
     // TODO(ogkevin): merge all these settings editor view IDs into one
     SettingsMenu,
     SettingsValueMenu,
     SettingsCategoryEditor,
     LibraryEditor,
+    TapZonesEditor,
+    GestureMapEditor,
+    BluetoothEditor,
     LibraryRename,
     LibraryRenameInput,
     AutoSuspendInput,
     AutoPowerOffInput,
+    AutoWifiOffInput,
+    CaptivePortalInput,
+    SyncServerInput,
+    SyncUsernameInput,
+    SyncPasswordInput,
+    WebdavServerInput,
+    WebdavUsernameInput,
+    WebdavPasswordInput,
     IntermissionSuspendInput,
     IntermissionPowerOffInput,
     IntermissionShareInput,
@@ -574,14 +750,33 @@ pub enum ViewId {
     AddressBarInput,
     Keyboard,
     AboutDialog,
+    BedtimeDialog,
+    CrashDialog,
+    CleanupMenu,
+    SetupDialog,
+    ResetDialog,
+    BackupsMenu,
+    KioskPin,
+    ConfirmDialog,
     ShareDialog,
+    PortalDialog,
     MarginCropper,
+    ImageViewer,
     TopBottomBars,
     TableOfContents,
     MessageNotif(Id),
+    ProgressDialog(Id),
+    NotificationDrawer,
     SubMenu(u8),
     Ota(ota::OtaViewId),
     FileChooser,
+    FileChooserFilterInput,
+    FileChooserSortMenu,
+    FileChooserNewFolder,
+    FileChooserNewFolderInput,
+    FileChooserRename,
+    FileChooserRenameInput,
+    FileEntryMenu,
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -605,7 +800,7 @@ impl SliderId {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Copy, Clone)]
 pub enum Align {
     Left(i32),
     Right(i32),
@@ -613,9 +808,27 @@ pub enum Align {
 }
 
 impl Align {
+    /// Swaps the left and right alignments.
+    pub fn mirror(self) -> Align {
+        match self {
+            Align::Left(dx) => Align::Right(dx),
+            Align::Right(dx) => Align::Left(dx),
+            Align::Center => Align::Center,
+        }
+    }
+
+    /// Returns the horizontal offset of a text of the given width within its container.
+    ///
+    /// The left and right alignments are swapped when the user interface is laid out from
+    /// right to left.
     #[inline]
     pub fn offset(&self, width: i32, container_width: i32) -> i32 {
-        match *self {
+        let align = if current_direction().is_rtl() {
+            self.mirror()
+        } else {
+            *self
+        };
+        match align {
             Align::Left(dx) => dx,
             Align::Right(dx) => container_width - width - dx,
             Align::Center => (container_width - width) / 2,
@@ -647,6 +860,8 @@ pub enum EntryKind {
     RadioButton(String, EntryId, bool),
     SubMenu(String, Vec<EntryKind>),
     More(Vec<EntryKind>),
+    /// An entry with the named icon in the margin left of its label.
+    Icon(String, Box<EntryKind>),
     Separator,
 }
 
@@ -654,7 +869,23 @@ pub enum EntryKind {
 pub enum EntryId {
     About,
     SystemInfo,
+    Notifications,
+    ClearNotifications,
     OpenDocumentation,
+    ViewCrashReport(PathBuf),
+    ExportCrashReport(PathBuf),
+    CleanUpStorage,
+    Reclaim(PathBuf),
+    ReclaimAll,
+    Setup(SetupChoice),
+    ResetCadmus {
+        backup: bool,
+    },
+    CreateBackup,
+    ShowBackups,
+    SelectBackup(PathBuf),
+    RestoreBackup(PathBuf),
+    ToggleKioskMode,
     LoadLibrary(usize),
     Load(PathBuf),
     Flush,
@@ -671,6 +902,8 @@ pub enum EntryId {
     AddDirectory(PathBuf),
     SelectDirectory(PathBuf),
     ToggleSelectDirectory(PathBuf),
+    PinDirectory(PathBuf),
+    UnpinDirectory(PathBuf),
     SetStatus(PathBuf, SimpleStatus),
     SearchAuthor(String),
     RemovePreset(usize),
@@ -678,6 +911,7 @@ pub enum EntryId {
     SecondColumn(SecondColumn),
     ThumbnailPreviews,
     ApplyCroppings(usize, PageScheme),
+    AutoCropMargins,
     RemoveCroppings,
     SetZoomMode(ZoomMode),
     SetScrollMode(ScrollMode),
@@ -698,16 +932,26 @@ pub enum EntryId {
     SearchDirection(LinearDir),
     SetButtonScheme(ButtonScheme),
     SetFontFamily(String),
+    SetStylesheet(Option<String>),
+    ToggleVerticalText,
+    SetTypographyPreset(usize),
+    UseAsDefault,
+    GoBack,
+    GoForward,
+    SetLibraryTypographyPreset(Option<String>),
     SetFontSize(i32),
     SetTextAlign(TextAlign),
     SetMarginWidth(i32),
     SetLineHeight(i32),
     SetContrastExponent(i32),
     SetContrastGray(i32),
+    SetBrightness(i32),
+    SetAutoPageTurn(Option<u32>),
     SetRotationLock(Option<RotationLock>),
     SetSearchTarget(Option<String>),
     SetInputText(ViewId, String),
     SetKeyboardLayout(String),
+    Suggestion(String),
     // TODO(ogkevin): Make one entryId for settings editor
     EditLibraryName,
     EditLibraryPath,
@@ -715,6 +959,11 @@ pub enum EntryId {
     DeleteLibrary(usize),
     SetIntermission(settings::IntermKind, settings::IntermissionDisplay),
     EditIntermissionImage(settings::IntermKind),
+    EditTapZones,
+    EditGestureMap,
+    SetGestureAction(Gesture, GestureAction),
+    FullRefresh,
+    SetTapAction(settings::TapZone, settings::TapAction),
     ToggleShowHidden,
     #[deprecated(note = "Use ToggleEvent::Settings instead")]
     ToggleSleepCover,
@@ -722,15 +971,52 @@ pub enum EntryId {
     ToggleAutoShare,
     EditAutoSuspend,
     EditAutoPowerOff,
+    EditAutoWifiOff,
+    SetUiScale(u16),
+    SetFullRefreshInterval(u16),
+    SetCoverDithering(settings::DitheringKind),
+    SetImageDithering(settings::DitheringKind),
+    SetStartupRotation(i8),
+    SetCoverCloseAction(settings::CoverCloseAction),
+    SetCoverOpenAction(settings::CoverOpenAction),
+    EditSyncServer,
+    EditSyncUsername,
+    EditSyncPassword,
+    RegisterSyncAccount,
+    AuthorizeSyncAccount,
+    FlushSyncQueue,
+    EditWebdavServer,
+    EditWebdavUsername,
+    EditWebdavPassword,
+    SyncWebdav,
+    ShowWebdavLog,
+    CheckMail,
+    RunHook(usize),
+    ShowDownloads,
+    ToggleDownloads,
+    RetryDownloads,
+    EditBluetoothDevices,
+    ScanBluetoothDevices,
+    PairBluetoothDevice(String),
+    ForgetBluetoothDevice(String),
     ToggleFuzzy,
     ToggleInverted,
     ToggleDithered,
+    ToggleTwoPages,
+    ToggleNightMode,
+    ToggleInspector,
+    TogglePerfHud,
+    ToggleReadAloud,
+    ToggleSpeechPause,
     ToggleWifi,
+    ToggleWebServer,
+    ToggleCalibre,
     Rotate(i8),
     Launch(AppCmd),
     SetPenSize(i32),
     SetPenColor(Color),
     TogglePenDynamism,
+    TogglePenEraser,
     ReloadDictionaries,
     New,
     Refresh,
@@ -739,17 +1025,65 @@ pub enum EntryId {
     Reboot,
     Quit,
     CheckForUpdates,
+    SyncProgress,
+    CheckPortal,
+    EditPortalField(usize),
+    TogglePortalField(usize),
+    SubmitPortalForm(Option<usize>),
+    ShareStorage,
+    SnoozeBedtime,
+    GoToBed,
+    RunAction(usize),
     FileEntry(PathBuf),
+    FileExtension(String),
+    FileChooserSelectAll,
+    FileSort(FileSortMethod),
     Ota(OtaEntryId),
 }
 
 impl EntryKind {
+    /// Shows the named icon, one of `ICONS_PIXMAPS`, left of the label.
+    ///
+    /// The icon of a check box or a radio button is hidden while it's set.
+    pub fn with_icon(self, name: &str) -> EntryKind {
+        EntryKind::Icon(name.to_string(), Box::new(self.inner_owned()))
+    }
+
+    /// Returns the entry stripped of its icon.
+    pub fn inner(&self) -> &EntryKind {
+        match *self {
+            EntryKind::Icon(_, ref kind) => kind.inner(),
+            _ => self,
+        }
+    }
+
+    pub fn inner_mut(&mut self) -> &mut EntryKind {
+        match *self {
+            EntryKind::Icon(_, ref mut kind) => kind.inner_mut(),
+            _ => self,
+        }
+    }
+
+    fn inner_owned(self) -> EntryKind {
+        match self {
+            EntryKind::Icon(_, kind) => kind.inner_owned(),
+            _ => self,
+        }
+    }
+
+    pub fn icon(&self) -> Option<&str> {
+        match *self {
+            EntryKind::Icon(ref name, _) => Some(name),
+            _ => None,
+        }
+    }
+
     pub fn is_separator(&self) -> bool {
-        matches!(*self, EntryKind::Separator)
+        matches!(*self.inner(), EntryKind::Separator)
     }
 
     pub fn text(&self) -> &str {
-        match *self {
+        match *self.inner() {
             EntryKind::Message(ref s, ..)
             | EntryKind::Command(ref s, ..)
             | EntryKind::CheckBox(ref s, ..)
@@ -761,14 +1095,14 @@ impl EntryKind {
     }
 
     pub fn get(&self) -> Option<bool> {
-        match *self {
+        match *self.inner() {
             EntryKind::CheckBox(_, _, v) | EntryKind::RadioButton(_, _, v) => Some(v),
             _ => None,
         }
     }
 
     pub fn set(&mut self, value: bool) {
-        match *self {
+        match *self.inner_mut() {
             EntryKind::CheckBox(_, _, ref mut v) | EntryKind::RadioButton(_, _, ref mut v) => {
                 *v = value
             }
@@ -827,6 +1161,28 @@ impl UpdateData {
     }
 }
 
+/// The order in which the batches of a render queue are flushed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Priority {
+    /// The feedback of a gesture: the fast modes and the updates that don't wait.
+    Urgent,
+    Normal,
+    /// The full refreshes, which flash the screen.
+    Background,
+}
+
+impl Priority {
+    pub fn new(mode: UpdateMode, wait: bool) -> Priority {
+        match mode {
+            UpdateMode::Fast | UpdateMode::FastMono | UpdateMode::Interactive => Priority::Urgent,
+            _ if !wait => Priority::Urgent,
+            UpdateMode::Full => Priority::Background,
+            UpdateMode::Gui | UpdateMode::Partial => Priority::Normal,
+        }
+    }
+}
+
+type Batch = ((UpdateMode, bool), Vec<(Option<Id>, Rectangle)>);
 type RQ = FxHashMap<(UpdateMode, bool), Vec<(Option<Id>, Rectangle)>>;
 pub struct RenderQueue(RQ);
 
@@ -841,17 +1197,73 @@ impl RenderQueue {
             .push((data.id, data.rect));
     }
 
-    #[cfg(test)]
+    /// Drains the queue, most urgent batches first.
+    ///
+    /// Within a batch, the rectangles contained in an exposed rectangle are dropped, and the
+    /// overlapping or contiguous rectangles of the same view are merged. The rectangles of the
+    /// other batches contained in an exposed rectangle of a full refresh are dropped too, since
+    /// the refresh updates them anyway.
+    pub fn batches(&mut self) -> Vec<Batch> {
+        let mut batches: Vec<Batch> = self
+            .drain()
+            .map(|(key, pairs)| (key, coalesce(pairs)))
+            .collect();
+
+        let refreshed: Vec<Rectangle> = batches
+            .iter()
+            .filter(|((mode, _), _)| *mode == UpdateMode::Full)
+            .flat_map(|(_, pairs)| pairs.iter().filter(|(id, _)| id.is_none()).map(|p| p.1))
+            .collect();
+
+        for ((mode, _), pairs) in &mut batches {
+            if *mode != UpdateMode::Full {
+                pairs.retain(|(_, rect)| !refreshed.iter().any(|r| r.contains(rect)));
+            }
+        }
+
+        batches.retain(|(_, pairs)| !pairs.is_empty());
+        batches.sort_by_key(|((mode, wait), _)| Priority::new(*mode, *wait));
+        batches
+    }
+
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
 
-    #[cfg(test)]
     pub fn len(&self) -> usize {
         self.0.values().map(|v| v.len()).sum()
     }
 }
 
+fn coalesce(pairs: Vec<(Option<Id>, Rectangle)>) -> Vec<(Option<Id>, Rectangle)> {
+    let exposed: Vec<Rectangle> = pairs
+        .iter()
+        .filter(|(id, _)| id.is_none())
+        .map(|p| p.1)
+        .collect();
+    let mut result: Vec<(Option<Id>, Rectangle)> = Vec::with_capacity(pairs.len());
+
+    for (id, mut rect) in pairs {
+        // An exposed rectangle renders all the views it intersects.
+        if id.is_some() && exposed.iter().any(|r| r.contains(&rect)) {
+            continue;
+        }
+        while let Some(index) = result.iter().position(|(other_id, other)| {
+            *other_id == id
+                && (other.contains(&rect)
+                    || rect.contains(other)
+                    || other.extends(&rect)
+                    || rect.extends(other))
+        }) {
+            let (_, other) = result.remove(index);
+            rect.absorb(&other);
+        }
+        result.push((id, rect));
+    }
+
+    result
+}
+
 impl Default for RenderQueue {
     fn default() -> Self {
         Self::new()
@@ -885,3 +1297,114 @@ impl IdFeeder {
         self.0.fetch_add(1, Ordering::Relaxed)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_helpers::create_test_context;
+
+    #[test]
+    fn test_render_queue_batches_are_coalesced() {
+        let mut rq = RenderQueue::new();
+        rq.add(RenderData::new(1, rect![0, 0, 100, 50], UpdateMode::Gui));
+        rq.add(RenderData::new(1, rect![0, 0, 100, 50], UpdateMode::Gui));
+        rq.add(RenderData::new(1, rect![0, 50, 100, 100], UpdateMode::Gui));
+        rq.add(RenderData::new(
+            2,
+            rect![300, 300, 350, 350],
+            UpdateMode::Gui,
+        ));
+        rq.add(RenderData::expose(
+            rect![200, 200, 400, 400],
+            UpdateMode::Gui,
+        ));
+        rq.add(RenderData::no_wait(
+            3,
+            rect![0, 500, 50, 550],
+            UpdateMode::Gui,
+        ));
+
+        let batches = rq.batches();
+        assert!(rq.is_empty());
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].0, (UpdateMode::Gui, false));
+        assert_eq!(batches[1].1.len(), 2);
+        assert!(batches[1].1.contains(&(Some(1), rect![0, 0, 100, 100])));
+        assert!(batches[1].1.contains(&(None, rect![200, 200, 400, 400])));
+    }
+
+    #[test]
+    fn test_partial_updates_give_way_to_full_refreshes() {
+        let mut context = create_test_context();
+        assert_eq!(
+            refresh_mode(UpdateMode::Gui, true, 1, &mut context),
+            UpdateMode::Gui
+        );
+
+        context.settings.display.full_refresh_interval = 3;
+        assert_eq!(
+            refresh_mode(UpdateMode::Partial, false, 1, &mut context),
+            UpdateMode::Partial
+        );
+        assert_eq!(
+            refresh_mode(UpdateMode::Fast, false, 4, &mut context),
+            UpdateMode::Fast
+        );
+        assert_eq!(
+            refresh_mode(UpdateMode::Gui, false, 1, &mut context),
+            UpdateMode::Full
+        );
+        assert_eq!(context.partial_updates, 0);
+
+        context.settings.display.full_refresh_on_close = true;
+        assert_eq!(
+            refresh_mode(UpdateMode::Gui, true, 1, &mut context),
+            UpdateMode::Full
+        );
+    }
+
+    #[test]
+    fn test_interactive_updates_are_flushed_first() {
+        let mut context = create_test_context();
+        context.settings.display.full_refresh_interval = 1;
+        assert_eq!(
+            refresh_mode(UpdateMode::Interactive, false, 1, &mut context),
+            UpdateMode::Interactive
+        );
+        assert_eq!(context.partial_updates, 0);
+
+        let mut rq = RenderQueue::new();
+        rq.add(RenderData::new(1, rect![0, 0, 100, 50], UpdateMode::Gui));
+        rq.add(RenderData::no_wait(
+            2,
+            rect![0, 100, 100, 150],
+            UpdateMode::Interactive,
+        ));
+        let batches = rq.batches();
+        assert_eq!(batches[0].0, (UpdateMode::Interactive, false));
+        assert_eq!(batches[1].0, (UpdateMode::Gui, true));
+    }
+
+    #[test]
+    fn test_full_refreshes_come_last_and_subsume() {
+        let mut rq = RenderQueue::new();
+        rq.add(RenderData::expose(rect![0, 0, 600, 400], UpdateMode::Full));
+        rq.add(RenderData::new(
+            1,
+            rect![0, 0, 100, 50],
+            UpdateMode::Partial,
+        ));
+        rq.add(RenderData::new(
+            2,
+            rect![0, 500, 100, 550],
+            UpdateMode::Fast,
+        ));
+
+        let batches = rq.batches();
+        assert!(batches
+            .iter()
+            .all(|((mode, _), _)| *mode != UpdateMode::Partial));
+        assert_eq!(batches[0].0 .0, UpdateMode::Fast);
+        assert_eq!(batches[batches.len() - 1].0 .0, UpdateMode::Full);
+    }
+}
@@ -2,7 +2,7 @@ use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::Fonts;
 use crate::geom::Point;
-use crate::unit::scale_by_dpi;
+use crate::unit::{scale_by_dpi, scale_thickness};
 use crate::view::home::directories_bar::DirectoriesBar;
 use crate::view::navigation::stack_navigation_bar::NavigationProvider;
 use crate::view::{View, SMALL_BAR_HEIGHT, THICKNESS_MEDIUM};
@@ -104,7 +104,7 @@ impl NavigationProvider for DirectoryNavigationProvider {
     fn resize_bar_by(&self, bar: &mut Self::Bar, delta_y: i32, fonts: &mut Fonts) -> i32 {
         let rectangle = *bar.rect();
         let dpi = CURRENT_DEVICE.dpi;
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let min_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32 - thickness;
 
         let y_max = (rectangle.max.y + delta_y).max(rectangle.min.y + min_height);
@@ -122,4 +122,27 @@ impl NavigationProvider for DirectoryNavigationProvider {
     fn shift_bar(&self, bar: &mut Self::Bar, delta: Point) {
         bar.shift(delta);
     }
+
+    fn persistence_key(&self) -> Option<&'static str> {
+        Some("directories")
+    }
+
+    fn pinned_level(&self, context: &Context) -> Option<(Self::LevelKey, Self::LevelData)> {
+        let library = context
+            .settings
+            .libraries
+            .get(context.settings.selected_library)?;
+        let dirs = library
+            .pinned_directories
+            .iter()
+            .map(|dir| context.library.home.join(dir))
+            .filter(|dir| dir.is_dir())
+            .collect::<BTreeSet<PathBuf>>();
+
+        if dirs.is_empty() {
+            return None;
+        }
+
+        Some((context.library.home.clone(), dirs))
+    }
 }
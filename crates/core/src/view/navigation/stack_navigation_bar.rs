@@ -1,10 +1,10 @@
-use crate::color::SEPARATOR_NORMAL;
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::{font_from_style, Fonts, NORMAL_STYLE};
 use crate::framebuffer::Framebuffer;
 use crate::geom::{Dir, Point, Rectangle};
-use crate::unit::scale_by_dpi;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
 use crate::view::filler::Filler;
 use crate::view::UpdateMode;
 use crate::view::{Bus, Event, Hub, Id, RenderData, RenderQueue, View, ID_FEEDER};
@@ -173,6 +173,25 @@ pub trait NavigationProvider {
 
     /// Shift a bar by a delta.
     fn shift_bar(&self, bar: &mut Self::Bar, delta: Point);
+
+    /// Returns the name under which the line counts of resized bars are saved.
+    ///
+    /// The counts are stored in `settings.navigation-bars`, one per level depth, and
+    /// take precedence over `estimate_line_count()` for the levels they cover.
+    /// Providers returning `None` don't persist their bars' heights.
+    fn persistence_key(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Returns the pinned pseudo-level shown above the stack, if any.
+    ///
+    /// The pinned level lists destinations pinned by the user, e.g. favorite
+    /// directories. It isn't part of the hierarchy: the returned key is only used to
+    /// create its bar, which is populated with the returned data through `update_bar()`
+    /// like any other level. Returning `None` hides the pinned level.
+    fn pinned_level(&self, _context: &Context) -> Option<(Self::LevelKey, Self::LevelData)> {
+        None
+    }
 }
 
 /// A vertically-stacked navigation bar with dynamic height and level management.
@@ -226,6 +245,10 @@ pub trait NavigationProvider {
 ///
 /// Minimum height constraints are enforced by the provider to prevent 1px collapse bugs.
 ///
+/// When the provider has a `persistence_key()`, the resulting line count is saved in the
+/// settings for the level's depth, and `set_selected()` restores it the next time a bar
+/// is created for a level at that depth.
+///
 /// # Level Management
 ///
 /// When `set_selected()` is called:
@@ -234,6 +257,12 @@ pub trait NavigationProvider {
 /// 3. Excess bars (beyond `max_levels`) are trimmed
 /// 4. Empty levels are skipped unless they're the selected level
 ///
+/// # Pinned Level
+///
+/// When the provider returns a `pinned_level()`, its bar and separator occupy the first
+/// two children and the levels are laid out below them. The pinned level is rebuilt by
+/// every call to `set_selected()`, and is kept when shrinking.
+///
 /// # Type Parameters
 ///
 /// * `P` - The navigation provider that implements domain-specific traversal logic
@@ -265,6 +294,8 @@ pub struct StackNavigationBar<P: NavigationProvider + 'static> {
     provider: P,
     /// If this bar type should allow resizing via gesture
     enable_resize: bool,
+    /// Whether the first bar and separator show the pinned level
+    pinned: bool,
 }
 
 impl<P: NavigationProvider + 'static> StackNavigationBar<P> {
@@ -295,6 +326,7 @@ impl<P: NavigationProvider + 'static> StackNavigationBar<P> {
             max_levels,
             provider,
             enable_resize: true,
+            pinned: false,
         }
     }
 
@@ -306,6 +338,7 @@ impl<P: NavigationProvider + 'static> StackNavigationBar<P> {
     /// Removes all child bars and separators.
     pub fn clear(&mut self) {
         self.children.clear();
+        self.pinned = false;
     }
 
     /// Returns the currently selected level key.
@@ -341,6 +374,18 @@ impl<P: NavigationProvider + 'static> StackNavigationBar<P> {
     ) {
         let layout = Layout::new(context);
 
+        self.remove_pinned_level();
+        let min_y = self.rect.min.y;
+        let pinned = self.provider.pinned_level(context).and_then(|(key, data)| {
+            let count = self.provider.estimate_line_count(&key, &data).max(1) as i32;
+            let height = count * layout.x_height + (count + 1) * layout.padding / 2;
+            let fits = min_y + height + layout.thickness + layout.min_height <= self.vertical_limit;
+            fits.then_some((key, data, height))
+        });
+        if let Some((_, _, height)) = pinned {
+            self.rect.min.y += height + layout.thickness;
+        }
+
         let first_key = self.first_bar_key();
         let mut last_key = self.last_bar_key();
 
@@ -373,7 +418,8 @@ impl<P: NavigationProvider + 'static> StackNavigationBar<P> {
                     break;
                 };
 
-                let (height, ok) = self.compute_bar_height(&layout, &current, data, y_max);
+                let saved = self.saved_line_count(&current, context);
+                let (height, ok) = self.compute_bar_height(&layout, &current, data, saved, y_max);
                 if !ok {
                     break;
                 }
@@ -408,12 +454,56 @@ impl<P: NavigationProvider + 'static> StackNavigationBar<P> {
             &mut context.fonts,
         );
 
+        self.rect.min.y = min_y;
+        if let Some((key, data, height)) = pinned {
+            self.insert_pinned_level(&layout, &key, &data, height, &selected, &mut context.fonts);
+        }
+
         self.rect.max.y = self.children[self.children.len() - 1].rect().max.y;
         rq.add(RenderData::new(self.id, self.rect, UpdateMode::Partial));
 
         self.selected = selected;
     }
 
+    /// Removes the bar and separator of the pinned level, if present.
+    #[inline]
+    fn remove_pinned_level(&mut self) {
+        if self.pinned {
+            self.children.drain(..2);
+            self.pinned = false;
+        }
+    }
+
+    /// Inserts the bar and separator of the pinned level at the top of the container.
+    #[inline]
+    fn insert_pinned_level(
+        &mut self,
+        layout: &Layout,
+        key: &P::LevelKey,
+        data: &P::LevelData,
+        height: i32,
+        selected: &P::LevelKey,
+        fonts: &mut Fonts,
+    ) {
+        let y_max = self.rect.min.y + height;
+        let sep_rect = rect![
+            self.rect.min.x,
+            y_max,
+            self.rect.max.x,
+            y_max + layout.thickness
+        ];
+        self.children.insert(
+            0,
+            Box::new(Filler::new(sep_rect, theme::current().separator_normal)),
+        );
+
+        let rect = rect![self.rect.min.x, self.rect.min.y, self.rect.max.x, y_max];
+        let mut bar = self.provider.create_bar(rect, key);
+        self.provider.update_bar(&mut bar, data, selected, fonts);
+        self.children.insert(0, Box::new(bar));
+        self.pinned = true;
+    }
+
     #[inline]
     fn first_bar_key(&self) -> Option<P::LevelKey> {
         self.children
@@ -585,8 +675,9 @@ impl<P: NavigationProvider + 'static> StackNavigationBar<P> {
     /// top edge.
     ///
     /// Calculation details:
-    /// - The provider's `estimate_line_count` is used to determine how many lines
-    ///   the bar should display. The count is clamped to a minimum of 1.
+    /// - The line count saved when the user last resized this level is used when
+    ///   present, the provider's `estimate_line_count` otherwise. The count is
+    ///   clamped to a minimum of 1.
     /// - The height formula is:
     ///   height = count * layout.x_height + (count + 1) * layout.padding / 2
     ///   which accounts for per-line x-height and vertical padding between/around lines.
@@ -599,6 +690,7 @@ impl<P: NavigationProvider + 'static> StackNavigationBar<P> {
     /// Parameters:
     /// - `layout` : Precomputed layout metrics (x_height, padding, thickness).
     /// - `key` / `data` : Provider-specific level identifier and data used to estimate lines.
+    /// - `saved` : The persisted line count of the level, if any.
     /// - `y_max` : The candidate bottom y coordinate (inclusive) where the bar would end.
     ///
     /// Returns:
@@ -610,9 +702,12 @@ impl<P: NavigationProvider + 'static> StackNavigationBar<P> {
         layout: &Layout,
         key: &P::LevelKey,
         data: &P::LevelData,
+        saved: Option<usize>,
         y_max: i32,
     ) -> (i32, bool) {
-        let count = self.provider.estimate_line_count(key, data).max(1) as i32;
+        let count = saved
+            .unwrap_or_else(|| self.provider.estimate_line_count(key, data))
+            .max(1) as i32;
         let height = count * layout.x_height + (count + 1) * layout.padding / 2;
 
         if y_max - height - layout.thickness < self.rect.min.y {
@@ -679,8 +774,10 @@ impl<P: NavigationProvider + 'static> StackNavigationBar<P> {
                 self.rect.max.x,
                 *y_max
             ];
-            self.children
-                .insert(*index, Box::new(Filler::new(sep_rect, SEPARATOR_NORMAL)));
+            self.children.insert(
+                *index,
+                Box::new(Filler::new(sep_rect, theme::current().separator_normal)),
+            );
             *y_max -= layout.thickness;
 
             return;
@@ -692,8 +789,10 @@ impl<P: NavigationProvider + 'static> StackNavigationBar<P> {
             self.rect.max.x,
             *y_max
         ];
-        self.children
-            .insert(*index, Box::new(Filler::new(sep_rect, SEPARATOR_NORMAL)));
+        self.children.insert(
+            *index,
+            Box::new(Filler::new(sep_rect, theme::current().separator_normal)),
+        );
         *y_max -= layout.thickness;
 
         let rect = rect![self.rect.min.x, *y_max - height, self.rect.max.x, *y_max];
@@ -780,6 +879,67 @@ impl<P: NavigationProvider + 'static> StackNavigationBar<P> {
         rq.add(RenderData::new(self.id, self.rect, UpdateMode::Partial));
     }
 
+    /// Returns the number of parent hops from `key` to the root.
+    #[inline]
+    fn depth(&self, key: &P::LevelKey, context: &Context) -> usize {
+        let mut depth = 0;
+        let mut current = key.clone();
+
+        while depth < 128 && !self.provider.is_root(&current, context) {
+            let Some(parent) = self.provider.parent(&current) else {
+                break;
+            };
+            current = parent;
+            depth += 1;
+        }
+
+        depth
+    }
+
+    /// Returns the line count saved for the level of `key`, if any.
+    #[inline]
+    fn saved_line_count(&self, key: &P::LevelKey, context: &Context) -> Option<usize> {
+        let name = self.provider.persistence_key()?;
+        let depth = self.depth(key, context);
+
+        context
+            .settings
+            .navigation_bars
+            .get(name)
+            .and_then(|counts| counts.get(depth))
+            .copied()
+            .filter(|count| *count > 0)
+    }
+
+    /// Saves the line count of the bar at `child_index` after the user resized it.
+    fn save_line_count(&self, child_index: usize, context: &mut Context) {
+        let Some(name) = self.provider.persistence_key() else {
+            return;
+        };
+        if self.pinned && child_index == 0 {
+            return;
+        }
+        let Some(bar) = self.children[child_index].downcast_ref::<P::Bar>() else {
+            return;
+        };
+
+        let layout = Layout::new(context);
+        let height = bar.rect().height() as i32;
+        let count = ((height - layout.padding / 2) / (layout.x_height + layout.padding / 2)).max(1)
+            as usize;
+        let depth = self.depth(&self.provider.bar_key(bar), context);
+
+        let counts = context
+            .settings
+            .navigation_bars
+            .entry(name.to_string())
+            .or_default();
+        if counts.len() <= depth {
+            counts.resize(depth + 1, 0);
+        }
+        counts[depth] = count;
+    }
+
     /// Shifts the entire navigation bar and all its children by a delta.
     ///
     /// This is typically used when repositioning the bar within the parent view.
@@ -800,7 +960,7 @@ impl<P: NavigationProvider + 'static> StackNavigationBar<P> {
     /// This method proportionally shrinks all bars based on their available space
     /// (height minus minimum height). Bars that cannot shrink further are left at
     /// minimum height. If needed, entire bar+separator pairs are removed from the
-    /// top of the stack.
+    /// top of the stack, the pinned level excepted.
     ///
     /// # Arguments
     ///
@@ -833,13 +993,16 @@ impl<P: NavigationProvider + 'static> StackNavigationBar<P> {
             }
         }
 
-        while self.children.len() > 1 && y_shift > delta_y {
+        // The levels are removed from the top, below the pinned level.
+        let start = if self.pinned { 2 } else { 0 };
+
+        while self.children.len() > start + 1 && y_shift > delta_y {
             let mut dy = 0;
-            for child in self.children.drain(0..2) {
+            for child in self.children.drain(start..start + 2) {
                 dy += child.rect().height() as i32;
             }
 
-            for child in &mut self.children {
+            for child in &mut self.children[start..] {
                 if let Some(bar) = child.downcast_mut::<P::Bar>() {
                     self.provider.shift_bar(bar, pt!(0, -dy));
                 } else {
@@ -914,7 +1077,7 @@ struct Layout {
 impl Layout {
     fn new(context: &mut Context) -> Self {
         let dpi = CURRENT_DEVICE.dpi;
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let min_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32 - thickness;
         let font = font_from_style(&mut context.fonts, &NORMAL_STYLE, dpi);
         let x_height = font.x_heights.0 as i32;
@@ -930,7 +1093,7 @@ impl Layout {
 
     fn new_for_fonts(fonts: &mut Fonts) -> Self {
         let dpi = CURRENT_DEVICE.dpi;
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let min_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32 - thickness;
         let font = font_from_style(fonts, &NORMAL_STYLE, dpi);
         let x_height = font.x_heights.0 as i32;
@@ -1018,6 +1181,7 @@ impl<P: NavigationProvider + 'static> View for StackNavigationBar<P> {
                         if let Some(index) = bar_index {
                             let delta_y = end.y - start.y;
                             let resized = self.resize_child(index, delta_y, &mut context.fonts);
+                            self.save_line_count(index, context);
                             bus.push_back(Event::NavigationBarResized(resized));
                         }
 
@@ -1057,11 +1221,16 @@ impl<P: NavigationProvider + 'static> View for StackNavigationBar<P> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::color::SEPARATOR_NORMAL;
 
     #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
     struct Key(i32);
 
-    struct Provider;
+    #[derive(Default)]
+    struct Provider {
+        /// Line count of the pinned level, hidden when `None`.
+        pinned: Option<usize>,
+    }
 
     impl NavigationProvider for Provider {
         type LevelKey = Key;
@@ -1118,7 +1287,7 @@ mod tests {
         fn resize_bar_by(&self, bar: &mut Self::Bar, delta_y: i32, _fonts: &mut Fonts) -> i32 {
             let rect = *bar.rect();
             let dpi = CURRENT_DEVICE.dpi;
-            let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+            let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
             let min_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32 - thickness;
 
             let y_max = (rect.max.y + delta_y).max(rect.min.y + min_height);
@@ -1132,11 +1301,19 @@ mod tests {
         fn shift_bar(&self, bar: &mut Self::Bar, delta: Point) {
             *bar.rect_mut() += delta;
         }
+
+        fn persistence_key(&self) -> Option<&'static str> {
+            Some("test")
+        }
+
+        fn pinned_level(&self, _context: &Context) -> Option<(Self::LevelKey, Self::LevelData)> {
+            self.pinned.map(|count| (Key(-1), count))
+        }
     }
 
     #[test]
     fn closest_ancestor_count_is_distance() {
-        let provider = Provider;
+        let provider = Provider::default();
         let last = Key(5);
         let selected = Key(3);
 
@@ -1148,7 +1325,7 @@ mod tests {
 
     #[test]
     fn closest_ancestor_is_none_when_unrelated() {
-        let provider = Provider;
+        let provider = Provider::default();
         let last = Key(5);
         let selected = Key(-1);
 
@@ -1196,7 +1373,7 @@ mod tests {
     fn set_selected_with_single_child_no_panic() {
         let mut context = create_test_context_for_nav_bar();
 
-        let provider = Provider;
+        let provider = Provider::default();
         let rect = rect![0, 0, 600, 100];
         let mut nav_bar = StackNavigationBar::new(rect, rect.max.y, 5, provider, Key(0));
         let mut rq = RenderQueue::new();
@@ -1212,7 +1389,7 @@ mod tests {
     fn set_selected_from_empty_state() {
         let mut context = create_test_context_for_nav_bar();
 
-        let provider = Provider;
+        let provider = Provider::default();
         let rect = rect![0, 0, 600, 100];
         let mut nav_bar = StackNavigationBar::new(rect, rect.max.y, 5, provider, Key(0));
         let mut rq = RenderQueue::new();
@@ -1229,7 +1406,7 @@ mod tests {
     fn set_selected_reuses_existing_bars() {
         let mut context = create_test_context_for_nav_bar();
 
-        let provider = Provider;
+        let provider = Provider::default();
         let rect = rect![0, 0, 600, 200];
         let mut nav_bar = StackNavigationBar::new(rect, rect.max.y, 5, provider, Key(0));
         let mut rq = RenderQueue::new();
@@ -1247,7 +1424,7 @@ mod tests {
     fn set_selected_to_parent_reduces_bars() {
         let mut context = create_test_context_for_nav_bar();
 
-        let provider = Provider;
+        let provider = Provider::default();
         let rect = rect![0, 0, 600, 200];
         let mut nav_bar = StackNavigationBar::new(rect, rect.max.y, 5, provider, Key(0));
         let mut rq = RenderQueue::new();
@@ -1265,7 +1442,7 @@ mod tests {
     fn set_selected_handles_max_levels() {
         let mut context = create_test_context_for_nav_bar();
 
-        let provider = Provider;
+        let provider = Provider::default();
         let rect = rect![0, 0, 600, 200];
         let max_levels = 3;
         let mut nav_bar = StackNavigationBar::new(rect, rect.max.y, max_levels, provider, Key(0));
@@ -1280,7 +1457,7 @@ mod tests {
     fn resize_child_with_aggressive_north_swipe_maintains_minimum_height() {
         let mut context = create_test_context_for_nav_bar();
 
-        let provider = Provider;
+        let provider = Provider::default();
         let rect = rect![0, 68, 600, 590];
         let vertical_limit = 642;
         let mut nav_bar = StackNavigationBar::new(rect, vertical_limit, 1, provider, Key(0));
@@ -1296,7 +1473,7 @@ mod tests {
         nav_bar.resize_child(0, aggressive_delta_y, &mut context.fonts);
 
         let dpi = CURRENT_DEVICE.dpi;
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let min_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32 - thickness;
 
         let final_child_rect = *nav_bar.children[0].rect();
@@ -1328,7 +1505,7 @@ mod tests {
     fn shrink_proportionally_distributes_across_multiple_bars() {
         let mut context = create_test_context_for_nav_bar();
 
-        let provider = Provider;
+        let provider = Provider::default();
         let rect = rect![0, 0, 600, 400];
         let mut nav_bar = StackNavigationBar::new(rect, rect.max.y, 5, provider, Key(0));
         let mut rq = RenderQueue::new();
@@ -1368,7 +1545,7 @@ mod tests {
     fn shrink_removes_bars_when_exceeding_available_space() {
         let mut context = create_test_context_for_nav_bar();
 
-        let provider = Provider;
+        let provider = Provider::default();
         let rect = rect![0, 0, 600, 300];
         let mut nav_bar = StackNavigationBar::new(rect, rect.max.y, 5, provider, Key(0));
         let mut rq = RenderQueue::new();
@@ -1393,7 +1570,7 @@ mod tests {
     fn shrink_handles_all_bars_at_minimum_height() {
         let mut context = create_test_context_for_nav_bar();
 
-        let provider = Provider;
+        let provider = Provider::default();
         let rect = rect![0, 0, 600, 100];
         let mut nav_bar = StackNavigationBar::new(rect, rect.max.y, 2, provider, Key(0));
         let mut rq = RenderQueue::new();
@@ -1401,7 +1578,7 @@ mod tests {
         nav_bar.set_selected(Key(1), &mut rq, &mut context);
 
         let dpi = CURRENT_DEVICE.dpi;
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let min_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32 - thickness;
 
         for i in (0..nav_bar.children.len()).step_by(2) {
@@ -1422,7 +1599,7 @@ mod tests {
     fn resize_child_expansion_respects_vertical_limit() {
         let mut context = create_test_context_for_nav_bar();
 
-        let provider = Provider;
+        let provider = Provider::default();
         let rect = rect![0, 0, 600, 200];
         let vertical_limit = 250;
         let mut nav_bar = StackNavigationBar::new(rect, vertical_limit, 3, provider, Key(0));
@@ -1457,7 +1634,7 @@ mod tests {
     fn resize_child_expansion_shifts_subsequent_children() {
         let mut context = create_test_context_for_nav_bar();
 
-        let provider = Provider;
+        let provider = Provider::default();
         let rect = rect![0, 0, 600, 300];
         let mut nav_bar = StackNavigationBar::new(rect, rect.max.y, 5, provider, Key(0));
         let mut rq = RenderQueue::new();
@@ -1499,7 +1676,7 @@ mod tests {
     fn shift_moves_all_children_and_container() {
         let mut context = create_test_context_for_nav_bar();
 
-        let provider = Provider;
+        let provider = Provider::default();
         let rect = rect![0, 0, 600, 200];
         let mut nav_bar = StackNavigationBar::new(rect, rect.max.y, 3, provider, Key(0));
         let mut rq = RenderQueue::new();
@@ -1536,7 +1713,7 @@ mod tests {
 
         let mut context = create_test_context_for_nav_bar();
 
-        let provider = Provider;
+        let provider = Provider::default();
         let rect = rect![0, 100, 600, 300];
         let mut nav_bar = StackNavigationBar::new(rect, rect.max.y, 3, provider, Key(0));
         let mut rq = RenderQueue::new();
@@ -1575,7 +1752,7 @@ mod tests {
 
         let mut context = create_test_context_for_nav_bar();
 
-        let provider = Provider;
+        let provider = Provider::default();
         let rect = rect![0, 100, 600, 300];
         let mut nav_bar = StackNavigationBar::new(rect, rect.max.y, 3, provider, Key(0));
         let mut rq = RenderQueue::new();
@@ -1614,7 +1791,7 @@ mod tests {
 
         let mut context = create_test_context_for_nav_bar();
 
-        let provider = Provider;
+        let provider = Provider::default();
         let rect = rect![0, 100, 600, 300];
         let mut nav_bar = StackNavigationBar::new(rect, rect.max.y, 3, provider, Key(0));
         let mut rq = RenderQueue::new();
@@ -1648,7 +1825,7 @@ mod tests {
 
         let mut context = create_test_context_for_nav_bar();
 
-        let provider = Provider;
+        let provider = Provider::default();
         let rect = rect![0, 100, 600, 300];
         let mut nav_bar = StackNavigationBar::new(rect, rect.max.y, 3, provider, Key(0));
         let mut rq = RenderQueue::new();
@@ -1677,7 +1854,7 @@ mod tests {
     fn set_selected_handles_vertical_limit_constraint() {
         let mut context = create_test_context_for_nav_bar();
 
-        let provider = Provider;
+        let provider = Provider::default();
         let rect = rect![0, 0, 600, 50];
         let vertical_limit = 100;
         let mut nav_bar = StackNavigationBar::new(rect, vertical_limit, 10, provider, Key(0));
@@ -1695,4 +1872,86 @@ mod tests {
             "Should have at least one bar even with tight constraints"
         );
     }
+
+    #[test]
+    fn resized_line_count_is_saved_and_restored() {
+        use crate::gesture::GestureEvent;
+
+        let mut context = create_test_context_for_nav_bar();
+        let layout = Layout::new(&mut context);
+        let height = |count: i32| count * layout.x_height + (count + 1) * layout.padding / 2;
+
+        context
+            .settings
+            .navigation_bars
+            .insert("test".to_string(), vec![0, 0, 3]);
+
+        let rect = rect![0, 100, 600, 700];
+        let mut nav_bar = StackNavigationBar::new(rect, rect.max.y, 3, Provider::default(), Key(0));
+        let mut rq = RenderQueue::new();
+        nav_bar.set_selected(Key(2), &mut rq, &mut context);
+
+        let leaf = nav_bar.children.len() - 1;
+        assert_eq!(nav_bar.children[leaf].rect().height() as i32, height(3));
+        assert_eq!(nav_bar.children[0].rect().height() as i32, height(1));
+
+        let (hub, _rx) = std::sync::mpsc::channel();
+        let mut bus = std::collections::VecDeque::new();
+        let start = nav_bar.children[0].rect().center();
+        let event = Event::Gesture(GestureEvent::Swipe {
+            dir: Dir::South,
+            start,
+            end: start + pt!(0, height(2)),
+        });
+        nav_bar.handle_event(&event, &hub, &mut bus, &mut rq, &mut context);
+
+        // The test provider reports every bar as the root level.
+        let saved = context.settings.navigation_bars["test"][0];
+        assert_eq!(
+            height(saved as i32),
+            nav_bar.children[0].rect().height() as i32
+        );
+        assert!(saved > 1);
+    }
+
+    #[test]
+    fn pinned_level_stays_on_top() {
+        let mut context = create_test_context_for_nav_bar();
+        let layout = Layout::new(&mut context);
+        let height = |count: i32| count * layout.x_height + (count + 1) * layout.padding / 2;
+
+        let provider = Provider { pinned: Some(2) };
+        let rect = rect![0, 100, 600, 700];
+        let mut nav_bar = StackNavigationBar::new(rect, rect.max.y, 3, provider, Key(0));
+        let mut rq = RenderQueue::new();
+
+        nav_bar.set_selected(Key(2), &mut rq, &mut context);
+        let children_count = nav_bar.children.len();
+        assert!(nav_bar.pinned);
+        assert_eq!(
+            *nav_bar.children[0].rect(),
+            rect![0, 100, 600, 100 + height(2)]
+        );
+        assert_eq!(
+            nav_bar.children[2].rect().min.y,
+            nav_bar.children[1].rect().max.y
+        );
+
+        nav_bar.set_selected(Key(3), &mut rq, &mut context);
+        assert_eq!(nav_bar.children.len(), children_count);
+        assert_eq!(nav_bar.children[0].rect().height() as i32, height(2));
+
+        let shrunk = nav_bar.shrink(-10_000, &mut context.fonts);
+        assert!(shrunk < 0);
+        assert_eq!(
+            nav_bar.children.len(),
+            3,
+            "The pinned level and one level remain"
+        );
+        assert_eq!(nav_bar.children[0].rect().min.y, 100);
+        assert_eq!(
+            nav_bar.children[2].rect().min.y,
+            nav_bar.children[1].rect().max.y
+        );
+    }
 }
@@ -0,0 +1,158 @@
+use super::Notification;
+use crate::context::Context;
+use crate::view::{Hub, RenderQueue, View, ViewId};
+use chrono::{DateTime, Local};
+use std::collections::VecDeque;
+
+// Number of toasts shown at the same time.
+const MAX_VISIBLE_TOASTS: u8 = 3;
+// Number of messages kept in the history.
+const HISTORY_SIZE: usize = 64;
+
+/// A message that was shown.
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub time: DateTime<Local>,
+    pub text: String,
+}
+
+/// Queues the toasts and keeps the history of the messages shown.
+#[derive(Debug, Default)]
+pub struct NotificationCenter {
+    pending: VecDeque<String>,
+    // The toasts on screen and the slots they occupy.
+    visible: Vec<(ViewId, u8)>,
+    // The most recent messages come first.
+    history: VecDeque<HistoryEntry>,
+}
+
+impl NotificationCenter {
+    pub fn record(&mut self, text: &str) {
+        self.history.push_front(HistoryEntry {
+            time: Local::now(),
+            text: text.to_string(),
+        });
+        self.history.truncate(HISTORY_SIZE);
+    }
+
+    /// Returns the messages shown, the most recent first.
+    pub fn history(&self) -> impl Iterator<Item = &HistoryEntry> {
+        self.history.iter()
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    fn free_slot(&self) -> Option<u8> {
+        (0..MAX_VISIBLE_TOASTS).find(|slot| self.visible.iter().all(|(_, s)| s != slot))
+    }
+
+    // Returns the next toast to show and its slot, if a slot is free.
+    fn next_toast(&mut self) -> Option<(String, u8)> {
+        let slot = self.free_slot()?;
+        let text = self.pending.pop_front()?;
+        Some((text, slot))
+    }
+
+    // Returns whether the given view was a toast.
+    fn release(&mut self, id: ViewId) -> bool {
+        let len = self.visible.len();
+        self.visible.retain(|(view_id, _)| *view_id != id);
+        self.visible.len() < len
+    }
+}
+
+/// Queues a toast and shows it if there's room for it.
+pub fn notify(
+    view: &mut dyn View,
+    text: String,
+    hub: &Hub,
+    rq: &mut RenderQueue,
+    context: &mut Context,
+) {
+    context.notifications.pending.push_back(text);
+    show_pending(view, hub, rq, context);
+}
+
+/// Frees the slot of the given toast and shows the next queued toast.
+///
+/// Returns `false` if the view isn't a toast.
+pub fn dismiss_toast(
+    view: &mut dyn View,
+    id: ViewId,
+    hub: &Hub,
+    rq: &mut RenderQueue,
+    context: &mut Context,
+) -> bool {
+    if !context.notifications.release(id) {
+        return false;
+    }
+    show_pending(view, hub, rq, context);
+    true
+}
+
+fn show_pending(view: &mut dyn View, hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
+    while let Some((text, slot)) = context.notifications.next_toast() {
+        let notif = Notification::toast(text, slot, hub, rq, context);
+        if let Some(view_id) = notif.view_id() {
+            context.notifications.visible.push((view_id, slot));
+        }
+        view.children_mut().push(Box::new(notif) as Box<dyn View>);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toasts_are_queued() {
+        let mut center = NotificationCenter::default();
+        for text in ["a", "b", "c", "d"] {
+            center.pending.push_back(text.to_string());
+        }
+
+        let mut shown = Vec::new();
+        while let Some((text, slot)) = center.next_toast() {
+            center
+                .visible
+                .push((ViewId::MessageNotif(shown.len() as u64), slot));
+            shown.push((text, slot));
+        }
+
+        assert_eq!(
+            shown,
+            [
+                ("a".to_string(), 0),
+                ("b".to_string(), 1),
+                ("c".to_string(), 2)
+            ]
+        );
+        assert_eq!(center.pending_count(), 1);
+
+        assert!(center.release(ViewId::MessageNotif(1)));
+        assert!(!center.release(ViewId::MessageNotif(1)));
+        assert_eq!(center.next_toast(), Some(("d".to_string(), 1)));
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut center = NotificationCenter::default();
+        for index in 0..HISTORY_SIZE + 2 {
+            center.record(&index.to_string());
+        }
+
+        assert_eq!(center.history().count(), HISTORY_SIZE);
+        assert_eq!(
+            center.history().next().map(|entry| entry.text.clone()),
+            Some((HISTORY_SIZE + 1).to_string())
+        );
+        center.clear_history();
+        assert_eq!(center.history().count(), 0);
+    }
+}
@@ -0,0 +1,219 @@
+use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::font::Fonts;
+use crate::framebuffer::{Framebuffer, UpdateMode};
+use crate::geom::Rectangle;
+use crate::gesture::GestureEvent;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
+use crate::view::button::Button;
+use crate::view::common::locate_by_id;
+use crate::view::filler::Filler;
+use crate::view::label::Label;
+use crate::view::{Align, Bus, EntryId, Event, Hub, Id, RenderData, RenderQueue, View, ViewId};
+use crate::view::{ID_FEEDER, SMALL_BAR_HEIGHT, THICKNESS_MEDIUM};
+
+/// Lists the messages shown recently, below the top edge of the screen.
+pub struct NotificationDrawer {
+    id: Id,
+    rect: Rectangle,
+    children: Vec<Box<dyn View>>,
+}
+
+impl NotificationDrawer {
+    pub fn new(context: &mut Context) -> NotificationDrawer {
+        let id = ID_FEEDER.next();
+        let (rect, children) = Self::build(context);
+        NotificationDrawer { id, rect, children }
+    }
+
+    fn build(context: &mut Context) -> (Rectangle, Vec<Box<dyn View>>) {
+        let dpi = CURRENT_DEVICE.dpi;
+        let (width, height) = context.display.dims;
+        let (width, height) = (width as i32, height as i32);
+        let row_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
+        let padding = row_height / 3;
+
+        // The drawer covers at most half of the screen.
+        let max_rows = ((height / 2 - 2 * row_height - thickness) / row_height).max(1) as usize;
+        let rows = context.notifications.history().count().clamp(1, max_rows);
+        let drawer_height = (rows as i32 + 2) * row_height + thickness;
+        let rect = rect![0, 0, width, drawer_height];
+
+        let mut children = Vec::new();
+
+        let header = Label::new(
+            rect![0, 0, width, row_height],
+            "Notifications".to_string(),
+            Align::Left(padding),
+        )
+        .scheme(theme::current().text_bump_small);
+        children.push(Box::new(header) as Box<dyn View>);
+
+        let mut y_pos = row_height;
+
+        if context.notifications.history().next().is_none() {
+            let label = Label::new(
+                rect![0, y_pos, width, y_pos + row_height],
+                "No notifications".to_string(),
+                Align::Center,
+            );
+            children.push(Box::new(label) as Box<dyn View>);
+            y_pos += row_height;
+        }
+
+        for entry in context.notifications.history().take(rows) {
+            let text = format!("{}  {}", entry.time.format("%H:%M"), entry.text);
+            let label = Label::new(
+                rect![0, y_pos, width, y_pos + row_height],
+                text,
+                Align::Left(padding),
+            );
+            children.push(Box::new(label) as Box<dyn View>);
+            y_pos += row_height;
+        }
+
+        let button_width = width / 4;
+        for (index, (text, event)) in [
+            ("Clear", Event::Select(EntryId::ClearNotifications)),
+            ("Close", Event::Close(ViewId::NotificationDrawer)),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let x_center = (2 * index as i32 + 1) * width / 4;
+            let button = Button::new(
+                rect![
+                    x_center - button_width / 2,
+                    y_pos + padding / 2,
+                    x_center + button_width / 2,
+                    y_pos + row_height - padding / 2
+                ],
+                event,
+                text.to_string(),
+            );
+            children.push(Box::new(button) as Box<dyn View>);
+        }
+
+        y_pos += row_height;
+
+        let separator = Filler::new(
+            rect![0, y_pos, width, y_pos + thickness],
+            theme::current().foreground,
+        );
+        children.push(Box::new(separator) as Box<dyn View>);
+
+        (rect, children)
+    }
+
+    fn rebuild(&mut self, rq: &mut RenderQueue, context: &mut Context) {
+        let previous = self.rect;
+        let (rect, children) = Self::build(context);
+        self.rect = rect;
+        self.children = children;
+        rq.add(RenderData::expose(previous, UpdateMode::Gui));
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+    }
+}
+
+/// Opens or closes the notification drawer.
+pub fn toggle_notification_drawer(
+    view: &mut dyn View,
+    enable: Option<bool>,
+    rq: &mut RenderQueue,
+    context: &mut Context,
+) {
+    if let Some(index) = locate_by_id(view, ViewId::NotificationDrawer) {
+        if let Some(true) = enable {
+            return;
+        }
+        rq.add(RenderData::expose(
+            *view.child(index).rect(),
+            UpdateMode::Gui,
+        ));
+        view.children_mut().remove(index);
+    } else {
+        if let Some(false) = enable {
+            return;
+        }
+        let drawer = NotificationDrawer::new(context);
+        rq.add(RenderData::new(
+            drawer.id(),
+            *drawer.rect(),
+            UpdateMode::Gui,
+        ));
+        view.children_mut().push(Box::new(drawer) as Box<dyn View>);
+    }
+}
+
+impl View for NotificationDrawer {
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, hub, _bus, rq, context), fields(event = ?evt), ret(level=tracing::Level::TRACE)))]
+    fn handle_event(
+        &mut self,
+        evt: &Event,
+        hub: &Hub,
+        _bus: &mut Bus,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        match *evt {
+            Event::Gesture(GestureEvent::Tap(center)) if !self.rect.includes(center) => {
+                hub.send(Event::Close(ViewId::NotificationDrawer)).ok();
+                true
+            }
+            Event::Gesture(..) => true,
+            Event::Select(EntryId::ClearNotifications) => {
+                context.notifications.clear_history();
+                self.rebuild(rq, context);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, fb, _fonts, _rect), fields(rect = ?_rect)))]
+    fn render(&self, fb: &mut dyn Framebuffer, _rect: Rectangle, _fonts: &mut Fonts) {
+        fb.draw_rectangle(&self.rect, theme::current().background);
+    }
+
+    fn resize(
+        &mut self,
+        _rect: Rectangle,
+        _hub: &Hub,
+        _rq: &mut RenderQueue,
+        context: &mut Context,
+    ) {
+        let (rect, children) = Self::build(context);
+        self.rect = rect;
+        self.children = children;
+    }
+
+    fn is_background(&self) -> bool {
+        true
+    }
+
+    fn rect(&self) -> &Rectangle {
+        &self.rect
+    }
+
+    fn rect_mut(&mut self) -> &mut Rectangle {
+        &mut self.rect
+    }
+
+    fn children(&self) -> &Vec<Box<dyn View>> {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<Box<dyn View>> {
+        &mut self.children
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn view_id(&self) -> Option<ViewId> {
+        Some(ViewId::NotificationDrawer)
+    }
+}
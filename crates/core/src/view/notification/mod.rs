@@ -34,10 +34,27 @@
 //! // Dismiss when done
 //! tx.send(Event::Close(download_id)).ok();
 //! ```
+//!
+//! ## Toasts
+//!
+//! [`NotificationEvent::Show`] queues a transient toast: at most three toasts are shown at once, stacked in
+//! the top right corner, and the next one in the queue appears when one is dismissed. Every
+//! message shown is kept in the history listed by the [`NotificationDrawer`].
+//!
+//! ```
+//! use cadmus_core::view::{Event, NotificationEvent};
+//! let (tx, rx) = std::sync::mpsc::channel();
+//! tx.send(Event::Notification(NotificationEvent::Show("Import finished.".to_string()))).ok();
+//! ```
+
+mod center;
+mod drawer;
+
+pub use self::center::{dismiss_toast, notify, HistoryEntry, NotificationCenter};
+pub use self::drawer::{toggle_notification_drawer, NotificationDrawer};
 
 use super::{Bus, Event, Hub, Id, RenderData, RenderQueue, View, ViewId, ID_FEEDER};
 use super::{BORDER_RADIUS_MEDIUM, SMALL_BAR_HEIGHT, THICKNESS_LARGE};
-use crate::color::{BLACK, TEXT_NORMAL, WHITE};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::{font_from_style, Fonts, NORMAL_STYLE};
@@ -45,7 +62,8 @@ use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::{BorderSpec, CornerSpec, Rectangle};
 use crate::gesture::GestureEvent;
 use crate::input::DeviceEvent;
-use crate::unit::scale_by_dpi;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
 use std::thread;
 use std::time::Duration;
 
@@ -81,6 +99,7 @@ pub struct Notification {
     index: u8,
     view_id: ViewId,
     progress: Option<u8>,
+    action: Option<Event>,
 }
 
 impl Notification {
@@ -105,10 +124,35 @@ impl Notification {
         hub: &Hub,
         rq: &mut RenderQueue,
         context: &mut Context,
+    ) -> Notification {
+        let index = context.notification_index;
+        context.notification_index = index.wrapping_add(1);
+        Notification::build(view_id, text, pinned, index, hub, rq, context)
+    }
+
+    /// Creates an auto-dismissing notification in the given slot of the right column.
+    pub(crate) fn toast(
+        text: String,
+        slot: u8,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> Notification {
+        Notification::build(None, text, false, slot, hub, rq, context)
+    }
+
+    fn build(
+        view_id: Option<ViewId>,
+        text: String,
+        pinned: bool,
+        index: u8,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
     ) -> Notification {
         let id = ID_FEEDER.next();
         let view_id = view_id.unwrap_or(ViewId::MessageNotif(id));
-        let index = context.notification_index;
+        context.notifications.record(&text);
 
         if !pinned {
             let hub2 = hub.clone();
@@ -143,7 +187,6 @@ impl Notification {
         let rect = rect![dx, dy, dx + dialog_width, dy + dialog_height];
 
         rq.add(RenderData::new(id, rect, UpdateMode::Gui));
-        context.notification_index = index.wrapping_add(1);
 
         Notification {
             id,
@@ -154,9 +197,16 @@ impl Notification {
             index,
             view_id,
             progress: None,
+            action: None,
         }
     }
 
+    /// Sends the given event, and dismisses the notification, when it's tapped.
+    pub fn with_action(mut self, action: Event) -> Notification {
+        self.action = Some(action);
+        self
+    }
+
     /// Updates the text content of the notification and schedules a re-render.
     ///
     /// # Arguments
@@ -191,17 +241,23 @@ impl Notification {
 }
 
 impl View for Notification {
-    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _hub, _bus, _rq, _context), fields(event = ?evt), ret(level=tracing::Level::TRACE)))]
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, hub, _bus, _rq, _context), fields(event = ?evt), ret(level=tracing::Level::TRACE)))]
     fn handle_event(
         &mut self,
         evt: &Event,
-        _hub: &Hub,
+        hub: &Hub,
         _bus: &mut Bus,
         _rq: &mut RenderQueue,
         _context: &mut Context,
     ) -> bool {
         match *evt {
-            Event::Gesture(GestureEvent::Tap(center)) if self.rect.includes(center) => true,
+            Event::Gesture(GestureEvent::Tap(center)) if self.rect.includes(center) => {
+                if let Some(action) = self.action.as_ref() {
+                    hub.send(action.clone()).ok();
+                    hub.send(Event::Close(self.view_id)).ok();
+                }
+                true
+            }
             Event::Gesture(GestureEvent::Swipe { start, .. }) if self.rect.includes(start) => true,
             Event::Device(DeviceEvent::Finger { position, .. }) if self.rect.includes(position) => {
                 true
@@ -215,16 +271,16 @@ impl View for Notification {
         let dpi = CURRENT_DEVICE.dpi;
 
         let border_radius = scale_by_dpi(BORDER_RADIUS_MEDIUM, dpi) as i32;
-        let border_thickness = scale_by_dpi(THICKNESS_LARGE, dpi) as u16;
+        let border_thickness = scale_thickness(THICKNESS_LARGE, dpi) as u16;
 
         fb.draw_rounded_rectangle_with_border(
             &self.rect,
             &CornerSpec::Uniform(border_radius),
             &BorderSpec {
                 thickness: border_thickness,
-                color: BLACK,
+                color: theme::current().foreground,
             },
-            &WHITE,
+            &theme::current().background,
         );
 
         let font = font_from_style(fonts, &NORMAL_STYLE, dpi);
@@ -235,7 +291,7 @@ impl View for Notification {
         let dy = (self.rect.height() as i32 - x_height) / 2;
         let pt = pt!(self.rect.min.x + dx, self.rect.max.y - dy);
 
-        font.render(fb, TEXT_NORMAL[1], &plan, pt);
+        font.render(fb, theme::current().text_normal[1], &plan, pt);
 
         if let Some(progress) = self.progress {
             let progress_clamped = progress.min(100);
@@ -250,7 +306,7 @@ impl View for Notification {
                 self.rect.min.x + padding + progress_bar_width,
                 progress_bar_y + progress_bar_height
             ];
-            fb.draw_rectangle(&progress_bg_rect, TEXT_NORMAL[0]);
+            fb.draw_rectangle(&progress_bg_rect, theme::current().text_normal[0]);
 
             let filled_width = (progress_bar_width * progress_clamped as i32) / 100;
             if filled_width > 0 {
@@ -260,7 +316,7 @@ impl View for Notification {
                     self.rect.min.x + padding + filled_width,
                     progress_bar_y + progress_bar_height
                 ];
-                fb.draw_rectangle(&progress_fill_rect, BLACK);
+                fb.draw_rectangle(&progress_fill_rect, theme::current().foreground);
             }
         }
     }
@@ -2,12 +2,12 @@ use super::dialog::Dialog;
 use super::input_field::InputField;
 use super::label::Label;
 use super::notification::Notification;
+use super::progress_dialog::{ProgressEvent, ProgressKind};
 use super::toggleable_keyboard::ToggleableKeyboard;
 use super::{
     Align, Bus, EntryId, Event, Hub, Id, NotificationEvent, RenderData, RenderQueue, UpdateMode,
     View, ViewId, ID_FEEDER,
 };
-use crate::color::WHITE;
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::{font_from_style, Fonts, NORMAL_STYLE};
@@ -15,6 +15,7 @@ use crate::framebuffer::Framebuffer;
 use crate::geom::Rectangle;
 use crate::gesture::GestureEvent;
 use crate::ota::{OtaClient, OtaProgress};
+use crate::theme;
 use crate::unit::scale_by_dpi;
 use crate::view::filler::Filler;
 use crate::view::BIG_BAR_HEIGHT;
@@ -136,7 +137,7 @@ impl OtaView {
 
         children.push(Box::new(Filler::new(
             rect![0, 0, width as i32, height as i32],
-            WHITE,
+            theme::current().background,
         )));
 
         let source_dialog = Self::build_source_selection_dialog(context);
@@ -189,7 +190,7 @@ impl OtaView {
 
         self.children.push(Box::new(Filler::new(
             rect![0, 0, width as i32, height as i32],
-            WHITE,
+            theme::current().background,
         )));
 
         let font = font_from_style(&mut context.fonts, &NORMAL_STYLE, dpi);
@@ -352,34 +353,33 @@ impl OtaView {
                 }
             };
 
-            let notify_id = ViewId::MessageNotif(ID_FEEDER.next());
-            hub2.send(Event::Notification(NotificationEvent::ShowPinned(
-                notify_id,
-                "Starting update download".to_string(),
-            )))
-            .ok();
-            hub2.send(Event::Notification(NotificationEvent::UpdateProgress(
-                notify_id, 0,
+            let progress_id = ViewId::ProgressDialog(ID_FEEDER.next());
+            hub2.send(Event::Progress(ProgressEvent::Show(
+                progress_id,
+                "Downloading the update".to_string(),
+                ProgressKind::Determinate,
             )))
             .ok();
 
             let download_result = client.download_pr_artifact(pr_number, |ota_progress| {
                 if let OtaProgress::DownloadingArtifact { downloaded, total } = ota_progress {
                     let progress = (downloaded as f32 / total as f32) * 100.0;
-                    let msg = format!("Downloading update: {}%", progress as u8);
-                    hub2.send(Event::Notification(NotificationEvent::UpdateText(
-                        notify_id, msg,
-                    )))
-                    .ok();
-                    hub2.send(Event::Notification(NotificationEvent::UpdateProgress(
-                        notify_id,
+                    let msg = format!(
+                        "{:.1} of {:.1} MB",
+                        downloaded as f32 / 1e6,
+                        total as f32 / 1e6
+                    );
+                    hub2.send(Event::Progress(ProgressEvent::UpdateText(progress_id, msg)))
+                        .ok();
+                    hub2.send(Event::Progress(ProgressEvent::Update(
+                        progress_id,
                         progress as u8,
                     )))
                     .ok();
                 }
             });
 
-            hub2.send(Event::Close(notify_id)).ok();
+            hub2.send(Event::Close(progress_id)).ok();
 
             match download_result {
                 Ok(zip_path) => {
@@ -445,34 +445,33 @@ impl OtaView {
                 }
             };
 
-            let notify_id = ViewId::MessageNotif(ID_FEEDER.next());
-            hub2.send(Event::Notification(NotificationEvent::ShowPinned(
-                notify_id,
-                "Starting main branch download".to_string(),
-            )))
-            .ok();
-            hub2.send(Event::Notification(NotificationEvent::UpdateProgress(
-                notify_id, 0,
+            let progress_id = ViewId::ProgressDialog(ID_FEEDER.next());
+            hub2.send(Event::Progress(ProgressEvent::Show(
+                progress_id,
+                "Downloading the main branch build".to_string(),
+                ProgressKind::Determinate,
             )))
             .ok();
 
             let download_result = client.download_default_branch_artifact(|ota_progress| {
                 if let OtaProgress::DownloadingArtifact { downloaded, total } = ota_progress {
                     let progress = (downloaded as f32 / total as f32) * 100.0;
-                    let msg = format!("Downloading update: {}%", progress as u8);
-                    hub2.send(Event::Notification(NotificationEvent::UpdateText(
-                        notify_id, msg,
-                    )))
-                    .ok();
-                    hub2.send(Event::Notification(NotificationEvent::UpdateProgress(
-                        notify_id,
+                    let msg = format!(
+                        "{:.1} of {:.1} MB",
+                        downloaded as f32 / 1e6,
+                        total as f32 / 1e6
+                    );
+                    hub2.send(Event::Progress(ProgressEvent::UpdateText(progress_id, msg)))
+                        .ok();
+                    hub2.send(Event::Progress(ProgressEvent::Update(
+                        progress_id,
                         progress as u8,
                     )))
                     .ok();
                 }
             });
 
-            hub2.send(Event::Close(notify_id)).ok();
+            hub2.send(Event::Close(progress_id)).ok();
 
             match download_result {
                 Ok(zip_path) => {
@@ -536,34 +535,33 @@ impl OtaView {
                 }
             };
 
-            let notify_id = ViewId::MessageNotif(ID_FEEDER.next());
-            hub2.send(Event::Notification(NotificationEvent::ShowPinned(
-                notify_id,
-                "Starting stable release download".to_string(),
-            )))
-            .ok();
-            hub2.send(Event::Notification(NotificationEvent::UpdateProgress(
-                notify_id, 0,
+            let progress_id = ViewId::ProgressDialog(ID_FEEDER.next());
+            hub2.send(Event::Progress(ProgressEvent::Show(
+                progress_id,
+                "Downloading the stable release".to_string(),
+                ProgressKind::Determinate,
             )))
             .ok();
 
             let download_result = client.download_stable_release_artifact(|ota_progress| {
                 if let OtaProgress::DownloadingArtifact { downloaded, total } = ota_progress {
                     let progress = (downloaded as f32 / total as f32) * 100.0;
-                    let msg = format!("Downloading update: {}%", progress as u8);
-                    hub2.send(Event::Notification(NotificationEvent::UpdateText(
-                        notify_id, msg,
-                    )))
-                    .ok();
-                    hub2.send(Event::Notification(NotificationEvent::UpdateProgress(
-                        notify_id,
+                    let msg = format!(
+                        "{:.1} of {:.1} MB",
+                        downloaded as f32 / 1e6,
+                        total as f32 / 1e6
+                    );
+                    hub2.send(Event::Progress(ProgressEvent::UpdateText(progress_id, msg)))
+                        .ok();
+                    hub2.send(Event::Progress(ProgressEvent::Update(
+                        progress_id,
                         progress as u8,
                     )))
                     .ok();
                 }
             });
 
-            hub2.send(Event::Close(notify_id)).ok();
+            hub2.send(Event::Close(progress_id)).ok();
 
             match download_result {
                 Ok(asset_path) => {
@@ -1,5 +1,4 @@
 use super::{Bus, Event, Hub, Id, RenderData, RenderQueue, View, ViewId, ID_FEEDER};
-use crate::color::{BLACK, WHITE};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::document::BYTES_PER_PAGE;
@@ -7,6 +6,7 @@ use crate::font::{font_from_style, Fonts, NORMAL_STYLE};
 use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::Rectangle;
 use crate::gesture::GestureEvent;
+use crate::theme;
 
 pub struct PageLabel {
     id: Id,
@@ -15,6 +15,7 @@ pub struct PageLabel {
     current_page: usize,
     pages_count: usize,
     synthetic: bool,
+    print_page: Option<String>,
 }
 
 impl PageLabel {
@@ -31,6 +32,7 @@ impl PageLabel {
             current_page,
             pages_count,
             synthetic,
+            print_page: None,
         }
     }
 
@@ -49,6 +51,13 @@ impl PageLabel {
         }
     }
 
+    pub fn update_print_page(&mut self, print_page: Option<String>, rq: &mut RenderQueue) {
+        if self.print_page != print_page {
+            self.print_page = print_page;
+            rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        }
+    }
+
     pub fn text(&self, size: u8) -> String {
         if self.pages_count == 0 {
             return "No pages".to_string();
@@ -63,6 +72,19 @@ impl PageLabel {
             (self.current_page as f64 + 1.0, self.pages_count as f64, 0)
         };
         let percent = 100.0 * self.current_page as f32 / self.pages_count as f32;
+        if let Some(print_page) = self.print_page.as_ref() {
+            match size {
+                0 => {
+                    return format!(
+                        "Page {1:.0$} of {2:.0$} (p. {3}, {4:.1}%)",
+                        precision, current_page, pages_count, print_page, percent
+                    )
+                }
+                1 => return format!("P. {} ({:.1}%)", print_page, percent),
+                2 => return format!("P. {}", print_page),
+                _ => (),
+            }
+        }
         match size {
             0 => format!(
                 "Page {1:.0$} of {2:.0$} ({3:.1}%)",
@@ -124,8 +146,8 @@ impl View for PageLabel {
         let dx = padding + (max_width - plan.width) / 2;
         let dy = (self.rect.height() as i32 - font.x_heights.0 as i32) / 2;
         let pt = pt!(self.rect.min.x + dx, self.rect.max.y - dy);
-        fb.draw_rectangle(&self.rect, WHITE);
-        font.render(fb, BLACK, &plan, pt);
+        fb.draw_rectangle(&self.rect, theme::current().background);
+        font.render(fb, theme::current().foreground, &plan, pt);
     }
 
     fn rect(&self) -> &Rectangle {
@@ -0,0 +1,258 @@
+//! Drawing live performance metrics over the screen.
+//!
+//! When the `performance-hud` setting is enabled, the `toggle-performance-hud` gesture action
+//! shows a box in the top right corner with the time spent handling the events and rendering
+//! the updated regions, the depth of the render queue, and the memory used by the program.
+//! The metrics are gathered between two refreshes of the box, once per second.
+
+use super::{Event, Hub, RenderData, RenderQueue};
+use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::font::{font_from_style, FONT_SIZES, NORMAL_STYLE};
+use crate::framebuffer::UpdateMode;
+use crate::geom::Rectangle;
+use crate::theme;
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+pub const PERF_HUD_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The number of slowest regions listed.
+const MAX_REGIONS: usize = 3;
+
+/// The number of lines of the box: the events, the queue, the regions and the memory.
+const LINES_COUNT: usize = 3 + MAX_REGIONS;
+
+// Incremented each time the box is shown, to stop the ticker of the previous showing.
+static GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+/// The metrics gathered since the last refresh, and the lines shown.
+#[derive(Debug, Default)]
+pub struct PerfHud {
+    pub visible: bool,
+    events_count: usize,
+    events_time: Duration,
+    slowest_event: Duration,
+    queue_depth: usize,
+    // The slowest regions, slowest first.
+    regions: Vec<(Rectangle, Duration)>,
+    lines: Vec<String>,
+}
+
+impl PerfHud {
+    pub fn record_event(&mut self, elapsed: Duration) {
+        if !self.visible {
+            return;
+        }
+        self.events_count += 1;
+        self.events_time += elapsed;
+        self.slowest_event = self.slowest_event.max(elapsed);
+    }
+
+    pub fn record_queue_depth(&mut self, depth: usize) {
+        if self.visible {
+            self.queue_depth = self.queue_depth.max(depth);
+        }
+    }
+
+    pub fn record_render(&mut self, rect: Rectangle, elapsed: Duration) {
+        if !self.visible {
+            return;
+        }
+        let index = self
+            .regions
+            .iter()
+            .position(|(_, time)| elapsed > *time)
+            .unwrap_or(self.regions.len());
+        if index < MAX_REGIONS {
+            self.regions.insert(index, (rect, elapsed));
+            self.regions.truncate(MAX_REGIONS);
+        }
+    }
+
+    /// Turns the gathered metrics into the lines shown, and starts gathering anew.
+    pub fn refresh(&mut self, memory: Option<u64>) {
+        let average = if self.events_count > 0 {
+            self.events_time / self.events_count as u32
+        } else {
+            Duration::ZERO
+        };
+        let mut lines = vec![
+            format!(
+                "Events: {}, avg {} ms, max {} ms",
+                self.events_count,
+                average.as_millis(),
+                self.slowest_event.as_millis()
+            ),
+            format!("Render queue: {}", self.queue_depth),
+        ];
+        for index in 0..MAX_REGIONS {
+            lines.push(match self.regions.get(index) {
+                Some((rect, time)) => format!(
+                    "Render {}×{}+{}+{}: {} ms",
+                    rect.width(),
+                    rect.height(),
+                    rect.min.x,
+                    rect.min.y,
+                    time.as_millis()
+                ),
+                None => String::new(),
+            });
+        }
+        lines.push(match memory {
+            Some(bytes) => format!("Memory: {:.1} MiB", bytes as f64 / (1024.0 * 1024.0)),
+            None => "Memory: unknown".to_string(),
+        });
+        self.lines = lines;
+        self.events_count = 0;
+        self.events_time = Duration::ZERO;
+        self.slowest_event = Duration::ZERO;
+        self.queue_depth = 0;
+        self.regions.clear();
+    }
+}
+
+/// Shows or hides the box, if the `performance-hud` setting allows it.
+///
+/// While the box is shown, `Event::PerfHudTick` is sent through the hub once per second.
+pub fn toggle_perf_hud(hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
+    let hud = &mut context.perf_hud;
+    if !context.settings.performance_hud && !hud.visible {
+        return;
+    }
+    hud.visible = !hud.visible;
+    let generation = GENERATION.fetch_add(1, Ordering::AcqRel) + 1;
+    if hud.visible {
+        hud.refresh(memory_usage());
+        let hub = hub.clone();
+        thread::spawn(move || loop {
+            thread::sleep(PERF_HUD_REFRESH_INTERVAL);
+            if GENERATION.load(Ordering::Acquire) != generation
+                || hub.send(Event::PerfHudTick).is_err()
+            {
+                break;
+            }
+        });
+    }
+    rq.add(RenderData::expose(context.fb.rect(), UpdateMode::Gui));
+}
+
+/// Refreshes the metrics shown, and schedules the update of the box.
+pub fn tick_perf_hud(rq: &mut RenderQueue, context: &mut Context) {
+    if !context.perf_hud.visible {
+        return;
+    }
+    context.perf_hud.refresh(memory_usage());
+    rq.add(RenderData::expose(hud_rect(context), UpdateMode::Fast));
+}
+
+fn hud_rect(context: &mut Context) -> Rectangle {
+    let dpi = CURRENT_DEVICE.dpi;
+    let mut style = NORMAL_STYLE;
+    style.size = FONT_SIZES[0];
+    let font = font_from_style(&mut context.fonts, &style, dpi);
+    let padding = font.em() as i32 / 2;
+    let height = LINES_COUNT as i32 * font.line_height() + 2 * padding;
+    let rect = context.fb.rect();
+    let width = rect.width() as i32 / 2;
+    rect![
+        rect.max.x - width,
+        rect.min.y,
+        rect.max.x,
+        rect.min.y + height
+    ]
+}
+
+/// Draws the box, if it overlaps one of the rectangles about to be updated, and adds it to
+/// these rectangles.
+pub fn draw_perf_hud(rects: &mut Vec<Rectangle>, context: &mut Context) {
+    if !context.perf_hud.visible {
+        return;
+    }
+
+    let hud = hud_rect(context);
+    if !rects.iter().any(|r| r.overlaps(&hud)) {
+        return;
+    }
+
+    let dpi = CURRENT_DEVICE.dpi;
+    let mut style = NORMAL_STYLE;
+    style.size = FONT_SIZES[0];
+    let font = font_from_style(&mut context.fonts, &style, dpi);
+    let padding = font.em() as i32 / 2;
+    let line_height = font.line_height();
+    let [background, foreground, _] = theme::current().text_inverted_hard;
+
+    context.fb.draw_rectangle(&hud, background);
+    for (index, line) in context.perf_hud.lines.iter().enumerate() {
+        let plan = font.plan(line, Some(hud.width() as i32 - 2 * padding), None);
+        let pt = pt!(
+            hud.min.x + padding,
+            hud.min.y + padding + (index as i32 + 1) * line_height - font.descender().abs()
+        );
+        font.render(context.fb.as_mut(), foreground, &plan, pt);
+    }
+
+    rects.retain(|r| !hud.contains(r));
+    rects.push(hud);
+}
+
+/// Returns the resident memory of the program, in bytes.
+fn memory_usage() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))
+        .and_then(|value| {
+            value
+                .trim()
+                .trim_end_matches("kB")
+                .trim()
+                .parse::<u64>()
+                .ok()
+        })
+        .map(|kilobytes| kilobytes * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refresh_lists_the_slowest_regions() {
+        let mut hud = PerfHud {
+            visible: true,
+            ..Default::default()
+        };
+        hud.record_event(Duration::from_millis(10));
+        hud.record_event(Duration::from_millis(30));
+        hud.record_queue_depth(4);
+        hud.record_queue_depth(2);
+        for (x, ms) in [(0, 5), (10, 40), (20, 1), (30, 20), (40, 8)] {
+            hud.record_render(rect![x, 0, x + 10, 20], Duration::from_millis(ms));
+        }
+
+        hud.refresh(Some(3 * 1024 * 1024));
+        assert_eq!(
+            hud.lines,
+            vec![
+                "Events: 2, avg 20 ms, max 30 ms",
+                "Render queue: 4",
+                "Render 10×20+10+0: 40 ms",
+                "Render 10×20+30+0: 20 ms",
+                "Render 10×20+40+0: 8 ms",
+                "Memory: 3.0 MiB",
+            ]
+        );
+
+        hud.refresh(None);
+        assert_eq!(hud.lines[0], "Events: 0, avg 0 ms, max 0 ms");
+        assert_eq!(hud.lines[LINES_COUNT - 1], "Memory: unknown");
+
+        hud.visible = false;
+        hud.record_event(Duration::from_millis(10));
+        assert_eq!(hud.events_count, 0);
+    }
+}
@@ -0,0 +1,227 @@
+//! A modal keypad for entering a numeric PIN.
+//!
+//! The pad shows its title, one dot per digit entered, and the keys `0` to `9`, *Delete* and
+//! *OK*. Validating sends an [`Event::Submit`] with the pad's [`ViewId`] and the digits, then an
+//! [`Event::Close`]; tapping outside of the pad only sends the latter. The digits typed on an
+//! external keyboard are taken as well.
+
+use super::backdrop::Elevation;
+use super::button::Button;
+use super::key::KeyKind;
+use super::label::Label;
+use super::{Align, Bus, Event, Hub, Id, RenderQueue, View, ViewId, ID_FEEDER};
+use super::{BORDER_RADIUS_MEDIUM, THICKNESS_LARGE};
+use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::font::{font_from_style, Fonts, NORMAL_STYLE};
+use crate::framebuffer::Framebuffer;
+use crate::geom::{BorderSpec, CornerSpec, LinearDir, Rectangle};
+use crate::gesture::GestureEvent;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
+
+const MAX_DIGITS: usize = 8;
+const KEYS: [&str; 12] = [
+    "1", "2", "3", "4", "5", "6", "7", "8", "9", "Delete", "0", "OK",
+];
+
+pub struct PinPad {
+    id: Id,
+    rect: Rectangle,
+    children: Vec<Box<dyn View>>,
+    view_id: ViewId,
+    digits: String,
+}
+
+impl PinPad {
+    pub fn new(view_id: ViewId, title: &str, context: &mut Context) -> PinPad {
+        let dpi = CURRENT_DEVICE.dpi;
+        let (width, height) = context.display.dims;
+        let font = font_from_style(&mut context.fonts, &NORMAL_STYLE, dpi);
+        let padding = font.em() as i32;
+        let line_height = font.line_height();
+        let key_side = 6 * font.x_heights.0 as i32;
+
+        let pad_width = (3 * key_side + 4 * padding).max(width as i32 / 2);
+        let pad_height = 2 * line_height + 4 * key_side + 7 * padding;
+        let dx = (width as i32 - pad_width) / 2;
+        let dy = (height as i32 - pad_height) / 2;
+        let rect = rect![dx, dy, dx + pad_width, dy + pad_height];
+
+        let mut children = Vec::new();
+        let mut y = rect.min.y + padding;
+        for text in [title, ""] {
+            let label = Label::new(
+                rect![
+                    rect.min.x + padding,
+                    y,
+                    rect.max.x - padding,
+                    y + line_height
+                ],
+                text.to_string(),
+                Align::Center,
+            );
+            children.push(Box::new(label) as Box<dyn View>);
+            y += line_height + padding;
+        }
+
+        let x = rect.min.x + (pad_width - 3 * key_side - 2 * padding) / 2;
+        for (index, text) in KEYS.iter().enumerate() {
+            let (row, column) = (index as i32 / 3, index as i32 % 3);
+            let min_x = x + column * (key_side + padding);
+            let min_y = y + row * (key_side + padding);
+            let kind = match *text {
+                "Delete" => KeyKind::Delete(LinearDir::Backward),
+                "OK" => KeyKind::Return,
+                digit => KeyKind::Output(digit.chars().next().unwrap_or('0')),
+            };
+            let button = Button::new(
+                rect![min_x, min_y, min_x + key_side, min_y + key_side],
+                Event::Key(kind),
+                text.to_string(),
+            );
+            children.push(Box::new(button) as Box<dyn View>);
+        }
+
+        PinPad {
+            id: ID_FEEDER.next(),
+            rect,
+            children,
+            view_id,
+            digits: String::new(),
+        }
+    }
+
+    fn update_dots(&mut self, rq: &mut RenderQueue) {
+        let dots = "•".repeat(self.digits.chars().count());
+        if let Some(label) = self.children[1].downcast_mut::<Label>() {
+            label.update(&dots, rq);
+        }
+    }
+}
+
+impl View for PinPad {
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, hub, bus, rq, _context), fields(event = ?evt), ret(level=tracing::Level::TRACE)))]
+    fn handle_event(
+        &mut self,
+        evt: &Event,
+        hub: &Hub,
+        bus: &mut Bus,
+        rq: &mut RenderQueue,
+        _context: &mut Context,
+    ) -> bool {
+        match *evt {
+            Event::Key(KeyKind::Output(c)) if c.is_ascii_digit() => {
+                if self.digits.len() < MAX_DIGITS {
+                    self.digits.push(c);
+                    self.update_dots(rq);
+                }
+                true
+            }
+            Event::Key(KeyKind::Delete(..)) => {
+                if self.digits.pop().is_some() {
+                    self.update_dots(rq);
+                }
+                true
+            }
+            Event::Key(KeyKind::Return) => {
+                bus.push_back(Event::Submit(self.view_id, self.digits.clone()));
+                bus.push_back(Event::Close(self.view_id));
+                true
+            }
+            Event::Key(..) => true,
+            Event::Gesture(GestureEvent::Tap(center)) if !self.rect.includes(center) => {
+                hub.send(Event::Close(self.view_id)).ok();
+                true
+            }
+            Event::Gesture(..) => true,
+            _ => false,
+        }
+    }
+
+    fn render(&self, fb: &mut dyn Framebuffer, _rect: Rectangle, _fonts: &mut Fonts) {
+        let dpi = CURRENT_DEVICE.dpi;
+        let border_radius = scale_by_dpi(BORDER_RADIUS_MEDIUM, dpi) as i32;
+        let border_thickness = scale_thickness(THICKNESS_LARGE, dpi) as u16;
+
+        fb.draw_rounded_rectangle_with_border(
+            &self.rect,
+            &CornerSpec::Uniform(border_radius),
+            &BorderSpec {
+                thickness: border_thickness,
+                color: theme::current().foreground,
+            },
+            &theme::current().background,
+        );
+    }
+
+    fn is_background(&self) -> bool {
+        true
+    }
+
+    fn elevation(&self) -> Elevation {
+        Elevation::Modal
+    }
+
+    fn rect(&self) -> &Rectangle {
+        &self.rect
+    }
+
+    fn rect_mut(&mut self) -> &mut Rectangle {
+        &mut self.rect
+    }
+
+    fn children(&self) -> &Vec<Box<dyn View>> {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<Box<dyn View>> {
+        &mut self.children
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn view_id(&self) -> Option<ViewId> {
+        Some(self.view_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_helpers::create_test_context;
+    use std::collections::VecDeque;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_pin_pad_submits_the_digits() {
+        let mut context = create_test_context();
+        let mut pad = PinPad::new(ViewId::KioskPin, "PIN", &mut context);
+        let (hub, _receiver) = channel();
+        let mut bus = VecDeque::new();
+        let mut rq = RenderQueue::new();
+
+        for kind in [
+            KeyKind::Output('1'),
+            KeyKind::Output('x'),
+            KeyKind::Output('2'),
+            KeyKind::Output('3'),
+            KeyKind::Delete(LinearDir::Backward),
+            KeyKind::Output('4'),
+            KeyKind::Return,
+        ] {
+            pad.handle_event(&Event::Key(kind), &hub, &mut bus, &mut rq, &mut context);
+        }
+
+        assert!(matches!(
+            bus.pop_front(),
+            Some(Event::Submit(ViewId::KioskPin, ref digits)) if digits == "124"
+        ));
+        assert!(matches!(
+            bus.pop_front(),
+            Some(Event::Close(ViewId::KioskPin))
+        ));
+    }
+}
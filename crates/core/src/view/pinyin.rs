@@ -0,0 +1,129 @@
+//! Composing Chinese text from its pinyin spelling.
+//!
+//! The letters typed on a layout with a pinyin table are composed instead of being sent to
+//! the text field. The table maps spellings, without tones, to the words they spell, the most
+//! frequent first:
+//!
+//! ```text
+//! ni 你 呢 泥
+//! nihao 你好
+//! ```
+//!
+//! The candidates of a composition are the words spelled by all its letters, then those
+//! spelled by its longest leading spellings: choosing one of the latter commits it and leaves
+//! the remaining letters in the composition.
+
+use anyhow::{Context as ResultExt, Error};
+use fxhash::FxHashMap;
+use std::fs;
+use std::path::Path;
+
+/// A word proposed for the start of a composition.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Candidate {
+    pub text: String,
+    /// The number of letters of the composition it spells.
+    pub consumed: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PinyinTable {
+    entries: FxHashMap<String, Vec<String>>,
+}
+
+impl PinyinTable {
+    /// Parses a table, one spelling followed by its words per line.
+    ///
+    /// The blank lines and the lines starting with `#` are ignored. The words of a spelling
+    /// listed on several lines are appended in order.
+    pub fn new(text: &str) -> PinyinTable {
+        let mut entries: FxHashMap<String, Vec<String>> = FxHashMap::default();
+
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            if let Some(spelling) = fields.next() {
+                entries
+                    .entry(spelling.to_lowercase())
+                    .or_default()
+                    .extend(fields.map(String::from));
+            }
+        }
+
+        PinyinTable { entries }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<PinyinTable, Error> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("can't read pinyin from {}", path.display()))?;
+        Ok(PinyinTable::new(&text))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the candidates of a composition of lowercase ASCII letters, best first.
+    pub fn candidates(&self, composition: &str, limit: usize) -> Vec<Candidate> {
+        let mut candidates: Vec<Candidate> = Vec::new();
+
+        for consumed in (1..=composition.len()).rev() {
+            let Some(words) = composition
+                .get(..consumed)
+                .and_then(|spelling| self.entries.get(spelling))
+            else {
+                continue;
+            };
+            for word in words {
+                if candidates.len() >= limit {
+                    return candidates;
+                }
+                if !candidates.iter().any(|c| c.text == *word) {
+                    candidates.push(Candidate {
+                        text: word.clone(),
+                        consumed,
+                    });
+                }
+            }
+        }
+
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_longest_spellings_come_first() {
+        let table = PinyinTable::new("# Comment.\nni 你 呢\nnihao 你好\nhao 好\nni 泥\n");
+        assert_eq!(table.len(), 3);
+
+        let texts = |composition, limit| {
+            table
+                .candidates(composition, limit)
+                .into_iter()
+                .map(|c| (c.text, c.consumed))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            texts("nihao", 3),
+            vec![
+                ("你好".to_string(), 5),
+                ("你".to_string(), 2),
+                ("呢".to_string(), 2)
+            ]
+        );
+        assert_eq!(texts("nih", 5).len(), 3);
+        assert!(texts("x", 5).is_empty());
+    }
+}
@@ -1,6 +1,5 @@
 use super::BORDER_RADIUS_MEDIUM;
 use super::{Bus, Event, Hub, Id, RenderData, RenderQueue, View, ID_FEEDER};
-use crate::color::{TEXT_INVERTED_HARD, TEXT_NORMAL};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::{font_from_style, Fonts, NORMAL_STYLE};
@@ -8,6 +7,7 @@ use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::{CornerSpec, CycleDir, Rectangle};
 use crate::gesture::GestureEvent;
 use crate::input::{DeviceEvent, FingerStatus};
+use crate::theme;
 use crate::unit::scale_by_dpi;
 
 pub struct Preset {
@@ -86,11 +86,11 @@ impl View for Preset {
 
         let (scheme, border_radius) = if self.active {
             (
-                TEXT_INVERTED_HARD,
+                theme::current().text_inverted_hard,
                 scale_by_dpi(BORDER_RADIUS_MEDIUM, dpi) as i32,
             )
         } else {
-            (TEXT_NORMAL, 0)
+            (theme::current().text_normal, 0)
         };
 
         fb.draw_rounded_rectangle(&self.rect, &CornerSpec::Uniform(border_radius), scheme[0]);
@@ -114,6 +114,10 @@ impl View for Preset {
         font.render(fb, scheme[1], &plan, pt);
     }
 
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
     fn rect(&self) -> &Rectangle {
         &self.rect
     }
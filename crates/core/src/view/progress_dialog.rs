@@ -0,0 +1,357 @@
+//! A modal dialog showing the progress of a long-running operation.
+//!
+//! The dialog is driven by [`ProgressEvent`]s sent through the hub, typically from the thread
+//! doing the work. [`ProgressEvent::Show`] opens the dialog, the following events are handled
+//! by the dialog whose [`ViewId`] they carry, and [`Event::Close`] removes it.
+//!
+//! A [`ProgressKind::Determinate`] dialog fills its bar according to the percentage received.
+//! A [`ProgressKind::Indeterminate`] dialog moves a block along the bar each time it's pulsed,
+//! which suits operations whose length isn't known, such as a library scan.
+//!
+//! Tapping outside of the dialog hides it: the operation continues in the background.
+//!
+//! ```
+//! use cadmus_core::view::progress_dialog::{ProgressEvent, ProgressKind};
+//! use cadmus_core::view::{Event, ViewId, ID_FEEDER};
+//!
+//! let (tx, rx) = std::sync::mpsc::channel();
+//! let id = ViewId::ProgressDialog(ID_FEEDER.next());
+//! tx.send(Event::Progress(ProgressEvent::Show(
+//!     id,
+//!     "Downloading update".to_string(),
+//!     ProgressKind::Determinate,
+//! )))
+//! .ok();
+//! tx.send(Event::Progress(ProgressEvent::Update(id, 50))).ok();
+//! tx.send(Event::Progress(ProgressEvent::UpdateText(id, "12 of 24 MB".to_string()))).ok();
+//! tx.send(Event::Close(id)).ok();
+//! ```
+
+use super::backdrop::Elevation;
+use super::common::locate_by_id;
+use super::label::Label;
+use super::{Align, Bus, Event, Hub, Id, RenderData, RenderQueue, View, ViewId, ID_FEEDER};
+use super::{BORDER_RADIUS_MEDIUM, THICKNESS_LARGE, THICKNESS_MEDIUM};
+use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::font::{font_from_style, Fonts, NORMAL_STYLE};
+use crate::framebuffer::{Framebuffer, UpdateMode};
+use crate::geom::{BorderSpec, CornerSpec, Rectangle};
+use crate::gesture::GestureEvent;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
+
+// Number of positions of the block of an indeterminate dialog.
+const PULSE_STEPS: u8 = 5;
+
+/// Whether the length of an operation is known.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ProgressKind {
+    Determinate,
+    Indeterminate,
+}
+
+/// Events driving the progress dialogs.
+#[derive(Debug, Clone)]
+pub enum ProgressEvent {
+    /// Opens a dialog with the given title.
+    Show(ViewId, String, ProgressKind),
+    /// Sets the progress of a determinate dialog (0-100).
+    Update(ViewId, u8),
+    /// Moves the block of an indeterminate dialog.
+    Pulse(ViewId),
+    /// Sets the text shown below the bar.
+    UpdateText(ViewId, String),
+}
+
+pub struct ProgressDialog {
+    id: Id,
+    rect: Rectangle,
+    children: Vec<Box<dyn View>>,
+    view_id: ViewId,
+    kind: ProgressKind,
+    // The percentage for a determinate dialog, the position of the block otherwise.
+    value: u8,
+}
+
+impl ProgressDialog {
+    pub fn new(
+        view_id: ViewId,
+        title: String,
+        kind: ProgressKind,
+        context: &mut Context,
+    ) -> ProgressDialog {
+        let id = ID_FEEDER.next();
+        let rect = Self::layout(context);
+        let children = vec![
+            Box::new(Label::new(Rectangle::default(), title, Align::Center)) as Box<dyn View>,
+            Box::new(Label::new(
+                Rectangle::default(),
+                String::new(),
+                Align::Center,
+            )) as Box<dyn View>,
+        ];
+
+        let mut dialog = ProgressDialog {
+            id,
+            rect,
+            children,
+            view_id,
+            kind,
+            value: 0,
+        };
+        dialog.layout_children(&mut context.fonts);
+        dialog
+    }
+
+    /// Computes the rectangle of the dialog, centered on the display.
+    fn layout(context: &mut Context) -> Rectangle {
+        let dpi = CURRENT_DEVICE.dpi;
+        let (width, height) = context.display.dims;
+        let font = font_from_style(&mut context.fonts, &NORMAL_STYLE, dpi);
+        let padding = font.em() as i32;
+        let line_height = font.line_height();
+        let x_height = font.x_heights.0 as i32;
+
+        let dialog_width = 2 * width as i32 / 3;
+        let dialog_height = 2 * line_height + 2 * x_height + 4 * padding;
+        let dx = (width as i32 - dialog_width) / 2;
+        let dy = (height as i32 - dialog_height) / 2;
+
+        rect![dx, dy, dx + dialog_width, dy + dialog_height]
+    }
+
+    fn layout_children(&mut self, fonts: &mut Fonts) {
+        let dpi = CURRENT_DEVICE.dpi;
+        let font = font_from_style(fonts, &NORMAL_STYLE, dpi);
+        let padding = font.em() as i32;
+        let line_height = font.line_height();
+
+        let (min_x, max_x) = (self.rect.min.x + padding, self.rect.max.x - padding);
+        *self.children[0].rect_mut() = rect![
+            min_x,
+            self.rect.min.y + padding,
+            max_x,
+            self.rect.min.y + padding + line_height
+        ];
+        *self.children[1].rect_mut() = rect![
+            min_x,
+            self.rect.max.y - padding - line_height,
+            max_x,
+            self.rect.max.y - padding
+        ];
+    }
+
+    fn bar_rect(&self) -> Rectangle {
+        let top = self.children[0].rect().max.y;
+        let bottom = self.children[1].rect().min.y;
+        let height = (bottom - top) / 2;
+        let y_min = top + (bottom - top - height) / 2;
+        rect![
+            self.children[0].rect().min.x,
+            y_min,
+            self.children[0].rect().max.x,
+            y_min + height
+        ]
+    }
+
+    /// Returns the extent of the filled part of the bar.
+    fn fill_rect(&self, bar: Rectangle) -> Rectangle {
+        let width = bar.width() as i32;
+        match self.kind {
+            ProgressKind::Determinate => {
+                let filled = width * self.value.min(100) as i32 / 100;
+                rect![bar.min.x, bar.min.y, bar.min.x + filled, bar.max.y]
+            }
+            ProgressKind::Indeterminate => {
+                let block = width / PULSE_STEPS as i32;
+                let x_min = bar.min.x + self.value as i32 * block;
+                rect![x_min, bar.min.y, x_min + block, bar.max.y]
+            }
+        }
+    }
+
+    fn update_bar(&mut self, value: u8, rq: &mut RenderQueue) {
+        if self.value != value {
+            self.value = value;
+            rq.add(RenderData::new(self.id, self.bar_rect(), UpdateMode::Fast));
+        }
+    }
+}
+
+/// Opens a progress dialog above the other children of the given view.
+///
+/// A dialog with the same identifier is replaced.
+pub fn show_progress_dialog(
+    view: &mut dyn View,
+    view_id: ViewId,
+    title: String,
+    kind: ProgressKind,
+    rq: &mut RenderQueue,
+    context: &mut Context,
+) {
+    if let Some(index) = locate_by_id(view, view_id) {
+        rq.add(RenderData::expose(
+            *view.child(index).rect(),
+            UpdateMode::Gui,
+        ));
+        view.children_mut().remove(index);
+    }
+    let dialog = ProgressDialog::new(view_id, title, kind, context);
+    rq.add(RenderData::new(
+        dialog.id(),
+        *dialog.rect(),
+        UpdateMode::Gui,
+    ));
+    view.children_mut().push(Box::new(dialog) as Box<dyn View>);
+}
+
+impl View for ProgressDialog {
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, hub, _bus, rq, _context), fields(event = ?evt), ret(level=tracing::Level::TRACE)))]
+    fn handle_event(
+        &mut self,
+        evt: &Event,
+        hub: &Hub,
+        _bus: &mut Bus,
+        rq: &mut RenderQueue,
+        _context: &mut Context,
+    ) -> bool {
+        match *evt {
+            Event::Progress(ProgressEvent::Update(id, value)) if id == self.view_id => {
+                self.update_bar(value, rq);
+                true
+            }
+            Event::Progress(ProgressEvent::Pulse(id)) if id == self.view_id => {
+                self.update_bar((self.value + 1) % PULSE_STEPS, rq);
+                true
+            }
+            Event::Progress(ProgressEvent::UpdateText(id, ref text)) if id == self.view_id => {
+                if let Some(label) = self.children[1].downcast_mut::<Label>() {
+                    label.update(text, rq);
+                }
+                true
+            }
+            Event::Gesture(GestureEvent::Tap(center)) if !self.rect.includes(center) => {
+                hub.send(Event::Close(self.view_id)).ok();
+                true
+            }
+            Event::Gesture(..) => true,
+            _ => false,
+        }
+    }
+
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, fb, _fonts), fields(rect = ?rect)))]
+    fn render(&self, fb: &mut dyn Framebuffer, rect: Rectangle, _fonts: &mut Fonts) {
+        let dpi = CURRENT_DEVICE.dpi;
+        let border_radius = scale_by_dpi(BORDER_RADIUS_MEDIUM, dpi) as i32;
+        let border_thickness = scale_thickness(THICKNESS_LARGE, dpi) as u16;
+        let bar_thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as u16;
+
+        if rect.contains(&self.rect) {
+            fb.draw_rounded_rectangle_with_border(
+                &self.rect,
+                &CornerSpec::Uniform(border_radius),
+                &BorderSpec {
+                    thickness: border_thickness,
+                    color: theme::current().foreground,
+                },
+                &theme::current().background,
+            );
+        }
+
+        let bar = self.bar_rect();
+        fb.draw_rounded_rectangle_with_border(
+            &bar,
+            &CornerSpec::Uniform(bar.height() as i32 / 2),
+            &BorderSpec {
+                thickness: bar_thickness,
+                color: theme::current().foreground,
+            },
+            &theme::current().background,
+        );
+
+        let fill = self.fill_rect(bar);
+        if !fill.is_empty() {
+            fb.draw_rectangle(&fill, theme::current().foreground);
+        }
+    }
+
+    fn resize(&mut self, _rect: Rectangle, hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
+        self.rect = Self::layout(context);
+        self.layout_children(&mut context.fonts);
+        for child in &mut self.children {
+            let rect = *child.rect();
+            child.resize(rect, hub, rq, context);
+        }
+    }
+
+    fn is_background(&self) -> bool {
+        true
+    }
+
+    fn elevation(&self) -> Elevation {
+        Elevation::Modal
+    }
+
+    fn rect(&self) -> &Rectangle {
+        &self.rect
+    }
+
+    fn rect_mut(&mut self) -> &mut Rectangle {
+        &mut self.rect
+    }
+
+    fn children(&self) -> &Vec<Box<dyn View>> {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<Box<dyn View>> {
+        &mut self.children
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn view_id(&self) -> Option<ViewId> {
+        Some(self.view_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_helpers::create_test_context;
+    use std::collections::VecDeque;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_progress_events() {
+        let mut context = create_test_context();
+        let (hub, _receiver) = channel();
+        let mut bus = VecDeque::new();
+        let mut rq = RenderQueue::new();
+        let view_id = ViewId::ProgressDialog(1);
+        let mut dialog = ProgressDialog::new(
+            view_id,
+            "Scanning".to_string(),
+            ProgressKind::Determinate,
+            &mut context,
+        );
+
+        let update = Event::Progress(ProgressEvent::Update(view_id, 50));
+        assert!(dialog.handle_event(&update, &hub, &mut bus, &mut rq, &mut context));
+        let bar = dialog.bar_rect();
+        assert_eq!(dialog.fill_rect(bar).width(), bar.width() / 2);
+
+        let other = Event::Progress(ProgressEvent::Update(ViewId::ProgressDialog(2), 80));
+        assert!(!dialog.handle_event(&other, &hub, &mut bus, &mut rq, &mut context));
+        assert_eq!(dialog.value, 50);
+
+        dialog.kind = ProgressKind::Indeterminate;
+        dialog.value = PULSE_STEPS - 1;
+        let pulse = Event::Progress(ProgressEvent::Pulse(view_id));
+        dialog.handle_event(&pulse, &hub, &mut bus, &mut rq, &mut context);
+        assert_eq!(dialog.fill_rect(bar).min.x, bar.min.x);
+    }
+}
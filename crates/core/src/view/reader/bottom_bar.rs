@@ -1,5 +1,4 @@
 use super::chapter_label::ChapterLabel;
-use crate::color::WHITE;
 use crate::context::Context;
 use crate::document::{Document, Neighbors, TocEntry};
 use crate::font::Fonts;
@@ -7,6 +6,7 @@ use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::{halves, CycleDir, Rectangle};
 use crate::gesture::GestureEvent;
 use crate::input::DeviceEvent;
+use crate::theme;
 use crate::view::filler::Filler;
 use crate::view::icon::Icon;
 use crate::view::page_label::PageLabel;
@@ -39,7 +39,7 @@ impl BottomBar {
         let prev_rect = rect![rect.min, rect.min + side];
 
         if is_prev_disabled {
-            let prev_filler = Filler::new(prev_rect, WHITE);
+            let prev_filler = Filler::new(prev_rect, theme::current().background);
             children.push(Box::new(prev_filler) as Box<dyn View>);
         } else {
             let prev_icon = Icon::new("arrow-left", prev_rect, Event::Page(CycleDir::Previous));
@@ -74,7 +74,7 @@ impl BottomBar {
         let next_rect = rect![rect.max - side, rect.max];
 
         if is_next_disabled {
-            let next_filler = Filler::new(next_rect, WHITE);
+            let next_filler = Filler::new(next_rect, theme::current().background);
             children.push(Box::new(next_filler) as Box<dyn View>);
         } else {
             let next_icon = Icon::new(
@@ -109,6 +109,11 @@ impl BottomBar {
         page_label.update(current_page, pages_count, rq);
     }
 
+    pub fn update_print_page_label(&mut self, print_page: Option<String>, rq: &mut RenderQueue) {
+        let page_label = self.child_mut(2).downcast_mut::<PageLabel>().unwrap();
+        page_label.update_print_page(print_page, rq);
+    }
+
     pub fn update_icons(&mut self, neighbors: &Neighbors, rq: &mut RenderQueue) {
         let is_prev_disabled = neighbors.previous_page.is_none();
 
@@ -116,7 +121,7 @@ impl BottomBar {
             let index = 0;
             let prev_rect = *self.child(index).rect();
             if is_prev_disabled {
-                let prev_filler = Filler::new(prev_rect, WHITE);
+                let prev_filler = Filler::new(prev_rect, theme::current().background);
                 self.children[index] = Box::new(prev_filler) as Box<dyn View>;
             } else {
                 let prev_icon = Icon::new("arrow-left", prev_rect, Event::Page(CycleDir::Previous));
@@ -132,7 +137,7 @@ impl BottomBar {
             let index = self.len() - 1;
             let next_rect = *self.child(index).rect();
             if is_next_disabled {
-                let next_filler = Filler::new(next_rect, WHITE);
+                let next_filler = Filler::new(next_rect, theme::current().background);
                 self.children[index] = Box::new(next_filler) as Box<dyn View>;
             } else {
                 let next_icon = Icon::new("arrow-right", next_rect, Event::Page(CycleDir::Next));
@@ -0,0 +1,347 @@
+use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::document::{Document, Location};
+use crate::font::Fonts;
+use crate::framebuffer::{Framebuffer, Pixmap, UpdateMode};
+use crate::geom::{Boundary, Point, Rectangle, Vec2};
+use crate::gesture::GestureEvent;
+use crate::input::DeviceEvent;
+use crate::settings::DitheringKind;
+use crate::theme;
+use crate::unit::scale_by_dpi;
+use crate::view::rounded_button::RoundedButton;
+use crate::view::SMALL_BAR_HEIGHT;
+use crate::view::{Bus, Event, Hub, Id, RenderData, RenderQueue, View, ViewId, ID_FEEDER};
+use std::sync::{Arc, Mutex};
+
+// Zoom factors are relative to the scale at which the image fits the screen.
+const MAX_ZOOM: f32 = 4.0;
+// Upper bound on the size of a rasterized page, in pixels.
+const MAX_PAGE_AREA: f32 = 16_000_000.0;
+
+/// A full-screen viewer for an image embedded in a document page.
+///
+/// The image is rasterized from the document at the current zoom level. While the user
+/// zooms or rotates, the previous raster is stretched and shown with a fast update, then
+/// a sharp raster is produced once the resulting [`Event::RefineImage`] is received.
+pub struct ImageViewer {
+    id: Id,
+    rect: Rectangle,
+    children: Vec<Box<dyn View>>,
+    doc: Arc<Mutex<Box<dyn Document>>>,
+    location: usize,
+    boundary: Boundary,
+    pixmap: Pixmap,
+    pixmap_scale: f32,
+    scale: f32,
+    zoom: f32,
+    quarter_turns: i8,
+    // Top left corner of the image on screen, at the current scale.
+    position: Point,
+    generation: u32,
+    dithering: DitheringKind,
+}
+
+impl ImageViewer {
+    pub fn new(
+        rect: Rectangle,
+        doc: Arc<Mutex<Box<dyn Document>>>,
+        location: usize,
+        boundary: Boundary,
+        dithering: DitheringKind,
+    ) -> ImageViewer {
+        let id = ID_FEEDER.next();
+        let mut children = Vec::new();
+
+        let dpi = CURRENT_DEVICE.dpi;
+        let button_diameter = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
+        let padding = button_diameter / 2;
+
+        let close_button = RoundedButton::new(
+            "close",
+            rect![
+                rect.min.x + padding,
+                rect.max.y - padding - button_diameter,
+                rect.min.x + padding + button_diameter,
+                rect.max.y - padding
+            ],
+            Event::Close(ViewId::ImageViewer),
+        );
+        children.push(Box::new(close_button) as Box<dyn View>);
+
+        let mut image_viewer = ImageViewer {
+            id,
+            rect,
+            children,
+            doc,
+            location,
+            boundary,
+            pixmap: Pixmap::empty(0, 0, CURRENT_DEVICE.color_samples()),
+            pixmap_scale: 1.0,
+            scale: 1.0,
+            zoom: 1.0,
+            quarter_turns: 0,
+            position: rect.min,
+            generation: 0,
+            dithering,
+        };
+
+        image_viewer.scale = image_viewer.fit_scale();
+        image_viewer.rasterize();
+        image_viewer.center();
+        image_viewer
+    }
+
+    fn image_size(&self, scale: f32) -> (i32, i32) {
+        let width = (self.boundary.width() * scale).round() as i32;
+        let height = (self.boundary.height() * scale).round() as i32;
+        if self.quarter_turns % 2 == 0 {
+            (width, height)
+        } else {
+            (height, width)
+        }
+    }
+
+    fn fit_scale(&self) -> f32 {
+        let (width, height) = self.image_size(1.0);
+        (self.rect.width() as f32 / width.max(1) as f32)
+            .min(self.rect.height() as f32 / height.max(1) as f32)
+    }
+
+    fn max_scale(&self) -> f32 {
+        let dims = self
+            .doc
+            .lock()
+            .ok()
+            .and_then(|doc| doc.dims(self.location))
+            .unwrap_or((self.boundary.max.x, self.boundary.max.y));
+        (MAX_PAGE_AREA / (dims.0 * dims.1).max(1.0)).sqrt()
+    }
+
+    fn rasterize(&mut self) {
+        let pixmap = {
+            let mut doc = self.doc.lock().unwrap();
+            doc.pixmap(
+                Location::Exact(self.location),
+                self.scale,
+                CURRENT_DEVICE.color_samples(),
+            )
+        };
+
+        if let Some((page, _)) = pixmap {
+            if let Some(frame) = (self.boundary * self.scale)
+                .to_rect()
+                .intersection(&page.rect())
+            {
+                self.pixmap = crop_pixmap(&page, &frame).rotate(self.quarter_turns);
+                self.pixmap.dither(self.dithering);
+                self.pixmap_scale = self.scale;
+            }
+        }
+    }
+
+    fn center(&mut self) {
+        let (width, height) = self.image_size(self.scale);
+        self.position = pt!(
+            self.rect.min.x + (self.rect.width() as i32 - width) / 2,
+            self.rect.min.y + (self.rect.height() as i32 - height) / 2
+        );
+    }
+
+    // Images smaller than the screen stay centered, larger ones can't leave gaps on the edges.
+    fn clamp_position(&mut self) {
+        let (width, height) = self.image_size(self.scale);
+        let (rect_width, rect_height) = (self.rect.width() as i32, self.rect.height() as i32);
+
+        self.position.x = if width <= rect_width {
+            self.rect.min.x + (rect_width - width) / 2
+        } else {
+            self.position
+                .x
+                .clamp(self.rect.max.x - width, self.rect.min.x)
+        };
+
+        self.position.y = if height <= rect_height {
+            self.rect.min.y + (rect_height - height) / 2
+        } else {
+            self.position
+                .y
+                .clamp(self.rect.max.y - height, self.rect.min.y)
+        };
+    }
+
+    fn zoom_by(&mut self, center: Point, factor: f32, hub: &Hub, rq: &mut RenderQueue) {
+        let fit_scale = self.fit_scale();
+        let zoom = (self.zoom * factor).clamp(1.0, MAX_ZOOM);
+        let scale = (fit_scale * zoom).min(self.max_scale());
+
+        if (scale - self.scale).abs() < f32::EPSILON {
+            return;
+        }
+
+        let ratio = scale / self.scale;
+        self.position = center - Point::from(ratio * Vec2::from(center - self.position));
+        self.zoom = zoom;
+        self.scale = scale;
+        self.clamp_position();
+        self.preview(hub, rq);
+    }
+
+    fn rotate(&mut self, quarter_turns: i8, hub: &Hub, rq: &mut RenderQueue) {
+        self.quarter_turns = (self.quarter_turns + quarter_turns).rem_euclid(4);
+        self.pixmap = self.pixmap.rotate(quarter_turns);
+        self.zoom = 1.0;
+        self.scale = self.fit_scale();
+        self.center();
+        self.preview(hub, rq);
+    }
+
+    fn preview(&mut self, hub: &Hub, rq: &mut RenderQueue) {
+        self.generation = self.generation.wrapping_add(1);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Fast));
+        hub.send(Event::RefineImage(self.generation)).ok();
+    }
+}
+
+fn crop_pixmap(pixmap: &Pixmap, frame: &Rectangle) -> Pixmap {
+    let mut result = Pixmap::new(frame.width(), frame.height(), pixmap.samples);
+    result.draw_framed_pixmap(pixmap, frame, pt!(0));
+    result
+}
+
+impl View for ImageViewer {
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, hub, _bus, rq, _context), fields(event = ?evt), ret(level=tracing::Level::TRACE)))]
+    fn handle_event(
+        &mut self,
+        evt: &Event,
+        hub: &Hub,
+        _bus: &mut Bus,
+        rq: &mut RenderQueue,
+        _context: &mut Context,
+    ) -> bool {
+        match *evt {
+            Event::Gesture(GestureEvent::Spread { center, factor, .. })
+            | Event::Gesture(GestureEvent::Pinch { center, factor, .. })
+                if factor.is_finite() && self.rect.includes(center) =>
+            {
+                self.zoom_by(center, factor, hub, rq);
+                true
+            }
+            Event::Gesture(GestureEvent::Rotate { quarter_turns, .. }) if quarter_turns != 0 => {
+                self.rotate(quarter_turns, hub, rq);
+                true
+            }
+            Event::Gesture(GestureEvent::Swipe { start, end, .. }) if self.rect.includes(start) => {
+                self.position += end - start;
+                self.clamp_position();
+                rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+                true
+            }
+            Event::RefineImage(generation) => {
+                if generation == self.generation
+                    && (self.scale - self.pixmap_scale).abs() > f32::EPSILON
+                {
+                    self.rasterize();
+                    rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+                }
+                true
+            }
+            Event::Gesture(GestureEvent::Tap(center))
+            | Event::Gesture(GestureEvent::HoldFingerShort(center, ..))
+            | Event::Gesture(GestureEvent::HoldFingerLong(center, ..))
+                if self.rect.includes(center) =>
+            {
+                true
+            }
+            Event::Device(DeviceEvent::Finger { position, .. }) if self.rect.includes(position) => {
+                true
+            }
+            _ => false,
+        }
+    }
+
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, fb, _fonts), fields(rect = ?rect)))]
+    fn render(&self, fb: &mut dyn Framebuffer, rect: Rectangle, _fonts: &mut Fonts) {
+        fb.draw_rectangle(&rect, theme::current().background);
+
+        if self.pixmap.data.is_empty() {
+            return;
+        }
+
+        let (width, height) = self.image_size(self.scale);
+        let image_rect = rect![self.position, self.position + pt!(width, height)];
+
+        let Some(visible) = image_rect.intersection(&rect) else {
+            return;
+        };
+
+        let ratio = self.scale / self.pixmap_scale;
+
+        if (ratio - 1.0).abs() < f32::EPSILON {
+            let frame = visible - self.position;
+            if let Some(frame) = frame.intersection(&self.pixmap.rect()) {
+                fb.draw_framed_pixmap(&self.pixmap, &frame, visible.min);
+            }
+            return;
+        }
+
+        // Nearest neighbor stretch of the previous raster, good enough until it's refined.
+        for y in visible.min.y..visible.max.y {
+            let sy = (((y - self.position.y) as f32 / ratio) as u32).min(self.pixmap.height - 1);
+            for x in visible.min.x..visible.max.x {
+                let sx = (((x - self.position.x) as f32 / ratio) as u32).min(self.pixmap.width - 1);
+                fb.set_pixel(x as u32, y as u32, self.pixmap.get_pixel(sx, sy));
+            }
+        }
+    }
+
+    fn render_rect(&self, rect: &Rectangle) -> Rectangle {
+        rect.intersection(&self.rect).unwrap_or(self.rect)
+    }
+
+    fn is_background(&self) -> bool {
+        true
+    }
+
+    fn view_id(&self) -> Option<ViewId> {
+        Some(ViewId::ImageViewer)
+    }
+
+    fn rect(&self) -> &Rectangle {
+        &self.rect
+    }
+
+    fn rect_mut(&mut self) -> &mut Rectangle {
+        &mut self.rect
+    }
+
+    fn children(&self) -> &Vec<Box<dyn View>> {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<Box<dyn View>> {
+        &mut self.children
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::{BLACK, WHITE};
+
+    #[test]
+    fn test_crop_pixmap() {
+        let mut pixmap = Pixmap::new(4, 4, 1);
+        pixmap.set_pixel(2, 1, BLACK);
+
+        let cropped = crop_pixmap(&pixmap, &rect![1, 1, 3, 3]);
+
+        assert_eq!((cropped.width, cropped.height), (2, 2));
+        assert_eq!(cropped.get_pixel(1, 0), BLACK);
+        assert_eq!(cropped.get_pixel(0, 0), WHITE);
+    }
+}
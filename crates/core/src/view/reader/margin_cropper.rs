@@ -1,4 +1,4 @@
-use crate::color::{BLACK, GRAY12, WHITE};
+use crate::color::GRAY12;
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::Fonts;
@@ -6,13 +6,68 @@ use crate::framebuffer::{Framebuffer, Pixmap, UpdateMode};
 use crate::geom::{BorderSpec, CornerSpec, Point, Rectangle};
 use crate::gesture::GestureEvent;
 use crate::metadata::Margin;
-use crate::unit::scale_by_dpi;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
 use crate::view::rounded_button::RoundedButton;
 use crate::view::{Bus, Event, Hub, Id, RenderData, RenderQueue, View, ViewId, ID_FEEDER};
 use crate::view::{SMALL_BAR_HEIGHT, THICKNESS_MEDIUM};
 
 pub const BUTTON_DIAMETER: f32 = 30.0;
 
+// Samples lighter than this are considered part of the background.
+const CONTENT_THRESHOLD: u8 = 0xD0;
+
+/// Returns the smallest margin that leaves every non-background sample of `pixmap` visible.
+///
+/// Rows and columns containing fewer dark samples than a thousandth of their length are
+/// treated as scanning noise. A blank page yields the default margin.
+pub fn detect_margin(pixmap: &Pixmap) -> Margin {
+    let (width, height) = (pixmap.width, pixmap.height);
+
+    if width == 0 || height == 0 || pixmap.data.is_empty() {
+        return Margin::default();
+    }
+
+    let mut rows = vec![0u32; height as usize];
+    let mut columns = vec![0u32; width as usize];
+
+    for y in 0..height {
+        for x in 0..width {
+            if pixmap.get_pixel(x, y).gray() < CONTENT_THRESHOLD {
+                rows[y as usize] += 1;
+                columns[x as usize] += 1;
+            }
+        }
+    }
+
+    let row_min = (width / 1000).max(1);
+    let column_min = (height / 1000).max(1);
+    let top = rows.iter().position(|&n| n >= row_min);
+    let bottom = rows.iter().rposition(|&n| n >= row_min);
+    let left = columns.iter().position(|&n| n >= column_min);
+    let right = columns.iter().rposition(|&n| n >= column_min);
+
+    match (top, right, bottom, left) {
+        (Some(top), Some(right), Some(bottom), Some(left)) => {
+            // A little breathing room so glyphs aren't clipped by rounding.
+            let pad_x = (width / 100) as usize;
+            let pad_y = (height / 100) as usize;
+            let top = top.saturating_sub(pad_y);
+            let left = left.saturating_sub(pad_x);
+            let bottom = (bottom + 1 + pad_y).min(height as usize);
+            let right = (right + 1 + pad_x).min(width as usize);
+
+            Margin::new(
+                top as f32 / height as f32,
+                (width as usize - right) as f32 / width as f32,
+                (height as usize - bottom) as f32 / height as f32,
+                left as f32 / width as f32,
+            )
+        }
+        _ => Margin::default(),
+    }
+}
+
 pub struct MarginCropper {
     id: Id,
     rect: Rectangle,
@@ -30,18 +85,7 @@ impl MarginCropper {
     ) -> MarginCropper {
         let id = ID_FEEDER.next();
         let mut children = Vec::new();
-
-        let pt = pt!(
-            (rect.width() as i32 - pixmap.width as i32) / 2,
-            (rect.height() as i32 - pixmap.height as i32) / 2
-        );
-        let x_min = pt.x + (margin.left * pixmap.width as f32).round() as i32;
-        let y_min = pt.y + (margin.top * pixmap.height as f32).round() as i32;
-        let x_max =
-            pt.x + pixmap.width as i32 - (margin.right * pixmap.width as f32).round() as i32;
-        let y_max =
-            pt.y + pixmap.height as i32 - (margin.bottom * pixmap.height as f32).round() as i32;
-        let frame = rect![x_min, y_min, x_max, y_max];
+        let frame = Self::frame(&rect, &pixmap, margin);
 
         let dpi = CURRENT_DEVICE.dpi;
         let small_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
@@ -60,6 +104,18 @@ impl MarginCropper {
         );
         children.push(Box::new(cancel_button) as Box<dyn View>);
 
+        let detect_button = RoundedButton::new(
+            "crop",
+            rect![
+                rect.min.x + (rect.width() as i32 - big_button_diameter) / 2,
+                rect.max.y - padding - big_button_diameter,
+                rect.min.x + (rect.width() as i32 + big_button_diameter) / 2,
+                rect.max.y - padding
+            ],
+            Event::Guess,
+        );
+        children.push(Box::new(detect_button) as Box<dyn View>);
+
         let validate_button = RoundedButton::new(
             "check_mark-large",
             rect![
@@ -81,6 +137,20 @@ impl MarginCropper {
         }
     }
 
+    fn frame(rect: &Rectangle, pixmap: &Pixmap, margin: &Margin) -> Rectangle {
+        let pt = pt!(
+            (rect.width() as i32 - pixmap.width as i32) / 2,
+            (rect.height() as i32 - pixmap.height as i32) / 2
+        );
+        let x_min = pt.x + (margin.left * pixmap.width as f32).round() as i32;
+        let y_min = pt.y + (margin.top * pixmap.height as f32).round() as i32;
+        let x_max =
+            pt.x + pixmap.width as i32 - (margin.right * pixmap.width as f32).round() as i32;
+        let y_max =
+            pt.y + pixmap.height as i32 - (margin.bottom * pixmap.height as f32).round() as i32;
+        rect![x_min, y_min, x_max, y_max]
+    }
+
     fn update(&mut self, start: Point, end: Point) {
         let mut nearest = None;
         let mut dmin = u32::MAX;
@@ -180,6 +250,12 @@ impl View for MarginCropper {
                 bus.push_back(Event::Close(ViewId::MarginCropper));
                 true
             }
+            Event::Guess => {
+                let margin = detect_margin(&self.pixmap);
+                self.frame = Self::frame(&self.rect, &self.pixmap, &margin);
+                rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+                true
+            }
             _ => false,
         }
     }
@@ -189,10 +265,10 @@ impl View for MarginCropper {
         let dx = (self.rect.width() as i32 - self.pixmap.width as i32) / 2;
         let dy = (self.rect.height() as i32 - self.pixmap.height as i32) / 2;
 
-        fb.draw_rectangle(&self.rect, WHITE);
+        fb.draw_rectangle(&self.rect, theme::current().background);
         fb.draw_pixmap(&self.pixmap, pt!(dx, dy));
 
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as u16;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as u16;
 
         fb.draw_blended_rectangle(
             &rect![
@@ -239,7 +315,7 @@ impl View for MarginCropper {
             &self.frame,
             &BorderSpec {
                 thickness: thickness as u16,
-                color: BLACK,
+                color: theme::current().foreground,
             },
         );
 
@@ -265,9 +341,9 @@ impl View for MarginCropper {
                     &CornerSpec::Uniform(button_radius),
                     &BorderSpec {
                         thickness: thickness as u16,
-                        color: BLACK,
+                        color: theme::current().foreground,
                     },
-                    &WHITE,
+                    &theme::current().background,
                 );
             }
         }
@@ -301,3 +377,34 @@ impl View for MarginCropper {
         self.id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::BLACK;
+
+    #[test]
+    fn test_detect_margin_finds_content_box() {
+        let mut pixmap = Pixmap::new(400, 600, 1);
+        pixmap.draw_rectangle(&rect![100, 150, 300, 450], BLACK);
+
+        let margin = detect_margin(&pixmap);
+
+        // One percent of padding is added around the content.
+        assert!((margin.left - 96.0 / 400.0).abs() < 1e-4);
+        assert!((margin.right - 96.0 / 400.0).abs() < 1e-4);
+        assert!((margin.top - 144.0 / 600.0).abs() < 1e-4);
+        assert!((margin.bottom - 144.0 / 600.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_detect_margin_blank_page() {
+        let pixmap = Pixmap::new(400, 600, 1);
+        let margin = detect_margin(&pixmap);
+
+        assert_eq!(margin.top, 0.0);
+        assert_eq!(margin.right, 0.0);
+        assert_eq!(margin.bottom, 0.0);
+        assert_eq!(margin.left, 0.0);
+    }
+}
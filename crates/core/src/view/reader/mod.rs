@@ -1,16 +1,20 @@
 mod bottom_bar;
 mod chapter_label;
+mod image_viewer;
 mod margin_cropper;
+mod prerender;
 mod results_bar;
 mod results_label;
 mod tool_bar;
 
 use self::bottom_bar::BottomBar;
-use self::margin_cropper::{MarginCropper, BUTTON_DIAMETER};
+use self::image_viewer::ImageViewer;
+use self::margin_cropper::{detect_margin, MarginCropper, BUTTON_DIAMETER};
+use self::prerender::{prerender, PageKey, PrerenderCache};
 use self::results_bar::ResultsBar;
 use self::tool_bar::ToolBar;
 use super::top_bar::{TopBar, TopBarVariant};
-use crate::color::{BLACK, WHITE};
+use crate::color::Color;
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::document::epub::EpubDocumentStatic;
@@ -19,7 +23,8 @@ use crate::document::{
     annotations_as_html, bookmarks_as_html, toc_as_html, SimpleTocEntry, TocEntry, TocLocation,
 };
 use crate::document::{
-    open, BoundedText, Document, Location, Neighbors, TextLocation, BYTES_PER_PAGE,
+    open, stylesheet_names, BoundedText, Document, Location, Neighbors, TextLocation,
+    BYTES_PER_PAGE,
 };
 use crate::font::family_names;
 use crate::font::Fonts;
@@ -30,20 +35,26 @@ use crate::geom::{BorderSpec, Boundary, CornerSpec, Point, Rectangle, Vec2};
 use crate::gesture::GestureEvent;
 use crate::helpers::AsciiExtension;
 use crate::input::{ButtonCode, ButtonStatus, DeviceEvent, FingerStatus};
+use crate::kosync::{self, KosyncClient, KosyncError};
 use crate::metadata::{make_query, CroppingMargins, Margin};
 use crate::metadata::{
     Annotation, FileInfo, Info, PageScheme, ReaderInfo, ScrollMode, TextAlign, ZoomMode,
 };
-use crate::metadata::{DEFAULT_CONTRAST_EXPONENT, DEFAULT_CONTRAST_GRAY};
+use crate::metadata::{DEFAULT_BRIGHTNESS, DEFAULT_CONTRAST_EXPONENT, DEFAULT_CONTRAST_GRAY};
+use crate::net;
 use crate::settings::{
-    guess_frontlight, BottomRightGestureAction, EastStripAction, FinishedAction,
-    SouthEastCornerAction, SouthStripAction, WestStripAction,
+    guess_frontlight, BottomRightGestureAction, FinishedAction, ReaderSettings, Settings,
+    SyncSettings, TapAction, TapZone,
 };
 use crate::settings::{
     DEFAULT_FONT_FAMILY, DEFAULT_LINE_HEIGHT, DEFAULT_MARGIN_WIDTH, DEFAULT_TEXT_ALIGN,
 };
 use crate::settings::{HYPHEN_PENALTY, STRETCH_TOLERANCE};
-use crate::unit::{mm_to_px, scale_by_dpi};
+use crate::speech::{self, Speaker, SpeechEvent};
+use crate::sync_queue;
+use crate::theme;
+use crate::unit::{mm_to_px, scale_by_dpi, scale_thickness};
+use crate::view::command_palette::ActionRegistry;
 use crate::view::common::{locate, locate_by_id, rlocate};
 use crate::view::common::{toggle_battery_menu, toggle_clock_menu, toggle_main_menu};
 use crate::view::filler::Filler;
@@ -56,6 +67,7 @@ use crate::view::search_bar::SearchBar;
 use crate::view::{AppCmd, Bus, Event, Hub, RenderData, RenderQueue, View};
 use crate::view::{EntryId, EntryKind, Id, SliderId, ViewId, ID_FEEDER};
 use crate::view::{BIG_BAR_HEIGHT, SMALL_BAR_HEIGHT, THICKNESS_MEDIUM};
+use anyhow::Error;
 use chrono::Local;
 use fxhash::{FxHashMap, FxHashSet};
 use rand_core::Rng;
@@ -63,20 +75,26 @@ use regex::Regex;
 use septem::prelude::*;
 use septem::{Digit, Roman};
 use std::collections::{BTreeMap, VecDeque};
-use std::fs::OpenOptions;
+use std::fs::{self, OpenOptions};
 use std::io::prelude::*;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering as AtomicOrdering;
 use std::sync::{Arc, Mutex};
 use std::thread;
-use tracing::{error, info};
+use std::time::Duration;
+use tracing::{error, info, warn};
 
 const HISTORY_SIZE: usize = 32;
 const RECT_DIST_JITTER: f32 = 24.0;
 const ANNOTATION_DRIFT: u8 = 0x44;
 const HIGHLIGHT_DRIFT: u8 = 0x22;
+// The highlighter colors, on color screens.
+const HIGHLIGHT_TINT: Color = Color::Rgb(0xFF, 0xE6, 0x70);
+const ANNOTATION_TINT: Color = Color::Rgb(0xA8, 0xD4, 0xFF);
 const MEM_SCHEME: &str = "mem:";
+// Gray levels added per brightness menu step, ten percent of the full range.
+const BRIGHTNESS_STEP: f32 = 25.5;
 
 pub struct Reader {
     id: Id,
@@ -84,10 +102,12 @@ pub struct Reader {
     children: Vec<Box<dyn View>>,
     doc: Arc<Mutex<Box<dyn Document>>>,
     cache: BTreeMap<usize, Resource>,         // Cached page pixmaps.
+    prerendered: Arc<Mutex<PrerenderCache>>,  // Pixmaps of the neighboring pages.
     chunks: Vec<RenderChunk>,                 // Chunks of pages being rendered.
     text: FxHashMap<usize, Vec<BoundedText>>, // Text of the current chunks.
     annotations: FxHashMap<usize, Vec<Annotation>>, // Annotations for the current chunks.
     noninverted_regions: FxHashMap<usize, Vec<Boundary>>,
+    night_mode: bool, // Inverts the pages, except their images.
     focus: Option<ViewId>,
     search: Option<Search>,
     search_direction: LinearDir,
@@ -95,6 +115,7 @@ pub struct Reader {
     selection: Option<Selection>,
     target_annotation: Option<[TextLocation; 2]>,
     history: VecDeque<usize>,
+    forward_history: Vec<usize>,
     state: State,
     info: Info,
     current_page: usize,
@@ -102,10 +123,14 @@ pub struct Reader {
     view_port: ViewPort,
     contrast: Contrast,
     synthetic: bool,
+    page_list: BTreeMap<usize, String>, // Print page labels.
     page_turns: usize,
     reflowable: bool,
     ephemeral: bool,
     finished: bool,
+    auto_page_turn: Option<AutoPageTurn>,
+    speech: Option<Speech>,
+    annotated: bool, // Whether annotations were added, changed or removed.
 }
 
 struct ViewPort {
@@ -113,6 +138,7 @@ struct ViewPort {
     scroll_mode: ScrollMode,
     page_offset: Point, // Offset relative to the top left corner of a resource's frame.
     margin_width: i32,
+    two_pages: bool,
 }
 
 impl Default for ViewPort {
@@ -122,6 +148,7 @@ impl Default for ViewPort {
             scroll_mode: ScrollMode::Screen,
             page_offset: pt!(0, 0),
             margin_width: 0,
+            two_pages: false,
         }
     }
 }
@@ -137,6 +164,18 @@ struct Selection {
     start: TextLocation,
     end: TextLocation,
     anchor: TextLocation,
+    // The area updated while the selection is dragged.
+    dragged: Option<Rectangle>,
+}
+
+impl Selection {
+    fn drag(&mut self, rect: Rectangle, id: Id, rq: &mut RenderQueue) {
+        rq.add(RenderData::new(id, rect, UpdateMode::Interactive));
+        match self.dragged.as_mut() {
+            Some(dragged) => dragged.absorb(&rect),
+            None => self.dragged = Some(rect),
+        }
+    }
 }
 
 struct Resource {
@@ -173,9 +212,44 @@ impl Default for Search {
     }
 }
 
+struct AutoPageTurn {
+    interval: u32,
+    remaining: u32,
+    paused: bool,
+    countdown: bool,
+    // The position last reached, manual navigation restarts the countdown.
+    position: (usize, Point),
+    running: Arc<AtomicBool>,
+}
+
+impl AutoPageTurn {
+    // The width of the elapsed part of a countdown strip of the given width.
+    fn progress_width(&self, width: i32) -> i32 {
+        let elapsed = self.interval.saturating_sub(self.remaining);
+        width * elapsed as i32 / self.interval.max(1) as i32
+    }
+}
+
+struct Speech {
+    // Absent while paused.
+    speaker: Option<Speaker>,
+    paused: bool,
+    // The page whose sentences are being spoken.
+    page: usize,
+    // The start of the chapter being read, the speech stops when leaving it.
+    chapter: Option<usize>,
+    sentences: Vec<[TextLocation; 2]>,
+    texts: Vec<String>,
+    current: usize,
+    // The next page and the last word of it that was spoken with the current page.
+    carry: Option<(usize, TextLocation)>,
+}
+
 struct Contrast {
     exponent: f32,
     gray: f32,
+    // Offset added to every sample after the contrast curve.
+    brightness: f32,
 }
 
 impl Default for Contrast {
@@ -183,7 +257,122 @@ impl Default for Contrast {
         Contrast {
             exponent: DEFAULT_CONTRAST_EXPONENT,
             gray: DEFAULT_CONTRAST_GRAY,
+            brightness: DEFAULT_BRIGHTNESS,
+        }
+    }
+}
+
+impl Contrast {
+    // The contrast of the books that don't have their own.
+    fn from_defaults(settings: &ReaderSettings) -> Contrast {
+        Contrast {
+            exponent: settings.contrast_exponent,
+            gray: settings.contrast_gray,
+            brightness: settings.brightness,
+        }
+    }
+}
+
+// An empty name in the book's settings opts out of the global stylesheet.
+fn stylesheet_name<'a>(info: &'a Info, settings: &'a Settings) -> Option<&'a str> {
+    info.reader
+        .as_ref()
+        .and_then(|r| r.stylesheet.as_deref())
+        .or(settings.reader.stylesheet.as_deref())
+        .filter(|name| !name.is_empty())
+}
+
+fn load_stylesheet(styles_path: &Path, name: &str) -> Option<String> {
+    fs::read_to_string(styles_path.join(name))
+        .map_err(|e| error!("Can't read stylesheet {}: {:#}.", name, e))
+        .ok()
+}
+
+// Fetches the position synced by the other devices, the answer is sent as `Event::SyncProgress`.
+fn fetch_sync_progress(settings: &SyncSettings, path: PathBuf, full_path: PathBuf, hub: &Hub) {
+    let settings = settings.clone();
+    let hub = hub.clone();
+
+    thread::spawn(move || {
+        let _job = net::Job::start();
+        let result = kosync::document_hash(&full_path)
+            .map_err(Error::from)
+            .and_then(|document| {
+                let client = KosyncClient::new(&settings)?;
+                Ok(client.get_progress(&document)?)
+            });
+
+        match result {
+            Ok(Some(remote)) if remote.device_id != settings.device_id => {
+                hub.send(Event::SyncProgress(path, Box::new(remote))).ok();
+            }
+            Ok(_) => (),
+            Err(e) => error!("Can't fetch the synced progress: {:#}.", e),
+        }
+    });
+}
+
+fn push_sync_progress(
+    settings: &SyncSettings,
+    full_path: PathBuf,
+    progress: String,
+    percentage: f32,
+    online: bool,
+) {
+    let settings = settings.clone();
+
+    thread::spawn(move || {
+        let _job = net::Job::start();
+        let document = match kosync::document_hash(&full_path) {
+            Ok(document) => document,
+            Err(e) => {
+                error!("Can't sync the progress: {:#}.", e);
+                return;
+            }
+        };
+        let progress = kosync::Progress {
+            document,
+            progress,
+            percentage,
+            device: settings.device_name.clone(),
+            device_id: settings.device_id.clone(),
+            timestamp: None,
+        };
+
+        if !online {
+            sync_queue::push(sync_queue::Operation::Progress(progress));
+            return;
+        }
+
+        let result =
+            KosyncClient::new(&settings).and_then(|client| client.update_progress(&progress));
+
+        match result {
+            Ok(()) => (),
+            Err(KosyncError::Request(e)) => {
+                warn!("Can't sync the progress, queuing it: {}.", e);
+                sync_queue::push(sync_queue::Operation::Progress(progress));
+            }
+            Err(e) => error!("Can't sync the progress: {:#}.", e),
         }
+    });
+}
+
+// Grayscale screens darken the highlights, color screens tint them.
+fn highlight(fb: &mut dyn Framebuffer, rect: &Rectangle, drift: u8, tint: Color) {
+    if CURRENT_DEVICE.color_samples() > 1 {
+        fb.tint_region(rect, tint);
+    } else {
+        fb.shift_region(rect, drift);
+    }
+}
+
+// Reflowable documents are laid out on half the screen when two pages are shown side by side.
+fn layout_width(width: u32, height: u32, two_pages: bool) -> u32 {
+    if two_pages && width > height {
+        width / 2
+    } else {
+        width
     }
 }
 
@@ -284,19 +473,36 @@ impl Reader {
         let path = context.library.home.join(&info.file.path);
 
         open(&path).and_then(|mut doc| {
+            // Books opened for the first time start with the library's preset.
+            let preset = settings
+                .libraries
+                .get(settings.selected_library)
+                .and_then(|library| library.typography_preset.as_deref())
+                .filter(|_| info.reader.is_none() && doc.is_reflowable())
+                .and_then(|name| settings.reader.typography_preset(name))
+                .cloned()
+                .unwrap_or_default();
             let (width, height) = context.display.dims;
             let font_size = info
                 .reader
                 .as_ref()
                 .and_then(|r| r.font_size)
+                .or(preset.font_size)
                 .unwrap_or(settings.reader.font_size);
+            let two_pages = settings.reader.two_pages_in_landscape;
 
-            doc.layout(width, height, font_size, CURRENT_DEVICE.dpi);
+            doc.layout(
+                layout_width(width, height, two_pages),
+                height,
+                font_size,
+                CURRENT_DEVICE.dpi,
+            );
 
             let margin_width = info
                 .reader
                 .as_ref()
                 .and_then(|r| r.margin_width)
+                .or(preset.margin_width)
                 .unwrap_or(settings.reader.margin_width);
 
             if margin_width != DEFAULT_MARGIN_WIDTH {
@@ -307,6 +513,7 @@ impl Reader {
                 .reader
                 .as_ref()
                 .and_then(|r| r.font_family.as_ref())
+                .or(preset.font_family.as_ref())
                 .unwrap_or(&settings.reader.font_family);
 
             if font_family != DEFAULT_FONT_FAMILY {
@@ -317,6 +524,7 @@ impl Reader {
                 .reader
                 .as_ref()
                 .and_then(|r| r.line_height)
+                .or(preset.line_height)
                 .unwrap_or(settings.reader.line_height);
 
             if (line_height - DEFAULT_LINE_HEIGHT).abs() > f32::EPSILON {
@@ -327,6 +535,7 @@ impl Reader {
                 .reader
                 .as_ref()
                 .and_then(|r| r.text_align)
+                .or(preset.text_align)
                 .unwrap_or(settings.reader.text_align);
 
             if text_align != DEFAULT_TEXT_ALIGN {
@@ -349,10 +558,22 @@ impl Reader {
                 doc.set_ignore_document_css(true);
             }
 
+            if let Some(name) = stylesheet_name(&info, settings) {
+                let css = load_stylesheet(&settings.reader.styles_path, name);
+                doc.set_user_stylesheet(css);
+            }
+
+            if info.reader.as_ref().and_then(|r| r.vertical_text) == Some(true) {
+                doc.set_vertical_text(true);
+            }
+
             let first_location = doc.resolve_location(Location::Exact(0))?;
 
-            let mut view_port = ViewPort::default();
-            let mut contrast = Contrast::default();
+            let mut view_port = ViewPort {
+                two_pages,
+                ..Default::default()
+            };
+            let mut contrast = Contrast::from_defaults(&settings.reader);
             let pages_count = doc.pages_count();
             let current_page;
 
@@ -402,21 +623,44 @@ impl Reader {
                 if let Some(gray) = r.contrast_gray {
                     contrast.gray = gray;
                 }
+
+                if let Some(brightness) = r.brightness {
+                    contrast.brightness = brightness;
+                }
             } else {
                 current_page = first_location;
 
                 info.reader = Some(ReaderInfo {
                     current_page,
                     pages_count,
+                    font_family: preset.font_family,
+                    font_size: preset.font_size,
+                    margin_width: preset.margin_width,
+                    line_height: preset.line_height,
+                    text_align: preset.text_align,
+                    cropping_margins: settings
+                        .reader
+                        .cropping_margins
+                        .clone()
+                        .filter(|_| !doc.is_reflowable()),
                     ..Default::default()
                 });
             }
 
             let synthetic = doc.has_synthetic_page_numbers();
             let reflowable = doc.is_reflowable();
+            let page_list = if reflowable {
+                doc.page_list()
+            } else {
+                BTreeMap::new()
+            };
 
             info!("{}", info.file.path.display());
 
+            if settings.sync.enabled {
+                fetch_sync_progress(&settings.sync, info.file.path.clone(), path.clone(), hub);
+            }
+
             hub.send(Event::Update(UpdateMode::Partial)).ok();
 
             Some(Reader {
@@ -425,10 +669,12 @@ impl Reader {
                 children: Vec::new(),
                 doc: Arc::new(Mutex::new(doc)),
                 cache: BTreeMap::new(),
+                prerendered: Arc::new(Mutex::new(PrerenderCache::default())),
                 chunks: Vec::new(),
                 text: FxHashMap::default(),
                 annotations: FxHashMap::default(),
                 noninverted_regions: FxHashMap::default(),
+                night_mode: settings.reader.night_mode,
                 focus: None,
                 search: None,
                 search_direction: LinearDir::Forward,
@@ -436,17 +682,22 @@ impl Reader {
                 selection: None,
                 target_annotation: None,
                 history: VecDeque::new(),
+                forward_history: Vec::new(),
                 state: State::Idle,
                 info,
                 current_page,
                 pages_count,
                 view_port,
                 synthetic,
+                page_list,
                 page_turns: 0,
                 contrast,
                 ephemeral: false,
                 reflowable,
                 finished: false,
+                auto_page_turn: None,
+                speech: None,
+                annotated: false,
             })
         })
     }
@@ -496,10 +747,12 @@ impl Reader {
             children: Vec::new(),
             doc: Arc::new(Mutex::new(Box::new(doc))),
             cache: BTreeMap::new(),
+            prerendered: Arc::new(Mutex::new(PrerenderCache::default())),
             chunks: Vec::new(),
             text: FxHashMap::default(),
             annotations: FxHashMap::default(),
             noninverted_regions: FxHashMap::default(),
+            night_mode: context.settings.reader.night_mode,
             focus: None,
             search: None,
             search_direction: LinearDir::Forward,
@@ -507,17 +760,22 @@ impl Reader {
             selection: None,
             target_annotation: None,
             history: VecDeque::new(),
+            forward_history: Vec::new(),
             state: State::Idle,
             info,
             current_page,
             pages_count,
             view_port: ViewPort::default(),
             synthetic: true,
+            page_list: BTreeMap::new(),
             page_turns: 0,
             contrast: Contrast::default(),
             ephemeral: true,
             reflowable: true,
             finished: false,
+            auto_page_turn: None,
+            speech: None,
+            annotated: false,
         }
     }
 
@@ -554,10 +812,12 @@ impl Reader {
             children: Vec::new(),
             doc: Arc::new(Mutex::new(Box::new(doc))),
             cache: BTreeMap::new(),
+            prerendered: Arc::new(Mutex::new(PrerenderCache::default())),
             chunks: Vec::new(),
             text: FxHashMap::default(),
             annotations: FxHashMap::default(),
             noninverted_regions: FxHashMap::default(),
+            night_mode: context.settings.reader.night_mode,
             focus: None,
             search: None,
             search_direction: LinearDir::Forward,
@@ -565,20 +825,102 @@ impl Reader {
             selection: None,
             target_annotation: None,
             history: VecDeque::new(),
+            forward_history: Vec::new(),
             state: State::Idle,
             info,
             current_page: 0,
             pages_count,
             view_port: ViewPort::default(),
             synthetic: true,
+            page_list: BTreeMap::new(),
             page_turns: 0,
             contrast: Contrast::default(),
             ephemeral: true,
             reflowable: true,
             finished: false,
+            auto_page_turn: None,
+            speech: None,
+            annotated: false,
         })
     }
 
+    fn is_spread(&self) -> bool {
+        self.view_port.two_pages
+            && self.view_port.zoom_mode == ZoomMode::FitToPage
+            && self.rect.width() > self.rect.height()
+    }
+
+    // The area a single page is fitted into.
+    fn page_rect(&self) -> Rectangle {
+        if self.is_spread() {
+            rect![
+                self.rect.min.x,
+                self.rect.min.y,
+                self.rect.min.x + self.rect.width() as i32 / 2,
+                self.rect.max.y
+            ]
+        } else {
+            self.rect
+        }
+    }
+
+    fn toggle_night_mode(&mut self, rq: &mut RenderQueue, context: &mut Context) {
+        self.night_mode = !self.night_mode;
+        context.settings.reader.night_mode = self.night_mode;
+        self.update_noninverted_regions(self.night_mode || context.fb.inverted());
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Full));
+    }
+
+    fn toggle_two_pages(&mut self, hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
+        let landscape = self.rect.width() > self.rect.height();
+
+        // The document is shared with a background thread and can't be laid out again.
+        if self.reflowable && landscape && Arc::strong_count(&self.doc) > 1 {
+            // The entry of the menu was checked when tapped: it has to reflect the
+            // unchanged setting.
+            self.toggle_title_menu(Rectangle::default(), Some(false), rq, context);
+            let notif = Notification::new(
+                None,
+                "The document is busy, try again in a moment.".to_string(),
+                false,
+                hub,
+                rq,
+                context,
+            );
+            self.children.push(Box::new(notif) as Box<dyn View>);
+            return;
+        }
+
+        let two_pages = !self.view_port.two_pages;
+        self.view_port.two_pages = two_pages;
+        context.settings.reader.two_pages_in_landscape = two_pages;
+
+        if self.reflowable && landscape {
+            let font_size = self
+                .info
+                .reader
+                .as_ref()
+                .and_then(|r| r.font_size)
+                .unwrap_or(context.settings.reader.font_size);
+            let mut doc = self.doc.lock().unwrap();
+            doc.layout(
+                layout_width(self.rect.width(), self.rect.height(), two_pages),
+                self.rect.height(),
+                font_size,
+                CURRENT_DEVICE.dpi,
+            );
+            let current_page = self.current_page.min(doc.pages_count() - 1);
+            if let Some(location) = doc.resolve_location(Location::Exact(current_page)) {
+                self.current_page = location;
+            }
+            self.text.clear();
+        }
+
+        self.cache.clear();
+        self.update(Some(UpdateMode::Full), hub, rq, context);
+        self.update_bottom_bar(rq);
+    }
+
     fn load_pixmap(&mut self, location: usize) {
         if self.cache.contains_key(&location) {
             return;
@@ -595,17 +937,22 @@ impl Reader {
         let dims = doc.dims(location).unwrap_or((3.0, 4.0));
         let screen_margin_width = self.view_port.margin_width;
         let scale = scaling_factor(
-            &self.rect,
+            &self.page_rect(),
             &cropping_margin,
             screen_margin_width,
             dims,
             self.view_port.zoom_mode,
         );
-        if let Some((pixmap, _)) = doc.pixmap(
-            Location::Exact(location),
-            scale,
-            CURRENT_DEVICE.color_samples(),
-        ) {
+        let key = PageKey::new(self.info.file.path.clone(), location, scale);
+        let prerendered = self.prerendered.lock().unwrap().take(&key);
+        if let Some(pixmap) = prerendered.or_else(|| {
+            doc.pixmap(
+                Location::Exact(location),
+                scale,
+                CURRENT_DEVICE.color_samples(),
+            )
+            .map(|(pixmap, _)| pixmap)
+        }) {
             let frame = rect![
                 (cropping_margin.left * pixmap.width as f32).ceil() as i32,
                 (cropping_margin.top * pixmap.height as f32).ceil() as i32,
@@ -636,6 +983,42 @@ impl Reader {
         }
     }
 
+    // Renders the given page of a fixed-layout document in the background, the result is
+    // picked up by `load_pixmap` on the next page turn.
+    fn prerender_pixmap(&self, location: usize) {
+        if self.cache.contains_key(&location) {
+            return;
+        }
+
+        let cropping_margin = self
+            .info
+            .reader
+            .as_ref()
+            .and_then(|r| r.cropping_margins.as_ref().map(|c| c.margin(location)))
+            .cloned()
+            .unwrap_or_default();
+        let page_rect = self.page_rect();
+        let screen_margin_width = self.view_port.margin_width;
+        let zoom_mode = self.view_port.zoom_mode;
+
+        prerender(
+            self.doc.clone(),
+            self.prerendered.clone(),
+            self.info.file.path.clone(),
+            location,
+            CURRENT_DEVICE.color_samples(),
+            move |dims| {
+                scaling_factor(
+                    &page_rect,
+                    &cropping_margin,
+                    screen_margin_width,
+                    dims,
+                    zoom_mode,
+                )
+            },
+        );
+    }
+
     fn load_text(&mut self, location: usize) {
         if self.text.contains_key(&location) {
             return;
@@ -662,10 +1045,8 @@ impl Reader {
 
         if let Some(location) = loc {
             if record {
-                self.history.push_back(self.current_page);
-                if self.history.len() > HISTORY_SIZE {
-                    self.history.pop_front();
-                }
+                self.record_history();
+                self.forward_history.clear();
             }
 
             if let Some(ref mut s) = self.search {
@@ -792,8 +1173,23 @@ impl Reader {
         }
     }
 
-    fn go_to_last_page(&mut self, hub: &Hub, rq: &mut RenderQueue, context: &Context) {
+    fn record_history(&mut self) {
+        self.history.push_back(self.current_page);
+        if self.history.len() > HISTORY_SIZE {
+            self.history.pop_front();
+        }
+    }
+
+    fn go_back(&mut self, hub: &Hub, rq: &mut RenderQueue, context: &Context) {
         if let Some(location) = self.history.pop_back() {
+            self.forward_history.push(self.current_page);
+            self.go_to_page(location, false, hub, rq, context);
+        }
+    }
+
+    fn go_forward(&mut self, hub: &Hub, rq: &mut RenderQueue, context: &Context) {
+        if let Some(location) = self.forward_history.pop() {
+            self.record_history();
             self.go_to_page(location, false, hub, rq, context);
         }
     }
@@ -1014,7 +1410,11 @@ impl Reader {
                     }
                 },
                 CycleDir::Next => match self.view_port.zoom_mode {
-                    ZoomMode::FitToPage => Location::Next(current_page),
+                    ZoomMode::FitToPage => Location::Next(
+                        self.chunks
+                            .last()
+                            .map_or(current_page, |chunk| chunk.location),
+                    ),
                     ZoomMode::FitToWidth => match self.view_port.scroll_mode {
                         ScrollMode::Screen => {
                             let &RenderChunk {
@@ -1054,7 +1454,15 @@ impl Reader {
                 },
             };
             let mut doc = self.doc.lock().unwrap();
-            doc.resolve_location(neighloc)
+            let loc = doc.resolve_location(neighloc);
+            if dir == CycleDir::Previous && self.is_spread() {
+                loc.map(|location| {
+                    doc.resolve_location(Location::Previous(location))
+                        .unwrap_or(location)
+                })
+            } else {
+                loc
+            }
         };
         match loc {
             Some(location)
@@ -1179,16 +1587,18 @@ impl Reader {
             let chapter = rtoc.as_ref().and_then(|toc| doc.chapter(current_page, toc));
             let title = chapter.map(|(c, _)| c.title.clone()).unwrap_or_default();
             let progress = chapter.map(|(_, p)| p).unwrap_or_default();
-            let bottom_bar = self.children[index]
-                .as_mut()
-                .downcast_mut::<BottomBar>()
-                .unwrap();
             let neighbors = Neighbors {
                 previous_page: doc.resolve_location(Location::Previous(current_page)),
                 next_page: doc.resolve_location(Location::Next(current_page)),
             };
+            let print_page = self.print_page(neighbors.next_page);
+            let bottom_bar = self.children[index]
+                .as_mut()
+                .downcast_mut::<BottomBar>()
+                .unwrap();
             bottom_bar.update_chapter_label(title, progress, rq);
             bottom_bar.update_page_label(self.current_page, self.pages_count, rq);
+            bottom_bar.update_print_page_label(print_page, rq);
             bottom_bar.update_icons(&neighbors, rq);
         }
     }
@@ -1335,7 +1745,8 @@ impl Reader {
                 .by_kind
                 .get(&self.info.file.kind)
                 .unwrap_or_else(|| &context.settings.reader.refresh_rate.global);
-            let refresh_rate = if context.fb.inverted() {
+            // Dark pages need more frequent full refreshes.
+            let refresh_rate = if context.fb.inverted() != self.night_mode {
                 pair.inverted
             } else {
                 pair.regular
@@ -1352,6 +1763,31 @@ impl Reader {
         let smw = self.view_port.margin_width;
 
         match self.view_port.zoom_mode {
+            ZoomMode::FitToPage if self.is_spread() => {
+                let next_location = {
+                    let mut doc = self.doc.lock().unwrap();
+                    doc.resolve_location(Location::Next(location))
+                };
+                let mut width = 0;
+                for location in std::iter::once(location).chain(next_location) {
+                    self.load_pixmap(location);
+                    self.load_text(location);
+                    let Resource { frame, scale, .. } = self.cache[&location];
+                    let dy = smw + ((self.rect.height() - frame.height()) as i32 - 2 * smw) / 2;
+                    self.chunks.push(RenderChunk {
+                        frame,
+                        location,
+                        position: pt!(width, dy),
+                        scale,
+                    });
+                    width += frame.width() as i32;
+                }
+                // The pair is centered as a whole so facing pages touch in the middle.
+                let dx = (self.rect.width() as i32 - width) / 2;
+                for chunk in &mut self.chunks {
+                    chunk.position.x += dx;
+                }
+            }
             ZoomMode::FitToPage => {
                 self.load_pixmap(location);
                 self.load_text(location);
@@ -1477,7 +1913,15 @@ impl Reader {
         }
 
         self.update_annotations();
-        self.update_noninverted_regions(context.fb.inverted());
+        self.update_noninverted_regions(self.night_mode || context.fb.inverted());
+
+        if self
+            .speech
+            .as_ref()
+            .is_some_and(|s| s.page != self.current_page)
+        {
+            self.speak_page(hub, context);
+        }
 
         if self.view_port.zoom_mode == ZoomMode::FitToPage
             || self.view_port.zoom_mode == ZoomMode::FitToWidth
@@ -1634,7 +2078,7 @@ impl Reader {
                 scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32,
                 scale_by_dpi(BIG_BAR_HEIGHT, dpi) as i32,
             );
-            let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+            let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
             let (small_thickness, big_thickness) = halves(thickness);
 
             let mut kb_rect = rect![
@@ -1661,7 +2105,7 @@ impl Reader {
                         self.rect.max.x,
                         kb_rect.max.y + thickness
                     ],
-                    BLACK,
+                    theme::current().foreground,
                 );
                 self.children
                     .insert(index, Box::new(separator) as Box<dyn View>);
@@ -1678,7 +2122,7 @@ impl Reader {
                     self.rect.max.x,
                     kb_rect.min.y
                 ],
-                BLACK,
+                theme::current().foreground,
             );
             self.children
                 .insert(index, Box::new(separator) as Box<dyn View>);
@@ -1750,7 +2194,7 @@ impl Reader {
             );
             self.children.insert(2, Box::new(tool_bar) as Box<dyn View>);
 
-            let separator = Filler::new(sp_rect, BLACK);
+            let separator = Filler::new(sp_rect, theme::current().foreground);
             self.children
                 .insert(2, Box::new(separator) as Box<dyn View>);
         }
@@ -1772,7 +2216,7 @@ impl Reader {
             }
 
             let dpi = CURRENT_DEVICE.dpi;
-            let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+            let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
             let small_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
             let index = locate::<TopBar>(self).map(|index| index + 2).unwrap_or(0);
 
@@ -1795,7 +2239,7 @@ impl Reader {
                 );
                 self.children
                     .insert(index, Box::new(results_bar) as Box<dyn View>);
-                let separator = Filler::new(sp_rect, BLACK);
+                let separator = Filler::new(sp_rect, theme::current().foreground);
                 self.children
                     .insert(index, Box::new(separator) as Box<dyn View>);
                 rect.absorb(&sp_rect);
@@ -1837,7 +2281,7 @@ impl Reader {
             self.toggle_tool_bar(false, rq, context);
 
             let dpi = CURRENT_DEVICE.dpi;
-            let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+            let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
             let (small_thickness, big_thickness) = halves(thickness);
             let small_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
             let index = locate::<TopBar>(self).map(|index| index + 2).unwrap_or(0);
@@ -1849,7 +2293,7 @@ impl Reader {
                     self.rect.max.x,
                     self.rect.max.y - small_height + big_thickness
                 ];
-                let separator = Filler::new(sp_rect, BLACK);
+                let separator = Filler::new(sp_rect, theme::current().foreground);
                 self.children
                     .insert(index, Box::new(separator) as Box<dyn View>);
             }
@@ -1871,7 +2315,7 @@ impl Reader {
             self.children
                 .insert(index, Box::new(search_bar) as Box<dyn View>);
 
-            let separator = Filler::new(sp_rect, BLACK);
+            let separator = Filler::new(sp_rect, theme::current().foreground);
             self.children
                 .insert(index, Box::new(separator) as Box<dyn View>);
 
@@ -1931,7 +2375,7 @@ impl Reader {
             }
 
             let dpi = CURRENT_DEVICE.dpi;
-            let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+            let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
             let (small_thickness, big_thickness) = halves(thickness);
             let (small_height, big_height) = (
                 scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32,
@@ -1964,7 +2408,7 @@ impl Reader {
                     self.rect.max.x,
                     self.rect.min.y + small_height + big_thickness
                 ],
-                BLACK,
+                theme::current().foreground,
             );
             self.children
                 .insert(index, Box::new(separator) as Box<dyn View>);
@@ -1981,7 +2425,7 @@ impl Reader {
                             self.rect.max.x,
                             self.rect.max.y - 3 * small_height + big_thickness
                         ],
-                        BLACK,
+                        theme::current().foreground,
                     );
                     self.children
                         .insert(index, Box::new(separator) as Box<dyn View>);
@@ -2010,7 +2454,7 @@ impl Reader {
                             self.rect.max.x,
                             self.rect.max.y - 2 * small_height + big_thickness
                         ],
-                        BLACK,
+                        theme::current().foreground,
                     );
                     self.children
                         .insert(index, Box::new(separator) as Box<dyn View>);
@@ -2041,7 +2485,7 @@ impl Reader {
                         self.rect.max.x,
                         self.rect.max.y - (small_height + tb_height) as i32 + big_thickness
                     ],
-                    BLACK,
+                    theme::current().foreground,
                 );
                 self.children
                     .insert(index, Box::new(separator) as Box<dyn View>);
@@ -2070,7 +2514,7 @@ impl Reader {
                     self.rect.max.x,
                     self.rect.max.y - small_height + big_thickness
                 ],
-                BLACK,
+                theme::current().foreground,
             );
             self.children
                 .insert(index, Box::new(separator) as Box<dyn View>);
@@ -2081,7 +2525,7 @@ impl Reader {
                 next_page: doc.resolve_location(Location::Next(self.current_page)),
             };
 
-            let bottom_bar = BottomBar::new(
+            let mut bottom_bar = BottomBar::new(
                 rect![
                     self.rect.min.x,
                     self.rect.max.y - small_height + big_thickness,
@@ -2095,6 +2539,7 @@ impl Reader {
                 &neighbors,
                 self.synthetic,
             );
+            bottom_bar.update_print_page_label(self.print_page(neighbors.next_page), rq);
             self.children
                 .insert(index, Box::new(bottom_bar) as Box<dyn View>);
 
@@ -2162,6 +2607,69 @@ impl Reader {
         }
     }
 
+    // Opens the image under `center`. Pages of fixed-layout documents without any text,
+    // like comics and scans, are images in themselves when `whole_page` is set.
+    fn open_image_viewer(
+        &mut self,
+        center: Point,
+        whole_page: bool,
+        rq: &mut RenderQueue,
+        context: &Context,
+    ) -> bool {
+        let mut found = None;
+
+        for chunk in &self.chunks {
+            let chunk_rect = chunk.frame - chunk.frame.min + chunk.position;
+            if !chunk_rect.includes(center) {
+                continue;
+            }
+
+            let mut doc = self.doc.lock().unwrap();
+            found = doc
+                .images(Location::Exact(chunk.location))
+                .and_then(|(images, _)| {
+                    images.into_iter().find(|image| {
+                        let rect =
+                            (*image * chunk.scale).to_rect() - chunk.frame.min + chunk.position;
+                        rect.includes(center)
+                    })
+                })
+                .or_else(|| {
+                    let has_text = self
+                        .text
+                        .get(&chunk.location)
+                        .is_some_and(|words| !words.is_empty());
+                    if whole_page && !has_text {
+                        let (width, height) = doc.dims(chunk.location)?;
+                        Some(bndr![0.0, 0.0, width, height])
+                    } else {
+                        None
+                    }
+                })
+                .map(|boundary| (chunk.location, boundary));
+            break;
+        }
+
+        if let Some((location, boundary)) = found {
+            let image_viewer = ImageViewer::new(
+                self.rect,
+                self.doc.clone(),
+                location,
+                boundary,
+                context.settings.display.image_dithering,
+            );
+            rq.add(RenderData::new(
+                image_viewer.id(),
+                *image_viewer.rect(),
+                UpdateMode::Gui,
+            ));
+            self.children.push(Box::new(image_viewer) as Box<dyn View>);
+            true
+        } else {
+            false
+        }
+    }
+
     fn toggle_edit_note(
         &mut self,
         text: Option<String>,
@@ -2312,6 +2820,16 @@ impl Reader {
         }
     }
 
+    /// The book being read.
+    pub fn info(&self) -> &Info {
+        &self.info
+    }
+
+    /// The current page, starting at zero, and the number of pages.
+    pub fn position(&self) -> (usize, usize) {
+        (self.current_page, self.pages_count)
+    }
+
     pub fn toggle_annotation_menu(
         &mut self,
         annot: &Annotation,
@@ -2422,6 +2940,7 @@ impl Reader {
                 .reader
                 .as_ref()
                 .map_or(false, |r| !r.page_names.is_empty())
+                || !self.page_list.is_empty()
             {
                 entries.push(EntryKind::Command(
                     "Go To".to_string(),
@@ -2541,6 +3060,17 @@ impl Reader {
                 entries.push(EntryKind::Command("Save".to_string(), EntryId::Save));
             }
 
+            if !self.history.is_empty() {
+                entries.push(EntryKind::Command("Back".to_string(), EntryId::GoBack));
+            }
+
+            if !self.forward_history.is_empty() {
+                entries.push(EntryKind::Command(
+                    "Forward".to_string(),
+                    EntryId::GoForward,
+                ));
+            }
+
             if self
                 .info
                 .reader
@@ -2565,6 +3095,85 @@ impl Reader {
                 ));
             }
 
+            if self.reflowable && !context.settings.reader.typography_presets.is_empty() {
+                let preset_entries = context
+                    .settings
+                    .reader
+                    .typography_presets
+                    .iter()
+                    .enumerate()
+                    .map(|(index, preset)| {
+                        EntryKind::Command(preset.name.clone(), EntryId::SetTypographyPreset(index))
+                    })
+                    .collect();
+                entries.push(EntryKind::SubMenu("Typography".to_string(), preset_entries));
+            }
+
+            if self.reflowable {
+                entries.push(EntryKind::Command(
+                    "Use as Default".to_string(),
+                    EntryId::UseAsDefault,
+                ));
+            }
+
+            if !self.reflowable {
+                let brightness_entries = (-4..=4)
+                    .map(|v| {
+                        let b = v as f32 * BRIGHTNESS_STEP;
+                        EntryKind::RadioButton(
+                            if v == 0 {
+                                "0%".to_string()
+                            } else {
+                                format!("{:+}%", v * 10)
+                            },
+                            EntryId::SetBrightness(v),
+                            (b - self.contrast.brightness).abs() < f32::EPSILON,
+                        )
+                    })
+                    .collect();
+                entries.push(EntryKind::SubMenu(
+                    "Brightness".to_string(),
+                    brightness_entries,
+                ));
+            }
+
+            let current_interval = self.auto_page_turn.as_ref().map(|apt| apt.interval);
+            let mut intervals = vec![10, 20, 30, 60, 120];
+            intervals.push(context.settings.reader.auto_page_turn.interval);
+            intervals.sort_unstable();
+            intervals.dedup();
+            let mut auto_page_turn_entries = vec![EntryKind::RadioButton(
+                "Off".to_string(),
+                EntryId::SetAutoPageTurn(None),
+                current_interval.is_none(),
+            )];
+            auto_page_turn_entries.extend(intervals.into_iter().filter(|&i| i > 0).map(|i| {
+                EntryKind::RadioButton(
+                    format!("{} s", i),
+                    EntryId::SetAutoPageTurn(Some(i)),
+                    current_interval == Some(i),
+                )
+            }));
+            entries.push(EntryKind::SubMenu(
+                "Auto Page Turn".to_string(),
+                auto_page_turn_entries,
+            ));
+
+            if self.reflowable {
+                entries.push(EntryKind::CheckBox(
+                    "Read Aloud".to_string(),
+                    EntryId::ToggleReadAloud,
+                    self.speech.is_some(),
+                ));
+                if let Some(speech) = self.speech.as_ref() {
+                    entries.push(EntryKind::CheckBox(
+                        "Pause Reading".to_string(),
+                        EntryId::ToggleSpeechPause,
+                        speech.paused,
+                    ));
+                }
+            }
+
             if !entries.is_empty() {
                 entries.push(EntryKind::Separator);
             }
@@ -2575,6 +3184,20 @@ impl Reader {
                 context.fb.dithered(),
             ));
 
+            entries.push(EntryKind::CheckBox(
+                "Night Mode".to_string(),
+                EntryId::ToggleNightMode,
+                self.night_mode,
+            ));
+
+            if !self.ephemeral {
+                entries.push(EntryKind::CheckBox(
+                    "Two Pages in Landscape".to_string(),
+                    EntryId::ToggleTwoPages,
+                    self.view_port.two_pages,
+                ));
+            }
+
             let mut title_menu = Menu::new(
                 rect,
                 ViewId::TitleMenu,
@@ -2629,7 +3252,7 @@ impl Reader {
                 .and_then(|r| r.font_family.clone())
                 .unwrap_or_else(|| context.settings.reader.font_family.clone());
             families.insert(DEFAULT_FONT_FAMILY.to_string());
-            let entries = families
+            let mut entries: Vec<EntryKind> = families
                 .iter()
                 .map(|f| {
                     EntryKind::RadioButton(
@@ -2639,6 +3262,37 @@ impl Reader {
                     )
                 })
                 .collect();
+            let current_stylesheet = stylesheet_name(&self.info, &context.settings);
+            let mut stylesheets = vec![EntryKind::RadioButton(
+                "None".to_string(),
+                EntryId::SetStylesheet(None),
+                current_stylesheet.is_none(),
+            )];
+            stylesheets.extend(
+                stylesheet_names(&context.settings.reader.styles_path)
+                    .into_iter()
+                    .map(|name| {
+                        let selected = current_stylesheet == Some(name.as_str());
+                        EntryKind::RadioButton(
+                            name.clone(),
+                            EntryId::SetStylesheet(Some(name)),
+                            selected,
+                        )
+                    }),
+            );
+            entries.push(EntryKind::Separator);
+            entries.push(EntryKind::SubMenu("Style Sheet".to_string(), stylesheets));
+            let vertical_text = self
+                .info
+                .reader
+                .as_ref()
+                .and_then(|r| r.vertical_text)
+                .unwrap_or(false);
+            entries.push(EntryKind::CheckBox(
+                "Vertical Text".to_string(),
+                EntryId::ToggleVerticalText,
+                vertical_text,
+            ));
             let font_family_menu = Menu::new(
                 rect,
                 ViewId::FontFamilyMenu,
@@ -3101,6 +3755,8 @@ impl Reader {
                     EntryId::ApplyCroppings(current_page, PageScheme::EvenOdd),
                     is_split.is_some() && is_split.unwrap(),
                 ),
+                EntryKind::Separator,
+                EntryKind::Command("Auto Detect".to_string(), EntryId::AutoCropMargins),
             ];
 
             let is_applied = self
@@ -3203,7 +3859,12 @@ impl Reader {
         {
             let mut doc = self.doc.lock().unwrap();
 
-            doc.layout(width, height, font_size, CURRENT_DEVICE.dpi);
+            doc.layout(
+                layout_width(width, height, self.view_port.two_pages),
+                height,
+                font_size,
+                CURRENT_DEVICE.dpi,
+            );
 
             if self.synthetic {
                 let current_page = self.current_page.min(doc.pages_count() - 1);
@@ -3304,26 +3965,223 @@ impl Reader {
         self.update_bottom_bar(rq);
     }
 
-    fn set_line_height(
-        &mut self,
-        line_height: f32,
-        hub: &Hub,
-        rq: &mut RenderQueue,
-        context: &mut Context,
-    ) {
-        if Arc::strong_count(&self.doc) > 1 {
+    // Makes the settings of this book the defaults of the books that don't have their own.
+    // The contrast and the cropping margins only apply to fixed-layout documents.
+    fn use_as_default(&mut self, hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
+        let Some(r) = self.info.reader.as_ref() else {
             return;
+        };
+
+        let defaults = &mut context.settings.reader;
+
+        if let Some(font_family) = r.font_family.as_ref() {
+            defaults.font_family = font_family.clone();
         }
 
-        if let Some(ref mut r) = self.info.reader {
-            r.line_height = Some(line_height);
+        if let Some(font_size) = r.font_size {
+            defaults.font_size = font_size;
         }
 
-        {
-            let mut doc = self.doc.lock().unwrap();
-            doc.set_line_height(line_height);
+        if let Some(margin_width) = r.margin_width {
+            defaults.margin_width = margin_width;
+        }
 
-            if self.synthetic {
+        if let Some(line_height) = r.line_height {
+            defaults.line_height = line_height;
+        }
+
+        if let Some(text_align) = r.text_align {
+            defaults.text_align = text_align;
+        }
+
+        if !self.reflowable {
+            defaults.contrast_exponent = self.contrast.exponent;
+            defaults.contrast_gray = self.contrast.gray;
+            defaults.brightness = self.contrast.brightness;
+            defaults.cropping_margins = r.cropping_margins.clone();
+        }
+
+        let notif = Notification::new(
+            None,
+            "Settings saved as default.".to_string(),
+            false,
+            hub,
+            rq,
+            context,
+        );
+        self.children.push(Box::new(notif) as Box<dyn View>);
+    }
+
+    fn set_typography_preset(
+        &mut self,
+        index: usize,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) {
+        if Arc::strong_count(&self.doc) > 1 {
+            return;
+        }
+
+        let Some(preset) = context.settings.reader.typography_presets.get(index) else {
+            return;
+        };
+
+        if let Some(ref mut r) = self.info.reader {
+            r.font_family = preset.font_family.clone().or(r.font_family.take());
+            r.font_size = preset.font_size.or(r.font_size);
+            r.margin_width = preset.margin_width.or(r.margin_width);
+            r.line_height = preset.line_height.or(r.line_height);
+            r.text_align = preset.text_align.or(r.text_align);
+        }
+
+        let (width, height) = context.display.dims;
+        {
+            let mut doc = self.doc.lock().unwrap();
+
+            if let Some(font_family) = preset.font_family.as_ref() {
+                let font_path = if font_family == DEFAULT_FONT_FAMILY {
+                    "fonts"
+                } else {
+                    &context.settings.reader.font_path
+                };
+                doc.set_font_family(font_family, font_path);
+            }
+
+            if let Some(margin_width) = preset.margin_width {
+                doc.set_margin_width(margin_width);
+            }
+
+            if let Some(line_height) = preset.line_height {
+                doc.set_line_height(line_height);
+            }
+
+            if let Some(text_align) = preset.text_align {
+                doc.set_text_align(text_align);
+            }
+
+            if let Some(font_size) = preset.font_size {
+                doc.layout(
+                    layout_width(width, height, self.view_port.two_pages),
+                    height,
+                    font_size,
+                    CURRENT_DEVICE.dpi,
+                );
+            }
+
+            if self.synthetic {
+                let current_page = self.current_page.min(doc.pages_count() - 1);
+                if let Some(location) = doc.resolve_location(Location::Exact(current_page)) {
+                    self.current_page = location;
+                }
+            } else {
+                let ratio = doc.pages_count() / self.pages_count;
+                self.pages_count = doc.pages_count();
+                self.current_page = (ratio * self.current_page).min(self.pages_count - 1);
+            }
+        }
+
+        self.cache.clear();
+        self.text.clear();
+        self.update(None, hub, rq, context);
+        self.update_tool_bar(rq, context);
+        self.update_bottom_bar(rq);
+    }
+
+    fn toggle_vertical_text(&mut self, hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
+        if Arc::strong_count(&self.doc) > 1 {
+            return;
+        }
+
+        let mut vertical = false;
+
+        if let Some(ref mut r) = self.info.reader {
+            vertical = !r.vertical_text.unwrap_or(false);
+            r.vertical_text = Some(vertical);
+        }
+
+        {
+            let mut doc = self.doc.lock().unwrap();
+            doc.set_vertical_text(vertical);
+
+            if self.synthetic {
+                let current_page = self.current_page.min(doc.pages_count() - 1);
+                if let Some(location) = doc.resolve_location(Location::Exact(current_page)) {
+                    self.current_page = location;
+                }
+            } else {
+                self.pages_count = doc.pages_count();
+                self.current_page = self.current_page.min(self.pages_count - 1);
+            }
+        }
+
+        self.cache.clear();
+        self.text.clear();
+        self.update(None, hub, rq, context);
+        self.update_tool_bar(rq, context);
+        self.update_bottom_bar(rq);
+    }
+
+    fn set_stylesheet(
+        &mut self,
+        name: Option<&str>,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) {
+        if Arc::strong_count(&self.doc) > 1 {
+            return;
+        }
+
+        if let Some(ref mut r) = self.info.reader {
+            r.stylesheet = Some(name.unwrap_or_default().to_string());
+        }
+
+        {
+            let mut doc = self.doc.lock().unwrap();
+            let css =
+                name.and_then(|name| load_stylesheet(&context.settings.reader.styles_path, name));
+
+            doc.set_user_stylesheet(css);
+
+            if self.synthetic {
+                let current_page = self.current_page.min(doc.pages_count() - 1);
+                if let Some(location) = doc.resolve_location(Location::Exact(current_page)) {
+                    self.current_page = location;
+                }
+            } else {
+                self.pages_count = doc.pages_count();
+                self.current_page = self.current_page.min(self.pages_count - 1);
+            }
+        }
+
+        self.cache.clear();
+        self.text.clear();
+        self.update(None, hub, rq, context);
+        self.update_tool_bar(rq, context);
+        self.update_bottom_bar(rq);
+    }
+
+    fn set_line_height(
+        &mut self,
+        line_height: f32,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) {
+        if Arc::strong_count(&self.doc) > 1 {
+            return;
+        }
+
+        if let Some(ref mut r) = self.info.reader {
+            r.line_height = Some(line_height);
+        }
+
+        {
+            let mut doc = self.doc.lock().unwrap();
+            doc.set_line_height(line_height);
+
+            if self.synthetic {
                 let current_page = self.current_page.min(doc.pages_count() - 1);
                 if let Some(location) = doc.resolve_location(Location::Exact(current_page)) {
                     self.current_page = location;
@@ -3412,6 +4270,444 @@ impl Reader {
         rq.add(RenderData::new(self.id, rect, UpdateMode::Gui));
     }
 
+    fn perform_tap_action(
+        &mut self,
+        action: TapAction,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) {
+        match action {
+            TapAction::NextPage => self.go_to_neighbor(CycleDir::Next, hub, rq, context),
+            TapAction::PreviousPage => self.go_to_neighbor(CycleDir::Previous, hub, rq, context),
+            TapAction::ToggleBars => self.toggle_bars(None, hub, rq, context),
+            TapAction::ToggleFrontlight => {
+                hub.send(Event::ToggleFrontlight).ok();
+            }
+            TapAction::ToggleBookmark => self.toggle_bookmark(rq),
+            TapAction::GoToPage => {
+                hub.send(Event::Toggle(ViewId::GoToPage)).ok();
+            }
+            TapAction::TableOfContents => {
+                // Documents opened from memory have no table of contents worth showing,
+                // the corner is used to leave them instead.
+                if self.ephemeral && self.info.file.path == PathBuf::from(MEM_SCHEME) {
+                    self.quit(context);
+                    hub.send(Event::Back).ok();
+                } else {
+                    hub.send(Event::Show(ViewId::TableOfContents)).ok();
+                }
+            }
+            TapAction::LastPage => self.go_back(hub, rq, context),
+            TapAction::None => (),
+        }
+    }
+
+    fn auto_page_turn_rect(&self) -> Rectangle {
+        let thickness = scale_thickness(THICKNESS_MEDIUM, CURRENT_DEVICE.dpi) as i32;
+        rect![
+            self.rect.min.x,
+            self.rect.max.y - thickness,
+            self.rect.max.x,
+            self.rect.max.y
+        ]
+    }
+
+    fn set_auto_page_turn(
+        &mut self,
+        interval: Option<u32>,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &Context,
+    ) {
+        let mut countdown = false;
+
+        if let Some(apt) = self.auto_page_turn.take() {
+            apt.running.store(false, AtomicOrdering::Relaxed);
+            countdown = apt.countdown;
+        }
+
+        if let Some(interval) = interval.filter(|&i| i > 0) {
+            let running = Arc::new(AtomicBool::new(true));
+            let running2 = running.clone();
+            let hub2 = hub.clone();
+
+            thread::spawn(move || loop {
+                thread::sleep(Duration::from_secs(1));
+                if !running2.load(AtomicOrdering::Relaxed) {
+                    break;
+                }
+                if hub2.send(Event::AutoPageTurnTick).is_err() {
+                    break;
+                }
+            });
+
+            countdown |= context.settings.reader.auto_page_turn.countdown;
+            info!(interval, "auto page turn started");
+            self.auto_page_turn = Some(AutoPageTurn {
+                interval,
+                remaining: interval,
+                paused: false,
+                countdown: context.settings.reader.auto_page_turn.countdown,
+                position: (self.current_page, self.view_port.page_offset),
+                running,
+            });
+        } else {
+            info!("auto page turn stopped");
+        }
+
+        if countdown {
+            rq.add(RenderData::new(
+                self.id,
+                self.auto_page_turn_rect(),
+                UpdateMode::Gui,
+            ));
+        }
+    }
+
+    fn toggle_auto_page_turn_pause(&mut self, rq: &mut RenderQueue) {
+        if let Some(apt) = self.auto_page_turn.as_mut() {
+            apt.paused = !apt.paused;
+            if apt.countdown {
+                rq.add(RenderData::new(
+                    self.id,
+                    self.auto_page_turn_rect(),
+                    UpdateMode::Gui,
+                ));
+            }
+        }
+    }
+
+    fn tick_auto_page_turn(&mut self, hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
+        let position = (self.current_page, self.view_port.page_offset);
+        let strip_width = self.auto_page_turn_rect().width() as i32;
+
+        let Some(apt) = self.auto_page_turn.as_mut() else {
+            return;
+        };
+
+        // Bars, menus and dialogs suspend the countdown until they're dismissed.
+        if apt.paused || !self.children.is_empty() {
+            return;
+        }
+
+        let progress_width = apt.progress_width(strip_width);
+
+        if apt.position != position {
+            apt.position = position;
+            apt.remaining = apt.interval;
+        } else {
+            apt.remaining = apt.remaining.saturating_sub(1);
+        }
+
+        if apt.remaining == 0 {
+            // Turning pages without touching the screen shouldn't let the device fall asleep.
+            hub.send(Event::Device(DeviceEvent::UserActivity)).ok();
+            self.go_to_neighbor(CycleDir::Next, hub, rq, context);
+
+            let position = (self.current_page, self.view_port.page_offset);
+            if let Some(apt) = self.auto_page_turn.as_mut() {
+                if apt.position == position {
+                    self.set_auto_page_turn(None, hub, rq, context);
+                    return;
+                }
+                apt.position = position;
+                apt.remaining = apt.interval;
+            }
+        }
+
+        // The strip is only refreshed when its progress is visibly different.
+        if self
+            .auto_page_turn
+            .as_ref()
+            .is_some_and(|apt| apt.countdown && apt.progress_width(strip_width) != progress_width)
+        {
+            rq.add(RenderData::new(
+                self.id,
+                self.auto_page_turn_rect(),
+                UpdateMode::Gui,
+            ));
+        }
+    }
+
+    // The location of the start of the chapter containing the given location.
+    fn chapter_location(&self, location: usize) -> Option<usize> {
+        let mut doc = self.doc.lock().unwrap();
+        let toc = self.toc().or_else(|| doc.toc())?;
+        let chapter = doc
+            .chapter(location, &toc)
+            .map(|(c, _)| c.location.clone())?;
+        doc.resolve_location(chapter)
+    }
+
+    fn toggle_read_aloud(&mut self, hub: &Hub, rq: &mut RenderQueue, context: &Context) {
+        if self.speech.is_some() {
+            self.stop_speech(rq);
+            info!("read aloud stopped");
+            return;
+        }
+
+        info!("read aloud started");
+        self.speech = Some(Speech {
+            speaker: None,
+            paused: false,
+            page: self.current_page,
+            chapter: self.chapter_location(self.current_page),
+            sentences: Vec::new(),
+            texts: Vec::new(),
+            current: 0,
+            carry: None,
+        });
+        self.speak_page(hub, context);
+    }
+
+    // Splits the visible text into sentences and starts speaking them.
+    fn speak_page(&mut self, hub: &Hub, context: &Context) {
+        let Some(speech) = self.speech.as_mut() else {
+            return;
+        };
+
+        let skip = speech
+            .carry
+            .take()
+            .filter(|(page, _)| *page == self.current_page)
+            .map(|(_, location)| location);
+        let ws = word_separator(&self.info.language);
+        let words: Vec<&BoundedText> = self
+            .chunks
+            .iter()
+            .flat_map(|chunk| {
+                self.text
+                    .get(&chunk.location)
+                    .into_iter()
+                    .flatten()
+                    .filter(move |word| (word.rect * chunk.scale).to_rect().overlaps(&chunk.frame))
+            })
+            .filter(|word| skip.is_none_or(|location| word.location > location))
+            .collect();
+
+        let ranges = speech::sentences(&words.iter().map(|w| w.text.as_str()).collect::<Vec<_>>());
+        let mut sentences = Vec::with_capacity(ranges.len());
+        let mut texts = Vec::with_capacity(ranges.len());
+
+        for range in ranges {
+            let sentence = &words[range];
+            sentences.push([sentence[0].location, sentence[sentence.len() - 1].location]);
+            texts.push(
+                sentence
+                    .iter()
+                    .map(|w| w.text.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(ws),
+            );
+        }
+
+        // A sentence running over the next page is spoken at once.
+        let mut carry = None;
+        if let (Some(last_word), Some(last_chunk), Some(text)) =
+            (words.last(), self.chunks.last(), texts.last_mut())
+        {
+            if !speech::ends_sentence(&last_word.text) {
+                let mut doc = self.doc.lock().unwrap();
+                if let Some(next_page) = doc.resolve_location(Location::Next(last_chunk.location)) {
+                    if let Some((next_words, _)) = doc.words(Location::Exact(next_page)) {
+                        if let Some(end) = next_words
+                            .iter()
+                            .position(|w| speech::ends_sentence(&w.text))
+                        {
+                            for word in &next_words[..=end] {
+                                text.push_str(ws);
+                                text.push_str(&word.text);
+                            }
+                            carry = Some((next_page, next_words[end].location));
+                        }
+                    }
+                }
+            }
+        }
+
+        speech.page = self.current_page;
+        speech.sentences = sentences;
+        speech.texts = texts;
+        speech.current = 0;
+        speech.carry = carry;
+        // The previous speaker is dropped before the next one starts.
+        speech.speaker = None;
+        if !speech.paused {
+            speech.speaker = Some(Speaker::new(
+                &context.settings.reader.speech,
+                &self.info.language,
+                speech.texts.clone(),
+                0,
+                hub,
+            ));
+        }
+    }
+
+    fn toggle_speech_pause(&mut self, hub: &Hub, context: &Context) {
+        if let Some(speech) = self.speech.as_mut() {
+            speech.paused = !speech.paused;
+            speech.speaker = if speech.paused {
+                None
+            } else {
+                Some(Speaker::new(
+                    &context.settings.reader.speech,
+                    &self.info.language,
+                    speech.texts.clone(),
+                    speech.current,
+                    hub,
+                ))
+            };
+        }
+    }
+
+    fn stop_speech(&mut self, rq: &mut RenderQueue) {
+        if let Some(speech) = self.speech.take() {
+            if let Some(rect) = speech
+                .sentences
+                .get(speech.current)
+                .and_then(|sentence| self.sentence_rect(sentence))
+            {
+                rq.add(RenderData::new(self.id, rect, UpdateMode::Gui));
+            }
+        }
+    }
+
+    // The screen area covered by the given sentence and its underline.
+    fn sentence_rect(&self, sentence: &[TextLocation; 2]) -> Option<Rectangle> {
+        let thickness = scale_thickness(THICKNESS_MEDIUM, CURRENT_DEVICE.dpi) as i32;
+        let [start, end] = *sentence;
+        let mut result: Option<Rectangle> = None;
+
+        for chunk in &self.chunks {
+            for word in self
+                .text
+                .get(&chunk.location)
+                .into_iter()
+                .flatten()
+                .filter(|w| w.location >= start && w.location <= end)
+            {
+                let mut rect =
+                    (word.rect * chunk.scale).to_rect() - chunk.frame.min + chunk.position;
+                rect.max.y += thickness;
+                if let Some(r) = result.as_mut() {
+                    r.absorb(&rect);
+                } else {
+                    result = Some(rect);
+                }
+            }
+        }
+
+        result
+    }
+
+    fn handle_speech_event(
+        &mut self,
+        id: u32,
+        event: SpeechEvent,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) {
+        // Events from interrupted speakers are ignored.
+        if self
+            .speech
+            .as_ref()
+            .and_then(|s| s.speaker.as_ref())
+            .map(Speaker::id)
+            != Some(id)
+        {
+            return;
+        }
+
+        match event {
+            SpeechEvent::Sentence(index) => {
+                let Some(speech) = self.speech.as_mut() else {
+                    return;
+                };
+                let previous = std::mem::replace(&mut speech.current, index);
+                let Some(speech) = self.speech.as_ref() else {
+                    return;
+                };
+                for sentence in [previous, index]
+                    .iter()
+                    .filter_map(|i| speech.sentences.get(*i))
+                {
+                    if let Some(rect) = self.sentence_rect(sentence) {
+                        rq.add(RenderData::new(self.id, rect, UpdateMode::Gui));
+                    }
+                }
+                // Listening without touching the screen shouldn't let the device fall asleep.
+                hub.send(Event::Device(DeviceEvent::UserActivity)).ok();
+            }
+            SpeechEvent::Finished => {
+                let next_page = self.chunks.last().and_then(|chunk| {
+                    let mut doc = self.doc.lock().unwrap();
+                    doc.resolve_location(Location::Next(chunk.location))
+                });
+                let chapter = self.speech.as_ref().and_then(|s| s.chapter);
+                if next_page.is_none_or(|page| self.chapter_location(page) != chapter) {
+                    self.stop_speech(rq);
+                    return;
+                }
+                self.go_to_neighbor(CycleDir::Next, hub, rq, context);
+                if self
+                    .speech
+                    .as_ref()
+                    .is_some_and(|s| s.page != self.current_page)
+                {
+                    self.stop_speech(rq);
+                }
+            }
+            SpeechEvent::Failed => {
+                self.stop_speech(rq);
+                let notif = Notification::new(
+                    None,
+                    "Text-to-speech isn't available.".to_string(),
+                    false,
+                    hub,
+                    rq,
+                    context,
+                );
+                self.children.push(Box::new(notif) as Box<dyn View>);
+            }
+        }
+    }
+
+    fn handle_sync_progress(
+        &mut self,
+        remote: &kosync::Progress,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) {
+        let local = kosync::percentage(self.current_page, self.pages_count, self.reflowable);
+        let location = kosync::location(remote, self.pages_count, self.reflowable);
+        let current_location = {
+            let mut doc = self.doc.lock().unwrap();
+            doc.resolve_location(Location::Exact(location))
+        };
+
+        if current_location.is_none_or(|l| l == self.current_page) {
+            return;
+        }
+
+        // Only move forward, going back is left to the user.
+        let message = if remote.percentage > local {
+            self.go_to_page(location, true, hub, rq, context);
+            format!("Synced to the position of {}.", remote.device)
+        } else {
+            format!(
+                "{} is at {:.0}%.",
+                remote.device,
+                100.0 * remote.percentage.clamp(0.0, 1.0)
+            )
+        };
+
+        let notif = Notification::new(None, message, false, hub, rq, context);
+        self.children.push(Box::new(notif) as Box<dyn View>);
+    }
+
     fn set_contrast_exponent(
         &mut self,
         exponent: f32,
@@ -3442,6 +4738,20 @@ impl Reader {
         self.update_tool_bar(rq, context);
     }
 
+    fn set_brightness(
+        &mut self,
+        brightness: f32,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) {
+        if let Some(ref mut r) = self.info.reader {
+            r.brightness = Some(brightness);
+        }
+        self.contrast.brightness = brightness;
+        self.update(None, hub, rq, context);
+    }
+
     fn set_zoom_mode(
         &mut self,
         zoom_mode: ZoomMode,
@@ -3534,6 +4844,48 @@ impl Reader {
         self.update(None, hub, rq, context);
     }
 
+    // Split croppings get a detection pass for each parity, using the facing page.
+    fn auto_crop_margins(&mut self, hub: &Hub, rq: &mut RenderQueue, context: &Context) {
+        let current_page = self.current_page;
+        let is_split = self
+            .info
+            .reader
+            .as_ref()
+            .and_then(|r| r.cropping_margins.as_ref())
+            .is_some_and(CroppingMargins::is_split);
+
+        let facing_page = if !is_split || self.pages_count < 2 {
+            None
+        } else if current_page + 1 < self.pages_count {
+            Some(current_page + 1)
+        } else {
+            Some(current_page - 1)
+        };
+
+        let (margin, facing_margin) = {
+            let mut doc = self.doc.lock().unwrap();
+            let (pixmap, _) = build_pixmap(&self.rect, doc.as_mut(), current_page);
+            let facing_margin = facing_page.map(|index| {
+                let (pixmap, _) = build_pixmap(&self.rect, doc.as_mut(), index);
+                (index, detect_margin(&pixmap))
+            });
+            (detect_margin(&pixmap), facing_margin)
+        };
+
+        if let Some((index, facing_margin)) = facing_margin {
+            if let Some(c) = self
+                .info
+                .reader
+                .as_mut()
+                .and_then(|r| r.cropping_margins.as_mut())
+            {
+                *c.margin_mut(index) = facing_margin;
+            }
+        }
+
+        self.crop_margins(current_page, &margin, hub, rq, context);
+    }
+
     fn toc(&self) -> Option<Vec<TocEntry>> {
         let mut index = 0;
         self.info
@@ -3576,7 +4928,25 @@ impl Reader {
         toc
     }
 
+    // Returns the label of the print page on which the current page ends.
+    fn print_page(&self, next_page: Option<usize>) -> Option<String> {
+        let end = next_page.unwrap_or(self.pages_count);
+        self.page_list
+            .range(..end.max(self.current_page + 1))
+            .next_back()
+            .map(|(_, label)| label.clone())
+    }
+
     fn find_page_by_name(&self, name: &str) -> Option<usize> {
+        if let Some(location) = self
+            .page_list
+            .iter()
+            .find(|(_, label)| label.as_str() == name)
+            .map(|(location, _)| *location)
+        {
+            return Some(location);
+        }
+
         self.info.reader.as_ref().and_then(|r| {
             if let Ok(a) = name.parse::<u32>() {
                 r.page_names
@@ -3731,6 +5101,12 @@ impl Reader {
             s.running.store(false, AtomicOrdering::Relaxed);
         }
 
+        if let Some(apt) = self.auto_page_turn.take() {
+            apt.running.store(false, AtomicOrdering::Relaxed);
+        }
+
+        self.speech = None;
+
         if self.ephemeral {
             return;
         }
@@ -3757,19 +5133,32 @@ impl Reader {
 
             r.rotation = Some(CURRENT_DEVICE.to_canonical(context.display.rotation));
 
-            if (self.contrast.exponent - DEFAULT_CONTRAST_EXPONENT).abs() > f32::EPSILON {
-                r.contrast_exponent = Some(self.contrast.exponent);
-                if (self.contrast.gray - DEFAULT_CONTRAST_GRAY).abs() > f32::EPSILON {
-                    r.contrast_gray = Some(self.contrast.gray);
-                } else {
-                    r.contrast_gray = None;
-                }
-            } else {
-                r.contrast_exponent = None;
-                r.contrast_gray = None;
-            }
+            // The values equal to the defaults follow them when they change.
+            let defaults = &context.settings.reader;
+            let own = |value: f32, default: f32| {
+                Some(value).filter(|v| (v - default).abs() > f32::EPSILON)
+            };
+            r.contrast_exponent = own(self.contrast.exponent, defaults.contrast_exponent);
+            r.contrast_gray = own(self.contrast.gray, defaults.contrast_gray);
+            r.brightness = own(self.contrast.brightness, defaults.brightness);
+
+            context.library.sync_reader_info(&self.info.file.path, r);
+        }
 
-            context.library.sync_reader_info(&self.info.file.path, r);
+        if context.settings.sync.enabled {
+            push_sync_progress(
+                &context.settings.sync,
+                context.library.home.join(&self.info.file.path),
+                kosync::progress(self.current_page, self.reflowable),
+                kosync::percentage(self.current_page, self.pages_count, self.reflowable),
+                context.online,
+            );
+        }
+
+        if self.annotated && context.settings.webdav.annotations.is_some() && !context.online {
+            sync_queue::push(sync_queue::Operation::Annotations {
+                path: self.info.file.path.clone(),
+            });
         }
     }
 
@@ -3946,6 +5335,14 @@ impl View for Reader {
                 }
                 true
             }
+            Event::Gesture(GestureEvent::MultiSwipe { dir, .. }) => {
+                match dir {
+                    Dir::West => self.go_back(hub, rq, context),
+                    Dir::East => self.go_forward(hub, rq, context),
+                    Dir::North | Dir::South => self.toggle_night_mode(rq, context),
+                }
+                true
+            }
             Event::Gesture(GestureEvent::Corner { dir, .. }) => {
                 match dir {
                     DiagDir::NorthWest => self.go_to_bookmark(CycleDir::Previous, hub, rq, context),
@@ -3957,6 +5354,9 @@ impl View for Reader {
                         BottomRightGestureAction::ToggleInverted => {
                             hub.send(Event::Select(EntryId::ToggleInverted)).ok();
                         }
+                        BottomRightGestureAction::ToggleNightMode => {
+                            self.toggle_night_mode(rq, context);
+                        }
                     },
                     DiagDir::SouthWest => {
                         if context.settings.frontlight_presets.len() > 1 {
@@ -4097,12 +5497,12 @@ impl View for Reader {
                                         rect.absorb(&next_rect);
                                     }
                                 } else {
-                                    rq.add(RenderData::new(self.id, rect, UpdateMode::Fast));
+                                    selection.drag(rect, self.id, rq);
                                     rect = next_rect;
                                 }
                                 i += 1;
                             }
-                            rq.add(RenderData::new(self.id, rect, UpdateMode::Fast));
+                            selection.drag(rect, self.id, rq);
                         }
                     }
 
@@ -4126,12 +5526,12 @@ impl View for Reader {
                                         rect.absorb(&prev_rect);
                                     }
                                 } else {
-                                    rq.add(RenderData::new(self.id, rect, UpdateMode::Fast));
+                                    selection.drag(rect, self.id, rq);
                                     rect = prev_rect;
                                 }
                                 i -= 1;
                             }
-                            rq.add(RenderData::new(self.id, rect, UpdateMode::Fast));
+                            selection.drag(rect, self.id, rq);
                         }
                     }
 
@@ -4147,6 +5547,9 @@ impl View for Reader {
                 ..
             }) if self.state == State::Selection(id) => {
                 self.state = State::Idle;
+                if let Some(rect) = self.selection.as_mut().and_then(|s| s.dragged.take()) {
+                    rq.add(RenderData::new(self.id, rect, UpdateMode::Gui));
+                }
                 let radius = scale_by_dpi(24.0, CURRENT_DEVICE.dpi) as i32;
                 self.toggle_selection_menu(
                     Rectangle::from_disk(position, radius),
@@ -4278,6 +5681,38 @@ impl View for Reader {
                     return true;
                 }
 
+                if self.speech.is_some()
+                    && context.settings.reader.speech.pause_on_tap
+                    && matches!(
+                        Region::from_point(
+                            center,
+                            self.rect,
+                            context.settings.reader.strip_width,
+                            context.settings.reader.corner_width,
+                        ),
+                        Region::Center
+                    )
+                {
+                    self.toggle_speech_pause(hub, context);
+                    return true;
+                }
+
+                if self.auto_page_turn.is_some()
+                    && context.settings.reader.auto_page_turn.pause_on_tap
+                    && matches!(
+                        Region::from_point(
+                            center,
+                            self.rect,
+                            context.settings.reader.strip_width,
+                            context.settings.reader.corner_width,
+                        ),
+                        Region::Center
+                    )
+                {
+                    self.toggle_auto_page_turn_pause(rq);
+                    return true;
+                }
+
                 let mut nearest_link = None;
                 let mut dmin = u32::MAX;
                 let dmax =
@@ -4359,15 +5794,25 @@ impl View for Reader {
                     return true;
                 }
 
+                let region = Region::from_point(
+                    center,
+                    self.rect,
+                    context.settings.reader.strip_width,
+                    context.settings.reader.corner_width,
+                );
+
+                // The strips and the corners keep turning the pages, even over an image.
+                if self.reflowable
+                    && matches!(region, Region::Center)
+                    && self.open_image_viewer(center, false, rq, context)
+                {
+                    return true;
+                }
+
                 if let ZoomMode::Custom(_) = self.view_port.zoom_mode {
                     let dx = self.rect.width() as i32 - 2 * self.view_port.margin_width;
                     let dy = self.rect.height() as i32 - 2 * self.view_port.margin_width;
-                    match Region::from_point(
-                        center,
-                        self.rect,
-                        context.settings.reader.strip_width,
-                        context.settings.reader.corner_width,
-                    ) {
+                    match region {
                         Region::Corner(diag_dir) => match diag_dir {
                             DiagDir::NorthEast => {
                                 self.directional_scroll(pt!(dx, -dy), hub, rq, context)
@@ -4394,88 +5839,33 @@ impl View for Reader {
                     return true;
                 }
 
-                match Region::from_point(
-                    center,
-                    self.rect,
-                    context.settings.reader.strip_width,
-                    context.settings.reader.corner_width,
-                ) {
-                    Region::Corner(diag_dir) => match diag_dir {
-                        DiagDir::NorthWest => self.go_to_last_page(hub, rq, context),
-                        DiagDir::NorthEast => self.toggle_bookmark(rq),
-                        DiagDir::SouthEast => {
-                            if self.search.is_none() {
-                                match context.settings.reader.south_east_corner {
-                                    SouthEastCornerAction::GoToPage => {
-                                        hub.send(Event::Toggle(ViewId::GoToPage)).ok();
-                                    }
-                                    SouthEastCornerAction::NextPage => {
-                                        self.go_to_neighbor(CycleDir::Next, hub, rq, context);
-                                    }
-                                }
-                            } else {
-                                self.go_to_neighbor(CycleDir::Next, hub, rq, context);
-                            }
+                let zone = TapZone::from(region);
+
+                if self.search.is_some() {
+                    match zone {
+                        TapZone::West => {
+                            self.go_to_results_neighbor(CycleDir::Previous, hub, rq, context);
+                            return true;
                         }
-                        DiagDir::SouthWest => {
-                            if self.search.is_none() {
-                                if self.ephemeral
-                                    && self.info.file.path == PathBuf::from(MEM_SCHEME)
-                                {
-                                    self.quit(context);
-                                    hub.send(Event::Back).ok();
-                                } else {
-                                    hub.send(Event::Show(ViewId::TableOfContents)).ok();
-                                }
-                            } else {
-                                self.go_to_neighbor(CycleDir::Previous, hub, rq, context);
-                            }
+                        TapZone::East => {
+                            self.go_to_results_neighbor(CycleDir::Next, hub, rq, context);
+                            return true;
                         }
-                    },
-                    Region::Strip(dir) => match dir {
-                        Dir::West => {
-                            if self.search.is_none() {
-                                match context.settings.reader.west_strip {
-                                    WestStripAction::PreviousPage => {
-                                        self.go_to_neighbor(CycleDir::Previous, hub, rq, context);
-                                    }
-                                    WestStripAction::NextPage => {
-                                        self.go_to_neighbor(CycleDir::Next, hub, rq, context);
-                                    }
-                                    WestStripAction::None => (),
-                                }
-                            } else {
-                                self.go_to_results_neighbor(CycleDir::Previous, hub, rq, context);
-                            }
+                        TapZone::SouthWest => {
+                            self.go_to_neighbor(CycleDir::Previous, hub, rq, context);
+                            return true;
                         }
-                        Dir::East => {
-                            if self.search.is_none() {
-                                match context.settings.reader.east_strip {
-                                    EastStripAction::PreviousPage => {
-                                        self.go_to_neighbor(CycleDir::Previous, hub, rq, context);
-                                    }
-                                    EastStripAction::NextPage => {
-                                        self.go_to_neighbor(CycleDir::Next, hub, rq, context);
-                                    }
-                                    EastStripAction::None => (),
-                                }
-                            } else {
-                                self.go_to_results_neighbor(CycleDir::Next, hub, rq, context);
-                            }
+                        TapZone::SouthEast => {
+                            self.go_to_neighbor(CycleDir::Next, hub, rq, context);
+                            return true;
                         }
-                        Dir::South => match context.settings.reader.south_strip {
-                            SouthStripAction::ToggleBars => {
-                                self.toggle_bars(None, hub, rq, context);
-                            }
-                            SouthStripAction::NextPage => {
-                                self.go_to_neighbor(CycleDir::Next, hub, rq, context);
-                            }
-                        },
-                        Dir::North => self.toggle_bars(None, hub, rq, context),
-                    },
-                    Region::Center => self.toggle_bars(None, hub, rq, context),
+                        _ => (),
+                    }
                 }
 
+                let action = context.settings.reader.tap_zones[zone];
+                self.perform_tap_action(action, hub, rq, context);
+
                 true
             }
             Event::Gesture(GestureEvent::HoldFingerShort(center, id))
@@ -4539,10 +5929,13 @@ impl View for Reader {
                             start: anchor,
                             end: anchor,
                             anchor,
+                            dragged: None,
                         });
                         self.state = State::Selection(id);
                         rq.add(RenderData::new(self.id, rect, UpdateMode::Fast));
                     }
+                } else {
+                    self.open_image_viewer(center, !self.reflowable, rq, context);
                 }
 
                 true
@@ -4570,7 +5963,11 @@ impl View for Reader {
                 true
             }
             Event::LoadPixmap(location) => {
-                self.load_pixmap(location);
+                if self.reflowable {
+                    self.load_pixmap(location);
+                } else {
+                    self.prerender_pixmap(location);
+                }
                 true
             }
             Event::Submit(ViewId::GoToPageInput, ref text) => {
@@ -4663,11 +6060,32 @@ impl View for Reader {
                     }
                 }
 
+                self.annotated = true;
                 self.update_annotations();
                 self.toggle_keyboard(false, None, hub, rq, context);
                 true
             }
             Event::Submit(ViewId::ReaderSearchInput, ref text) => {
+                // The archives of articles are searched by title.
+                let titles = self.doc.lock().unwrap().search_titles(text);
+                if let Some(entries) = titles {
+                    if entries.is_empty() {
+                        let notif = Notification::new(
+                            None,
+                            "No matching articles.".to_string(),
+                            false,
+                            hub,
+                            rq,
+                            context,
+                        );
+                        self.children.push(Box::new(notif) as Box<dyn View>);
+                    } else {
+                        self.toggle_keyboard(false, None, hub, rq, context);
+                        hub.send(Event::OpenHtml(toc_as_html(&entries, usize::MAX), None))
+                            .ok();
+                    }
+                    return true;
+                }
                 match make_query(text) {
                     Some(query) => {
                         self.search(text, query, hub, rq);
@@ -4909,6 +6327,16 @@ impl View for Reader {
                 self.toggle_margin_cropper(true, hub, rq, context);
                 true
             }
+            Event::Close(ViewId::ImageViewer) => {
+                if let Some(index) = locate::<ImageViewer>(self) {
+                    rq.add(RenderData::expose(
+                        *self.child(index).rect(),
+                        UpdateMode::Gui,
+                    ));
+                    self.children.remove(index);
+                }
+                true
+            }
             Event::Close(ViewId::MarginCropper) => {
                 self.toggle_margin_cropper(false, hub, rq, context);
                 true
@@ -4987,6 +6415,7 @@ impl View for Reader {
                     if let Some(rect) = self.text_rect([sel.start, sel.end]) {
                         rq.add(RenderData::new(self.id, rect, UpdateMode::Gui));
                     }
+                    self.annotated = true;
                     self.update_annotations();
                 }
 
@@ -5068,6 +6497,7 @@ impl View for Reader {
                 if let Some(annot) = self.find_annotation_mut(sel) {
                     annot.note.clear();
                     annot.modified = Local::now().naive_local();
+                    self.annotated = true;
                     self.update_annotations();
                 }
                 if let Some(rect) = self.text_rect(sel) {
@@ -5080,6 +6510,7 @@ impl View for Reader {
                     annotations.retain(|annot| {
                         annot.selection[0] != sel[0] || annot.selection[1] != sel[1]
                     });
+                    self.annotated = true;
                     self.update_annotations();
                 }
                 if let Some(rect) = self.text_rect(sel) {
@@ -5095,6 +6526,39 @@ impl View for Reader {
                 self.set_scroll_mode(scroll_mode, hub, rq, context);
                 true
             }
+            Event::Select(EntryId::SetAutoPageTurn(interval)) => {
+                self.set_auto_page_turn(interval, hub, rq, context);
+                true
+            }
+            Event::AutoPageTurnTick => {
+                self.tick_auto_page_turn(hub, rq, context);
+                true
+            }
+            Event::Select(EntryId::ToggleReadAloud) => {
+                self.toggle_read_aloud(hub, rq, context);
+                true
+            }
+            Event::Select(EntryId::ToggleSpeechPause) => {
+                self.toggle_speech_pause(hub, context);
+                true
+            }
+            Event::Speech(id, event) => {
+                self.handle_speech_event(id, event, hub, rq, context);
+                true
+            }
+            Event::SyncProgress(ref path, ref remote) if *path == self.info.file.path => {
+                self.handle_sync_progress(remote, hub, rq, context);
+                true
+            }
+            Event::Select(EntryId::SyncProgress) => {
+                fetch_sync_progress(
+                    &context.settings.sync,
+                    self.info.file.path.clone(),
+                    context.library.home.join(&self.info.file.path),
+                    hub,
+                );
+                true
+            }
             Event::Select(EntryId::Save) => {
                 let name = format!(
                     "{}-{}.{}",
@@ -5120,6 +6584,14 @@ impl View for Reader {
                 });
                 true
             }
+            Event::Select(EntryId::ToggleTwoPages) => {
+                self.toggle_two_pages(hub, rq, context);
+                true
+            }
+            Event::Select(EntryId::AutoCropMargins) => {
+                self.auto_crop_margins(hub, rq, context);
+                true
+            }
             Event::Select(EntryId::RemoveCroppings) => {
                 if let Some(r) = self.info.reader.as_mut() {
                     r.cropping_margins = None;
@@ -5136,6 +6608,30 @@ impl View for Reader {
                 self.set_font_family(font_family, hub, rq, context);
                 true
             }
+            Event::Select(EntryId::GoBack) => {
+                self.go_back(hub, rq, context);
+                true
+            }
+            Event::Select(EntryId::GoForward) => {
+                self.go_forward(hub, rq, context);
+                true
+            }
+            Event::Select(EntryId::UseAsDefault) => {
+                self.use_as_default(hub, rq, context);
+                true
+            }
+            Event::Select(EntryId::SetTypographyPreset(index)) => {
+                self.set_typography_preset(index, hub, rq, context);
+                true
+            }
+            Event::Select(EntryId::SetStylesheet(ref name)) => {
+                self.set_stylesheet(name.as_deref(), hub, rq, context);
+                true
+            }
+            Event::Select(EntryId::ToggleVerticalText) => {
+                self.toggle_vertical_text(hub, rq, context);
+                true
+            }
             Event::Select(EntryId::SetTextAlign(text_align)) => {
                 self.set_text_align(text_align, hub, rq, context);
                 true
@@ -5170,6 +6666,11 @@ impl View for Reader {
                 self.set_contrast_gray(gray, hub, rq, context);
                 true
             }
+            Event::Select(EntryId::SetBrightness(v)) => {
+                let brightness = v as f32 * BRIGHTNESS_STEP;
+                self.set_brightness(brightness, hub, rq, context);
+                true
+            }
             Event::Select(EntryId::SetPageName) => {
                 self.toggle_name_page(None, hub, rq, context);
                 true
@@ -5181,9 +6682,13 @@ impl View for Reader {
                 true
             }
             Event::Select(EntryId::ToggleInverted) => {
-                self.update_noninverted_regions(!context.fb.inverted());
+                self.update_noninverted_regions(self.night_mode || !context.fb.inverted());
                 false
             }
+            Event::Select(EntryId::ToggleNightMode) => {
+                self.toggle_night_mode(rq, context);
+                true
+            }
             Event::Reseed => {
                 self.reseed(rq, context);
                 true
@@ -5237,7 +6742,7 @@ impl View for Reader {
 
     #[cfg_attr(feature = "otel", tracing::instrument(skip(self, fb, _fonts), fields(rect = ?rect)))]
     fn render(&self, fb: &mut dyn Framebuffer, rect: Rectangle, _fonts: &mut Fonts) {
-        fb.draw_rectangle(&rect, WHITE);
+        fb.draw_rectangle(&rect, theme::current().background);
 
         for chunk in &self.chunks {
             let Resource {
@@ -5254,9 +6759,19 @@ impl View for Reader {
                     chunk_position,
                     self.contrast.exponent,
                     self.contrast.gray,
+                    self.contrast.brightness,
                 );
 
-                if let Some(rects) = self.noninverted_regions.get(&chunk.location) {
+                if self.night_mode {
+                    fb.invert_region(&region_rect);
+                }
+
+                // The images are inverted back, unless both inversions cancel each other.
+                if let Some(rects) = self
+                    .noninverted_regions
+                    .get(&chunk.location)
+                    .filter(|_| self.night_mode != fb.inverted())
+                {
                     for r in rects {
                         let rect = (*r * scale).to_rect() - chunk.frame.min + chunk.position;
                         if let Some(ref image_rect) = rect.intersection(&region_rect) {
@@ -5309,10 +6824,10 @@ impl View for Reader {
 
                 if let Some(annotations) = self.annotations.get(&chunk.location) {
                     for annot in annotations {
-                        let drift = if annot.note.is_empty() {
-                            HIGHLIGHT_DRIFT
+                        let (drift, tint) = if annot.note.is_empty() {
+                            (HIGHLIGHT_DRIFT, HIGHLIGHT_TINT)
                         } else {
-                            ANNOTATION_DRIFT
+                            (ANNOTATION_DRIFT, ANNOTATION_TINT)
                         };
                         let [start, end] = annot.selection;
                         if let Some(text) = self.text.get(&chunk.location) {
@@ -5324,7 +6839,7 @@ impl View for Reader {
                                 let rect = (word.rect * scale).to_rect() - chunk.frame.min
                                     + chunk.position;
                                 if let Some(ref sel_rect) = rect.intersection(&region_rect) {
-                                    fb.shift_region(sel_rect, drift);
+                                    highlight(fb, sel_rect, drift, tint);
                                 }
                                 if let Some(last) = last_rect {
                                     // Are `rect` and `last` on the same line?
@@ -5349,7 +6864,7 @@ impl View for Reader {
                                         };
                                         if let Some(ref sel_rect) = space.intersection(&region_rect)
                                         {
-                                            fb.shift_region(sel_rect, drift);
+                                            highlight(fb, sel_rect, drift, tint);
                                         }
                                     }
                                 }
@@ -5400,6 +6915,42 @@ impl View for Reader {
                         }
                     }
                 }
+
+                if let Some([start, end]) = self
+                    .speech
+                    .as_ref()
+                    .and_then(|s| s.sentences.get(s.current))
+                {
+                    if let Some(text) = self.text.get(&chunk.location) {
+                        let thickness =
+                            scale_thickness(THICKNESS_MEDIUM, CURRENT_DEVICE.dpi) as i32;
+                        let mut last_rect: Option<Rectangle> = None;
+                        for word in text
+                            .iter()
+                            .filter(|w| w.location >= *start && w.location <= *end)
+                        {
+                            let rect =
+                                (word.rect * scale).to_rect() - chunk.frame.min + chunk.position;
+                            // The underline spans the space following the previous word of the line.
+                            let min_x = match last_rect {
+                                Some(last)
+                                    if rect.max.y.min(last.max.y) - rect.min.y.max(last.min.y)
+                                        > rect.height().min(last.height()) as i32 / 2
+                                        && last.max.x < rect.min.x =>
+                                {
+                                    last.max.x
+                                }
+                                _ => rect.min.x,
+                            };
+                            let underline =
+                                rect![min_x, rect.max.y, rect.max.x, rect.max.y + thickness];
+                            if let Some(ref underline_rect) = underline.intersection(&region_rect) {
+                                fb.invert_region(underline_rect);
+                            }
+                            last_rect = Some(rect);
+                        }
+                    }
+                }
             }
         }
 
@@ -5418,11 +6969,28 @@ impl View for Reader {
                 &CornerSpec::Uniform(radius),
                 &BorderSpec {
                     thickness,
-                    color: WHITE,
+                    color: theme::current().background,
                 },
-                &BLACK,
+                &theme::current().foreground,
             );
         }
+
+        if let Some(apt) = self.auto_page_turn.as_ref().filter(|apt| apt.countdown) {
+            let strip = self.auto_page_turn_rect();
+            if let Some(strip_rect) = rect.intersection(&strip) {
+                fb.draw_rectangle(&strip_rect, theme::current().background);
+                let width = apt.progress_width(strip.width() as i32);
+                let progress = rect![strip.min.x, strip.min.y, strip.min.x + width, strip.max.y];
+                if let Some(progress_rect) = rect.intersection(&progress) {
+                    let color = if apt.paused {
+                        theme::current().text_normal[2]
+                    } else {
+                        theme::current().foreground
+                    };
+                    fb.draw_rectangle(&progress_rect, color);
+                }
+            }
+        }
     }
 
     fn render_rect(&self, rect: &Rectangle) -> Rectangle {
@@ -5432,7 +7000,7 @@ impl View for Reader {
     fn resize(&mut self, rect: Rectangle, hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
         if !self.children.is_empty() {
             let dpi = CURRENT_DEVICE.dpi;
-            let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+            let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
             let (small_thickness, big_thickness) = halves(thickness);
             let (small_height, big_height) = (
                 scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32,
@@ -5592,7 +7160,12 @@ impl View for Reader {
                 .and_then(|r| r.font_size)
                 .unwrap_or(context.settings.reader.font_size);
             let mut doc = self.doc.lock().unwrap();
-            doc.layout(rect.width(), rect.height(), font_size, CURRENT_DEVICE.dpi);
+            doc.layout(
+                layout_width(rect.width(), rect.height(), self.view_port.two_pages),
+                rect.height(),
+                font_size,
+                CURRENT_DEVICE.dpi,
+            );
             let current_page = self.current_page.min(doc.pages_count() - 1);
             if let Some(location) = doc.resolve_location(Location::Exact(current_page)) {
                 self.current_page = location;
@@ -5608,6 +7181,16 @@ impl View for Reader {
         self.search.is_none()
     }
 
+    fn contribute_actions(&self, registry: &mut ActionRegistry, context: &Context) {
+        registry.register("Table of Contents", Event::Show(ViewId::TableOfContents));
+        if context.settings.sync.enabled {
+            registry.register(
+                "Sync Reading Progress",
+                Event::Select(EntryId::SyncProgress),
+            );
+        }
+    }
+
     fn is_background(&self) -> bool {
         true
     }
@@ -5632,3 +7215,424 @@ impl View for Reader {
         self.id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_helpers::create_test_context;
+    use std::sync::mpsc::channel;
+
+    // A fixed-layout document of blank pages, optionally covered by full-page images.
+    struct BlankDocument {
+        pages_count: usize,
+        illustrated: bool,
+    }
+
+    impl Document for BlankDocument {
+        fn dims(&self, index: usize) -> Option<(f32, f32)> {
+            (index < self.pages_count).then_some((300.0, 400.0))
+        }
+
+        fn pages_count(&self) -> usize {
+            self.pages_count
+        }
+
+        fn toc(&mut self) -> Option<Vec<TocEntry>> {
+            None
+        }
+
+        fn chapter<'a>(
+            &mut self,
+            _offset: usize,
+            _toc: &'a [TocEntry],
+        ) -> Option<(&'a TocEntry, f32)> {
+            None
+        }
+
+        fn chapter_relative<'a>(
+            &mut self,
+            _offset: usize,
+            _dir: CycleDir,
+            _toc: &'a [TocEntry],
+        ) -> Option<&'a TocEntry> {
+            None
+        }
+
+        fn words(&mut self, loc: Location) -> Option<(Vec<BoundedText>, usize)> {
+            self.resolve_location(loc).map(|index| (Vec::new(), index))
+        }
+
+        fn lines(&mut self, loc: Location) -> Option<(Vec<BoundedText>, usize)> {
+            self.words(loc)
+        }
+
+        fn links(&mut self, loc: Location) -> Option<(Vec<BoundedText>, usize)> {
+            self.words(loc)
+        }
+
+        fn images(&mut self, loc: Location) -> Option<(Vec<Boundary>, usize)> {
+            let index = self.resolve_location(loc)?;
+            let images = if self.illustrated {
+                let (width, height) = self.dims(index)?;
+                vec![bndr![0.0, 0.0, width, height]]
+            } else {
+                Vec::new()
+            };
+            Some((images, index))
+        }
+
+        fn pixmap(&mut self, loc: Location, scale: f32, samples: usize) -> Option<(Pixmap, usize)> {
+            let index = self.resolve_location(loc)?;
+            let (width, height) = self.dims(index)?;
+            let pixmap = Pixmap::new(
+                (width * scale).max(1.0) as u32,
+                (height * scale).max(1.0) as u32,
+                samples,
+            );
+            Some((pixmap, index))
+        }
+
+        fn layout(&mut self, _width: u32, _height: u32, _font_size: f32, _dpi: u16) {}
+        fn set_font_family(&mut self, _family_name: &str, _search_path: &str) {}
+        fn set_margin_width(&mut self, _width: i32) {}
+        fn set_text_align(&mut self, _text_align: TextAlign) {}
+        fn set_line_height(&mut self, _line_height: f32) {}
+        fn set_hyphen_penalty(&mut self, _hyphen_penalty: i32) {}
+        fn set_stretch_tolerance(&mut self, _stretch_tolerance: f32) {}
+        fn set_ignore_document_css(&mut self, _ignore: bool) {}
+
+        fn title(&self) -> Option<String> {
+            None
+        }
+
+        fn author(&self) -> Option<String> {
+            None
+        }
+
+        fn metadata(&self, _key: &str) -> Option<String> {
+            None
+        }
+
+        fn is_reflowable(&self) -> bool {
+            false
+        }
+    }
+
+    fn create_test_reader(rect: Rectangle, pages_count: usize, context: &mut Context) -> Reader {
+        let (hub, _receiver) = channel();
+        let mut reader = Reader::from_html(rect, "<p></p>", None, &hub, context);
+        reader.doc = Arc::new(Mutex::new(Box::new(BlankDocument {
+            pages_count,
+            illustrated: false,
+        })));
+        reader.info.reader = Some(ReaderInfo {
+            pages_count,
+            ..Default::default()
+        });
+        reader.pages_count = pages_count;
+        reader.reflowable = false;
+        reader.ephemeral = false;
+        reader
+    }
+
+    #[test]
+    fn test_use_as_default() {
+        let mut context = create_test_context();
+        let (hub, _receiver) = channel();
+        let mut bus = VecDeque::new();
+        let mut rq = RenderQueue::new();
+        let mut reader = create_test_reader(rect![0, 0, 600, 800], 4, &mut context);
+
+        if let Some(r) = reader.info.reader.as_mut() {
+            r.font_size = Some(9.0);
+            r.margin_width = Some(4);
+            r.cropping_margins = Some(CroppingMargins::Any(Margin::new(0.1, 0.0, 0.1, 0.0)));
+        }
+        reader.contrast = Contrast {
+            exponent: 2.0,
+            gray: 192.0,
+            brightness: BRIGHTNESS_STEP,
+        };
+
+        let evt = Event::Select(EntryId::UseAsDefault);
+        assert!(reader.handle_event(&evt, &hub, &mut bus, &mut rq, &mut context));
+
+        let defaults = &context.settings.reader;
+        assert_eq!(defaults.font_size, 9.0);
+        assert_eq!(defaults.margin_width, 4);
+        assert_eq!(defaults.line_height, DEFAULT_LINE_HEIGHT);
+        assert_eq!(defaults.contrast_exponent, 2.0);
+        assert_eq!(defaults.contrast_gray, 192.0);
+        assert_eq!(defaults.brightness, BRIGHTNESS_STEP);
+        assert!(matches!(
+            defaults.cropping_margins,
+            Some(CroppingMargins::Any(Margin { top, .. })) if top == 0.1
+        ));
+    }
+
+    #[test]
+    fn test_back_and_forward_history() {
+        let mut context = create_test_context();
+        let (hub, _receiver) = channel();
+        let mut bus = VecDeque::new();
+        let mut rq = RenderQueue::new();
+        let mut reader = create_test_reader(rect![0, 0, 600, 800], 10, &mut context);
+        let swipe = |dir| {
+            Event::Gesture(GestureEvent::MultiSwipe {
+                dir,
+                starts: [pt!(0); 2],
+                ends: [pt!(0); 2],
+            })
+        };
+
+        for location in [2, 5, 7] {
+            reader.handle_event(
+                &Event::GoTo(location),
+                &hub,
+                &mut bus,
+                &mut rq,
+                &mut context,
+            );
+        }
+        assert_eq!(reader.current_page, 7);
+        assert_eq!(reader.history, [0, 2, 5]);
+
+        reader.handle_event(&swipe(Dir::West), &hub, &mut bus, &mut rq, &mut context);
+        let evt = Event::Select(EntryId::GoBack);
+        reader.handle_event(&evt, &hub, &mut bus, &mut rq, &mut context);
+        assert_eq!(reader.current_page, 2);
+        assert_eq!(reader.forward_history, [7, 5]);
+
+        reader.handle_event(&swipe(Dir::East), &hub, &mut bus, &mut rq, &mut context);
+        assert_eq!(reader.current_page, 5);
+        assert_eq!(reader.history, [0, 2]);
+        assert_eq!(reader.forward_history, [7]);
+
+        reader.handle_event(&Event::GoTo(9), &hub, &mut bus, &mut rq, &mut context);
+        assert_eq!(reader.current_page, 9);
+        assert_eq!(reader.history, [0, 2, 5]);
+        assert!(reader.forward_history.is_empty());
+
+        let evt = Event::Select(EntryId::GoForward);
+        reader.handle_event(&evt, &hub, &mut bus, &mut rq, &mut context);
+        assert_eq!(reader.current_page, 9);
+    }
+
+    #[test]
+    fn test_tap_on_an_image_turns_the_page() {
+        let mut context = create_test_context();
+        let (hub, _receiver) = channel();
+        let mut bus = VecDeque::new();
+        let mut rq = RenderQueue::new();
+        let rect = rect![0, 0, 600, 800];
+        let mut reader = create_test_reader(rect, 4, &mut context);
+        reader.doc = Arc::new(Mutex::new(Box::new(BlankDocument {
+            pages_count: 4,
+            illustrated: true,
+        })));
+        reader.reflowable = true;
+        reader.update(None, &hub, &mut rq, &context);
+        let children_count = reader.children.len();
+
+        let evt = Event::Gesture(GestureEvent::Tap(pt!(rect.max.x - 1, rect.center().y)));
+        assert!(reader.handle_event(&evt, &hub, &mut bus, &mut rq, &mut context));
+        assert_eq!(reader.current_page, 1);
+        assert_eq!(reader.children.len(), children_count);
+
+        let evt = Event::Gesture(GestureEvent::Tap(rect.center()));
+        reader.handle_event(&evt, &hub, &mut bus, &mut rq, &mut context);
+        assert_eq!(reader.current_page, 1);
+        assert_eq!(reader.children.len(), children_count + 1);
+    }
+
+    #[test]
+    fn test_countdown_refreshes_on_visible_progress() {
+        let mut context = create_test_context();
+        let (hub, _receiver) = channel();
+        let mut rq = RenderQueue::new();
+        let mut reader = create_test_reader(rect![0, 0, 600, 800], 4, &mut context);
+        reader.children.clear();
+        reader.auto_page_turn = Some(AutoPageTurn {
+            interval: 1200,
+            remaining: 1200,
+            paused: false,
+            countdown: true,
+            position: (reader.current_page, reader.view_port.page_offset),
+            running: Arc::new(AtomicBool::new(false)),
+        });
+
+        // Half a pixel of progress.
+        reader.tick_auto_page_turn(&hub, &mut rq, &mut context);
+        assert!(rq.is_empty());
+
+        reader.tick_auto_page_turn(&hub, &mut rq, &mut context);
+        let batches = rq.batches();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].0, (UpdateMode::Gui, true));
+        assert_eq!(
+            batches[0].1,
+            [(Some(reader.id), reader.auto_page_turn_rect())]
+        );
+    }
+
+    #[test]
+    fn test_layout_width() {
+        assert_eq!(layout_width(800, 600, true), 400);
+        assert_eq!(layout_width(800, 600, false), 800);
+        assert_eq!(layout_width(600, 800, true), 600);
+    }
+
+    fn create_spread_reader(pages_count: usize, context: &mut Context) -> Reader {
+        let (hub, _receiver) = channel();
+        let mut rq = RenderQueue::new();
+        context.settings.reader.finished = FinishedAction::Notify;
+        let mut reader = create_test_reader(rect![0, 0, 800, 600], pages_count, context);
+        reader.view_port.two_pages = true;
+        reader.update(None, &hub, &mut rq, context);
+        reader
+    }
+
+    // The locations and the horizontal positions of the pages shown.
+    fn spread(reader: &Reader) -> Vec<(usize, i32)> {
+        reader
+            .chunks
+            .iter()
+            .map(|chunk| (chunk.location, chunk.position.x))
+            .collect()
+    }
+
+    #[test]
+    fn test_spread_turns_two_pages() {
+        let mut context = create_test_context();
+        let (hub, _receiver) = channel();
+        let mut rq = RenderQueue::new();
+        let mut reader = create_spread_reader(6, &mut context);
+
+        assert!(reader.is_spread());
+        assert_eq!(reader.page_rect(), rect![0, 0, 400, 600]);
+        // The first page is on the left, and its facing page touches it in the middle.
+        assert_eq!(spread(&reader), [(0, 0), (1, 400)]);
+
+        reader.go_to_neighbor(CycleDir::Next, &hub, &mut rq, &mut context);
+        assert_eq!(reader.current_page, 2);
+        assert_eq!(spread(&reader), [(2, 0), (3, 400)]);
+
+        reader.go_to_neighbor(CycleDir::Next, &hub, &mut rq, &mut context);
+        assert_eq!(spread(&reader), [(4, 0), (5, 400)]);
+
+        // The last pair is the end of the document.
+        reader.go_to_neighbor(CycleDir::Next, &hub, &mut rq, &mut context);
+        assert_eq!(reader.current_page, 4);
+        assert!(reader.finished);
+
+        reader.go_to_neighbor(CycleDir::Previous, &hub, &mut rq, &mut context);
+        assert_eq!(reader.current_page, 2);
+        reader.go_to_neighbor(CycleDir::Previous, &hub, &mut rq, &mut context);
+        assert_eq!(reader.current_page, 0);
+        reader.go_to_neighbor(CycleDir::Previous, &hub, &mut rq, &mut context);
+        assert_eq!(reader.current_page, 0);
+    }
+
+    #[test]
+    fn test_spread_of_an_odd_number_of_pages() {
+        let mut context = create_test_context();
+        let (hub, _receiver) = channel();
+        let mut rq = RenderQueue::new();
+        let mut reader = create_spread_reader(5, &mut context);
+
+        reader.go_to_neighbor(CycleDir::Next, &hub, &mut rq, &mut context);
+        reader.go_to_neighbor(CycleDir::Next, &hub, &mut rq, &mut context);
+        // The last page is alone, in the middle of the screen.
+        assert_eq!(reader.current_page, 4);
+        assert_eq!(spread(&reader), [(4, 200)]);
+        assert!(!reader.finished);
+
+        reader.go_to_neighbor(CycleDir::Next, &hub, &mut rq, &mut context);
+        assert_eq!(reader.current_page, 4);
+        assert!(reader.finished);
+
+        reader.go_to_neighbor(CycleDir::Previous, &hub, &mut rq, &mut context);
+        assert_eq!(spread(&reader), [(2, 0), (3, 400)]);
+    }
+
+    #[test]
+    fn test_two_pages_while_the_document_is_busy() {
+        let mut context = create_test_context();
+        let (hub, _receiver) = channel();
+        let mut bus = VecDeque::new();
+        let mut rq = RenderQueue::new();
+        let mut reader = create_test_reader(rect![0, 0, 800, 600], 4, &mut context);
+        reader.reflowable = true;
+        let two_pages = reader.view_port.two_pages;
+        let children_count = reader.children.len();
+        let _doc = Arc::clone(&reader.doc);
+
+        let evt = Event::Select(EntryId::ToggleTwoPages);
+        reader.handle_event(&evt, &hub, &mut bus, &mut rq, &mut context);
+
+        assert_eq!(reader.view_port.two_pages, two_pages);
+        assert_eq!(context.settings.reader.two_pages_in_landscape, two_pages);
+        assert_eq!(reader.children.len(), children_count + 1);
+    }
+
+    #[test]
+    fn test_no_spread_in_portrait() {
+        let mut context = create_test_context();
+        let (hub, _receiver) = channel();
+        let mut rq = RenderQueue::new();
+        let mut reader = create_test_reader(rect![0, 0, 600, 800], 4, &mut context);
+        reader.view_port.two_pages = true;
+        reader.update(None, &hub, &mut rq, &context);
+
+        assert!(!reader.is_spread());
+        assert_eq!(reader.page_rect(), reader.rect);
+        assert_eq!(reader.chunks.len(), 1);
+
+        reader.go_to_neighbor(CycleDir::Next, &hub, &mut rq, &mut context);
+        assert_eq!(reader.current_page, 1);
+    }
+
+    #[test]
+    fn test_brightness_steps() {
+        let mut context = create_test_context();
+        let (hub, _receiver) = channel();
+        let mut bus = VecDeque::new();
+        let mut rq = RenderQueue::new();
+        let mut reader = create_test_reader(rect![0, 0, 600, 800], 4, &mut context);
+
+        // Each step is ten percent of the range of the gray levels.
+        assert_eq!(BRIGHTNESS_STEP, 0.1 * 255.0);
+
+        let evt = Event::Select(EntryId::SetBrightness(2));
+        reader.handle_event(&evt, &hub, &mut bus, &mut rq, &mut context);
+        assert_eq!(reader.contrast.brightness, 51.0);
+        assert_eq!(reader.info.reader.as_ref().unwrap().brightness, Some(51.0));
+
+        let evt = Event::Select(EntryId::SetBrightness(-4));
+        reader.handle_event(&evt, &hub, &mut bus, &mut rq, &mut context);
+        assert_eq!(reader.contrast.brightness, -102.0);
+
+        let evt = Event::Select(EntryId::SetBrightness(0));
+        reader.handle_event(&evt, &hub, &mut bus, &mut rq, &mut context);
+        assert_eq!(reader.contrast.brightness, DEFAULT_BRIGHTNESS);
+    }
+
+    #[test]
+    fn test_stylesheet_precedence() {
+        let mut settings = Settings::default();
+        let mut info = Info::default();
+        assert_eq!(stylesheet_name(&info, &settings), None);
+
+        settings.reader.stylesheet = Some("global.css".to_string());
+        assert_eq!(stylesheet_name(&info, &settings), Some("global.css"));
+
+        info.reader = Some(ReaderInfo::default());
+        assert_eq!(stylesheet_name(&info, &settings), Some("global.css"));
+
+        info.reader.as_mut().unwrap().stylesheet = Some("book.css".to_string());
+        assert_eq!(stylesheet_name(&info, &settings), Some("book.css"));
+
+        info.reader.as_mut().unwrap().stylesheet = Some(String::new());
+        assert_eq!(stylesheet_name(&info, &settings), None);
+    }
+}
@@ -0,0 +1,223 @@
+use crate::document::{Document, Location};
+use crate::framebuffer::Pixmap;
+#[cfg(target_os = "linux")]
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+// Upper bound on the memory used by the pre-rendered pages, in bytes.
+const MAX_CACHE_SIZE: usize = 48 * 1024 * 1024;
+// Pre-rendering stops, and the cache is emptied, when less memory is available, in bytes.
+const MIN_FREE_MEMORY: u64 = 32 * 1024 * 1024;
+
+/// Identifies a rendered page: the same page rendered at another zoom level is another entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageKey {
+    document: PathBuf,
+    location: usize,
+    scale: u32,
+}
+
+impl PageKey {
+    pub fn new(document: PathBuf, location: usize, scale: f32) -> PageKey {
+        PageKey {
+            document,
+            location,
+            scale: scale.to_bits(),
+        }
+    }
+}
+
+/// Pages rendered ahead of time, in the background.
+///
+/// The least recently used pages are evicted first when the cache grows beyond its size,
+/// and every page is dropped when the device runs low on memory.
+pub struct PrerenderCache {
+    // The most recently used entries come last.
+    entries: Vec<(PageKey, Pixmap)>,
+    max_size: usize,
+}
+
+impl Default for PrerenderCache {
+    fn default() -> Self {
+        PrerenderCache::new(MAX_CACHE_SIZE)
+    }
+}
+
+impl PrerenderCache {
+    pub fn new(max_size: usize) -> PrerenderCache {
+        PrerenderCache {
+            entries: Vec::new(),
+            max_size,
+        }
+    }
+
+    pub fn contains(&self, key: &PageKey) -> bool {
+        self.entries.iter().any(|(k, _)| k == key)
+    }
+
+    /// Removes and returns the page matching the given key.
+    pub fn take(&mut self, key: &PageKey) -> Option<Pixmap> {
+        let index = self.entries.iter().position(|(k, _)| k == key)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    pub fn insert(&mut self, key: PageKey, pixmap: Pixmap) {
+        self.entries.retain(|(k, _)| *k != key);
+        self.entries.push((key, pixmap));
+
+        while self.size() > self.max_size && !self.entries.is_empty() {
+            self.entries.remove(0);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn size(&self) -> usize {
+        self.entries.iter().map(|(_, p)| p.data.len()).sum()
+    }
+}
+
+/// Returns the memory that can be claimed without swapping, in bytes, from the content of
+/// `/proc/meminfo`.
+///
+/// The free memory alone stays low because of the page cache, hence the kernel's estimate is
+/// preferred, and the older kernels that lack it fall back on the free and cached memory.
+fn available_memory(meminfo: &str) -> Option<u64> {
+    let field = |name: &str| {
+        meminfo.lines().find_map(|line| {
+            let value = line.strip_prefix(name)?.strip_prefix(':')?;
+            let kib = value.trim().trim_end_matches("kB").trim();
+            kib.parse::<u64>().ok().map(|kib| kib * 1024)
+        })
+    };
+
+    field("MemAvailable").or_else(|| {
+        let free = field("MemFree")?;
+        Some(free + field("Buffers").unwrap_or(0) + field("Cached").unwrap_or(0))
+    })
+}
+
+#[cfg(any(target_os = "linux", test))]
+fn is_low_memory(meminfo: &str) -> bool {
+    available_memory(meminfo).is_some_and(|available| available < MIN_FREE_MEMORY)
+}
+
+#[cfg(target_os = "linux")]
+fn low_memory() -> bool {
+    fs::read_to_string("/proc/meminfo").is_ok_and(|meminfo| is_low_memory(&meminfo))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn low_memory() -> bool {
+    false
+}
+
+/// Renders the page at the given location in the background.
+///
+/// `scale_for` returns the scale of the page from its dimensions.
+pub fn prerender<F>(
+    doc: Arc<Mutex<Box<dyn Document>>>,
+    cache: Arc<Mutex<PrerenderCache>>,
+    document: PathBuf,
+    location: usize,
+    samples: usize,
+    scale_for: F,
+) where
+    F: FnOnce((f32, f32)) -> f32 + Send + 'static,
+{
+    thread::spawn(move || {
+        if low_memory() {
+            cache.lock().unwrap().clear();
+            return;
+        }
+
+        let mut doc = doc.lock().unwrap();
+        let dims = doc.dims(location).unwrap_or((3.0, 4.0));
+        let scale = scale_for(dims);
+        let key = PageKey::new(document, location, scale);
+
+        if cache.lock().unwrap().contains(&key) {
+            return;
+        }
+
+        if let Some((pixmap, _)) = doc.pixmap(Location::Exact(location), scale, samples) {
+            cache.lock().unwrap().insert(key, pixmap);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(location: usize) -> PageKey {
+        PageKey::new(PathBuf::from("book.pdf"), location, 1.5)
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let page_size = 10 * 10;
+        let mut cache = PrerenderCache::new(2 * page_size);
+
+        cache.insert(key(1), Pixmap::new(10, 10, 1));
+        cache.insert(key(2), Pixmap::new(10, 10, 1));
+        cache.insert(key(1), Pixmap::new(10, 10, 1));
+        cache.insert(key(3), Pixmap::new(10, 10, 1));
+
+        assert_eq!(cache.entries.len(), 2);
+        assert!(!cache.contains(&key(2)));
+        assert!(cache.take(&key(1)).is_some());
+        assert!(cache.take(&key(1)).is_none());
+        assert!(cache.contains(&key(3)));
+    }
+
+    #[test]
+    fn test_low_memory() {
+        let meminfo = "MemTotal:         515340 kB\n\
+                       MemFree:           12400 kB\n\
+                       MemAvailable:     201852 kB\n\
+                       Buffers:           10292 kB\n\
+                       Cached:           170120 kB\n";
+        assert_eq!(available_memory(meminfo), Some(201852 * 1024));
+        assert!(!is_low_memory(meminfo));
+
+        let meminfo = "MemTotal:         515340 kB\n\
+                       MemFree:           12400 kB\n\
+                       MemAvailable:      20480 kB\n";
+        assert!(is_low_memory(meminfo));
+
+        // Kernels older than 3.14 don't report the available memory.
+        let meminfo = "MemTotal:         253280 kB\n\
+                       MemFree:            8120 kB\n\
+                       Buffers:            4096 kB\n\
+                       Cached:            65536 kB\n";
+        assert_eq!(
+            available_memory(meminfo),
+            Some((8120 + 4096 + 65536) * 1024)
+        );
+        assert!(!is_low_memory(meminfo));
+
+        let meminfo = "MemTotal:         253280 kB\n\
+                       MemFree:            8120 kB\n\
+                       Buffers:            1024 kB\n\
+                       Cached:            12288 kB\n";
+        assert!(is_low_memory(meminfo));
+
+        assert_eq!(available_memory(""), None);
+        assert!(!is_low_memory(""));
+    }
+
+    #[test]
+    fn test_keys_include_zoom() {
+        let mut cache = PrerenderCache::default();
+        cache.insert(key(1), Pixmap::new(1, 1, 1));
+
+        assert!(!cache.contains(&PageKey::new(PathBuf::from("book.pdf"), 1, 2.0)));
+        assert!(!cache.contains(&PageKey::new(PathBuf::from("other.pdf"), 1, 1.5)));
+        assert!(cache.contains(&key(1)));
+    }
+}
@@ -1,9 +1,9 @@
-use crate::color::TEXT_NORMAL;
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::{font_from_style, Fonts, NORMAL_STYLE};
 use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::Rectangle;
+use crate::theme;
 use crate::view::{Bus, Event, Hub, Id, RenderData, RenderQueue, View, ID_FEEDER};
 
 pub struct ResultsLabel {
@@ -31,12 +31,10 @@ impl ResultsLabel {
     }
 
     fn text(&self) -> String {
-        let qualifier = if self.count != 1 { "results" } else { "result" };
-
         if self.count == 0 {
-            format!("No {}", qualifier)
+            tr!("No results")
         } else {
-            format!("{} {}", self.count, qualifier)
+            trn!("{count} result", "{count} results", self.count)
         }
     }
 }
@@ -71,11 +69,11 @@ impl View for ResultsLabel {
         let dx = padding + (max_width - plan.width) / 2;
         let dy = (self.rect.height() as i32 - font.x_heights.0 as i32) / 2;
         let pt = pt!(self.rect.min.x + dx, self.rect.max.y - dy);
-        fb.draw_rectangle(&self.rect, TEXT_NORMAL[0]);
+        fb.draw_rectangle(&self.rect, theme::current().text_normal[0]);
         let color = if self.completed {
-            TEXT_NORMAL[1]
+            theme::current().text_normal[1]
         } else {
-            TEXT_NORMAL[2]
+            theme::current().text_normal[2]
         };
         font.render(fb, color, &plan, pt);
     }
@@ -1,4 +1,3 @@
-use crate::color::{SEPARATOR_NORMAL, WHITE};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::Fonts;
@@ -9,7 +8,8 @@ use crate::input::DeviceEvent;
 use crate::metadata::{ReaderInfo, TextAlign};
 use crate::metadata::{DEFAULT_CONTRAST_EXPONENT, DEFAULT_CONTRAST_GRAY};
 use crate::settings::ReaderSettings;
-use crate::unit::scale_by_dpi;
+use crate::theme;
+use crate::unit::scale_thickness;
 use crate::view::filler::Filler;
 use crate::view::icon::Icon;
 use crate::view::labeled_icon::LabeledIcon;
@@ -36,7 +36,7 @@ impl ToolBar {
         let id = ID_FEEDER.next();
         let mut children = Vec::new();
         let dpi = CURRENT_DEVICE.dpi;
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let side = (rect.height() as i32 + thickness) / 2 - thickness;
 
         if reflowable {
@@ -102,7 +102,7 @@ impl ToolBar {
             // Separator.
             let separator = Filler::new(
                 rect![rect.min.x, rect.min.y + side, rect.max.x, rect.max.y - side],
-                SEPARATOR_NORMAL,
+                theme::current().separator_normal,
             );
             children.push(Box::new(separator) as Box<dyn View>);
 
@@ -215,14 +215,14 @@ impl ToolBar {
                     rect.max.x,
                     rect.min.y + side
                 ],
-                WHITE,
+                theme::current().background,
             );
             children.push(Box::new(filler) as Box<dyn View>);
 
             // Separator.
             let separator = Filler::new(
                 rect![rect.min.x, rect.min.y + side, rect.max.x, rect.max.y - side],
-                SEPARATOR_NORMAL,
+                theme::current().separator_normal,
             );
             children.push(Box::new(separator) as Box<dyn View>);
 
@@ -246,7 +246,7 @@ impl ToolBar {
                     rect.min.x + side + small_padding,
                     rect.max.y
                 ],
-                WHITE,
+                theme::current().background,
             );
             children.push(Box::new(filler) as Box<dyn View>);
 
@@ -271,7 +271,7 @@ impl ToolBar {
                     rect.max.x - 2 * side,
                     rect.max.y
                 ],
-                WHITE,
+                theme::current().background,
             );
             children.push(Box::new(filler) as Box<dyn View>);
         }
@@ -379,7 +379,7 @@ impl View for ToolBar {
 
     fn resize(&mut self, rect: Rectangle, hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
         let dpi = CURRENT_DEVICE.dpi;
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let side = (rect.height() as i32 + thickness) / 2 - thickness;
 
         let mut index = 0;
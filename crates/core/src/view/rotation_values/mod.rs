@@ -1,10 +1,10 @@
-use crate::color::{BLACK, GRAY07, WHITE};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::{font_from_style, Fonts, DISPLAY_STYLE, NORMAL_STYLE};
 use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::{Point, Rectangle};
 use crate::gesture::GestureEvent;
+use crate::theme;
 use crate::view::{Bus, Event, Hub, RenderData, RenderQueue, View};
 use crate::view::{Id, ID_FEEDER};
 use std::mem;
@@ -144,7 +144,7 @@ impl View for RotationValues {
         let height = self.rect.height() as i32;
         let side = width.min(height) / 4;
 
-        fb.draw_rectangle(&self.rect, WHITE);
+        fb.draw_rectangle(&self.rect, theme::current().background);
 
         let step = 1 + (self.taps.len() % CORNERS_COUNT);
         let msg = format!("{} / {}", step, CORNERS_COUNT);
@@ -153,7 +153,12 @@ impl View for RotationValues {
         let dx = (width - plan.width as i32) / 2;
         let mut dy = (height - font.x_heights.1 as i32) / 3;
 
-        font.render(fb, BLACK, &plan, self.rect.min + pt!(dx, dy));
+        font.render(
+            fb,
+            theme::current().foreground,
+            &plan,
+            self.rect.min + pt!(dx, dy),
+        );
 
         dy += 4 * (font.x_heights.1 as i32) / 3;
         let msg = if self.taps.len() < CORNERS_COUNT {
@@ -166,19 +171,25 @@ impl View for RotationValues {
         for line in msg.lines() {
             let plan = font.plan(line, None, None);
             let dx = (width - plan.width as i32) / 2;
-            font.render(fb, BLACK, &plan, self.rect.min + pt!(dx, dy));
+            font.render(
+                fb,
+                theme::current().foreground,
+                &plan,
+                self.rect.min + pt!(dx, dy),
+            );
             dy += 3 * font.x_heights.0 as i32;
         }
 
         if self.taps.len() < CORNERS_COUNT {
-            fb.draw_triangle(&[pt!(0, 0), pt!(side, 0), pt!(0, side)], GRAY07);
+            let color = theme::current().separator_strong;
+            fb.draw_triangle(&[pt!(0, 0), pt!(side, 0), pt!(0, side)], color);
             fb.draw_triangle(
                 &[
                     pt!(width - 1, 0),
                     pt!(width - 1, side),
                     pt!(width - 1 - side, 0),
                 ],
-                GRAY07,
+                color,
             );
             fb.draw_triangle(
                 &[
@@ -186,7 +197,7 @@ impl View for RotationValues {
                     pt!(width - 1 - side, height - 1),
                     pt!(width - 1, height - 1 - side),
                 ],
-                GRAY07,
+                color,
             );
             fb.draw_triangle(
                 &[
@@ -194,10 +205,13 @@ impl View for RotationValues {
                     pt!(0, height - 1 - side),
                     pt!(side, height - 1),
                 ],
-                GRAY07,
+                color,
             );
         } else {
-            fb.draw_triangle(&[pt!(0, 0), pt!(side, 0), pt!(0, side)], BLACK);
+            fb.draw_triangle(
+                &[pt!(0, 0), pt!(side, 0), pt!(0, side)],
+                theme::current().foreground,
+            );
         }
     }
 
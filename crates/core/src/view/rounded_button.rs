@@ -1,7 +1,6 @@
 use super::icon::ICONS_PIXMAPS;
 use super::THICKNESS_MEDIUM;
 use super::{Bus, Event, Hub, Id, RenderData, RenderQueue, View, ID_FEEDER};
-use crate::color::{TEXT_INVERTED_HARD, TEXT_NORMAL};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::Fonts;
@@ -9,7 +8,8 @@ use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::{BorderSpec, CornerSpec, Rectangle};
 use crate::gesture::GestureEvent;
 use crate::input::{DeviceEvent, FingerStatus};
-use crate::unit::scale_by_dpi;
+use crate::theme;
+use crate::unit::scale_thickness;
 
 pub struct RoundedButton {
     id: Id,
@@ -69,13 +69,13 @@ impl View for RoundedButton {
     #[cfg_attr(feature = "otel", tracing::instrument(skip(self, fb, _fonts, _rect), fields(rect = ?_rect)))]
     fn render(&self, fb: &mut dyn Framebuffer, _rect: Rectangle, _fonts: &mut Fonts) {
         let dpi = CURRENT_DEVICE.dpi;
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as u16;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as u16;
         let button_radius = self.rect.height() as i32 / 2;
 
         let scheme = if self.active {
-            TEXT_INVERTED_HARD
+            theme::current().text_inverted_hard
         } else {
-            TEXT_NORMAL
+            theme::current().text_normal
         };
 
         let pixmap = ICONS_PIXMAPS.get(&self.name[..]).unwrap();
@@ -96,6 +96,10 @@ impl View for RoundedButton {
         fb.draw_blended_pixmap(pixmap, pt, scheme[1]);
     }
 
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
     fn rect(&self) -> &Rectangle {
         &self.rect
     }
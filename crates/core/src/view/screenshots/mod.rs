@@ -0,0 +1,464 @@
+//! Browsing the screenshots.
+//!
+//! ```text
+//! Screenshots
+//!   ├── TopBar (Back button, "Screenshots" title, delete action)
+//!   ├── Filler (separator)
+//!   ├── Image (the current screenshot) or Label ("No screenshots")
+//!   ├── Filler (separator)
+//!   └── Bottom bar: previous arrow, name of the screenshot, next arrow
+//! ```
+//!
+//! The screenshots are saved by [`take_screenshot`] in the `Screenshots` directory of the
+//! library, and listed from the most recent. Swiping left or right also goes to the next or
+//! previous screenshot.
+
+use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::font::Fonts;
+use crate::framebuffer::{Framebuffer, Pixmap, UpdateMode};
+use crate::geom::{halves, CycleDir, Dir, Rectangle};
+use crate::gesture::GestureEvent;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
+use crate::view::common::locate_by_id;
+use crate::view::confirm_dialog::ConfirmDialog;
+use crate::view::filler::Filler;
+use crate::view::icon::Icon;
+use crate::view::image::Image;
+use crate::view::label::Label;
+use crate::view::notification::Notification;
+use crate::view::top_bar::{TopBar, TopBarAction, TopBarVariant};
+use crate::view::{Align, Bus, Event, Hub, Id, RenderData, RenderQueue, View, ViewId, ID_FEEDER};
+use crate::view::{SMALL_BAR_HEIGHT, THICKNESS_MEDIUM};
+use anyhow::{Context as ResultExt, Error};
+use chrono::Local;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use tracing::error;
+
+/// The directory of the library where the screenshots are saved.
+pub const SCREENSHOTS_DIRNAME: &str = "Screenshots";
+
+// How long the screen stays inverted after a capture.
+const FLASH_DURATION: Duration = Duration::from_millis(150);
+
+// Index of the view showing the current screenshot.
+const CONTENT_INDEX: usize = 2;
+// Index of the first of the three children of the bottom bar.
+const BOTTOM_BAR_INDEX: usize = 4;
+
+/// Saves the content of the framebuffer as a PNG in the screenshots directory, and briefly
+/// inverts the screen to signal the capture.
+pub fn take_screenshot(context: &mut Context) -> Result<PathBuf, Error> {
+    let dir = context.library.home.join(SCREENSHOTS_DIRNAME);
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("can't create directory {}", dir.display()))?;
+    let name = Local::now().format("screenshot-%Y%m%d_%H%M%S.png");
+    let path = dir.join(name.to_string());
+    context.fb.save(&path.to_string_lossy())?;
+    flash(context.fb.as_mut());
+    Ok(path)
+}
+
+fn flash(fb: &mut dyn Framebuffer) {
+    let rect = fb.rect();
+    fb.invert_region(&rect);
+    if let Ok(token) = fb.update(&rect, UpdateMode::FastMono) {
+        fb.wait(token).ok();
+    }
+    thread::sleep(FLASH_DURATION);
+    fb.invert_region(&rect);
+    fb.update(&rect, UpdateMode::Gui).ok();
+}
+
+/// Returns the PNG images of the given directory, the most recent first.
+fn list_screenshots(dir: &Path) -> Vec<PathBuf> {
+    let mut paths = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| {
+                    path.extension()
+                        .is_some_and(|ext| ext.eq_ignore_ascii_case("png"))
+                })
+                .collect::<Vec<PathBuf>>()
+        })
+        .unwrap_or_default();
+    // The names hold the date of the capture.
+    paths.sort_by(|a, b| b.file_name().cmp(&a.file_name()));
+    paths
+}
+
+pub struct Screenshots {
+    id: Id,
+    rect: Rectangle,
+    children: Vec<Box<dyn View>>,
+    dir: PathBuf,
+    paths: Vec<PathBuf>,
+    current: usize,
+}
+
+impl Screenshots {
+    pub fn new(rect: Rectangle, rq: &mut RenderQueue, context: &mut Context) -> Screenshots {
+        let id = ID_FEEDER.next();
+        let dir = context.library.home.join(SCREENSHOTS_DIRNAME);
+        let paths = list_screenshots(&dir);
+        let mut screenshots = Screenshots {
+            id,
+            rect,
+            children: Vec::new(),
+            dir,
+            paths,
+            current: 0,
+        };
+        screenshots.build_children(context);
+        rq.add(RenderData::new(id, rect, UpdateMode::Full));
+        screenshots
+    }
+
+    fn build_children(&mut self, context: &mut Context) {
+        let dpi = CURRENT_DEVICE.dpi;
+        let small_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
+        let (small_thickness, big_thickness) = halves(thickness);
+        let rect = self.rect;
+
+        self.children.clear();
+
+        let top_bar = TopBar::new(
+            rect![
+                rect.min.x,
+                rect.min.y,
+                rect.max.x,
+                rect.min.y + small_height - small_thickness
+            ],
+            TopBarVariant::Back,
+            "Screenshots".to_string(),
+            context,
+        )
+        .actions(vec![TopBarAction::new(
+            "trash",
+            Event::Show(ViewId::ConfirmDialog),
+        )]);
+        self.children.push(Box::new(top_bar) as Box<dyn View>);
+
+        let separator = Filler::new(
+            rect![
+                rect.min.x,
+                rect.min.y + small_height - small_thickness,
+                rect.max.x,
+                rect.min.y + small_height + big_thickness
+            ],
+            theme::current().foreground,
+        );
+        self.children.push(Box::new(separator) as Box<dyn View>);
+
+        let content = self.content(self.content_rect());
+        self.children.push(content);
+
+        let separator = Filler::new(
+            rect![
+                rect.min.x,
+                rect.max.y - small_height - small_thickness,
+                rect.max.x,
+                rect.max.y - small_height + big_thickness
+            ],
+            theme::current().foreground,
+        );
+        self.children.push(Box::new(separator) as Box<dyn View>);
+
+        let bottom_bar = self.bottom_bar();
+        self.children.extend(bottom_bar);
+    }
+
+    fn content_rect(&self) -> Rectangle {
+        let dpi = CURRENT_DEVICE.dpi;
+        let small_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
+        let (small_thickness, big_thickness) = halves(thickness);
+        rect![
+            self.rect.min.x,
+            self.rect.min.y + small_height + big_thickness,
+            self.rect.max.x,
+            self.rect.max.y - small_height - small_thickness
+        ]
+    }
+
+    fn content(&self, rect: Rectangle) -> Box<dyn View> {
+        let Some(path) = self.paths.get(self.current) else {
+            return Box::new(Label::new(
+                rect,
+                "No screenshots".to_string(),
+                Align::Center,
+            ));
+        };
+
+        let pixmap = Pixmap::from_png(path)
+            .map(|pixmap| pixmap.scale_to_fit(rect.width(), rect.height()))
+            .map_err(|e| error!("Can't open {}: {:#}.", path.display(), e))
+            .ok();
+
+        match pixmap {
+            Some(pixmap) => Box::new(Image::new(rect, pixmap)) as Box<dyn View>,
+            None => Box::new(Label::new(
+                rect,
+                "The screenshot can't be opened".to_string(),
+                Align::Center,
+            )),
+        }
+    }
+
+    fn bottom_bar(&self) -> Vec<Box<dyn View>> {
+        let dpi = CURRENT_DEVICE.dpi;
+        let small_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
+        let (_, big_thickness) = halves(thickness);
+
+        let bar_rect = rect![
+            self.rect.min.x,
+            self.rect.max.y - small_height + big_thickness,
+            self.rect.max.x,
+            self.rect.max.y
+        ];
+        let side = bar_rect.height() as i32;
+        let count = self.paths.len();
+        let mut children = Vec::new();
+
+        let prev_rect = rect![bar_rect.min, bar_rect.min + side];
+        if self.current > 0 {
+            let prev_icon = Icon::new("arrow-left", prev_rect, Event::Page(CycleDir::Previous));
+            children.push(Box::new(prev_icon) as Box<dyn View>);
+        } else {
+            let prev_filler = Filler::new(prev_rect, theme::current().background);
+            children.push(Box::new(prev_filler) as Box<dyn View>);
+        }
+
+        let text = self
+            .paths
+            .get(self.current)
+            .and_then(|path| path.file_name())
+            .map(|name| {
+                format!(
+                    "{} ({}/{})",
+                    name.to_string_lossy(),
+                    self.current + 1,
+                    count
+                )
+            })
+            .unwrap_or_default();
+        let label = Label::new(
+            rect![
+                bar_rect.min.x + side,
+                bar_rect.min.y,
+                bar_rect.max.x - side,
+                bar_rect.max.y
+            ],
+            text,
+            Align::Center,
+        );
+        children.push(Box::new(label) as Box<dyn View>);
+
+        let next_rect = rect![bar_rect.max - side, bar_rect.max];
+        if self.current + 1 < count {
+            let next_icon = Icon::new("arrow-right", next_rect, Event::Page(CycleDir::Next));
+            children.push(Box::new(next_icon) as Box<dyn View>);
+        } else {
+            let next_filler = Filler::new(next_rect, theme::current().background);
+            children.push(Box::new(next_filler) as Box<dyn View>);
+        }
+
+        children
+    }
+
+    // Shows the current screenshot and updates the bottom bar, keeping the dialogs.
+    fn update(&mut self, rq: &mut RenderQueue) {
+        self.children[CONTENT_INDEX] = self.content(self.content_rect());
+        let bottom_bar = self.bottom_bar();
+        self.children.splice(
+            BOTTOM_BAR_INDEX..BOTTOM_BAR_INDEX + bottom_bar.len(),
+            bottom_bar,
+        );
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+    }
+
+    fn go_to(&mut self, dir: CycleDir, rq: &mut RenderQueue) {
+        let current = match dir {
+            CycleDir::Next if self.current + 1 < self.paths.len() => self.current + 1,
+            CycleDir::Previous if self.current > 0 => self.current - 1,
+            _ => return,
+        };
+        self.current = current;
+        self.update(rq);
+    }
+
+    fn confirm_delete(&mut self, rq: &mut RenderQueue, context: &mut Context) {
+        let Some(path) = self.paths.get(self.current) else {
+            return;
+        };
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let dialog = ConfirmDialog::new(
+            ViewId::ConfirmDialog,
+            "Delete this screenshot?",
+            &name,
+            "Delete",
+            Event::DeleteFile(path.clone()),
+            context,
+        );
+        rq.add(RenderData::new(
+            dialog.id(),
+            *dialog.rect(),
+            UpdateMode::Gui,
+        ));
+        self.children.push(Box::new(dialog) as Box<dyn View>);
+    }
+
+    fn delete(&mut self, path: &Path, hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
+        if let Err(e) = fs::remove_file(path) {
+            let msg = format!("Can't delete {}: {}.", path.display(), e);
+            let notif = Notification::new(None, msg, false, hub, rq, context);
+            self.children.push(Box::new(notif) as Box<dyn View>);
+            return;
+        }
+        self.paths = list_screenshots(&self.dir);
+        self.current = self.current.min(self.paths.len().saturating_sub(1));
+        self.update(rq);
+    }
+}
+
+impl View for Screenshots {
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, hub, _bus, rq, context), fields(event = ?evt), ret(level=tracing::Level::TRACE)))]
+    fn handle_event(
+        &mut self,
+        evt: &Event,
+        hub: &Hub,
+        _bus: &mut Bus,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        match *evt {
+            Event::Page(dir) => {
+                self.go_to(dir, rq);
+                true
+            }
+            Event::Gesture(GestureEvent::Swipe { dir, start, .. })
+                if self.children[CONTENT_INDEX].rect().includes(start) =>
+            {
+                match dir {
+                    Dir::West => self.go_to(CycleDir::Next, rq),
+                    Dir::East => self.go_to(CycleDir::Previous, rq),
+                    _ => (),
+                }
+                true
+            }
+            Event::Show(ViewId::ConfirmDialog) => {
+                self.confirm_delete(rq, context);
+                true
+            }
+            Event::DeleteFile(ref path) => {
+                self.delete(path, hub, rq, context);
+                true
+            }
+            Event::Close(ViewId::ConfirmDialog) => {
+                if let Some(index) = locate_by_id(self, ViewId::ConfirmDialog) {
+                    rq.add(RenderData::expose(
+                        *self.child(index).rect(),
+                        UpdateMode::Gui,
+                    ));
+                    self.children.remove(index);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _fb, _fonts), fields(rect = ?_rect)))]
+    fn render(&self, _fb: &mut dyn Framebuffer, _rect: Rectangle, _fonts: &mut Fonts) {}
+
+    fn resize(&mut self, rect: Rectangle, _hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
+        self.rect = rect;
+        self.build_children(context);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Full));
+    }
+
+    fn rect(&self) -> &Rectangle {
+        &self.rect
+    }
+
+    fn rect_mut(&mut self) -> &mut Rectangle {
+        &mut self.rect
+    }
+
+    fn children(&self) -> &Vec<Box<dyn View>> {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<Box<dyn View>> {
+        &mut self.children
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn is_background(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_helpers::create_test_context;
+    use std::collections::VecDeque;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_screenshots_are_listed_and_deleted() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut context = create_test_context();
+        context.library.home = temp_dir.path().to_path_buf();
+
+        let path = take_screenshot(&mut context).unwrap();
+        assert_eq!(
+            path.parent(),
+            Some(temp_dir.path().join("Screenshots").as_path())
+        );
+        let older = path.with_file_name("screenshot-20000101_000000.png");
+        fs::copy(&path, &older).unwrap();
+        fs::write(path.with_file_name("notes.txt"), "").unwrap();
+
+        let mut rq = RenderQueue::new();
+        let mut screenshots = Screenshots::new(context.fb.rect(), &mut rq, &mut context);
+        assert_eq!(screenshots.paths, vec![path.clone(), older.clone()]);
+
+        let (hub, _receiver) = channel();
+        let mut bus = VecDeque::new();
+        screenshots.handle_event(
+            &Event::Page(CycleDir::Next),
+            &hub,
+            &mut bus,
+            &mut rq,
+            &mut context,
+        );
+        assert_eq!(screenshots.current, 1);
+
+        screenshots.handle_event(
+            &Event::DeleteFile(older.clone()),
+            &hub,
+            &mut bus,
+            &mut rq,
+            &mut context,
+        );
+        assert!(!older.exists());
+        assert_eq!(screenshots.paths, vec![path]);
+        assert_eq!(screenshots.current, 0);
+        assert_eq!(screenshots.children.len(), BOTTOM_BAR_INDEX + 3);
+    }
+}
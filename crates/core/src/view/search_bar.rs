@@ -2,7 +2,6 @@ use super::filler::Filler;
 use super::icon::Icon;
 use super::input_field::InputField;
 use super::{Bus, Event, Hub, Id, RenderQueue, View, ViewId, ID_FEEDER, THICKNESS_MEDIUM};
-use crate::color::{SEPARATOR_NORMAL, TEXT_BUMP_SMALL};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::Fonts;
@@ -10,7 +9,8 @@ use crate::framebuffer::Framebuffer;
 use crate::geom::Rectangle;
 use crate::gesture::GestureEvent;
 use crate::input::DeviceEvent;
-use crate::unit::scale_by_dpi;
+use crate::theme;
+use crate::unit::scale_thickness;
 
 pub struct SearchBar {
     id: Id,
@@ -29,7 +29,7 @@ impl SearchBar {
         let id = ID_FEEDER.next();
         let mut children = Vec::new();
         let dpi = CURRENT_DEVICE.dpi;
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let side = rect.height() as i32;
 
         let search_rect = rect![rect.min, rect.min + side];
@@ -38,7 +38,7 @@ impl SearchBar {
             search_rect,
             Event::ToggleNear(ViewId::SearchMenu, search_rect),
         )
-        .background(TEXT_BUMP_SMALL[0]);
+        .background(theme::current().text_bump_small[0]);
 
         children.push(Box::new(search_icon) as Box<dyn View>);
 
@@ -47,7 +47,7 @@ impl SearchBar {
                 pt!(rect.min.x + side, rect.min.y),
                 pt!(rect.min.x + side + thickness, rect.max.y)
             ],
-            SEPARATOR_NORMAL,
+            theme::current().separator_normal,
         );
 
         children.push(Box::new(separator) as Box<dyn View>);
@@ -70,7 +70,7 @@ impl SearchBar {
                 pt!(rect.max.x - side - thickness, rect.min.y),
                 pt!(rect.max.x - side, rect.max.y)
             ],
-            SEPARATOR_NORMAL,
+            theme::current().separator_normal,
         );
 
         children.push(Box::new(separator) as Box<dyn View>);
@@ -83,7 +83,7 @@ impl SearchBar {
             ],
             Event::Close(ViewId::SearchBar),
         )
-        .background(TEXT_BUMP_SMALL[0]);
+        .background(theme::current().text_bump_small[0]);
 
         children.push(Box::new(close_icon) as Box<dyn View>);
 
@@ -126,7 +126,7 @@ impl View for SearchBar {
 
     fn resize(&mut self, rect: Rectangle, hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
         let dpi = CURRENT_DEVICE.dpi;
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let side = rect.height() as i32;
         self.children[0].resize(rect![rect.min, rect.min + side], hub, rq, context);
         self.children[1].resize(
@@ -0,0 +1,381 @@
+use super::bottom_bar::{BottomBarVariant, SettingsEditorBottomBar};
+use crate::bluetooth::{self, BluetoothDevice};
+use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::font::Fonts;
+use crate::framebuffer::{Framebuffer, UpdateMode};
+use crate::geom::{halves, Rectangle};
+use crate::gesture::GestureEvent;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
+use crate::view::action_label::ActionLabel;
+use crate::view::common::locate_by_id;
+use crate::view::filler::Filler;
+use crate::view::label::Label;
+use crate::view::menu::{Menu, MenuKind};
+use crate::view::{Align, Bus, EntryId, EntryKind, Event, Hub, Id, NotificationEvent, RenderData};
+use crate::view::{RenderQueue, View, ViewId, ID_FEEDER, SMALL_BAR_HEIGHT, THICKNESS_MEDIUM};
+use std::thread;
+
+/// A list editor pairing the Bluetooth page-turn remotes.
+///
+/// Each row shows a known device next to whether it's paired. Tapping the status opens a
+/// menu to pair or forget the device. The search button discovers the devices nearby.
+/// The *bluetoothctl* commands run in the background, and the list is rebuilt from the
+/// [`Event::BluetoothDevices`] they send back.
+///
+/// # Fields
+///
+/// * `id` - Unique identifier for this view
+/// * `rect` - The rectangular area occupied by this editor
+/// * `children` - Background, a name and a status per device, the bottom separator and bar,
+///   plus the optional device menu
+/// * `devices` - The devices listed, the paired ones first
+pub struct BluetoothEditor {
+    id: Id,
+    rect: Rectangle,
+    children: Vec<Box<dyn View>>,
+    devices: Vec<BluetoothDevice>,
+}
+
+impl BluetoothEditor {
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(hub, rq, context)))]
+    pub fn new(
+        rect: Rectangle,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> BluetoothEditor {
+        let id = ID_FEEDER.next();
+        let devices = Vec::new();
+        let children = Self::build_children(rect, &devices);
+
+        if context.settings.bluetooth {
+            Self::run(hub, None, bluetooth::list_devices);
+        }
+
+        rq.add(RenderData::new(id, rect, UpdateMode::Gui));
+
+        BluetoothEditor {
+            id,
+            rect,
+            children,
+            devices,
+        }
+    }
+
+    fn build_children(rect: Rectangle, devices: &[BluetoothDevice]) -> Vec<Box<dyn View>> {
+        let mut children = Vec::new();
+
+        let dpi = CURRENT_DEVICE.dpi;
+        let bar_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
+        let separator_thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
+        let (separator_top_half, separator_bottom_half) = halves(separator_thickness);
+
+        children.push(Box::new(Filler::new(rect, theme::current().background)) as Box<dyn View>);
+
+        let list_height = rect.height() as i32 - bar_height - separator_top_half;
+        let row_height = (list_height / devices.len().max(1) as i32).min(bar_height);
+        let padding = row_height / 3;
+        let middle_x = rect.min.x + rect.width() as i32 / 2;
+
+        if devices.is_empty() {
+            let message = Label::new(
+                rect![rect.min.x, rect.min.y, rect.max.x, rect.min.y + row_height],
+                "No devices. Tap the search button to scan.".to_string(),
+                Align::Center,
+            );
+            children.push(Box::new(message) as Box<dyn View>);
+        }
+
+        for (index, device) in devices.iter().enumerate() {
+            let y_min = rect.min.y + index as i32 * row_height;
+            let y_max = y_min + row_height;
+
+            let name = Label::new(
+                rect![rect.min.x, y_min, middle_x, y_max],
+                device.name.clone(),
+                Align::Left(padding),
+            );
+            children.push(Box::new(name) as Box<dyn View>);
+
+            let value_rect = rect![middle_x, y_min, rect.max.x, y_max];
+            let status = if device.paired { "Paired" } else { "Available" };
+            let value = ActionLabel::new(value_rect, status.to_string(), Align::Right(padding))
+                .event(Some(Self::tap_event(value_rect, device)));
+            children.push(Box::new(value) as Box<dyn View>);
+        }
+
+        children.push(Box::new(Filler::new(
+            rect![
+                rect.min.x,
+                rect.max.y - bar_height - separator_top_half,
+                rect.max.x,
+                rect.max.y - bar_height + separator_bottom_half
+            ],
+            theme::current().foreground,
+        )) as Box<dyn View>);
+
+        children.push(Box::new(SettingsEditorBottomBar::new(
+            rect![
+                rect.min.x,
+                rect.max.y - bar_height + separator_bottom_half,
+                rect.max.x,
+                rect.max.y
+            ],
+            BottomBarVariant::TwoButtons {
+                left_event: Event::Select(EntryId::ScanBluetoothDevices),
+                left_icon: "search",
+                right_event: Event::Close(ViewId::BluetoothEditor),
+                right_icon: "close",
+            },
+        )) as Box<dyn View>);
+
+        children
+    }
+
+    fn tap_event(rect: Rectangle, device: &BluetoothDevice) -> Event {
+        let entry = if device.paired {
+            EntryKind::Command(
+                "Forget".to_string(),
+                EntryId::ForgetBluetoothDevice(device.address.clone()),
+            )
+        } else {
+            EntryKind::Command(
+                "Pair".to_string(),
+                EntryId::PairBluetoothDevice(device.address.clone()),
+            )
+        };
+        Event::SubMenu(rect, vec![entry])
+    }
+
+    /// Runs a Bluetooth command in the background, then sends the devices it returns.
+    ///
+    /// The message, if any, is shown when the command starts.
+    fn run<F>(hub: &Hub, message: Option<String>, command: F)
+    where
+        F: FnOnce() -> Result<Vec<BluetoothDevice>, anyhow::Error> + Send + 'static,
+    {
+        if let Some(message) = message {
+            hub.send(Event::Notification(NotificationEvent::Show(message)))
+                .ok();
+        }
+        let hub2 = hub.clone();
+        thread::spawn(move || match command() {
+            Ok(devices) => {
+                hub2.send(Event::BluetoothDevices(devices)).ok();
+            }
+            Err(e) => {
+                hub2.send(Event::Notification(NotificationEvent::Show(format!(
+                    "Bluetooth failed: {:#}.",
+                    e
+                ))))
+                .ok();
+            }
+        });
+    }
+
+    #[inline]
+    fn handle_bluetooth_event(&mut self, entry: &EntryId, hub: &Hub, context: &Context) -> bool {
+        if !context.settings.bluetooth {
+            hub.send(Event::Notification(NotificationEvent::Show(
+                "Bluetooth is disabled.".to_string(),
+            )))
+            .ok();
+            return true;
+        }
+
+        match entry {
+            EntryId::ScanBluetoothDevices => Self::run(
+                hub,
+                Some("Scanning for devices.".to_string()),
+                bluetooth::scan,
+            ),
+            EntryId::PairBluetoothDevice(address) => {
+                let address = address.clone();
+                Self::run(hub, Some(format!("Pairing {}.", address)), move || {
+                    bluetooth::pair(&address)?;
+                    bluetooth::list_devices()
+                })
+            }
+            EntryId::ForgetBluetoothDevice(address) => {
+                let address = address.clone();
+                Self::run(hub, None, move || {
+                    bluetooth::forget(&address)?;
+                    bluetooth::list_devices()
+                })
+            }
+            _ => return false,
+        }
+
+        true
+    }
+
+    #[inline]
+    fn handle_devices_event(&mut self, devices: &[BluetoothDevice], rq: &mut RenderQueue) -> bool {
+        self.devices = devices.to_vec();
+        self.children = Self::build_children(self.rect, &self.devices);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        true
+    }
+
+    #[inline]
+    fn handle_submenu_event(
+        &mut self,
+        rect: Rectangle,
+        entries: &[EntryKind],
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        let menu = Menu::new(
+            rect,
+            ViewId::SettingsValueMenu,
+            MenuKind::Contextual,
+            entries.to_vec(),
+            context,
+        );
+        rq.add(RenderData::new(menu.id(), *menu.rect(), UpdateMode::Gui));
+        self.children.push(Box::new(menu));
+        true
+    }
+
+    #[inline]
+    fn handle_close_event(&mut self, view_id: ViewId, rq: &mut RenderQueue) -> bool {
+        match view_id {
+            ViewId::SettingsValueMenu => {
+                if let Some(index) = locate_by_id(self, ViewId::SettingsValueMenu) {
+                    self.children.remove(index);
+                    rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl View for BluetoothEditor {
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, hub, _bus, rq, context), fields(event = ?evt), ret(level=tracing::Level::TRACE)))]
+    fn handle_event(
+        &mut self,
+        evt: &Event,
+        hub: &Hub,
+        _bus: &mut Bus,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        match *evt {
+            Event::Select(
+                ref entry @ (EntryId::ScanBluetoothDevices
+                | EntryId::PairBluetoothDevice(_)
+                | EntryId::ForgetBluetoothDevice(_)),
+            ) => self.handle_bluetooth_event(entry, hub, context),
+            Event::BluetoothDevices(ref devices) => self.handle_devices_event(devices, rq),
+            Event::SubMenu(rect, ref entries) => {
+                self.handle_submenu_event(rect, entries, rq, context)
+            }
+            Event::Close(view_id) => self.handle_close_event(view_id, rq),
+            // The editor covers the category editor, nothing beneath it should react.
+            Event::Gesture(GestureEvent::Tap(center))
+            | Event::Gesture(GestureEvent::HoldFingerShort(center, _)) => {
+                self.rect.includes(center)
+            }
+            _ => false,
+        }
+    }
+
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _fb, _fonts), fields(rect = ?_rect)))]
+    fn render(&self, _fb: &mut dyn Framebuffer, _rect: Rectangle, _fonts: &mut Fonts) {}
+
+    fn rect(&self) -> &Rectangle {
+        &self.rect
+    }
+
+    fn rect_mut(&mut self) -> &mut Rectangle {
+        &mut self.rect
+    }
+
+    fn children(&self) -> &Vec<Box<dyn View>> {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<Box<dyn View>> {
+        &mut self.children
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn view_id(&self) -> Option<ViewId> {
+        Some(ViewId::BluetoothEditor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_helpers::create_test_context;
+    use std::collections::VecDeque;
+    use std::sync::mpsc::channel;
+
+    fn device(name: &str, paired: bool) -> BluetoothDevice {
+        BluetoothDevice {
+            address: format!("00:11:22:33:44:{:02X}", name.len()),
+            name: name.to_string(),
+            paired,
+        }
+    }
+
+    #[test]
+    fn test_devices_event_lists_the_devices() {
+        let mut context = create_test_context();
+        let mut rq = RenderQueue::new();
+        let (hub, _receiver) = channel();
+        let mut bus = VecDeque::new();
+        let mut editor = BluetoothEditor::new(rect![0, 0, 600, 800], &hub, &mut rq, &mut context);
+
+        let devices = vec![device("Remote", true), device("Page Turner", false)];
+        let handled = editor.handle_event(
+            &Event::BluetoothDevices(devices.clone()),
+            &hub,
+            &mut bus,
+            &mut rq,
+            &mut context,
+        );
+
+        assert!(handled);
+        assert_eq!(editor.devices, devices);
+        let statuses: Vec<String> = editor
+            .children
+            .iter()
+            .filter_map(|child| child.downcast_ref::<ActionLabel>())
+            .map(|label| label.value())
+            .collect();
+        assert_eq!(statuses, vec!["Paired", "Available"]);
+    }
+
+    #[test]
+    fn test_scan_while_disabled_notifies() {
+        let mut context = create_test_context();
+        let mut rq = RenderQueue::new();
+        let (hub, receiver) = channel();
+        let mut bus = VecDeque::new();
+        let mut editor = BluetoothEditor::new(rect![0, 0, 600, 800], &hub, &mut rq, &mut context);
+
+        let handled = editor.handle_event(
+            &Event::Select(EntryId::ScanBluetoothDevices),
+            &hub,
+            &mut bus,
+            &mut rq,
+            &mut context,
+        );
+
+        assert!(handled);
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(Event::Notification(NotificationEvent::Show(ref message)))
+                if message == "Bluetooth is disabled."
+        ));
+    }
+}
@@ -1,7 +1,7 @@
-use crate::color::WHITE;
 use crate::font::Fonts;
 use crate::framebuffer::Framebuffer;
 use crate::geom::Rectangle;
+use crate::theme;
 use crate::view::filler::Filler;
 use crate::view::icon::Icon;
 use crate::view::{Event, Id, View, ID_FEEDER};
@@ -71,7 +71,7 @@ impl SettingsEditorBottomBar {
         let id = ID_FEEDER.next();
         let mut children = Vec::new();
 
-        let background = Filler::new(rect, WHITE);
+        let background = Filler::new(rect, theme::current().background);
         children.push(Box::new(background) as Box<dyn View>);
 
         match variant {
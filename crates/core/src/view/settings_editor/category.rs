@@ -1,5 +1,7 @@
 use super::setting_row::Kind as RowKind;
 use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::geom::CycleDir;
 
 /// Categories of settings available in the settings editor.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -10,6 +12,14 @@ pub enum Category {
     Libraries,
     /// Intermission screen display settings
     Intermissions,
+    /// Screen refresh settings
+    Display,
+    /// Reader behavior settings
+    Reader,
+    /// Reading progress synchronization settings
+    Sync,
+    /// Wireless accessories, like the Bluetooth remotes
+    Devices,
 }
 
 impl Category {
@@ -19,6 +29,10 @@ impl Category {
             Category::General => "General".to_string(),
             Category::Libraries => "Libraries".to_string(),
             Category::Intermissions => "Intermission Screens".to_string(),
+            Category::Display => "Display".to_string(),
+            Category::Reader => "Reader".to_string(),
+            Category::Sync => "Sync".to_string(),
+            Category::Devices => "Devices".to_string(),
         }
     }
 
@@ -29,9 +43,17 @@ impl Category {
                 RowKind::AutoShare,
                 RowKind::AutoSuspend,
                 RowKind::AutoPowerOff,
+                RowKind::AutoWifiOff,
+                RowKind::Bedtime,
                 RowKind::ButtonScheme,
+                RowKind::HighContrast,
                 RowKind::KeyboardLayout,
                 RowKind::SleepCover,
+                RowKind::CoverClose,
+                RowKind::CoverOpen,
+                RowKind::UiScale,
+                RowKind::GestureMap,
+                RowKind::Backups,
             ],
             Category::Libraries => (0..context.settings.libraries.len())
                 .map(RowKind::Library)
@@ -41,6 +63,36 @@ impl Category {
                 RowKind::IntermissionPowerOff,
                 RowKind::IntermissionShare,
             ],
+            Category::Display => {
+                let mut rows = vec![
+                    RowKind::FullRefreshInterval,
+                    RowKind::FullRefreshOnClose,
+                    RowKind::DimOverlays,
+                    RowKind::OverlayShadows,
+                    RowKind::CoverDithering,
+                    RowKind::ImageDithering,
+                    RowKind::StartupRotation,
+                ];
+                if CURRENT_DEVICE.has_gyroscope() {
+                    rows.push(RowKind::RotationLock);
+                }
+                rows
+            }
+            Category::Reader => vec![RowKind::TapZones],
+            Category::Sync => vec![
+                RowKind::SyncEnabled,
+                RowKind::SyncServer,
+                RowKind::SyncUsername,
+                RowKind::SyncPassword,
+                RowKind::SyncAccount,
+                RowKind::SyncQueue,
+                RowKind::WebdavEnabled,
+                RowKind::WebdavServer,
+                RowKind::WebdavUsername,
+                RowKind::WebdavPassword,
+                RowKind::WebdavSync,
+            ],
+            Category::Devices => vec![RowKind::Bluetooth, RowKind::BluetoothDevices],
         }
     }
 
@@ -50,9 +102,23 @@ impl Category {
             Category::General,
             Category::Libraries,
             Category::Intermissions,
+            Category::Display,
+            Category::Reader,
+            Category::Sync,
+            Category::Devices,
         ]
     }
 
+    /// Returns the category before or after this one, if any.
+    pub fn adjacent(&self, dir: CycleDir) -> Option<Category> {
+        let categories = Self::all();
+        let index = categories.iter().position(|c| c == self)?;
+        match dir {
+            CycleDir::Next => categories.get(index + 1).copied(),
+            CycleDir::Previous => index.checked_sub(1).map(|i| categories[i]),
+        }
+    }
+
     /// Returns the number of categories.
     pub fn count() -> usize {
         Self::all().len()
@@ -1,27 +1,38 @@
-use crate::color::{BLACK, WHITE};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::framebuffer::{Framebuffer, UpdateMode};
-use crate::geom::{halves, Rectangle};
+use crate::geom::{halves, CycleDir, Dir, Rectangle};
 use crate::gesture::GestureEvent;
-use crate::settings::{ButtonScheme, LibraryMode, LibrarySettings, Settings};
-use crate::unit::scale_by_dpi;
+use crate::kosync::{self, KosyncClient};
+use crate::settings::{
+    ButtonScheme, CoverCloseAction, CoverOpenAction, DitheringKind, LibraryMode, LibrarySettings,
+    RotationLock, Settings,
+};
+use crate::sync_queue;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
 use crate::view::common::locate_by_id;
+use crate::view::confirm_dialog::ConfirmDialog;
 use crate::view::filler::Filler;
 use crate::view::menu::{Menu, MenuKind};
 use crate::view::toggleable_keyboard::ToggleableKeyboard;
 use crate::view::{
-    Bus, EntryId, EntryKind, Event, Hub, Id, RenderData, RenderQueue, ToggleEvent, View, ViewId,
-    ID_FEEDER, SMALL_BAR_HEIGHT, THICKNESS_MEDIUM,
+    Bus, EntryId, EntryKind, Event, Hub, Id, NotificationEvent, RenderData, RenderQueue,
+    ToggleEvent, View, ViewId, ID_FEEDER, SMALL_BAR_HEIGHT, THICKNESS_MEDIUM,
 };
 
+use super::bluetooth_editor::BluetoothEditor;
 use super::bottom_bar::{BottomBarVariant, SettingsEditorBottomBar};
 use super::category::Category;
+use super::gesture_map_editor::GestureMapEditor;
 use super::library_editor::LibraryEditor;
 use super::setting_row::{Kind as RowKind, SettingRow};
+use super::tap_zones_editor::TapZonesEditor;
 use crate::view::file_chooser::{FileChooser, SelectionMode};
 use crate::view::settings_editor::ToggleSettings;
+use secrecy::SecretString;
 use std::path::PathBuf;
+use std::thread;
 
 /// A view for editing category-specific settings.
 ///
@@ -88,7 +99,7 @@ impl CategoryEditor {
 
         let content_rect = content_rect;
 
-        let background = Filler::new(content_rect, WHITE);
+        let background = Filler::new(content_rect, theme::current().background);
         children.push(Box::new(background) as Box<dyn View>);
 
         let first_row_index = children.len();
@@ -155,7 +166,7 @@ impl CategoryEditor {
         let dpi = CURRENT_DEVICE.dpi;
         let small_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
 
-        let separator_thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let separator_thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let (separator_top_half, separator_bottom_half) = halves(separator_thickness);
         let bar_height = small_height;
 
@@ -187,7 +198,7 @@ impl CategoryEditor {
                 rect.max.x,
                 rect.max.y - bar_height + separator_bottom_half
             ],
-            BLACK,
+            theme::current().foreground,
         );
         Box::new(separator) as Box<dyn View>
     }
@@ -355,6 +366,29 @@ impl CategoryEditor {
         true
     }
 
+    /// Handles a horizontal swipe by switching to the adjacent category.
+    ///
+    /// Swiping west shows the next category, swiping east the previous one. The swipe is
+    /// ignored while an overlay, such as a menu or the library editor, is open.
+    #[inline]
+    fn handle_swipe(&self, dir: Dir, bus: &mut Bus) -> bool {
+        let dir = match dir {
+            Dir::West => CycleDir::Next,
+            Dir::East => CycleDir::Previous,
+            _ => return false,
+        };
+
+        if self.children.len() > self.keyboard_index + 1 {
+            return false;
+        }
+
+        if let Some(category) = self.category.adjacent(dir) {
+            bus.push_back(Event::SelectSettingsCategory(category));
+        }
+
+        true
+    }
+
     /// Handles a short hold finger gesture to show a context menu for deleting libraries.
     #[inline]
     fn handle_hold_finger_short(
@@ -433,6 +467,180 @@ impl CategoryEditor {
         true
     }
 
+    #[inline]
+    fn handle_set_ui_scale(
+        &mut self,
+        percent: u16,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        context.settings.ui_scale = percent as f32 / 100.0;
+        self.refresh_setting_values(context, rq);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        true
+    }
+
+    #[inline]
+    fn handle_set_full_refresh_interval(
+        &mut self,
+        interval: u16,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        context.settings.display.full_refresh_interval = interval;
+        context.partial_updates = 0;
+        self.refresh_setting_values(context, rq);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        true
+    }
+
+    #[inline]
+    fn handle_set_cover_dithering(
+        &mut self,
+        kind: DitheringKind,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        context.settings.display.cover_dithering = kind;
+        self.refresh_setting_values(context, rq);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        true
+    }
+
+    #[inline]
+    fn handle_set_image_dithering(
+        &mut self,
+        kind: DitheringKind,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        context.settings.display.image_dithering = kind;
+        self.refresh_setting_values(context, rq);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        true
+    }
+
+    #[inline]
+    fn handle_set_cover_close_action(
+        &mut self,
+        action: CoverCloseAction,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        context.settings.sleep_cover_actions.close = action;
+        self.refresh_setting_values(context, rq);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        true
+    }
+
+    #[inline]
+    fn handle_set_cover_open_action(
+        &mut self,
+        action: CoverOpenAction,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        context.settings.sleep_cover_actions.open = action;
+        self.refresh_setting_values(context, rq);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        true
+    }
+
+    #[inline]
+    fn handle_set_startup_rotation(
+        &mut self,
+        rotation: i8,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        context.settings.display.startup_rotation = rotation;
+        self.refresh_setting_values(context, rq);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        true
+    }
+
+    #[inline]
+    fn handle_set_rotation_lock(
+        &mut self,
+        lock: Option<RotationLock>,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        context.settings.rotation_lock = lock;
+        self.refresh_setting_values(context, rq);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        true
+    }
+
+    #[inline]
+    fn handle_toggle_full_refresh_on_close(
+        &mut self,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        let display = &mut context.settings.display;
+        display.full_refresh_on_close = !display.full_refresh_on_close;
+        self.refresh_setting_values(context, rq);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        true
+    }
+
+    #[inline]
+    fn handle_toggle_dim_overlays(&mut self, rq: &mut RenderQueue, context: &mut Context) -> bool {
+        let display = &mut context.settings.display;
+        display.dim_overlays = !display.dim_overlays;
+        self.refresh_setting_values(context, rq);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        true
+    }
+
+    #[inline]
+    fn handle_toggle_overlay_shadows(
+        &mut self,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        let display = &mut context.settings.display;
+        display.overlay_shadows = !display.overlay_shadows;
+        self.refresh_setting_values(context, rq);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        true
+    }
+
+    #[inline]
+    fn handle_toggle_bedtime(&mut self, rq: &mut RenderQueue, context: &mut Context) -> bool {
+        let bedtime = &mut context.settings.bedtime;
+        bedtime.enabled = !bedtime.enabled;
+        self.refresh_setting_values(context, rq);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        true
+    }
+
+    #[inline]
+    fn handle_toggle_webdav(&mut self, rq: &mut RenderQueue, context: &mut Context) -> bool {
+        let webdav = &mut context.settings.webdav;
+        webdav.enabled = !webdav.enabled;
+        self.refresh_setting_values(context, rq);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        true
+    }
+
+    /// Flips the Bluetooth setting, and lets the application power the radio on or off.
+    #[inline]
+    fn handle_toggle_bluetooth(
+        &mut self,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        let enabled = !context.settings.bluetooth;
+        context.settings.bluetooth = enabled;
+        self.refresh_setting_values(context, rq);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        hub.send(Event::SetBluetooth(enabled)).ok();
+        true
+    }
+
     #[inline]
     fn handle_toggle_sleep_cover(
         &mut self,
@@ -463,6 +671,170 @@ impl CategoryEditor {
         true
     }
 
+    #[inline]
+    fn handle_toggle_high_contrast(
+        &mut self,
+        _evt: &Event,
+        _hub: &Hub,
+        _bus: &mut Bus,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        context.settings.high_contrast = !context.settings.high_contrast;
+        self.refresh_setting_values(context, rq);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        true
+    }
+
+    #[inline]
+    fn handle_toggle_sync(
+        &mut self,
+        _evt: &Event,
+        _hub: &Hub,
+        _bus: &mut Bus,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        context.settings.sync.enabled = !context.settings.sync.enabled;
+        self.refresh_setting_values(context, rq);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        true
+    }
+
+    /// Opens an input field for the server, user name or password of the progress sync or
+    /// of the WebDAV sync.
+    ///
+    /// The password fields always start empty: the passwords are never shown.
+    #[inline]
+    fn handle_edit_sync_field(
+        &mut self,
+        view_id: ViewId,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        let (label, text) = match view_id {
+            ViewId::SyncServerInput => ("Sync Server", context.settings.sync.server.clone()),
+            ViewId::SyncUsernameInput => ("Username", context.settings.sync.username.clone()),
+            ViewId::WebdavServerInput => ("WebDAV Server", context.settings.webdav.server.clone()),
+            ViewId::WebdavUsernameInput => ("Username", context.settings.webdav.username.clone()),
+            _ => ("Password", String::new()),
+        };
+
+        let mut input = crate::view::named_input::NamedInput::new(
+            label.to_string(),
+            view_id,
+            view_id,
+            32,
+            context,
+        );
+
+        input.set_text(&text, rq, context);
+
+        self.children.push(Box::new(input));
+        hub.send(Event::Focus(Some(view_id))).ok();
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+
+        true
+    }
+
+    #[inline]
+    fn handle_submit_sync_field(
+        &mut self,
+        view_id: ViewId,
+        text: &str,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        let sync = &mut context.settings.sync;
+        let webdav = &mut context.settings.webdav;
+
+        match view_id {
+            ViewId::SyncServerInput => sync.server = text.trim().to_string(),
+            ViewId::SyncUsernameInput => sync.username = text.trim().to_string(),
+            ViewId::SyncPasswordInput => {
+                sync.userkey = if text.is_empty() {
+                    None
+                } else {
+                    Some(SecretString::from(kosync::userkey(text)))
+                }
+            }
+            ViewId::WebdavServerInput => webdav.server = text.trim().to_string(),
+            ViewId::WebdavUsernameInput => webdav.username = text.trim().to_string(),
+            _ => {
+                webdav.password = if text.is_empty() {
+                    None
+                } else {
+                    Some(SecretString::from(text.to_string()))
+                }
+            }
+        }
+
+        self.refresh_setting_values(context, rq);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+
+        hub.send(Event::Focus(None)).ok();
+
+        true
+    }
+
+    /// Registers or checks the sync account in the background.
+    ///
+    /// The outcome is reported through a notification.
+    #[inline]
+    fn handle_sync_account(&mut self, register: bool, hub: &Hub, context: &Context) -> bool {
+        let settings = context.settings.sync.clone();
+        let hub2 = hub.clone();
+
+        thread::spawn(move || {
+            let result = KosyncClient::new(&settings).and_then(|client| {
+                if register {
+                    client.register()
+                } else {
+                    client.authorize()
+                }
+            });
+
+            let message = match (register, result) {
+                (true, Ok(())) => format!("Registered as {}.", settings.username),
+                (false, Ok(())) => format!("Logged in as {}.", settings.username),
+                (true, Err(e)) => format!("Can't register: {}.", e),
+                (false, Err(e)) => format!("Can't log in: {}.", e),
+            };
+
+            hub2.send(Event::Notification(NotificationEvent::Show(message)))
+                .ok();
+        });
+
+        true
+    }
+
+    fn handle_flush_sync_queue(&mut self, hub: &Hub, context: &Context) -> bool {
+        let count = sync_queue::pending_count();
+        let message = if count == 0 {
+            "Nothing to sync.".to_string()
+        } else if !context.online {
+            "The pending changes will be synced once the network is up.".to_string()
+        } else {
+            sync_queue::flush(&context.settings, hub);
+            if context.settings.webdav.annotations.is_some()
+                && sync_queue::has_pending_annotations()
+            {
+                hub.send(Event::Select(EntryId::SyncWebdav)).ok();
+            }
+            format!(
+                "Syncing {} pending change{}.",
+                count,
+                if count == 1 { "" } else { "s" }
+            )
+        };
+
+        hub.send(Event::Notification(NotificationEvent::Show(message)))
+            .ok();
+        true
+    }
+
     #[inline]
     fn handle_edit_auto_suspend(
         &mut self,
@@ -522,6 +894,35 @@ impl CategoryEditor {
         true
     }
 
+    #[inline]
+    fn handle_edit_auto_wifi_off(
+        &mut self,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        let mut wifi_off_input = crate::view::named_input::NamedInput::new(
+            "Auto Wi-Fi Off (minutes, 0 = never)".to_string(),
+            ViewId::AutoWifiOffInput,
+            ViewId::AutoWifiOffInput,
+            10,
+            context,
+        );
+        let text = if context.settings.auto_wifi_off == 0.0 {
+            "0".to_string()
+        } else {
+            format!("{:.1}", context.settings.auto_wifi_off)
+        };
+
+        wifi_off_input.set_text(&text, rq, context);
+
+        self.children.push(Box::new(wifi_off_input));
+        hub.send(Event::Focus(Some(ViewId::AutoWifiOffInput))).ok();
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+
+        true
+    }
+
     #[inline]
     fn handle_set_button_scheme(
         &mut self,
@@ -545,6 +946,41 @@ impl CategoryEditor {
         index: usize,
         rq: &mut RenderQueue,
         context: &mut Context,
+    ) -> bool {
+        if let Some(menu_index) = locate_by_id(self, ViewId::SettingsValueMenu) {
+            self.children.remove(menu_index);
+            rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        }
+
+        let Some(library) = context.settings.libraries.get(index) else {
+            return true;
+        };
+        let name = library.name.clone();
+
+        let dialog = ConfirmDialog::new(
+            ViewId::ConfirmDialog,
+            "Delete the library?",
+            &name,
+            "Delete",
+            Event::DeleteLibrary(index),
+            context,
+        );
+        rq.add(RenderData::new(
+            dialog.id(),
+            *dialog.rect(),
+            UpdateMode::Gui,
+        ));
+        self.children.push(Box::new(dialog));
+
+        true
+    }
+
+    #[inline]
+    fn handle_delete_library_event(
+        &mut self,
+        index: usize,
+        rq: &mut RenderQueue,
+        context: &mut Context,
     ) -> bool {
         if index < context.settings.libraries.len() {
             let original_count = context.settings.libraries.len();
@@ -553,11 +989,6 @@ impl CategoryEditor {
             self.rebuild_library_rows(rq, context, Some(original_count));
         }
 
-        if let Some(menu_index) = locate_by_id(self, ViewId::SettingsValueMenu) {
-            self.children.remove(menu_index);
-            rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
-        }
-
         true
     }
 
@@ -583,7 +1014,7 @@ impl CategoryEditor {
         rq: &mut RenderQueue,
         context: &mut Context,
     ) -> bool {
-        self.handle_close_view_event(&ViewId::SettingsValueMenu, rq);
+        self.handle_close_view_event(&ViewId::SettingsValueMenu, rq, context);
 
         self.active_intermission_edit = Some(*kind);
 
@@ -647,6 +1078,41 @@ impl CategoryEditor {
         true
     }
 
+    /// Handles the `EditTapZones` entry by opening a `TapZonesEditor` overlay.
+    ///
+    /// Like the `LibraryEditor`, the overlay is pushed after the keyboard so `keyboard_index`
+    /// remains valid.
+    #[inline]
+    fn handle_edit_tap_zones(&mut self, rq: &mut RenderQueue, context: &mut Context) -> bool {
+        let tap_zones_editor = TapZonesEditor::new(self.rect, rq, context);
+        self.children.push(Box::new(tap_zones_editor));
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        true
+    }
+
+    /// Handles the `EditGestureMap` entry by opening a `GestureMapEditor` overlay.
+    #[inline]
+    fn handle_edit_gesture_map(&mut self, rq: &mut RenderQueue, context: &mut Context) -> bool {
+        let gesture_map_editor = GestureMapEditor::new(self.rect, rq, context);
+        self.children.push(Box::new(gesture_map_editor));
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        true
+    }
+
+    /// Handles the `EditBluetoothDevices` entry by opening a `BluetoothEditor` overlay.
+    #[inline]
+    fn handle_edit_bluetooth_devices(
+        &mut self,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        let bluetooth_editor = BluetoothEditor::new(self.rect, hub, rq, context);
+        self.children.push(Box::new(bluetooth_editor));
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        true
+    }
+
     /// Handles the `EditLibrary` event by opening a `LibraryEditor` overlay for the specified library.
     ///
     /// This function creates a `LibraryEditor` view that allows the user to modify an existing
@@ -705,6 +1171,26 @@ impl CategoryEditor {
         true
     }
 
+    #[inline]
+    fn handle_submit_auto_wifi_off(
+        &mut self,
+        text: &str,
+        hub: &Hub,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        if let Ok(value) = text.parse::<f32>() {
+            context.settings.auto_wifi_off = value;
+        }
+
+        self.refresh_setting_values(context, rq);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+
+        hub.send(Event::Focus(None)).ok();
+
+        true
+    }
+
     #[inline]
     fn handle_submit_auto_power_off(
         &mut self,
@@ -759,10 +1245,14 @@ impl CategoryEditor {
     ///
     /// This method manages the closure of different overlay and child views:
     ///
-    /// - **LibraryEditor, AutoSuspendInput, AutoPowerOffInput, SettingsValueMenu**: These overlay
-    ///   views are removed from the children list and a GUI update is scheduled. The event is
+    /// - **LibraryEditor, BluetoothEditor, AutoSuspendInput, AutoPowerOffInput, AutoWifiOffInput, Sync*Input,
+    ///   SettingsValueMenu**: These
+    ///   overlay views are removed from the children list and a GUI update is scheduled. The event is
     ///   considered handled.
     ///
+    /// - **TapZonesEditor, GestureMapEditor**: The overlay is removed and the setting values are
+    ///   refreshed, since the mappings were edited in place.
+    ///
     /// - **FileChooser**: The file chooser is removed from the children list, the active
     ///   intermission edit state is cleared, and a GUI update is scheduled.
     ///
@@ -772,16 +1262,39 @@ impl CategoryEditor {
     ///
     /// * `view_id` - The ID of the view being closed
     /// * `rq` - The render queue for scheduling UI updates
+    /// * `context` - The application context containing settings
     ///
     /// # Returns
     ///
     /// `true` if the event was handled, `false` otherwise.
     #[inline]
-    fn handle_close_view_event(&mut self, view_id: &ViewId, rq: &mut RenderQueue) -> bool {
+    fn handle_close_view_event(
+        &mut self,
+        view_id: &ViewId,
+        rq: &mut RenderQueue,
+        context: &Context,
+    ) -> bool {
         match view_id {
+            ViewId::TapZonesEditor | ViewId::GestureMapEditor => {
+                if let Some(index) = locate_by_id(self, *view_id) {
+                    self.children.remove(index);
+                    self.refresh_setting_values(context, rq);
+                    rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+                }
+                true
+            }
             ViewId::LibraryEditor
+            | ViewId::BluetoothEditor
+            | ViewId::ConfirmDialog
             | ViewId::AutoSuspendInput
             | ViewId::AutoPowerOffInput
+            | ViewId::AutoWifiOffInput
+            | ViewId::SyncServerInput
+            | ViewId::SyncUsernameInput
+            | ViewId::SyncPasswordInput
+            | ViewId::WebdavServerInput
+            | ViewId::WebdavUsernameInput
+            | ViewId::WebdavPasswordInput
             | ViewId::SettingsValueMenu => {
                 if let Some(index) = locate_by_id(self, *view_id) {
                     self.children.remove(index);
@@ -839,6 +1352,18 @@ impl CategoryEditor {
                         context,
                     ),
                 },
+                ToggleSettings::Sync => self.handle_toggle_sync(evt, hub, bus, rq, context),
+                ToggleSettings::HighContrast => {
+                    self.handle_toggle_high_contrast(evt, hub, bus, rq, context)
+                }
+                ToggleSettings::FullRefreshOnClose => {
+                    self.handle_toggle_full_refresh_on_close(rq, context)
+                }
+                ToggleSettings::DimOverlays => self.handle_toggle_dim_overlays(rq, context),
+                ToggleSettings::OverlayShadows => self.handle_toggle_overlay_shadows(rq, context),
+                ToggleSettings::Bluetooth => self.handle_toggle_bluetooth(hub, rq, context),
+                ToggleSettings::Bedtime => self.handle_toggle_bedtime(rq, context),
+                ToggleSettings::Webdav => self.handle_toggle_webdav(rq, context),
             },
             _ => unreachable!("mismatched toggle event"),
         }
@@ -860,6 +1385,11 @@ impl View for CategoryEditor {
             Event::Gesture(GestureEvent::HoldFingerShort(point, _)) => {
                 self.handle_hold_finger_short(point, bus, context)
             }
+            Event::Gesture(GestureEvent::Swipe { dir, start, .. })
+                if self.rect.includes(*start) =>
+            {
+                self.handle_swipe(*dir, bus)
+            }
             Event::SubMenu(rect, ref entries) => {
                 self.handle_submenu_event(rect, entries, rq, context)
             }
@@ -872,6 +1402,27 @@ impl View for CategoryEditor {
                 }
                 EntryId::EditAutoSuspend => self.handle_edit_auto_suspend(hub, rq, context),
                 EntryId::EditAutoPowerOff => self.handle_edit_auto_power_off(hub, rq, context),
+                EntryId::EditAutoWifiOff => self.handle_edit_auto_wifi_off(hub, rq, context),
+                EntryId::SetUiScale(percent) => self.handle_set_ui_scale(*percent, rq, context),
+                EntryId::SetFullRefreshInterval(interval) => {
+                    self.handle_set_full_refresh_interval(*interval, rq, context)
+                }
+                EntryId::SetCoverDithering(kind) => {
+                    self.handle_set_cover_dithering(*kind, rq, context)
+                }
+                EntryId::SetImageDithering(kind) => {
+                    self.handle_set_image_dithering(*kind, rq, context)
+                }
+                EntryId::SetCoverCloseAction(action) => {
+                    self.handle_set_cover_close_action(*action, rq, context)
+                }
+                EntryId::SetCoverOpenAction(action) => {
+                    self.handle_set_cover_open_action(*action, rq, context)
+                }
+                EntryId::SetStartupRotation(rotation) => {
+                    self.handle_set_startup_rotation(*rotation, rq, context)
+                }
+                EntryId::SetRotationLock(lock) => self.handle_set_rotation_lock(*lock, rq, context),
                 EntryId::SetButtonScheme(button_scheme) => {
                     self.handle_set_button_scheme(button_scheme, evt, hub, bus, rq, context)
                 }
@@ -882,6 +1433,32 @@ impl View for CategoryEditor {
                 EntryId::EditIntermissionImage(kind) => {
                     self.handle_edit_intermission_image(kind, hub, rq, context)
                 }
+                EntryId::EditTapZones => self.handle_edit_tap_zones(rq, context),
+                EntryId::EditGestureMap => self.handle_edit_gesture_map(rq, context),
+                EntryId::EditBluetoothDevices => {
+                    self.handle_edit_bluetooth_devices(hub, rq, context)
+                }
+                EntryId::EditSyncServer => {
+                    self.handle_edit_sync_field(ViewId::SyncServerInput, hub, rq, context)
+                }
+                EntryId::EditSyncUsername => {
+                    self.handle_edit_sync_field(ViewId::SyncUsernameInput, hub, rq, context)
+                }
+                EntryId::EditSyncPassword => {
+                    self.handle_edit_sync_field(ViewId::SyncPasswordInput, hub, rq, context)
+                }
+                EntryId::RegisterSyncAccount => self.handle_sync_account(true, hub, context),
+                EntryId::AuthorizeSyncAccount => self.handle_sync_account(false, hub, context),
+                EntryId::FlushSyncQueue => self.handle_flush_sync_queue(hub, context),
+                EntryId::EditWebdavServer => {
+                    self.handle_edit_sync_field(ViewId::WebdavServerInput, hub, rq, context)
+                }
+                EntryId::EditWebdavUsername => {
+                    self.handle_edit_sync_field(ViewId::WebdavUsernameInput, hub, rq, context)
+                }
+                EntryId::EditWebdavPassword => {
+                    self.handle_edit_sync_field(ViewId::WebdavPasswordInput, hub, rq, context)
+                }
                 _ => false,
             },
             Event::AddLibrary => self.handle_add_library_event(hub, rq, context),
@@ -889,16 +1466,29 @@ impl View for CategoryEditor {
             Event::UpdateLibrary(index, ref library) => {
                 self.handle_update_library_event(*index, library, rq, context)
             }
+            Event::DeleteLibrary(index) => self.handle_delete_library_event(*index, rq, context),
             Event::Submit(ViewId::AutoSuspendInput, ref text) => {
                 self.handle_submit_auto_suspend(text, hub, rq, context)
             }
             Event::Submit(ViewId::AutoPowerOffInput, ref text) => {
                 self.handle_submit_auto_power_off(text, hub, rq, context)
             }
+            Event::Submit(ViewId::AutoWifiOffInput, ref text) => {
+                self.handle_submit_auto_wifi_off(text, hub, rq, context)
+            }
+            Event::Submit(
+                view_id @ (ViewId::SyncServerInput
+                | ViewId::SyncUsernameInput
+                | ViewId::SyncPasswordInput
+                | ViewId::WebdavServerInput
+                | ViewId::WebdavUsernameInput
+                | ViewId::WebdavPasswordInput),
+                ref text,
+            ) => self.handle_submit_sync_field(*view_id, text, hub, rq, context),
             Event::FileChooserClosed(ref path) => {
                 self.handle_file_chooser_closed(path, rq, context)
             }
-            Event::Close(view_id) => self.handle_close_view_event(view_id, rq),
+            Event::Close(view_id) => self.handle_close_view_event(view_id, rq, context),
             _ => false,
         }
     }
@@ -1071,6 +1661,29 @@ mod tests {
         );
 
         assert!(handled);
+        assert_eq!(
+            context.settings.libraries.len(),
+            2,
+            "the library should only be deleted once confirmed"
+        );
+        assert!(locate_by_id(&editor, ViewId::ConfirmDialog).is_some());
+
+        editor.handle_event(
+            &Event::DeleteLibrary(0),
+            &hub,
+            &mut bus,
+            &mut rq,
+            &mut context,
+        );
+        editor.handle_event(
+            &Event::Close(ViewId::ConfirmDialog),
+            &hub,
+            &mut bus,
+            &mut rq,
+            &mut context,
+        );
+
+        assert!(locate_by_id(&editor, ViewId::ConfirmDialog).is_none());
         assert_eq!(context.settings.libraries.len(), 1);
         assert_eq!(context.settings.libraries[0].name, "Library 1");
 
@@ -1347,7 +1960,7 @@ mod tests {
             .children
             .push(Box::new(crate::view::filler::Filler::new(
                 rect![0, 0, 100, 100],
-                crate::color::WHITE,
+                theme::current().background,
             )));
 
         let handled = editor.handle_event(
@@ -1363,4 +1976,106 @@ mod tests {
             "Close event for FileChooser should not capture the event so that settings editor can refresh the whole screen.");
         assert!(editor.active_intermission_edit.is_none());
     }
+
+    #[test]
+    fn test_submit_sync_password_stores_userkey() {
+        use secrecy::ExposeSecret;
+
+        let mut context = create_test_context();
+        let rect = rect![0, 0, 600, 800];
+        let mut rq = RenderQueue::new();
+        let mut editor = CategoryEditor::new(rect, Category::Sync, &mut rq, &mut context);
+        let (hub, _receiver) = channel();
+        let mut bus = VecDeque::new();
+
+        let handled = editor.handle_event(
+            &Event::Submit(ViewId::SyncPasswordInput, "password".to_string()),
+            &hub,
+            &mut bus,
+            &mut rq,
+            &mut context,
+        );
+
+        assert!(handled);
+        assert_eq!(
+            context
+                .settings
+                .sync
+                .userkey
+                .as_ref()
+                .map(|key| key.expose_secret()),
+            Some("5f4dcc3b5aa765d61d8327deb882cf99")
+        );
+
+        editor.handle_event(
+            &Event::Submit(ViewId::SyncPasswordInput, String::new()),
+            &hub,
+            &mut bus,
+            &mut rq,
+            &mut context,
+        );
+
+        assert!(context.settings.sync.userkey.is_none());
+    }
+
+    #[test]
+    fn test_submit_webdav_password_stores_password() {
+        use secrecy::ExposeSecret;
+
+        let mut context = create_test_context();
+        let rect = rect![0, 0, 600, 800];
+        let mut rq = RenderQueue::new();
+        let mut editor = CategoryEditor::new(rect, Category::Sync, &mut rq, &mut context);
+        let (hub, _receiver) = channel();
+        let mut bus = VecDeque::new();
+
+        let handled = editor.handle_event(
+            &Event::Submit(ViewId::WebdavPasswordInput, "app-password".to_string()),
+            &hub,
+            &mut bus,
+            &mut rq,
+            &mut context,
+        );
+
+        assert!(handled);
+        assert_eq!(
+            context
+                .settings
+                .webdav
+                .password
+                .as_ref()
+                .map(|password| password.expose_secret()),
+            Some("app-password")
+        );
+        assert!(context.settings.sync.userkey.is_none());
+    }
+
+    #[test]
+    fn test_horizontal_swipe_selects_adjacent_category() {
+        let mut context = create_test_context();
+        let rect = rect![0, 0, 600, 800];
+        let mut rq = RenderQueue::new();
+        let mut editor = CategoryEditor::new(rect, Category::General, &mut rq, &mut context);
+        let (hub, _receiver) = channel();
+        let mut bus = VecDeque::new();
+
+        let swipe = |dir| {
+            Event::Gesture(GestureEvent::Swipe {
+                dir,
+                start: Point::new(300, 400),
+                end: Point::new(100, 400),
+            })
+        };
+
+        assert!(editor.handle_event(&swipe(Dir::West), &hub, &mut bus, &mut rq, &mut context));
+        assert!(matches!(
+            bus.pop_front(),
+            Some(Event::SelectSettingsCategory(Category::Libraries))
+        ));
+
+        assert!(editor.handle_event(&swipe(Dir::East), &hub, &mut bus, &mut rq, &mut context));
+        assert!(bus.is_empty(), "General is the first category");
+
+        assert!(!editor.handle_event(&swipe(Dir::North), &hub, &mut bus, &mut rq, &mut context));
+    }
 }
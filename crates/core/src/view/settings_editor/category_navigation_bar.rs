@@ -1,13 +1,15 @@
 use super::category::Category;
 use super::category_button::CategoryButton;
-use crate::color::TEXT_BUMP_SMALL;
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::{font_from_style, Fonts, NORMAL_STYLE};
-use crate::framebuffer::Framebuffer;
-use crate::geom::{Point, Rectangle};
+use crate::framebuffer::{Framebuffer, UpdateMode};
+use crate::geom::{CycleDir, Dir, Point, Rectangle};
+use crate::gesture::GestureEvent;
+use crate::theme;
 use crate::view::filler::Filler;
-use crate::view::{Align, Bus, Event, Hub, Id, RenderQueue, View, ID_FEEDER};
+use crate::view::icon::Icon;
+use crate::view::{Align, Bus, Event, Hub, Id, RenderData, RenderQueue, View, ID_FEEDER};
 
 /// Horizontal navigation bar displaying category tabs.
 ///
@@ -15,18 +17,27 @@ use crate::view::{Align, Bus, Event, Hub, Id, RenderQueue, View, ID_FEEDER};
 /// Intermissions) as horizontal tabs. The selected category is visually highlighted
 /// using `ActionLabel` children that manage their own color states.
 ///
+/// When the tabs don't fit, the bar shows the ones starting at `offset` between two
+/// chevrons. Tapping a chevron or swiping over the bar scrolls the tabs by one, and the
+/// selected tab is always kept in view.
+///
 /// # Structure
 ///
 /// ```text
 /// ┌─────────────────────────────────────────────┐
 /// │ [General] [Libraries] [Intermissions]       │
 /// └─────────────────────────────────────────────┘
+/// ┌─────────────────────────────────────────────┐
+/// │ < [Libraries] [Intermission Screens]      > │
+/// └─────────────────────────────────────────────┘
 /// ```
 pub struct CategoryNavigationBar {
     id: Id,
     pub rect: Rectangle,
     children: Vec<Box<dyn View>>,
     pub selected: Category,
+    // Index of the first visible category.
+    offset: usize,
 }
 
 impl CategoryNavigationBar {
@@ -39,38 +50,76 @@ impl CategoryNavigationBar {
             rect,
             children: Vec::new(),
             selected,
+            offset: 0,
         }
     }
 
     #[cfg_attr(feature = "otel", tracing::instrument(skip(self, fonts)))]
     pub fn update_content(&mut self, selected: Category, fonts: &mut Fonts) {
         self.selected = selected;
+        let widths = Self::button_widths(fonts);
+        let chevron_width = self.rect.height() as i32;
+        let width = self.rect.width() as i32 - Self::padding(fonts) / 2;
+        if let Some(index) = Category::all().iter().position(|c| *c == selected) {
+            self.offset = scroll_into_view(&widths, self.offset, index, width, chevron_width);
+        }
         self.children.clear();
-        self.children = Self::build_category_buttons(self.rect, selected, fonts);
+        self.children = self.build_category_buttons(&widths, fonts);
     }
 
-    #[cfg_attr(feature = "otel", tracing::instrument(skip(fonts)))]
-    fn build_category_buttons(
-        rect: Rectangle,
-        selected: Category,
-        fonts: &mut Fonts,
-    ) -> Vec<Box<dyn View>> {
+    fn padding(fonts: &mut Fonts) -> i32 {
+        font_from_style(fonts, &NORMAL_STYLE, CURRENT_DEVICE.dpi).em() as i32
+    }
+
+    fn button_widths(fonts: &mut Fonts) -> Vec<i32> {
+        let font = font_from_style(fonts, &NORMAL_STYLE, CURRENT_DEVICE.dpi);
+        let padding = font.em() as i32;
+        Category::all()
+            .iter()
+            .map(|category| font.plan(category.label(), None, None).width + padding)
+            .collect()
+    }
+
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, widths, fonts)))]
+    fn build_category_buttons(&self, widths: &[i32], fonts: &mut Fonts) -> Vec<Box<dyn View>> {
+        let rect = self.rect;
         let mut children = Vec::new();
         let categories = Category::all();
-        let dpi = CURRENT_DEVICE.dpi;
-        let font = font_from_style(fonts, &NORMAL_STYLE, dpi);
-        let padding = font.em() as i32;
-        let background = TEXT_BUMP_SMALL[0];
+        let padding = Self::padding(fonts);
+        let background = theme::current().text_bump_small[0];
+        let chevron_width = rect.height() as i32;
+        let count = visible_count(
+            widths,
+            self.offset,
+            rect.width() as i32 - padding / 2,
+            chevron_width,
+        );
+        let end = self.offset + count;
 
         let mut x_pos = rect.min.x + padding / 2;
 
-        for category in categories.iter() {
-            let text = category.label();
-            let plan = font.plan(&text, None, None);
-            let button_width = plan.width + padding;
+        if self.offset > 0 {
+            let chevron = Icon::new(
+                "angle-left",
+                rect![
+                    rect.min.x,
+                    rect.min.y,
+                    rect.min.x + chevron_width,
+                    rect.max.y
+                ],
+                Event::Page(CycleDir::Previous),
+            )
+            .background(background);
+            children.push(Box::new(chevron) as Box<dyn View>);
+            x_pos = rect.min.x + chevron_width;
+        }
 
+        for (category, button_width) in categories[self.offset..end]
+            .iter()
+            .zip(&widths[self.offset..end])
+        {
             let button_rect = rect![x_pos, rect.min.y, x_pos + button_width, rect.max.y];
-            let is_selected = *category == selected;
+            let is_selected = *category == self.selected;
 
             let button = CategoryButton::new(
                 button_rect,
@@ -83,15 +132,47 @@ impl CategoryNavigationBar {
             x_pos += button_width;
         }
 
-        if x_pos < rect.max.x {
-            let filler_rect = rect![x_pos, rect.min.y, rect.max.x, rect.max.y];
+        let max_x = if end < categories.len() {
+            rect.max.x - chevron_width
+        } else {
+            rect.max.x
+        };
+
+        if x_pos < max_x {
+            let filler_rect = rect![x_pos, rect.min.y, max_x, rect.max.y];
             let filler = Filler::new(filler_rect, background);
             children.push(Box::new(filler) as Box<dyn View>);
         }
 
+        if end < categories.len() {
+            let chevron = Icon::new(
+                "angle-right",
+                rect![max_x, rect.min.y, rect.max.x, rect.max.y],
+                Event::Page(CycleDir::Next),
+            )
+            .background(background);
+            children.push(Box::new(chevron) as Box<dyn View>);
+        }
+
         children
     }
 
+    /// Scrolls the tabs by one, returns whether they moved.
+    fn scroll(&mut self, dir: CycleDir, fonts: &mut Fonts) -> bool {
+        let widths = Self::button_widths(fonts);
+        let chevron_width = self.rect.height() as i32;
+        let width = self.rect.width() as i32 - Self::padding(fonts) / 2;
+        let count = visible_count(&widths, self.offset, width, chevron_width);
+        let offset = match dir {
+            CycleDir::Next if self.offset + count < widths.len() => self.offset + 1,
+            CycleDir::Previous if self.offset > 0 => self.offset - 1,
+            _ => return false,
+        };
+        self.offset = offset;
+        self.children = self.build_category_buttons(&widths, fonts);
+        true
+    }
+
     #[cfg_attr(feature = "otel", tracing::instrument(skip(self, fonts)))]
     pub fn update_selection(&mut self, selected: Category, fonts: &mut Fonts) {
         if self.selected == selected {
@@ -113,17 +194,67 @@ impl CategoryNavigationBar {
     }
 }
 
+/// Returns how many tabs starting at `offset` fit in `width`, leaving room for the chevrons.
+fn visible_count(widths: &[i32], offset: usize, width: i32, chevron_width: i32) -> usize {
+    let left = if offset > 0 { chevron_width } else { 0 };
+    if left + widths[offset..].iter().sum::<i32>() <= width {
+        return widths.len() - offset;
+    }
+    let available = width - left - chevron_width;
+    let mut used = 0;
+    let count = widths[offset..]
+        .iter()
+        .take_while(|w| {
+            used += *w;
+            used <= available
+        })
+        .count();
+    count.max(1)
+}
+
+/// Returns the offset closest to `offset` showing the tab at `index`.
+fn scroll_into_view(
+    widths: &[i32],
+    offset: usize,
+    index: usize,
+    width: i32,
+    chevron_width: i32,
+) -> usize {
+    let mut offset = offset.min(index);
+    while offset + visible_count(widths, offset, width, chevron_width) <= index {
+        offset += 1;
+    }
+    offset
+}
+
 impl View for CategoryNavigationBar {
-    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _hub, _bus, _rq, _context), fields(event = ?_evt), ret(level=tracing::Level::TRACE)))]
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _hub, _bus, rq, context), fields(event = ?evt), ret(level=tracing::Level::TRACE)))]
     fn handle_event(
         &mut self,
-        _evt: &Event,
+        evt: &Event,
         _hub: &Hub,
         _bus: &mut Bus,
-        _rq: &mut RenderQueue,
-        _context: &mut Context,
+        rq: &mut RenderQueue,
+        context: &mut Context,
     ) -> bool {
-        false
+        let dir = match *evt {
+            Event::Page(dir) => dir,
+            Event::Gesture(GestureEvent::Swipe {
+                dir: Dir::West,
+                start,
+                ..
+            }) if self.rect.includes(start) => CycleDir::Next,
+            Event::Gesture(GestureEvent::Swipe {
+                dir: Dir::East,
+                start,
+                ..
+            }) if self.rect.includes(start) => CycleDir::Previous,
+            _ => return false,
+        };
+        if self.scroll(dir, &mut context.fonts) {
+            rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        }
+        true
     }
 
     #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _fb, _fonts), fields(rect = ?_rect)))]
@@ -149,3 +280,22 @@ impl View for CategoryNavigationBar {
         self.id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overflowing_tabs_scroll() {
+        let widths = [100, 100, 100, 100, 100];
+
+        assert_eq!(visible_count(&widths, 0, 500, 50), 5);
+        assert_eq!(visible_count(&widths, 0, 400, 50), 3);
+        assert_eq!(visible_count(&widths, 1, 400, 50), 3);
+        assert_eq!(visible_count(&widths, 2, 400, 50), 3);
+
+        assert_eq!(scroll_into_view(&widths, 0, 1, 400, 50), 0);
+        assert_eq!(scroll_into_view(&widths, 0, 4, 400, 50), 2);
+        assert_eq!(scroll_into_view(&widths, 2, 0, 400, 50), 0);
+    }
+}
@@ -0,0 +1,293 @@
+use super::bottom_bar::{BottomBarVariant, SettingsEditorBottomBar};
+use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::font::Fonts;
+use crate::framebuffer::{Framebuffer, UpdateMode};
+use crate::geom::{halves, Rectangle};
+use crate::gesture::GestureEvent;
+use crate::settings::{Gesture, GestureAction};
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
+use crate::view::action_label::ActionLabel;
+use crate::view::common::locate_by_id;
+use crate::view::filler::Filler;
+use crate::view::label::Label;
+use crate::view::menu::{Menu, MenuKind};
+use crate::view::{Align, Bus, EntryId, EntryKind, Event, Hub, Id, RenderData, RenderQueue, View};
+use crate::view::{ViewId, ID_FEEDER, SMALL_BAR_HEIGHT, THICKNESS_MEDIUM};
+
+/// A list editor mapping the global gestures to actions.
+///
+/// Each row shows a [`Gesture`] next to the [`GestureAction`] it triggers. Tapping the
+/// action opens a menu of every available action, and the selected action is written to
+/// `context.settings.gestures` right away.
+///
+/// # Fields
+///
+/// * `id` - Unique identifier for this view
+/// * `rect` - The rectangular area occupied by this editor
+/// * `children` - Background, a name and a value per gesture, the bottom separator and bar,
+///   plus the optional action menu
+/// * `first_row_index` - Index of the first gesture name in the children vector
+pub struct GestureMapEditor {
+    id: Id,
+    rect: Rectangle,
+    children: Vec<Box<dyn View>>,
+    first_row_index: usize,
+}
+
+impl GestureMapEditor {
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(rq, context)))]
+    pub fn new(rect: Rectangle, rq: &mut RenderQueue, context: &mut Context) -> GestureMapEditor {
+        let id = ID_FEEDER.next();
+        let mut children = Vec::new();
+
+        let dpi = CURRENT_DEVICE.dpi;
+        let bar_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
+        let separator_thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
+        let (separator_top_half, separator_bottom_half) = halves(separator_thickness);
+
+        children.push(Box::new(Filler::new(rect, theme::current().background)) as Box<dyn View>);
+
+        let list_height = rect.height() as i32 - bar_height - separator_top_half;
+        let gestures = Gesture::all();
+        let row_height = (list_height / gestures.len() as i32).min(bar_height);
+        let padding = row_height / 3;
+        let middle_x = rect.min.x + rect.width() as i32 / 2;
+
+        let first_row_index = children.len();
+
+        for (index, gesture) in gestures.iter().enumerate() {
+            let y_min = rect.min.y + index as i32 * row_height;
+            let y_max = y_min + row_height;
+
+            let name = Label::new(
+                rect![rect.min.x, y_min, middle_x, y_max],
+                gesture.label().to_string(),
+                Align::Left(padding),
+            );
+            children.push(Box::new(name) as Box<dyn View>);
+
+            let value_rect = rect![middle_x, y_min, rect.max.x, y_max];
+            let action = context.settings.gestures[*gesture];
+            let value = ActionLabel::new(value_rect, action.to_string(), Align::Right(padding))
+                .event(Some(Self::tap_event(value_rect, *gesture, action)));
+            children.push(Box::new(value) as Box<dyn View>);
+        }
+
+        children.push(Box::new(Filler::new(
+            rect![
+                rect.min.x,
+                rect.max.y - bar_height - separator_top_half,
+                rect.max.x,
+                rect.max.y - bar_height + separator_bottom_half
+            ],
+            theme::current().foreground,
+        )) as Box<dyn View>);
+
+        children.push(Box::new(SettingsEditorBottomBar::new(
+            rect![
+                rect.min.x,
+                rect.max.y - bar_height + separator_bottom_half,
+                rect.max.x,
+                rect.max.y
+            ],
+            BottomBarVariant::SingleButton {
+                event: Event::Close(ViewId::GestureMapEditor),
+                icon: "close",
+            },
+        )) as Box<dyn View>);
+
+        rq.add(RenderData::new(id, rect, UpdateMode::Gui));
+
+        GestureMapEditor {
+            id,
+            rect,
+            children,
+            first_row_index,
+        }
+    }
+
+    fn tap_event(rect: Rectangle, gesture: Gesture, current: GestureAction) -> Event {
+        let entries = GestureAction::all()
+            .iter()
+            .map(|action| {
+                EntryKind::RadioButton(
+                    action.to_string(),
+                    EntryId::SetGestureAction(gesture, *action),
+                    *action == current,
+                )
+            })
+            .collect();
+        Event::SubMenu(rect, entries)
+    }
+
+    fn value_mut(&mut self, gesture: Gesture) -> Option<&mut ActionLabel> {
+        let index = Gesture::all().iter().position(|g| *g == gesture)?;
+        self.children[self.first_row_index + 2 * index + 1].downcast_mut::<ActionLabel>()
+    }
+
+    #[inline]
+    fn handle_set_gesture_action(
+        &mut self,
+        gesture: Gesture,
+        action: GestureAction,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        context.settings.gestures[gesture] = action;
+
+        if let Some(value) = self.value_mut(gesture) {
+            let rect = *value.rect();
+            value.update(&action.to_string(), rq);
+            value.set_event(Some(Self::tap_event(rect, gesture, action)));
+        }
+
+        true
+    }
+
+    #[inline]
+    fn handle_submenu_event(
+        &mut self,
+        rect: Rectangle,
+        entries: &[EntryKind],
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        let menu = Menu::new(
+            rect,
+            ViewId::SettingsValueMenu,
+            MenuKind::Contextual,
+            entries.to_vec(),
+            context,
+        );
+        rq.add(RenderData::new(menu.id(), *menu.rect(), UpdateMode::Gui));
+        self.children.push(Box::new(menu));
+        true
+    }
+
+    #[inline]
+    fn handle_close_event(&mut self, view_id: ViewId, rq: &mut RenderQueue) -> bool {
+        match view_id {
+            ViewId::SettingsValueMenu => {
+                if let Some(index) = locate_by_id(self, ViewId::SettingsValueMenu) {
+                    self.children.remove(index);
+                    rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl View for GestureMapEditor {
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _hub, _bus, rq, context), fields(event = ?evt), ret(level=tracing::Level::TRACE)))]
+    fn handle_event(
+        &mut self,
+        evt: &Event,
+        _hub: &Hub,
+        _bus: &mut Bus,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        match *evt {
+            Event::Select(EntryId::SetGestureAction(gesture, action)) => {
+                self.handle_set_gesture_action(gesture, action, rq, context)
+            }
+            Event::SubMenu(rect, ref entries) => {
+                self.handle_submenu_event(rect, entries, rq, context)
+            }
+            Event::Close(view_id) => self.handle_close_event(view_id, rq),
+            // The editor covers the category editor, nothing beneath it should react.
+            Event::Gesture(GestureEvent::Tap(center))
+            | Event::Gesture(GestureEvent::HoldFingerShort(center, _)) => {
+                self.rect.includes(center)
+            }
+            _ => false,
+        }
+    }
+
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _fb, _fonts), fields(rect = ?_rect)))]
+    fn render(&self, _fb: &mut dyn Framebuffer, _rect: Rectangle, _fonts: &mut Fonts) {}
+
+    fn rect(&self) -> &Rectangle {
+        &self.rect
+    }
+
+    fn rect_mut(&mut self) -> &mut Rectangle {
+        &mut self.rect
+    }
+
+    fn children(&self) -> &Vec<Box<dyn View>> {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<Box<dyn View>> {
+        &mut self.children
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn view_id(&self) -> Option<ViewId> {
+        Some(ViewId::GestureMapEditor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_helpers::create_test_context;
+    use crate::geom::Dir;
+    use std::collections::VecDeque;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_tap_on_value_opens_action_menu() {
+        let mut context = create_test_context();
+        let mut rq = RenderQueue::new();
+        let mut editor = GestureMapEditor::new(rect![0, 0, 600, 800], &mut rq, &mut context);
+        let (hub, _receiver) = channel();
+        let mut bus = VecDeque::new();
+
+        let value = *editor.children[editor.first_row_index + 1].rect();
+        let event = Event::Gesture(GestureEvent::Tap(pt!(
+            value.min.x + value.width() as i32 / 2,
+            value.min.y + value.height() as i32 / 2
+        )));
+
+        let handled =
+            crate::view::handle_event(&mut editor, &event, &hub, &mut bus, &mut rq, &mut context);
+
+        assert!(handled);
+        assert!(locate_by_id(&editor, ViewId::SettingsValueMenu).is_some());
+    }
+
+    #[test]
+    fn test_set_gesture_action_updates_settings_and_value() {
+        let mut context = create_test_context();
+        let mut rq = RenderQueue::new();
+        let mut editor = GestureMapEditor::new(rect![0, 0, 600, 800], &mut rq, &mut context);
+        let (hub, _receiver) = channel();
+        let mut bus = VecDeque::new();
+
+        let gesture = Gesture::MultiSwipe(Dir::North);
+        let event = Event::Select(EntryId::SetGestureAction(
+            gesture,
+            GestureAction::ToggleFrontlight,
+        ));
+        let handled = editor.handle_event(&event, &hub, &mut bus, &mut rq, &mut context);
+
+        assert!(handled);
+        assert_eq!(
+            context.settings.gestures[gesture],
+            GestureAction::ToggleFrontlight
+        );
+        assert_eq!(
+            editor.value_mut(gesture).map(|value| value.value()),
+            Some(GestureAction::ToggleFrontlight.to_string())
+        );
+    }
+}
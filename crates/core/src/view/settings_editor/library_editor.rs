@@ -1,6 +1,5 @@
 use super::bottom_bar::{BottomBarVariant, SettingsEditorBottomBar};
 use super::setting_row::{Kind as RowKind, SettingRow};
-use crate::color::{BLACK, WHITE};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::Fonts;
@@ -8,7 +7,8 @@ use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::{halves, Rectangle};
 use crate::gesture::GestureEvent;
 use crate::settings::{LibrarySettings, Settings};
-use crate::unit::scale_by_dpi;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
 use crate::view::common::locate_by_id;
 use crate::view::file_chooser::{FileChooser, SelectionMode};
 use crate::view::filler::Filler;
@@ -22,8 +22,8 @@ use crate::view::{SMALL_BAR_HEIGHT, THICKNESS_MEDIUM};
 /// A view for editing library settings.
 ///
 /// The `LibraryEditor` provides a user interface for configuring library properties
-/// such as name, path, and mode. It manages a collection of child views including
-/// setting rows, a keyboard for text input, and various overlays (dialogs, menus).
+/// such as name, path, mode and typography preset. It manages a collection of child views
+/// including setting rows, a keyboard for text input, and various overlays (dialogs, menus).
 ///
 /// # Fields
 ///
@@ -65,7 +65,7 @@ impl LibraryEditor {
         }
         let settings = settings;
 
-        children.push(Box::new(Filler::new(rect, WHITE)) as Box<dyn View>);
+        children.push(Box::new(Filler::new(rect, theme::current().background)) as Box<dyn View>);
 
         let (bar_height, separator_thickness, separator_top_half, separator_bottom_half) =
             Self::calculate_dimensions();
@@ -114,7 +114,7 @@ impl LibraryEditor {
     fn calculate_dimensions() -> (i32, i32, i32, i32) {
         let dpi = CURRENT_DEVICE.dpi;
         let small_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
-        let separator_thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let separator_thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let (separator_top_half, separator_bottom_half) = halves(separator_thickness);
         let bar_height = small_height;
 
@@ -174,6 +174,17 @@ impl LibraryEditor {
                 settings,
                 fonts,
             ));
+            current_y += row_height;
+        }
+
+        if current_y + row_height <= content_end_y {
+            let preset_row_rect = rect![rect.min.x, current_y, rect.max.x, current_y + row_height];
+            children.push(Box::new(SettingRow::new(
+                RowKind::LibraryTypographyPreset(library_index),
+                preset_row_rect,
+                settings,
+                fonts,
+            )) as Box<dyn View>);
         }
 
         children
@@ -237,7 +248,7 @@ impl LibraryEditor {
                 rect.max.x,
                 rect.max.y - bar_height + separator_bottom_half
             ],
-            BLACK,
+            theme::current().foreground,
         );
         Box::new(separator) as Box<dyn View>
     }
@@ -397,6 +408,17 @@ impl LibraryEditor {
         false
     }
 
+    #[inline]
+    fn handle_set_typography_preset_event(
+        &mut self,
+        preset: &Option<String>,
+        rq: &mut RenderQueue,
+    ) -> bool {
+        self.library.typography_preset = preset.clone();
+        self.update_row_value(rq);
+        false
+    }
+
     #[inline]
     fn handle_submit_name_event(&mut self, text: &str, rq: &mut RenderQueue) -> bool {
         self.library.name = text.to_string();
@@ -504,6 +526,9 @@ impl View for LibraryEditor {
                 self.handle_edit_path_event(hub, rq, context)
             }
             Event::Select(EntryId::SetLibraryMode(mode)) => self.handle_set_mode_event(mode, rq),
+            Event::Select(EntryId::SetLibraryTypographyPreset(ref preset)) => {
+                self.handle_set_typography_preset_event(preset, rq)
+            }
             Event::Submit(ViewId::LibraryRenameInput, ref text) => {
                 self.handle_submit_name_event(text, rq)
             }
@@ -789,6 +814,34 @@ mod tests {
         assert!(!rq.is_empty());
     }
 
+    #[test]
+    fn test_set_typography_preset_updates_library() {
+        let mut context = create_test_context();
+        let rect = rect![0, 0, 600, 800];
+        let (hub, _receiver) = channel();
+        let mut rq = RenderQueue::new();
+
+        let library = create_test_library();
+
+        let mut editor = LibraryEditor::new(rect, 0, library, &hub, &mut rq, &mut context);
+
+        assert_eq!(editor.library.typography_preset, None);
+
+        let mut bus = VecDeque::new();
+
+        editor.handle_event(
+            &Event::Select(EntryId::SetLibraryTypographyPreset(Some(
+                "Compact".to_string(),
+            ))),
+            &hub,
+            &mut bus,
+            &mut rq,
+            &mut context,
+        );
+
+        assert_eq!(editor.library.typography_preset.as_deref(), Some("Compact"));
+    }
+
     #[test]
     fn test_file_chooser_closed_updates_path() {
         let mut context = create_test_context();
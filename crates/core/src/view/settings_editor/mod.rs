@@ -5,7 +5,7 @@
 //! ```text
 //! SettingsEditor (Main view)
 //!   ├── TopBar (Back button, "Settings" title)
-//!   ├── StackNavigationBar (Category tabs: General | Libraries | Intermissions)
+//!   ├── StackNavigationBar (Category tabs: General | Libraries | Intermissions | Reader)
 //!   └── CategoryEditor (Embedded, shows settings for selected category)
 //!       ├── SettingRow (One for each setting in the category)
 //!       │   ├── Label (Setting name)
@@ -21,19 +21,25 @@
 //! - **SettingRow**: Individual setting with label and value
 //! - **SettingValue**: Interactive value display that opens editors/menus
 //! - **LibraryEditor**: Specialized editor for library settings
+//! - **TapZonesEditor**: Grid editor mapping the reader's tap zones to actions
+//! - **GestureMapEditor**: List editor mapping the global gestures to actions
+//! - **BluetoothEditor**: List editor pairing the Bluetooth page-turn remotes
 //!
 //! ## Event Flow
 //!
 //! When a setting is modified, the CategoryEditor directly updates `context.settings`,
 //! providing immediate feedback. Settings are persisted to disk when the settings editor
 //! is closed.
+//!
+//! Swiping west or east over the CategoryEditor sends a `SelectSettingsCategory` event for
+//! the next or previous category, just like tapping its tab.
 
-use crate::color::BLACK;
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::{halves, Rectangle};
-use crate::unit::scale_by_dpi;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
 use crate::view::common::toggle_main_menu;
 use crate::view::filler::Filler;
 use crate::view::navigation::stack_navigation_bar::StackNavigationBar;
@@ -41,26 +47,33 @@ use crate::view::top_bar::{TopBar, TopBarVariant};
 use crate::view::{Bus, Event, Hub, Id, RenderData, RenderQueue, View, ViewId, ID_FEEDER};
 use crate::view::{SMALL_BAR_HEIGHT, THICKNESS_MEDIUM};
 
+mod bluetooth_editor;
 mod bottom_bar;
 mod category;
 mod category_button;
 mod category_editor;
 mod category_navigation_bar;
 mod category_provider;
+mod gesture_map_editor;
 mod library_editor;
 mod setting_row;
 mod setting_value;
+mod tap_zone_cell;
+mod tap_zones_editor;
 
 pub use setting_value::ToggleSettings;
 
+pub use self::bluetooth_editor::BluetoothEditor;
 pub use self::bottom_bar::{BottomBarVariant, SettingsEditorBottomBar};
 pub use self::category::Category;
 pub use self::category_button::CategoryButton;
 pub use self::category_editor::CategoryEditor;
 pub use self::category_navigation_bar::CategoryNavigationBar;
 pub use self::category_provider::SettingsCategoryProvider;
+pub use self::gesture_map_editor::GestureMapEditor;
 pub use self::setting_row::{Kind as RowKind, SettingRow};
 pub use self::setting_value::SettingValue;
+pub use self::tap_zones_editor::TapZonesEditor;
 
 /// Main settings editor view.
 ///
@@ -147,7 +160,7 @@ impl SettingsEditor {
     fn calculate_dimensions() -> (i32, i32, i32, i32) {
         let dpi = CURRENT_DEVICE.dpi;
         let small_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
-        let separator_thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let separator_thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let (separator_top_half, separator_bottom_half) = halves(separator_thickness);
         let bar_height = small_height;
 
@@ -192,7 +205,7 @@ impl SettingsEditor {
                 rect.max.x,
                 rect.min.y + bar_height + separator_bottom_half
             ],
-            BLACK,
+            theme::current().foreground,
         );
         Box::new(separator) as Box<dyn View>
     }
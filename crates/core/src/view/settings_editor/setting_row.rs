@@ -11,17 +11,48 @@ use crate::view::settings_editor::ToggleSettings;
 pub enum Kind {
     KeyboardLayout,
     SleepCover,
+    CoverClose,
+    CoverOpen,
     AutoShare,
     AutoSuspend,
     AutoPowerOff,
+    AutoWifiOff,
+    Bedtime,
     ButtonScheme,
+    HighContrast,
+    UiScale,
+    GestureMap,
+    Backups,
+    FullRefreshInterval,
+    FullRefreshOnClose,
+    DimOverlays,
+    OverlayShadows,
+    CoverDithering,
+    ImageDithering,
+    StartupRotation,
+    RotationLock,
     Library(usize),
     LibraryName(usize),
     LibraryPath(usize),
     LibraryMode(usize),
+    LibraryTypographyPreset(usize),
     IntermissionSuspend,
     IntermissionPowerOff,
     IntermissionShare,
+    TapZones,
+    SyncEnabled,
+    SyncServer,
+    SyncUsername,
+    SyncPassword,
+    SyncAccount,
+    SyncQueue,
+    WebdavEnabled,
+    WebdavServer,
+    WebdavUsername,
+    WebdavPassword,
+    WebdavSync,
+    Bluetooth,
+    BluetoothDevices,
 }
 
 impl Kind {
@@ -38,10 +69,26 @@ impl Kind {
         match self {
             Kind::KeyboardLayout => "Keyboard Layout".to_string(),
             Kind::SleepCover => "Enable Sleep Cover".to_string(),
+            Kind::CoverClose => "Closing the Cover".to_string(),
+            Kind::CoverOpen => "Opening the Cover".to_string(),
             Kind::AutoShare => "Enable Auto Share".to_string(),
             Kind::AutoSuspend => "Auto Suspend (minutes)".to_string(),
             Kind::AutoPowerOff => "Auto Power Off (days)".to_string(),
+            Kind::AutoWifiOff => "Auto Wi-Fi Off (minutes)".to_string(),
+            Kind::Bedtime => "Bedtime Mode".to_string(),
             Kind::ButtonScheme => "Button Scheme".to_string(),
+            Kind::HighContrast => "High Contrast".to_string(),
+            Kind::UiScale => "Interface Scale".to_string(),
+            Kind::GestureMap => "Gestures".to_string(),
+            Kind::Backups => "Backups".to_string(),
+            Kind::FullRefreshInterval => "Full Refresh Every".to_string(),
+            Kind::FullRefreshOnClose => "Full Refresh On Close".to_string(),
+            Kind::DimOverlays => "Dim Behind Overlays".to_string(),
+            Kind::OverlayShadows => "Overlay Shadows".to_string(),
+            Kind::CoverDithering => "Cover Dithering".to_string(),
+            Kind::ImageDithering => "Image Dithering".to_string(),
+            Kind::StartupRotation => "Startup Rotation".to_string(),
+            Kind::RotationLock => "Gyroscope".to_string(),
             Kind::Library(index) => settings
                 .libraries
                 .get(*index)
@@ -50,9 +97,24 @@ impl Kind {
             Kind::LibraryName(_) => "Name".to_string(),
             Kind::LibraryPath(_) => "Path".to_string(),
             Kind::LibraryMode(_) => "Mode".to_string(),
+            Kind::LibraryTypographyPreset(_) => "Typography".to_string(),
             Kind::IntermissionSuspend => "Suspend Screen".to_string(),
             Kind::IntermissionPowerOff => "Power Off Screen".to_string(),
             Kind::IntermissionShare => "Share Screen".to_string(),
+            Kind::TapZones => "Tap Zones".to_string(),
+            Kind::SyncEnabled => "Enable Progress Sync".to_string(),
+            Kind::SyncServer => "Server".to_string(),
+            Kind::SyncUsername => "Username".to_string(),
+            Kind::SyncPassword => "Password".to_string(),
+            Kind::SyncAccount => "Account".to_string(),
+            Kind::SyncQueue => "Pending Changes".to_string(),
+            Kind::WebdavEnabled => "Enable WebDAV Sync".to_string(),
+            Kind::WebdavServer => "WebDAV Server".to_string(),
+            Kind::WebdavUsername => "WebDAV Username".to_string(),
+            Kind::WebdavPassword => "WebDAV Password".to_string(),
+            Kind::WebdavSync => "WebDAV Folders".to_string(),
+            Kind::Bluetooth => "Enable Bluetooth".to_string(),
+            Kind::BluetoothDevices => "Page-Turn Remotes".to_string(),
         }
     }
 
@@ -60,17 +122,48 @@ impl Kind {
         match self {
             Kind::KeyboardLayout => ValueKind::KeyboardLayout,
             Kind::SleepCover => ValueKind::Toggle(ToggleSettings::SleepCover),
+            Kind::CoverClose => ValueKind::CoverClose,
+            Kind::CoverOpen => ValueKind::CoverOpen,
             Kind::AutoShare => ValueKind::Toggle(ToggleSettings::AutoShare),
             Kind::AutoSuspend => ValueKind::AutoSuspend,
             Kind::AutoPowerOff => ValueKind::AutoPowerOff,
+            Kind::AutoWifiOff => ValueKind::AutoWifiOff,
+            Kind::Bedtime => ValueKind::Toggle(ToggleSettings::Bedtime),
             Kind::ButtonScheme => ValueKind::Toggle(ToggleSettings::ButtonScheme),
+            Kind::HighContrast => ValueKind::Toggle(ToggleSettings::HighContrast),
+            Kind::UiScale => ValueKind::UiScale,
+            Kind::GestureMap => ValueKind::GestureMap,
+            Kind::Backups => ValueKind::Backups,
+            Kind::FullRefreshInterval => ValueKind::FullRefreshInterval,
+            Kind::FullRefreshOnClose => ValueKind::Toggle(ToggleSettings::FullRefreshOnClose),
+            Kind::DimOverlays => ValueKind::Toggle(ToggleSettings::DimOverlays),
+            Kind::OverlayShadows => ValueKind::Toggle(ToggleSettings::OverlayShadows),
+            Kind::CoverDithering => ValueKind::CoverDithering,
+            Kind::ImageDithering => ValueKind::ImageDithering,
+            Kind::StartupRotation => ValueKind::StartupRotation,
+            Kind::RotationLock => ValueKind::RotationLock,
             Kind::Library(index) => ValueKind::LibraryInfo(*index),
             Kind::LibraryName(index) => ValueKind::LibraryName(*index),
             Kind::LibraryPath(index) => ValueKind::LibraryPath(*index),
             Kind::LibraryMode(index) => ValueKind::LibraryMode(*index),
+            Kind::LibraryTypographyPreset(index) => ValueKind::LibraryTypographyPreset(*index),
             Kind::IntermissionSuspend => ValueKind::IntermissionSuspend,
             Kind::IntermissionPowerOff => ValueKind::IntermissionPowerOff,
             Kind::IntermissionShare => ValueKind::IntermissionShare,
+            Kind::TapZones => ValueKind::TapZones,
+            Kind::SyncEnabled => ValueKind::Toggle(ToggleSettings::Sync),
+            Kind::SyncServer => ValueKind::SyncServer,
+            Kind::SyncUsername => ValueKind::SyncUsername,
+            Kind::SyncPassword => ValueKind::SyncPassword,
+            Kind::SyncAccount => ValueKind::SyncAccount,
+            Kind::SyncQueue => ValueKind::SyncQueue,
+            Kind::WebdavEnabled => ValueKind::Toggle(ToggleSettings::Webdav),
+            Kind::WebdavServer => ValueKind::WebdavServer,
+            Kind::WebdavUsername => ValueKind::WebdavUsername,
+            Kind::WebdavPassword => ValueKind::WebdavPassword,
+            Kind::WebdavSync => ValueKind::WebdavSync,
+            Kind::Bluetooth => ValueKind::Toggle(ToggleSettings::Bluetooth),
+            Kind::BluetoothDevices => ValueKind::BluetoothDevices,
         }
     }
 }
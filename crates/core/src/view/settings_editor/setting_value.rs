@@ -1,16 +1,34 @@
 use super::super::action_label::ActionLabel;
 use super::super::EntryKind;
 use super::super::{Align, Bus, Event, Hub, Id, RenderQueue, View, ID_FEEDER};
+use crate::backup;
 use crate::context::Context;
 use crate::framebuffer::Framebuffer;
 use crate::geom::Rectangle;
-use crate::settings::{ButtonScheme, IntermKind, Settings};
+use crate::settings::{
+    ButtonScheme, CoverCloseAction, CoverOpenAction, DitheringKind, GestureMap, IntermKind,
+    RotationLock, Settings, TapZones,
+};
+use crate::sync_queue;
 use crate::view::toggle::Toggle;
 use crate::view::{EntryId, ToggleEvent};
 use anyhow::Error;
 use std::fs;
 use std::path::Path;
 
+/// Interface scales offered in the settings editor, in percents.
+const UI_SCALES: [u16; 5] = [100, 125, 150, 175, 200];
+
+/// Numbers of partial updates between the full refreshes offered in the settings editor.
+const FULL_REFRESH_INTERVALS: [u16; 5] = [0, 8, 16, 32, 64];
+
+const DITHERING_KINDS: [DitheringKind; 4] = [
+    DitheringKind::None,
+    DitheringKind::Ordered,
+    DitheringKind::FloydSteinberg,
+    DitheringKind::BlueNoise,
+];
+
 #[derive(Debug, Clone)]
 pub enum ToggleSettings {
     /// Sleep cover enable/disable setting
@@ -19,6 +37,22 @@ pub enum ToggleSettings {
     AutoShare,
     /// Button scheme selection (natural or inverted)
     ButtonScheme,
+    /// Reading progress synchronization enable/disable setting
+    Sync,
+    /// High contrast mode enable/disable setting
+    HighContrast,
+    /// Full refresh of the area left by a closed overlay enable/disable setting
+    FullRefreshOnClose,
+    /// Dimming of the screen around the menus and dialogs enable/disable setting
+    DimOverlays,
+    /// Shadows beneath the overlays enable/disable setting
+    OverlayShadows,
+    /// Bluetooth radio and remotes enable/disable setting
+    Bluetooth,
+    /// Bedtime schedule enable/disable setting
+    Bedtime,
+    /// WebDAV synchronization on network connection enable/disable setting
+    Webdav,
 }
 
 /// Represents the type of setting value being displayed.
@@ -29,10 +63,28 @@ pub enum ToggleSettings {
 pub enum Kind {
     /// Keyboard layout selection setting
     KeyboardLayout,
+    /// What closing the sleep cover does
+    CoverClose,
+    /// What opening the sleep cover does
+    CoverOpen,
     /// Auto-suspend timeout setting (in minutes)
     AutoSuspend,
     /// Auto power-off timeout setting (in minutes)
     AutoPowerOff,
+    /// Wi-Fi inactivity timeout setting (in minutes)
+    AutoWifiOff,
+    /// Scale factor of the user interface
+    UiScale,
+    /// Number of partial updates between the full refreshes
+    FullRefreshInterval,
+    /// Dithering of the book covers
+    CoverDithering,
+    /// Dithering of the images opened in the image viewer
+    ImageDithering,
+    /// Orientation of the screen at startup
+    StartupRotation,
+    /// Orientations followed when the gyroscope detects a rotation
+    RotationLock,
 
     /// Generic toggle setting
     Toggle(ToggleSettings),
@@ -45,12 +97,40 @@ pub enum Kind {
     LibraryPath(usize),
     /// Library mode setting (database or filesystem) for the library at the given index
     LibraryMode(usize),
+    /// Typography preset applied to the new books of the library at the given index
+    LibraryTypographyPreset(usize),
     /// Intermission display setting for suspend screen
     IntermissionSuspend,
     /// Intermission display setting for power-off screen
     IntermissionPowerOff,
     /// Intermission display setting for share screen
     IntermissionShare,
+    /// Mapping of the reader's tap zones to actions, edited in a grid
+    TapZones,
+    /// Mapping of the global gestures to actions, edited in a list
+    GestureMap,
+    /// Number of the archives of the application data, with the backup and restore commands
+    Backups,
+    /// Base URL of the sync server
+    SyncServer,
+    /// User name of the sync account
+    SyncUsername,
+    /// Password of the sync account, only shown as set or not
+    SyncPassword,
+    /// Registration and login commands of the sync account
+    SyncAccount,
+    /// Number of the sync operations queued while offline
+    SyncQueue,
+    /// URL of the WebDAV server
+    WebdavServer,
+    /// User name of the WebDAV account
+    WebdavUsername,
+    /// Password of the WebDAV account, only shown as set or not
+    WebdavPassword,
+    /// Sync and log commands of the WebDAV folders
+    WebdavSync,
+    /// Pairing of the Bluetooth remotes, managed in a list
+    BluetoothDevices,
 }
 
 impl Kind {
@@ -139,7 +219,15 @@ impl SettingValue {
                     fonts,
                     Align::Right(10),
                 )),
-                ToggleSettings::SleepCover => Box::new(Toggle::new(
+                ToggleSettings::SleepCover
+                | ToggleSettings::Sync
+                | ToggleSettings::HighContrast
+                | ToggleSettings::FullRefreshOnClose
+                | ToggleSettings::DimOverlays
+                | ToggleSettings::OverlayShadows
+                | ToggleSettings::Bluetooth
+                | ToggleSettings::Bedtime
+                | ToggleSettings::Webdav => Box::new(Toggle::new(
                     self.rect,
                     "on",
                     "off",
@@ -177,12 +265,30 @@ impl SettingValue {
     ) -> (String, Vec<EntryKind>, Option<bool>) {
         match kind {
             Kind::KeyboardLayout => Self::fetch_keyboard_layout_data(settings),
+            Kind::CoverClose => Self::fetch_cover_close_data(settings),
+            Kind::CoverOpen => Self::fetch_cover_open_data(settings),
             Kind::AutoSuspend => Self::fetch_auto_suspend_data(settings),
             Kind::AutoPowerOff => Self::fetch_auto_power_off_data(settings),
+            Kind::AutoWifiOff => Self::fetch_auto_wifi_off_data(settings),
+            Kind::UiScale => Self::fetch_ui_scale_data(settings),
+            Kind::FullRefreshInterval => Self::fetch_full_refresh_interval_data(settings),
+            Kind::CoverDithering => Self::fetch_dithering_data(
+                settings.display.cover_dithering,
+                EntryId::SetCoverDithering,
+            ),
+            Kind::ImageDithering => Self::fetch_dithering_data(
+                settings.display.image_dithering,
+                EntryId::SetImageDithering,
+            ),
+            Kind::StartupRotation => Self::fetch_startup_rotation_data(settings),
+            Kind::RotationLock => Self::fetch_rotation_lock_data(settings),
             Kind::LibraryInfo(index) => Self::fetch_library_info_data(*index, settings),
             Kind::LibraryName(index) => Self::fetch_library_name_data(*index, settings),
             Kind::LibraryPath(index) => Self::fetch_library_path_data(*index, settings),
             Kind::LibraryMode(index) => Self::fetch_library_mode_data(*index, settings),
+            Kind::LibraryTypographyPreset(index) => {
+                Self::fetch_library_typography_preset_data(*index, settings)
+            }
             Kind::IntermissionSuspend => {
                 Self::fetch_intermission_data(crate::settings::IntermKind::Suspend, settings)
             }
@@ -192,10 +298,37 @@ impl SettingValue {
             Kind::IntermissionShare => {
                 Self::fetch_intermission_data(crate::settings::IntermKind::Share, settings)
             }
+            Kind::TapZones => Self::fetch_tap_zones_data(settings),
+            Kind::GestureMap => Self::fetch_gesture_map_data(settings),
+            Kind::Backups => Self::fetch_backups_data(),
+            Kind::SyncServer => Self::fetch_text_data(&settings.sync.server),
+            Kind::SyncUsername => Self::fetch_text_data(&settings.sync.username),
+            Kind::SyncPassword => Self::fetch_sync_password_data(settings),
+            Kind::SyncAccount => Self::fetch_sync_account_data(),
+            Kind::SyncQueue => Self::fetch_sync_queue_data(),
+            Kind::WebdavServer => Self::fetch_text_data(&settings.webdav.server),
+            Kind::WebdavUsername => Self::fetch_text_data(&settings.webdav.username),
+            Kind::WebdavPassword => Self::fetch_webdav_password_data(settings),
+            Kind::WebdavSync => Self::fetch_webdav_sync_data(settings),
+            Kind::BluetoothDevices => ("Manage".to_string(), vec![], None),
             Kind::Toggle(toggle) => match toggle {
                 ToggleSettings::SleepCover => Self::fetch_sleep_cover_data(settings),
                 ToggleSettings::AutoShare => Self::fetch_auto_share_data(settings),
                 ToggleSettings::ButtonScheme => Self::fetch_button_scheme_data(settings),
+                ToggleSettings::Sync => Self::fetch_sync_data(settings),
+                ToggleSettings::HighContrast => Self::fetch_high_contrast_data(settings),
+                ToggleSettings::FullRefreshOnClose => {
+                    Self::fetch_full_refresh_on_close_data(settings)
+                }
+                ToggleSettings::DimOverlays => {
+                    Self::fetch_enabled_data(settings.display.dim_overlays)
+                }
+                ToggleSettings::OverlayShadows => {
+                    Self::fetch_enabled_data(settings.display.overlay_shadows)
+                }
+                ToggleSettings::Bluetooth => Self::fetch_enabled_data(settings.bluetooth),
+                ToggleSettings::Bedtime => Self::fetch_enabled_data(settings.bedtime.enabled),
+                ToggleSettings::Webdav => Self::fetch_enabled_data(settings.webdav.enabled),
             },
         }
     }
@@ -271,6 +404,288 @@ impl SettingValue {
         (value, vec![], None)
     }
 
+    fn fetch_auto_wifi_off_data(settings: &Settings) -> (String, Vec<EntryKind>, Option<bool>) {
+        let value = if settings.auto_wifi_off == 0.0 {
+            "Never".to_string()
+        } else {
+            format!("{:.1}", settings.auto_wifi_off)
+        };
+
+        (value, vec![], None)
+    }
+
+    fn fetch_ui_scale_data(settings: &Settings) -> (String, Vec<EntryKind>, Option<bool>) {
+        let current = (settings.ui_scale * 100.0).round() as u16;
+
+        let entries = UI_SCALES
+            .iter()
+            .map(|&percent| {
+                EntryKind::RadioButton(
+                    format!("{}%", percent),
+                    EntryId::SetUiScale(percent),
+                    percent == current,
+                )
+            })
+            .collect();
+
+        (format!("{}%", current), entries, None)
+    }
+
+    fn fetch_full_refresh_interval_data(
+        settings: &Settings,
+    ) -> (String, Vec<EntryKind>, Option<bool>) {
+        let current = settings.display.full_refresh_interval;
+        let label = |interval: u16| {
+            if interval == 0 {
+                "Never".to_string()
+            } else {
+                format!("{} updates", interval)
+            }
+        };
+
+        let entries = FULL_REFRESH_INTERVALS
+            .iter()
+            .map(|&interval| {
+                EntryKind::RadioButton(
+                    label(interval),
+                    EntryId::SetFullRefreshInterval(interval),
+                    interval == current,
+                )
+            })
+            .collect();
+
+        (label(current), entries, None)
+    }
+
+    fn fetch_dithering_data(
+        current: DitheringKind,
+        entry_id: fn(DitheringKind) -> EntryId,
+    ) -> (String, Vec<EntryKind>, Option<bool>) {
+        let entries = DITHERING_KINDS
+            .iter()
+            .map(|&kind| EntryKind::RadioButton(kind.to_string(), entry_id(kind), kind == current))
+            .collect();
+
+        (current.to_string(), entries, None)
+    }
+
+    fn fetch_cover_close_data(settings: &Settings) -> (String, Vec<EntryKind>, Option<bool>) {
+        let current = settings.sleep_cover_actions.close;
+        let entries = [
+            CoverCloseAction::Suspend,
+            CoverCloseAction::Lock,
+            CoverCloseAction::Ignore,
+        ]
+        .iter()
+        .map(|&action| {
+            EntryKind::RadioButton(
+                action.to_string(),
+                EntryId::SetCoverCloseAction(action),
+                action == current,
+            )
+        })
+        .collect();
+
+        (current.to_string(), entries, None)
+    }
+
+    fn fetch_cover_open_data(settings: &Settings) -> (String, Vec<EntryKind>, Option<bool>) {
+        let current = settings.sleep_cover_actions.open;
+        let entries = [CoverOpenAction::Wake, CoverOpenAction::Ignore]
+            .iter()
+            .map(|&action| {
+                EntryKind::RadioButton(
+                    action.to_string(),
+                    EntryId::SetCoverOpenAction(action),
+                    action == current,
+                )
+            })
+            .collect();
+
+        (current.to_string(), entries, None)
+    }
+
+    fn fetch_startup_rotation_data(settings: &Settings) -> (String, Vec<EntryKind>, Option<bool>) {
+        let current = settings.display.startup_rotation.rem_euclid(4);
+        let label = |rotation: i8| format!("{}°", rotation as i16 * 90);
+
+        let entries = (0..4)
+            .map(|rotation| {
+                EntryKind::RadioButton(
+                    label(rotation),
+                    EntryId::SetStartupRotation(rotation),
+                    rotation == current,
+                )
+            })
+            .collect();
+
+        (label(current), entries, None)
+    }
+
+    fn fetch_rotation_lock_data(settings: &Settings) -> (String, Vec<EntryKind>, Option<bool>) {
+        let current = settings.rotation_lock;
+        let label = |lock: Option<RotationLock>| match lock {
+            None => "Auto".to_string(),
+            Some(RotationLock::Portrait) => "Portrait".to_string(),
+            Some(RotationLock::Landscape) => "Landscape".to_string(),
+            Some(RotationLock::Current) => "Ignore".to_string(),
+        };
+
+        let entries = [
+            None,
+            Some(RotationLock::Portrait),
+            Some(RotationLock::Landscape),
+            Some(RotationLock::Current),
+        ]
+        .into_iter()
+        .map(|lock| {
+            EntryKind::RadioButton(label(lock), EntryId::SetRotationLock(lock), lock == current)
+        })
+        .collect();
+
+        (label(current), entries, None)
+    }
+
+    fn fetch_full_refresh_on_close_data(
+        settings: &Settings,
+    ) -> (String, Vec<EntryKind>, Option<bool>) {
+        Self::fetch_enabled_data(settings.display.full_refresh_on_close)
+    }
+
+    fn fetch_enabled_data(enabled: bool) -> (String, Vec<EntryKind>, Option<bool>) {
+        let value = if enabled {
+            "Enabled".to_string()
+        } else {
+            "Disabled".to_string()
+        };
+
+        (value, vec![], Some(enabled))
+    }
+
+    fn fetch_sync_data(settings: &Settings) -> (String, Vec<EntryKind>, Option<bool>) {
+        let value = if settings.sync.enabled {
+            "Enabled".to_string()
+        } else {
+            "Disabled".to_string()
+        };
+
+        (value, vec![], Some(settings.sync.enabled))
+    }
+
+    fn fetch_high_contrast_data(settings: &Settings) -> (String, Vec<EntryKind>, Option<bool>) {
+        let value = if settings.high_contrast {
+            "Enabled".to_string()
+        } else {
+            "Disabled".to_string()
+        };
+
+        (value, vec![], Some(settings.high_contrast))
+    }
+
+    fn fetch_text_data(text: &str) -> (String, Vec<EntryKind>, Option<bool>) {
+        let value = if text.is_empty() {
+            "Not set".to_string()
+        } else {
+            text.to_string()
+        };
+
+        (value, vec![], None)
+    }
+
+    fn fetch_sync_password_data(settings: &Settings) -> (String, Vec<EntryKind>, Option<bool>) {
+        let value = if settings.sync.userkey.is_some() {
+            "Set".to_string()
+        } else {
+            "Not set".to_string()
+        };
+
+        (value, vec![], None)
+    }
+
+    fn fetch_sync_account_data() -> (String, Vec<EntryKind>, Option<bool>) {
+        let entries = vec![
+            EntryKind::Command("Register".to_string(), EntryId::RegisterSyncAccount),
+            EntryKind::Command("Log In".to_string(), EntryId::AuthorizeSyncAccount),
+        ];
+
+        ("Register or Log In".to_string(), entries, None)
+    }
+
+    fn fetch_sync_queue_data() -> (String, Vec<EntryKind>, Option<bool>) {
+        let entries = vec![EntryKind::Command(
+            "Sync Now".to_string(),
+            EntryId::FlushSyncQueue,
+        )];
+
+        let value = match sync_queue::pending_count() {
+            0 => "None".to_string(),
+            1 => "1 change".to_string(),
+            count => format!("{} changes", count),
+        };
+
+        (value, entries, None)
+    }
+
+    fn fetch_backups_data() -> (String, Vec<EntryKind>, Option<bool>) {
+        let entries = vec![
+            EntryKind::Command("Back Up Now".to_string(), EntryId::CreateBackup),
+            EntryKind::Command("Restore".to_string(), EntryId::ShowBackups),
+        ];
+
+        let value = match backup::archives().len() {
+            0 => "None".to_string(),
+            1 => "1 archive".to_string(),
+            count => format!("{} archives", count),
+        };
+
+        (value, entries, None)
+    }
+
+    fn fetch_webdav_password_data(settings: &Settings) -> (String, Vec<EntryKind>, Option<bool>) {
+        let value = if settings.webdav.password.is_some() {
+            "Set".to_string()
+        } else {
+            "Not set".to_string()
+        };
+
+        (value, vec![], None)
+    }
+
+    fn fetch_webdav_sync_data(settings: &Settings) -> (String, Vec<EntryKind>, Option<bool>) {
+        let entries = vec![
+            EntryKind::Command("Sync Now".to_string(), EntryId::SyncWebdav),
+            EntryKind::Command("Show Log".to_string(), EntryId::ShowWebdavLog),
+        ];
+
+        let value = match settings.webdav.folders.len() {
+            0 => "None".to_string(),
+            1 => "1 folder".to_string(),
+            count => format!("{} folders", count),
+        };
+
+        (value, entries, None)
+    }
+
+    fn fetch_tap_zones_data(settings: &Settings) -> (String, Vec<EntryKind>, Option<bool>) {
+        let value = if settings.reader.tap_zones == TapZones::default() {
+            "Default".to_string()
+        } else {
+            "Custom".to_string()
+        };
+
+        (value, vec![], None)
+    }
+
+    fn fetch_gesture_map_data(settings: &Settings) -> (String, Vec<EntryKind>, Option<bool>) {
+        let value = if settings.gestures == GestureMap::default() {
+            "Default".to_string()
+        } else {
+            "Custom".to_string()
+        };
+
+        (value, vec![], None)
+    }
+
     fn fetch_library_info_data(
         index: usize,
         settings: &Settings,
@@ -332,6 +747,31 @@ impl SettingValue {
         (mode.to_string(), entries, None)
     }
 
+    fn fetch_library_typography_preset_data(
+        index: usize,
+        settings: &Settings,
+    ) -> (String, Vec<EntryKind>, Option<bool>) {
+        let current = settings
+            .libraries
+            .get(index)
+            .and_then(|library| library.typography_preset.clone());
+
+        let mut entries = vec![EntryKind::RadioButton(
+            "None".to_string(),
+            EntryId::SetLibraryTypographyPreset(None),
+            current.is_none(),
+        )];
+        entries.extend(settings.reader.typography_presets.iter().map(|preset| {
+            EntryKind::RadioButton(
+                preset.name.clone(),
+                EntryId::SetLibraryTypographyPreset(Some(preset.name.clone())),
+                current.as_ref() == Some(&preset.name),
+            )
+        }));
+
+        (current.unwrap_or_else(|| "None".to_string()), entries, None)
+    }
+
     fn get_available_layouts() -> Result<Vec<String>, Error> {
         let layouts_dir = Path::new("keyboard-layouts");
         let mut layouts = Vec::new();
@@ -372,9 +812,11 @@ impl SettingValue {
 
         let display = &settings.intermissions[kind];
 
+        let is_slideshow = matches!(display, IntermissionDisplay::Slideshow(_));
         let (value, is_logo, is_cover) = match display {
             IntermissionDisplay::Logo => ("Logo".to_string(), true, false),
             IntermissionDisplay::Cover => ("Cover".to_string(), false, true),
+            IntermissionDisplay::Slideshow(_) => ("Slideshow".to_string(), false, false),
             IntermissionDisplay::Image(path) => {
                 let display_name = path
                     .file_name()
@@ -396,6 +838,11 @@ impl SettingValue {
                 EntryId::SetIntermission(kind, IntermissionDisplay::Cover),
                 is_cover,
             ),
+            EntryKind::RadioButton(
+                "Slideshow".to_string(),
+                EntryId::SetIntermission(kind, IntermissionDisplay::Slideshow(None)),
+                is_slideshow,
+            ),
             EntryKind::Command(
                 "Custom Image...".to_string(),
                 EntryId::EditIntermissionImage(kind),
@@ -426,9 +873,14 @@ impl SettingValue {
     /// to ensure the ActionLabel always has the correct tap behavior.
     ///
     /// The behavior varies by setting type:
-    /// - **Direct edit settings** (LibraryInfo, LibraryName, LibraryPath, AutoSuspend, AutoPowerOff):
-    ///   Return specific edit events that trigger their corresponding input dialogs.
-    /// - **Settings with multiple options** (KeyboardLayout, SleepCover, AutoShare, ButtonScheme, LibraryMode, Intermission*):
+    /// - **Direct edit settings** (LibraryInfo, LibraryName, LibraryPath, AutoSuspend, AutoPowerOff, AutoWifiOff,
+    ///   TapZones, GestureMap, SyncServer, SyncUsername, SyncPassword, WebdavServer, WebdavUsername,
+    ///   WebdavPassword, BluetoothDevices): Return specific edit events that trigger
+    ///   their corresponding editors.
+    /// - **Settings with multiple options** (KeyboardLayout, SleepCover, CoverClose, CoverOpen, AutoShare, ButtonScheme, UiScale,
+    ///   FullRefreshInterval, CoverDithering, ImageDithering, StartupRotation,
+    ///   RotationLock, LibraryMode, LibraryTypographyPreset, Intermission*, SyncAccount, SyncQueue,
+    ///   WebdavSync, Backups):
     ///   Return a SubMenu event that displays all available entries as radio buttons or checkboxes.
     ///
     /// # Returns
@@ -446,6 +898,16 @@ impl SettingValue {
             Kind::LibraryPath(_) => Some(Event::Select(EntryId::EditLibraryPath)),
             Kind::AutoSuspend => Some(Event::Select(EntryId::EditAutoSuspend)),
             Kind::AutoPowerOff => Some(Event::Select(EntryId::EditAutoPowerOff)),
+            Kind::AutoWifiOff => Some(Event::Select(EntryId::EditAutoWifiOff)),
+            Kind::TapZones => Some(Event::Select(EntryId::EditTapZones)),
+            Kind::GestureMap => Some(Event::Select(EntryId::EditGestureMap)),
+            Kind::SyncServer => Some(Event::Select(EntryId::EditSyncServer)),
+            Kind::SyncUsername => Some(Event::Select(EntryId::EditSyncUsername)),
+            Kind::SyncPassword => Some(Event::Select(EntryId::EditSyncPassword)),
+            Kind::WebdavServer => Some(Event::Select(EntryId::EditWebdavServer)),
+            Kind::WebdavUsername => Some(Event::Select(EntryId::EditWebdavUsername)),
+            Kind::WebdavPassword => Some(Event::Select(EntryId::EditWebdavPassword)),
+            Kind::BluetoothDevices => Some(Event::Select(EntryId::EditBluetoothDevices)),
             Kind::Toggle(ref toggle) => {
                 Some(Event::NewToggle(ToggleEvent::Setting(toggle.clone())))
             }
@@ -472,6 +934,10 @@ impl View for SettingValue {
     fn render(&self, _fb: &mut dyn Framebuffer, _rect: Rectangle, _fonts: &mut crate::font::Fonts) {
     }
 
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
     fn rect(&self) -> &Rectangle {
         &self.rect
     }
@@ -612,6 +1078,102 @@ mod tests {
         assert!(!rq.is_empty());
     }
 
+    #[test]
+    fn test_ui_scale_select_updates_value() {
+        let mut context = create_test_context();
+        let settings = Settings::default();
+        let rect = rect![0, 0, 200, 50];
+
+        let mut value = SettingValue::new(Kind::UiScale, rect, &settings, &mut context.fonts);
+        let mut rq = RenderQueue::new();
+
+        assert_eq!(value.value(), "100%");
+
+        context.settings.ui_scale = 1.5;
+        value.refresh_from_context(&context, &mut rq);
+
+        assert_eq!(value.value(), "150%");
+        assert!(value.entries.iter().any(|entry| matches!(
+            entry,
+            EntryKind::RadioButton(_, EntryId::SetUiScale(150), true)
+        )));
+    }
+
+    #[test]
+    fn test_full_refresh_interval_select_updates_value() {
+        let mut context = create_test_context();
+        let settings = Settings::default();
+        let rect = rect![0, 0, 200, 50];
+
+        let mut value = SettingValue::new(
+            Kind::FullRefreshInterval,
+            rect,
+            &settings,
+            &mut context.fonts,
+        );
+        let mut rq = RenderQueue::new();
+
+        assert_eq!(value.value(), "Never");
+
+        context.settings.display.full_refresh_interval = 16;
+        value.refresh_from_context(&context, &mut rq);
+
+        assert_eq!(value.value(), "16 updates");
+        assert!(value.entries.iter().any(|entry| matches!(
+            entry,
+            EntryKind::RadioButton(_, EntryId::SetFullRefreshInterval(16), true)
+        )));
+    }
+
+    #[test]
+    fn test_dithering_select_updates_value() {
+        let mut context = create_test_context();
+        let settings = Settings::default();
+        let rect = rect![0, 0, 200, 50];
+
+        let mut value =
+            SettingValue::new(Kind::ImageDithering, rect, &settings, &mut context.fonts);
+        let mut rq = RenderQueue::new();
+
+        assert_eq!(value.value(), "None");
+
+        context.settings.display.image_dithering = DitheringKind::FloydSteinberg;
+        value.refresh_from_context(&context, &mut rq);
+
+        assert_eq!(value.value(), "Floyd–Steinberg");
+        assert!(value.entries.iter().any(|entry| matches!(
+            entry,
+            EntryKind::RadioButton(
+                _,
+                EntryId::SetImageDithering(DitheringKind::FloydSteinberg),
+                true
+            )
+        )));
+    }
+
+    #[test]
+    fn test_startup_rotation_select_updates_value() {
+        let mut context = create_test_context();
+        let settings = Settings::default();
+        let rect = rect![0, 0, 200, 50];
+
+        let mut value =
+            SettingValue::new(Kind::StartupRotation, rect, &settings, &mut context.fonts);
+        let mut rq = RenderQueue::new();
+
+        assert_eq!(value.value(), "0°");
+
+        context.settings.display.startup_rotation = 2;
+        value.refresh_from_context(&context, &mut rq);
+
+        assert_eq!(value.value(), "180°");
+        assert_eq!(value.entries.len(), 4);
+        assert!(value.entries.iter().any(|entry| matches!(
+            entry,
+            EntryKind::RadioButton(_, EntryId::SetStartupRotation(2), true)
+        )));
+    }
+
     #[test]
     fn test_library_mode_select_updates_value() {
         use crate::settings::{LibraryMode, LibrarySettings};
@@ -672,6 +1234,23 @@ mod tests {
         assert!(!rq.is_empty());
     }
 
+    #[test]
+    fn test_auto_wifi_off_submit_updates_value() {
+        let mut context = create_test_context();
+        let settings = Settings::default();
+        let rect = rect![0, 0, 200, 50];
+
+        let mut value = SettingValue::new(Kind::AutoWifiOff, rect, &settings, &mut context.fonts);
+        let mut rq = RenderQueue::new();
+        assert_eq!(value.value(), "Never");
+
+        context.settings.auto_wifi_off = 10.0;
+        value.refresh_from_context(&context, &mut rq);
+
+        assert_eq!(value.value(), "10.0");
+        assert!(!rq.is_empty());
+    }
+
     #[test]
     fn test_library_name_submit_updates_value() {
         use crate::settings::LibrarySettings;
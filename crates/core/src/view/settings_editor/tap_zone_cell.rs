@@ -0,0 +1,160 @@
+use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::font::{font_from_style, Fonts, NORMAL_STYLE};
+use crate::framebuffer::{Framebuffer, UpdateMode};
+use crate::geom::{BorderSpec, Rectangle};
+use crate::gesture::GestureEvent;
+use crate::input::{DeviceEvent, FingerStatus};
+use crate::settings::{TapAction, TapZone};
+use crate::theme;
+use crate::unit::scale_thickness;
+use crate::view::{
+    Bus, EntryId, EntryKind, Event, Hub, Id, RenderData, RenderQueue, View, ID_FEEDER,
+    THICKNESS_SMALL,
+};
+
+/// One cell of the tap zones grid.
+///
+/// Displays the name of a reader [`TapZone`] above the [`TapAction`] it's mapped to.
+/// Tapping the cell opens a menu listing every available action.
+pub struct TapZoneCell {
+    id: Id,
+    rect: Rectangle,
+    children: Vec<Box<dyn View>>,
+    zone: TapZone,
+    action: TapAction,
+    active: bool,
+}
+
+impl TapZoneCell {
+    pub fn new(rect: Rectangle, zone: TapZone, action: TapAction) -> TapZoneCell {
+        TapZoneCell {
+            id: ID_FEEDER.next(),
+            rect,
+            children: Vec::new(),
+            zone,
+            action,
+            active: false,
+        }
+    }
+
+    pub fn zone(&self) -> TapZone {
+        self.zone
+    }
+
+    #[cfg(test)]
+    pub fn action(&self) -> TapAction {
+        self.action
+    }
+
+    pub fn update(&mut self, action: TapAction, rq: &mut RenderQueue) {
+        if self.action != action {
+            self.action = action;
+            rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+        }
+    }
+
+    fn entries(&self) -> Vec<EntryKind> {
+        TapAction::all()
+            .iter()
+            .map(|action| {
+                EntryKind::RadioButton(
+                    action.to_string(),
+                    EntryId::SetTapAction(self.zone, *action),
+                    *action == self.action,
+                )
+            })
+            .collect()
+    }
+}
+
+impl View for TapZoneCell {
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _hub, bus, rq, _context), fields(event = ?evt), ret(level=tracing::Level::TRACE)))]
+    fn handle_event(
+        &mut self,
+        evt: &Event,
+        _hub: &Hub,
+        bus: &mut Bus,
+        rq: &mut RenderQueue,
+        _context: &mut Context,
+    ) -> bool {
+        match *evt {
+            Event::Device(DeviceEvent::Finger {
+                status, position, ..
+            }) => match status {
+                FingerStatus::Down if self.rect.includes(position) => {
+                    self.active = true;
+                    rq.add(RenderData::new(self.id, self.rect, UpdateMode::Fast));
+                    true
+                }
+                FingerStatus::Up if self.active => {
+                    self.active = false;
+                    rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+                    true
+                }
+                _ => false,
+            },
+            Event::Gesture(GestureEvent::Tap(center)) if self.rect.includes(center) => {
+                bus.push_back(Event::SubMenu(self.rect, self.entries()));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, fb, fonts), fields(rect = ?_rect)))]
+    fn render(&self, fb: &mut dyn Framebuffer, _rect: Rectangle, fonts: &mut Fonts) {
+        let dpi = CURRENT_DEVICE.dpi;
+        let scheme = if self.active {
+            theme::current().text_inverted_hard
+        } else {
+            theme::current().text_normal
+        };
+
+        fb.draw_rectangle(&self.rect, scheme[0]);
+        fb.draw_rectangle_outline(
+            &self.rect,
+            &BorderSpec {
+                thickness: scale_thickness(THICKNESS_SMALL, dpi) as u16,
+                color: theme::current().foreground,
+            },
+        );
+
+        let font = font_from_style(fonts, &NORMAL_STYLE, dpi);
+        let x_height = font.x_heights.0 as i32;
+        let padding = font.em() as i32;
+        let max_width = self.rect.width() as i32 - padding;
+        let center_y = self.rect.min.y + self.rect.height() as i32 / 2;
+
+        let zone_plan = font.plan(self.zone.label(), Some(max_width), None);
+        let dx = (self.rect.width() as i32 - zone_plan.width) / 2;
+        let pt = pt!(self.rect.min.x + dx, center_y - x_height);
+        let zone_color = if self.active { scheme[1] } else { scheme[2] };
+        font.render(fb, zone_color, &zone_plan, pt);
+
+        let action_plan = font.plan(self.action.to_string(), Some(max_width), None);
+        let dx = (self.rect.width() as i32 - action_plan.width) / 2;
+        let pt = pt!(self.rect.min.x + dx, center_y + 2 * x_height);
+        font.render(fb, scheme[1], &action_plan, pt);
+    }
+
+    fn rect(&self) -> &Rectangle {
+        &self.rect
+    }
+
+    fn rect_mut(&mut self) -> &mut Rectangle {
+        &mut self.rect
+    }
+
+    fn children(&self) -> &Vec<Box<dyn View>> {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<Box<dyn View>> {
+        &mut self.children
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+}
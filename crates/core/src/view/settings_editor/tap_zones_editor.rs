@@ -0,0 +1,306 @@
+use super::bottom_bar::{BottomBarVariant, SettingsEditorBottomBar};
+use super::tap_zone_cell::TapZoneCell;
+use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::font::Fonts;
+use crate::framebuffer::{Framebuffer, UpdateMode};
+use crate::geom::{halves, Rectangle};
+use crate::gesture::GestureEvent;
+use crate::settings::{TapAction, TapZone};
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
+use crate::view::common::locate_by_id;
+use crate::view::filler::Filler;
+use crate::view::menu::{Menu, MenuKind};
+use crate::view::{Bus, EntryId, EntryKind, Event, Hub, Id, RenderData, RenderQueue, View};
+use crate::view::{ViewId, ID_FEEDER, SMALL_BAR_HEIGHT, THICKNESS_MEDIUM};
+
+/// A grid editor mapping the reader's tap zones to actions.
+///
+/// The screen is split into a 3×3 grid of [`TapZoneCell`]s mirroring the layout of the
+/// reader's touch regions. Tapping a cell opens a menu of [`TapAction`]s, and the selected
+/// action is written to `context.settings.reader.tap_zones` right away.
+///
+/// # Fields
+///
+/// * `id` - Unique identifier for this view
+/// * `rect` - The rectangular area occupied by this editor
+/// * `children` - Background, the nine cells, the bottom separator and bar, plus the
+///   optional action menu
+/// * `first_cell_index` - Index of the first cell in the children vector
+pub struct TapZonesEditor {
+    id: Id,
+    rect: Rectangle,
+    children: Vec<Box<dyn View>>,
+    first_cell_index: usize,
+}
+
+impl TapZonesEditor {
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(rq, context)))]
+    pub fn new(rect: Rectangle, rq: &mut RenderQueue, context: &mut Context) -> TapZonesEditor {
+        let id = ID_FEEDER.next();
+        let mut children = Vec::new();
+
+        let dpi = CURRENT_DEVICE.dpi;
+        let bar_height = scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32;
+        let separator_thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
+        let (separator_top_half, separator_bottom_half) = halves(separator_thickness);
+
+        children.push(Box::new(Filler::new(rect, theme::current().background)) as Box<dyn View>);
+
+        let grid_rect = rect![
+            rect.min.x,
+            rect.min.y,
+            rect.max.x,
+            rect.max.y - bar_height - separator_top_half
+        ];
+
+        let first_cell_index = children.len();
+        let tap_zones = &context.settings.reader.tap_zones;
+
+        for (index, zone) in TapZone::all().iter().enumerate() {
+            let cell = TapZoneCell::new(Self::cell_rect(grid_rect, index), *zone, tap_zones[*zone]);
+            children.push(Box::new(cell) as Box<dyn View>);
+        }
+
+        children.push(Box::new(Filler::new(
+            rect![
+                rect.min.x,
+                rect.max.y - bar_height - separator_top_half,
+                rect.max.x,
+                rect.max.y - bar_height + separator_bottom_half
+            ],
+            theme::current().foreground,
+        )) as Box<dyn View>);
+
+        children.push(Box::new(SettingsEditorBottomBar::new(
+            rect![
+                rect.min.x,
+                rect.max.y - bar_height + separator_bottom_half,
+                rect.max.x,
+                rect.max.y
+            ],
+            BottomBarVariant::SingleButton {
+                event: Event::Close(ViewId::TapZonesEditor),
+                icon: "close",
+            },
+        )) as Box<dyn View>);
+
+        rq.add(RenderData::new(id, rect, UpdateMode::Gui));
+
+        TapZonesEditor {
+            id,
+            rect,
+            children,
+            first_cell_index,
+        }
+    }
+
+    // The last row and column absorb the remainder of the division so the grid
+    // always covers the whole area.
+    fn cell_rect(grid_rect: Rectangle, index: usize) -> Rectangle {
+        let (row, column) = ((index / 3) as i32, (index % 3) as i32);
+        let width = grid_rect.width() as i32 / 3;
+        let height = grid_rect.height() as i32 / 3;
+        let min_x = grid_rect.min.x + column * width;
+        let min_y = grid_rect.min.y + row * height;
+        let max_x = if column == 2 {
+            grid_rect.max.x
+        } else {
+            min_x + width
+        };
+        let max_y = if row == 2 {
+            grid_rect.max.y
+        } else {
+            min_y + height
+        };
+
+        rect![min_x, min_y, max_x, max_y]
+    }
+
+    fn cell_mut(&mut self, zone: TapZone) -> Option<&mut TapZoneCell> {
+        self.children[self.first_cell_index..self.first_cell_index + 9]
+            .iter_mut()
+            .filter_map(|child| child.downcast_mut::<TapZoneCell>())
+            .find(|cell| cell.zone() == zone)
+    }
+
+    #[inline]
+    fn handle_set_tap_action(
+        &mut self,
+        zone: TapZone,
+        action: TapAction,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        context.settings.reader.tap_zones[zone] = action;
+
+        if let Some(cell) = self.cell_mut(zone) {
+            cell.update(action, rq);
+        }
+
+        true
+    }
+
+    #[inline]
+    fn handle_submenu_event(
+        &mut self,
+        rect: Rectangle,
+        entries: &[EntryKind],
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        let menu = Menu::new(
+            rect,
+            ViewId::SettingsValueMenu,
+            MenuKind::Contextual,
+            entries.to_vec(),
+            context,
+        );
+        rq.add(RenderData::new(menu.id(), *menu.rect(), UpdateMode::Gui));
+        self.children.push(Box::new(menu));
+        true
+    }
+
+    #[inline]
+    fn handle_close_event(&mut self, view_id: ViewId, rq: &mut RenderQueue) -> bool {
+        match view_id {
+            ViewId::SettingsValueMenu => {
+                if let Some(index) = locate_by_id(self, ViewId::SettingsValueMenu) {
+                    self.children.remove(index);
+                    rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl View for TapZonesEditor {
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _hub, _bus, rq, context), fields(event = ?evt), ret(level=tracing::Level::TRACE)))]
+    fn handle_event(
+        &mut self,
+        evt: &Event,
+        _hub: &Hub,
+        _bus: &mut Bus,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        match *evt {
+            Event::Select(EntryId::SetTapAction(zone, action)) => {
+                self.handle_set_tap_action(zone, action, rq, context)
+            }
+            Event::SubMenu(rect, ref entries) => {
+                self.handle_submenu_event(rect, entries, rq, context)
+            }
+            Event::Close(view_id) => self.handle_close_event(view_id, rq),
+            // The editor covers the category editor, nothing beneath it should react.
+            Event::Gesture(GestureEvent::Tap(center))
+            | Event::Gesture(GestureEvent::HoldFingerShort(center, _)) => {
+                self.rect.includes(center)
+            }
+            _ => false,
+        }
+    }
+
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _fb, _fonts), fields(rect = ?_rect)))]
+    fn render(&self, _fb: &mut dyn Framebuffer, _rect: Rectangle, _fonts: &mut Fonts) {}
+
+    fn rect(&self) -> &Rectangle {
+        &self.rect
+    }
+
+    fn rect_mut(&mut self) -> &mut Rectangle {
+        &mut self.rect
+    }
+
+    fn children(&self) -> &Vec<Box<dyn View>> {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<Box<dyn View>> {
+        &mut self.children
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn view_id(&self) -> Option<ViewId> {
+        Some(ViewId::TapZonesEditor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_helpers::create_test_context;
+    use crate::geom::Point;
+    use std::collections::VecDeque;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_grid_covers_all_zones() {
+        let mut context = create_test_context();
+        let mut rq = RenderQueue::new();
+        let editor = TapZonesEditor::new(rect![0, 0, 600, 800], &mut rq, &mut context);
+
+        let zones: Vec<TapZone> = editor.children
+            [editor.first_cell_index..editor.first_cell_index + 9]
+            .iter()
+            .filter_map(|child| child.downcast_ref::<TapZoneCell>())
+            .map(|cell| cell.zone())
+            .collect();
+
+        assert_eq!(zones, TapZone::all().to_vec());
+    }
+
+    #[test]
+    fn test_tap_on_cell_opens_action_menu() {
+        let mut context = create_test_context();
+        let mut rq = RenderQueue::new();
+        let mut editor = TapZonesEditor::new(rect![0, 0, 600, 800], &mut rq, &mut context);
+        let initial_children_count = editor.children.len();
+        let (hub, _receiver) = channel();
+        let mut bus = VecDeque::new();
+
+        let center = *editor.children[editor.first_cell_index + 4].rect();
+        let event = Event::Gesture(GestureEvent::Tap(Point::new(
+            center.min.x + center.width() as i32 / 2,
+            center.min.y + center.height() as i32 / 2,
+        )));
+
+        let handled =
+            crate::view::handle_event(&mut editor, &event, &hub, &mut bus, &mut rq, &mut context);
+
+        assert!(handled);
+        assert_eq!(editor.children.len(), initial_children_count + 1);
+        assert!(locate_by_id(&editor, ViewId::SettingsValueMenu).is_some());
+    }
+
+    #[test]
+    fn test_set_tap_action_updates_settings_and_cell() {
+        let mut context = create_test_context();
+        let mut rq = RenderQueue::new();
+        let mut editor = TapZonesEditor::new(rect![0, 0, 600, 800], &mut rq, &mut context);
+        let (hub, _receiver) = channel();
+        let mut bus = VecDeque::new();
+
+        let event = Event::Select(EntryId::SetTapAction(
+            TapZone::Center,
+            TapAction::ToggleFrontlight,
+        ));
+        let handled = editor.handle_event(&event, &hub, &mut bus, &mut rq, &mut context);
+
+        assert!(handled);
+        assert_eq!(
+            context.settings.reader.tap_zones[TapZone::Center],
+            TapAction::ToggleFrontlight
+        );
+        assert_eq!(
+            editor.cell_mut(TapZone::Center).map(|cell| cell.action()),
+            Some(TapAction::ToggleFrontlight)
+        );
+    }
+}
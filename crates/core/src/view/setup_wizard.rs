@@ -0,0 +1,184 @@
+//! The guided setup shown at the first start.
+//!
+//! One dialog after the other, the wizard asks for the language of the user interface, whether
+//! to restore a backup when there's one, the library, and whether the Wi-Fi and the frontlight
+//! should be on. Each answer is applied as
+//! soon as it's given, and the settings are saved after the last one. Tapping outside of a
+//! dialog leaves the wizard: the remaining steps keep their defaults.
+
+use super::dialog::Dialog;
+use super::{EntryId, Event, Hub, ViewId};
+use crate::backup;
+use crate::context::Context;
+use crate::i18n::language_name;
+use crate::settings::{LibrarySettings, Settings, EXTERNAL_CARD_ROOT, INTERNAL_CARD_ROOT};
+use crate::storage;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SetupStep {
+    Language,
+    Restore,
+    Library,
+    Wifi,
+    Frontlight,
+}
+
+impl SetupStep {
+    pub fn next(self) -> Option<SetupStep> {
+        match self {
+            SetupStep::Language => Some(SetupStep::Restore),
+            SetupStep::Restore => Some(SetupStep::Library),
+            SetupStep::Library => Some(SetupStep::Wifi),
+            SetupStep::Wifi => Some(SetupStep::Frontlight),
+            SetupStep::Frontlight => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SetupChoice {
+    Language(String),
+    /// Leaves the wizard for the list of the backups when true.
+    Restore(bool),
+    Library(PathBuf),
+    /// Opens a file chooser, whose directory is then given as a [`SetupChoice::Library`].
+    ChooseLibrary,
+    Wifi(bool),
+    Frontlight(bool),
+}
+
+impl SetupChoice {
+    pub fn step(&self) -> SetupStep {
+        match self {
+            SetupChoice::Language(..) => SetupStep::Language,
+            SetupChoice::Restore(..) => SetupStep::Restore,
+            SetupChoice::Library(..) | SetupChoice::ChooseLibrary => SetupStep::Library,
+            SetupChoice::Wifi(..) => SetupStep::Wifi,
+            SetupChoice::Frontlight(..) => SetupStep::Frontlight,
+        }
+    }
+}
+
+/// Builds the dialog of the given step.
+pub fn dialog(step: SetupStep, context: &mut Context) -> Dialog {
+    let select = |choice| Event::Select(EntryId::Setup(choice));
+    let builder = match step {
+        SetupStep::Language => {
+            let mut builder = Dialog::builder(ViewId::SetupDialog, tr!("Choose a language."));
+            for language in context.available_languages() {
+                builder = builder.add_button(
+                    language_name(&language),
+                    select(SetupChoice::Language(language.clone())),
+                );
+            }
+            builder
+        }
+        SetupStep::Restore => {
+            Dialog::builder(ViewId::SetupDialog, tr!("A backup was found. Restore it?"))
+                .add_button(&tr!("No"), select(SetupChoice::Restore(false)))
+                .add_button(&tr!("Restore"), select(SetupChoice::Restore(true)))
+        }
+        SetupStep::Library => {
+            let mut builder =
+                Dialog::builder(ViewId::SetupDialog, tr!("Where are your books stored?"))
+                    .add_button(
+                        &tr!("On Board"),
+                        select(SetupChoice::Library(PathBuf::from(INTERNAL_CARD_ROOT))),
+                    );
+            if storage::is_mounted(Path::new(EXTERNAL_CARD_ROOT)) {
+                builder = builder.add_button(
+                    &tr!("Removable"),
+                    select(SetupChoice::Library(PathBuf::from(EXTERNAL_CARD_ROOT))),
+                );
+            }
+            builder.add_button(&tr!("Choose Folder"), select(SetupChoice::ChooseLibrary))
+        }
+        SetupStep::Wifi => Dialog::builder(ViewId::SetupDialog, tr!("Turn the Wi-Fi on?"))
+            .add_button(&tr!("No"), select(SetupChoice::Wifi(false)))
+            .add_button(&tr!("Yes"), select(SetupChoice::Wifi(true))),
+        SetupStep::Frontlight => {
+            Dialog::builder(ViewId::SetupDialog, tr!("Turn the frontlight on?"))
+                .add_button(&tr!("No"), select(SetupChoice::Frontlight(false)))
+                .add_button(&tr!("Yes"), select(SetupChoice::Frontlight(true)))
+        }
+    };
+    builder.build(context)
+}
+
+/// Applies the choice, and returns the step that follows.
+///
+/// The library and the Wi-Fi are changed through the hub, since the application owns them,
+/// and the choices that open another view are left to it. The restoration step is skipped
+/// when there's no backup.
+pub fn apply(choice: &SetupChoice, hub: &Hub, context: &mut Context) -> Option<SetupStep> {
+    match choice {
+        SetupChoice::Language(language) => {
+            context.settings.language = language.clone();
+            context.load_translations();
+        }
+        SetupChoice::Library(path) => {
+            let index = library_index(&mut context.settings, path);
+            hub.send(Event::Select(EntryId::LoadLibrary(index))).ok();
+        }
+        SetupChoice::Restore(false) => (),
+        SetupChoice::Restore(true) | SetupChoice::ChooseLibrary => return Some(choice.step()),
+        SetupChoice::Wifi(enable) => {
+            hub.send(Event::SetWifi(*enable)).ok();
+        }
+        SetupChoice::Frontlight(enable) => {
+            if *enable != context.settings.frontlight {
+                hub.send(Event::ToggleFrontlight).ok();
+            }
+        }
+    }
+    match choice.step().next() {
+        Some(SetupStep::Restore) if backup::archives().is_empty() => Some(SetupStep::Library),
+        step => step,
+    }
+}
+
+/// Returns the index of the library at `path`, adding one when there's none.
+pub fn library_index(settings: &mut Settings, path: &Path) -> usize {
+    if let Some(index) = settings
+        .libraries
+        .iter()
+        .position(|library| library.path == path)
+    {
+        return index;
+    }
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string());
+    settings.libraries.push(LibrarySettings {
+        name,
+        path: path.to_path_buf(),
+        ..Default::default()
+    });
+    settings.libraries.len() - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_library_index() {
+        let mut settings = Settings::default();
+        let count = settings.libraries.len();
+        assert_eq!(
+            library_index(&mut settings, Path::new(INTERNAL_CARD_ROOT)),
+            0
+        );
+        assert_eq!(settings.libraries.len(), count);
+
+        let index = library_index(&mut settings, Path::new("/mnt/onboard/Books"));
+        assert_eq!(index, count);
+        assert_eq!(settings.libraries[index].name, "Books");
+        assert_eq!(
+            library_index(&mut settings, Path::new("/mnt/onboard/Books")),
+            index
+        );
+    }
+}
@@ -5,8 +5,9 @@ use crate::font::Fonts;
 use crate::framebuffer::{Framebuffer, Pixmap, UpdateMode};
 use crate::geom::{CornerSpec, Point, Rectangle};
 use crate::helpers::IsHidden;
-use crate::input::{DeviceEvent, FingerStatus};
-use crate::settings::{ImportSettings, Pen};
+use crate::input::{ButtonCode, ButtonStatus, DeviceEvent, FingerStatus};
+use crate::library::Library;
+use crate::settings::{ImportSettings, Pen, Settings};
 use crate::unit::scale_by_dpi;
 use crate::view::common::locate_by_id;
 use crate::view::icon::{Icon, ICONS_PIXMAPS};
@@ -22,6 +23,7 @@ use globset::Glob;
 use std::fs::{self, File};
 use std::io::BufReader;
 use std::path::PathBuf;
+use tracing::error;
 use walkdir::WalkDir;
 
 const FILENAME_PATTERN: &str = "sketch-%Y%m%d_%H%M%S.png";
@@ -48,6 +50,11 @@ pub struct Sketch {
     pixmap: Pixmap,
     fingers: FxHashMap<i32, TouchState>,
     pen: Pen,
+    eraser: Pen,
+    erasing: bool,
+    // Whether the eraser button of the stylus is pressed.
+    eraser_held: bool,
+    notes_library: Option<usize>,
     save_path: PathBuf,
     filename: String,
 }
@@ -78,10 +85,18 @@ impl Sketch {
         )
         .corners(Some(CornerSpec::Uniform(border_radius)));
         children.push(Box::new(icon) as Box<dyn View>);
-        let save_path = context
-            .library
-            .home
-            .join(&context.settings.sketch.save_path);
+        let notes_library = notes_library(&context.settings);
+        let home = notes_library.map_or_else(
+            || context.library.home.clone(),
+            |index| context.settings.libraries[index].path.clone(),
+        );
+        let save_path = home.join(&context.settings.sketch.save_path);
+        let eraser = Pen {
+            size: context.settings.sketch.eraser_size,
+            color: WHITE,
+            dynamic: false,
+            ..Default::default()
+        };
         rq.add(RenderData::new(id, rect, UpdateMode::Full));
         Sketch {
             id,
@@ -90,6 +105,10 @@ impl Sketch {
             pixmap: Pixmap::new(rect.width(), rect.height(), 1),
             fingers: FxHashMap::default(),
             pen: context.settings.sketch.pen.clone(),
+            eraser,
+            erasing: false,
+            eraser_held: false,
+            notes_library,
             save_path,
             filename: Local::now().format(FILENAME_PATTERN).to_string(),
         }
@@ -176,6 +195,7 @@ impl Sketch {
             let mut entries = vec![
                 EntryKind::SubMenu("Size".to_string(), sizes),
                 EntryKind::SubMenu("Color".to_string(), colors),
+                EntryKind::CheckBox("Eraser".to_string(), EntryId::TogglePenEraser, self.erasing),
                 EntryKind::Separator,
                 EntryKind::Command("Save".to_string(), EntryId::Save),
                 EntryKind::Command("Refresh".to_string(), EntryId::Refresh),
@@ -241,10 +261,30 @@ impl Sketch {
             allowed_kinds: ["png".to_string()].iter().cloned().collect(),
             ..Default::default()
         };
-        context.library.import(&import_settings);
+        match self.notes_library {
+            Some(index) if index != context.settings.selected_library => {
+                let library_settings = &context.settings.libraries[index];
+                if let Ok(mut library) = Library::new(&library_settings.path, library_settings.mode)
+                    .map_err(|e| error!("Can't load the notes library: {:#}.", e))
+                {
+                    library.import(&import_settings);
+                    library.flush();
+                }
+            }
+            _ => context.library.import(&import_settings),
+        }
     }
 }
 
+/// Returns the index of the library where the sketches are saved, if it exists.
+fn notes_library(settings: &Settings) -> Option<usize> {
+    let name = settings.sketch.library.as_ref()?;
+    settings
+        .libraries
+        .iter()
+        .position(|library| library.name == *name)
+}
+
 #[inline]
 fn draw_segment(
     pixmap: &mut Pixmap,
@@ -309,13 +349,18 @@ impl View for Sketch {
                 position,
                 time,
             }) => {
+                let pen = if self.erasing || self.eraser_held {
+                    &self.eraser
+                } else {
+                    &self.pen
+                };
                 if let Some(ts) = self.fingers.get_mut(&id) {
                     draw_segment(
                         &mut self.pixmap,
                         ts,
                         position,
                         time,
-                        &self.pen,
+                        pen,
                         self.id,
                         &self.rect,
                         rq,
@@ -329,7 +374,12 @@ impl View for Sketch {
                 position,
                 time,
             }) => {
-                let radius = self.pen.size as f32 / 2.0;
+                let pen = if self.erasing || self.eraser_held {
+                    &self.eraser
+                } else {
+                    &self.pen
+                };
+                let radius = pen.size as f32 / 2.0;
                 self.fingers
                     .insert(id, TouchState::new(position, time, radius));
                 true
@@ -340,13 +390,18 @@ impl View for Sketch {
                 position,
                 time,
             }) => {
+                let pen = if self.erasing || self.eraser_held {
+                    &self.eraser
+                } else {
+                    &self.pen
+                };
                 if let Some(ts) = self.fingers.get_mut(&id) {
                     draw_segment(
                         &mut self.pixmap,
                         ts,
                         position,
                         time,
-                        &self.pen,
+                        pen,
                         self.id,
                         &self.rect,
                         rq,
@@ -371,6 +426,22 @@ impl View for Sketch {
                 self.pen.dynamic = !self.pen.dynamic;
                 true
             }
+            Event::Select(EntryId::TogglePenEraser) => {
+                self.erasing = !self.erasing;
+                true
+            }
+            Event::Device(DeviceEvent::Button {
+                code: ButtonCode::Erase,
+                status,
+                ..
+            }) => {
+                match status {
+                    ButtonStatus::Pressed => self.eraser_held = true,
+                    ButtonStatus::Released => self.eraser_held = false,
+                    ButtonStatus::Repeated => (),
+                }
+                true
+            }
             Event::Select(EntryId::Load(ref name)) => {
                 if let Err(e) = self.load(name) {
                     let msg = format!("Couldn't load sketch: {}).", e);
@@ -454,3 +525,25 @@ impl View for Sketch {
         self.id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::LibrarySettings;
+
+    #[test]
+    fn test_notes_library() {
+        let mut settings = Settings::default();
+        assert_eq!(notes_library(&settings), None);
+
+        settings.libraries.push(LibrarySettings {
+            name: "Notes".to_string(),
+            path: PathBuf::from("/mnt/onboard/Notes"),
+            ..Default::default()
+        });
+        assert_eq!(notes_library(&settings), Some(settings.libraries.len() - 1));
+
+        settings.sketch.library = None;
+        assert_eq!(notes_library(&settings), None);
+    }
+}
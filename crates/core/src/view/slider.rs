@@ -1,14 +1,14 @@
 use super::{
     Bus, Event, Hub, Id, RenderData, RenderQueue, SliderId, View, ID_FEEDER, THICKNESS_SMALL,
 };
-use crate::color::{BLACK, PROGRESS_EMPTY, PROGRESS_FULL, PROGRESS_VALUE, WHITE};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::{font_from_style, Fonts, SLIDER_VALUE};
 use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::{halves, BorderSpec, CornerSpec, Rectangle};
 use crate::input::{DeviceEvent, FingerStatus};
-use crate::unit::scale_by_dpi;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
 
 const PROGRESS_HEIGHT: f32 = 7.0;
 const BUTTON_DIAMETER: f32 = 46.0;
@@ -94,7 +94,7 @@ impl View for Slider {
                     rq.add(RenderData::no_wait(
                         self.id,
                         self.rect,
-                        UpdateMode::FastMono,
+                        UpdateMode::Interactive,
                     ));
                     bus.push_back(Event::Slider(self.slider_id, self.value, status));
                     self.last_x = position.x;
@@ -121,7 +121,7 @@ impl View for Slider {
         let dpi = CURRENT_DEVICE.dpi;
         let progress_height = scale_by_dpi(PROGRESS_HEIGHT, dpi) as i32;
         let button_diameter = scale_by_dpi(BUTTON_DIAMETER, dpi) as i32;
-        let border_thickness = scale_by_dpi(THICKNESS_SMALL, dpi) as u16;
+        let border_thickness = scale_thickness(THICKNESS_SMALL, dpi) as u16;
 
         let progress = (self.value - self.min_value) / (self.max_value - self.min_value);
         let (small_radius, big_radius) = halves(button_diameter);
@@ -129,7 +129,7 @@ impl View for Slider {
             + small_radius
             + ((self.rect.width() as f32 - button_diameter as f32) * progress) as i32;
 
-        fb.draw_rectangle(&self.rect, WHITE);
+        fb.draw_rectangle(&self.rect, theme::current().background);
 
         let (small_mini_radius, big_mini_radius) = halves(progress_height);
         let (small_padding, big_padding) = halves(self.rect.height() as i32 - progress_height);
@@ -145,34 +145,31 @@ impl View for Slider {
             &CornerSpec::Uniform(small_mini_radius),
             &BorderSpec {
                 thickness: border_thickness,
-                color: BLACK,
+                color: theme::current().foreground,
             },
             &|x, _| {
                 if x < x_offset {
-                    PROGRESS_FULL
+                    theme::current().progress_full
                 } else {
-                    PROGRESS_EMPTY
+                    theme::current().progress_empty
                 }
             },
         );
 
-        let (small_padding, big_padding) = halves(self.rect.height() as i32 - button_diameter);
-        let rect = rect![
-            x_offset - small_radius,
-            self.rect.min.y + small_padding,
-            x_offset + big_radius,
-            self.rect.max.y - big_padding
-        ];
-        let fill_color = if self.active { BLACK } else { WHITE };
+        let (small_padding, _) = halves(self.rect.height() as i32 - button_diameter);
+        let fill_color = if self.active {
+            theme::current().foreground
+        } else {
+            theme::current().background
+        };
 
-        fb.draw_rounded_rectangle_with_border(
-            &rect,
-            &CornerSpec::Uniform(small_radius),
-            &BorderSpec {
-                thickness: 2 * border_thickness,
-                color: BLACK,
-            },
-            &fill_color,
+        let center = pt!(x_offset, self.rect.min.y + small_padding + small_radius);
+        fb.draw_disk(center, small_radius, fill_color);
+        fb.draw_circle(
+            center,
+            small_radius,
+            2.0 * border_thickness as f32,
+            theme::current().foreground,
         );
 
         let font = font_from_style(fonts, &SLIDER_VALUE, dpi);
@@ -189,7 +186,7 @@ impl View for Slider {
             x_offset + x_drift,
             self.rect.min.y + x_height.max(small_padding)
         );
-        font.render(fb, PROGRESS_VALUE, &plan, pt);
+        font.render(fb, theme::current().progress_value, &plan, pt);
     }
 
     fn rect(&self) -> &Rectangle {
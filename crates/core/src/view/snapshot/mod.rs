@@ -0,0 +1,268 @@
+//! Comparing the rendering of views with golden images.
+//!
+//! A snapshot test renders a view tree into a [`Pixmap`] and compares it with the PNG of the
+//! same name in `src/view/snapshot/golden`. A pixel differs when its gray level is off by more
+//! than [`PIXEL_TOLERANCE`], and the test fails when more than [`DIFF_THRESHOLD`] of the pixels
+//! differ: the small variations of the anti-aliasing between versions of FreeType pass, moved
+//! or overlapping views don't.
+//!
+//! The clocks are blanked out before comparing, since they show the time of the run.
+//!
+//! After an intended change of the layout, run the tests with `UPDATE_SNAPSHOTS=1` to write the
+//! new golden images, and commit them. When a comparison fails, the rendered image and a map of
+//! the differing pixels, in black, are written to `cadmus-snapshots` in the temporary directory.
+
+use super::clock::Clock;
+use super::View;
+use crate::color::{BLACK, WHITE};
+use crate::context::Context;
+use crate::font::Fonts;
+use crate::framebuffer::{Framebuffer, Pixmap};
+use crate::geom::Rectangle;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const GOLDEN_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/view/snapshot/golden");
+
+/// The largest difference between two gray levels considered equal.
+pub const PIXEL_TOLERANCE: u8 = 48;
+
+/// The largest fraction of differing pixels of a matching snapshot.
+pub const DIFF_THRESHOLD: f32 = 0.002;
+
+/// Renders the view, and its children, cropped to its rectangle.
+pub fn render_view(view: &dyn View, context: &mut Context) -> Pixmap {
+    let fb_rect = context.fb.rect();
+    let mut pixmap = Pixmap::new(fb_rect.width(), fb_rect.height(), 1);
+    render_tree(view, &mut pixmap, &mut context.fonts);
+
+    let mut volatile = Vec::new();
+    collect_volatile(view, &mut volatile);
+    for rect in volatile {
+        pixmap.draw_rectangle(&rect, WHITE);
+    }
+
+    let rect = view.rect().intersection(&fb_rect).unwrap_or_default();
+    crop(&pixmap, &rect)
+}
+
+// Renders the leaves and the backgrounds, from bottom to top, like a full update would.
+fn render_tree(view: &dyn View, fb: &mut dyn Framebuffer, fonts: &mut Fonts) {
+    if view.len() == 0 || view.is_background() {
+        view.render(fb, *view.rect(), fonts);
+    }
+    for child in view.children() {
+        render_tree(child.as_ref(), fb, fonts);
+    }
+}
+
+fn collect_volatile(view: &dyn View, rects: &mut Vec<Rectangle>) {
+    if view.is::<Clock>() {
+        rects.push(*view.rect());
+    }
+    for child in view.children() {
+        collect_volatile(child.as_ref(), rects);
+    }
+}
+
+fn crop(pixmap: &Pixmap, rect: &Rectangle) -> Pixmap {
+    let mut cropped = Pixmap::new(rect.width(), rect.height(), pixmap.samples);
+    let row_len = rect.width() as usize * pixmap.samples;
+    for y in 0..rect.height() as usize {
+        let start = ((rect.min.y as usize + y) * pixmap.width as usize + rect.min.x as usize)
+            * pixmap.samples;
+        cropped.data[y * row_len..(y + 1) * row_len]
+            .copy_from_slice(&pixmap.data[start..start + row_len]);
+    }
+    cropped
+}
+
+/// Returns the number of differing pixels, and the map of these pixels.
+pub fn diff(expected: &Pixmap, actual: &Pixmap) -> (usize, Pixmap) {
+    let mut map = Pixmap::new(actual.width, actual.height, 1);
+    let mut count = 0;
+
+    for y in 0..actual.height.min(expected.height) {
+        for x in 0..actual.width.min(expected.width) {
+            let a = expected.get_pixel(x, y).gray();
+            let b = actual.get_pixel(x, y).gray();
+            if a.abs_diff(b) > PIXEL_TOLERANCE {
+                map.set_pixel(x, y, BLACK);
+                count += 1;
+            }
+        }
+    }
+
+    (count, map)
+}
+
+/// Panics if the rendering of the view doesn't match the golden image with the given name.
+pub fn assert_snapshot(name: &str, view: &dyn View, context: &mut Context) {
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        let path = golden_path(name);
+        fs::create_dir_all(GOLDEN_DIR).unwrap();
+        render_view(view, context)
+            .save(&path.to_string_lossy())
+            .unwrap();
+        return;
+    }
+
+    if let Err(msg) = check_snapshot(name, view, context) {
+        panic!("{}", msg);
+    }
+}
+
+/// Compares the rendering of the view with the golden image with the given name.
+pub fn check_snapshot(name: &str, view: &dyn View, context: &mut Context) -> Result<(), String> {
+    let actual = render_view(view, context);
+    let path = golden_path(name);
+
+    let expected = Pixmap::from_png(&path).map_err(|e| {
+        format!(
+            "Can't load {}: {:#}. Run the test with UPDATE_SNAPSHOTS=1 to create it.",
+            path.display(),
+            e
+        )
+    })?;
+
+    if (expected.width, expected.height) != (actual.width, actual.height) {
+        let actual_path = save_failure(name, "actual", &actual);
+        return Err(format!(
+            "The snapshot {} is {}×{} instead of {}×{}, see {}.",
+            name,
+            actual.width,
+            actual.height,
+            expected.width,
+            expected.height,
+            actual_path.display()
+        ));
+    }
+
+    let (count, map) = diff(&expected, &actual);
+    let ratio = count as f32 / (actual.width * actual.height).max(1) as f32;
+
+    if ratio > DIFF_THRESHOLD {
+        let actual_path = save_failure(name, "actual", &actual);
+        let diff_path = save_failure(name, "diff", &map);
+        return Err(format!(
+            "The snapshot {} differs in {} pixels ({:.2}%), see {} and {}.",
+            name,
+            count,
+            100.0 * ratio,
+            actual_path.display(),
+            diff_path.display()
+        ));
+    }
+
+    Ok(())
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    Path::new(GOLDEN_DIR).join(format!("{}.png", name))
+}
+
+fn save_failure(name: &str, suffix: &str, pixmap: &Pixmap) -> PathBuf {
+    let dir = env::temp_dir().join("cadmus-snapshots");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("{}.{}.png", name, suffix));
+    pixmap.save(&path.to_string_lossy()).unwrap();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::{GRAY08, GRAY13};
+    use crate::context::test_helpers::create_test_context;
+    use crate::view::filler::Filler;
+    use crate::view::settings_editor::SettingsEditor;
+    use crate::view::{Bus, Event, Hub, Id, RenderQueue, ID_FEEDER};
+
+    #[test]
+    fn test_slight_differences_are_tolerated() {
+        let expected = Pixmap::new(100, 100, 1);
+        let mut actual = expected.clone();
+        actual.set_pixel(3, 4, GRAY13);
+        actual.set_pixel(5, 6, BLACK);
+
+        let (count, map) = diff(&expected, &actual);
+        assert_eq!(count, 1);
+        assert_eq!(map.get_pixel(5, 6), BLACK);
+        assert_eq!(map.get_pixel(3, 4), WHITE);
+    }
+
+    #[test]
+    fn test_layout_matches_its_snapshot() {
+        let mut context = create_test_context();
+        let mut column = Column {
+            id: ID_FEEDER.next(),
+            rect: rect![0, 0, 120, 90],
+            children: Vec::new(),
+        };
+        for (rect, color) in [
+            (rect![0, 0, 120, 30], GRAY13),
+            (rect![0, 30, 120, 32], BLACK),
+            (rect![10, 40, 110, 80], GRAY08),
+        ] {
+            column
+                .children
+                .push(Box::new(Filler::new(rect, color)) as Box<dyn View>);
+        }
+        assert_snapshot("layout", &column, &mut context);
+
+        column.children[2].rect_mut().min.y += 4;
+        let msg = check_snapshot("layout", &column, &mut context).unwrap_err();
+        assert!(msg.contains("differs in 400 pixels"));
+    }
+
+    #[test]
+    fn test_settings_editor_matches_its_snapshot() {
+        let mut context = create_test_context();
+        let mut rq = RenderQueue::new();
+        let rect = context.fb.rect();
+        let editor = SettingsEditor::new(rect, &mut rq, &mut context);
+        assert_snapshot("settings_editor", &editor, &mut context);
+    }
+
+    struct Column {
+        id: Id,
+        rect: Rectangle,
+        children: Vec<Box<dyn View>>,
+    }
+
+    impl View for Column {
+        fn handle_event(
+            &mut self,
+            _evt: &Event,
+            _hub: &Hub,
+            _bus: &mut Bus,
+            _rq: &mut RenderQueue,
+            _context: &mut Context,
+        ) -> bool {
+            false
+        }
+
+        fn render(&self, _fb: &mut dyn Framebuffer, _rect: Rectangle, _fonts: &mut Fonts) {}
+
+        fn rect(&self) -> &Rectangle {
+            &self.rect
+        }
+
+        fn rect_mut(&mut self) -> &mut Rectangle {
+            &mut self.rect
+        }
+
+        fn children(&self) -> &Vec<Box<dyn View>> {
+            &self.children
+        }
+
+        fn children_mut(&mut self) -> &mut Vec<Box<dyn View>> {
+            &mut self.children
+        }
+
+        fn id(&self) -> Id {
+            self.id
+        }
+    }
+}
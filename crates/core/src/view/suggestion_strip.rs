@@ -0,0 +1,124 @@
+use super::label::Label;
+use super::{Align, Bus, EntryId, Event, Hub, Id, RenderData, RenderQueue, View, ID_FEEDER};
+use crate::context::Context;
+use crate::font::Fonts;
+use crate::framebuffer::{Framebuffer, UpdateMode};
+use crate::geom::Rectangle;
+use crate::theme;
+
+/// The row of words above the keys of the keyboard.
+///
+/// Tapping a word sends `Event::Select(EntryId::Suggestion(word))`.
+pub struct SuggestionStrip {
+    id: Id,
+    rect: Rectangle,
+    children: Vec<Box<dyn View>>,
+    suggestions: Vec<String>,
+}
+
+impl SuggestionStrip {
+    pub fn new(rect: Rectangle) -> SuggestionStrip {
+        SuggestionStrip {
+            id: ID_FEEDER.next(),
+            rect,
+            children: Vec::new(),
+            suggestions: Vec::new(),
+        }
+    }
+
+    pub fn suggestions(&self) -> &[String] {
+        &self.suggestions
+    }
+
+    pub fn update(&mut self, suggestions: Vec<String>, rq: &mut RenderQueue) {
+        if self.suggestions == suggestions {
+            return;
+        }
+        self.suggestions = suggestions;
+        self.layout();
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+    }
+
+    fn layout(&mut self) {
+        self.children.clear();
+
+        let count = self.suggestions.len() as i32;
+        if count == 0 {
+            return;
+        }
+
+        let t = theme::current();
+        let scheme = [t.keyboard_bg, t.text_normal[1], t.text_normal[2]];
+        let width = self.rect.width() as i32 / count;
+
+        for (i, word) in self.suggestions.iter().enumerate() {
+            let x_min = self.rect.min.x + i as i32 * width;
+            let x_max = if i as i32 == count - 1 {
+                self.rect.max.x
+            } else {
+                x_min + width
+            };
+            let rect = rect![x_min, self.rect.min.y, x_max, self.rect.max.y];
+            let label = Label::new(rect, word.clone(), Align::Center)
+                .scheme(scheme)
+                .event(Some(Event::Select(EntryId::Suggestion(word.clone()))));
+            self.children.push(Box::new(label) as Box<dyn View>);
+        }
+    }
+}
+
+impl View for SuggestionStrip {
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _evt, _hub, _bus, _rq, _context), fields(event = ?_evt), ret(level=tracing::Level::TRACE)))]
+    fn handle_event(
+        &mut self,
+        _evt: &Event,
+        _hub: &Hub,
+        _bus: &mut Bus,
+        _rq: &mut RenderQueue,
+        _context: &mut Context,
+    ) -> bool {
+        false
+    }
+
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, fb, _fonts), fields(rect = ?rect)))]
+    fn render(&self, fb: &mut dyn Framebuffer, rect: Rectangle, _fonts: &mut Fonts) {
+        if let Some(region) = rect.intersection(&self.rect) {
+            fb.draw_rectangle(&region, theme::current().keyboard_bg);
+        }
+    }
+
+    fn render_rect(&self, rect: &Rectangle) -> Rectangle {
+        rect.intersection(&self.rect).unwrap_or(self.rect)
+    }
+
+    fn resize(
+        &mut self,
+        rect: Rectangle,
+        _hub: &Hub,
+        _rq: &mut RenderQueue,
+        _context: &mut Context,
+    ) {
+        self.rect = rect;
+        self.layout();
+    }
+
+    fn rect(&self) -> &Rectangle {
+        &self.rect
+    }
+
+    fn rect_mut(&mut self) -> &mut Rectangle {
+        &mut self.rect
+    }
+
+    fn children(&self) -> &Vec<Box<dyn View>> {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<Box<dyn View>> {
+        &mut self.children
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+}
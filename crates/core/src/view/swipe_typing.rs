@@ -0,0 +1,178 @@
+//! Guessing the words typed on the on-screen keyboard.
+//!
+//! A swipe is reduced to the letters of the keys it crosses. The candidates are the words of
+//! the layout's word list that start and end with the first and last of these letters, and
+//! whose letters appear in the same order along the swipe. Longer matches and more frequent
+//! words rank first.
+//!
+//! The word being typed is completed from the titles and authors of the recently read books,
+//! then from the word list.
+
+use crate::library::Library;
+use anyhow::{Context as ResultExt, Error};
+use std::cmp::Reverse;
+use std::fs;
+use std::path::Path;
+
+// The number of books, most recently opened or added first, whose titles and authors are
+// suggested.
+const RECENT_BOOKS_COUNT: usize = 64;
+
+// The score of each letter of a candidate matched along the swipe, against the logarithm of
+// the candidate's rank in the word list.
+const LETTER_WEIGHT: f32 = 2.0;
+
+/// The words of a language, from the most frequent to the least.
+#[derive(Debug, Clone, Default)]
+pub struct WordList {
+    words: Vec<String>,
+}
+
+impl WordList {
+    /// Parses a list of words, one per line, the most frequent first.
+    ///
+    /// The blank lines and the lines starting with `#` are ignored.
+    pub fn new(text: &str) -> WordList {
+        let words = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(String::from)
+            .collect();
+        WordList { words }
+    }
+
+    /// Returns the words of the titles and authors of the recently opened or added books.
+    pub fn from_library(library: &Library) -> WordList {
+        let mut books: Vec<_> = library.db.values().collect();
+        books.sort_by_key(|info| Reverse(info.reader.as_ref().map_or(info.added, |r| r.opened)));
+
+        let separator = |c: char| !c.is_alphanumeric() && c != '\'';
+        let mut words: Vec<String> = Vec::new();
+        for info in books.into_iter().take(RECENT_BOOKS_COUNT) {
+            for word in info
+                .title
+                .split(separator)
+                .chain(info.author.split(separator))
+            {
+                if word.chars().count() > 2 && !words.iter().any(|w| w == word) {
+                    words.push(word.to_string());
+                }
+            }
+        }
+
+        WordList { words }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<WordList, Error> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("can't read words from {}", path.display()))?;
+        Ok(WordList::new(&text))
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Returns the words that start with the given prefix, regardless of case, most frequent
+    /// first.
+    pub fn completions(&self, prefix: &str, limit: usize) -> Vec<String> {
+        let prefix = prefix.to_lowercase();
+        if prefix.is_empty() {
+            return Vec::new();
+        }
+        self.words
+            .iter()
+            .filter(|word| {
+                let word = word.to_lowercase();
+                word.len() > prefix.len() && word.starts_with(&prefix)
+            })
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the words traced by a swipe that crossed the given letters, best first.
+    ///
+    /// The letters are lowercase, and consecutive duplicates are removed.
+    pub fn candidates(&self, letters: &[char], limit: usize) -> Vec<String> {
+        let (Some(first), Some(last)) = (letters.first(), letters.last()) else {
+            return Vec::new();
+        };
+
+        let mut scored = Vec::new();
+
+        for (rank, word) in self.words.iter().enumerate() {
+            let traced = collapse(word);
+            if traced.len() < 2 || traced[0] != *first || traced[traced.len() - 1] != *last {
+                continue;
+            }
+            if !is_subsequence(&traced, letters) {
+                continue;
+            }
+            let score = LETTER_WEIGHT * traced.len() as f32 - ((rank + 1) as f32).ln();
+            scored.push((score, word));
+        }
+
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        scored
+            .into_iter()
+            .take(limit)
+            .map(|(_, word)| word.clone())
+            .collect()
+    }
+}
+
+// Returns the lowercase letters of a word, without the consecutive duplicates: the double
+// letters of a word are traced by a single key.
+fn collapse(word: &str) -> Vec<char> {
+    let mut letters: Vec<char> = word.chars().flat_map(char::to_lowercase).collect();
+    letters.dedup();
+    letters
+}
+
+fn is_subsequence(needle: &[char], haystack: &[char]) -> bool {
+    let mut haystack = haystack.iter();
+    needle.iter().all(|c| haystack.any(|h| h == c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn letters(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    #[test]
+    fn test_candidates_follow_the_swipe() {
+        let words = WordList::new("# Most frequent first.\nthe\nhe\nher\nhello\n\nhole\n");
+        assert_eq!(words.len(), 5);
+
+        let candidates = words.candidates(&letters("hgtrewsdfghjklkio"), 3);
+        assert_eq!(candidates, vec!["hello"]);
+
+        assert_eq!(words.candidates(&letters("hre"), 3), vec!["he"]);
+        assert!(words.candidates(&letters("tre"), 3).is_empty());
+        assert!(words.candidates(&[], 3).is_empty());
+    }
+
+    #[test]
+    fn test_completions_ignore_case() {
+        let words = WordList::new("the\nthere\nTheodore\nthey\n");
+        assert_eq!(words.completions("The", 2), vec!["there", "Theodore"]);
+        assert!(words.completions("they", 2).is_empty());
+        assert!(words.completions("", 2).is_empty());
+    }
+
+    #[test]
+    fn test_frequent_words_rank_first() {
+        let words = WordList::new("to\ntoo\ntoto\n");
+        assert_eq!(words.candidates(&letters("tyuio"), 3), vec!["to", "too"]);
+    }
+}
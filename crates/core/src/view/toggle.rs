@@ -1,11 +1,11 @@
 use super::{Align, Bus, Event, Hub, Id, RenderQueue, View, ID_FEEDER};
-use crate::color::{BLACK, GRAY08, TEXT_NORMAL};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::{font_from_style, Fonts, NORMAL_STYLE};
 use crate::framebuffer::Framebuffer;
-use crate::geom::{BorderSpec, Rectangle};
-use crate::unit::scale_by_dpi;
+use crate::geom::{BorderSpec, CornerSpec, Rectangle};
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
 use crate::view::filler::Filler;
 use crate::view::label::Label;
 
@@ -81,13 +81,14 @@ impl View for SelectionBox {
         let pt = self.target_rect.min + pt!(x_offset, y_offset);
         let border_box_rect = rect![pt, pt + pt!(border_box_width, border_box_height)];
 
-        let border_thickness = scale_by_dpi(THICKNESS_SMALL, dpi) as u16;
+        let border_thickness = scale_thickness(THICKNESS_SMALL, dpi) as u16;
 
-        fb.draw_rectangle_outline(
+        fb.draw_rounded_rectangle_outline(
             &border_box_rect,
+            &CornerSpec::Uniform(x_height / 2),
             &BorderSpec {
                 thickness: border_thickness,
-                color: BLACK,
+                color: theme::current().foreground,
             },
         );
     }
@@ -113,16 +114,17 @@ impl View for SelectionBox {
     }
 }
 
-/// A toggle component that displays two options side-by-side, separated by a vertical line.
+/// A toggle component that displays two or more options side-by-side, separated by vertical lines.
 ///
-/// The Toggle component provides a binary choice control where one option is highlighted
-/// with a minimal border box while the other appears without highlighting. Tapping either
-/// label toggles the state and emits a configured event.
+/// The Toggle component provides a choice control where one option is highlighted
+/// with a minimal border box while the others appear without highlighting. Tapping a
+/// label selects its option, tapping the selected label selects the next one, and the
+/// event of the newly selected option is emitted.
 ///
 /// # Implementation Note
 ///
 /// Toggle uses a child view approach for the selection box. The SelectionBox is added as
-/// the 4th child and renders on top of the labels (due to z-order). When the toggle state
+/// the last child and renders on top of the labels (due to z-order). When the toggle state
 /// changes, the SelectionBox is updated to reposition around the selected label.
 ///
 /// # Visual Layout
@@ -140,19 +142,22 @@ impl View for SelectionBox {
 ///
 /// # Event Flow
 ///
-/// 1. User taps on either label
-/// 2. Label emits its configured event (bubbles to parent via bus)
+/// 1. User taps on a label
+/// 2. Label emits `Event::SelectToggleValue` with its index (bubbles to parent via bus)
 /// 3. Toggle intercepts this event in its handle_event()
-/// 4. Toggle updates internal state (flips enabled)
+/// 4. Toggle updates internal state (selects the option)
 /// 5. Toggle updates the SelectionBox child to reposition
 /// 6. Toggle triggers a re-render
-/// 7. Toggle re-emits the event to continue bubbling up
+/// 7. Toggle emits the event of the selected option to continue bubbling up
+///
+/// An event with the same variant as the one of the first option selects the next option,
+/// as if the selected label had been tapped.
 ///
 /// # Example
 ///
 /// ```
 /// use cadmus_core::view::toggle::Toggle;
-/// use cadmus_core::view::{Align, Event, ViewId, ToggleEvent};
+/// use cadmus_core::view::{Align, EntryId, Event, ViewId, ToggleEvent};
 /// use cadmus_core::font::Fonts;
 /// use cadmus_core::rect;
 /// use std::env;
@@ -170,39 +175,52 @@ impl View for SelectionBox {
 ///     fonts,
 ///     Align::Right(10)
 /// );
+///
+/// // A third "Auto" state, each option sending its own event.
+/// let light_toggle = Toggle::with_values(
+///     rect,
+///     vec![
+///         ("Off".to_string(), Event::Select(EntryId::ToggleFuzzy)),
+///         ("Manual".to_string(), Event::Select(EntryId::ToggleInverted)),
+///         ("Auto".to_string(), Event::Select(EntryId::ToggleDithered)),
+///     ],
+///     2,          // Initial state (Auto selected)
+///     fonts,
+///     Align::Right(10)
+/// );
+/// assert_eq!(light_toggle.selected(), 2);
 /// ```
 ///
 /// # Alignment Behavior
 ///
-/// The right label uses the provided alignment, while the left label remains
-/// centered to avoid crowding the separator. This keeps the toggle right-aligned
+/// The last label uses the provided alignment, while the other labels remain
+/// centered to avoid crowding the separators. This keeps the toggle right-aligned
 /// with other setting values while maintaining consistent padding to the edge.
 ///
 /// # Fields
 ///
 /// * `id` - Unique identifier for this view
 /// * `rect` - The rectangular bounds of the toggle
-/// * `children` - Contains 4 children: [Label, Filler, Label, SelectionBox]
-/// * `enabled` - true = first option selected, false = second option selected
-/// * `event` - Event to emit and intercept when toggling
-/// * `left_label_index` - Index of left label in children vec
-/// * `right_label_index` - Index of right label in children vec
+/// * `children` - The labels separated by fillers, then the selection box:
+///   [Label, Filler, Label, …, SelectionBox]
+/// * `values` - The text of each option and the event it emits when selected
+/// * `selected` - Index of the selected option
+/// * `label_indices` - Index of each label in children vec
 /// * `selection_box_index` - Index of selection box in children vec
+/// * `text_widths` - Width of the text of each label
 pub struct Toggle {
     id: Id,
     rect: Rectangle,
     children: Vec<Box<dyn View>>,
-    enabled: bool,
-    event: Event,
-    left_label_index: usize,
-    right_label_index: usize,
+    values: Vec<(String, Event)>,
+    selected: usize,
+    label_indices: Vec<usize>,
     selection_box_index: usize,
-    left_text_width: i32,
-    right_text_width: i32,
+    text_widths: Vec<i32>,
 }
 
 impl Toggle {
-    /// Creates a new Toggle component.
+    /// Creates a new two-state Toggle component.
     ///
     /// # Arguments
     ///
@@ -225,82 +243,113 @@ impl Toggle {
         fonts: &mut Fonts,
         align: Align,
     ) -> Toggle {
+        Toggle::with_values(
+            rect,
+            vec![
+                (text_enabled.to_string(), event.clone()),
+                (text_disabled.to_string(), event),
+            ],
+            if enabled { 0 } else { 1 },
+            fonts,
+            align,
+        )
+    }
+
+    /// Creates a Toggle component with one label per value.
+    ///
+    /// # Arguments
+    ///
+    /// * `rect` - The rectangular bounds for the toggle
+    /// * `values` - The text of each option and the event emitted when it gets selected
+    /// * `selected` - Index of the initially selected option
+    /// * `align` - Alignment to apply to the last label
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values` is empty.
+    pub fn with_values(
+        rect: Rectangle,
+        values: Vec<(String, Event)>,
+        selected: usize,
+        fonts: &mut Fonts,
+        align: Align,
+    ) -> Toggle {
+        assert!(!values.is_empty(), "a toggle needs at least one value");
+
+        let id = ID_FEEDER.next();
         let dpi = CURRENT_DEVICE.dpi;
-        let separator_width = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let separator_width = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
 
         let font = font_from_style(fonts, &NORMAL_STYLE, dpi);
         let padding = font.em() as i32;
-        let left_plan = font.plan(text_enabled, None, None);
-        let right_plan = font.plan(text_disabled, None, None);
-        let left_text_width = left_plan.width;
-        let right_text_width = right_plan.width;
-        let left_width = left_text_width + padding;
-        let right_width = right_text_width + padding;
-        let total_width = left_width + separator_width + right_width;
+        let text_widths: Vec<i32> = values
+            .iter()
+            .map(|(text, _)| font.plan(text, None, None).width)
+            .collect();
+        let total_width = text_widths.iter().map(|w| w + padding).sum::<i32>()
+            + (values.len() as i32 - 1) * separator_width;
 
-        let x_offset = rect.width() as i32 - total_width;
+        let mut x_pos = rect.min.x + rect.width() as i32 - total_width;
 
+        let selected = selected.min(values.len() - 1);
         let mut children = Vec::new();
-
-        let left_rect = rect![
-            rect.min.x + x_offset,
-            rect.min.y,
-            rect.min.x + x_offset + left_width,
-            rect.max.y
-        ];
-        let left_label = Label::new(left_rect, text_enabled.to_string(), Align::Center)
-            .scheme(TEXT_NORMAL)
-            .event(Some(event.clone()));
-        children.push(Box::new(left_label) as Box<dyn View>);
-        let left_label_index = children.len() - 1;
+        let mut label_indices = Vec::new();
 
         let separator_height = rect.height() as i32;
         let separator_padding = separator_height / 4;
-        let separator_rect = rect![
-            rect.min.x + x_offset + left_width,
-            rect.min.y + separator_padding,
-            rect.min.x + x_offset + left_width + separator_width,
-            rect.max.y - separator_padding
-        ];
-        let separator = Filler::new(separator_rect, GRAY08);
-        children.push(Box::new(separator) as Box<dyn View>);
-
-        let right_rect = rect![
-            rect.min.x + x_offset + left_width + separator_width,
-            rect.min.y,
-            rect.max.x,
-            rect.max.y
-        ];
-        let right_label = Label::new(right_rect, text_disabled.to_string(), align)
-            .scheme(TEXT_NORMAL)
-            .event(Some(event.clone()));
-        children.push(Box::new(right_label) as Box<dyn View>);
-        let right_label_index = children.len() - 1;
-
-        let selected_rect = if enabled { left_rect } else { right_rect };
-        let selected_text_width = if enabled {
-            left_text_width
-        } else {
-            right_text_width
-        };
-        let selection_box = SelectionBox::new(rect, selected_rect, selected_text_width, true);
+
+        for (index, (text, _)) in values.iter().enumerate() {
+            let last = index == values.len() - 1;
+
+            if index > 0 {
+                let separator_rect = rect![
+                    x_pos,
+                    rect.min.y + separator_padding,
+                    x_pos + separator_width,
+                    rect.max.y - separator_padding
+                ];
+                let separator = Filler::new(separator_rect, theme::current().separator_strong);
+                children.push(Box::new(separator) as Box<dyn View>);
+                x_pos += separator_width;
+            }
+
+            let x_max = if last {
+                rect.max.x
+            } else {
+                x_pos + text_widths[index] + padding
+            };
+            let label_rect = rect![x_pos, rect.min.y, x_max, rect.max.y];
+            let label_align = if last { align } else { Align::Center };
+            let label = Label::new(label_rect, text.clone(), label_align)
+                .scheme(theme::current().text_normal)
+                .event(Some(Event::SelectToggleValue(id, index)));
+            children.push(Box::new(label) as Box<dyn View>);
+            label_indices.push(children.len() - 1);
+            x_pos = x_max;
+        }
+
+        let selected_rect = *children[label_indices[selected]].rect();
+        let selection_box = SelectionBox::new(rect, selected_rect, text_widths[selected], true);
         children.push(Box::new(selection_box) as Box<dyn View>);
         let selection_box_index = children.len() - 1;
 
         Toggle {
-            id: ID_FEEDER.next(),
+            id,
             rect,
             children,
-            enabled,
-            event,
-            left_label_index,
-            right_label_index,
+            values,
+            selected,
+            label_indices,
             selection_box_index,
-            left_text_width,
-            right_text_width,
+            text_widths,
         }
     }
 
+    /// Returns the index of the selected option.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
     fn request_rerender(&mut self, rq: &mut RenderQueue) {
         rq.add(crate::view::RenderData::new(
             self.id,
@@ -310,19 +359,8 @@ impl Toggle {
     }
 
     fn update_selection_box(&mut self, rq: &mut RenderQueue) {
-        let selected_label_index = if self.enabled {
-            self.left_label_index
-        } else {
-            self.right_label_index
-        };
-
-        let text_width = if self.enabled {
-            self.left_text_width
-        } else {
-            self.right_text_width
-        };
-
-        let selected_rect = *self.children[selected_label_index].rect();
+        let selected_rect = *self.children[self.label_indices[self.selected]].rect();
+        let text_width = self.text_widths[self.selected];
 
         if let Some(selection_box) =
             self.children[self.selection_box_index].downcast_mut::<SelectionBox>()
@@ -332,9 +370,21 @@ impl Toggle {
         self.request_rerender(rq);
     }
 
+    // Selects the option of the given index, or the next one if it's already selected, and
+    // emits its event.
+    fn select(&mut self, index: usize, bus: &mut Bus, rq: &mut RenderQueue) {
+        self.selected = if index == self.selected {
+            (index + 1) % self.values.len()
+        } else {
+            index
+        };
+        self.update_selection_box(rq);
+        bus.push_back(self.values[self.selected].1.clone());
+    }
+
     #[cfg(test)]
     pub fn is_enabled(&self) -> bool {
-        self.enabled
+        self.selected == 0
     }
 }
 
@@ -348,14 +398,17 @@ impl View for Toggle {
         rq: &mut RenderQueue,
         _context: &mut Context,
     ) -> bool {
-        if std::mem::discriminant(evt) == std::mem::discriminant(&self.event) {
-            self.enabled = !self.enabled;
-            self.update_selection_box(rq);
-            bus.push_back(evt.clone());
-            return true;
+        match *evt {
+            Event::SelectToggleValue(id, index) if id == self.id => {
+                self.select(index, bus, rq);
+                true
+            }
+            _ if std::mem::discriminant(evt) == std::mem::discriminant(&self.values[0].1) => {
+                self.select(self.selected, bus, rq);
+                true
+            }
+            _ => false,
         }
-
-        false
     }
 
     #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _fb, _fonts), fields(rect = ?_rect)))]
@@ -385,8 +438,10 @@ impl View for Toggle {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::color::TEXT_NORMAL;
     use crate::context::test_helpers::create_test_context;
-    use crate::view::{ToggleEvent, ViewId};
+    use crate::gesture::GestureEvent;
+    use crate::view::{EntryId, ToggleEvent, ViewId};
     use std::collections::VecDeque;
     use std::sync::mpsc::channel;
 
@@ -625,4 +680,52 @@ mod tests {
         assert!(toggle.children[2].is::<Label>());
         assert!(toggle.children[3].is::<SelectionBox>());
     }
+
+    #[test]
+    fn test_three_values_select_the_tapped_label() {
+        let mut context = create_test_context();
+        let rect = rect![0, 0, 300, 50];
+        let mut toggle = Toggle::with_values(
+            rect,
+            vec![
+                ("Off".to_string(), Event::Select(EntryId::ToggleFuzzy)),
+                ("Manual".to_string(), Event::Select(EntryId::ToggleInverted)),
+                ("Auto".to_string(), Event::Select(EntryId::ToggleDithered)),
+            ],
+            0,
+            &mut context.fonts,
+            Align::Center,
+        );
+
+        assert_eq!(toggle.children.len(), 6);
+        let auto_label = toggle.children[4].downcast_ref::<Label>().unwrap();
+        assert_eq!(auto_label.text(), "Auto");
+
+        let (hub, _receiver) = channel();
+        let mut bus = VecDeque::new();
+        let mut rq = RenderQueue::new();
+
+        let center = toggle.children[4].rect().center();
+        let tap = Event::Gesture(GestureEvent::Tap(center));
+        assert!(toggle.children[4].handle_event(&tap, &hub, &mut bus, &mut rq, &mut context));
+        let select = bus.pop_front().unwrap();
+        assert!(toggle.handle_event(&select, &hub, &mut bus, &mut rq, &mut context));
+        assert_eq!(toggle.selected(), 2);
+        assert!(matches!(
+            bus.pop_front(),
+            Some(Event::Select(EntryId::ToggleDithered))
+        ));
+
+        let other = Event::SelectToggleValue(toggle.id() + 1, 1);
+        assert!(!toggle.handle_event(&other, &hub, &mut bus, &mut rq, &mut context));
+
+        // Tapping the selected label wraps around.
+        let select = Event::SelectToggleValue(toggle.id(), 2);
+        toggle.handle_event(&select, &hub, &mut bus, &mut rq, &mut context);
+        assert_eq!(toggle.selected(), 0);
+        assert!(matches!(
+            bus.pop_front(),
+            Some(Event::Select(EntryId::ToggleFuzzy))
+        ));
+    }
 }
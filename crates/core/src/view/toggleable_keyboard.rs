@@ -3,13 +3,13 @@
 //! `ToggleableKeyboard` encapsulates the keyboard view along with its separator,
 //! providing a clean API for managing keyboard visibility in parent views.
 
-use crate::color::BLACK;
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::Fonts;
 use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::{halves, Rectangle};
-use crate::unit::scale_by_dpi;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
 use crate::view::filler::Filler;
 use crate::view::keyboard::Keyboard;
 use crate::view::{Bus, Event, Hub, Id, RenderData, RenderQueue, View, ID_FEEDER};
@@ -148,7 +148,7 @@ impl ToggleableKeyboard {
             scale_by_dpi(SMALL_BAR_HEIGHT, dpi) as i32,
             scale_by_dpi(BIG_BAR_HEIGHT, dpi) as i32,
         );
-        let thickness = scale_by_dpi(THICKNESS_MEDIUM, dpi) as i32;
+        let thickness = scale_thickness(THICKNESS_MEDIUM, dpi) as i32;
         let (_small_thickness, big_thickness) = halves(thickness);
 
         let separator = Filler::new(
@@ -158,7 +158,7 @@ impl ToggleableKeyboard {
                 self.parent_rect.max.x,
                 self.parent_rect.max.y - (small_height + 3 * big_height) + thickness
             ],
-            BLACK,
+            theme::current().foreground,
         );
         self.children.push(Box::new(separator) as Box<dyn View>);
 
@@ -3,6 +3,7 @@ use crate::font::Fonts;
 use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::Rectangle;
 use crate::gesture::GestureEvent;
+use crate::i18n::Direction;
 use crate::input::DeviceEvent;
 use crate::view::battery::Battery;
 use crate::view::clock::Clock;
@@ -17,14 +18,42 @@ pub enum TopBarVariant {
     Back,
     /// Shows a cancel/close icon and emits the specified event when clicked
     Cancel(Event),
-    /// Shows a search icon and emits the specified event when clicked (typically Event::Toggle(ViewId::SearchBar))
+    /// Shows a search icon and emits the specified event when clicked (typically Event::NewToggle(ToggleEvent::View(ViewId::SearchBar)))
     Search(Event),
 }
 
+// Index of the first action icon in the children.
+const ACTIONS_INDEX: usize = 6;
+
+/// Maximum number of action icons a top bar can hold.
+pub const MAX_ACTIONS: usize = 2;
+
+/// An icon shown on the right side of the top bar, between the title and the clock.
+#[derive(Debug, Clone)]
+pub struct TopBarAction {
+    /// Name of the icon, e.g. `"search"`.
+    pub icon: String,
+    /// Event emitted when the icon is tapped.
+    ///
+    /// The rectangle of an [`Event::ToggleNear`] event is replaced by the icon's, so a menu
+    /// opened by the action shows up below it.
+    pub event: Event,
+}
+
+impl TopBarAction {
+    pub fn new(icon: &str, event: Event) -> TopBarAction {
+        TopBarAction {
+            icon: icon.to_string(),
+            event,
+        }
+    }
+}
+
 pub struct TopBar {
     id: Id,
     rect: Rectangle,
     children: Vec<Box<dyn View>>,
+    direction: Direction,
 }
 
 impl TopBar {
@@ -36,6 +65,8 @@ impl TopBar {
     ) -> TopBar {
         let id = ID_FEEDER.next();
         let mut children = Vec::new();
+        let direction = context.direction;
+        let place = |r: Rectangle| Self::place(r, rect, direction);
 
         let side = rect.height() as i32;
         let (icon_name, root_event) = match variant {
@@ -44,14 +75,25 @@ impl TopBar {
             TopBarVariant::Search(event) => ("search", event),
         };
 
-        let root_icon = Icon::new(icon_name, rect![rect.min, rect.min + side], root_event);
+        let root_icon = Icon::new(
+            icon_name,
+            place(rect![rect.min, rect.min + side]),
+            root_event,
+        );
         children.push(Box::new(root_icon) as Box<dyn View>);
 
         let mut clock_rect = rect![rect.max - pt!(4 * side, side), rect.max - pt!(3 * side, 0)];
-        let clock_label = Clock::new(&mut clock_rect, context);
-        let title_rect = rect![rect.min.x + side, rect.min.y, clock_rect.min.x, rect.max.y];
+        let mut clock_label = Clock::new(&mut clock_rect, context);
+        *clock_label.rect_mut() = place(clock_rect);
+        let title_rect = place(rect![
+            rect.min.x + side,
+            rect.min.y,
+            clock_rect.min.x,
+            rect.max.y
+        ]);
         let title_label = Label::new(title_rect, title, Align::Center)
-            .event(Some(Event::ToggleNear(ViewId::TitleMenu, title_rect)));
+            .event(Some(Event::ToggleNear(ViewId::TitleMenu, title_rect)))
+            .hold_event(Some(Event::Show(ViewId::CommandPalette)));
         children.push(Box::new(title_label) as Box<dyn View>);
         children.push(Box::new(clock_label) as Box<dyn View>);
 
@@ -61,7 +103,10 @@ impl TopBar {
             .status()
             .map_or(crate::battery::Status::Discharging, |v| v[0]);
         let battery_widget = Battery::new(
-            rect![rect.max - pt!(3 * side, side), rect.max - pt!(2 * side, 0)],
+            place(rect![
+                rect.max - pt!(3 * side, side),
+                rect.max - pt!(2 * side, 0)
+            ]),
             capacity,
             status,
         );
@@ -74,12 +119,15 @@ impl TopBar {
         };
         let frontlight_icon = Icon::new(
             name,
-            rect![rect.max - pt!(2 * side, side), rect.max - pt!(side, 0)],
+            place(rect![
+                rect.max - pt!(2 * side, side),
+                rect.max - pt!(side, 0)
+            ]),
             Event::Show(ViewId::Frontlight),
         );
         children.push(Box::new(frontlight_icon) as Box<dyn View>);
 
-        let menu_rect = rect![rect.max - side, rect.max];
+        let menu_rect = place(rect![rect.max - side, rect.max]);
         let menu_icon = Icon::new(
             "menu",
             menu_rect,
@@ -87,7 +135,72 @@ impl TopBar {
         );
         children.push(Box::new(menu_icon) as Box<dyn View>);
 
-        TopBar { id, rect, children }
+        TopBar {
+            id,
+            rect,
+            children,
+            direction,
+        }
+    }
+
+    /// Adds action icons before the clock, the title is shrunk to make room for them.
+    ///
+    /// Only the first [`MAX_ACTIONS`] actions are kept. The icons follow the other
+    /// children and can be updated with [`TopBar::update_action_icon`].
+    pub fn actions(mut self, actions: Vec<TopBarAction>) -> TopBar {
+        let side = self.rect.height() as i32;
+        let count = actions.len().min(MAX_ACTIONS);
+        let max_x = self.place_in_bar(*self.children[2].rect()).min.x;
+
+        for (index, action) in actions.into_iter().take(count).enumerate() {
+            let rect = self.place_in_bar(Self::action_rect(self.rect, max_x, index, count));
+            let event = match action.event {
+                Event::ToggleNear(view_id, _) => Event::ToggleNear(view_id, rect),
+                event => event,
+            };
+            let icon = Icon::new(&action.icon, rect, event);
+            self.children.push(Box::new(icon) as Box<dyn View>);
+        }
+
+        let title_rect = self.place_in_bar(rect![
+            self.rect.min.x + side,
+            self.rect.min.y,
+            max_x - count as i32 * side,
+            self.rect.max.y
+        ]);
+        let title_label = self.children[1].downcast_mut::<Label>().unwrap();
+        *title_label.rect_mut() = title_rect;
+        title_label.set_event(Some(Event::ToggleNear(ViewId::TitleMenu, title_rect)));
+
+        self
+    }
+
+    // The rectangles of the children are computed from left to right, and mirrored when the
+    // user interface is laid out from right to left.
+    #[inline]
+    fn place(rect: Rectangle, bar: Rectangle, direction: Direction) -> Rectangle {
+        if direction.is_rtl() {
+            rect.mirror(&bar)
+        } else {
+            rect
+        }
+    }
+
+    #[inline]
+    fn place_in_bar(&self, rect: Rectangle) -> Rectangle {
+        Self::place(rect, self.rect, self.direction)
+    }
+
+    #[inline]
+    fn action_rect(rect: Rectangle, max_x: i32, index: usize, count: usize) -> Rectangle {
+        let side = rect.height() as i32;
+        let min_x = max_x - (count - index) as i32 * side;
+        rect![min_x, rect.min.y, min_x + side, rect.max.y]
+    }
+
+    #[inline]
+    fn actions_count(&self) -> usize {
+        self.children.len() - ACTIONS_INDEX
     }
 
     pub fn update_root_icon(&mut self, name: &str, rq: &mut RenderQueue) {
@@ -98,6 +211,19 @@ impl TopBar {
         }
     }
 
+    pub fn update_action_icon(&mut self, index: usize, name: &str, rq: &mut RenderQueue) {
+        if let Some(icon) = self
+            .children
+            .get_mut(ACTIONS_INDEX + index)
+            .and_then(|child| child.downcast_mut::<Icon>())
+        {
+            if icon.name != name {
+                icon.name = name.to_string();
+                rq.add(RenderData::new(icon.id(), *icon.rect(), UpdateMode::Gui));
+            }
+        }
+    }
+
     pub fn update_title_label(&mut self, title: &str, rq: &mut RenderQueue) {
         let title_label = self.children[1].as_mut().downcast_mut::<Label>().unwrap();
         title_label.update(title, rq);
@@ -167,32 +293,49 @@ impl View for TopBar {
 
     fn resize(&mut self, rect: Rectangle, hub: &Hub, rq: &mut RenderQueue, context: &mut Context) {
         let side = rect.height() as i32;
-        self.children[0].resize(rect![rect.min, rect.min + side], hub, rq, context);
+        let place = |r: Rectangle| Self::place(r, rect, self.direction);
+        self.children[0].resize(place(rect![rect.min, rect.min + side]), hub, rq, context);
         let clock_width = self.children[2].rect().width() as i32;
         let clock_rect = rect![
             rect.max - pt!(3 * side + clock_width, side),
             rect.max - pt!(3 * side, 0)
         ];
+        let count = self.actions_count();
         self.children[1].resize(
-            rect![rect.min.x + side, rect.min.y, clock_rect.min.x, rect.max.y],
+            place(rect![
+                rect.min.x + side,
+                rect.min.y,
+                clock_rect.min.x - count as i32 * side,
+                rect.max.y
+            ]),
             hub,
             rq,
             context,
         );
-        self.children[2].resize(clock_rect, hub, rq, context);
+        for index in 0..count {
+            let action_rect = Self::action_rect(rect, clock_rect.min.x, index, count);
+            self.children[ACTIONS_INDEX + index].resize(place(action_rect), hub, rq, context);
+        }
+        self.children[2].resize(place(clock_rect), hub, rq, context);
         self.children[3].resize(
-            rect![rect.max - pt!(3 * side, side), rect.max - pt!(2 * side, 0)],
+            place(rect![
+                rect.max - pt!(3 * side, side),
+                rect.max - pt!(2 * side, 0)
+            ]),
             hub,
             rq,
             context,
         );
         self.children[4].resize(
-            rect![rect.max - pt!(2 * side, side), rect.max - pt!(side, 0)],
+            place(rect![
+                rect.max - pt!(2 * side, side),
+                rect.max - pt!(side, 0)
+            ]),
             hub,
             rq,
             context,
         );
-        self.children[5].resize(rect![rect.max - side, rect.max], hub, rq, context);
+        self.children[5].resize(place(rect![rect.max - side, rect.max]), hub, rq, context);
         self.rect = rect;
     }
 
@@ -216,3 +359,68 @@ impl View for TopBar {
         self.id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_helpers::create_test_context;
+    use crate::view::ToggleEvent;
+
+    #[test]
+    fn test_actions_sit_between_title_and_clock() {
+        let mut context = create_test_context();
+        let top_bar = TopBar::new(
+            rect![0, 0, 600, 60],
+            TopBarVariant::Back,
+            "Library".to_string(),
+            &mut context,
+        )
+        .actions(vec![
+            TopBarAction::new(
+                "search",
+                Event::NewToggle(ToggleEvent::View(ViewId::SearchBar)),
+            ),
+            TopBarAction::new(
+                "enclosed_menu",
+                Event::ToggleNear(ViewId::TitleMenu, Rectangle::default()),
+            ),
+            TopBarAction::new("home", Event::Back),
+        ]);
+
+        assert_eq!(top_bar.actions_count(), MAX_ACTIONS);
+
+        let clock = *top_bar.children[2].rect();
+        let search = *top_bar.children[ACTIONS_INDEX].rect();
+        let overflow = *top_bar.children[ACTIONS_INDEX + 1].rect();
+        assert_eq!(overflow.max.x, clock.min.x);
+        assert_eq!(search.max.x, overflow.min.x);
+        assert_eq!(top_bar.children[1].rect().max.x, search.min.x);
+    }
+
+    #[test]
+    fn test_right_to_left_layout_is_mirrored() {
+        let mut context = create_test_context();
+        context.direction = Direction::RightToLeft;
+        let rect = rect![0, 0, 600, 60];
+        let top_bar = TopBar::new(
+            rect,
+            TopBarVariant::Back,
+            "Library".to_string(),
+            &mut context,
+        )
+        .actions(vec![TopBarAction::new(
+            "search",
+            Event::NewToggle(ToggleEvent::View(ViewId::SearchBar)),
+        )]);
+
+        let root = *top_bar.children[0].rect();
+        let menu = *top_bar.children[5].rect();
+        let clock = *top_bar.children[2].rect();
+        let search = *top_bar.children[ACTIONS_INDEX].rect();
+        assert_eq!(root.max.x, rect.max.x);
+        assert_eq!(menu.min.x, rect.min.x);
+        assert_eq!(search.min.x, clock.max.x);
+        assert_eq!(top_bar.children[1].rect().min.x, search.max.x);
+        assert_eq!(top_bar.children[1].rect().max.x, root.min.x);
+    }
+}
@@ -1,9 +1,10 @@
-use crate::color::{BLACK, GRAY05, GRAY10, WHITE};
+use crate::color::{GRAY05, GRAY10};
 use crate::context::Context;
 use crate::device::CURRENT_DEVICE;
 use crate::font::Fonts;
 use crate::framebuffer::{Framebuffer, UpdateMode};
 use crate::geom::{CornerSpec, Dir, Rectangle, Region};
+use crate::theme;
 use crate::unit::scale_by_dpi;
 use crate::view::icon::Icon;
 use crate::view::notification::Notification;
@@ -79,10 +80,10 @@ impl View for TouchEvents {
                     self.strip_width,
                     self.corner_width,
                 ) {
-                    Region::Corner(..) => BLACK,
+                    Region::Corner(..) => theme::current().foreground,
                     Region::Strip(Dir::West) | Region::Strip(Dir::East) => GRAY05,
                     Region::Strip(Dir::South) | Region::Strip(Dir::North) => GRAY10,
-                    Region::Center => WHITE,
+                    Region::Center => theme::current().background,
                 };
                 fb.set_pixel(x as u32, y as u32, color);
             }
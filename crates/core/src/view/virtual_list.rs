@@ -0,0 +1,273 @@
+//! A paged list that only builds the rows it shows.
+//!
+//! The items of a [`VirtualList`] come from a [`ListProvider`]: the list asks for the number
+//! of items and creates the views of the rows of the current page only, so a list of
+//! thousands of search results or annotations costs no more than a single page of them.
+//!
+//! There's no kinetic scrolling, which suits e-ink displays poorly: swiping west or north
+//! shows the next page, swiping east or south shows the previous one.
+
+use super::filler::Filler;
+use super::{Bus, Event, Hub, Id, RenderData, RenderQueue, View, ID_FEEDER};
+use super::{BIG_BAR_HEIGHT, THICKNESS_SMALL};
+use crate::context::Context;
+use crate::device::CURRENT_DEVICE;
+use crate::font::Fonts;
+use crate::framebuffer::{Framebuffer, UpdateMode};
+use crate::geom::{CycleDir, Dir, Rectangle};
+use crate::gesture::GestureEvent;
+use crate::theme;
+use crate::unit::{scale_by_dpi, scale_thickness};
+
+/// Domain adapter for [`VirtualList`].
+///
+/// A `ListProvider` owns the items and knows how to turn one of them into a row view.
+pub trait ListProvider {
+    /// Returns the number of items.
+    fn item_count(&self) -> usize;
+
+    /// Creates the view of the item at `index`, occupying `rect`.
+    fn row(&self, index: usize, rect: Rectangle, context: &mut Context) -> Box<dyn View>;
+
+    /// Returns the height of a row, separator excluded.
+    fn row_height(&self) -> i32 {
+        scale_by_dpi(BIG_BAR_HEIGHT, CURRENT_DEVICE.dpi) as i32
+    }
+}
+
+pub struct VirtualList<P: ListProvider> {
+    id: Id,
+    rect: Rectangle,
+    children: Vec<Box<dyn View>>,
+    provider: P,
+    current_page: usize,
+}
+
+impl<P: ListProvider> VirtualList<P> {
+    pub fn new(rect: Rectangle, provider: P, context: &mut Context) -> VirtualList<P> {
+        let mut list = VirtualList {
+            id: ID_FEEDER.next(),
+            rect,
+            children: Vec::new(),
+            provider,
+            current_page: 0,
+        };
+        list.build_rows(context);
+        list
+    }
+
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Gives access to the items, call [`VirtualList::reload`] after changing them.
+    pub fn provider_mut(&mut self) -> &mut P {
+        &mut self.provider
+    }
+
+    pub fn current_page(&self) -> usize {
+        self.current_page
+    }
+
+    pub fn rows_per_page(&self) -> usize {
+        let thickness = scale_thickness(THICKNESS_SMALL, CURRENT_DEVICE.dpi) as i32;
+        let row_height = self.provider.row_height() + thickness;
+        (self.rect.height() as i32 / row_height.max(1)).max(1) as usize
+    }
+
+    pub fn pages_count(&self) -> usize {
+        self.provider
+            .item_count()
+            .div_ceil(self.rows_per_page())
+            .max(1)
+    }
+
+    /// Shows the given page, clamped to the last one.
+    pub fn go_to_page(&mut self, index: usize, rq: &mut RenderQueue, context: &mut Context) {
+        let index = index.min(self.pages_count() - 1);
+        if index != self.current_page {
+            self.current_page = index;
+            self.build_rows(context);
+            rq.add(RenderData::new(self.id, self.rect, UpdateMode::Partial));
+        }
+    }
+
+    /// Moves to the next or the previous page, returns whether the page changed.
+    pub fn page(&mut self, dir: CycleDir, rq: &mut RenderQueue, context: &mut Context) -> bool {
+        let current_page = self.current_page;
+        match dir {
+            CycleDir::Next => self.go_to_page(current_page + 1, rq, context),
+            CycleDir::Previous if current_page > 0 => {
+                self.go_to_page(current_page - 1, rq, context)
+            }
+            CycleDir::Previous => (),
+        }
+        self.current_page != current_page
+    }
+
+    /// Rebuilds the rows after the items of the provider changed.
+    pub fn reload(&mut self, rq: &mut RenderQueue, context: &mut Context) {
+        self.current_page = self.current_page.min(self.pages_count() - 1);
+        self.build_rows(context);
+        rq.add(RenderData::new(self.id, self.rect, UpdateMode::Gui));
+    }
+
+    fn build_rows(&mut self, context: &mut Context) {
+        let thickness = scale_thickness(THICKNESS_SMALL, CURRENT_DEVICE.dpi) as i32;
+        let row_height = self.provider.row_height();
+        let rows_per_page = self.rows_per_page();
+        let first = self.current_page * rows_per_page;
+        let last = (first + rows_per_page).min(self.provider.item_count());
+
+        self.children.clear();
+        let mut y_pos = self.rect.min.y;
+
+        for index in first..last {
+            let rect = rect![self.rect.min.x, y_pos, self.rect.max.x, y_pos + row_height];
+            self.children.push(self.provider.row(index, rect, context));
+            y_pos += row_height;
+
+            let separator = Filler::new(
+                rect![self.rect.min.x, y_pos, self.rect.max.x, y_pos + thickness],
+                theme::current().separator_normal,
+            );
+            self.children.push(Box::new(separator) as Box<dyn View>);
+            y_pos += thickness;
+        }
+    }
+}
+
+impl<P: ListProvider + 'static> View for VirtualList<P> {
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, _hub, _bus, rq, context), fields(event = ?evt), ret(level=tracing::Level::TRACE)))]
+    fn handle_event(
+        &mut self,
+        evt: &Event,
+        _hub: &Hub,
+        _bus: &mut Bus,
+        rq: &mut RenderQueue,
+        context: &mut Context,
+    ) -> bool {
+        match *evt {
+            Event::Gesture(GestureEvent::Swipe { dir, start, .. }) if self.rect.includes(start) => {
+                match dir {
+                    Dir::West | Dir::North => {
+                        self.page(CycleDir::Next, rq, context);
+                    }
+                    Dir::East | Dir::South => {
+                        self.page(CycleDir::Previous, rq, context);
+                    }
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    #[cfg_attr(feature = "otel", tracing::instrument(skip(self, fb, _fonts), fields(rect = ?rect)))]
+    fn render(&self, fb: &mut dyn Framebuffer, rect: Rectangle, _fonts: &mut Fonts) {
+        // The area below the last row of the last page.
+        let bottom = self
+            .children
+            .last()
+            .map_or(self.rect.min.y, |child| child.rect().max.y);
+        if let Some(empty) = rect.intersection(&rect![
+            self.rect.min.x,
+            bottom,
+            self.rect.max.x,
+            self.rect.max.y
+        ]) {
+            fb.draw_rectangle(&empty, theme::current().background);
+        }
+    }
+
+    fn resize(
+        &mut self,
+        rect: Rectangle,
+        _hub: &Hub,
+        _rq: &mut RenderQueue,
+        context: &mut Context,
+    ) {
+        let first_item = self.current_page * self.rows_per_page();
+        self.rect = rect;
+        self.current_page = (first_item / self.rows_per_page()).min(self.pages_count() - 1);
+        self.build_rows(context);
+    }
+
+    fn rect(&self) -> &Rectangle {
+        &self.rect
+    }
+
+    fn rect_mut(&mut self) -> &mut Rectangle {
+        &mut self.rect
+    }
+
+    fn children(&self) -> &Vec<Box<dyn View>> {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<Box<dyn View>> {
+        &mut self.children
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::test_helpers::create_test_context;
+    use crate::view::label::Label;
+    use crate::view::Align;
+
+    struct Items(Vec<String>);
+
+    impl ListProvider for Items {
+        fn item_count(&self) -> usize {
+            self.0.len()
+        }
+
+        fn row(&self, index: usize, rect: Rectangle, _context: &mut Context) -> Box<dyn View> {
+            Box::new(Label::new(rect, self.0[index].clone(), Align::Center))
+        }
+
+        fn row_height(&self) -> i32 {
+            99
+        }
+    }
+
+    fn labels(list: &VirtualList<Items>) -> Vec<String> {
+        list.children()
+            .iter()
+            .filter_map(|child| child.downcast_ref::<Label>())
+            .map(|label| label.text().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_only_the_current_page_is_built() {
+        let mut context = create_test_context();
+        let mut rq = RenderQueue::new();
+        let items = Items((0..1000).map(|i| i.to_string()).collect());
+        let mut list = VirtualList::new(rect![0, 0, 600, 400], items, &mut context);
+
+        let rows_per_page = list.rows_per_page();
+        assert_eq!(rows_per_page, 4);
+        assert_eq!(list.pages_count(), 250);
+        assert_eq!(labels(&list), ["0", "1", "2", "3"]);
+
+        assert!(!list.page(CycleDir::Previous, &mut rq, &mut context));
+        assert!(list.page(CycleDir::Next, &mut rq, &mut context));
+        assert_eq!(labels(&list), ["4", "5", "6", "7"]);
+
+        list.go_to_page(usize::MAX, &mut rq, &mut context);
+        assert_eq!(list.current_page(), 249);
+        assert!(!list.page(CycleDir::Next, &mut rq, &mut context));
+
+        list.provider_mut().0.truncate(6);
+        list.reload(&mut rq, &mut context);
+        assert_eq!(list.current_page(), 1);
+        assert_eq!(labels(&list), ["4", "5"]);
+    }
+}
@@ -0,0 +1,175 @@
+//! A remote control of the device, for home automation setups and accessibility tools.
+//!
+//! The API is only served once a token is set in the settings, and each request must carry
+//! it as `Authorization: Bearer <token>`. The actions are sent to the application as
+//! events, as if they came from the device itself:
+//!
+//! - `POST /api/page/next` and `POST /api/page/previous` turn the page.
+//! - `POST /api/frontlight/toggle` toggles the frontlight.
+//! - `POST /api/open` opens the book whose path, relative to the current library, is the
+//!   body of the request.
+//! - `GET /api/status` answers the state of the device and of the book being read, in JSON.
+
+use super::http::{Request, Response};
+use super::Site;
+use crate::context::Context;
+use crate::geom::CycleDir;
+use crate::view::reader::Reader;
+use crate::view::Event;
+use secrecy::ExposeSecret;
+use serde::Serialize;
+use std::io::{BufRead, Read};
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// How long the application has to answer a status request.
+const STATUS_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The largest path accepted by `/api/open`.
+const MAX_PATH_SIZE: u64 = 4096;
+
+/// The state of the device, as answered by the application.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Status {
+    /// The charge of the battery, in percents.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub battery: Option<f32>,
+    pub frontlight: bool,
+    pub wifi: bool,
+    pub online: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub book: Option<BookStatus>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BookStatus {
+    /// The path of the book, relative to its library.
+    pub path: PathBuf,
+    pub title: String,
+    pub author: String,
+    /// The current page, starting at one.
+    pub page: usize,
+    pub pages: usize,
+}
+
+impl Status {
+    /// Gathers the state of the device, and of the book open in the given reader.
+    pub fn new(context: &mut Context, reader: Option<&Reader>) -> Status {
+        Status {
+            battery: context
+                .battery
+                .capacity()
+                .ok()
+                .and_then(|capacity| capacity.first().copied()),
+            frontlight: context.settings.frontlight,
+            wifi: context.settings.wifi,
+            online: context.online,
+            book: reader.map(|reader| {
+                let info = reader.info();
+                let (current_page, pages_count) = reader.position();
+                BookStatus {
+                    path: info.file.path.clone(),
+                    title: info.title.clone(),
+                    author: info.author.clone(),
+                    page: current_page + 1,
+                    pages: pages_count,
+                }
+            }),
+        }
+    }
+}
+
+impl Site {
+    pub(super) fn api<R: BufRead>(
+        &self,
+        request: &Request,
+        segments: &[String],
+        body: &mut R,
+    ) -> Response {
+        let Some(token) = self.api_token.as_ref() else {
+            return Response::not_found();
+        };
+        let authorized = request
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|given| {
+                constant_time_eq(given.trim().as_bytes(), token.expose_secret().as_bytes())
+            });
+        if !authorized {
+            return Response::text(401, "Unauthorized")
+                .with_header("WWW-Authenticate", "Bearer realm=\"cadmus\"");
+        }
+
+        let segments = segments.iter().map(String::as_str).collect::<Vec<&str>>();
+        match (request.method.as_str(), segments.as_slice()) {
+            ("POST", ["page", "next"]) => self.act(Event::Page(CycleDir::Next)),
+            ("POST", ["page", "previous"]) => self.act(Event::Page(CycleDir::Previous)),
+            ("POST", ["frontlight", "toggle"]) => self.act(Event::ToggleFrontlight),
+            ("POST", ["open"]) => self.open(request, body),
+            ("GET", ["status"]) => self.status(),
+            (_, ["page", "next" | "previous"] | ["frontlight", "toggle"] | ["open"]) => {
+                Response::text(405, "Method Not Allowed").with_header("Allow", "POST")
+            }
+            (_, ["status"]) => {
+                Response::text(405, "Method Not Allowed").with_header("Allow", "GET")
+            }
+            _ => Response::not_found(),
+        }
+    }
+
+    fn act(&self, event: Event) -> Response {
+        match self.hub.send(event) {
+            Ok(()) => Response::text(202, "Accepted"),
+            Err(_) => Response::text(503, "The application isn't running."),
+        }
+    }
+
+    fn open<R: BufRead>(&self, request: &Request, body: &mut R) -> Response {
+        let Some(length) = request.content_length() else {
+            return Response::text(411, "Length Required");
+        };
+        if length > MAX_PATH_SIZE {
+            return Response::text(400, "The path is too long.");
+        }
+        let mut path = String::new();
+        if body.take(length).read_to_string(&mut path).is_err() {
+            return Response::text(400, "The path isn't valid UTF-8.");
+        }
+        let path = Path::new(path.trim());
+        if path.as_os_str().is_empty() {
+            return Response::text(400, "The body must hold the path of the book.");
+        }
+        if !path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Response::text(403, "The path must be relative to the library.");
+        }
+        self.act(Event::RemoteOpen(path.to_path_buf()))
+    }
+
+    fn status(&self) -> Response {
+        let (sender, receiver) = mpsc::channel();
+        if self.hub.send(Event::RemoteStatus(sender)).is_err() {
+            return Response::text(503, "The application isn't running.");
+        }
+        match receiver.recv_timeout(STATUS_TIMEOUT) {
+            Ok(status) => match serde_json::to_vec(&status) {
+                Ok(json) => Response::new(200, "application/json", json),
+                Err(e) => Response::text(500, &e.to_string()),
+            },
+            Err(_) => Response::text(503, "The application didn't answer."),
+        }
+    }
+}
+
+/// Compares the tokens without leaking, through the time it takes, how much of them match.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
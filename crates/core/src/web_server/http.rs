@@ -0,0 +1,266 @@
+//! Just enough of HTTP/1.1 to talk to web browsers: every response closes the connection.
+
+use anyhow::{format_err, Error};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use std::fs::File;
+use std::io::{self, BufRead, Read, Write};
+use std::path::Path;
+
+/// The maximum size of the request line and headers.
+const MAX_HEAD_SIZE: u64 = 16 * 1024;
+
+/// The characters escaped in the segments of a path.
+const SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+#[derive(Debug)]
+pub struct Request {
+    pub method: String,
+    /// The path, as sent, without the query.
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl Request {
+    /// Reads the request line and the headers, leaving the body in the reader.
+    pub fn read<R: BufRead>(reader: &mut R) -> Result<Request, Error> {
+        let mut head = reader.by_ref().take(MAX_HEAD_SIZE);
+        let mut line = String::new();
+        head.read_line(&mut line)?;
+        let mut parts = line.split_whitespace();
+        let (method, target) = parts
+            .next()
+            .zip(parts.next())
+            .ok_or_else(|| format_err!("invalid request line: {:?}", line.trim_end()))?;
+        let path = target.split('?').next().unwrap_or_default().to_string();
+        let method = method.to_string();
+
+        let mut headers = Vec::new();
+        loop {
+            line.clear();
+            if head.read_line(&mut line)? == 0 {
+                return Err(format_err!("unexpected end of the headers"));
+            }
+            let header = line.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+            }
+        }
+
+        Ok(Request {
+            method,
+            path,
+            headers,
+        })
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn content_length(&self) -> Option<u64> {
+        self.header("content-length")
+            .and_then(|value| value.parse().ok())
+    }
+
+    /// Returns the decoded segments of the path, ignoring the empty ones.
+    pub fn segments(&self) -> Vec<String> {
+        self.path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| percent_decode_str(segment).decode_utf8_lossy().into_owned())
+            .collect()
+    }
+}
+
+/// Joins the given segments into a path, escaping them.
+pub fn encode_path<S: AsRef<str>>(segments: &[S]) -> String {
+    let mut path = String::new();
+    for segment in segments {
+        path.push('/');
+        path.extend(utf8_percent_encode(segment.as_ref(), SEGMENT));
+    }
+    path
+}
+
+enum Body {
+    Bytes(Vec<u8>),
+    File(File, u64),
+}
+
+pub struct Response {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Body,
+}
+
+impl Response {
+    pub fn new(status: u16, content_type: &str, body: Vec<u8>) -> Response {
+        Response {
+            status,
+            headers: vec![("Content-Type".to_string(), content_type.to_string())],
+            body: Body::Bytes(body),
+        }
+    }
+
+    pub fn html(body: String) -> Response {
+        Response::new(200, "text/html; charset=utf-8", body.into_bytes())
+    }
+
+    /// A response explaining its status to a human.
+    pub fn text(status: u16, message: &str) -> Response {
+        Response::new(
+            status,
+            "text/plain; charset=utf-8",
+            format!("{}\n", message).into_bytes(),
+        )
+    }
+
+    pub fn not_found() -> Response {
+        Response::text(404, "Not Found")
+    }
+
+    /// Sends the given file as an attachment.
+    pub fn file(path: &Path) -> io::Result<Response> {
+        let file = File::open(path)?;
+        let size = file.metadata()?.len();
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let content_type = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| mime_type(&ext.to_lowercase()))
+            .unwrap_or("application/octet-stream");
+        Ok(Response {
+            status: 200,
+            headers: vec![
+                ("Content-Type".to_string(), content_type.to_string()),
+                (
+                    "Content-Disposition".to_string(),
+                    format!(
+                        "attachment; filename*=UTF-8''{}",
+                        utf8_percent_encode(&name, SEGMENT)
+                    ),
+                ),
+            ],
+            body: Body::File(file, size),
+        })
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Response {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    pub fn write_to<W: Write>(self, writer: &mut W) -> io::Result<()> {
+        let length = match &self.body {
+            Body::Bytes(bytes) => bytes.len() as u64,
+            Body::File(_, size) => *size,
+        };
+        write!(
+            writer,
+            "HTTP/1.1 {} {}\r\n",
+            self.status,
+            reason(self.status)
+        )?;
+        for (name, value) in &self.headers {
+            write!(writer, "{}: {}\r\n", name, value)?;
+        }
+        write!(
+            writer,
+            "Content-Length: {}\r\nConnection: close\r\n\r\n",
+            length
+        )?;
+        match self.body {
+            Body::Bytes(bytes) => writer.write_all(&bytes)?,
+            Body::File(mut file, _) => {
+                io::copy(&mut file, writer)?;
+            }
+        }
+        writer.flush()
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        415 => "Unsupported Media Type",
+        503 => "Service Unavailable",
+        507 => "Insufficient Storage",
+        _ => "Internal Server Error",
+    }
+}
+
+pub fn mime_type(extension: &str) -> &'static str {
+    match extension {
+        "epub" => "application/epub+zip",
+        "pdf" => "application/pdf",
+        "djvu" | "djv" => "image/vnd.djvu",
+        "cbz" => "application/vnd.comicbook+zip",
+        "fb2" => "application/x-fictionbook+xml",
+        "mobi" => "application/x-mobipocket-ebook",
+        "xps" | "oxps" => "application/oxps",
+        "txt" => "text/plain; charset=utf-8",
+        "html" | "htm" => "text/html; charset=utf-8",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufReader;
+
+    #[test]
+    fn test_read_request() {
+        let data = b"PUT /library/0/Sci%20Fi/Dune.epub?v=2 HTTP/1.1\r\n\
+                     Host: kobo:8080\r\nContent-Length: 4\r\n\r\nbody";
+        let mut reader = BufReader::new(&data[..]);
+        let request = Request::read(&mut reader).unwrap();
+        assert_eq!(request.method, "PUT");
+        assert_eq!(request.segments(), ["library", "0", "Sci Fi", "Dune.epub"]);
+        assert_eq!(request.header("Host"), Some("kobo:8080"));
+        assert_eq!(request.content_length(), Some(4));
+        let mut body = String::new();
+        reader.read_to_string(&mut body).unwrap();
+        assert_eq!(body, "body");
+
+        let mut reader = BufReader::new(&b"GET / HTTP/1.1\r\nHost: kobo"[..]);
+        assert!(Request::read(&mut reader).is_err());
+    }
+
+    #[test]
+    fn test_encode_path() {
+        assert_eq!(
+            encode_path(&["library", "0", "L'Étranger & co.epub"]),
+            "/library/0/L%27%C3%89tranger%20%26%20co.epub"
+        );
+    }
+}
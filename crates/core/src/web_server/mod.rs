@@ -0,0 +1,695 @@
+//! A web server for browsing the libraries from a web browser on the same network.
+//!
+//! The books can be downloaded, and new ones uploaded into any directory of a library:
+//! they're imported as soon as they've been received. The libraries are also published as
+//! an OPDS catalog, under `/opds`, and the device can be driven remotely through `/api`.
+
+pub mod api;
+mod http;
+mod opds;
+
+use self::http::{encode_path, Request, Response};
+use crate::document::HumanSize;
+use crate::helpers::escape_html;
+use crate::settings::Settings;
+use crate::storage;
+use crate::view::{Event, Hub};
+use anyhow::Error;
+use fxhash::FxHashSet;
+use secrecy::SecretString;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+/// How often the listener checks whether it should stop.
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How long a connection can stay silent.
+const IO_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The number of connections served at once, the others are turned away.
+const MAX_CONNECTIONS: usize = 8;
+
+/// The space left free on the partition of the library after an upload, in bytes.
+const FREE_SPACE_MARGIN: u64 = 16 * 1024 * 1024;
+
+/// The server runs until it's dropped.
+pub struct WebServer {
+    port: u16,
+    running: Arc<AtomicBool>,
+}
+
+impl WebServer {
+    pub fn start(settings: &Settings, hub: &Hub) -> Result<WebServer, Error> {
+        let listener = TcpListener::bind(("0.0.0.0", settings.web_server.port))?;
+        listener.set_nonblocking(true)?;
+        let port = listener.local_addr()?.port();
+        let running = Arc::new(AtomicBool::new(true));
+        let site = Arc::new(Site {
+            libraries: settings
+                .libraries
+                .iter()
+                .map(|library| (library.name.clone(), library.path.clone()))
+                .collect(),
+            kinds: settings.import.allowed_kinds.clone(),
+            api_token: settings.web_server.api_token.clone(),
+            max_upload_size: settings
+                .web_server
+                .max_upload_size
+                .saturating_mul(1024 * 1024),
+            hub: hub.clone(),
+        });
+        let running2 = running.clone();
+        let connections = Arc::new(AtomicUsize::new(0));
+        thread::spawn(move || {
+            while running2.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        if connections.fetch_add(1, Ordering::AcqRel) >= MAX_CONNECTIONS {
+                            connections.fetch_sub(1, Ordering::AcqRel);
+                            refuse(stream);
+                            continue;
+                        }
+                        let site = site.clone();
+                        let connections = connections.clone();
+                        thread::spawn(move || {
+                            if let Err(e) = site.serve(stream) {
+                                warn!("can't serve the request: {:#}", e);
+                            }
+                            connections.fetch_sub(1, Ordering::AcqRel);
+                        });
+                    }
+                    Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
+                        thread::sleep(ACCEPT_INTERVAL);
+                    }
+                    Err(e) => {
+                        error!("can't accept a connection: {:#}", e);
+                        thread::sleep(ACCEPT_INTERVAL);
+                    }
+                }
+            }
+        });
+        info!(port, "web server started");
+        Ok(WebServer { port, running })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+}
+
+impl Drop for WebServer {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
+
+// A file's name and metadata.
+type FileEntry = (String, fs::Metadata);
+
+struct Site {
+    // The names and paths of the libraries.
+    libraries: Vec<(String, PathBuf)>,
+    kinds: FxHashSet<String>,
+    // The remote API is disabled without a token.
+    api_token: Option<SecretString>,
+    // In bytes.
+    max_upload_size: u64,
+    hub: Hub,
+}
+
+// Answers a connection beyond the limit without reading its request.
+fn refuse(stream: TcpStream) {
+    let result = stream
+        .set_nonblocking(false)
+        .and_then(|_| stream.set_write_timeout(Some(IO_TIMEOUT)))
+        .and_then(|_| {
+            let mut writer = &stream;
+            Response::text(503, "Too many connections, try again later.")
+                .with_header("Retry-After", "5")
+                .write_to(&mut writer)
+        });
+    if let Err(e) = result {
+        debug!("can't turn the connection away: {:#}", e);
+    }
+}
+
+impl Site {
+    fn serve(&self, stream: TcpStream) -> Result<(), Error> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(IO_TIMEOUT))?;
+        stream.set_write_timeout(Some(IO_TIMEOUT))?;
+        let mut writer = stream.try_clone()?;
+        let mut reader = BufReader::new(stream);
+        let response = match Request::read(&mut reader) {
+            Ok(request) => {
+                let response = self.respond(&request, &mut reader);
+                debug!(method = %request.method, path = %request.path, status = response.status());
+                response
+            }
+            Err(e) => Response::text(400, &format!("{:#}", e)),
+        };
+        response.write_to(&mut writer)?;
+        Ok(())
+    }
+
+    fn respond<R: BufRead>(&self, request: &Request, body: &mut R) -> Response {
+        let segments = request.segments();
+        match (
+            request.method.as_str(),
+            segments.first().map(String::as_str),
+        ) {
+            ("GET", None) => Response::html(self.index()),
+            ("GET", Some("opds")) => self.opds(&segments[1..]),
+            (_, Some("api")) => self.api(request, &segments[1..], body),
+            (method, Some("library")) if segments.len() >= 2 => {
+                let Some((index, name, root)) = self.library(&segments[1]) else {
+                    return Response::not_found();
+                };
+                let rest = &segments[2..];
+                match method {
+                    "GET" => self.get(index, name, root, rest),
+                    "PUT" => self.put(root, rest, request, body),
+                    _ => Response::text(405, "Method Not Allowed").with_header("Allow", "GET, PUT"),
+                }
+            }
+            _ => Response::not_found(),
+        }
+    }
+
+    /// Returns the index, name and root of the library designated by the given segment.
+    fn library(&self, segment: &str) -> Option<(usize, &str, &Path)> {
+        let index = segment.parse::<usize>().ok()?;
+        self.libraries
+            .get(index)
+            .map(|(name, root)| (index, name.as_str(), root.as_path()))
+    }
+
+    fn get(&self, index: usize, name: &str, root: &Path, segments: &[String]) -> Response {
+        let Some(path) = resolve(root, segments).filter(|path| is_within(root, path)) else {
+            return Response::not_found();
+        };
+        if path.is_dir() {
+            match self.directory(index, name, &path, segments) {
+                Ok(body) => Response::html(body),
+                Err(e) => Response::text(500, &format!("{:#}", e)),
+            }
+        } else if self.is_allowed(&path) {
+            Response::file(&path).unwrap_or_else(|_| Response::not_found())
+        } else {
+            Response::not_found()
+        }
+    }
+
+    fn put<R: BufRead>(
+        &self,
+        root: &Path,
+        segments: &[String],
+        request: &Request,
+        body: &mut R,
+    ) -> Response {
+        let Some(path) = resolve(root, segments) else {
+            return Response::text(403, "Forbidden");
+        };
+        let Some(parent) = path
+            .parent()
+            .filter(|parent| !segments.is_empty() && parent.is_dir() && is_within(root, parent))
+        else {
+            return Response::not_found();
+        };
+        if !self.is_allowed(&path) {
+            return Response::text(415, "This kind of file can't be imported.");
+        }
+        if path.exists() {
+            return Response::text(409, "A file with this name already exists.");
+        }
+        let Some(length) = request.content_length() else {
+            return Response::text(411, "Length Required");
+        };
+        if length > self.max_upload_size {
+            return Response::text(
+                413,
+                &format!(
+                    "The file is larger than {}.",
+                    self.max_upload_size.human_size()
+                ),
+            );
+        }
+        if storage::space(parent)
+            .is_some_and(|(free, _)| length.saturating_add(FREE_SPACE_MARGIN) > free)
+        {
+            return Response::text(507, "There isn't enough free space for this file.");
+        }
+
+        // The file is hidden from the import until it's complete.
+        let name = segments[segments.len() - 1].as_str();
+        let partial = parent.join(format!(".{}.part", name));
+        let result = File::create(&partial).and_then(|mut file| {
+            let copied = io::copy(&mut body.take(length), &mut file)?;
+            if copied < length {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "the upload was interrupted",
+                ));
+            }
+            file.sync_all()?;
+            fs::rename(&partial, &path)
+        });
+        match result {
+            Ok(()) => {
+                info!(path = %path.display(), "file received");
+                self.hub.send(Event::FileReceived(path)).ok();
+                Response::text(201, "Created")
+            }
+            Err(e) => {
+                fs::remove_file(&partial).ok();
+                Response::text(500, &format!("Can't save the file: {}.", e))
+            }
+        }
+    }
+
+    fn is_allowed(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| self.kinds.contains(&ext.to_lowercase()))
+    }
+
+    /// Lists the visible directories and the allowed files of the given directory, by name.
+    fn entries(&self, path: &Path) -> io::Result<(Vec<String>, Vec<FileEntry>)> {
+        let mut dirs = Vec::new();
+        let mut files = Vec::new();
+        for entry in fs::read_dir(path)?.filter_map(|entry| entry.ok()) {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.') {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                dirs.push(name);
+            } else if self.is_allowed(&entry.path()) {
+                files.push((name, metadata));
+            }
+        }
+        dirs.sort_by_key(|name| name.to_lowercase());
+        files.sort_by_key(|(name, _)| name.to_lowercase());
+        Ok((dirs, files))
+    }
+
+    fn index(&self) -> String {
+        let mut body = String::from("<h1>Libraries</h1>\n<ul>\n");
+        for (index, (name, _)) in self.libraries.iter().enumerate() {
+            body.push_str(&format!(
+                "<li><a href=\"{}/\">{}</a></li>\n",
+                encode_path(&["library", &index.to_string()]),
+                escape_html(name)
+            ));
+        }
+        body.push_str("</ul>\n<p><a href=\"/opds\">OPDS catalog</a></p>\n");
+        page("Libraries", &body)
+    }
+
+    fn directory(
+        &self,
+        index: usize,
+        name: &str,
+        path: &Path,
+        segments: &[String],
+    ) -> Result<String, Error> {
+        let mut base = vec!["library".to_string(), index.to_string()];
+        let mut body = format!(
+            "<p><a href=\"/\">Libraries</a> / <a href=\"{}/\">{}</a>",
+            encode_path(&base),
+            escape_html(name)
+        );
+        for segment in segments {
+            base.push(segment.clone());
+            body.push_str(&format!(
+                " / <a href=\"{}/\">{}</a>",
+                encode_path(&base),
+                escape_html(segment)
+            ));
+        }
+        body.push_str("</p>\n");
+
+        let (dirs, files) = self.entries(path)?;
+        body.push_str("<ul>\n");
+        for dir in &dirs {
+            let mut link = base.clone();
+            link.push(dir.clone());
+            body.push_str(&format!(
+                "<li class=\"dir\"><a href=\"{}/\">{}/</a></li>\n",
+                encode_path(&link),
+                escape_html(dir)
+            ));
+        }
+        for (file, metadata) in &files {
+            let mut link = base.clone();
+            link.push(file.clone());
+            body.push_str(&format!(
+                "<li><a href=\"{}\">{}</a> <small>{}</small></li>\n",
+                encode_path(&link),
+                escape_html(file),
+                metadata.len().human_size()
+            ));
+        }
+        if dirs.is_empty() && files.is_empty() {
+            body.push_str("<li><em>Empty</em></li>\n");
+        }
+        body.push_str("</ul>\n");
+
+        let mut accept = self
+            .kinds
+            .iter()
+            .map(|kind| format!(".{}", kind))
+            .collect::<Vec<_>>();
+        accept.sort();
+        body.push_str(
+            &UPLOAD_FORM
+                .replace("{accept}", &escape_html(&accept.join(",")))
+                .replace("{base}", &encode_path(&base)),
+        );
+
+        Ok(page(segments.last().map_or(name, String::as_str), &body))
+    }
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{} — Cadmus</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape_html(title),
+        STYLE,
+        body
+    )
+}
+
+// Each file is sent in its own request, which the library directory receives.
+const UPLOAD_FORM: &str = r#"<form id="upload">
+<input type="file" id="files" accept="{accept}" multiple>
+<button>Upload</button>
+</form>
+<p id="status"></p>
+<script>
+document.getElementById("upload").onsubmit = async (event) => {
+  event.preventDefault();
+  const status = document.getElementById("status");
+  for (const file of document.getElementById("files").files) {
+    status.textContent = "Uploading " + file.name + "…";
+    const response = await fetch("{base}/" + encodeURIComponent(file.name), {method: "PUT", body: file});
+    if (!response.ok) {
+      status.textContent = file.name + ": " + await response.text();
+      return;
+    }
+  }
+  location.reload();
+};
+</script>
+"#;
+
+const STYLE: &str = "body{font-family:sans-serif;max-width:40em;margin:auto;padding:1em}\
+                     li{margin:.4em 0}.dir{font-weight:bold}small{color:#666}";
+
+/// Appends the given segments to the root, refusing the hidden ones and those that could
+/// climb out of it.
+fn resolve(root: &Path, segments: &[String]) -> Option<PathBuf> {
+    let mut path = root.to_path_buf();
+    for segment in segments {
+        if segment.starts_with('.') || segment.contains(['/', '\\', '\0']) {
+            return None;
+        }
+        path.push(segment);
+    }
+    Some(path)
+}
+
+/// Whether the path, once the symbolic links are followed, is still inside the root.
+fn is_within(root: &Path, path: &Path) -> bool {
+    match (root.canonicalize(), path.canonicalize()) {
+        (Ok(root), Ok(path)) => path.starts_with(root),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::CycleDir;
+    use crate::library::METADATA_FILENAME;
+    use std::io::Write;
+    use std::sync::mpsc;
+
+    fn site(root: &Path) -> (Site, mpsc::Receiver<Event>) {
+        let (hub, receiver) = mpsc::channel();
+        let site = Site {
+            libraries: vec![("Books".to_string(), root.to_path_buf())],
+            kinds: ["epub".to_string()].into_iter().collect(),
+            api_token: None,
+            max_upload_size: 1024,
+            hub,
+        };
+        (site, receiver)
+    }
+
+    fn request(site: &Site, head: &str, body: &[u8]) -> Response {
+        let data = [head.as_bytes(), b"\r\n\r\n", body].concat();
+        let mut reader = BufReader::new(&data[..]);
+        let request = Request::read(&mut reader).unwrap();
+        site.respond(&request, &mut reader)
+    }
+
+    #[test]
+    fn test_upload_and_download() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        fs::create_dir_all(root.join("Sci Fi")).unwrap();
+        let (site, receiver) = site(&root);
+
+        let response = request(
+            &site,
+            "PUT /library/0/Sci%20Fi/Dune.epub HTTP/1.1\r\nContent-Length: 4",
+            b"dune",
+        );
+        assert_eq!(response.status(), 201);
+        assert!(matches!(receiver.try_recv(), Ok(Event::FileReceived(path))
+                         if path == root.join("Sci Fi/Dune.epub")));
+        assert_eq!(fs::read(root.join("Sci Fi/Dune.epub")).unwrap(), b"dune");
+
+        let response = request(
+            &site,
+            "PUT /library/0/Sci%20Fi/Dune.epub HTTP/1.1\r\nContent-Length: 4",
+            b"dune",
+        );
+        assert_eq!(response.status(), 409);
+        let response = request(
+            &site,
+            "PUT /library/0/notes.exe HTTP/1.1\r\nContent-Length: 4",
+            b"nope",
+        );
+        assert_eq!(response.status(), 415);
+        let response = request(
+            &site,
+            "PUT /library/0/Dune.epub HTTP/1.1\r\nContent-Length: 8",
+            b"cut",
+        );
+        assert_eq!(response.status(), 500);
+        assert!(!root.join("Dune.epub").exists());
+        assert!(!root.join(".Dune.epub.part").exists());
+
+        let mut output = Vec::new();
+        request(&site, "GET /library/0/Sci%20Fi/Dune.epub HTTP/1.1", b"")
+            .write_to(&mut output)
+            .unwrap();
+        assert!(output.starts_with(b"HTTP/1.1 200 OK\r\n"));
+        assert!(output.ends_with(b"\r\n\r\ndune"));
+
+        let mut output = Vec::new();
+        request(&site, "GET /library/0/ HTTP/1.1", b"")
+            .write_to(&mut output)
+            .unwrap();
+        let page = String::from_utf8(output).unwrap();
+        assert!(page.contains("<a href=\"/library/0/Sci%20Fi/\">Sci Fi/</a>"));
+    }
+
+    #[test]
+    fn test_upload_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        let (mut site, _receiver) = site(&root);
+
+        let response = request(
+            &site,
+            "PUT /library/0/Dune.epub HTTP/1.1\r\nContent-Length: 2048",
+            b"dune",
+        );
+        assert_eq!(response.status(), 413);
+        assert!(!root.join(".Dune.epub.part").exists());
+
+        site.max_upload_size = u64::MAX;
+        let response = request(
+            &site,
+            "PUT /library/0/Dune.epub HTTP/1.1\r\nContent-Length: 1152921504606846976",
+            b"dune",
+        );
+        assert_eq!(response.status(), 507);
+        assert!(!root.join(".Dune.epub.part").exists());
+    }
+
+    #[test]
+    fn test_too_many_connections() {
+        let (hub, _receiver) = mpsc::channel();
+        let mut settings = Settings::default();
+        settings.web_server.port = 0;
+        let server = WebServer::start(&settings, &hub).unwrap();
+        let address = ("127.0.0.1", server.port());
+
+        let idle: Vec<TcpStream> = (0..MAX_CONNECTIONS)
+            .map(|_| TcpStream::connect(address).unwrap())
+            .collect();
+        thread::sleep(ACCEPT_INTERVAL * 4);
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.set_read_timeout(Some(IO_TIMEOUT)).unwrap();
+        let mut output = String::new();
+        stream.read_to_string(&mut output).unwrap();
+        assert!(output.starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+        drop(idle);
+    }
+
+    #[test]
+    fn test_opds_catalog() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        fs::create_dir_all(root.join("Essays")).unwrap();
+        fs::write(root.join("Essays/walden.epub"), b"walden").unwrap();
+        fs::write(root.join("Essays/cover.png"), b"png").unwrap();
+        fs::write(
+            root.join(METADATA_FILENAME),
+            r#"{"00000000000000AB": {"title": "Walden & Civil Disobedience",
+                "author": "Henry David Thoreau", "language": "en",
+                "file": {"path": "Essays/walden.epub", "kind": "epub", "size": 6}}}"#,
+        )
+        .unwrap();
+        let (site, _receiver) = site(&root);
+
+        let mut output = Vec::new();
+        request(&site, "GET /opds/0 HTTP/1.1", b"")
+            .write_to(&mut output)
+            .unwrap();
+        let feed = String::from_utf8(output).unwrap();
+        assert!(feed.contains("kind=acquisition"));
+        assert!(feed.contains("<link rel=\"subsection\" href=\"/opds/0/Essays\""));
+
+        let mut output = Vec::new();
+        request(&site, "GET /opds/0/Essays HTTP/1.1", b"")
+            .write_to(&mut output)
+            .unwrap();
+        let feed = String::from_utf8(output).unwrap();
+        assert!(feed.contains("<title>Walden &amp; Civil Disobedience</title>"));
+        assert!(feed.contains("<author><name>Henry David Thoreau</name></author>"));
+        assert!(feed.contains(
+            "href=\"/library/0/Essays/walden.epub\" type=\"application/epub+zip\" length=\"6\""
+        ));
+        assert!(!feed.contains("cover.png"));
+
+        assert_eq!(
+            request(&site, "GET /opds/0/..%2F HTTP/1.1", b"").status(),
+            404
+        );
+    }
+
+    #[test]
+    fn test_remote_api() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        let (mut site, receiver) = site(&root);
+        assert_eq!(
+            request(&site, "POST /api/page/next HTTP/1.1", b"").status(),
+            404
+        );
+
+        site.api_token = Some(SecretString::from("s3cret"));
+        for head in [
+            "POST /api/page/next HTTP/1.1",
+            "POST /api/page/next HTTP/1.1\r\nAuthorization: Bearer s3cre",
+            "POST /api/page/next HTTP/1.1\r\nAuthorization: Basic s3cret",
+        ] {
+            assert_eq!(request(&site, head, b"").status(), 401, "{}", head);
+        }
+        assert!(receiver.try_recv().is_err());
+
+        let auth = "Authorization: Bearer s3cret";
+        let head = format!("POST /api/page/previous HTTP/1.1\r\n{}", auth);
+        assert_eq!(request(&site, &head, b"").status(), 202);
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(Event::Page(CycleDir::Previous))
+        ));
+        let head = format!("POST /api/frontlight/toggle HTTP/1.1\r\n{}", auth);
+        assert_eq!(request(&site, &head, b"").status(), 202);
+        assert!(matches!(receiver.try_recv(), Ok(Event::ToggleFrontlight)));
+        let head = format!("POST /api/open HTTP/1.1\r\n{}\r\nContent-Length: 13", auth);
+        assert_eq!(request(&site, &head, b"Sci/Dune.epub").status(), 202);
+        assert!(matches!(receiver.try_recv(), Ok(Event::RemoteOpen(path))
+                         if path == Path::new("Sci/Dune.epub")));
+        let head = format!("POST /api/open HTTP/1.1\r\n{}\r\nContent-Length: 9", auth);
+        assert_eq!(request(&site, &head, b"../x.epub").status(), 403);
+        let head = format!("GET /api/page/next HTTP/1.1\r\n{}", auth);
+        assert_eq!(request(&site, &head, b"").status(), 405);
+
+        let answer = thread::spawn(move || {
+            if let Ok(Event::RemoteStatus(sender)) = receiver.recv() {
+                sender
+                    .send(api::Status {
+                        battery: Some(80.0),
+                        frontlight: true,
+                        ..Default::default()
+                    })
+                    .ok();
+            }
+        });
+        let mut output = Vec::new();
+        let head = format!("GET /api/status HTTP/1.1\r\n{}", auth);
+        request(&site, &head, b"").write_to(&mut output).unwrap();
+        answer.join().unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(output.ends_with(
+            "\r\n\r\n{\"battery\":80.0,\"frontlight\":true,\"wifi\":false,\"online\":false}"
+        ));
+    }
+
+    #[test]
+    fn test_paths_stay_inside_the_library() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("library");
+        fs::create_dir(&root).unwrap();
+        let secret = root.with_extension("epub");
+        File::create(&secret).unwrap().write_all(b"secret").unwrap();
+        let secret_name = secret.file_name().unwrap().to_string_lossy().into_owned();
+        let (site, _receiver) = site(&root);
+
+        for path in [
+            "/library/0/..%2F..%2Fetc/passwd",
+            &format!("/library/0/../{}", secret_name),
+            &format!("/library/0/%2E%2E/{}", secret_name),
+            "/library/1/",
+        ] {
+            let head = format!("GET {} HTTP/1.1", path);
+            assert_eq!(request(&site, &head, b"").status(), 404, "{}", path);
+        }
+        let response = request(
+            &site,
+            "PUT /library/0/..%2Fescape.epub HTTP/1.1\r\nContent-Length: 1",
+            b"x",
+        );
+        assert_eq!(response.status(), 403);
+    }
+}
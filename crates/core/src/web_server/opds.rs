@@ -0,0 +1,204 @@
+//! An OPDS catalog of the libraries, for the reading apps that can browse one.
+//!
+//! Each directory is an acquisition feed: its subdirectories lead to their own feeds, and
+//! its books are acquired through the links of the web UI. The metadata of the libraries in
+//! database mode gives their titles and authors to the books.
+
+use super::http::{encode_path, mime_type, Response};
+use super::{is_within, resolve, Site};
+use crate::helpers::{escape_html, load_json, Fp};
+use crate::library::METADATA_FILENAME;
+use crate::metadata::Info;
+use chrono::{DateTime, SecondsFormat, Utc};
+use fxhash::FxHashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+const NAVIGATION: &str = "application/atom+xml;profile=opds-catalog;kind=navigation";
+const ACQUISITION: &str = "application/atom+xml;profile=opds-catalog;kind=acquisition";
+
+fn timestamp(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn feed(id: &str, title: &str, href: &str, kind: &str, entries: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <feed xmlns=\"http://www.w3.org/2005/Atom\" xmlns:dc=\"http://purl.org/dc/terms/\">\n\
+         <id>{}</id>\n<title>{}</title>\n<updated>{}</updated>\n\
+         <author><name>Cadmus</name></author>\n\
+         <link rel=\"self\" href=\"{}\" type=\"{}\"/>\n\
+         <link rel=\"start\" href=\"/opds\" type=\"{}\"/>\n{}</feed>\n",
+        escape_html(id),
+        escape_html(title),
+        timestamp(SystemTime::now()),
+        href,
+        kind,
+        NAVIGATION,
+        entries
+    )
+}
+
+fn subsection(id: &str, title: &str, href: &str, updated: SystemTime) -> String {
+    format!(
+        "<entry>\n<id>{}</id>\n<title>{}</title>\n<updated>{}</updated>\n\
+         <link rel=\"subsection\" href=\"{}\" type=\"{}\"/>\n</entry>\n",
+        escape_html(id),
+        escape_html(title),
+        timestamp(updated),
+        href,
+        ACQUISITION
+    )
+}
+
+/// Returns the metadata of the books of the library, by path relative to its root.
+fn catalog(root: &Path) -> FxHashMap<PathBuf, Info> {
+    let path = root.join(METADATA_FILENAME);
+    if !path.exists() {
+        return FxHashMap::default();
+    }
+    load_json::<FxHashMap<Fp, Info>, _>(&path)
+        .map(|db| {
+            db.into_values()
+                .map(|info| (info.file.path.clone(), info))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+impl Site {
+    pub(super) fn opds(&self, segments: &[String]) -> Response {
+        let Some(first) = segments.first() else {
+            return self.opds_root();
+        };
+        let Some((index, name, root)) = self.library(first) else {
+            return Response::not_found();
+        };
+        let segments = &segments[1..];
+        let Some(path) = resolve(root, segments).filter(|path| is_within(root, path)) else {
+            return Response::not_found();
+        };
+        let Ok((dirs, files)) = self.entries(&path) else {
+            return Response::not_found();
+        };
+
+        let mut base = vec!["opds".to_string(), index.to_string()];
+        let mut link = vec!["library".to_string(), index.to_string()];
+        base.extend(segments.iter().cloned());
+        link.extend(segments.iter().cloned());
+        let id = format!("urn:cadmus:{}", encode_path(&base));
+
+        let mut entries = String::new();
+        for dir in &dirs {
+            let mut href = base.clone();
+            href.push(dir.clone());
+            let updated = path
+                .join(dir)
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .unwrap_or_else(|_| SystemTime::now());
+            entries.push_str(&subsection(
+                &format!("urn:cadmus:{}", encode_path(&href)),
+                dir,
+                &encode_path(&href),
+                updated,
+            ));
+        }
+
+        let catalog = catalog(root);
+        let relative = segments.iter().collect::<PathBuf>();
+        for (file, metadata) in &files {
+            let info = catalog.get(&relative.join(file));
+            let mut href = link.clone();
+            href.push(file.clone());
+            let title = info.map(|info| info.title.as_str()).unwrap_or_default();
+            let title = if title.is_empty() {
+                Path::new(file)
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| file.clone())
+            } else {
+                title.to_string()
+            };
+            let kind = Path::new(file)
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            let updated = metadata.modified().unwrap_or_else(|_| SystemTime::now());
+
+            entries.push_str("<entry>\n");
+            entries.push_str(&format!(
+                "<id>urn:cadmus:{}</id>\n<title>{}</title>\n<updated>{}</updated>\n",
+                encode_path(&href),
+                escape_html(&title),
+                timestamp(updated)
+            ));
+            if let Some(info) = info {
+                if !info.author.is_empty() {
+                    entries.push_str(&format!(
+                        "<author><name>{}</name></author>\n",
+                        escape_html(&info.author)
+                    ));
+                }
+                for (element, value) in [
+                    ("dc:language", &info.language),
+                    ("dc:publisher", &info.publisher),
+                    ("dc:issued", &info.year),
+                ] {
+                    if !value.is_empty() {
+                        entries.push_str(&format!("<{0}>{1}</{0}>\n", element, escape_html(value)));
+                    }
+                }
+                for category in &info.categories {
+                    entries.push_str(&format!(
+                        "<category term=\"{0}\" label=\"{0}\"/>\n",
+                        escape_html(category)
+                    ));
+                }
+            }
+            entries.push_str(&format!(
+                "<link rel=\"http://opds-spec.org/acquisition\" href=\"{}\" type=\"{}\" length=\"{}\"/>\n\
+                 </entry>\n",
+                encode_path(&href),
+                mime_type(&kind),
+                metadata.len()
+            ));
+        }
+
+        let title = segments.last().map_or(name, String::as_str);
+        Response::new(
+            200,
+            ACQUISITION,
+            feed(&id, title, &encode_path(&base), ACQUISITION, &entries).into_bytes(),
+        )
+    }
+
+    fn opds_root(&self) -> Response {
+        let mut entries = String::new();
+        for (index, (name, root)) in self.libraries.iter().enumerate() {
+            let href = encode_path(&["opds", &index.to_string()]);
+            let updated = root
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .unwrap_or_else(|_| SystemTime::now());
+            entries.push_str(&subsection(
+                &format!("urn:cadmus:{}", href),
+                name,
+                &href,
+                updated,
+            ));
+        }
+        Response::new(
+            200,
+            NAVIGATION,
+            feed(
+                "urn:cadmus:/opds",
+                "Libraries",
+                "/opds",
+                NAVIGATION,
+                &entries,
+            )
+            .into_bytes(),
+        )
+    }
+}
@@ -0,0 +1,202 @@
+//! The export of the annotations of the books, one Markdown file per book.
+
+use super::client::{WebdavClient, WebdavError};
+use super::{Log, Report};
+use crate::document::TextLocation;
+use crate::helpers::{load_json, Fingerprint, Fp, IsHidden};
+use crate::library::{FAT32_EPOCH_FILENAME, METADATA_FILENAME, READING_STATES_DIRNAME};
+use crate::metadata::{Annotation, Info, ReaderInfo};
+use crate::settings::{LibraryMode, LibrarySettings};
+use fxhash::FxHashMap;
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// The title and the author of a book.
+type Book = (String, String);
+
+/// Returns the books of the library, by fingerprint.
+fn books(library: &LibrarySettings) -> FxHashMap<Fp, Book> {
+    if library.mode == LibraryMode::Database {
+        return load_json::<FxHashMap<Fp, Info>, _>(library.path.join(METADATA_FILENAME))
+            .map(|db| {
+                db.into_iter()
+                    .map(|(fp, info)| {
+                        let title = if info.title.is_empty() {
+                            file_stem(&info.file.path)
+                        } else {
+                            info.title
+                        };
+                        (fp, (title, info.author))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+    }
+
+    let Ok(epoch) = library
+        .path
+        .join(FAT32_EPOCH_FILENAME)
+        .metadata()
+        .and_then(|metadata| metadata.modified())
+    else {
+        return FxHashMap::default();
+    };
+
+    WalkDir::new(&library.path)
+        .min_depth(1)
+        .into_iter()
+        .filter_entry(|entry| !entry.is_hidden())
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let fp = entry.metadata().ok()?.fingerprint(epoch).ok()?;
+            Some((fp, (file_stem(entry.path()), String::new())))
+        })
+        .collect()
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Renders the annotations of a book, in the order of the text.
+pub fn markdown(title: &str, author: &str, annotations: &[Annotation]) -> String {
+    let mut annotations = annotations.iter().collect::<Vec<_>>();
+    annotations.sort_by_key(|annotation| annotation.selection[0]);
+
+    let mut text = format!("# {}\n", title);
+    if !author.is_empty() {
+        text.push_str(&format!("\n*{}*\n", author));
+    }
+
+    for annotation in annotations {
+        text.push('\n');
+        if let TextLocation::Static(page, _) = annotation.selection[0] {
+            text.push_str(&format!("## Page {}\n\n", page + 1));
+        }
+        for line in annotation.text.lines() {
+            text.push_str(&format!("> {}\n", line));
+        }
+        if !annotation.note.is_empty() {
+            if !annotation.text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(&format!("{}\n", annotation.note));
+        }
+        text.push_str(&format!(
+            "\n*{}*\n",
+            annotation.modified.format("%Y-%m-%d %H:%M")
+        ));
+    }
+
+    text
+}
+
+/// Uploads the annotations of the books of the given libraries to the given remote folder.
+pub fn export(
+    client: &WebdavClient,
+    libraries: &[LibrarySettings],
+    remote_root: &str,
+    log: &mut Log,
+    report: &mut Report,
+) -> Result<(), WebdavError> {
+    let remote_root = remote_root.trim_matches('/');
+    if !remote_root.is_empty() {
+        client.create_folder(remote_root)?;
+    }
+
+    for library in libraries {
+        let Ok(entries) = fs::read_dir(library.path.join(READING_STATES_DIRNAME)) else {
+            continue;
+        };
+        let books = books(library);
+
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            let Some((title, author)) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<Fp>().ok())
+                .and_then(|fp| books.get(&fp))
+            else {
+                continue;
+            };
+            let Ok(reader) = load_json::<ReaderInfo, _>(&path) else {
+                continue;
+            };
+            if reader.annotations.is_empty() {
+                continue;
+            }
+
+            let mut name = title.replace(['/', '\\'], "-");
+            if !author.is_empty() {
+                name = format!("{} - {}", name, author.replace(['/', '\\'], "-"));
+            }
+            let remote_path = if remote_root.is_empty() {
+                format!("{}.md", name)
+            } else {
+                format!("{}/{}.md", remote_root, name)
+            };
+
+            match client.upload(
+                &remote_path,
+                markdown(title, author, &reader.annotations).into_bytes(),
+            ) {
+                Ok(_) => report.exported += 1,
+                Err(e) => {
+                    report.errors += 1;
+                    log.push(format!("Can't export {}: {}.", remote_path, e));
+                }
+            }
+        }
+    }
+
+    if report.exported > 0 {
+        log.push(format!(
+            "Exported the annotations of {} books.",
+            report.exported
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_markdown() {
+        let modified = NaiveDate::from_ymd_opt(2026, 10, 14)
+            .unwrap()
+            .and_hms_opt(21, 30, 0)
+            .unwrap();
+        let annotations = [
+            Annotation {
+                note: "Fear of the unknown.".to_string(),
+                text: "Fear is the mind-killer.".to_string(),
+                selection: [TextLocation::Static(11, 4), TextLocation::Static(11, 8)],
+                modified,
+            },
+            Annotation {
+                note: String::new(),
+                text: "A beginning is the time\nfor taking the most delicate care.".to_string(),
+                selection: [TextLocation::Static(0, 0), TextLocation::Static(0, 9)],
+                modified,
+            },
+        ];
+
+        assert_eq!(
+            markdown("Dune", "Frank Herbert", &annotations),
+            "# Dune\n\n*Frank Herbert*\n\n\
+             ## Page 1\n\n> A beginning is the time\n> for taking the most delicate care.\n\n\
+             *2026-10-14 21:30*\n\n\
+             ## Page 12\n\n> Fear is the mind-killer.\n\nFear of the unknown.\n\n\
+             *2026-10-14 21:30*\n"
+        );
+    }
+}
@@ -0,0 +1,363 @@
+use lazy_static::lazy_static;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use regex::Regex;
+use reqwest::blocking::{Body, Client, RequestBuilder, Response};
+use reqwest::{Method, StatusCode, Url};
+use secrecy::{ExposeSecret, SecretString};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::download;
+use crate::helpers::decode_entities;
+use crate::net;
+use crate::settings::WebdavSettings;
+
+/// Timeout of each request in seconds.
+const TIMEOUT_SECS: u64 = 60;
+
+/// The properties requested when listing a folder.
+const PROPFIND_BODY: &str = "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+                             <d:propfind xmlns:d=\"DAV:\"><d:prop>\
+                             <d:resourcetype/><d:getetag/><d:getcontentlength/>\
+                             </d:prop></d:propfind>";
+
+/// The characters escaped in the segments of a path.
+const SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+lazy_static! {
+    static ref RESPONSE: Regex =
+        Regex::new(r"(?s)<(?:[\w-]+:)?response(?:\s[^>]*)?>(.*?)</(?:[\w-]+:)?response>").unwrap();
+    static ref COLLECTION: Regex = Regex::new(r"<(?:[\w-]+:)?collection(?:\s[^>]*)?/?>").unwrap();
+}
+
+/// Client of a WebDAV server, authenticated with a user name and a password.
+///
+/// Paths are relative to the URL of the server and use `/` as separator.
+pub struct WebdavClient {
+    client: Client,
+    /// The URL of the server, ending with a slash.
+    base: String,
+    /// The decoded path of the URL of the server, ending with a slash.
+    base_path: String,
+    username: String,
+    password: SecretString,
+}
+
+/// Error types that can occur while talking to the WebDAV server.
+#[derive(thiserror::Error, Debug)]
+pub enum WebdavError {
+    /// The server, the user name or the password isn't configured
+    #[error("WebDAV account not configured")]
+    NotConfigured,
+
+    /// The URL of the server can't be parsed
+    #[error("Invalid server URL: {0}")]
+    InvalidServer(String),
+
+    /// The server rejected the credentials
+    #[error("Unauthorized")]
+    Unauthorized,
+
+    /// The server answered with an unexpected status
+    #[error("Server error: {0}")]
+    Server(StatusCode),
+
+    /// HTTP request failed during communication with the server
+    #[error("HTTP request error: {0}")]
+    Request(#[from] reqwest::Error),
+
+    /// A local file couldn't be read or written
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    /// A file couldn't be downloaded
+    #[error("Download error: {0}")]
+    Download(String),
+
+    /// TLS/SSL configuration failed when setting up HTTPS client
+    #[error("TLS configuration error: {0}")]
+    TlsConfig(String),
+}
+
+/// A file or a folder on the server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resource {
+    pub path: String,
+    pub collection: bool,
+    /// Changes whenever the content of the file changes.
+    pub etag: String,
+    pub size: u64,
+}
+
+impl WebdavClient {
+    /// Creates a client for the account configured in the given settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WebdavError::NotConfigured` if the server, the user name or the password is
+    /// missing, and `WebdavError::TlsConfig` if the HTTP client fails to initialize.
+    pub fn new(settings: &WebdavSettings) -> Result<Self, WebdavError> {
+        let password = settings
+            .password
+            .clone()
+            .filter(|password| !password.expose_secret().is_empty())
+            .ok_or(WebdavError::NotConfigured)?;
+
+        if settings.server.is_empty() || settings.username.is_empty() {
+            return Err(WebdavError::NotConfigured);
+        }
+
+        let base = format!("{}/", settings.server.trim_end_matches('/'));
+        let url = Url::parse(&base).map_err(|e| WebdavError::InvalidServer(e.to_string()))?;
+        let base_path = percent_decode_str(url.path())
+            .decode_utf8_lossy()
+            .into_owned();
+
+        let client = net::client_builder("cadmus-webdav", Duration::from_secs(TIMEOUT_SECS))
+            .build()
+            .map_err(|e| WebdavError::TlsConfig(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(Self {
+            client,
+            base,
+            base_path,
+            username: settings.username.clone(),
+            password,
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        let segments = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| utf8_percent_encode(segment, SEGMENT).to_string())
+            .collect::<Vec<_>>();
+        format!("{}{}", self.base, segments.join("/"))
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.client
+            .request(method, self.url(path))
+            .basic_auth(&self.username, Some(self.password.expose_secret()))
+    }
+
+    fn propfind(&self, path: &str, depth: u8) -> Result<Vec<Resource>, WebdavError> {
+        let method = Method::from_bytes(b"PROPFIND").expect("PROPFIND should be a valid method");
+        let response = self
+            .request(method, path)
+            .header("Depth", depth.to_string())
+            .header("Content-Type", "application/xml; charset=utf-8")
+            .body(PROPFIND_BODY)
+            .send()?;
+        let body = check(response)?.text()?;
+        Ok(parse_multistatus(&body, &self.base_path))
+    }
+
+    /// Returns the file or folder at the given path, if it exists.
+    pub fn stat(&self, path: &str) -> Result<Option<Resource>, WebdavError> {
+        match self.propfind(path, 0) {
+            Ok(resources) => Ok(resources.into_iter().next()),
+            Err(WebdavError::Server(StatusCode::NOT_FOUND)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns the files and folders found below the given folder, at any depth.
+    pub fn list(&self, path: &str) -> Result<Vec<Resource>, WebdavError> {
+        let path = path.trim_matches('/');
+        let mut resources = Vec::new();
+        let mut pending = vec![path.to_string()];
+
+        while let Some(dir) = pending.pop() {
+            for resource in self.propfind(&dir, 1)? {
+                if resource.path == dir {
+                    continue;
+                }
+                if resource.collection {
+                    pending.push(resource.path.clone());
+                }
+                resources.push(resource);
+            }
+        }
+
+        Ok(resources)
+    }
+
+    /// Downloads the file at the given path through the download queue, replacing the
+    /// destination once complete.
+    pub fn download(&self, path: &str, destination: &Path) -> Result<(), WebdavError> {
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let label = path.rsplit('/').next().unwrap_or(path);
+        let mut request = download::Request::new(label, &self.url(path), destination.to_path_buf());
+        request.credentials = Some((self.username.clone(), self.password.clone()));
+        request.quiet = true;
+
+        download::enqueue(request, None)
+            .wait(|_| ())
+            .map(|_| ())
+            .map_err(|e| WebdavError::Download(format!("{:#}", e)))
+    }
+
+    /// Uploads the given content, and returns the new entity tag of the file if the server
+    /// sent it.
+    pub fn upload(&self, path: &str, body: impl Into<Body>) -> Result<Option<String>, WebdavError> {
+        let response = check(self.request(Method::PUT, path).body(body).send()?)?;
+        Ok(response
+            .headers()
+            .get("etag")
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim_matches('"').to_string()))
+    }
+
+    /// Creates the given folder, unless it already exists.
+    pub fn create_folder(&self, path: &str) -> Result<(), WebdavError> {
+        let method = Method::from_bytes(b"MKCOL").expect("MKCOL should be a valid method");
+        let response = self.request(method, path).send()?;
+
+        match response.status() {
+            // The folder already exists.
+            StatusCode::METHOD_NOT_ALLOWED => Ok(()),
+            _ => check(response).map(|_| ()),
+        }
+    }
+
+    pub fn delete(&self, path: &str) -> Result<(), WebdavError> {
+        let response = self.request(Method::DELETE, path).send()?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(()),
+            _ => check(response).map(|_| ()),
+        }
+    }
+}
+
+fn check(response: Response) -> Result<Response, WebdavError> {
+    let status = response.status();
+
+    if status.is_success() {
+        Ok(response)
+    } else if status == StatusCode::UNAUTHORIZED {
+        Err(WebdavError::Unauthorized)
+    } else {
+        Err(WebdavError::Server(status))
+    }
+}
+
+/// Returns the text content of the first element with the given name, whatever its prefix.
+fn element<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    let open = Regex::new(&format!(r"<(?:[\w-]+:)?{}(?:\s[^>]*)?>", name)).ok()?;
+    let start = open.find(xml)?.end();
+    let close = Regex::new(&format!(r"</(?:[\w-]+:)?{}>", name)).ok()?;
+    let end = close.find(&xml[start..])?.start();
+    Some(&xml[start..start + end])
+}
+
+/// Parses the answer to a `PROPFIND` request.
+///
+/// The paths of the resources are made relative to the given path, the one of the URL of the
+/// server.
+fn parse_multistatus(xml: &str, base_path: &str) -> Vec<Resource> {
+    RESPONSE
+        .captures_iter(xml)
+        .filter_map(|caps| {
+            let block = caps.get(1)?.as_str();
+            let href = decode_entities(element(block, "href")?.trim()).into_owned();
+            // Some servers send absolute URLs.
+            let href = match href.find("://") {
+                Some(index) => href[index + 3..]
+                    .find('/')
+                    .map_or("/", |start| &href[index + 3 + start..])
+                    .to_string(),
+                None => href,
+            };
+            let href = percent_decode_str(&href).decode_utf8_lossy().into_owned();
+            let path = href
+                .strip_prefix(base_path)
+                .or_else(|| href.strip_prefix(base_path.trim_end_matches('/')))?
+                .trim_matches('/')
+                .to_string();
+            let etag = element(block, "getetag")
+                .map(|etag| decode_entities(etag.trim()).trim_matches('"').to_string())
+                .unwrap_or_default();
+            let size = element(block, "getcontentlength")
+                .and_then(|size| size.trim().parse().ok())
+                .unwrap_or(0);
+            let collection = element(block, "resourcetype")
+                .is_some_and(|resource_type| COLLECTION.is_match(resource_type));
+            Some(Resource {
+                path,
+                collection,
+                etag,
+                size,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_multistatus() {
+        let xml = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:oc="http://owncloud.org/ns">
+ <d:response>
+  <d:href>/remote.php/dav/files/alice/Books/</d:href>
+  <d:propstat><d:prop>
+   <d:resourcetype><d:collection/></d:resourcetype>
+   <d:getetag>&quot;65f1&quot;</d:getetag>
+  </d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat>
+  <d:propstat><d:prop><d:getcontentlength/></d:prop>
+   <d:status>HTTP/1.1 404 Not Found</d:status></d:propstat>
+ </d:response>
+ <d:response>
+  <d:href>/remote.php/dav/files/alice/Books/L%27%C3%89tranger%20%26%20co.epub</d:href>
+  <d:propstat><d:prop>
+   <d:resourcetype/>
+   <d:getetag>&quot;a9c2&quot;</d:getetag>
+   <d:getcontentlength>1024</d:getcontentlength>
+  </d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat>
+ </d:response>
+ <D:response xmlns:D="DAV:">
+  <D:href>https://cloud.example.com/remote.php/dav/files/alice/Books/Sci-Fi/</D:href>
+  <D:propstat><D:prop>
+   <D:resourcetype><D:collection/></D:resourcetype>
+  </D:prop></D:propstat>
+ </D:response>
+</d:multistatus>"#;
+
+        assert_eq!(
+            parse_multistatus(xml, "/remote.php/dav/files/alice/"),
+            vec![
+                Resource {
+                    path: "Books".to_string(),
+                    collection: true,
+                    etag: "65f1".to_string(),
+                    size: 0,
+                },
+                Resource {
+                    path: "Books/L'Étranger & co.epub".to_string(),
+                    collection: false,
+                    etag: "a9c2".to_string(),
+                    size: 1024,
+                },
+                Resource {
+                    path: "Books/Sci-Fi".to_string(),
+                    collection: true,
+                    etag: String::new(),
+                    size: 0,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,255 @@
+//! Synchronization of folders with a WebDAV server, such as Nextcloud.
+//!
+//! Each configured folder is synced both ways: the files added, changed or removed on one
+//! side since the last sync are added, changed or removed on the other. When a file changed on
+//! both sides, the server's version is kept next to it, under a name marking the conflict, and
+//! both versions end up on both sides.
+//!
+//! The annotations of the books can also be exported, as Markdown, to a remote folder. The
+//! outcome of each sync is appended to a log, shown from the settings editor.
+
+mod annotations;
+mod client;
+mod sync;
+
+pub use client::{Resource, WebdavClient, WebdavError};
+
+use crate::helpers::escape_html;
+use crate::net;
+use crate::settings::Settings;
+use crate::sync_queue;
+use crate::view::{Event, Hub, NotificationEvent};
+use chrono::Local;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use tracing::{error, info};
+
+pub const WEBDAV_LOG_PATH: &str = "webdav-sync.log";
+
+/// The number of lines kept in the log.
+const MAX_LOG_LINES: usize = 500;
+
+static SYNCING: AtomicBool = AtomicBool::new(false);
+
+/// The changes made by a sync.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Report {
+    pub sent: usize,
+    pub received: usize,
+    /// Files removed from the device.
+    pub removed_here: usize,
+    /// Files removed from the server.
+    pub removed_there: usize,
+    pub conflicts: usize,
+    /// Books whose annotations were exported.
+    pub exported: usize,
+    pub errors: usize,
+}
+
+impl Report {
+    /// Tells whether files were added, changed or removed on the device.
+    pub fn changed_locally(&self) -> bool {
+        self.received > 0 || self.removed_here > 0 || self.conflicts > 0
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let plural = |count: usize, noun: &str| {
+            format!("{} {}{}", count, noun, if count == 1 { "" } else { "s" })
+        };
+        let mut parts = Vec::new();
+        if self.received > 0 {
+            parts.push(format!("{} received", self.received));
+        }
+        if self.sent > 0 {
+            parts.push(format!("{} sent", self.sent));
+        }
+        if self.removed_here + self.removed_there > 0 {
+            parts.push(format!(
+                "{} removed",
+                self.removed_here + self.removed_there
+            ));
+        }
+        if self.conflicts > 0 {
+            parts.push(plural(self.conflicts, "conflict"));
+        }
+        if self.errors > 0 {
+            parts.push(plural(self.errors, "error"));
+        }
+        if parts.is_empty() {
+            write!(f, "Everything is in sync")
+        } else {
+            write!(f, "Synced: {}", parts.join(", "))
+        }
+    }
+}
+
+/// The messages of a sync, prefixed with their time.
+#[derive(Debug, Default)]
+pub struct Log {
+    lines: Vec<String>,
+}
+
+impl Log {
+    pub fn push(&mut self, message: String) {
+        info!("{}", message);
+        self.lines.push(format!(
+            "{}\t{}",
+            Local::now().format("%Y-%m-%d %H:%M:%S"),
+            message
+        ));
+    }
+
+    /// Appends the messages to the log file, dropping its oldest lines.
+    fn save(&self, path: &Path) {
+        let previous = fs::read_to_string(path).unwrap_or_default();
+        let mut lines = previous
+            .lines()
+            .map(String::from)
+            .chain(self.lines.iter().cloned())
+            .collect::<Vec<_>>();
+        let excess = lines.len().saturating_sub(MAX_LOG_LINES);
+        lines.drain(..excess);
+        lines.push(String::new());
+        if let Err(e) = fs::write(path, lines.join("\n")) {
+            error!("Can't save the WebDAV sync log: {:#}.", e);
+        }
+    }
+}
+
+/// Syncs the configured folders, then exports the annotations.
+///
+/// A folder that can't be synced doesn't prevent the others from being synced: the
+/// failure is logged and counted in the report.
+pub fn sync(settings: &Settings) -> Result<Report, WebdavError> {
+    let mut log = Log::default();
+    let client = match WebdavClient::new(&settings.webdav) {
+        Ok(client) => client,
+        Err(e) => {
+            log.push(format!("Can't sync: {}.", e));
+            log.save(Path::new(WEBDAV_LOG_PATH));
+            return Err(e);
+        }
+    };
+
+    let mut report = Report::default();
+    for folder in &settings.webdav.folders {
+        log.push(format!(
+            "Syncing {} with {}.",
+            folder.local.display(),
+            folder.remote
+        ));
+        if let Err(e) = sync::sync_folder(
+            &client,
+            &folder.local,
+            &folder.remote,
+            &mut log,
+            &mut report,
+        ) {
+            report.errors += 1;
+            log.push(format!("Can't sync {}: {}.", folder.local.display(), e));
+        }
+    }
+
+    if let Some(remote) = settings.webdav.annotations.as_ref() {
+        match annotations::export(&client, &settings.libraries, remote, &mut log, &mut report) {
+            Ok(()) => sync_queue::clear_annotations(),
+            Err(e) => {
+                report.errors += 1;
+                log.push(format!("Can't export the annotations: {}.", e));
+            }
+        }
+    }
+
+    log.push(format!("{}.", report));
+    log.save(Path::new(WEBDAV_LOG_PATH));
+
+    Ok(report)
+}
+
+/// Syncs in the background, unless a sync is already running.
+///
+/// The outcome is sent as `Event::WebdavSynced`, or as a notification if the sync
+/// couldn't start. Returns whether a sync was started.
+pub fn spawn_sync(settings: &Settings, hub: &Hub) -> bool {
+    if SYNCING.swap(true, Ordering::AcqRel) {
+        return false;
+    }
+
+    let settings = settings.clone();
+    let hub = hub.clone();
+
+    thread::spawn(move || {
+        let _job = net::Job::start();
+        let event = match sync(&settings) {
+            Ok(report) => Event::WebdavSynced(report),
+            Err(e) => Event::Notification(NotificationEvent::Show(format!(
+                "Can't sync with the WebDAV server: {}.",
+                e
+            ))),
+        };
+        SYNCING.store(false, Ordering::Release);
+        hub.send(event).ok();
+    });
+
+    true
+}
+
+/// Renders the log, the most recent messages first.
+pub fn log_as_html() -> String {
+    let log = fs::read_to_string(WEBDAV_LOG_PATH).unwrap_or_default();
+    let mut buf = "<html>\n\t<head>\n\t\t<title>WebDAV Sync Log</title>\n\t\t\
+                   <link rel=\"stylesheet\" type=\"text/css\" \
+                   href=\"css/sysinfo.css\"/>\n\t</head>\n\t<body>\n"
+        .to_string();
+
+    if log.trim().is_empty() {
+        buf.push_str("\t\t<p>No sync yet.</p>\n");
+    } else {
+        buf.push_str("\t\t<table>\n");
+        for line in log.lines().rev().filter(|line| !line.is_empty()) {
+            let (time, message) = line.split_once('\t').unwrap_or(("", line));
+            buf.push_str("\t\t\t<tr>\n");
+            buf.push_str(&format!(
+                "\t\t\t\t<td class=\"key\">{}</td>\n",
+                escape_html(time)
+            ));
+            buf.push_str(&format!(
+                "\t\t\t\t<td class=\"value\">{}</td>\n",
+                escape_html(message)
+            ));
+            buf.push_str("\t\t\t</tr>\n");
+        }
+        buf.push_str("\t\t</table>\n");
+    }
+
+    buf.push_str("\t</body>\n</html>");
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_message() {
+        assert_eq!(Report::default().to_string(), "Everything is in sync");
+        let report = Report {
+            sent: 2,
+            received: 1,
+            removed_there: 1,
+            conflicts: 1,
+            errors: 3,
+            ..Default::default()
+        };
+        assert_eq!(
+            report.to_string(),
+            "Synced: 1 received, 2 sent, 1 removed, 1 conflict, 3 errors"
+        );
+        assert!(report.changed_locally());
+    }
+}
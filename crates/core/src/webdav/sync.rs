@@ -0,0 +1,453 @@
+//! The two-way synchronization of a local folder with a remote one.
+//!
+//! The state of each file after the last sync, its size and modification time on the device
+//! and its entity tag on the server, is stored in the local folder. A file that changed on
+//! one side since is copied to the other, and a file that disappeared from one side is
+//! removed from the other, unless it changed there.
+
+use super::client::{Resource, WebdavClient, WebdavError};
+use super::{Log, Report};
+use crate::helpers::{load_json, save_json, IsHidden};
+use chrono::Local;
+use fxhash::FxHashSet;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use walkdir::WalkDir;
+
+pub const STATE_FILENAME: &str = ".webdav-sync.json";
+
+/// The state of a file after the last sync.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Entry {
+    pub size: u64,
+    /// Seconds since the Unix epoch.
+    pub modified: i64,
+    pub etag: String,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LocalFile {
+    pub size: u64,
+    /// Seconds since the Unix epoch.
+    pub modified: i64,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Action {
+    Upload,
+    Download,
+    DeleteLocal,
+    DeleteRemote,
+    /// The file changed on both sides.
+    Conflict,
+    /// The file is the same on both sides, but wasn't synced before.
+    Record,
+    /// The file is gone from both sides.
+    Forget,
+}
+
+type State = BTreeMap<String, Entry>;
+
+/// Decides what to do with each file, given its local and remote versions and its state
+/// after the last sync.
+pub fn plan(
+    local: &BTreeMap<String, LocalFile>,
+    remote: &BTreeMap<String, Resource>,
+    state: &State,
+) -> Vec<(String, Action)> {
+    let paths = local
+        .keys()
+        .chain(remote.keys())
+        .chain(state.keys())
+        .collect::<FxHashSet<_>>();
+    let mut paths = paths.into_iter().collect::<Vec<_>>();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let local = local.get(path);
+            let remote = remote.get(path);
+            let entry = state.get(path);
+            let local_changed = |file: &LocalFile, entry: &Entry| {
+                file.size != entry.size || file.modified != entry.modified
+            };
+            let remote_changed = |resource: &Resource, entry: &Entry| resource.etag != entry.etag;
+
+            let action = match (local, remote, entry) {
+                (Some(file), Some(resource), None) => {
+                    if file.size == resource.size {
+                        Action::Record
+                    } else {
+                        Action::Conflict
+                    }
+                }
+                (Some(file), Some(resource), Some(entry)) => {
+                    match (local_changed(file, entry), remote_changed(resource, entry)) {
+                        (false, false) => return None,
+                        (true, false) => Action::Upload,
+                        (false, true) => Action::Download,
+                        (true, true) => Action::Conflict,
+                    }
+                }
+                (Some(_), None, None) => Action::Upload,
+                (None, Some(_), None) => Action::Download,
+                (Some(file), None, Some(entry)) => {
+                    if local_changed(file, entry) {
+                        Action::Upload
+                    } else {
+                        Action::DeleteLocal
+                    }
+                }
+                (None, Some(resource), Some(entry)) => {
+                    if remote_changed(resource, entry) {
+                        Action::Download
+                    } else {
+                        Action::DeleteRemote
+                    }
+                }
+                (None, None, Some(_)) => Action::Forget,
+                (None, None, None) => return None,
+            };
+
+            Some((path.clone(), action))
+        })
+        .collect()
+}
+
+/// Returns the path under which the remote version of a conflicting file is saved.
+pub fn conflict_path(path: &str, date: &str) -> String {
+    let (dir, name) = match path.rsplit_once('/') {
+        Some((dir, name)) => (format!("{}/", dir), name),
+        None => (String::new(), path),
+    };
+    match name.rsplit_once('.').filter(|(stem, _)| !stem.is_empty()) {
+        Some((stem, ext)) => format!("{}{} (conflict {}).{}", dir, stem, date, ext),
+        None => format!("{}{} (conflict {})", dir, name, date),
+    }
+}
+
+fn local_file(path: &Path) -> Option<LocalFile> {
+    let metadata = path.metadata().ok()?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_secs() as i64);
+    Some(LocalFile {
+        size: metadata.len(),
+        modified,
+    })
+}
+
+/// Returns the files of the folder, by path relative to it, skipping the hidden ones.
+fn local_files(root: &Path) -> BTreeMap<String, LocalFile> {
+    WalkDir::new(root)
+        .min_depth(1)
+        .into_iter()
+        .filter_entry(|entry| !entry.is_hidden())
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let relative = entry.path().strip_prefix(root).ok()?;
+            let path = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            Some((path, local_file(entry.path())?))
+        })
+        .collect()
+}
+
+fn join(root: &str, path: &str) -> String {
+    if root.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}/{}", root, path)
+    }
+}
+
+/// Keeps track of the remote folders, to create the missing ones before the uploads.
+struct Folders<'a> {
+    client: &'a WebdavClient,
+    known: FxHashSet<String>,
+}
+
+impl Folders<'_> {
+    fn create_parents(&mut self, path: &str) -> Result<(), WebdavError> {
+        let mut dir = String::new();
+        let mut segments = path.split('/').collect::<Vec<_>>();
+        segments.pop();
+        for segment in segments {
+            dir = join(&dir, segment);
+            if self.known.insert(dir.clone()) {
+                self.client.create_folder(&dir)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Syncs the given local folder with the given remote folder.
+///
+/// The failures of the transfers are logged and counted, and the files concerned are retried
+/// on the next sync.
+pub fn sync_folder(
+    client: &WebdavClient,
+    local_root: &Path,
+    remote_root: &str,
+    log: &mut Log,
+    report: &mut Report,
+) -> Result<(), WebdavError> {
+    let remote_root = remote_root.trim_matches('/');
+    fs::create_dir_all(local_root)?;
+
+    let mut folders = Folders {
+        client,
+        known: FxHashSet::default(),
+    };
+    folders.create_parents(&join(remote_root, "_"))?;
+
+    let local = local_files(local_root);
+    let prefix = format!("{}/", remote_root);
+    let mut remote = BTreeMap::new();
+    for resource in client.list(remote_root)? {
+        let path = if remote_root.is_empty() {
+            resource.path.clone()
+        } else {
+            match resource.path.strip_prefix(&prefix) {
+                Some(path) => path.to_string(),
+                None => continue,
+            }
+        };
+        if resource.collection {
+            folders.known.insert(resource.path);
+        } else if !path.split('/').any(|segment| segment.starts_with('.')) {
+            remote.insert(path, resource);
+        }
+    }
+
+    let state_path = local_root.join(STATE_FILENAME);
+    let mut state: State = if state_path.exists() {
+        load_json(&state_path).unwrap_or_default()
+    } else {
+        State::new()
+    };
+
+    let date = Local::now().format("%Y-%m-%d").to_string();
+
+    for (path, action) in plan(&local, &remote, &state) {
+        let local_path = local_root.join(&path);
+        let remote_path = join(remote_root, &path);
+
+        let result = match action {
+            Action::Upload => upload(client, &mut folders, &local_path, &remote_path).map(|etag| {
+                report.sent += 1;
+                log.push(format!("Sent {}.", remote_path));
+                local
+                    .get(&path)
+                    .map(|file| (file.size, file.modified, etag))
+            }),
+            Action::Download => client.download(&remote_path, &local_path).map(|_| {
+                report.received += 1;
+                log.push(format!("Received {}.", local_path.display()));
+                local_file(&local_path)
+                    .map(|file| (file.size, file.modified, remote[&path].etag.clone()))
+            }),
+            Action::DeleteLocal => {
+                fs::remove_file(&local_path)
+                    .map_err(WebdavError::from)
+                    .map(|_| {
+                        report.removed_here += 1;
+                        log.push(format!("Removed {}.", local_path.display()));
+                        None
+                    })
+            }
+            Action::DeleteRemote => client.delete(&remote_path).map(|_| {
+                report.removed_there += 1;
+                log.push(format!("Removed {}.", remote_path));
+                None
+            }),
+            Action::Conflict => {
+                let copy = conflict_path(&path, &date);
+                let copy_path = local_root.join(&copy);
+                let remote_copy = join(remote_root, &copy);
+                client
+                    .download(&remote_path, &copy_path)
+                    .and_then(|_| upload(client, &mut folders, &copy_path, &remote_copy))
+                    .and_then(|etag| {
+                        if let Some(file) = local_file(&copy_path) {
+                            state.insert(
+                                copy.clone(),
+                                Entry {
+                                    size: file.size,
+                                    modified: file.modified,
+                                    etag,
+                                },
+                            );
+                        }
+                        upload(client, &mut folders, &local_path, &remote_path)
+                    })
+                    .map(|etag| {
+                        report.conflicts += 1;
+                        log.push(format!(
+                            "{} changed on both sides: the server's version was saved as {}.",
+                            remote_path, copy
+                        ));
+                        local
+                            .get(&path)
+                            .map(|file| (file.size, file.modified, etag))
+                    })
+            }
+            Action::Record => Ok(local
+                .get(&path)
+                .map(|file| (file.size, file.modified, remote[&path].etag.clone()))),
+            Action::Forget => Ok(None),
+        };
+
+        match result {
+            Ok(Some((size, modified, etag))) => {
+                state.insert(
+                    path,
+                    Entry {
+                        size,
+                        modified,
+                        etag,
+                    },
+                );
+            }
+            Ok(None) => {
+                state.remove(&path);
+            }
+            Err(e) => {
+                report.errors += 1;
+                log.push(format!("Can't sync {}: {}.", remote_path, e));
+            }
+        }
+    }
+
+    save_json(&state, &state_path).map_err(|e| io::Error::other(format!("{:#}", e)))?;
+
+    Ok(())
+}
+
+/// Uploads the given file, and returns its entity tag on the server.
+fn upload(
+    client: &WebdavClient,
+    folders: &mut Folders,
+    local_path: &Path,
+    remote_path: &str,
+) -> Result<String, WebdavError> {
+    folders.create_parents(remote_path)?;
+    let etag = match client.upload(remote_path, fs::File::open(local_path)?)? {
+        Some(etag) => etag,
+        None => client
+            .stat(remote_path)?
+            .map(|resource| resource.etag)
+            .unwrap_or_default(),
+    };
+    Ok(etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resource(path: &str, etag: &str, size: u64) -> Resource {
+        Resource {
+            path: path.to_string(),
+            collection: false,
+            etag: etag.to_string(),
+            size,
+        }
+    }
+
+    #[test]
+    fn test_plan() {
+        let file = |size, modified| LocalFile { size, modified };
+        let entry = |size, modified, etag: &str| Entry {
+            size,
+            modified,
+            etag: etag.to_string(),
+        };
+
+        let local = BTreeMap::from([
+            ("changed-here.epub".to_string(), file(20, 2)),
+            ("changed-there.epub".to_string(), file(10, 1)),
+            ("changed-twice.epub".to_string(), file(20, 2)),
+            ("deleted-there.epub".to_string(), file(10, 1)),
+            ("new-here.epub".to_string(), file(10, 1)),
+            ("new-twice.epub".to_string(), file(10, 1)),
+            ("same.epub".to_string(), file(10, 1)),
+        ]);
+        let remote = BTreeMap::from([
+            (
+                "changed-here.epub".to_string(),
+                resource("changed-here.epub", "a", 10),
+            ),
+            (
+                "changed-there.epub".to_string(),
+                resource("changed-there.epub", "b", 20),
+            ),
+            (
+                "changed-twice.epub".to_string(),
+                resource("changed-twice.epub", "b", 30),
+            ),
+            (
+                "deleted-here.epub".to_string(),
+                resource("deleted-here.epub", "a", 10),
+            ),
+            (
+                "new-there.epub".to_string(),
+                resource("new-there.epub", "a", 10),
+            ),
+            (
+                "new-twice.epub".to_string(),
+                resource("new-twice.epub", "a", 10),
+            ),
+            ("same.epub".to_string(), resource("same.epub", "a", 10)),
+        ]);
+        let state = State::from([
+            ("changed-here.epub".to_string(), entry(10, 1, "a")),
+            ("changed-there.epub".to_string(), entry(10, 1, "a")),
+            ("changed-twice.epub".to_string(), entry(10, 1, "a")),
+            ("deleted-both.epub".to_string(), entry(10, 1, "a")),
+            ("deleted-here.epub".to_string(), entry(10, 1, "a")),
+            ("deleted-there.epub".to_string(), entry(10, 1, "a")),
+            ("same.epub".to_string(), entry(10, 1, "a")),
+        ]);
+
+        assert_eq!(
+            plan(&local, &remote, &state),
+            vec![
+                ("changed-here.epub".to_string(), Action::Upload),
+                ("changed-there.epub".to_string(), Action::Download),
+                ("changed-twice.epub".to_string(), Action::Conflict),
+                ("deleted-both.epub".to_string(), Action::Forget),
+                ("deleted-here.epub".to_string(), Action::DeleteRemote),
+                ("deleted-there.epub".to_string(), Action::DeleteLocal),
+                ("new-here.epub".to_string(), Action::Upload),
+                ("new-there.epub".to_string(), Action::Download),
+                ("new-twice.epub".to_string(), Action::Record),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_conflict_path() {
+        assert_eq!(
+            conflict_path("Sci-Fi/Dune.epub", "2026-10-14"),
+            "Sci-Fi/Dune (conflict 2026-10-14).epub"
+        );
+        assert_eq!(
+            conflict_path(".profile", "2026-10-14"),
+            ".profile (conflict 2026-10-14)"
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//! Capturing the emulator window, for the documentation and the bug reports.
+//!
+//! Unlike the screenshots taken by the application, the captures neither flash the screen nor
+//! show a notification. The recordings save every frame as a numbered PNG, along with a
+//! `frames.ffconcat` index holding how long each frame was shown, so that *ffmpeg* can encode
+//! them at their original pace.
+
+use cadmus_core::anyhow::{Context as ResultExt, Error};
+use cadmus_core::chrono::Local;
+use cadmus_core::framebuffer::Framebuffer;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+pub const DEFAULT_CAPTURE_DIR: &str = "captures";
+
+const INDEX_NAME: &str = "frames.ffconcat";
+
+pub struct Capture {
+    dir: PathBuf,
+    recording: Option<Recording>,
+}
+
+struct Recording {
+    dir: PathBuf,
+    count: usize,
+    last: Option<(String, Instant)>,
+    index: File,
+}
+
+impl Recording {
+    fn new(dir: PathBuf) -> Result<Recording, Error> {
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("can't create recording directory {}", dir.display()))?;
+        let path = dir.join(INDEX_NAME);
+        let mut index =
+            File::create(&path).with_context(|| format!("can't create {}", path.display()))?;
+        writeln!(index, "ffconcat version 1.0")?;
+        Ok(Recording {
+            dir,
+            count: 0,
+            last: None,
+            index,
+        })
+    }
+
+    // Writes how long the last frame was shown.
+    fn close_frame(&mut self, now: Instant) -> Result<(), Error> {
+        if let Some((_, start)) = self.last.as_ref() {
+            let duration = now.duration_since(*start).as_secs_f64();
+            writeln!(self.index, "duration {:.3}", duration)?;
+        }
+        Ok(())
+    }
+
+    fn record(&mut self, fb: &dyn Framebuffer) -> Result<(), Error> {
+        self.count += 1;
+        let name = format!("frame-{:06}.png", self.count);
+        let path = self.dir.join(&name);
+        fb.save(&path.to_string_lossy())?;
+        let now = Instant::now();
+        self.close_frame(now)?;
+        writeln!(self.index, "file '{}'", name)?;
+        self.last = Some((name, now));
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<PathBuf, Error> {
+        self.close_frame(Instant::now())?;
+        // The demuxer ignores the duration of the last entry.
+        if let Some((name, _)) = self.last.as_ref() {
+            writeln!(self.index, "file '{}'", name)?;
+        }
+        self.index.flush()?;
+        Ok(self.dir)
+    }
+}
+
+impl Capture {
+    pub fn new<P: AsRef<Path>>(dir: P) -> Capture {
+        Capture {
+            dir: dir.as_ref().to_path_buf(),
+            recording: None,
+        }
+    }
+
+    fn stamp() -> String {
+        Local::now().format("%Y%m%d_%H%M%S%.3f").to_string()
+    }
+
+    /// Saves the window as a PNG in the capture directory and returns its path.
+    pub fn screenshot(&self, fb: &dyn Framebuffer) -> Result<PathBuf, Error> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("can't create capture directory {}", self.dir.display()))?;
+        let path = self.dir.join(format!("capture-{}.png", Capture::stamp()));
+        fb.save(&path.to_string_lossy())?;
+        Ok(path)
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Starts recording in a new directory, and saves the current frame.
+    pub fn start_recording(&mut self, fb: &dyn Framebuffer) -> Result<&Path, Error> {
+        let dir = self.dir.join(format!("recording-{}", Capture::stamp()));
+        let mut recording = Recording::new(dir)?;
+        recording.record(fb)?;
+        Ok(&self.recording.insert(recording).dir)
+    }
+
+    /// Stops recording and returns the directory holding the frames.
+    pub fn stop_recording(&mut self) -> Option<Result<PathBuf, Error>> {
+        self.recording.take().map(Recording::finish)
+    }
+
+    /// Saves the frame that was just displayed, if a recording is in progress.
+    pub fn record_frame(&mut self, fb: &dyn Framebuffer) -> Result<(), Error> {
+        match self.recording.as_mut() {
+            Some(recording) => recording.record(fb),
+            None => Ok(()),
+        }
+    }
+}
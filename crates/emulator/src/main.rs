@@ -1,38 +1,59 @@
-use cadmus_core::anyhow::{Context as ResultExt, Error};
+mod capture;
+
+use crate::capture::{Capture, DEFAULT_CAPTURE_DIR};
+use cadmus_core::anyhow::{format_err, Context as ResultExt, Error};
 use cadmus_core::assets::open_documentation;
 use cadmus_core::battery::{Battery, FakeBattery};
+use cadmus_core::calibre::Calibre;
 use cadmus_core::chrono::Local;
 use cadmus_core::color::Color;
 use cadmus_core::context::Context;
 use cadmus_core::device::CURRENT_DEVICE;
 use cadmus_core::document::sys_info_as_html;
+use cadmus_core::download;
+use cadmus_core::external_keyboard::{from_hid_usage, key_event, Modifiers};
 use cadmus_core::font::Fonts;
 use cadmus_core::framebuffer::{Framebuffer, UpdateMode};
 use cadmus_core::frontlight::{Frontlight, LightLevels};
-use cadmus_core::geom::{Axis, Rectangle};
+use cadmus_core::geom::{Axis, CycleDir, Rectangle};
 use cadmus_core::gesture::{gesture_events, GestureEvent};
 use cadmus_core::helpers::{load_toml, save_toml};
+use cadmus_core::hooks;
 use cadmus_core::input::{ButtonCode, ButtonStatus, DeviceEvent, FingerStatus};
 use cadmus_core::library::Library;
 use cadmus_core::lightsensor::LightSensor;
+use cadmus_core::mail;
 use cadmus_core::png;
 use cadmus_core::pt;
+use cadmus_core::recorder::{load_records, replay, Recorder};
 use cadmus_core::settings::{IntermKind, Settings, SETTINGS_PATH};
+use cadmus_core::view::battery_stats::BatteryStats;
 use cadmus_core::view::calculator::Calculator;
+use cadmus_core::view::captive_portal::CaptivePortal;
+use cadmus_core::view::command_palette::show_command_palette;
 use cadmus_core::view::common::{
-    find_notification_mut, locate, locate_by_id, overlapping_rectangle, transfer_notifications,
+    find_notification_mut, gesture_map_event, locate, locate_by_id, overlapping_rectangle,
+    transfer_notifications, ButtonMapping,
 };
 use cadmus_core::view::common::{toggle_input_history_menu, toggle_keyboard_layout_menu};
+use cadmus_core::view::dashboard::Dashboard;
 use cadmus_core::view::dialog::Dialog;
 use cadmus_core::view::dictionary::Dictionary;
+use cadmus_core::view::focus::{activate_focus, move_focus, navigates, ButtonNavigation};
 use cadmus_core::view::frontlight::FrontlightWindow;
 use cadmus_core::view::home::Home;
+use cadmus_core::view::inspector::toggle_inspector;
 use cadmus_core::view::intermission::Intermission;
 use cadmus_core::view::menu::{Menu, MenuKind};
-use cadmus_core::view::notification::Notification;
+use cadmus_core::view::notification::{
+    dismiss_toast, notify, toggle_notification_drawer, Notification,
+};
 use cadmus_core::view::ota::show_ota_view;
+use cadmus_core::view::perf_hud::{tick_perf_hud, toggle_perf_hud};
+use cadmus_core::view::progress_dialog::{show_progress_dialog, ProgressEvent};
 use cadmus_core::view::reader::Reader;
 use cadmus_core::view::rotation_values::RotationValues;
+use cadmus_core::view::screenshots::{take_screenshot, Screenshots};
 use cadmus_core::view::settings_editor::SettingsEditor;
 use cadmus_core::view::sketch::Sketch;
 use cadmus_core::view::touch_events::TouchEvents;
@@ -40,6 +61,8 @@ use cadmus_core::view::{
     handle_event, process_render_queue, wait_for_all, RenderData, RenderQueue,
 };
 use cadmus_core::view::{AppCmd, EntryId, EntryKind, Event, NotificationEvent, View, ViewId};
+use cadmus_core::web_server::{api, WebServer};
+use cadmus_core::webdav;
 use sdl2::event::Event as SdlEvent;
 use sdl2::keyboard::{Keycode, Mod, Scancode};
 use sdl2::mouse::MouseState;
@@ -48,13 +71,14 @@ use sdl2::rect::Point as SdlPoint;
 use sdl2::rect::Rect as SdlRect;
 use sdl2::render::{BlendMode, WindowCanvas};
 use std::collections::VecDeque;
+use std::env;
 use std::fs::File;
 use std::mem;
 use std::path::Path;
 use std::sync::mpsc;
 use std::thread;
-use std::time::Duration;
-use tracing::info;
+use std::time::{Duration, Instant};
+use tracing::{error, info};
 
 pub const APP_NAME: &str = "Cadmus";
 const DEFAULT_ROTATION: i8 = 1;
@@ -201,6 +225,26 @@ impl Framebuffer for FBCanvas {
         }
     }
 
+    fn tint_region(&mut self, rect: &Rectangle, color: Color) {
+        let width = rect.width();
+        let s_rect = Some(SdlRect::new(rect.min.x, rect.min.y, width, rect.height()));
+        if let Ok(data) = self.0.read_pixels(s_rect, PixelFormatEnum::RGB24) {
+            for y in rect.min.y..rect.max.y {
+                let v = (y - rect.min.y) as u32;
+                for x in rect.min.x..rect.max.x {
+                    let u = (x - rect.min.x) as u32;
+                    let addr = 3 * (v * width + u);
+                    let red = data[addr as usize];
+                    let green = data[(addr + 1) as usize];
+                    let blue = data[(addr + 2) as usize];
+                    let mut c = Color::Rgb(red, green, blue);
+                    c.tint(color);
+                    self.set_pixel(x as u32, y as u32, c);
+                }
+            }
+        }
+    }
+
     fn update(&mut self, _rect: &Rectangle, _mode: UpdateMode) -> Result<u32, Error> {
         self.0.present();
         Ok(Local::now().timestamp_subsec_millis())
@@ -270,6 +314,17 @@ impl Framebuffer for FBCanvas {
     }
 }
 
+fn toggle_recording(capture: &mut Capture, context: &mut Context) {
+    match capture.stop_recording() {
+        Some(Ok(dir)) => info!("Recorded the frames to {}.", dir.display()),
+        Some(Err(e)) => error!("Can't finish recording: {:#}.", e),
+        None => match capture.start_recording(context.fb.as_ref()) {
+            Ok(dir) => info!("Recording the frames to {}.", dir.display()),
+            Err(e) => error!("Can't start recording: {:#}.", e),
+        },
+    }
+}
+
 fn main() -> Result<(), Error> {
     let sdl_context = sdl2::init().unwrap();
     let video_subsystem = sdl_context.video().unwrap();
@@ -291,9 +346,20 @@ fn main() -> Result<(), Error> {
 
     context.load_dictionaries();
     context.load_keyboard_layouts();
+    context.load_themes();
+    context.apply_theme();
+    context.load_translations();
 
     let (tx, rx) = mpsc::channel();
     let (ty, ry) = mpsc::channel();
+    let mut calibre: Option<Calibre> = None;
+    let mut web_server = if context.settings.web_server.enabled {
+        WebServer::start(&context.settings, &tx)
+            .map_err(|e| error!("Can't start the web server: {:#}.", e))
+            .ok()
+    } else {
+        None
+    };
     let touch_screen = gesture_events(ry);
 
     let tx2 = tx.clone();
@@ -314,6 +380,11 @@ fn main() -> Result<(), Error> {
     let mut view: Box<dyn View> =
         Box::new(Home::new(context.fb.rect(), &tx, &mut rq, &mut context)?);
 
+    if context.settings.home.dashboard.start_screen {
+        let dashboard = Dashboard::new(context.fb.rect(), &mut rq, &mut context);
+        history.push(mem::replace(&mut view, Box::new(dashboard)));
+    }
+
     let mut updating = Vec::new();
 
     if context.settings.frontlight {
@@ -336,11 +407,65 @@ fn main() -> Result<(), Error> {
     );
 
     let mut bus = VecDeque::with_capacity(4);
+    let mut button_navigation = ButtonNavigation::default();
+    let mut button_mapping = ButtonMapping::default();
+    // While a text field has the focus, the keys are typed into it.
+    let mut typing = false;
+
+    let mut recorder = Recorder::from_settings(&context.settings.logging)
+        .map_err(|e| error!("Can't record events: {:#}.", e))
+        .ok()
+        .flatten();
+
+    let mut capture = Capture::new(DEFAULT_CAPTURE_DIR);
+    let mut record = false;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--replay" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| format_err!("missing argument: events path"))?;
+                replay(load_records(&path)?, tx.clone());
+            }
+            "--capture-dir" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| format_err!("missing argument: capture directory"))?;
+                capture = Capture::new(path);
+            }
+            "--record" => record = true,
+            _ => (),
+        }
+    }
+
+    if record {
+        toggle_recording(&mut capture, &mut context);
+    }
 
     'outer: loop {
         let mut event_pump = sdl_context.event_pump().unwrap();
         while let Some(sdl_evt) = event_pump.poll_event() {
             match sdl_evt {
+                SdlEvent::KeyDown {
+                    scancode: Some(scancode),
+                    keymod,
+                    ..
+                } if typing => {
+                    let modifiers = Modifiers {
+                        shift: keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD),
+                        ctrl: keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD),
+                        alt: keymod.intersects(Mod::LALTMOD | Mod::RALTMOD),
+                        caps_lock: keymod.contains(Mod::CAPSMOD),
+                    };
+                    if let Some(evt) = from_hid_usage(scancode as i32 as u16)
+                        .and_then(|code| key_event(code, &modifiers, true))
+                    {
+                        tx.send(evt).ok();
+                    }
+                }
+                SdlEvent::KeyUp { .. } if typing => (),
                 SdlEvent::Quit { .. }
                 | SdlEvent::KeyDown {
                     keycode: Some(Keycode::Escape),
@@ -399,6 +524,12 @@ fn main() -> Result<(), Error> {
                         Scancode::S => {
                             tx.send(Event::Select(EntryId::TakeScreenshot)).ok();
                         }
+                        Scancode::Tab => {
+                            tx.send(Event::MoveFocus(CycleDir::Next)).ok();
+                        }
+                        Scancode::Return => {
+                            tx.send(Event::ActivateFocus).ok();
+                        }
                         Scancode::B
                         | Scancode::F
                         | Scancode::P
@@ -443,7 +574,18 @@ fn main() -> Result<(), Error> {
                         }
                         _ => (),
                     },
+                    Mod::LCTRLMOD | Mod::RCTRLMOD => match scancode {
+                        Scancode::S => match capture.screenshot(context.fb.as_ref()) {
+                            Ok(path) => info!("Captured the window to {}.", path.display()),
+                            Err(e) => error!("Can't capture the window: {:#}.", e),
+                        },
+                        Scancode::R => toggle_recording(&mut capture, &mut context),
+                        _ => (),
+                    },
                     Mod::LSHIFTMOD | Mod::RSHIFTMOD => match scancode {
+                        Scancode::Tab => {
+                            tx.send(Event::MoveFocus(CycleDir::Previous)).ok();
+                        }
                         Scancode::S | Scancode::P | Scancode::C => {
                             if let Some(index) = locate::<Intermission>(view.as_ref()) {
                                 let rect = *view.child(index).rect();
@@ -463,7 +605,9 @@ fn main() -> Result<(), Error> {
                                     Scancode::C => IntermKind::Share,
                                     _ => unreachable!(),
                                 };
-                                let interm = Intermission::new(context.fb.rect(), kind, &context);
+                                let interm =
+                                    Intermission::new(context.fb.rect(), kind, &mut context);
+                                interm.animate(&tx);
                                 rq.add(RenderData::new(
                                     interm.id(),
                                     *interm.rect(),
@@ -485,6 +629,25 @@ fn main() -> Result<(), Error> {
         }
 
         while let Ok(evt) = rx.recv_timeout(Duration::from_millis(20)) {
+            let handling_started = Instant::now();
+            if let Some(recorder) = recorder.as_mut() {
+                recorder
+                    .record(&evt)
+                    .map_err(|e| error!("Can't record event: {:#}.", e))
+                    .ok();
+            }
+
+            if context.settings.button_navigation
+                && navigates(view.as_ref())
+                && button_navigation.handle_event(&evt, &tx)
+            {
+                continue;
+            }
+
+            if button_mapping.handle_event(&evt, view.as_ref(), &tx, &context) {
+                continue;
+            }
+
             match evt {
                 Event::Open(info) => {
                     let rotation = context.display.rotation;
@@ -570,6 +733,18 @@ fn main() -> Result<(), Error> {
                             &mut rq,
                             &mut context,
                         )),
+                        AppCmd::Dashboard => {
+                            Box::new(Dashboard::new(context.fb.rect(), &mut rq, &mut context))
+                        }
+                        AppCmd::BatteryStats => {
+                            Box::new(BatteryStats::new(context.fb.rect(), &mut rq, &mut context))
+                        }
+                        AppCmd::CaptivePortal => Box::new(CaptivePortal::new(
+                            context.fb.rect(),
+                            &tx,
+                            &mut rq,
+                            &mut context,
+                        )),
                         AppCmd::SettingsEditor => Box::new(SettingsEditor::new(
                             context.fb.rect(),
                             &mut rq,
@@ -578,6 +753,9 @@ fn main() -> Result<(), Error> {
                         AppCmd::TouchEvents => {
                             Box::new(TouchEvents::new(context.fb.rect(), &mut rq, &mut context))
                         }
+                        AppCmd::Screenshots => {
+                            Box::new(Screenshots::new(context.fb.rect(), &mut rq, &mut context))
+                        }
                         AppCmd::RotationValues => Box::new(RotationValues::new(
                             context.fb.rect(),
                             &mut rq,
@@ -636,6 +814,19 @@ fn main() -> Result<(), Error> {
                             .push(Box::new(preset_menu) as Box<dyn View>);
                     }
                 }
+                Event::Focus(view_id) => {
+                    typing = view_id.is_some();
+                    handle_event(view.as_mut(), &evt, &tx, &mut bus, &mut rq, &mut context);
+                }
+                Event::MoveFocus(dir) => {
+                    move_focus(view.as_ref(), dir, &mut rq, &mut context);
+                }
+                Event::ActivateFocus => {
+                    activate_focus(view.as_ref(), &tx, &context);
+                }
+                Event::Show(ViewId::CommandPalette) => {
+                    show_command_palette(view.as_mut(), &tx, &mut rq, &mut context);
+                }
                 Event::Show(ViewId::Frontlight) => {
                     if !context.settings.frontlight {
                         context.set_frontlight(true);
@@ -680,6 +871,10 @@ fn main() -> Result<(), Error> {
                         rq.add(RenderData::expose(rect, UpdateMode::Gui));
                         view.children_mut().remove(index);
                     }
+                    dismiss_toast(view.as_mut(), id, &tx, &mut rq, &mut context);
+                }
+                Event::Select(EntryId::Notifications) => {
+                    toggle_notification_drawer(view.as_mut(), None, &mut rq, &mut context);
                 }
                 Event::Select(EntryId::About) => {
                     let dialog = Dialog::builder(
@@ -745,6 +940,18 @@ fn main() -> Result<(), Error> {
                     // Re-dispatch event to view hierarchy so UI can update
                     handle_event(view.as_mut(), &evt, &tx, &mut bus, &mut rq, &mut context);
                 }
+                Event::Gesture(ge) if gesture_map_event(&ge, view.as_ref(), &context).is_some() => {
+                    if let Some(mapped_evt) = gesture_map_event(&ge, view.as_ref(), &context) {
+                        tx.send(mapped_evt).ok();
+                    }
+                }
+                Event::Select(EntryId::FullRefresh) => {
+                    rq.add(RenderData::new(
+                        view.id(),
+                        context.fb.rect(),
+                        UpdateMode::Full,
+                    ));
+                }
                 Event::Select(EntryId::ToggleInverted) => {
                     context.fb.toggle_inverted();
                     rq.add(RenderData::new(
@@ -753,19 +960,181 @@ fn main() -> Result<(), Error> {
                         UpdateMode::Gui,
                     ));
                 }
+                Event::Select(EntryId::ToggleInspector) => {
+                    toggle_inspector(&mut rq, &mut context);
+                }
+                Event::Select(EntryId::TogglePerfHud) => {
+                    toggle_perf_hud(&tx, &mut rq, &mut context);
+                }
+                Event::PerfHudTick => {
+                    tick_perf_hud(&mut rq, &mut context);
+                }
                 Event::Select(EntryId::TakeScreenshot) => {
-                    let name = Local::now().format("screenshot-%Y%m%d_%H%M%S.png");
-                    let msg = match context.fb.save(&name.to_string()) {
-                        Err(e) => format!("Couldn't take screenshot: {}).", e),
-                        Ok(_) => format!("Saved {}.", name),
+                    let msg = match take_screenshot(&mut context) {
+                        Err(e) => format!("Couldn't take screenshot: {:#}.", e),
+                        Ok(path) => format!("Saved {}.", path.display()),
                     };
                     let notif = Notification::new(None, msg, false, &tx, &mut rq, &mut context);
                     view.children_mut().push(Box::new(notif) as Box<dyn View>);
                 }
+                Event::Select(EntryId::ToggleWebServer) => {
+                    context.settings.web_server.enabled = !context.settings.web_server.enabled;
+                    let msg = if context.settings.web_server.enabled {
+                        match WebServer::start(&context.settings, &tx) {
+                            Ok(server) => {
+                                let msg = format!(
+                                    "Web server started at http://localhost:{}/.",
+                                    server.port()
+                                );
+                                web_server.replace(server);
+                                msg
+                            }
+                            Err(e) => {
+                                context.settings.web_server.enabled = false;
+                                format!("Can't start the web server: {:#}.", e)
+                            }
+                        }
+                    } else {
+                        drop(web_server.take());
+                        "Web server stopped.".to_string()
+                    };
+                    notify(view.as_mut(), msg, &tx, &mut rq, &mut context);
+                }
+                Event::FileReceived(ref path) | Event::FileRemoved(ref path) => {
+                    context.batch_import();
+                    view.handle_event(&Event::Reseed, &tx, &mut bus, &mut rq, &mut context);
+                    let name = path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    let msg = if matches!(evt, Event::FileReceived(..)) {
+                        format!("Received {}.", name)
+                    } else {
+                        format!("Removed {}.", name)
+                    };
+                    notify(view.as_mut(), msg, &tx, &mut rq, &mut context);
+                }
+                Event::Select(EntryId::ToggleCalibre) => {
+                    if calibre.take().is_some() {
+                        context.calibre = false;
+                    } else {
+                        context.library.flush();
+                        match Calibre::connect(&context.settings, &tx) {
+                            Ok(connection) => {
+                                calibre = Some(connection);
+                                context.calibre = true;
+                                notify(
+                                    view.as_mut(),
+                                    "Looking for Calibre.".to_string(),
+                                    &tx,
+                                    &mut rq,
+                                    &mut context,
+                                );
+                            }
+                            Err(e) => {
+                                let msg = format!("Can't connect to Calibre: {:#}.", e);
+                                notify(view.as_mut(), msg, &tx, &mut rq, &mut context);
+                            }
+                        }
+                    }
+                }
+                Event::CalibreDisconnected => {
+                    calibre = None;
+                    context.calibre = false;
+                }
+                Event::Select(EntryId::SyncWebdav) => {
+                    context.library.flush();
+                    let msg = if webdav::spawn_sync(&context.settings, &tx) {
+                        "Syncing with the WebDAV server."
+                    } else {
+                        "A WebDAV sync is already running."
+                    };
+                    notify(view.as_mut(), msg.to_string(), &tx, &mut rq, &mut context);
+                }
+                Event::Select(EntryId::ShowWebdavLog) => {
+                    tx.send(Event::OpenHtml(webdav::log_as_html(), None)).ok();
+                }
+                Event::Select(EntryId::CheckMail) => {
+                    let msg = if mail::spawn_check(&context.settings, &tx, false) {
+                        "Checking the mailbox."
+                    } else {
+                        "The mailbox is already being checked."
+                    };
+                    notify(view.as_mut(), msg.to_string(), &tx, &mut rq, &mut context);
+                }
+                Event::Select(EntryId::RunHook(index)) => {
+                    if let Some(hook) = context.settings.hooks.get(index) {
+                        if let Err(e) = hooks::run(hook, "menu", None, &context, &tx) {
+                            let msg = format!("Can't run {}: {:#}.", hook.name, e);
+                            notify(view.as_mut(), msg, &tx, &mut rq, &mut context);
+                        }
+                    }
+                }
+                Event::Select(EntryId::ShowDownloads) => {
+                    tx.send(Event::OpenHtml(download::queue_as_html(), None))
+                        .ok();
+                }
+                Event::Select(EntryId::ToggleDownloads) => {
+                    let paused = !download::is_paused();
+                    download::set_paused(paused);
+                    let msg = if paused {
+                        "Downloads paused."
+                    } else {
+                        "Downloads resumed."
+                    };
+                    notify(view.as_mut(), msg.to_string(), &tx, &mut rq, &mut context);
+                }
+                Event::Select(EntryId::RetryDownloads) => {
+                    let count = download::retry_failed();
+                    let msg = format!(
+                        "Retrying {} download{}.",
+                        count,
+                        if count == 1 { "" } else { "s" }
+                    );
+                    notify(view.as_mut(), msg, &tx, &mut rq, &mut context);
+                }
+                Event::RemoteOpen(ref path) => {
+                    let parent = path.parent().map_or_else(
+                        || context.library.home.clone(),
+                        |parent| context.library.home.join(parent),
+                    );
+                    let (files, _) = context.library.list(&parent, None, false);
+                    if let Some(info) = files.into_iter().find(|info| info.file.path == *path) {
+                        if view.is::<Reader>() {
+                            view.handle_event(&Event::Back, &tx, &mut bus, &mut rq, &mut context);
+                            tx.send(Event::Back).ok();
+                        }
+                        tx.send(Event::Open(Box::new(info))).ok();
+                    } else {
+                        let msg = format!("Can't find {}.", path.display());
+                        notify(view.as_mut(), msg, &tx, &mut rq, &mut context);
+                    }
+                }
+                Event::RemoteStatus(ref sender) => {
+                    let reader = std::iter::once(&view)
+                        .chain(history.iter().rev())
+                        .find_map(|view| view.downcast_ref::<Reader>());
+                    sender.send(api::Status::new(&mut context, reader)).ok();
+                }
+                Event::WebdavSynced(ref report) => {
+                    if report.changed_locally() && !context.shared {
+                        context.batch_import();
+                        view.handle_event(&Event::Reseed, &tx, &mut bus, &mut rq, &mut context);
+                    }
+                    notify(
+                        view.as_mut(),
+                        format!("{}.", report),
+                        &tx,
+                        &mut rq,
+                        &mut context,
+                    );
+                }
+                Event::Progress(ProgressEvent::Show(id, title, kind)) => {
+                    show_progress_dialog(view.as_mut(), id, title, kind, &mut rq, &mut context);
+                }
                 Event::Notification(notif_event) => match notif_event {
                     NotificationEvent::Show(msg) => {
-                        let notif = Notification::new(None, msg, false, &tx, &mut rq, &mut context);
-                        view.children_mut().push(Box::new(notif) as Box<dyn View>);
+                        notify(view.as_mut(), msg, &tx, &mut rq, &mut context);
                     }
                     NotificationEvent::ShowPinned(id, msg) => {
                         let notif =
@@ -794,8 +1163,7 @@ fn main() -> Result<(), Error> {
                     }
                 },
                 Event::Notify(msg) => {
-                    let notif = Notification::new(None, msg, false, &tx, &mut rq, &mut context);
-                    view.children_mut().push(Box::new(notif) as Box<dyn View>);
+                    notify(view.as_mut(), msg, &tx, &mut rq, &mut context);
                 }
                 Event::Device(DeviceEvent::NetUp)
                 | Event::CheckFetcher(..)
@@ -839,10 +1207,20 @@ fn main() -> Result<(), Error> {
                     handle_event(view.as_mut(), &evt, &tx, &mut bus, &mut rq, &mut context);
                 }
             }
+
+            context.perf_hud.record_event(handling_started.elapsed());
         }
 
+        let rendering = !rq.is_empty();
         process_render_queue(view.as_ref(), &mut rq, &mut context, &mut updating);
 
+        if rendering {
+            if let Err(e) = capture.record_frame(context.fb.as_ref()) {
+                error!("Can't record frame: {:#}.", e);
+                toggle_recording(&mut capture, &mut context);
+            }
+        }
+
         while let Some(ce) = bus.pop_front() {
             tx.send(ce).ok();
         }
@@ -872,6 +1250,10 @@ fn main() -> Result<(), Error> {
         context.settings.frontlight_levels = context.frontlight.levels();
     }
 
+    if capture.is_recording() {
+        toggle_recording(&mut capture, &mut context);
+    }
+
     context.library.flush();
 
     let path = Path::new(SETTINGS_PATH);
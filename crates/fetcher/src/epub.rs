@@ -0,0 +1,398 @@
+//! Conversion of the articles to EPUB, on the device.
+//!
+//! The content is stripped of everything that isn't text, links, images or tables, and the
+//! images are downloaded and embedded, so that the articles can be read offline.
+
+use cadmus_core::anyhow::Error;
+use cadmus_core::document::html::dom::{NodeData, NodeRef};
+use cadmus_core::document::html::xml::XmlParser;
+use cadmus_core::helpers::{decode_entities, escape_html};
+use regex::Regex;
+use reqwest::Url;
+use std::collections::HashMap;
+use std::io::{Seek, Write};
+use zip::write::{SimpleFileOptions, ZipWriter};
+use zip::CompressionMethod;
+
+/// The elements removed with their content.
+const DROPPED_ELEMENTS: [&str; 18] = [
+    "audio", "button", "canvas", "embed", "footer", "form", "head", "iframe", "input", "nav",
+    "noscript", "object", "script", "select", "style", "svg", "textarea", "video",
+];
+
+/// The elements kept, the others are replaced by their content.
+const KEPT_ELEMENTS: [&str; 50] = [
+    "a",
+    "abbr",
+    "article",
+    "b",
+    "blockquote",
+    "br",
+    "caption",
+    "cite",
+    "code",
+    "dd",
+    "del",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "figcaption",
+    "figure",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "i",
+    "img",
+    "ins",
+    "kbd",
+    "li",
+    "mark",
+    "ol",
+    "p",
+    "pre",
+    "q",
+    "s",
+    "samp",
+    "section",
+    "small",
+    "span",
+    "strong",
+    "sub",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "tr",
+    "ul",
+];
+
+/// The maximum number of images embedded in an article.
+const MAX_IMAGES: usize = 32;
+
+const CONTAINER: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+<container version=\"1.0\" xmlns=\"urn:oasis:names:tc:opendocument:xmlns:container\">\n\
+<rootfiles><rootfile full-path=\"OEBPS/content.opf\" media-type=\"application/oebps-package+xml\"/></rootfiles>\n\
+</container>\n";
+
+const STYLESHEET: &str = "img { max-width: 100%; }\n\
+figcaption, .source { font-size: 0.8em; }\n";
+
+pub struct Article<'a> {
+    pub identifier: &'a str,
+    pub title: &'a str,
+    pub author: &'a str,
+    pub language: &'a str,
+    pub url: &'a str,
+    /// The HTML content of the article.
+    pub content: &'a str,
+}
+
+pub struct Image {
+    pub data: Vec<u8>,
+    pub media_type: String,
+}
+
+fn extension(media_type: &str) -> Option<&'static str> {
+    match media_type {
+        "image/jpeg" => Some("jpg"),
+        "image/png" => Some("png"),
+        "image/gif" => Some("gif"),
+        "image/webp" => Some("webp"),
+        "image/svg+xml" => Some("svg"),
+        _ => None,
+    }
+}
+
+/// Rewrites the content of an article as XHTML, embedding its images.
+struct Cleaner<'a> {
+    base: Option<Url>,
+    fetch: &'a mut dyn FnMut(&Url) -> Option<Image>,
+    /// The embedded images, by file name.
+    images: Vec<(String, Image)>,
+    /// The file names of the images, by source URL, or `None` if they couldn't be fetched.
+    sources: HashMap<String, Option<String>>,
+}
+
+impl Cleaner<'_> {
+    fn resolve(&self, href: &str) -> Option<Url> {
+        let href = decode_entities(href.trim());
+        match &self.base {
+            Some(base) => base.join(&href).ok(),
+            None => Url::parse(&href).ok(),
+        }
+        .filter(|url| matches!(url.scheme(), "http" | "https" | "mailto"))
+    }
+
+    fn image(&mut self, src: &str) -> Option<String> {
+        let url = self.resolve(src)?;
+        if let Some(name) = self.sources.get(url.as_str()) {
+            return name.clone();
+        }
+        let name = if self.images.len() < MAX_IMAGES {
+            (self.fetch)(&url).and_then(|image| {
+                let name = format!(
+                    "images/{}.{}",
+                    self.images.len() + 1,
+                    extension(&image.media_type)?
+                );
+                self.images.push((name.clone(), image));
+                Some(name)
+            })
+        } else {
+            None
+        };
+        self.sources.insert(url.to_string(), name.clone());
+        name
+    }
+
+    fn write(&mut self, node: NodeRef, buf: &mut String) {
+        match node.data() {
+            NodeData::Element(data) => {
+                let name = data.name.to_lowercase();
+                let name = name.rsplit(':').next().unwrap_or_default();
+                if DROPPED_ELEMENTS.contains(&name) {
+                    return;
+                }
+                if !KEPT_ELEMENTS.contains(&name) {
+                    self.write_children(node, buf);
+                    return;
+                }
+
+                let mut attributes = String::new();
+                match name {
+                    "a" => {
+                        if let Some(url) =
+                            node.attribute("href").and_then(|href| self.resolve(href))
+                        {
+                            attributes = format!(" href=\"{}\"", escape_html(url.as_str()));
+                        }
+                    }
+                    "img" => {
+                        let Some(src) = node.attribute("src").and_then(|src| self.image(src))
+                        else {
+                            return;
+                        };
+                        let alt = node
+                            .attribute("alt")
+                            .map(|alt| escape_html(&decode_entities(alt)))
+                            .unwrap_or_default();
+                        attributes = format!(" src=\"{}\" alt=\"{}\"", src, alt);
+                    }
+                    "td" | "th" => {
+                        for key in ["colspan", "rowspan"] {
+                            if let Some(value) = node
+                                .attribute(key)
+                                .filter(|value| value.chars().all(|c| c.is_ascii_digit()))
+                            {
+                                attributes.push_str(&format!(" {}=\"{}\"", key, value));
+                            }
+                        }
+                    }
+                    _ => (),
+                }
+
+                if matches!(name, "br" | "hr" | "img") {
+                    buf.push_str(&format!("<{}{}/>", name, attributes));
+                } else {
+                    buf.push_str(&format!("<{}{}>", name, attributes));
+                    self.write_children(node, buf);
+                    buf.push_str(&format!("</{}>", name));
+                }
+            }
+            NodeData::Text(data) | NodeData::Whitespace(data) => {
+                buf.push_str(&escape_html(&decode_entities(&data.text)));
+            }
+            _ => self.write_children(node, buf),
+        }
+    }
+
+    fn write_children(&mut self, node: NodeRef, buf: &mut String) {
+        for child in node.children() {
+            self.write(child, buf);
+        }
+    }
+}
+
+/// Returns the cleaned content of the article, and the images it embeds.
+pub fn clean(
+    content: &str,
+    url: &str,
+    fetch: &mut dyn FnMut(&Url) -> Option<Image>,
+) -> (String, Vec<(String, Image)>) {
+    // The parser expects the void elements to be closed.
+    let void_elements = Regex::new(r"(?i)<(area|base|br|col|embed|hr|img|input|link|meta|param|source|track|wbr)(\s[^>]*?)?\s*/?>")
+        .expect("the void elements pattern should be valid");
+    let content = void_elements.replace_all(content, "<$1$2/>");
+    let tree = XmlParser::new(&content).parse();
+
+    let mut cleaner = Cleaner {
+        base: Url::parse(url).ok(),
+        fetch,
+        images: Vec::new(),
+        sources: HashMap::new(),
+    };
+    let mut body = String::new();
+    cleaner.write(tree.root(), &mut body);
+
+    (body, cleaner.images)
+}
+
+/// Writes the article as an EPUB.
+pub fn write_epub<W: Write + Seek>(
+    writer: W,
+    article: &Article,
+    fetch: &mut dyn FnMut(&Url) -> Option<Image>,
+) -> Result<(), Error> {
+    let (body, images) = clean(article.content, article.url, fetch);
+    let title = escape_html(article.title);
+    let language = if article.language.is_empty() {
+        "en".to_string()
+    } else {
+        article.language.replace('_', "-")
+    };
+
+    let mut chapter = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <html xmlns=\"http://www.w3.org/1999/xhtml\" xml:lang=\"{}\">\n\
+         <head><title>{}</title><link rel=\"stylesheet\" type=\"text/css\" href=\"style.css\"/></head>\n\
+         <body>\n<h1>{}</h1>\n",
+        escape_html(&language),
+        title,
+        title
+    );
+    if !article.author.is_empty() {
+        chapter.push_str(&format!(
+            "<p><em>{}</em></p>\n",
+            escape_html(article.author)
+        ));
+    }
+    chapter.push_str(&body);
+    if !article.url.is_empty() {
+        chapter.push_str(&format!(
+            "\n<p class=\"source\"><a href=\"{0}\">{0}</a></p>",
+            escape_html(article.url)
+        ));
+    }
+    chapter.push_str("\n</body>\n</html>\n");
+
+    let mut manifest = String::new();
+    for (index, (name, image)) in images.iter().enumerate() {
+        manifest.push_str(&format!(
+            "<item id=\"image{}\" href=\"{}\" media-type=\"{}\"/>\n",
+            index + 1,
+            name,
+            image.media_type
+        ));
+    }
+
+    let author = if article.author.is_empty() {
+        String::new()
+    } else {
+        format!("<dc:creator>{}</dc:creator>\n", escape_html(article.author))
+    };
+    let package = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <package xmlns=\"http://www.idpf.org/2007/opf\" version=\"2.0\" unique-identifier=\"id\">\n\
+         <metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n\
+         <dc:identifier id=\"id\">{}</dc:identifier>\n<dc:title>{}</dc:title>\n{}\
+         <dc:language>{}</dc:language>\n</metadata>\n\
+         <manifest>\n\
+         <item id=\"ncx\" href=\"toc.ncx\" media-type=\"application/x-dtbncx+xml\"/>\n\
+         <item id=\"style\" href=\"style.css\" media-type=\"text/css\"/>\n\
+         <item id=\"article\" href=\"article.xhtml\" media-type=\"application/xhtml+xml\"/>\n\
+         {}</manifest>\n\
+         <spine toc=\"ncx\"><itemref idref=\"article\"/></spine>\n\
+         </package>\n",
+        escape_html(article.identifier),
+        title,
+        author,
+        escape_html(&language),
+        manifest
+    );
+    let toc = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <ncx xmlns=\"http://www.daisy.org/z3986/2005/ncx/\" version=\"2005-1\">\n\
+         <head><meta name=\"dtb:uid\" content=\"{}\"/></head>\n\
+         <docTitle><text>{}</text></docTitle>\n\
+         <navMap><navPoint id=\"article\" playOrder=\"1\">\
+         <navLabel><text>{}</text></navLabel><content src=\"article.xhtml\"/>\
+         </navPoint></navMap>\n</ncx>\n",
+        escape_html(article.identifier),
+        title,
+        title
+    );
+
+    let mut zip = ZipWriter::new(writer);
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    let deflated = SimpleFileOptions::default();
+    zip.start_file("mimetype", stored)?;
+    zip.write_all(b"application/epub+zip")?;
+    for (name, content) in [
+        ("META-INF/container.xml", CONTAINER),
+        ("OEBPS/content.opf", package.as_str()),
+        ("OEBPS/toc.ncx", toc.as_str()),
+        ("OEBPS/style.css", STYLESHEET),
+        ("OEBPS/article.xhtml", chapter.as_str()),
+    ] {
+        zip.start_file(name, deflated)?;
+        zip.write_all(content.as_bytes())?;
+    }
+    for (name, image) in &images {
+        zip.start_file(format!("OEBPS/{}", name), stored)?;
+        zip.write_all(&image.data)?;
+    }
+    zip.finish()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean() {
+        let content = r#"<div class="post"><script>track();</script>
+<p style="color: red">Caf&eacute; &amp; <b>bar</b><br>
+<img src="/logo.png" alt="Logo"><img src="https://ads.example.com/pixel.gif"></p>
+<form><input type="text"></form><custom-tag>Kept text</custom-tag>
+<a href="javascript:alert(1)">Bad link</a> <a href="other.html">Good link</a></div>"#;
+        let mut fetched = Vec::new();
+        let (body, images) = clean(
+            content,
+            "https://blog.example.com/posts/1",
+            &mut |url: &Url| {
+                fetched.push(url.to_string());
+                url.path().ends_with(".png").then(|| Image {
+                    data: vec![0x89, b'P', b'N', b'G'],
+                    media_type: "image/png".to_string(),
+                })
+            },
+        );
+
+        assert_eq!(
+            body,
+            "<div>\n<p>Café &amp; <b>bar</b><br/>\n\
+             <img src=\"images/1.png\" alt=\"Logo\"/></p>\n\
+             Kept text\n<a>Bad link</a> \
+             <a href=\"https://blog.example.com/posts/other.html\">Good link</a></div>"
+        );
+        assert_eq!(
+            fetched,
+            [
+                "https://blog.example.com/logo.png",
+                "https://ads.example.com/pixel.gif"
+            ]
+        );
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].0, "images/1.png");
+    }
+}
@@ -1,19 +1,26 @@
+mod epub;
+
+use crate::epub::{Article, Image};
 use cadmus_core::anyhow::{format_err, Context, Error};
 use cadmus_core::chrono::{DateTime, Duration, Local, Utc};
+use cadmus_core::download;
 use cadmus_core::helpers::{decode_entities, load_json, load_toml, save_json};
+use cadmus_core::net;
 use cadmus_core::serde::{Deserialize, Serialize};
 use cadmus_core::serde_json::{self, json, Value as JsonValue};
 use reqwest::blocking::Client;
 use std::env;
 use std::fs::{self, File};
-use std::io;
+use std::io::{self, Read};
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time;
 
 const SETTINGS_PATH: &str = "Settings.toml";
 const SESSION_PATH: &str = ".session.json";
 const URLS_PATH: &str = "urls.txt";
+const TIMEOUT_SECS: u64 = 60;
 // Nearly RFC 3339
 const DATE_FORMAT: &str = "%FT%T%z";
 
@@ -29,6 +36,7 @@ struct Settings {
     sync_finished: bool,
     remove_finished: bool,
     balance_limit: usize,
+    server_export: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -110,6 +118,39 @@ fn update_token(client: &Client, session: &mut Session, settings: &Settings) ->
     }
 }
 
+/// The maximum size of an embedded image, in bytes.
+const MAX_IMAGE_SIZE: u64 = 4 * 1024 * 1024;
+
+fn fetch_image(client: &Client, url: &reqwest::Url) -> Option<Image> {
+    let response = client
+        .get(url.clone())
+        .send()
+        .ok()
+        .filter(|response| response.status().is_success())
+        .filter(|response| {
+            response
+                .content_length()
+                .is_none_or(|size| size <= MAX_IMAGE_SIZE)
+        })?;
+    let media_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(|value| value.trim().to_lowercase())
+        .filter(|value| value.starts_with("image/"))?;
+    // The length header can be missing or wrong.
+    let mut data = Vec::new();
+    response
+        .take(MAX_IMAGE_SIZE + 1)
+        .read_to_end(&mut data)
+        .ok()?;
+    if data.len() as u64 > MAX_IMAGE_SIZE {
+        return None;
+    }
+    Some(Image { data, media_type })
+}
+
 // The *detail* parameter is only available in 2.4.0 and up.
 fn is_detail_available(client: &Client, settings: &Settings) -> bool {
     // /api/info is only available in 2.4.0 and up.
@@ -169,7 +210,11 @@ fn main() -> Result<(), Error> {
         fs::create_dir(&save_path)?;
     }
 
-    let client = Client::new();
+    let client = net::client_builder(
+        concat!("cadmus-fetcher/", env!("CARGO_PKG_VERSION")),
+        time::Duration::from_secs(TIMEOUT_SECS),
+    )
+    .build()?;
 
     if session.access_token.valid_until <= Utc::now() {
         update_token(&client, &mut session, &settings)?;
@@ -349,7 +394,8 @@ fn main() -> Result<(), Error> {
         "perPage": 8,
     });
 
-    if is_detail_available(&client, &settings) {
+    // The content of the articles is needed to convert them.
+    if settings.server_export && is_detail_available(&client, &settings) {
         query["perPage"] = JsonValue::from(100);
         query["detail"] = JsonValue::from("metadata");
     }
@@ -463,17 +509,35 @@ fn main() -> Result<(), Error> {
                     continue;
                 }
 
-                let mut file = File::create(&epub_path)?;
-                let url = format!("{}/api/entries/{}/export.epub", settings.base_url, id);
-
-                let response = client
-                    .get(&url)
-                    .header(
-                        reqwest::header::AUTHORIZATION,
-                        format!("Bearer {}", &session.access_token.data),
-                    )
-                    .send()
-                    .and_then(|mut body| body.copy_to(&mut file));
+                let response = if settings.server_export {
+                    let url = format!("{}/api/entries/{}/export.epub", settings.base_url, id);
+                    let mut request = download::Request::new(&title, &url, epub_path.clone());
+                    request.token = Some(session.access_token.data.clone().into());
+                    request.quiet = true;
+                    download::enqueue(request, None).wait(|_| ()).map(|_| ())
+                } else {
+                    let field = |name| {
+                        element
+                            .get(name)
+                            .and_then(JsonValue::as_str)
+                            .unwrap_or_default()
+                    };
+                    let article = Article {
+                        identifier: &format!("{}/view/{}", settings.base_url, id),
+                        title: &title,
+                        author: &author,
+                        language: field("language"),
+                        url: field("url"),
+                        content: field("content"),
+                    };
+                    File::create(&epub_path)
+                        .map_err(Error::from)
+                        .and_then(|mut file| {
+                            epub::write_epub(&mut file, &article, &mut |url| {
+                                fetch_image(&client, url)
+                            })
+                        })
+                };
 
                 if let Err(err) = response {
                     eprintln!("Can't download {}: {:#}.", id, err);
@@ -487,7 +551,7 @@ fn main() -> Result<(), Error> {
                     let file_info = json!({
                         "path": path,
                         "kind": "epub",
-                        "size": file.metadata().ok()
+                        "size": fs::metadata(&epub_path).ok()
                                     .map_or(0, |m| m.len()),
                     });
 
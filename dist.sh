@@ -9,6 +9,7 @@
 
 mkdir -p dist/libs
 mkdir dist/dictionaries
+mkdir dist/themes
 
 cp libs/libz.so dist/libs/libz.so.1
 cp libs/libbz2.so dist/libs/libbz2.so.1.0
@@ -27,6 +28,7 @@ cp libs/libmupdf.so dist/libs
 
 cp -R hyphenation-patterns dist
 cp -R keyboard-layouts dist
+cp -R translations dist
 cp -R bin dist
 cp -R scripts dist
 cp -R icons dist
@@ -0,0 +1,11 @@
+#! /bin/sh
+
+# Prints a `Device <address> <name>` line per known device, or per paired device.
+if [ "$1" = paired ]; then
+	env -u LD_LIBRARY_PATH bluetoothctl devices Paired 2> /dev/null | grep "^Device " ||
+		env -u LD_LIBRARY_PATH bluetoothctl paired-devices | grep "^Device "
+else
+	env -u LD_LIBRARY_PATH bluetoothctl devices | grep "^Device "
+fi
+
+exit 0
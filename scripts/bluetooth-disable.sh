@@ -0,0 +1,5 @@
+#! /bin/sh
+
+pidof bluetoothd > /dev/null || exit 1
+
+env -u LD_LIBRARY_PATH bluetoothctl power off > /dev/null
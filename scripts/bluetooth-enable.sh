@@ -0,0 +1,19 @@
+#! /bin/sh
+
+if [ "$WIFI_MODULE" = wlan_drv_gen4m ]; then
+	MODULE_PATH=/drivers/$PLATFORM/mt66xx
+	grep -q "^wmt_drv\b" /proc/modules || insmod "$MODULE_PATH"/wmt_drv.ko
+	grep -q "^wmt_cdev_bt\b" /proc/modules || insmod "$MODULE_PATH"/wmt_cdev_bt.ko
+fi
+
+pidof bluetoothd > /dev/null || env -u LD_LIBRARY_PATH \
+	/usr/libexec/bluetooth/bluetoothd -n > /dev/null 2>&1 &
+
+REM_TRIES=20
+while [ "$REM_TRIES" -gt 0 ] ; do
+	env -u LD_LIBRARY_PATH bluetoothctl power on > /dev/null 2>&1 && exit 0
+	REM_TRIES=$((REM_TRIES-1))
+	sleep 0.2
+done
+
+exit 1
@@ -0,0 +1,5 @@
+#! /bin/sh
+
+[ "$1" ] || exit 1
+
+env -u LD_LIBRARY_PATH bluetoothctl remove "$1" > /dev/null
@@ -0,0 +1,7 @@
+#! /bin/sh
+
+[ "$1" ] || exit 1
+
+env -u LD_LIBRARY_PATH bluetoothctl --timeout 20 pair "$1" > /dev/null || exit 1
+env -u LD_LIBRARY_PATH bluetoothctl trust "$1" > /dev/null
+env -u LD_LIBRARY_PATH bluetoothctl --timeout 10 connect "$1" > /dev/null
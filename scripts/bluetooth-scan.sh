@@ -0,0 +1,5 @@
+#! /bin/sh
+
+SCAN_DURATION=${SCAN_DURATION:-10}
+
+env -u LD_LIBRARY_PATH bluetoothctl --timeout "$SCAN_DURATION" scan on > /dev/null